@@ -0,0 +1,29 @@
+//! Embeds the git commit and the build time into the binary, they are reported by `GET /version`.
+//! `GIT_COMMIT` and `BUILD_TIMESTAMP` from the environment win, docker builds have no `.git` directory.
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    let git_commit = env::var("GIT_COMMIT").ok().or_else(git_head).unwrap_or_else(|| "unknown".to_string());
+    let build_timestamp = env::var("BUILD_TIMESTAMP").ok().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+            .to_string()
+    });
+
+    println!("cargo:rustc-env=GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_timestamp);
+}
+
+fn git_head() -> Option<String> {
+    Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+}
@@ -0,0 +1,9 @@
+CREATE TABLE coupon_redemptions (
+    token UUID PRIMARY KEY,
+    coupon_id INTEGER NOT NULL REFERENCES coupons (id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL REFERENCES products (id),
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX coupon_redemptions_coupon_id_idx ON coupon_redemptions (coupon_id);
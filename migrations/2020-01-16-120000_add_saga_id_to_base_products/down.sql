@@ -0,0 +1 @@
+ALTER TABLE base_products DROP COLUMN saga_id;
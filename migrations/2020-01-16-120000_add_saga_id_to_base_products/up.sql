@@ -0,0 +1,2 @@
+ALTER TABLE base_products ADD COLUMN saga_id UUID;
+CREATE INDEX base_products_saga_id_idx ON base_products (saga_id);
@@ -0,0 +1 @@
+ALTER TABLE stores DROP COLUMN quota_plan;
@@ -0,0 +1 @@
+ALTER TABLE stores ADD COLUMN quota_plan VARCHAR;
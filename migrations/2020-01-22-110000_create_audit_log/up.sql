@@ -0,0 +1,11 @@
+CREATE TABLE audit_log (
+    id SERIAL PRIMARY KEY,
+    actor_user_id INTEGER NOT NULL,
+    effective_user_id INTEGER NOT NULL,
+    method VARCHAR NOT NULL,
+    path VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX audit_log_actor_user_id_idx ON audit_log (actor_user_id);
+CREATE INDEX audit_log_effective_user_id_idx ON audit_log (effective_user_id);
@@ -0,0 +1,4 @@
+DROP TABLE store_verification_codes;
+
+ALTER TABLE stores DROP COLUMN phone_verified;
+ALTER TABLE stores DROP COLUMN email_verified;
@@ -0,0 +1,15 @@
+ALTER TABLE stores ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT 'f';
+ALTER TABLE stores ADD COLUMN phone_verified BOOLEAN NOT NULL DEFAULT 'f';
+
+CREATE TABLE store_verification_codes (
+    id SERIAL PRIMARY KEY,
+    store_id INTEGER NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    channel VARCHAR NOT NULL,
+    destination VARCHAR NOT NULL,
+    code VARCHAR NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX store_verification_codes_store_id_idx ON store_verification_codes (store_id);
@@ -0,0 +1,2 @@
+DROP TABLE attribute_group_attributes;
+DROP TABLE attribute_groups;
@@ -0,0 +1,18 @@
+CREATE TABLE attribute_groups (
+    id SERIAL PRIMARY KEY,
+    name JSONB NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+SELECT diesel_manage_updated_at('attribute_groups');
+
+CREATE TABLE attribute_group_attributes (
+    id SERIAL PRIMARY KEY,
+    group_id INTEGER NOT NULL REFERENCES attribute_groups (id) ON DELETE CASCADE,
+    attribute_id INTEGER NOT NULL UNIQUE REFERENCES attributes (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX attribute_group_attributes_group_id_idx ON attribute_group_attributes (group_id);
@@ -0,0 +1,13 @@
+CREATE TABLE store_faqs (
+    id SERIAL PRIMARY KEY,
+    store_id INTEGER NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    question JSONB NOT NULL,
+    answer JSONB NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX store_faqs_store_id_idx ON store_faqs (store_id);
+
+SELECT diesel_manage_updated_at('store_faqs');
@@ -0,0 +1,3 @@
+ALTER TABLE stores DROP CONSTRAINT stores_location_check;
+ALTER TABLE stores DROP COLUMN longitude;
+ALTER TABLE stores DROP COLUMN latitude;
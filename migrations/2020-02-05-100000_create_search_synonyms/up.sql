@@ -0,0 +1,8 @@
+CREATE TABLE search_synonyms (
+    id SERIAL PRIMARY KEY,
+    terms VARCHAR[] NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+SELECT diesel_manage_updated_at('search_synonyms');
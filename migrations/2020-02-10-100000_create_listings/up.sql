@@ -0,0 +1,10 @@
+CREATE TABLE listings (
+    id SERIAL PRIMARY KEY,
+    category_id INTEGER NOT NULL REFERENCES categories (id),
+    attributes JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (category_id, attributes)
+);
+
+SELECT diesel_manage_updated_at('listings');
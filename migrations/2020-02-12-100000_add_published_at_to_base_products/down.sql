@@ -0,0 +1 @@
+ALTER TABLE base_products DROP COLUMN published_at;
@@ -0,0 +1,4 @@
+-- New base products are drafts until the owner publishes them
+ALTER TABLE base_products ADD COLUMN published_at TIMESTAMP;
+-- Existing base products stay published, they are reindexed to get the field in elastic
+UPDATE base_products SET published_at = current_timestamp, kafka_update_no = kafka_update_no + 1;
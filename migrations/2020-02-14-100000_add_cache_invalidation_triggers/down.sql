@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS user_roles_cache_invalidation ON user_roles;
+DROP TRIGGER IF EXISTS attribute_values_cache_invalidation ON attribute_values;
+DROP TRIGGER IF EXISTS attributes_cache_invalidation ON attributes;
+DROP TRIGGER IF EXISTS cat_attr_values_cache_invalidation ON cat_attr_values;
+DROP TRIGGER IF EXISTS categories_cache_invalidation ON categories;
+DROP FUNCTION IF EXISTS notify_cache_invalidation();
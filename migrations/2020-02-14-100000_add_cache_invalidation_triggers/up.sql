@@ -0,0 +1,25 @@
+-- Instances with in-process caches listen on `cache_invalidation` channel, payload is `<table>:<cache key>`
+CREATE OR REPLACE FUNCTION notify_cache_invalidation() RETURNS TRIGGER AS $$
+DECLARE
+    changed_row RECORD;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed_row := OLD;
+    ELSE
+        changed_row := NEW;
+    END IF;
+    PERFORM pg_notify('cache_invalidation', TG_TABLE_NAME || ':' || (to_json(changed_row) ->> TG_ARGV[0]));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER categories_cache_invalidation AFTER INSERT OR UPDATE OR DELETE ON categories
+    FOR EACH ROW EXECUTE PROCEDURE notify_cache_invalidation('id');
+CREATE TRIGGER cat_attr_values_cache_invalidation AFTER INSERT OR UPDATE OR DELETE ON cat_attr_values
+    FOR EACH ROW EXECUTE PROCEDURE notify_cache_invalidation('cat_id');
+CREATE TRIGGER attributes_cache_invalidation AFTER INSERT OR UPDATE OR DELETE ON attributes
+    FOR EACH ROW EXECUTE PROCEDURE notify_cache_invalidation('id');
+CREATE TRIGGER attribute_values_cache_invalidation AFTER INSERT OR UPDATE OR DELETE ON attribute_values
+    FOR EACH ROW EXECUTE PROCEDURE notify_cache_invalidation('attr_id');
+CREATE TRIGGER user_roles_cache_invalidation AFTER INSERT OR UPDATE OR DELETE ON user_roles
+    FOR EACH ROW EXECUTE PROCEDURE notify_cache_invalidation('user_id');
@@ -0,0 +1,6 @@
+CREATE TABLE product_views (
+    base_product_id INTEGER NOT NULL REFERENCES base_products (id) ON DELETE CASCADE,
+    viewer_hash VARCHAR NOT NULL,
+    day DATE NOT NULL DEFAULT current_date,
+    PRIMARY KEY (base_product_id, viewer_hash, day)
+);
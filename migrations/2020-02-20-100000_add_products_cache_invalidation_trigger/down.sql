@@ -0,0 +1 @@
+DROP TRIGGER IF EXISTS products_cache_invalidation ON products;
@@ -0,0 +1,2 @@
+CREATE TRIGGER products_cache_invalidation AFTER INSERT OR UPDATE OR DELETE ON products
+    FOR EACH ROW EXECUTE PROCEDURE notify_cache_invalidation('id');
@@ -0,0 +1,2 @@
+ALTER TABLE base_products DROP COLUMN max_price;
+ALTER TABLE base_products DROP COLUMN min_price;
@@ -0,0 +1,12 @@
+ALTER TABLE base_products ADD COLUMN min_price DOUBLE PRECISION;
+ALTER TABLE base_products ADD COLUMN max_price DOUBLE PRECISION;
+
+UPDATE base_products
+SET min_price = price_range.min_price, max_price = price_range.max_price
+FROM (
+    SELECT base_product_id, MIN(price) AS min_price, MAX(price) AS max_price
+    FROM products
+    WHERE is_active = true
+    GROUP BY base_product_id
+) AS price_range
+WHERE base_products.id = price_range.base_product_id;
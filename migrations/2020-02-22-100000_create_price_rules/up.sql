@@ -0,0 +1,17 @@
+CREATE TABLE price_rules (
+    id SERIAL PRIMARY KEY,
+    store_id INTEGER NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    category_id INTEGER REFERENCES categories (id) ON DELETE CASCADE,
+    max_stock INTEGER,
+    max_views INTEGER,
+    percent INTEGER NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX price_rules_store_id_idx ON price_rules (store_id);
+
+SELECT diesel_manage_updated_at('price_rules');
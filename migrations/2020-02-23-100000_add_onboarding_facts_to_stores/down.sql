@@ -0,0 +1,2 @@
+ALTER TABLE stores DROP COLUMN shipping_profile_set;
+ALTER TABLE stores DROP COLUMN payout_info_present;
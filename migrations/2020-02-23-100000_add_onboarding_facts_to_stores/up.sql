@@ -0,0 +1,2 @@
+ALTER TABLE stores ADD COLUMN payout_info_present BOOLEAN NOT NULL DEFAULT 'f';
+ALTER TABLE stores ADD COLUMN shipping_profile_set BOOLEAN NOT NULL DEFAULT 'f';
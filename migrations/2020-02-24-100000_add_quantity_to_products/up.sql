@@ -0,0 +1 @@
+ALTER TABLE products ADD COLUMN quantity INTEGER CHECK (quantity >= 0);
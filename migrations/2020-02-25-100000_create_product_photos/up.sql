@@ -0,0 +1,14 @@
+CREATE TABLE product_photos (
+    id SERIAL PRIMARY KEY,
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    url VARCHAR NOT NULL,
+    position INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (product_id, url)
+);
+
+INSERT INTO product_photos (product_id, url, position)
+SELECT products.id, photo.url, (photo.ordinality - 1)::INTEGER
+FROM products, jsonb_array_elements_text(products.additional_photos) WITH ORDINALITY AS photo(url, ordinality)
+WHERE jsonb_typeof(products.additional_photos) = 'array'
+ON CONFLICT DO NOTHING;
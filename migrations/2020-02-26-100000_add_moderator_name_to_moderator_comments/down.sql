@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS moderator_store_comments_created_at_idx;
+DROP INDEX IF EXISTS moderator_product_comments_created_at_idx;
+DROP INDEX IF EXISTS moderator_store_comments_store_id_idx;
+DROP INDEX IF EXISTS moderator_product_comments_base_product_id_idx;
+
+ALTER TABLE moderator_store_comments DROP COLUMN IF EXISTS moderator_name;
+ALTER TABLE moderator_product_comments DROP COLUMN IF EXISTS moderator_name;
@@ -0,0 +1,7 @@
+ALTER TABLE moderator_product_comments ADD COLUMN moderator_name VARCHAR;
+ALTER TABLE moderator_store_comments ADD COLUMN moderator_name VARCHAR;
+
+CREATE INDEX moderator_product_comments_base_product_id_idx ON moderator_product_comments (base_product_id, id);
+CREATE INDEX moderator_store_comments_store_id_idx ON moderator_store_comments (store_id, id);
+CREATE INDEX moderator_product_comments_created_at_idx ON moderator_product_comments (created_at);
+CREATE INDEX moderator_store_comments_created_at_idx ON moderator_store_comments (created_at);
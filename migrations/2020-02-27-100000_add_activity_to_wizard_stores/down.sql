@@ -0,0 +1,7 @@
+DROP TABLE archived_wizard_stores;
+
+DROP INDEX IF EXISTS wizard_stores_updated_at_idx;
+
+ALTER TABLE wizard_stores DROP COLUMN IF EXISTS reminded_at;
+ALTER TABLE wizard_stores DROP COLUMN IF EXISTS updated_at;
+ALTER TABLE wizard_stores DROP COLUMN IF EXISTS created_at;
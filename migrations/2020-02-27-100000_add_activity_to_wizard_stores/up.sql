@@ -0,0 +1,15 @@
+ALTER TABLE wizard_stores ADD COLUMN created_at TIMESTAMP NOT NULL DEFAULT current_timestamp;
+ALTER TABLE wizard_stores ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp;
+ALTER TABLE wizard_stores ADD COLUMN reminded_at TIMESTAMP;
+
+CREATE INDEX wizard_stores_updated_at_idx ON wizard_stores (updated_at) WHERE completed = false;
+
+CREATE TABLE archived_wizard_stores (
+    id SERIAL PRIMARY KEY,
+    wizard_store_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    data JSONB NOT NULL,
+    archived_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX archived_wizard_stores_user_id_idx ON archived_wizard_stores (user_id);
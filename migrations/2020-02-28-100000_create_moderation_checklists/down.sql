@@ -0,0 +1,3 @@
+DROP TABLE base_product_checklist_results;
+
+DROP TABLE moderation_checklist_items;
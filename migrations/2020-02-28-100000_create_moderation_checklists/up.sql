@@ -0,0 +1,21 @@
+CREATE TABLE moderation_checklist_items (
+    id SERIAL PRIMARY KEY,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    description VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX moderation_checklist_items_category_id_idx ON moderation_checklist_items (category_id);
+
+SELECT diesel_manage_updated_at('moderation_checklist_items');
+
+CREATE TABLE base_product_checklist_results (
+    id SERIAL PRIMARY KEY,
+    base_product_id INTEGER NOT NULL REFERENCES base_products (id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL REFERENCES moderation_checklist_items (id) ON DELETE CASCADE,
+    passed BOOLEAN NOT NULL,
+    moderator_id INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (base_product_id, item_id)
+);
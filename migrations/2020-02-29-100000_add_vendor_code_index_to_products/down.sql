@@ -0,0 +1 @@
+DROP INDEX IF EXISTS products_vendor_code_idx;
@@ -0,0 +1 @@
+CREATE INDEX products_vendor_code_idx ON products (vendor_code) WHERE is_active = true;
@@ -0,0 +1 @@
+ALTER TABLE stores ADD COLUMN franchise BOOLEAN NOT NULL DEFAULT 'f';
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS products_default_variant_idx;
+ALTER TABLE products DROP COLUMN is_default;
+ALTER TABLE products DROP COLUMN position;
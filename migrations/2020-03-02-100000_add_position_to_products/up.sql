@@ -0,0 +1,10 @@
+ALTER TABLE products ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN is_default BOOLEAN NOT NULL DEFAULT 'f';
+
+UPDATE products SET position = numbered.position
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY base_product_id ORDER BY id) - 1 AS position FROM products
+) AS numbered
+WHERE products.id = numbered.id;
+
+CREATE UNIQUE INDEX products_default_variant_idx ON products (base_product_id) WHERE is_default = 't';
@@ -0,0 +1,15 @@
+CREATE TABLE stock_reservations (
+    id SERIAL PRIMARY KEY,
+    cart_id UUID NOT NULL,
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE UNIQUE INDEX stock_reservations_cart_product_idx ON stock_reservations (cart_id, product_id);
+CREATE INDEX stock_reservations_product_id_idx ON stock_reservations (product_id);
+CREATE INDEX stock_reservations_expires_at_idx ON stock_reservations (expires_at);
+
+SELECT diesel_manage_updated_at('stock_reservations');
@@ -0,0 +1,2 @@
+ALTER TABLE products DROP COLUMN discount_ends_at;
+ALTER TABLE products DROP COLUMN discount_starts_at;
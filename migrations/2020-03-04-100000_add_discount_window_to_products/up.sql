@@ -0,0 +1,2 @@
+ALTER TABLE products ADD COLUMN discount_starts_at TIMESTAMP;
+ALTER TABLE products ADD COLUMN discount_ends_at TIMESTAMP;
@@ -0,0 +1,13 @@
+CREATE TABLE store_categories (
+    id SERIAL PRIMARY KEY,
+    store_id INTEGER NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    status VARCHAR NOT NULL,
+    moderator_id INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE UNIQUE INDEX store_categories_store_category_idx ON store_categories (store_id, category_id);
+
+SELECT diesel_manage_updated_at('store_categories');
@@ -0,0 +1,3 @@
+ALTER TABLE base_products DROP COLUMN store_is_on_vacation;
+ALTER TABLE stores DROP COLUMN vacation_message;
+ALTER TABLE stores DROP COLUMN is_on_vacation;
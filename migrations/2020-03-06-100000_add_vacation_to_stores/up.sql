@@ -0,0 +1,3 @@
+ALTER TABLE stores ADD COLUMN is_on_vacation BOOLEAN NOT NULL DEFAULT 'f';
+ALTER TABLE stores ADD COLUMN vacation_message VARCHAR;
+ALTER TABLE base_products ADD COLUMN store_is_on_vacation BOOLEAN NOT NULL DEFAULT 'f';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS products_gtin_idx;
+ALTER TABLE products DROP COLUMN gtin;
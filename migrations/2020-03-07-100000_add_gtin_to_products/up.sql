@@ -0,0 +1,2 @@
+ALTER TABLE products ADD COLUMN gtin VARCHAR;
+CREATE INDEX products_gtin_idx ON products (gtin) WHERE is_active = true;
@@ -0,0 +1,2 @@
+ALTER TABLE stores DROP COLUMN timezone;
+ALTER TABLE stores DROP COLUMN opening_hours;
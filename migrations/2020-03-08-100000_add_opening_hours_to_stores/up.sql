@@ -0,0 +1,2 @@
+ALTER TABLE stores ADD COLUMN opening_hours JSONB;
+ALTER TABLE stores ADD COLUMN timezone VARCHAR;
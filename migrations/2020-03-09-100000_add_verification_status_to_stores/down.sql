@@ -0,0 +1,2 @@
+ALTER TABLE stores DROP COLUMN badges;
+ALTER TABLE stores DROP COLUMN verification_status;
@@ -0,0 +1,2 @@
+ALTER TABLE stores ADD COLUMN verification_status VARCHAR NOT NULL DEFAULT 'unverified';
+ALTER TABLE stores ADD COLUMN badges JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,10 @@
+CREATE TABLE store_product_positions (
+    id SERIAL PRIMARY KEY,
+    store_id INTEGER NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    base_product_id INTEGER NOT NULL REFERENCES base_products (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    pinned BOOLEAN NOT NULL DEFAULT 'f',
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE UNIQUE INDEX store_product_positions_store_id_base_product_id_idx ON store_product_positions (store_id, base_product_id);
@@ -0,0 +1,5 @@
+ALTER TABLE base_products DROP COLUMN dimensions_inherited;
+ALTER TABLE stores DROP COLUMN default_weight_g;
+ALTER TABLE stores DROP COLUMN default_height_cm;
+ALTER TABLE stores DROP COLUMN default_width_cm;
+ALTER TABLE stores DROP COLUMN default_length_cm;
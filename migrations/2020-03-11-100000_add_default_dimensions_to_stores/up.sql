@@ -0,0 +1,5 @@
+ALTER TABLE stores ADD COLUMN default_length_cm INTEGER;
+ALTER TABLE stores ADD COLUMN default_width_cm INTEGER;
+ALTER TABLE stores ADD COLUMN default_height_cm INTEGER;
+ALTER TABLE stores ADD COLUMN default_weight_g INTEGER;
+ALTER TABLE base_products ADD COLUMN dimensions_inherited BOOLEAN NOT NULL DEFAULT 'f';
@@ -0,0 +1,15 @@
+CREATE TABLE category_promotions (
+    id SERIAL PRIMARY KEY,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    banner_url VARCHAR NOT NULL,
+    target_url VARCHAR NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    starts_at TIMESTAMP,
+    ends_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX category_promotions_category_id_idx ON category_promotions (category_id);
+
+SELECT diesel_manage_updated_at('category_promotions');
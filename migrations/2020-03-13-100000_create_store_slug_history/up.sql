@@ -0,0 +1,8 @@
+CREATE TABLE store_slug_history (
+    id SERIAL PRIMARY KEY,
+    store_id INTEGER NOT NULL REFERENCES stores (id) ON DELETE CASCADE,
+    slug VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE UNIQUE INDEX store_slug_history_slug_idx ON store_slug_history (slug);
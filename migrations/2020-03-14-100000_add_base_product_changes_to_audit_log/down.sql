@@ -0,0 +1,8 @@
+DELETE FROM audit_log WHERE method IS NULL OR path IS NULL;
+
+DROP INDEX audit_log_base_product_id_idx;
+
+ALTER TABLE audit_log DROP COLUMN change;
+ALTER TABLE audit_log DROP COLUMN base_product_id;
+ALTER TABLE audit_log ALTER COLUMN path SET NOT NULL;
+ALTER TABLE audit_log ALTER COLUMN method SET NOT NULL;
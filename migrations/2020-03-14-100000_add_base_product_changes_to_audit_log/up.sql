@@ -0,0 +1,6 @@
+ALTER TABLE audit_log ALTER COLUMN method DROP NOT NULL;
+ALTER TABLE audit_log ALTER COLUMN path DROP NOT NULL;
+ALTER TABLE audit_log ADD COLUMN base_product_id INTEGER REFERENCES base_products (id) ON DELETE CASCADE;
+ALTER TABLE audit_log ADD COLUMN change JSONB;
+
+CREATE INDEX audit_log_base_product_id_idx ON audit_log (base_product_id);
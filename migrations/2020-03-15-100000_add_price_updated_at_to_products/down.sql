@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS set_price_updated_at ON products;
+DROP FUNCTION IF EXISTS set_price_updated_at();
+ALTER TABLE products DROP COLUMN price_updated_at;
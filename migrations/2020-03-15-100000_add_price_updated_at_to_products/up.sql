@@ -0,0 +1,14 @@
+ALTER TABLE products ADD COLUMN price_updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp;
+UPDATE products SET price_updated_at = updated_at;
+
+CREATE OR REPLACE FUNCTION set_price_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF NEW.price IS DISTINCT FROM OLD.price OR NEW.currency IS DISTINCT FROM OLD.currency THEN
+        NEW.price_updated_at := current_timestamp;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_price_updated_at BEFORE UPDATE ON products
+    FOR EACH ROW EXECUTE PROCEDURE set_price_updated_at();
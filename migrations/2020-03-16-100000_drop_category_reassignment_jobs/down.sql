@@ -0,0 +1,16 @@
+CREATE TABLE category_reassignment_jobs (
+    id SERIAL PRIMARY KEY,
+    moderator_id INTEGER NOT NULL,
+    store_id INTEGER,
+    current_category_id INTEGER,
+    name_pattern VARCHAR,
+    new_category_id INTEGER NOT NULL,
+    status VARCHAR NOT NULL,
+    total INTEGER NOT NULL DEFAULT 0,
+    processed INTEGER NOT NULL DEFAULT 0,
+    error VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+SELECT diesel_manage_updated_at('category_reassignment_jobs');
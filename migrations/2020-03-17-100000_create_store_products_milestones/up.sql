@@ -0,0 +1,5 @@
+CREATE TABLE store_products_milestones (
+    store_id INTEGER PRIMARY KEY REFERENCES stores (id) ON DELETE CASCADE,
+    published_products INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
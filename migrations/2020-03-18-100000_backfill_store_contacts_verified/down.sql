@@ -0,0 +1 @@
+DROP INDEX store_verification_codes_store_id_channel_created_at_idx;
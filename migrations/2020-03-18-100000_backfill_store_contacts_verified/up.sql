@@ -0,0 +1,6 @@
+-- Contacts of stores published before verification was introduced are trusted,
+-- so sending them to moderation again is not blocked
+UPDATE stores SET email_verified = 't' WHERE status = 'published' AND email IS NOT NULL;
+UPDATE stores SET phone_verified = 't' WHERE status = 'published' AND phone IS NOT NULL;
+
+CREATE INDEX store_verification_codes_store_id_channel_created_at_idx ON store_verification_codes (store_id, channel, created_at);
@@ -0,0 +1 @@
+ALTER TABLE products DROP COLUMN deactivated_with_base_product;
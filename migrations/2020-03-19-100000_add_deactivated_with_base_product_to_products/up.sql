@@ -0,0 +1,9 @@
+ALTER TABLE products ADD COLUMN deactivated_with_base_product BOOLEAN NOT NULL DEFAULT 'f';
+
+-- Variants deactivated together with their base product got the same `updated_at` as it
+UPDATE products SET deactivated_with_base_product = 't'
+FROM base_products
+WHERE products.base_product_id = base_products.id
+    AND products.is_active = 'f'
+    AND base_products.is_active = 'f'
+    AND products.updated_at = base_products.updated_at;
@@ -0,0 +1,8 @@
+CREATE TABLE index_migrations (
+    id SERIAL PRIMARY KEY,
+    old_index VARCHAR NOT NULL,
+    new_index VARCHAR NOT NULL,
+    replays JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    switched_at TIMESTAMP
+);
@@ -0,0 +1,201 @@
+//! Fault injection for resilience tests of the gateway and sagas.
+//!
+//! Calls of a layer can be delayed and failed with some probability, faults are set with `/chaos/faults` endpoints.
+//! Faults exist only in builds with `chaos` feature, production builds go without it and never delay or fail calls.
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use failure::Error as FailureError;
+use futures::Future;
+use validator::Validate;
+
+use errors::Error;
+use repos::types::RepoFuture;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultLayer {
+    Repo,
+    Elastic,
+    HttpClient,
+}
+
+impl FaultLayer {
+    /// Error of the layer reported by failed calls, injected failures look like real ones
+    pub fn error(self) -> Error {
+        match self {
+            FaultLayer::Repo => Error::Connection,
+            FaultLayer::Elastic => Error::ElasticSearch,
+            FaultLayer::HttpClient => Error::Internal,
+        }
+    }
+}
+
+impl fmt::Display for FaultLayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FaultLayer::Repo => write!(f, "repo"),
+            FaultLayer::Elastic => write!(f, "elastic"),
+            FaultLayer::HttpClient => write!(f, "http_client"),
+        }
+    }
+}
+
+impl FromStr for FaultLayer {
+    type Err = FailureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "repo" => Ok(FaultLayer::Repo),
+            "elastic" => Ok(FaultLayer::Elastic),
+            "http_client" => Ok(FaultLayer::HttpClient),
+            _ => Err(format_err!("Unknown fault layer {}", s).context(Error::Parse).into()),
+        }
+    }
+}
+
+/// Every call of the layer is delayed by `latency_ms`, then it fails with `error_rate` probability
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Validate)]
+pub struct Fault {
+    pub latency_ms: u64,
+    #[validate(range(min = "0.0", max = "1.0"))]
+    pub error_rate: f64,
+}
+
+impl Fault {
+    pub fn latency(&self) -> Duration {
+        Duration::from_millis(self.latency_ms)
+    }
+
+    /// Fails the call of the layer with `error_rate` probability
+    pub fn trip(&self, layer: FaultLayer, roll: f64) -> Result<(), FailureError> {
+        if roll < self.error_rate {
+            Err(format_err!("Injected {} fault", layer).context(layer.error()).into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "chaos")]
+pub use self::injection::*;
+
+#[cfg(feature = "chaos")]
+mod injection {
+    use std::collections::HashMap;
+    use std::sync::RwLock;
+    use std::thread;
+
+    use futures::future;
+    use futures::sync::oneshot;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    lazy_static! {
+        static ref FAULTS: RwLock<HashMap<FaultLayer, Fault>> = RwLock::new(HashMap::new());
+    }
+
+    pub fn faults() -> HashMap<FaultLayer, Fault> {
+        FAULTS.read().map(|faults| faults.clone()).unwrap_or_default()
+    }
+
+    pub fn set_fault(layer: FaultLayer, fault: Fault) {
+        match FAULTS.write() {
+            Ok(mut faults) => {
+                faults.insert(layer, fault);
+            }
+            Err(e) => error!("Faults state is poisoned: {}", e),
+        }
+    }
+
+    pub fn reset_faults() {
+        match FAULTS.write() {
+            Ok(mut faults) => faults.clear(),
+            Err(e) => error!("Faults state is poisoned: {}", e),
+        }
+    }
+
+    fn fault(layer: FaultLayer) -> Option<Fault> {
+        FAULTS.read().ok().and_then(|faults| faults.get(&layer).cloned())
+    }
+
+    /// Applies fault of the layer to a blocking call, the current thread sleeps for the latency
+    pub fn inject(layer: FaultLayer) -> Result<(), FailureError> {
+        match fault(layer) {
+            Some(fault) => {
+                thread::sleep(fault.latency());
+                fault.trip(layer, thread_rng().gen())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Applies fault of the layer to a future, the latency is waited out on a separate thread
+    pub fn inject_future<T, F>(layer: FaultLayer, call: F) -> RepoFuture<T>
+    where
+        T: Send + 'static,
+        F: Future<Item = T, Error = FailureError> + Send + 'static,
+    {
+        let fault = match fault(layer) {
+            Some(fault) => fault,
+            None => return Box::new(call),
+        };
+
+        if fault.latency_ms == 0 {
+            return Box::new(future::result(fault.trip(layer, thread_rng().gen())).and_then(move |_| call));
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            thread::sleep(fault.latency());
+            let _ = sender.send(fault.trip(layer, thread_rng().gen()));
+        });
+        Box::new(
+            receiver
+                .map_err(|_| format_err!("Injected {} fault was canceled", layer).context(Error::Internal).into())
+                .and_then(|result| result)
+                .and_then(move |_| call),
+        )
+    }
+}
+
+/// Faults are not injected without `chaos` feature
+#[cfg(not(feature = "chaos"))]
+pub fn inject(_layer: FaultLayer) -> Result<(), FailureError> {
+    Ok(())
+}
+
+/// Faults are not injected without `chaos` feature
+#[cfg(not(feature = "chaos"))]
+pub fn inject_future<T, F>(_layer: FaultLayer, call: F) -> RepoFuture<T>
+where
+    T: Send + 'static,
+    F: Future<Item = T, Error = FailureError> + Send + 'static,
+{
+    Box::new(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fault_trips_with_error_rate() {
+        let fault = Fault {
+            latency_ms: 0,
+            error_rate: 0.25,
+        };
+
+        assert!(fault.trip(FaultLayer::Repo, 0.1).is_err());
+        assert!(fault.trip(FaultLayer::Repo, 0.25).is_ok());
+        assert!(Fault::default().trip(FaultLayer::Elastic, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_parse_fault_layer() {
+        assert_eq!("http_client".parse::<FaultLayer>().unwrap(), FaultLayer::HttpClient);
+        assert!("database".parse::<FaultLayer>().is_err());
+    }
+}
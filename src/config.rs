@@ -1,5 +1,9 @@
 //! Config module contains the top-level config for the app.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::env;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
 
 use stq_http;
 use stq_logging::GrayLogConfig;
@@ -9,19 +13,41 @@ use sentry_integration::SentryConfig;
 use config_crate::{Config as RawConfig, ConfigError, Environment, File};
 
 pub const ATTRIBUTE_CACHE_NAMESPACE: &'static str = "attribute";
+pub const CATALOG_HEALTH_CACHE_NAMESPACE: &'static str = "catalog_health";
 pub const CATEGORY_CACHE_NAMESPACE: &'static str = "category";
+pub const PRODUCT_CACHE_NAMESPACE: &'static str = "product";
+pub const DEFAULT_PRODUCT_CACHE_CAPACITY: usize = 10_000;
 pub const ROLES_CACHE_NAMESPACE: &'static str = "roles";
+pub const STORE_FEED_CACHE_NAMESPACE: &'static str = "store_feed";
+pub const STORE_PROFILE_CACHE_NAMESPACE: &'static str = "store_profile";
 
 /// Basic settings - HTTP binding address and database DSN
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub server: Server,
     pub client: Client,
+    pub elastic: Option<Elastic>,
     pub graylog: Option<GrayLogConfig>,
     pub sentry: Option<SentryConfig>,
     pub rocket_retail: Option<RocketRetail>,
     pub s3: Option<S3>,
     pub ticker: Option<Ticker>,
+    pub retention: Option<Retention>,
+    pub social_feed: Option<SocialFeed>,
+    pub search_throttle: Option<SearchThrottle>,
+    pub index_freshness: Option<IndexFreshness>,
+    pub cache_invalidation: Option<CacheInvalidation>,
+    pub search: Option<Search>,
+    pub product_quota: Option<ProductQuota>,
+    pub notifications: Option<Notifications>,
+    pub page_sizes: Option<PageSizes>,
+    pub storefront: Option<Storefront>,
+    pub price_rules: Option<PriceRules>,
+    pub wizard_cleanup: Option<WizardCleanup>,
+    pub stock_reservations: Option<StockReservations>,
+    pub jobs: Option<Jobs>,
+    pub shadow_reads: Option<ShadowReads>,
+    pub store_contact: Option<StoreContact>,
 }
 
 /// Common server settings
@@ -30,12 +56,24 @@ pub struct Server {
     pub host: String,
     pub port: String,
     pub database: String,
+    /// Read replica used by read halves of repos, reads go to `database` without it
+    pub database_replica: Option<String>,
     pub elastic: String,
     pub redis: Option<String>,
     pub thread_count: usize,
     pub cache_ttl_sec: u64,
 }
 
+/// Elastic cluster nodes, requests are balanced between nodes that pass health checks.
+/// Without this section `server.elastic` is the only node
+#[derive(Debug, Deserialize, Clone)]
+pub struct Elastic {
+    pub nodes: Vec<String>,
+    pub health_check_interval_s: u64,
+    /// Node that failed health check gets no requests for this time
+    pub eviction_s: u64,
+}
+
 /// Http client settings
 #[derive(Debug, Deserialize, Clone)]
 pub struct Client {
@@ -61,6 +99,275 @@ pub struct Ticker {
     pub thread_count: usize,
 }
 
+/// Retention job settings, products and stores deactivated `deactivated_days` ago are deleted
+#[derive(Debug, Deserialize, Clone)]
+pub struct Retention {
+    pub interval_s: u64,
+    pub deactivated_days: u64,
+    pub batch_size: usize,
+    pub thread_count: usize,
+    /// Has to include `ORDERS_REFERENCE_CHECK`, the job only runs dry without it
+    #[serde(default)]
+    pub reference_checks: Vec<ReferenceCheck>,
+    /// Only logs what would be deleted
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Name of the reference check of orders service, products of past orders must never be deleted
+pub const ORDERS_REFERENCE_CHECK: &str = "orders";
+
+impl Retention {
+    /// Deletions are made only when orders are checked for references
+    pub fn deletion_enabled(&self) -> bool {
+        !self.dry_run && self.reference_checks.iter().any(|check| check.name == ORDERS_REFERENCE_CHECK)
+    }
+}
+
+/// Internal endpoint of another service, answers which of the posted ids are still referenced
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReferenceCheck {
+    pub name: String,
+    pub url: String,
+}
+
+/// Social feed notifier settings, each event type can be switched off separately
+#[derive(Debug, Deserialize, Clone)]
+pub struct SocialFeed {
+    pub url: String,
+    pub store_published: bool,
+    pub products_milestone: bool,
+    pub big_discount_added: bool,
+    /// Minimal discount (0.0 - 1.0) announced as a big one
+    pub big_discount_threshold: f64,
+}
+
+/// Soft throttling of anonymous search requests
+#[derive(Debug, Deserialize, Clone)]
+pub struct SearchThrottle {
+    /// Length of the window search requests are counted in
+    pub window_s: u64,
+    /// Requests in the window after which responses start to slow down
+    pub delay_threshold: u32,
+    /// Delay added for every request over `delay_threshold`
+    pub delay_step_ms: u64,
+    pub max_delay_ms: u64,
+    /// Requests in the window after which the gateway is asked to show captcha
+    pub captcha_threshold: u32,
+    /// Gateways whose `X-Forwarded-For` entries are trusted, without them the peer address is the client
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+/// Check of elastic indices against the database, its result is sent in `X-Index-Freshness` header of search responses
+#[derive(Debug, Deserialize, Clone)]
+pub struct IndexFreshness {
+    pub interval_s: u64,
+}
+
+/// Without Redis, roles, categories, attributes and products are cached in the process and dropped on database notifications
+#[derive(Debug, Deserialize, Clone)]
+pub struct CacheInvalidation {
+    pub reconnect_interval_s: u64,
+    /// Max number of cached products, `DEFAULT_PRODUCT_CACHE_CAPACITY` if missing
+    pub product_cache_capacity: Option<usize>,
+}
+
+/// Search tuning, every part has defaults
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Search {
+    #[serde(default)]
+    pub auto_complete: AutoComplete,
+    #[serde(default)]
+    pub boosts: SearchBoosts,
+    #[serde(default)]
+    pub facets: FacetLimits,
+    /// Popular queries replayed against the rebuilt products index before it takes over the alias
+    #[serde(default)]
+    pub replay_queries: Vec<String>,
+}
+
+/// Relevance of the product search fields, fields with bigger boosts weigh more in the score
+#[derive(Debug, Deserialize, Clone)]
+pub struct SearchBoosts {
+    pub name: f64,
+    pub short_description: f64,
+    pub long_description: f64,
+    /// Boost of products whose store name matches the query, 0 turns the lookup of stores off
+    pub store_name: f64,
+}
+
+impl Default for SearchBoosts {
+    fn default() -> Self {
+        Self {
+            name: 1.0,
+            short_description: 1.0,
+            long_description: 1.0,
+            store_name: 0.0,
+        }
+    }
+}
+
+/// Values of string attributes returned in search filters, the rest are paged with `/search/facet_values`
+#[derive(Debug, Deserialize, Clone)]
+pub struct FacetLimits {
+    pub default_values: usize,
+    /// Limits of attributes keyed by attribute id, overriding `default_values`
+    #[serde(default)]
+    pub attributes: HashMap<String, usize>,
+}
+
+impl FacetLimits {
+    pub fn values_limit(&self, attribute_id: i32) -> usize {
+        self.attributes.get(&attribute_id.to_string()).cloned().unwrap_or(self.default_values)
+    }
+}
+
+impl Default for FacetLimits {
+    fn default() -> Self {
+        Self {
+            default_values: 20,
+            attributes: HashMap::new(),
+        }
+    }
+}
+
+/// Fuzzy matching of product auto complete used when client asks for corrections
+#[derive(Debug, Deserialize, Clone)]
+pub struct AutoComplete {
+    /// Elastic fuzziness, `AUTO` or max edit distance
+    pub fuzziness: String,
+    /// Leading characters that must match exactly
+    pub prefix_length: u32,
+    /// Input shorter than this is matched exactly
+    pub min_length: u32,
+}
+
+impl Default for AutoComplete {
+    fn default() -> Self {
+        Self {
+            fuzziness: "AUTO".to_string(),
+            prefix_length: 0,
+            min_length: 3,
+        }
+    }
+}
+
+/// Product quotas of billing plans. Stores without a plan pushed from billing
+/// get `default_plan`, plans missing in `plans` are unlimited
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProductQuota {
+    pub default_plan: String,
+    pub plans: HashMap<String, i32>,
+}
+
+/// Notifications microservice, delivers store verification codes by email and sms
+#[derive(Debug, Deserialize, Clone)]
+pub struct Notifications {
+    pub url: String,
+}
+
+/// Public site of the marketplace, store feeds link to its pages
+#[derive(Debug, Deserialize, Clone)]
+pub struct Storefront {
+    pub url: String,
+}
+
+/// Price rules of stores change customer prices only with this section, owners can preview them without it
+#[derive(Debug, Deserialize, Clone)]
+pub struct PriceRules {
+    /// Markdowns of rules are cut to this percent
+    pub max_markdown_percent: i32,
+}
+
+/// Abandoned store wizards. Owners are reminded through the notifications service once
+/// the wizard is untouched for `remind_after_days`, after `archive_after_days` it is archived
+#[derive(Debug, Deserialize, Clone)]
+pub struct WizardCleanup {
+    pub interval_s: u64,
+    pub remind_after_days: u64,
+    pub archive_after_days: u64,
+    /// Wizards reminded or archived in one run
+    pub batch_size: i64,
+}
+
+/// Units reserved by carts are held for `ttl_s`, expired reservations are removed every `interval_s`
+#[derive(Debug, Deserialize, Clone)]
+pub struct StockReservations {
+    pub ttl_s: u64,
+    pub interval_s: u64,
+}
+
+impl Default for StockReservations {
+    fn default() -> Self {
+        Self {
+            ttl_s: 30 * 60,
+            interval_s: 60,
+        }
+    }
+}
+
+/// Jobs left pending or running for `interrupted_after_s` were interrupted by a restart,
+/// they are failed at startup and then every `interval_s`
+#[derive(Debug, Deserialize, Clone)]
+pub struct Jobs {
+    pub interrupted_after_s: u64,
+    pub interval_s: u64,
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self {
+            interrupted_after_s: 10 * 60,
+            interval_s: 60,
+        }
+    }
+}
+
+/// Messages users send to stores, every user can send `max_messages` in `window_s`
+#[derive(Debug, Deserialize, Clone)]
+pub struct StoreContact {
+    pub window_s: u64,
+    pub max_messages: u32,
+}
+
+impl Default for StoreContact {
+    fn default() -> Self {
+        Self {
+            window_s: 60 * 60,
+            max_messages: 5,
+        }
+    }
+}
+
+/// Rewritten repo queries checked against production traffic, names are the ones passed to `shadow_read`
+#[derive(Debug, Deserialize, Clone)]
+pub struct ShadowReads {
+    pub queries: Vec<String>,
+}
+
+/// Page sizes of list and search endpoints. Entries of `routes` override `default`
+/// for single endpoints, keys are listed in config/base.toml
+#[derive(Debug, Deserialize, Clone)]
+pub struct PageSizes {
+    pub default: PageSize,
+    #[serde(default)]
+    pub routes: HashMap<String, PageSize>,
+}
+
+/// Page size used when `count` is not passed and the largest allowed `count`
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct PageSize {
+    pub default: i64,
+    pub max: i64,
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        Self { default: 20, max: 100 }
+    }
+}
+
 /// AWS S3 credentials
 #[derive(Debug, Deserialize, Clone)]
 pub struct S3 {
@@ -92,6 +399,71 @@ impl Config {
         s.try_into()
     }
 
+    /// Returns page size limits of the endpoint
+    pub fn page_size(&self, route: &str) -> PageSize {
+        self.page_sizes
+            .as_ref()
+            .map(|page_sizes| page_sizes.routes.get(route).cloned().unwrap_or(page_sizes.default))
+            .unwrap_or_default()
+    }
+
+    /// Returns search settings, defaults when the section is missing
+    pub fn search(&self) -> Search {
+        self.search.clone().unwrap_or_default()
+    }
+
+    /// Returns stock reservation settings, defaults when the section is missing
+    pub fn stock_reservations(&self) -> StockReservations {
+        self.stock_reservations.clone().unwrap_or_default()
+    }
+
+    /// Returns background jobs settings, defaults when the section is missing
+    pub fn jobs(&self) -> Jobs {
+        self.jobs.clone().unwrap_or_default()
+    }
+
+    /// Returns store contact settings, defaults when the section is missing
+    pub fn store_contact(&self) -> StoreContact {
+        self.store_contact.clone().unwrap_or_default()
+    }
+
+    /// Names of the optional sections present in the config, they switch the corresponding features on
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        let sections = vec![
+            ("elastic", self.elastic.is_some()),
+            ("graylog", self.graylog.is_some()),
+            ("sentry", self.sentry.is_some()),
+            ("rocket_retail", self.rocket_retail.is_some()),
+            ("s3", self.s3.is_some()),
+            ("ticker", self.ticker.is_some()),
+            ("retention", self.retention.is_some()),
+            ("social_feed", self.social_feed.is_some()),
+            ("search_throttle", self.search_throttle.is_some()),
+            ("index_freshness", self.index_freshness.is_some()),
+            ("cache_invalidation", self.cache_invalidation.is_some()),
+            ("search", self.search.is_some()),
+            ("product_quota", self.product_quota.is_some()),
+            ("notifications", self.notifications.is_some()),
+            ("page_sizes", self.page_sizes.is_some()),
+            ("storefront", self.storefront.is_some()),
+            ("price_rules", self.price_rules.is_some()),
+            ("wizard_cleanup", self.wizard_cleanup.is_some()),
+            ("stock_reservations", self.stock_reservations.is_some()),
+            ("jobs", self.jobs.is_some()),
+            ("shadow_reads", self.shadow_reads.is_some()),
+            ("store_contact", self.store_contact.is_some()),
+        ];
+        sections.into_iter().filter(|&(_, enabled)| enabled).map(|(name, _)| name).collect()
+    }
+
+    /// Hash of all settings, instances with equal checksums run with the same config.
+    /// Secrets are hashed too, but they can not be recovered from the checksum
+    pub fn checksum(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        format!("{:?}", self).hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
     pub fn to_http_config(&self) -> stq_http::client::Config {
         stq_http::client::Config {
             http_client_buffer_size: self.client.http_client_buffer_size,
@@ -5,6 +5,7 @@ use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
 use futures_cpupool::CpuPool;
+use isolang::Language;
 use r2d2::{ManageConnection, Pool};
 
 use stq_http::client::ClientHandle;
@@ -12,8 +13,10 @@ use stq_router::RouteParser;
 use stq_static_resources::Currency;
 use stq_types::UserId;
 
+use super::rate_limit::ContactRateLimiter;
 use super::routes::*;
 use config::Config;
+use elastic::ElasticPool;
 use repos::repo_factory::*;
 
 /// Static context for all app
@@ -24,10 +27,15 @@ where
     F: ReposFactory<T>,
 {
     pub db_pool: Pool<M>,
+    /// Connections to the read replica, only read halves of repos are created on them
+    pub db_replica_pool: Pool<M>,
     pub cpu_pool: CpuPool,
     pub config: Arc<Config>,
     pub route_parser: Arc<RouteParser<Route>>,
     pub client_handle: ClientHandle,
+    pub elastic_pool: Arc<ElasticPool>,
+    /// Shared by all connections, so the limit holds for the whole instance
+    pub contact_rate_limiter: Arc<ContactRateLimiter>,
     pub repo_factory: F,
 }
 
@@ -38,13 +46,25 @@ impl<
     > StaticContext<T, M, F>
 {
     /// Create a new static context
-    pub fn new(db_pool: Pool<M>, cpu_pool: CpuPool, client_handle: ClientHandle, config: Arc<Config>, repo_factory: F) -> Self {
+    pub fn new(
+        db_pool: Pool<M>,
+        db_replica_pool: Pool<M>,
+        cpu_pool: CpuPool,
+        client_handle: ClientHandle,
+        config: Arc<Config>,
+        repo_factory: F,
+    ) -> Self {
         let route_parser = Arc::new(create_route_parser());
+        let elastic_pool = Arc::new(ElasticPool::from_config(&config));
+        let contact_rate_limiter = Arc::new(ContactRateLimiter::new(config.store_contact()));
         Self {
             route_parser,
             db_pool,
+            db_replica_pool,
             cpu_pool,
             client_handle,
+            elastic_pool,
+            contact_rate_limiter,
             config,
             repo_factory,
         }
@@ -61,14 +81,49 @@ impl<
         Self {
             cpu_pool: self.cpu_pool.clone(),
             db_pool: self.db_pool.clone(),
+            db_replica_pool: self.db_replica_pool.clone(),
             route_parser: self.route_parser.clone(),
             client_handle: self.client_handle.clone(),
+            elastic_pool: self.elastic_pool.clone(),
+            contact_rate_limiter: self.contact_rate_limiter.clone(),
             config: self.config.clone(),
             repo_factory: self.repo_factory.clone(),
         }
     }
 }
 
+/// Version of the API requested by the client, responses of newer versions may change their shape
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum ApiVersion {
+    V1,
+    /// Prices of products are `Money` objects instead of floats in responses of
+    /// `GET /products`, `GET /products/<id>`, `GET /products/<id>/without_filters`, `GET /products/by_base_product/<id>`,
+    /// `GET /products/by_store/<id>`, `GET /products/by_gtin/<code>`, `GET /products/by_vendor_code`,
+    /// `POST /products/search_by_ids`, `POST /products`, `PUT /products/<id>` and `DELETE /products/<id>`.
+    /// Base products, search results, seller prices and converted prices keep floats
+    V2,
+}
+
+impl ApiVersion {
+    pub fn from_header(value: &str) -> Option<Self> {
+        match value.trim() {
+            "1" => Some(ApiVersion::V1),
+            "2" => Some(ApiVersion::V2),
+            _ => None,
+        }
+    }
+
+    pub fn has_money(self) -> bool {
+        self >= ApiVersion::V2
+    }
+}
+
+impl Default for ApiVersion {
+    fn default() -> Self {
+        ApiVersion::V1
+    }
+}
+
 /// Dynamic context for each request
 #[derive(Clone)]
 pub struct DynamicContext {
@@ -76,16 +131,44 @@ pub struct DynamicContext {
     pub currency: Currency,
     pub fiat_currency: Currency,
     pub correlation_token: String,
+    /// Language of validation messages
+    pub language: Language,
+    /// Superuser acting on behalf of `user_id`
+    pub impersonator: Option<UserId>,
+    pub api_version: ApiVersion,
 }
 
 impl DynamicContext {
     /// Create a new dynamic context for each request
-    pub fn new(user_id: Option<UserId>, currency: Currency, fiat_currency: Currency, correlation_token: String) -> Self {
+    pub fn new(
+        user_id: Option<UserId>,
+        currency: Currency,
+        fiat_currency: Currency,
+        correlation_token: String,
+        language: Language,
+    ) -> Self {
         Self {
             user_id,
             currency,
             fiat_currency,
             correlation_token,
+            language,
+            impersonator: None,
+            api_version: ApiVersion::default(),
+        }
+    }
+
+    /// Context of the same request with responses in the shape of `api_version`
+    pub fn with_api_version(self, api_version: ApiVersion) -> Self {
+        Self { api_version, ..self }
+    }
+
+    /// Context of the same request made with effective ACL of `user_id`
+    pub fn impersonate(self, user_id: UserId) -> Self {
+        Self {
+            impersonator: self.user_id,
+            user_id: Some(user_id),
+            ..self
         }
     }
 
@@ -0,0 +1,49 @@
+//! Embed endpoints are called from third-party sites, their successful responses
+//! may be cached by browsers and proxies and read from any origin.
+use futures::Future;
+use hyper::{
+    self,
+    header::{AccessControlAllowOrigin, CacheControl, CacheDirective},
+    server::{Request, Response, Service},
+};
+
+pub const EMBED_PATH_PREFIX: &'static str = "/embed/";
+
+/// How long embed responses may be reused without asking the server
+const EMBED_MAX_AGE_S: u32 = 300;
+
+/// Wraps application and sets caching and CORS headers on embed responses
+pub struct EmbedHeaders<S> {
+    inner: S,
+}
+
+impl<S> EmbedHeaders<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S> Service for EmbedHeaders<S>
+where
+    S: Service<Request = Request, Response = Response, Error = hyper::Error>,
+    S::Future: 'static,
+{
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error>>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        if !req.path().starts_with(EMBED_PATH_PREFIX) {
+            return Box::new(self.inner.call(req));
+        }
+
+        Box::new(self.inner.call(req).map(|mut response| {
+            if response.status().is_success() {
+                response.headers_mut().set(CacheControl(vec![CacheDirective::Public, CacheDirective::MaxAge(EMBED_MAX_AGE_S)]));
+                response.headers_mut().set(AccessControlAllowOrigin::Any);
+            }
+            response
+        }))
+    }
+}
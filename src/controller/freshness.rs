@@ -0,0 +1,120 @@
+//! Freshness of search results.
+//!
+//! Elastic indices follow the database asynchronously, so search may return stale data for a while
+//! after an update. Search responses get `X-Index-Freshness` header with the time of the last check
+//! that found indices caught up with the database, every change made before that time is searchable.
+//! The header is absent until the first successful check.
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use futures::Future;
+use hyper::{
+    self,
+    server::{Request, Response, Service},
+};
+
+use controller::throttling::SEARCH_PATH_PREFIXES;
+
+pub const INDEX_FRESHNESS_HEADER: &'static str = "X-Index-Freshness";
+
+/// Time of the last successful sync check, shared by all connections and the checking job
+#[derive(Default)]
+pub struct IndexFreshnessState {
+    synced_at: RwLock<Option<SystemTime>>,
+}
+
+impl IndexFreshnessState {
+    pub fn synced_at(&self) -> Option<SystemTime> {
+        match self.synced_at.read() {
+            Ok(synced_at) => *synced_at,
+            Err(e) => {
+                error!("Index freshness state is poisoned: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Records that indices contain every change made before `at`, older times are ignored
+    pub fn mark_synced(&self, at: SystemTime) {
+        match self.synced_at.write() {
+            Ok(mut synced_at) => {
+                if synced_at.map(|current| current < at).unwrap_or(true) {
+                    *synced_at = Some(at);
+                }
+            }
+            Err(e) => error!("Index freshness state is poisoned: {}", e),
+        }
+    }
+}
+
+/// Index is caught up when it has the latest update number the database had when the check started
+pub fn is_index_synced(database_max_update_no: Option<i32>, index_max_update_no: Option<i32>) -> bool {
+    database_max_update_no <= index_max_update_no
+}
+
+/// Wraps application and sets `X-Index-Freshness` header on search responses
+pub struct IndexFreshnessHeader<S> {
+    inner: S,
+    state: Option<Arc<IndexFreshnessState>>,
+}
+
+impl<S> IndexFreshnessHeader<S> {
+    pub fn new(inner: S, state: Option<Arc<IndexFreshnessState>>) -> Self {
+        Self { inner, state }
+    }
+}
+
+impl<S> Service for IndexFreshnessHeader<S>
+where
+    S: Service<Request = Request, Response = Response, Error = hyper::Error>,
+    S::Future: 'static,
+{
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error>>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let state = match self.state {
+            Some(ref state) if SEARCH_PATH_PREFIXES.iter().any(|prefix| req.path().starts_with(prefix)) => state.clone(),
+            _ => return Box::new(self.inner.call(req)),
+        };
+
+        Box::new(self.inner.call(req).map(move |mut response| {
+            if let Some(synced_at) = state.synced_at() {
+                response
+                    .headers_mut()
+                    .set_raw(INDEX_FRESHNESS_HEADER, DateTime::<Utc>::from(synced_at).to_rfc3339());
+            }
+            response
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_is_index_synced() {
+        assert!(is_index_synced(None, None));
+        assert!(is_index_synced(Some(10), Some(10)));
+        assert!(is_index_synced(Some(10), Some(12)));
+        assert!(!is_index_synced(Some(10), Some(9)));
+        assert!(!is_index_synced(Some(10), None));
+    }
+
+    #[test]
+    fn test_mark_synced_keeps_latest() {
+        let state = IndexFreshnessState::default();
+        let now = SystemTime::now();
+
+        state.mark_synced(now);
+        state.mark_synced(now - Duration::from_secs(10));
+
+        assert_eq!(state.synced_at(), Some(now));
+    }
+}
@@ -4,14 +4,19 @@
 //! of `Service` layer to http responses
 
 pub mod context;
+pub mod embed;
+pub mod freshness;
+pub mod rate_limit;
 pub mod responses;
 pub mod routes;
+pub mod throttling;
 pub mod utils;
+pub mod xml;
 
 use std::str::FromStr;
 
 use diesel::{connection::AnsiTransactionManager, pg::Pg, Connection};
-use failure::Fail;
+use failure::{Error as FailureError, Fail};
 use futures::{future, Future, IntoFuture};
 use hyper::{
     header::{Authorization, Cookie},
@@ -31,26 +36,53 @@ use stq_static_resources::{Currency, ModerationStatus};
 use stq_types::*;
 
 use self::routes::Route;
-use controller::context::{DynamicContext, StaticContext};
+use self::utils::{page_count, serialize_products};
+#[cfg(feature = "chaos")]
+use chaos::Fault;
+use controller::context::{ApiVersion, DynamicContext, StaticContext};
+use degradation;
 use errors::Error;
 use models::*;
 use repos::repo_factory::*;
 use repos::CouponSearch;
 use sentry_integration::log_and_capture_error;
+use services::attribute_groups::AttributeGroupsService;
 use services::attribute_values::{AttributeValuesService, NewAttributeValuePayload};
 use services::attributes::AttributesService;
 use services::base_products::BaseProductsService;
 use services::catalogs::CatalogService;
 use services::categories::CategoriesService;
+use services::category_promotions::CategoryPromotionsService;
+#[cfg(feature = "chaos")]
+use services::chaos::ChaosService;
 use services::coupons::CouponsService;
 use services::currency_exchange::CurrencyExchangeService;
 use services::custom_attributes::CustomAttributesService;
+use services::jobs::JobsService;
+use services::listings::ListingsService;
+use services::moderation_checklists::ModerationChecklistsService;
 use services::moderator_comments::ModeratorCommentsService;
+use services::price_rules::PriceRulesService;
 use services::products::ProductsService;
+use services::retention::RetentionService;
+use services::search_synonyms::SearchSynonymsService;
+use services::store_categories::StoreCategoriesService;
+use services::store_faqs::StoreFaqsService;
+use services::stock_reservations::StockReservationsService;
 use services::stores::StoresService;
 use services::user_roles::UserRolesService;
 use services::wizard_stores::WizardStoresService;
 use services::Service;
+use validation_messages::{language_from_header, localize_error, DEFAULT_LANGUAGE};
+
+/// Header with id of the user superuser acts on behalf of
+pub const IMPERSONATE_USER_HEADER: &'static str = "X-Impersonate-User";
+
+/// Header with token of anonymous viewer, product views are counted once per viewer per day
+pub const VIEWER_TOKEN_HEADER: &'static str = "X-Viewer-Token";
+
+/// Header with version of the API, the first one is used when it is missing
+pub const API_VERSION_HEADER: &'static str = "X-Api-Version";
 
 /// Controller handles route parsing and calling `Service` layer
 pub struct ControllerImpl<T, M, F>
@@ -82,6 +114,17 @@ impl<
 {
     /// Handle a request and get future response
     fn call(&self, req: Request) -> ControllerFuture {
+        // Instance endpoints are answered without currency headers so they can be requested from the instance directly
+        match (req.method(), self.static_context.route_parser.test(req.path())) {
+            // GET /version
+            (&Get, Some(Route::Version)) => {
+                return serialize_future(future::ok::<_, FailureError>(VersionInfo::new(&self.static_context.config)));
+            }
+            // GET /metrics/degradation
+            (&Get, Some(Route::DegradationMetrics)) => return serialize_future(future::ok::<_, FailureError>(degradation::stats())),
+            _ => {}
+        }
+
         let headers = req.headers().clone();
         let auth_header = headers.get::<Authorization<String>>();
         let user_id = auth_header
@@ -119,13 +162,69 @@ impl<
 
         let correlation_token = request_util::get_correlation_token(&req);
 
-        let dynamic_context = DynamicContext::new(user_id, currency, fiat_currency, correlation_token);
+        let impersonated_user_id = match headers.get_raw(IMPERSONATE_USER_HEADER).map(|raw| {
+            raw.one()
+                .and_then(|value| ::std::str::from_utf8(value).ok())
+                .and_then(|value| i32::from_str(value.trim()).ok())
+                .map(UserId)
+                .ok_or(format_err!("Invalid {} header", IMPERSONATE_USER_HEADER).context(Error::Parse).into())
+        }) {
+            Some(Ok(v)) => Some(v),
+            Some(Err(e)) => {
+                return Box::new(future::err(e));
+            }
+            None => None,
+        };
+
+        let viewer_token = headers
+            .get_raw(VIEWER_TOKEN_HEADER)
+            .and_then(|raw| raw.one())
+            .and_then(|value| ::std::str::from_utf8(value).ok())
+            .map(|value| value.to_string());
+
+        let language = headers
+            .get_raw("Accept-Language")
+            .and_then(|raw| raw.one())
+            .and_then(|value| ::std::str::from_utf8(value).ok())
+            .map(language_from_header)
+            .unwrap_or(DEFAULT_LANGUAGE);
+
+        let api_version = match headers.get_raw(API_VERSION_HEADER).map(|raw| {
+            raw.one()
+                .and_then(|value| ::std::str::from_utf8(value).ok())
+                .and_then(ApiVersion::from_header)
+                .ok_or(format_err!("Invalid {} header", API_VERSION_HEADER).context(Error::Parse).into())
+        }) {
+            Some(Ok(v)) => v,
+            Some(Err(e)) => {
+                return Box::new(future::err(e));
+            }
+            None => ApiVersion::default(),
+        };
 
-        let service = Service::new(self.static_context.clone(), dynamic_context);
+        let dynamic_context =
+            DynamicContext::new(user_id, currency, fiat_currency, correlation_token, language).with_api_version(api_version);
 
         let path = req.path().to_string();
 
-        let fut = match (&req.method().clone(), self.static_context.route_parser.test(req.path())) {
+        // Impersonated requests are dispatched only after the actor is checked and recorded in the audit log
+        let (service, impersonation) = match impersonated_user_id {
+            Some(impersonated_user_id) => {
+                let actor_service = Service::new(self.static_context.clone(), dynamic_context.clone());
+                let impersonation = actor_service.start_impersonation(impersonated_user_id, req.method().to_string(), path.clone());
+                let service = Service::new(self.static_context.clone(), dynamic_context.impersonate(impersonated_user_id));
+                (service, Some(impersonation))
+            }
+            None => (Service::new(self.static_context.clone(), dynamic_context), None),
+        };
+
+        let route = self.static_context.route_parser.test(req.path());
+        let method = req.method().clone();
+
+        let config = self.static_context.config.clone();
+        let contact_rate_limiter = self.static_context.contact_rate_limiter.clone();
+
+        let dispatch = move || match (&method, route) {
             // GET /stores/<store_id>
             (&Get, Some(Route::Store(store_id))) => {
                 let visibility = parse_query!(req.query().unwrap_or_default(), "visibility" => Visibility);
@@ -138,14 +237,21 @@ impl<
                 serialize_future(service.get_store_by_slug(store_slug, visibility))
             }
 
+            // GET /stores/<store_slug>/profile
+            (&Get, Some(Route::StoreProfile(store_slug))) => serialize_future(service.get_store_profile(store_slug)),
+
             // GET /stores
             (&Get, Some(Route::Stores)) => {
                 let params = parse_query!(
                     req.query().unwrap_or_default(),
-                    "offset" => StoreId, "count" => i32, "visibility" => Visibility
+                    "offset" => StoreId, "count" => i64, "visibility" => Visibility
                 );
+                let count = match page_count(config.page_size("stores"), params.1) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
 
-                if let (Some(offset), Some(count), visibility) = params {
+                if let (Some(offset), _, visibility) = params {
                     serialize_future(service.list_stores(offset, count, visibility))
                 } else {
                     Box::new(future::err(
@@ -162,11 +268,15 @@ impl<
                     req.query().unwrap_or_default(),
                     "skip_base_product_id" => BaseProductId,
                     "offset" => BaseProductId,
-                    "count" => i32,
+                    "count" => i64,
                     "visibility" => Visibility
                 );
+                let count = match page_count(config.page_size("store_products"), params.2) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
 
-                if let (skip_base_product_id, Some(offset), Some(count), visibility) = params {
+                if let (skip_base_product_id, Some(offset), _, visibility) = params {
                     serialize_future(service.get_base_products_of_the_store(store_id, skip_base_product_id, offset, count, visibility))
                 } else {
                     Box::new(future::err(
@@ -186,6 +296,69 @@ impl<
                 serialize_future(service.get_store_products_count(store_id, visibility))
             }
 
+            // GET /stores/:id/products/low_stock route
+            (&Get, Some(Route::StoreProductsLowStock(store_id))) => {
+                let threshold = parse_query!(req.query().unwrap_or_default(), "threshold" => i32);
+                serialize_future(service.get_low_stock_products(store_id, threshold))
+            }
+
+            // POST /stores/:id/products/bulk_deactivate route
+            (&Post, Some(Route::StoreProductsBulkDeactivate(store_id))) => {
+                let dry_run = parse_query!(req.query().unwrap_or_default(), "dry_run" => bool).unwrap_or(false);
+                serialize_future(
+                    parse_body::<BaseProductsBulkDeactivation>(req.body())
+                        .map_err(|e| {
+                            e.context("Parsing body failed, target: BaseProductsBulkDeactivation")
+                                .context(Error::Parse)
+                                .into()
+                        })
+                        .and_then(move |mut payload: BaseProductsBulkDeactivation| {
+                            payload.dry_run = payload.dry_run || dry_run;
+                            service.deactivate_base_products_of_store(store_id, payload)
+                        }),
+                )
+            }
+
+            // PUT /stores/:id/products/order route
+            (&Put, Some(Route::StoreProductsOrder(store_id))) => serialize_future(
+                parse_body::<SetStoreProductsOrder>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: SetStoreProductsOrder").context(Error::Parse).into())
+                    .and_then(move |payload| service.set_store_products_order(store_id, payload)),
+            ),
+
+            // PUT /stores/:id/base_products route
+            (&Put, Some(Route::StoreBaseProducts(store_id))) => serialize_future(
+                parse_body::<Vec<BaseProductBulkUpdate>>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: Vec<BaseProductBulkUpdate>")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |updates| service.update_base_products_of_store(store_id, updates)),
+            ),
+
+            // GET /stores/:id/base_products/archive route
+            (&Get, Some(Route::StoreBaseProductsArchive(store_id))) => {
+                let params = parse_query!(req.query().unwrap_or_default(), "offset" => BaseProductId, "count" => i64);
+                let count = match page_count(config.page_size("store_products"), params.1) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+
+                if let (Some(offset), _) = params {
+                    serialize_future(service.get_archived_base_products_of_the_store(store_id, offset, count))
+                } else {
+                    Box::new(future::err(
+                        format_err!(
+                            "Parsing query parameters failed, action: get archived products by store, store id: {}",
+                            store_id
+                        )
+                        .context(Error::Parse)
+                        .into(),
+                    ))
+                }
+            }
+
             // GET /stores/slug_exists route
             (&Get, Some(Route::StoresSlugExists)) => {
                 if let Some(slug) = parse_query!(req.query().unwrap_or_default(), "slug" => String) {
@@ -232,9 +405,21 @@ impl<
             // POST /stores/moderation
             (&Post, Some(Route::StoreModeration(store_id))) => serialize_future(service.send_store_to_moderation(store_id)),
 
+            // POST /stores/search_by_ids
+            (&Post, Some(Route::StoresByIds)) => serialize_future(
+                parse_body::<Vec<StoreId>>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: Vec<StoreId>").context(Error::Parse).into())
+                    .and_then(move |store_ids| service.find_many_stores(store_ids)),
+            ),
+
             // POST /stores/search
             (&Post, Some(Route::StoresSearch)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("stores_search"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
                     serialize_future(
                         parse_body::<SearchStore>(req.body())
                             .map_err(|e| e.context("Parsing body failed, target: SearchStore").context(Error::Parse).into())
@@ -270,9 +455,58 @@ impl<
                     .and_then(move |search_store| service.search_store_filters_category(search_store)),
             ),
 
+            // POST /stores/search/facets
+            (&Post, Some(Route::StoresSearchFacets)) => {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("stores_search"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                let offset = offset.unwrap_or(0);
+                serialize_future(
+                    parse_body::<SearchStore>(req.body())
+                        .map_err(|e| e.context("Parsing body failed, target: SearchStore").context(Error::Parse).into())
+                        .and_then(move |store_search| service.find_store_by_name_with_facets(store_search, count, offset)),
+                )
+            }
+
+            // POST /stores/search/nearby
+            (&Post, Some(Route::StoresSearchNearby)) => {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("stores_search"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                let offset = offset.unwrap_or(0);
+                serialize_future(
+                    parse_body::<SearchStoresNearby>(req.body())
+                        .map_err(|e| {
+                            e.context("Parsing body failed, target: SearchStoresNearby")
+                                .context(Error::Parse)
+                                .into()
+                        })
+                        .and_then(move |search| {
+                            search
+                                .validate()
+                                .map_err(|e| {
+                                    format_err!("Validation failed, target: SearchStoresNearby")
+                                        .context(Error::Validate(e))
+                                        .into()
+                                })
+                                .into_future()
+                                .and_then(move |_| service.search_nearby(search, count, offset))
+                        }),
+                )
+            }
+
             // POST /stores/auto_complete
             (&Post, Some(Route::StoresAutoComplete)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("stores_auto_complete"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
                     serialize_future(
                         read_body(req.body())
                             .map_err(|e| e.context("Parsing body failed, target: String").context(Error::Parse).into())
@@ -335,7 +569,13 @@ impl<
             (&Delete, Some(Route::Store(store_id))) => serialize_future(service.deactivate_store(store_id)),
 
             // DELETE /stores/:id/delete
-            (&Delete, Some(Route::StoreDelete(store_id))) => serialize_future(service.delete(store_id)),
+            (&Delete, Some(Route::StoreDelete(store_id))) => {
+                if parse_query!(req.query().unwrap_or_default(), "dry_run" => bool).unwrap_or(false) {
+                    serialize_future(service.delete_impact(store_id))
+                } else {
+                    serialize_future(service.delete(store_id))
+                }
+            }
 
             // DELETE /stores/by_saga_id/:saga_id
             (&Delete, Some(Route::StoreBySagaId(saga_id))) => serialize_future(service.deactivate_store_by_saga_id(saga_id)),
@@ -351,30 +591,317 @@ impl<
                 serialize_future(service.set_store_moderation_status(store_id, ModerationStatus::Published))
             }
 
+            // POST /stores/<store_id>/preview_currency_change
+            (&Post, Some(Route::StorePreviewCurrencyChange(store_id))) => serialize_future(
+                parse_body::<PreviewCurrencyChange>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: PreviewCurrencyChange")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.preview_currency_change(store_id, payload)),
+            ),
+
+            // GET /stores/<store_id>/quota
+            (&Get, Some(Route::StoreQuota(store_id))) => serialize_future(service.get_store_quota(store_id)),
+
+            // PUT /internal/stores/<store_id>/quota_plan
+            (&Put, Some(Route::StoreQuotaPlan(store_id))) => serialize_future(
+                parse_body::<SetStoreQuotaPlan>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: SetStoreQuotaPlan").context(Error::Parse).into())
+                    .and_then(move |payload| service.set_store_quota_plan(store_id, payload)),
+            ),
+
+            // GET /stores/<store_id>/onboarding
+            (&Get, Some(Route::StoreOnboarding(store_id))) => serialize_future(service.get_store_onboarding(store_id)),
+
+            // PUT /internal/stores/<store_id>/onboarding
+            (&Put, Some(Route::StoreOnboardingFacts(store_id))) => serialize_future(
+                parse_body::<SetStoreOnboarding>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: SetStoreOnboarding").context(Error::Parse).into())
+                    .and_then(move |payload| service.set_store_onboarding(store_id, payload)),
+            ),
+
+            // PUT /stores/<store_id>/franchise
+            (&Put, Some(Route::StoreFranchise(store_id))) => serialize_future(
+                parse_body::<SetStoreFranchise>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: SetStoreFranchise").context(Error::Parse).into())
+                    .and_then(move |payload| service.set_store_franchise(store_id, payload)),
+            ),
+
+            // POST /stores/<store_id>/vacation
+            (&Post, Some(Route::StoreVacation(store_id))) => serialize_future(
+                parse_body::<SetStoreVacation>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: SetStoreVacation").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: SetStoreVacation")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.set_store_vacation(store_id, payload))
+                    }),
+            ),
+
+            // POST /stores/<store_id>/verify
+            (&Post, Some(Route::StoreVerify(store_id))) => serialize_future(
+                parse_body::<VerifyStore>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: VerifyStore").context(Error::Parse).into())
+                    .and_then(move |payload| service.verify_store(store_id, payload)),
+            ),
+
+            // POST /stores/<store_id>/transfer_ownership
+            (&Post, Some(Route::StoreTransferOwnership(store_id))) => serialize_future(
+                parse_body::<TransferStoreOwnership>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: TransferStoreOwnership").context(Error::Parse).into())
+                    .and_then(move |payload| service.transfer_store_ownership(store_id, payload)),
+            ),
+
+            // POST /stores/<store_id>/clone
+            (&Post, Some(Route::StoreClone(store_id))) => serialize_future(
+                parse_body::<CloneStore>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: CloneStore").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: CloneStore")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.clone_store(store_id, payload))
+                    }),
+            ),
+
+            // GET /stores/<store_id>/catalog_health
+            (&Get, Some(Route::StoreCatalogHealth(store_id))) => {
+                let stale_price_days = parse_query!(req.query().unwrap_or_default(), "stale_price_days" => u64);
+                serialize_future(service.get_catalog_health(store_id, stale_price_days))
+            }
+
+            // GET /stores/<store_id>/statistics
+            (&Get, Some(Route::StoreStatistics(store_id))) => serialize_future(service.get_store_statistics(store_id)),
+
+            // GET /stores/<store_id>/categories
+            (&Get, Some(Route::StoreCategories(store_id))) => serialize_future(service.list_store_categories(store_id)),
+
+            // POST /stores/<store_id>/categories
+            (&Post, Some(Route::StoreCategories(store_id))) => serialize_future(
+                parse_body::<NewCategoryExpansion>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: NewCategoryExpansion").context(Error::Parse).into())
+                    .and_then(move |payload| service.request_category_expansion(store_id, payload)),
+            ),
+
+            // PUT /stores/<store_id>/categories/<category_id>
+            (&Put, Some(Route::StoreCategory(store_id, category_id))) => serialize_future(
+                parse_body::<ModerateCategoryExpansion>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: ModerateCategoryExpansion")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.moderate_category_expansion(store_id, category_id, payload)),
+            ),
+
+            // GET /stores/<store_id>/faqs
+            (&Get, Some(Route::StoreFaqs(store_id))) => serialize_future(service.list_store_faqs(store_id)),
+
+            // POST /stores/<store_id>/faqs
+            (&Post, Some(Route::StoreFaqs(store_id))) => serialize_future(
+                parse_body::<NewStoreFaqPayload>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: NewStoreFaqPayload").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: NewStoreFaqPayload")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.create_store_faq(NewStoreFaq::new(store_id, payload)))
+                    }),
+            ),
+
+            // PUT /stores/<store_id>/faqs/<faq_id>
+            (&Put, Some(Route::StoreFaq(store_id, faq_id))) => serialize_future(
+                parse_body::<UpdateStoreFaq>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: UpdateStoreFaq").context(Error::Parse).into())
+                    .and_then(move |update| {
+                        update
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: UpdateStoreFaq")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.update_store_faq(store_id, faq_id, update))
+                    }),
+            ),
+
+            // DELETE /stores/<store_id>/faqs/<faq_id>
+            (&Delete, Some(Route::StoreFaq(store_id, faq_id))) => serialize_future(service.delete_store_faq(store_id, faq_id)),
+
+            // GET /stores/<store_id>/price_rules
+            (&Get, Some(Route::StorePriceRules(store_id))) => serialize_future(service.list_price_rules(store_id)),
+
+            // POST /stores/<store_id>/price_rules
+            (&Post, Some(Route::StorePriceRules(store_id))) => serialize_future(
+                parse_body::<NewPriceRulePayload>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: NewPriceRulePayload").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: NewPriceRulePayload")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.create_price_rule(NewPriceRule::new(store_id, payload)))
+                    }),
+            ),
+
+            // PUT /stores/<store_id>/price_rules/<price_rule_id>
+            (&Put, Some(Route::StorePriceRule(store_id, price_rule_id))) => serialize_future(
+                parse_body::<UpdatePriceRule>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: UpdatePriceRule").context(Error::Parse).into())
+                    .and_then(move |update| {
+                        update
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: UpdatePriceRule")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.update_price_rule(store_id, price_rule_id, update))
+                    }),
+            ),
+
+            // DELETE /stores/<store_id>/price_rules/<price_rule_id>
+            (&Delete, Some(Route::StorePriceRule(store_id, price_rule_id))) => {
+                serialize_future(service.delete_price_rule(store_id, price_rule_id))
+            }
+
+            // POST /stores/<store_id>/price_rules/preview
+            (&Post, Some(Route::StorePriceRulesPreview(store_id))) => serialize_future(
+                parse_body::<PreviewPriceRules>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: PreviewPriceRules").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: PreviewPriceRules")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.preview_price_rules(store_id, payload))
+                    }),
+            ),
+
+            // GET /stores/<store_id>/feed.rss
+            (&Get, Some(Route::StoreFeedRss(store_id))) => {
+                let lang = parse_query!(req.query().unwrap_or_default(), "lang" => String);
+                service.get_store_rss(store_id, lang)
+            }
+
+            // GET /stores/<store_id>/sitemap.xml
+            (&Get, Some(Route::StoreSitemap(store_id))) => service.get_store_sitemap(store_id),
+
+            // POST /stores/<store_id>/verification
+            (&Post, Some(Route::StoreVerification(store_id))) => serialize_future(
+                parse_body::<SendStoreVerification>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: SendStoreVerification")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.send_store_verification(store_id, payload)),
+            ),
+
+            // POST /stores/<store_id>/verification/confirm
+            (&Post, Some(Route::StoreVerificationConfirm(store_id))) => serialize_future(
+                parse_body::<ConfirmStoreVerification>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: ConfirmStoreVerification")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.confirm_store_verification(store_id, payload)),
+            ),
+
+            // POST /stores/<store_id>/contact
+            (&Post, Some(Route::StoreContact(store_id))) => {
+                if let Err(e) = contact_rate_limiter.check(user_id) {
+                    return Box::new(future::err(e));
+                }
+
+                serialize_future(
+                    parse_body::<StoreContactMessage>(req.body())
+                        .map_err(|e| {
+                            e.context("Parsing body failed, target: StoreContactMessage")
+                                .context(Error::Parse)
+                                .into()
+                        })
+                        .and_then(move |payload| {
+                            payload
+                                .validate()
+                                .map_err(|e| {
+                                    format_err!("Validation failed, target: StoreContactMessage")
+                                        .context(Error::Validate(e))
+                                        .into()
+                                })
+                                .into_future()
+                                .and_then(move |_| service.contact_store(store_id, payload))
+                        }),
+                )
+            }
+
             // POST /stores/<store_id>/draft
             (&Post, Some(Route::StoreDraft(store_id))) => serialize_future(service.set_store_moderation_status_draft(store_id)),
 
             // GET /products/<product_id>
-            (&Get, Some(Route::Product(product_id))) => serialize_future(service.get_product(product_id)),
+            (&Get, Some(Route::Product(product_id))) => serialize_products(api_version, service.get_product(product_id)),
 
             // GET /products/<product_id>/without_filters
-            (&Get, Some(Route::ProductWithoutFilters(product_id))) => serialize_future(service.get_product_without_filters(product_id)),
+            (&Get, Some(Route::ProductWithoutFilters(product_id))) => {
+                serialize_products(api_version, service.get_product_without_filters(product_id))
+            }
 
             // GET /products/by_base_product/<base_product_id> route
             (&Get, Some(Route::ProductsByBaseProduct(base_product_id))) => {
-                serialize_future(service.find_products_with_base_id(base_product_id))
+                serialize_products(api_version, service.find_products_with_base_id(base_product_id))
             }
 
             // GET /products/by_store/<store_id> route
-            (&Get, Some(Route::ProductsByStore(store_id))) => serialize_future(service.find_products_with_store_id(store_id)),
+            (&Get, Some(Route::ProductsByStore(store_id))) => {
+                serialize_products(api_version, service.find_products_with_store_id(store_id))
+            }
 
             // GET /products/<product_id>/attributes route
             (&Get, Some(Route::ProductAttributes(product_id))) => serialize_future(service.find_products_attributes(product_id)),
 
+            // GET /products/<product_id>/attributes/grouped
+            (&Get, Some(Route::ProductGroupedAttributes(product_id))) => {
+                serialize_future(service.find_products_grouped_attributes(product_id))
+            }
+
             // GET /products
             (&Get, Some(Route::Products)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
-                    serialize_future(service.list_products(offset, count))
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("products"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
+                    serialize_products(api_version, service.list_products(offset, count))
                 } else {
                     Box::new(future::err(
                         format_err!("Parsing query parameters failed, action: get products")
@@ -385,12 +912,20 @@ impl<
             }
 
             // Post /products/search_by_ids
-            (&Post, Some(Route::ProductsByIds)) => serialize_future(
+            (&Post, Some(Route::ProductsByIds)) => serialize_products(
+                api_version,
                 parse_body::<GetProducts>(req.body())
                     .map_err(|e| e.context("Parsing body failed, target: GetProducts").context(Error::Parse).into())
                     .and_then(move |payload| service.get_products(payload.ids)),
             ),
 
+            // POST /products/convert_prices
+            (&Post, Some(Route::ProductsConvertPrices)) => serialize_future(
+                parse_body::<Vec<ConvertPrice>>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: Vec<ConvertPrice>").context(Error::Parse).into())
+                    .and_then(move |payload| service.convert_prices(payload)),
+            ),
+
             // GET /products/store_id
             (&Get, Some(Route::ProductStoreId)) => {
                 let params = parse_query!(
@@ -410,8 +945,31 @@ impl<
                 }
             }
 
+            // GET /products/by_gtin/<code>
+            (&Get, Some(Route::ProductsByGtin(gtin))) => serialize_products(api_version, service.get_products_by_gtin(gtin)),
+
+            // GET /products/by_vendor_code
+            (&Get, Some(Route::ProductByVendorCode)) => {
+                let params = parse_query!(
+                    req.query().unwrap_or_default(),
+                    "store_id" => StoreId,
+                    "vendor_code" => String
+                );
+
+                if let (Some(store_id), Some(vendor_code)) = params {
+                    serialize_products(api_version, service.get_product_by_vendor_code(store_id, vendor_code))
+                } else {
+                    Box::new(future::err(
+                        format_err!("Parsing query parameters failed, action: get product by vendor code")
+                            .context(Error::Parse)
+                            .into(),
+                    ))
+                }
+            }
+
             // POST /products
-            (&Post, Some(Route::Products)) => serialize_future(
+            (&Post, Some(Route::Products)) => serialize_products(
+                api_version,
                 parse_body::<NewProductWithAttributes>(req.body())
                     .map_err(|e| {
                         e.context("Parsing body failed, target: NewProductWithAttributes")
@@ -433,7 +991,8 @@ impl<
             ),
 
             // PUT /products/<product_id>
-            (&Put, Some(Route::Product(product_id))) => serialize_future(
+            (&Put, Some(Route::Product(product_id))) => serialize_products(
+                api_version,
                 parse_body::<UpdateProductWithAttributes>(req.body())
                     .map_err(|e| {
                         e.context("Parsing body failed, target: UpdateProductWithAttributes")
@@ -458,7 +1017,7 @@ impl<
             ),
 
             // DELETE /products/<product_id>
-            (&Delete, Some(Route::Product(product_id))) => serialize_future(service.deactivate_product(product_id)),
+            (&Delete, Some(Route::Product(product_id))) => serialize_products(api_version, service.deactivate_product(product_id)),
 
             // GET /base_products/<base_product_id>
             (&Get, Some(Route::BaseProduct(base_product_id))) => {
@@ -479,12 +1038,16 @@ impl<
 
             // GET /base_products/<base_product_id>/update_view
             (&Get, Some(Route::BaseProductWithViewsUpdate(base_product_id))) => {
-                serialize_future(service.get_base_product_with_views_update(base_product_id))
+                serialize_future(service.get_base_product_with_views_update(base_product_id, viewer_token))
             }
 
             // GET /store/by-slug/<store_slug>/base_products/by-slug/<base_product_slug>/update_view
             (&Get, Some(Route::BaseProductBySlugWithViewsUpdate(store_slug, base_product_slug))) => {
-                serialize_future(service.get_base_product_by_slug_with_views_update(StoreIdentifier::Slug(store_slug), base_product_slug))
+                serialize_future(service.get_base_product_by_slug_with_views_update(
+                    StoreIdentifier::Slug(store_slug),
+                    base_product_slug,
+                    viewer_token,
+                ))
             }
 
             // GET /base_products/<base_product_id>/custom_attributes
@@ -492,6 +1055,9 @@ impl<
                 serialize_future(service.get_custom_attributes_by_base_product(base_product_id))
             }
 
+            // GET /base_products/<base_product_id>/history
+            (&Get, Some(Route::BaseProductHistory(base_product_id))) => serialize_future(service.get_base_product_history(base_product_id)),
+
             // GET /base_products/by_product/<product_id>
             (&Get, Some(Route::BaseProductByProduct(product_id))) => {
                 let visibility = parse_query!(req.query().unwrap_or_default(), "visibility" => Visibility);
@@ -503,17 +1069,28 @@ impl<
             (&Get, Some(Route::BaseProducts)) => {
                 let params = parse_query!(
                     req.query().unwrap_or_default(),
-                    "offset" => BaseProductId, "count" => i32, "visibility" => Visibility
+                    "offset" => BaseProductId, "count" => i64, "visibility" => Visibility, "cursor" => String
                 );
-
-                if let (Some(offset), Some(count), visibility) = params {
-                    serialize_future(service.list_base_products(offset, count, visibility))
-                } else {
-                    Box::new(future::err(
+                let count = match page_count(config.page_size("base_products"), params.1) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+
+                match params {
+                    (Some(offset), _, visibility, None) => serialize_future(service.list_base_products(offset, count, visibility)),
+                    (None, _, visibility, cursor) => {
+                        let cursor = match cursor.map(|cursor| BaseProductsCursor::decode(&cursor)) {
+                            Some(Ok(cursor)) => Some(cursor),
+                            Some(Err(e)) => return Box::new(future::err(e.context("Parsing cursor failed").context(Error::Parse).into())),
+                            None => None,
+                        };
+                        serialize_future(service.list_base_products_by_cursor(cursor, count, visibility))
+                    }
+                    _ => Box::new(future::err(
                         format_err!("Parsing query parameters failed, action: get base products")
                             .context(Error::Parse)
                             .into(),
-                    ))
+                    )),
                 }
             }
 
@@ -525,7 +1102,7 @@ impl<
                             .context(Error::Parse)
                             .into()
                     })
-                    .and_then(move |payload| service.get_base_products(payload.ids)),
+                    .and_then(move |payload| service.find_many(payload.ids)),
             ),
 
             // GET /base_products/count
@@ -570,6 +1147,13 @@ impl<
                     .and_then(move |payload| service.replace_category(payload)),
             ),
 
+            // POST /moderator/base_products/reassign_category
+            (&Post, Some(Route::ModeratorCategoryReassignment)) => serialize_future(
+                parse_body::<CategoryReassignment>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: CategoryReassignment").context(Error::Parse).into())
+                    .and_then(move |payload| service.start_category_reassignment(payload)),
+            ),
+
             // POST /base_products/moderate
             (&Post, Some(Route::BaseProductModerate)) => serialize_future(
                 parse_body::<BaseProductModerate>(req.body())
@@ -579,7 +1163,11 @@ impl<
                             .into()
                     })
                     .and_then(move |base_product_moderate| {
-                        service.set_moderation_status_base_product(base_product_moderate.base_product_id, base_product_moderate.status)
+                        service.set_moderation_status_base_product(
+                            base_product_moderate.base_product_id,
+                            base_product_moderate.status,
+                            base_product_moderate.checklist,
+                        )
                     }),
             ),
 
@@ -657,39 +1245,142 @@ impl<
             // DELETE /base_products/<base_product_id>
             (&Delete, Some(Route::BaseProduct(base_product_id))) => serialize_future(service.deactivate_base_product(base_product_id)),
 
-            // POST /base_products/search
-            (&Post, Some(Route::BaseProductsSearch)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
-                    serialize_future(
-                        parse_body::<SearchProductsByName>(req.body())
+            // POST /base_products/<base_product_id>/restore
+            (&Post, Some(Route::BaseProductRestore(base_product_id))) => serialize_future(service.restore_base_product(base_product_id)),
+
+            // POST /base_products/<base_product_id>/duplicate
+            (&Post, Some(Route::BaseProductDuplicate(base_product_id))) => {
+                serialize_future(service.duplicate_base_product(base_product_id))
+            }
+
+            // POST /base_products/<base_product_id>/transfer
+            (&Post, Some(Route::BaseProductTransfer(base_product_id))) => serialize_future(
+                parse_body::<TransferBaseProduct>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: TransferBaseProduct").context(Error::Parse).into())
+                    .and_then(move |payload| service.transfer_base_product(base_product_id, payload)),
+            ),
+
+            // PUT /base_products/<base_product_id>/products/prices
+            (&Put, Some(Route::BaseProductProductsPrices(base_product_id))) => serialize_future(
+                parse_body::<UpdateProductPrices>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: UpdateProductPrices").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
                             .map_err(|e| {
-                                e.context("Parsing body failed, target: SearchProductsByName")
-                                    .context(Error::Parse)
+                                format_err!("Validation failed, target: UpdateProductPrices")
+                                    .context(Error::Validate(e))
                                     .into()
                             })
-                            .and_then(move |prod| service.search_base_products_by_name(prod, count, offset)),
-                    )
-                } else {
-                    Box::new(future::err(
-                        format_err!("Parsing query parameters failed, action: search base products")
-                            .context(Error::Parse)
-                            .into(),
-                    ))
+                            .into_future()
+                            .and_then(move |_| service.update_prices_by_base_product(base_product_id, payload))
+                    }),
+            ),
+
+            // PUT /base_products/<base_product_id>/products/order
+            (&Put, Some(Route::BaseProductProductsOrder(base_product_id))) => serialize_future(
+                parse_body::<ReorderProducts>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: ReorderProducts").context(Error::Parse).into())
+                    .and_then(move |payload| service.reorder_products(base_product_id, payload)),
+            ),
+
+            // GET /base_products/<base_product_id>/related
+            (&Get, Some(Route::BaseProductRelated(base_product_id))) => {
+                let count = parse_query!(req.query().unwrap_or_default(), "count" => i64);
+                match page_count(config.page_size("base_products_related"), count) {
+                    Ok(count) => serialize_future(service.get_related_base_products(base_product_id, count as i32)),
+                    Err(e) => Box::new(future::err(e)),
                 }
             }
 
-            // POST /base_products/auto_complete
-            (&Post, Some(Route::BaseProductsAutoComplete)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
+            // POST /base_products/<base_product_id>/publish
+            (&Post, Some(Route::BaseProductPublishById(base_product_id))) => {
+                serialize_future(service.publish_base_product(base_product_id))
+            }
+
+            // POST /base_products/<base_product_id>/unpublish
+            (&Post, Some(Route::BaseProductUnpublish(base_product_id))) => {
+                serialize_future(service.unpublish_base_product(base_product_id))
+            }
+
+            // POST /base_products/search
+            (&Post, Some(Route::BaseProductsSearch)) => {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("base_products_search"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
                     serialize_future(
-                        parse_body::<AutoCompleteProductName>(req.body())
+                        parse_body::<SearchProductsByName>(req.body())
                             .map_err(|e| {
-                                e.context("Parsing body failed, target: AutoCompleteProductName")
+                                e.context("Parsing body failed, target: SearchProductsByName")
                                     .context(Error::Parse)
                                     .into()
                             })
-                            .and_then(move |name| service.base_products_auto_complete(name, count, offset)),
+                            .and_then(move |prod| service.search_base_products_by_name(prod, count, offset)),
                     )
+                } else {
+                    Box::new(future::err(
+                        format_err!("Parsing query parameters failed, action: search base products")
+                            .context(Error::Parse)
+                            .into(),
+                    ))
+                }
+            }
+
+            // POST /base_products/search/after
+            (&Post, Some(Route::BaseProductsSearchAfter)) => {
+                let (count, token) = parse_query!(req.query().unwrap_or_default(), "count" => i64, "token" => String);
+                let count = match page_count(config.page_size("base_products_search"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                let token = match token.map(|token| SearchAfterToken::decode(&token)) {
+                    Some(Ok(token)) => Some(token),
+                    Some(Err(e)) => return Box::new(future::err(e.context("Parsing search token failed").context(Error::Parse).into())),
+                    None => None,
+                };
+                serialize_future(
+                    parse_body::<SearchProductsByName>(req.body())
+                        .map_err(|e| {
+                            e.context("Parsing body failed, target: SearchProductsByName")
+                                .context(Error::Parse)
+                                .into()
+                        })
+                        .and_then(move |prod| service.search_base_products_after(prod, count, token)),
+                )
+            }
+
+            // POST /base_products/auto_complete
+            (&Post, Some(Route::BaseProductsAutoComplete)) => {
+                let (offset, count, with_suggestions, suggest_corrections) = parse_query!(
+                    req.query().unwrap_or_default(),
+                    "offset" => i32,
+                    "count" => i64,
+                    "with_suggestions" => bool,
+                    "suggest_corrections" => bool
+                );
+                let suggest_corrections = suggest_corrections.unwrap_or(false);
+                let count = match page_count(config.page_size("base_products_auto_complete"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
+                    let name = parse_body::<AutoCompleteProductName>(req.body()).map_err(|e| {
+                        e.context("Parsing body failed, target: AutoCompleteProductName")
+                            .context(Error::Parse)
+                            .into()
+                    });
+                    if with_suggestions.unwrap_or(false) {
+                        serialize_future(name.and_then(move |name| {
+                            service.base_products_auto_complete_suggestions(name, count, offset, suggest_corrections)
+                        }))
+                    } else {
+                        serialize_future(name.and_then(move |name| {
+                            service.base_products_auto_complete(name, count, offset, suggest_corrections)
+                        }))
+                    }
                 } else {
                     Box::new(future::err(
                         format_err!("Parsing query parameters failed, action: auto complete base products")
@@ -701,7 +1392,12 @@ impl<
 
             // POST /base_products/most_discount
             (&Post, Some(Route::BaseProductsMostDiscount)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("base_products_most_discount"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
                     serialize_future(
                         parse_body::<MostDiscountProducts>(req.body())
                             .map_err(|e| {
@@ -722,7 +1418,12 @@ impl<
 
             // POST /base_products/most_viewed
             (&Post, Some(Route::BaseProductsMostViewed)) => {
-                if let (Some(offset), Some(count)) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i32) {
+                let (offset, count) = parse_query!(req.query().unwrap_or_default(), "offset" => i32, "count" => i64);
+                let count = match page_count(config.page_size("base_products_most_viewed"), count) {
+                    Ok(count) => count as i32,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                if let Some(offset) = offset {
                     serialize_future(
                         parse_body::<MostViewedProducts>(req.body())
                             .map_err(|e| {
@@ -741,6 +1442,18 @@ impl<
                 }
             }
 
+            // GET /embed/base_products/<base_product_id>
+            (&Get, Some(Route::EmbedBaseProduct(base_product_id))) => serialize_future(service.get_embed_base_product(base_product_id)),
+
+            // GET /base_products/sample
+            (&Get, Some(Route::BaseProductsSample)) => {
+                let (count, category_id) = parse_query!(req.query().unwrap_or_default(), "count" => i64, "category" => CategoryId);
+                match page_count(config.page_size("base_products_sample"), count) {
+                    Ok(count) => serialize_future(service.sample_base_products(category_id, count as i32)),
+                    Err(e) => Box::new(future::err(e)),
+                }
+            }
+
             // POST /base_products/search/filters/price
             (&Post, Some(Route::BaseProductsSearchFiltersPrice)) => serialize_future(
                 parse_body::<SearchProductsByName>(req.body())
@@ -771,6 +1484,22 @@ impl<
                     })
                     .and_then(move |search_prod| service.search_base_products_attributes(search_prod)),
             ),
+            // POST /search/facet_values
+            (&Post, Some(Route::SearchFacetValues)) => serialize_future(
+                parse_body::<SearchFacetValues>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: SearchFacetValues").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: SearchFacetValues")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.search_facet_values(payload))
+                    }),
+            ),
             // POST /base_products/search/filters/count
             (&Post, Some(Route::BaseProductsSearchFiltersCount)) => serialize_future(
                 parse_body::<SearchProductsByName>(req.body())
@@ -782,6 +1511,27 @@ impl<
                     .and_then(move |search_prod| service.search_base_products_filters_count(search_prod)),
             ),
 
+            // POST /base_products/match
+            (&Post, Some(Route::BaseProductsMatch)) => serialize_future(
+                parse_body::<ProductMatchPayload>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: ProductMatchPayload")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: ProductMatchPayload")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.match_base_products(payload))
+                    }),
+            ),
+
             // POST /base_products/publish
             (&Post, Some(Route::BaseProductPublish)) => serialize_future(
                 parse_body::<Vec<BaseProductId>>(req.body())
@@ -800,6 +1550,11 @@ impl<
                 serialize_future(service.set_base_product_moderation_status_draft(base_product_id))
             }
 
+            // GET /base_products/<base_product_id>/decline_details
+            (&Get, Some(Route::BaseProductDeclineDetails(base_product_id))) => {
+                serialize_future(service.get_base_product_decline_details(base_product_id))
+            }
+
             // POST /custom_attributes
             (&Post, Some(Route::CustomAttributes)) => serialize_future(
                 parse_body::<NewCustomAttribute>(req.body())
@@ -844,6 +1599,32 @@ impl<
             // GET /coupons/:id
             (&Get, Some(Route::Coupon(coupon_id))) => serialize_future(service.get_coupon(coupon_id)),
 
+            // GET /admin/coupons
+            (&Get, Some(Route::AdminCoupons)) => {
+                let (store_id, is_active, expiring_within_days, scope) = parse_query!(
+                    req.query().unwrap_or_default(),
+                    "store_id" => StoreId, "is_active" => bool, "expiring_within_days" => u32, "scope" => CouponScope
+                );
+                let (offset, skip_opt, count_opt) = parse_query!(
+                    req.query().unwrap_or_default(),
+                    "offset" => CouponId, "skip" => i64, "count" => i64
+                );
+
+                let skip = skip_opt.unwrap_or(0);
+                let count = match page_count(config.page_size("admin_coupons"), count_opt) {
+                    Ok(count) => count,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+                let terms = AdminCouponsSearchTerms {
+                    store_id,
+                    is_active,
+                    expiring_within_days,
+                    scope,
+                };
+
+                serialize_future(service.admin_search_coupons(offset, skip, count, terms))
+            }
+
             // GET /coupons/generate_code
             (&Get, Some(Route::CouponsGenerateCode)) => serialize_future(service.generate_coupon_code()),
 
@@ -937,6 +1718,28 @@ impl<
                 }),
             ) => serialize_future(service.delete_used_coupon(coupon_id, user_id_arg)),
 
+            // POST /internal/coupons/redeem
+            (&Post, Some(Route::CouponsRedeem)) => serialize_future(
+                parse_body::<RedeemCouponPayload>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: RedeemCouponPayload")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.redeem_coupon(payload)),
+            ),
+
+            // DELETE /internal/coupons/redemptions/:token
+            (&Delete, Some(Route::CouponRedemption(token))) => serialize_future(service.void_coupon_redemption(token)),
+
+            // POST /internal/compensations/stores/:saga_id
+            (&Post, Some(Route::CompensationStore(saga_id))) => serialize_future(service.compensate_store_creation(saga_id)),
+
+            // POST /internal/compensations/base_products/:saga_id
+            (&Post, Some(Route::CompensationBaseProducts(saga_id))) => {
+                serialize_future(service.compensate_base_products_creation(saga_id))
+            }
+
             (&Get, Some(Route::RolesByUserId { user_id })) => serialize_future({ service.get_roles(user_id) }),
             (&Get, Some(Route::UserIdByRole { role })) => serialize_future({ service.get_user_ids_by_role(role) }),
             (&Post, Some(Route::Roles)) => {
@@ -951,6 +1754,9 @@ impl<
             // GET /attributes/<attribute_id>
             (&Get, Some(Route::Attribute(attribute_id))) => serialize_future(service.get_attribute(attribute_id)),
 
+            // GET /attributes/<attribute_id>/schema
+            (&Get, Some(Route::AttributeSchema(attribute_id))) => serialize_future(service.get_attribute_schema(attribute_id)),
+
             // GET /attributes/values/<attribute_value_id>
             (&Get, Some(Route::AttributeValue(attribute_value_id))) => serialize_future(service.get_attribute_value(attribute_value_id)),
 
@@ -980,6 +1786,75 @@ impl<
                     }),
             ),
 
+            // GET /attributes/groups
+            (&Get, Some(Route::AttributeGroups)) => serialize_future(service.list_attribute_groups()),
+
+            // POST /attributes/groups
+            (&Post, Some(Route::AttributeGroups)) => serialize_future(
+                parse_body::<NewAttributeGroup>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: NewAttributeGroup").context(Error::Parse).into())
+                    .and_then(move |new_group| {
+                        new_group
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: NewAttributeGroup")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.create_attribute_group(new_group))
+                    }),
+            ),
+
+            // PUT /attributes/groups/<group_id>
+            (&Put, Some(Route::AttributeGroup(group_id))) => serialize_future(
+                parse_body::<UpdateAttributeGroup>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: UpdateAttributeGroup")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |update| {
+                        update
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: UpdateAttributeGroup")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.update_attribute_group(group_id, update))
+                    }),
+            ),
+
+            // DELETE /attributes/groups/<group_id>
+            (&Delete, Some(Route::AttributeGroup(group_id))) => serialize_future(service.delete_attribute_group(group_id)),
+
+            // PUT /attributes/groups/<group_id>/attributes
+            (&Put, Some(Route::AttributeGroupAttributes(group_id))) => serialize_future(
+                parse_body::<SetAttributeGroupAttributes>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: SetAttributeGroupAttributes")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.set_attribute_group_attributes(group_id, payload)),
+            ),
+
+            // POST /attributes/values/translations/import
+            (&Post, Some(Route::AttributeValueTranslationsImport)) => serialize_future(
+                read_body(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: String").context(Error::Parse).into())
+                    .and_then(move |csv| service.import_attribute_value_translations(csv)),
+            ),
+
+            // POST /attributes/values/translations/import_jobs
+            (&Post, Some(Route::AttributeValueTranslationsImportJob)) => serialize_future(
+                read_body(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: String").context(Error::Parse).into())
+                    .and_then(move |csv| service.start_attribute_value_translations_import(csv)),
+            ),
+
             // GET /attributes/<attribute_id>/values
             (&Get, Some(Route::AttributeValues(attribute_id))) => serialize_future(service.get_attribute_values(attribute_id)),
 
@@ -1055,10 +1930,170 @@ impl<
             ),
 
             // DELETE /attributes/<attribute_id>
-            (&Delete, Some(Route::Attribute(attribute_id))) => serialize_future(service.delete_attribute(attribute_id)),
+            (&Delete, Some(Route::Attribute(attribute_id))) => {
+                if parse_query!(req.query().unwrap_or_default(), "dry_run" => bool).unwrap_or(false) {
+                    serialize_future(service.delete_attribute_impact(attribute_id))
+                } else {
+                    serialize_future(service.delete_attribute(attribute_id))
+                }
+            }
 
             (&Get, Some(Route::Catalog)) => serialize_future(service.get_catalog()),
 
+            // GET /chaos/faults
+            #[cfg(feature = "chaos")]
+            (&Get, Some(Route::ChaosFaults)) => serialize_future(service.get_faults()),
+
+            // PUT /chaos/faults/<layer>
+            #[cfg(feature = "chaos")]
+            (&Put, Some(Route::ChaosFault(layer))) => serialize_future(
+                parse_body::<Fault>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: Fault").context(Error::Parse).into())
+                    .and_then(move |fault| {
+                        fault
+                            .validate()
+                            .map_err(|e| format_err!("Validation failed, target: Fault").context(Error::Validate(e)).into())
+                            .into_future()
+                            .and_then(move |_| service.set_fault(layer, fault))
+                    }),
+            ),
+
+            // DELETE /chaos/faults
+            #[cfg(feature = "chaos")]
+            (&Delete, Some(Route::ChaosFaults)) => serialize_future(service.reset_faults()),
+
+            // GET /search/synonyms
+            (&Get, Some(Route::SearchSynonyms)) => serialize_future(service.list_search_synonyms()),
+
+            // POST /search/synonyms
+            (&Post, Some(Route::SearchSynonyms)) => serialize_future(
+                parse_body::<NewSearchSynonym>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: NewSearchSynonym").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: NewSearchSynonym")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.create_search_synonym(payload))
+                    }),
+            ),
+
+            // DELETE /search/synonyms/<synonym_id>
+            (&Delete, Some(Route::SearchSynonym(synonym_id))) => serialize_future(service.delete_search_synonym(synonym_id)),
+
+            // GET /admin/elastic/migrate/<migration_id>/verification
+            (&Get, Some(Route::IndexMigrationVerification(migration_id))) => {
+                serialize_future(service.get_index_migration_verification(migration_id))
+            }
+
+            // POST /admin/retention
+            (&Post, Some(Route::Retention)) => {
+                if parse_query!(req.query().unwrap_or_default(), "dry_run" => bool).unwrap_or(false) {
+                    serialize_future(service.run_retention_impact())
+                } else {
+                    serialize_future(service.run_retention())
+                }
+            }
+
+            // POST /listings
+            (&Post, Some(Route::Listings)) => serialize_future(
+                parse_body::<ProductMatchPayload>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: ProductMatchPayload")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: ProductMatchPayload")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.create_listing(payload))
+                    }),
+            ),
+
+            // GET /listings/<listing_id>/offers
+            (&Get, Some(Route::ListingOffers(listing_id))) => {
+                let count = parse_query!(req.query().unwrap_or_default(), "count" => i64);
+                match page_count(config.page_size("listing_offers"), count) {
+                    Ok(count) => serialize_future(service.get_listing_offers(listing_id, count as usize)),
+                    Err(e) => Box::new(future::err(e)),
+                }
+            }
+
+            // GET /jobs/<job_id>
+            (&Get, Some(Route::Job(job_id))) => serialize_future(service.get_job(job_id)),
+
+            // POST /jobs/<job_id>/cancel
+            (&Post, Some(Route::JobCancel(job_id))) => serialize_future(service.cancel_job(job_id)),
+
+            // GET /internal/sync/state
+            (&Get, Some(Route::SyncState)) => serialize_future(service.get_sync_state()),
+
+            // GET /internal/sync/entities
+            (&Get, Some(Route::SyncEntities)) => {
+                let params = parse_query!(
+                    req.query().unwrap_or_default(),
+                    "type" => SyncEntityType, "from_no" => i32, "to_no" => i32, "count" => i64
+                );
+                let count = match page_count(config.page_size("sync_entities"), params.3) {
+                    Ok(count) => count,
+                    Err(e) => return Box::new(future::err(e)),
+                };
+
+                match params {
+                    (Some(entity_type), Some(from_no), to_no, _) => {
+                        if to_no.map(|to_no| to_no < from_no).unwrap_or(false) {
+                            return Box::new(future::err(
+                                format_err!("Range is invalid, action: get sync entities")
+                                    .context(Error::Validate(
+                                        validation_errors!({"to_no": ["range" => "to_no must not be less than from_no"]}),
+                                    ))
+                                    .into(),
+                            ));
+                        }
+                        serialize_future(service.get_sync_entities(entity_type, SyncRange { from_no, to_no }, count))
+                    }
+                    _ => Box::new(future::err(
+                        format_err!("Parsing query parameters failed, action: get sync entities")
+                            .context(Error::Parse)
+                            .into(),
+                    )),
+                }
+            }
+
+            // POST /internal/stock/reservations
+            (&Post, Some(Route::StockReservations)) => serialize_future(
+                parse_body::<ReserveStock>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: ReserveStock").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: ReserveStock")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.reserve_stock(payload))
+                    }),
+            ),
+
+            // POST /internal/stock/reconcile
+            (&Post, Some(Route::StockReconcile)) => serialize_future(
+                parse_body::<ReconcileStock>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: ReconcileStock").context(Error::Parse).into())
+                    .and_then(move |payload| service.reconcile_stock(payload)),
+            ),
+
             // GET /categories/<category_id>
             (&Get, Some(Route::Category(category_id))) => serialize_future(service.get_category(category_id)),
 
@@ -1066,7 +2101,13 @@ impl<
             (&Get, Some(Route::CategoryBySlug(category_slug))) => serialize_future(service.get_category_by_slug(category_slug)),
 
             // DELETE /categories/<category_id>
-            (&Delete, Some(Route::Category(category_id))) => serialize_future(service.delete_category(category_id)),
+            (&Delete, Some(Route::Category(category_id))) => {
+                if parse_query!(req.query().unwrap_or_default(), "dry_run" => bool).unwrap_or(false) {
+                    serialize_future(service.delete_category_impact(category_id))
+                } else {
+                    serialize_future(service.delete_category(category_id))
+                }
+            }
 
             // POST /categories
             (&Post, Some(Route::Categories)) => serialize_future(
@@ -1109,6 +2150,38 @@ impl<
             // GET /categories
             (&Get, Some(Route::Categories)) => serialize_future(service.get_all_categories()),
 
+            // POST /admin/categories/diff
+            (&Post, Some(Route::CategoriesDiff)) => serialize_future(
+                parse_body::<Category>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: Category").context(Error::Parse).into())
+                    .and_then(move |exported| service.diff_categories(exported)),
+            ),
+
+            // POST /categories/<category_id>/move
+            (&Post, Some(Route::CategoryMove(category_id))) => serialize_future(
+                parse_body::<MoveCategory>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: MoveCategory").context(Error::Parse).into())
+                    .and_then(move |payload| service.move_category(category_id, payload)),
+            ),
+
+            // POST /categories/merge
+            (&Post, Some(Route::CategoriesMerge)) => serialize_future(
+                parse_body::<MergeCategories>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: MergeCategories").context(Error::Parse).into())
+                    .and_then(move |payload| service.merge_categories(payload)),
+            ),
+
+            // POST /categories/paths
+            (&Post, Some(Route::CategoryPaths)) => serialize_future(
+                parse_body::<GetBaseProducts>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: GetBaseProducts")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.get_category_paths(payload.ids)),
+            ),
+
             // GET /categories/with_products
             (&Get, Some(Route::CategoriesWithProducts)) => serialize_future(service.get_all_categories_with_products()),
 
@@ -1129,6 +2202,110 @@ impl<
                     .and_then(move |old_category_attr| service.delete_attribute_from_category(old_category_attr)),
             ),
 
+            // GET /categories/<category_id>/moderation_checklist
+            (&Get, Some(Route::CategoryModerationChecklist(category_id))) => {
+                serialize_future(service.list_moderation_checklist(category_id))
+            }
+
+            // POST /categories/<category_id>/moderation_checklist
+            (&Post, Some(Route::CategoryModerationChecklist(category_id))) => serialize_future(
+                parse_body::<NewModerationChecklistItemPayload>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: NewModerationChecklistItemPayload")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: NewModerationChecklistItemPayload")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| {
+                                service.create_moderation_checklist_item(NewModerationChecklistItem::new(category_id, payload))
+                            })
+                    }),
+            ),
+
+            // PUT /moderation_checklist_items/<item_id>
+            (&Put, Some(Route::ModerationChecklistItem(item_id))) => serialize_future(
+                parse_body::<UpdateModerationChecklistItem>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: UpdateModerationChecklistItem")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |update| {
+                        update
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: UpdateModerationChecklistItem")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.update_moderation_checklist_item(item_id, update))
+                    }),
+            ),
+
+            // DELETE /moderation_checklist_items/<item_id>
+            (&Delete, Some(Route::ModerationChecklistItem(item_id))) => {
+                serialize_future(service.delete_moderation_checklist_item(item_id))
+            }
+
+            // GET /categories/<category_id>/promotions
+            (&Get, Some(Route::CategoryPromotions(category_id))) => {
+                let all = parse_query!(req.query().unwrap_or_default(), "all" => bool);
+                serialize_future(service.list_category_promotions(category_id, all.unwrap_or(false)))
+            }
+
+            // POST /categories/<category_id>/promotions
+            (&Post, Some(Route::CategoryPromotions(category_id))) => serialize_future(
+                parse_body::<NewCategoryPromotionPayload>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: NewCategoryPromotionPayload")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .and_then(|_| payload.validate_schedule())
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: NewCategoryPromotionPayload")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.create_category_promotion(NewCategoryPromotion::new(category_id, payload)))
+                    }),
+            ),
+
+            // PUT /categories/<category_id>/promotions/<promotion_id>
+            (&Put, Some(Route::CategoryPromotion(category_id, promotion_id))) => serialize_future(
+                parse_body::<UpdateCategoryPromotion>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: UpdateCategoryPromotion").context(Error::Parse).into())
+                    .and_then(move |update| {
+                        update
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: UpdateCategoryPromotion")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.update_category_promotion(category_id, promotion_id, update))
+                    }),
+            ),
+
+            // DELETE /categories/<category_id>/promotions/<promotion_id>
+            (&Delete, Some(Route::CategoryPromotion(category_id, promotion_id))) => {
+                serialize_future(service.delete_category_promotion(category_id, promotion_id))
+            }
+
             // GET /currency_exchange
             (&Get, Some(Route::CurrencyExchange)) => serialize_future(service.get_latest_currencies()),
 
@@ -1143,6 +2320,9 @@ impl<
                     .and_then(move |new_currency_exchange| service.update_currencies(new_currency_exchange)),
             ),
 
+            // GET /currencies/meta
+            (&Get, Some(Route::CurrenciesMeta)) => serialize_future(service.get_currencies_meta()),
+
             // GET /wizard_stores
             (&Get, Some(Route::WizardStores)) => serialize_future(service.get_wizard_store()),
 
@@ -1173,6 +2353,20 @@ impl<
             // DELETE /wizard_stores
             (&Delete, Some(Route::WizardStores)) => serialize_future(service.delete_wizard_store()),
 
+            // GET /wizard_stores/stats
+            (&Get, Some(Route::WizardStoresStats)) => serialize_future(service.get_wizard_stores_stats()),
+
+            // PUT /wizard_stores/categories
+            (&Put, Some(Route::WizardStoreCategories)) => serialize_future(
+                parse_body::<DeclareStoreCategories>(req.body())
+                    .map_err(|e| {
+                        e.context("Parsing body failed, target: DeclareStoreCategories")
+                            .context(Error::Parse)
+                            .into()
+                    })
+                    .and_then(move |payload| service.declare_wizard_store_categories(payload)),
+            ),
+
             // GET /moderator_product_comments/<base_product_id>
             (&Get, Some(Route::ModeratorBaseProductComment(base_product_id))) => {
                 serialize_future(service.get_latest_for_product(base_product_id))
@@ -1203,9 +2397,108 @@ impl<
                     .and_then(move |new_comments| service.create_store_comment(new_comments)),
             ),
 
+            // GET /moderator_product_comments/<base_product_id>/history
+            (&Get, Some(Route::ModeratorBaseProductCommentsHistory(base_product_id))) => {
+                let (skip, count) = parse_query!(req.query().unwrap_or_default(), "skip" => i64, "count" => i64);
+                match page_count(config.page_size("moderator_comments"), count) {
+                    Ok(count) => serialize_future(service.get_product_comments(base_product_id, skip.unwrap_or(0), count)),
+                    Err(e) => Box::new(future::err(e)),
+                }
+            }
+
+            // GET /moderator_store_comments/<store_id>/history
+            (&Get, Some(Route::ModeratorStoreCommentsHistory(store_id))) => {
+                let (skip, count) = parse_query!(req.query().unwrap_or_default(), "skip" => i64, "count" => i64);
+                match page_count(config.page_size("moderator_comments"), count) {
+                    Ok(count) => serialize_future(service.get_store_comments(store_id, skip.unwrap_or(0), count)),
+                    Err(e) => Box::new(future::err(e)),
+                }
+            }
+
+            // GET /moderator/comments
+            (&Get, Some(Route::ModeratorComments)) => {
+                let (author, text, from, count) = parse_query!(
+                    req.query().unwrap_or_default(),
+                    "author" => String, "text" => String, "from" => i64, "count" => i64
+                );
+                let terms = ModeratorCommentsSearchTerms { author, text };
+                match page_count(config.page_size("moderator_comments"), count) {
+                    Ok(count) => serialize_future(service.search_comments(terms, from.unwrap_or(0), count)),
+                    Err(e) => Box::new(future::err(e)),
+                }
+            }
+
             // GET /products/<product_id>/seller_price
             (&Get, Some(Route::SellerProductPrice(product_id))) => serialize_future(service.get_product_seller_price(product_id)),
 
+            // POST /products/<product_id>/decrement_stock
+            (&Post, Some(Route::ProductDecrementStock(product_id))) => serialize_future(
+                parse_body::<DecrementStock>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: DecrementStock").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: DecrementStock")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.decrement_product_stock(product_id, payload))
+                    }),
+            ),
+
+            // POST /products/<product_id>/photos
+            (&Post, Some(Route::ProductPhotos(product_id))) => serialize_future(
+                parse_body::<AddProductPhotos>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: AddProductPhotos").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: AddProductPhotos")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.add_product_photos(product_id, payload))
+                    }),
+            ),
+
+            // DELETE /products/<product_id>/photos
+            (&Delete, Some(Route::ProductPhotos(product_id))) => serialize_future(
+                parse_body::<RemoveProductPhotos>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: RemoveProductPhotos").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: RemoveProductPhotos")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.remove_product_photos(product_id, payload))
+                    }),
+            ),
+
+            // PUT /products/<product_id>/photos/order
+            (&Put, Some(Route::ProductPhotosOrder(product_id))) => serialize_future(
+                parse_body::<ReorderProductPhotos>(req.body())
+                    .map_err(|e| e.context("Parsing body failed, target: ReorderProductPhotos").context(Error::Parse).into())
+                    .and_then(move |payload| {
+                        payload
+                            .validate()
+                            .map_err(|e| {
+                                format_err!("Validation failed, target: ReorderProductPhotos")
+                                    .context(Error::Validate(e))
+                                    .into()
+                            })
+                            .into_future()
+                            .and_then(move |_| service.reorder_product_photos(product_id, payload))
+                    }),
+            ),
+
             // POST /stores/moderator_search
             (&Post, Some(Route::ModeratorStoreSearch)) => {
                 let (offset, skip_opt, count_opt) = parse_query!(
@@ -1214,7 +2507,10 @@ impl<
                 );
 
                 let skip = skip_opt.unwrap_or(0);
-                let count = count_opt.unwrap_or(0);
+                let count = match page_count(config.page_size("moderator_stores_search"), count_opt) {
+                    Ok(count) => count,
+                    Err(e) => return Box::new(future::err(e)),
+                };
 
                 serialize_future(
                     parse_body::<ModeratorStoreSearchTerms>(req.body())
@@ -1235,7 +2531,10 @@ impl<
                 );
 
                 let skip = skip_opt.unwrap_or(0);
-                let count = count_opt.unwrap_or(0);
+                let count = match page_count(config.page_size("moderator_base_products_search"), count_opt) {
+                    Ok(count) => count,
+                    Err(e) => return Box::new(future::err(e)),
+                };
 
                 serialize_future(
                     parse_body::<ModeratorBaseProductSearchTerms>(req.body())
@@ -1254,13 +2553,18 @@ impl<
                     .context(Error::NotFound)
                     .into(),
             )),
+        };
+
+        let fut = match impersonation {
+            Some(impersonation) => Box::new(impersonation.and_then(move |_| dispatch())) as ControllerFuture,
+            None => dispatch(),
         }
-        .map_err(|err| {
+        .map_err(move |err| {
             let wrapper = ErrorMessageWrapper::<Error>::from(&err);
             if wrapper.inner.code == 500 {
                 log_and_capture_error(&err);
             }
-            err
+            localize_error(err, language)
         });
 
         Box::new(fut)
@@ -0,0 +1,94 @@
+//! Hard per user limit of messages to stores. Unlike search throttling,
+//! requests over the limit are rejected with `429 Too Many Requests`.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use failure::Error as FailureError;
+use failure::Fail;
+
+use stq_types::UserId;
+
+use config::StoreContact as StoreContactConfig;
+use errors::Error;
+
+/// Stale users are cleaned up only when there are more of them than this
+const MAX_TRACKED_USERS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct UserWindow {
+    started_at: Instant,
+    messages: u32,
+}
+
+/// Messages of users in the current window, shared by all connections
+pub struct ContactRateLimiter {
+    config: StoreContactConfig,
+    users: Mutex<HashMap<UserId, UserWindow>>,
+}
+
+impl ContactRateLimiter {
+    pub fn new(config: StoreContactConfig) -> Self {
+        Self {
+            config,
+            users: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts the message of the user, anonymous users and users over the limit are rejected
+    pub fn check(&self, user_id: Option<UserId>) -> Result<(), FailureError> {
+        let user_id = user_id.ok_or_else(|| format_err!("Only authorized users can contact stores").context(Error::Forbidden))?;
+        let window = Duration::from_secs(self.config.window_s);
+        let now = Instant::now();
+
+        let messages = match self.users.lock() {
+            Ok(mut users) => {
+                if users.len() > MAX_TRACKED_USERS {
+                    users.retain(|_, user_window| now.duration_since(user_window.started_at) < window);
+                }
+
+                let user_window = users.entry(user_id).or_insert(UserWindow { started_at: now, messages: 0 });
+                if now.duration_since(user_window.started_at) >= window {
+                    *user_window = UserWindow { started_at: now, messages: 0 };
+                }
+                user_window.messages = user_window.messages.saturating_add(1);
+                user_window.messages
+            }
+            Err(e) => {
+                error!("Contact rate limiter state is poisoned: {}", e);
+                0
+            }
+        };
+
+        if messages > self.config.max_messages {
+            return Err(format_err!(
+                "User {} sent more than {} messages to stores in {} s",
+                user_id,
+                self.config.max_messages,
+                self.config.window_s
+            )
+            .context(Error::TooManyRequests)
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contact_rate_limiter() {
+        let limiter = ContactRateLimiter::new(StoreContactConfig {
+            window_s: 60,
+            max_messages: 2,
+        });
+
+        assert!(limiter.check(None).is_err());
+        assert!(limiter.check(Some(UserId(1))).is_ok());
+        assert!(limiter.check(Some(UserId(1))).is_ok());
+        assert!(limiter.check(Some(UserId(1))).is_err());
+        assert!(limiter.check(Some(UserId(2))).is_ok());
+    }
+}
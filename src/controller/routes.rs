@@ -1,5 +1,9 @@
 use stq_router::RouteParser;
 use stq_types::*;
+use uuid::Uuid;
+
+#[cfg(feature = "chaos")]
+use chaos::FaultLayer;
 
 /// List of all routes with params for the app
 #[derive(Clone, Debug, PartialEq)]
@@ -7,20 +11,30 @@ pub enum Route {
     Healthcheck,
     Attributes,
     Attribute(AttributeId),
+    AttributeSchema(AttributeId),
     AttributeValue(AttributeValueId),
     AttributeValues(AttributeId),
+    AttributeValueTranslationsImport,
+    AttributeValueTranslationsImportJob,
+    AttributeGroups,
+    AttributeGroup(i32),
+    AttributeGroupAttributes(i32),
     BaseProducts,
     BaseProductsByIds,
     BaseProductsCount,
     BaseProductWithVariants,
     BaseProductsSearch,
+    BaseProductsSearchAfter,
     BaseProductsAutoComplete,
     BaseProductsMostViewed,
     BaseProductsMostDiscount,
+    BaseProductsSample,
+    EmbedBaseProduct(BaseProductId),
     BaseProductsSearchFiltersPrice,
     BaseProductsSearchFiltersCategory,
     BaseProductsSearchFiltersAttributes,
     BaseProductsSearchFiltersCount,
+    BaseProductsMatch,
     BaseProduct(BaseProductId),
     BaseProductWithoutFilters(BaseProductId),
     BaseProductBySlug(StoreSlug, BaseProductSlug),
@@ -29,19 +43,42 @@ pub enum Route {
     BaseProductByProduct(ProductId),
     BaseProductWithVariant(BaseProductId),
     BaseProductCustomAttributes(BaseProductId),
+    BaseProductHistory(BaseProductId),
+    BaseProductRestore(BaseProductId),
+    BaseProductDuplicate(BaseProductId),
+    BaseProductTransfer(BaseProductId),
+    BaseProductProductsPrices(BaseProductId),
+    BaseProductProductsOrder(BaseProductId),
+    BaseProductRelated(BaseProductId),
+    BaseProductPublishById(BaseProductId),
+    BaseProductUnpublish(BaseProductId),
     BaseProductPublish,
     Catalog,
+    #[cfg(feature = "chaos")]
+    ChaosFaults,
+    #[cfg(feature = "chaos")]
+    ChaosFault(FaultLayer),
     Categories,
     CategoriesWithProducts,
+    CategoriesDiff,
+    CategoryPaths,
+    CategoriesMerge,
     Category(CategoryId),
+    CategoryMove(CategoryId),
     BaseProductsCategoryReplace,
     CategoryBySlug(CategorySlug),
     CategoryAttrs,
     CategoryAttr(CategoryId),
+    CategoryModerationChecklist(CategoryId),
+    ModerationChecklistItem(i32),
+    CategoryPromotions(CategoryId),
+    CategoryPromotion(CategoryId, i32),
     CurrencyExchange,
+    CurrenciesMeta,
     CustomAttributes,
     CustomAttribute(CustomAttributeId),
     Coupons,
+    AdminCoupons,
     Coupon(CouponId),
     CouponsSearchCode,
     CouponsValidateCode,
@@ -57,19 +94,34 @@ pub enum Route {
         coupon_id: CouponId,
     },
     BaseProductsByCoupon(CouponId),
+    CouponsRedeem,
+    CouponRedemption(Uuid),
+    CompensationStore(SagaId),
+    CompensationBaseProducts(SagaId),
     ModeratorProductComments,
     ModeratorBaseProductComment(BaseProductId),
+    ModeratorBaseProductCommentsHistory(BaseProductId),
     ModeratorBaseProductSearch,
+    ModeratorCategoryReassignment,
     ModeratorStoreComments,
     ModeratorStoreComment(StoreId),
+    ModeratorStoreCommentsHistory(StoreId),
+    ModeratorComments,
     ModeratorStoreSearch,
     Products,
     ProductsByIds,
+    ProductsConvertPrices,
     ProductStoreId,
+    ProductByVendorCode,
+    ProductsByGtin(String),
     Product(ProductId),
     ProductWithoutFilters(ProductId),
     ProductValidateUpdate(ProductId),
+    ProductDecrementStock(ProductId),
+    ProductPhotos(ProductId),
+    ProductPhotosOrder(ProductId),
     ProductAttributes(ProductId),
+    ProductGroupedAttributes(ProductId),
     ProductsByBaseProduct(BaseProductId),
     ProductsByStore(StoreId),
     SellerProductPrice(ProductId),
@@ -79,17 +131,63 @@ pub enum Route {
     StoresSearchFiltersCount,
     StoresSearchFiltersCountry,
     StoresSearchFiltersCategory,
+    StoresSearchFacets,
+    StoresSearchNearby,
     StoresCart,
     StoresSlugExists,
+    StoresByIds,
     Store(StoreId),
     StoreDelete(StoreId),
     StoreBySagaId(SagaId),
     StoreBySlug(StoreSlug),
+    StoreProfile(StoreSlug),
+    StoreFaqs(StoreId),
+    StoreFaq(StoreId, i32),
+    StoreCategories(StoreId),
+    StoreCategory(StoreId, CategoryId),
+    StorePriceRules(StoreId),
+    StorePriceRule(StoreId, i32),
+    StorePriceRulesPreview(StoreId),
     StoreCount,
     StoreByUser(UserId),
     StoreProducts(StoreId),
     StoreProductsCount(StoreId),
+    StoreProductsLowStock(StoreId),
+    StoreProductsBulkDeactivate(StoreId),
+    StoreProductsOrder(StoreId),
+    StoreBaseProducts(StoreId),
+    StoreBaseProductsArchive(StoreId),
     StorePublish(StoreId),
+    StorePreviewCurrencyChange(StoreId),
+    StoreQuota(StoreId),
+    StoreQuotaPlan(StoreId),
+    StoreCatalogHealth(StoreId),
+    StoreStatistics(StoreId),
+    StoreOnboarding(StoreId),
+    StoreOnboardingFacts(StoreId),
+    StoreFranchise(StoreId),
+    StoreVacation(StoreId),
+    StoreVerify(StoreId),
+    StoreTransferOwnership(StoreId),
+    StoreClone(StoreId),
+    SyncState,
+    SyncEntities,
+    StockReservations,
+    StockReconcile,
+    SearchSynonyms,
+    SearchSynonym(i32),
+    IndexMigrationVerification(i32),
+    Retention,
+    SearchFacetValues,
+    Listings,
+    ListingOffers(i32),
+    Job(i32),
+    JobCancel(i32),
+    StoreFeedRss(StoreId),
+    StoreSitemap(StoreId),
+    StoreVerification(StoreId),
+    StoreVerificationConfirm(StoreId),
+    StoreContact(StoreId),
     StoreDraft(StoreId),
     StoreValidateChangeModerationStatus,
     StoreValidateUpdate(StoreId),
@@ -98,6 +196,7 @@ pub enum Route {
     BaseProductModerate,
     BaseProductModeration(BaseProductId),
     BaseProductDraft(BaseProductId),
+    BaseProductDeclineDetails(BaseProductId),
     BaseProductValidateChangeModerationStatus,
     BaseProductValidateUpdate(BaseProductId),
     Roles,
@@ -110,7 +209,11 @@ pub enum Route {
     UserIdByRole {
         role: StoresRole,
     },
+    DegradationMetrics,
+    Version,
     WizardStores,
+    WizardStoresStats,
+    WizardStoreCategories,
 }
 
 pub fn create_route_parser() -> RouteParser<Route> {
@@ -119,6 +222,12 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Healthcheck
     router.add_route(r"^/healthcheck$", || Route::Healthcheck);
 
+    // Version of the running instance
+    router.add_route(r"^/version$", || Route::Version);
+
+    // Degraded responses of the instance
+    router.add_route(r"^/metrics/degradation$", || Route::DegradationMetrics);
+
     // Stores Routes
     router.add_route(r"^/stores$", || Route::Stores);
 
@@ -150,6 +259,64 @@ pub fn create_route_parser() -> RouteParser<Route> {
         params.get(0).map(|slug| slug.to_string()).map(StoreSlug).map(Route::StoreBySlug)
     });
 
+    // Stores/:slug/profile route
+    router.add_route_with_params(r"^/stores/([^/]+)/profile$", |params| {
+        params.get(0).map(|slug| slug.to_string()).map(StoreSlug).map(Route::StoreProfile)
+    });
+
+    // Stores/:id/faqs route
+    router.add_route_with_params(r"^/stores/(\d+)/faqs$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreFaqs)
+    });
+
+    // Stores/:id/faqs/:faq_id route
+    router.add_route_with_params(r"^/stores/(\d+)/faqs/(\d+)$", |params| {
+        let store_id = params.get(0).and_then(|string_id| string_id.parse::<StoreId>().ok())?;
+        let faq_id = params.get(1).and_then(|string_id| string_id.parse::<i32>().ok())?;
+        Some(Route::StoreFaq(store_id, faq_id))
+    });
+
+    // Stores/:id/categories route
+    router.add_route_with_params(r"^/stores/(\d+)/categories$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StoreCategories)
+    });
+
+    // Stores/:id/categories/:category_id route
+    router.add_route_with_params(r"^/stores/(\d+)/categories/(\d+)$", |params| {
+        let store_id = params.get(0).and_then(|string_id| string_id.parse::<StoreId>().ok())?;
+        let category_id = params.get(1).and_then(|string_id| string_id.parse::<CategoryId>().ok())?;
+        Some(Route::StoreCategory(store_id, category_id))
+    });
+
+    // Stores/:id/price_rules route
+    router.add_route_with_params(r"^/stores/(\d+)/price_rules$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StorePriceRules)
+    });
+
+    // Stores/:id/price_rules/preview route
+    router.add_route_with_params(r"^/stores/(\d+)/price_rules/preview$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<StoreId>().ok())
+            .map(Route::StorePriceRulesPreview)
+    });
+
+    // Stores/:id/price_rules/:price_rule_id route
+    router.add_route_with_params(r"^/stores/(\d+)/price_rules/(\d+)$", |params| {
+        let store_id = params.get(0).and_then(|string_id| string_id.parse::<StoreId>().ok())?;
+        let price_rule_id = params.get(1).and_then(|string_id| string_id.parse::<i32>().ok())?;
+        Some(Route::StorePriceRule(store_id, price_rule_id))
+    });
+
     // Stores/by_user_id/:id route
     router.add_route_with_params(r"^/stores/by_user_id/(\d+)$", |params| {
         params
@@ -177,6 +344,51 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::StoreProductsCount)
     });
 
+    // Stores/:id/products/low_stock route
+    router.add_route_with_params(r"^/stores/(\d+)/products/low_stock$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreProductsLowStock)
+    });
+
+    // Stores/:id/products/bulk_deactivate route
+    router.add_route_with_params(r"^/stores/(\d+)/products/bulk_deactivate$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreProductsBulkDeactivate)
+    });
+
+    // Stores/:id/products/order route
+    router.add_route_with_params(r"^/stores/(\d+)/products/order$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreProductsOrder)
+    });
+
+    // Stores/:id/base_products route
+    router.add_route_with_params(r"^/stores/(\d+)/base_products$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreBaseProducts)
+    });
+
+    // Stores/:id/base_products/archive route
+    router.add_route_with_params(r"^/stores/(\d+)/base_products/archive$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreBaseProductsArchive)
+    });
+
     // Stores count route
     router.add_route(r"^/stores/count$", || Route::StoreCount);
 
@@ -189,6 +401,9 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Stores Search route
     router.add_route(r"^/stores/search$", || Route::StoresSearch);
 
+    // Stores search by ids route
+    router.add_route(r"^/stores/search_by_ids$", || Route::StoresByIds);
+
     // Stores Search filter count route
     router.add_route(r"^/stores/search/filters/count$", || Route::StoresSearchFiltersCount);
 
@@ -198,6 +413,12 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Stores Search filter  route
     router.add_route(r"^/stores/search/filters/category$", || Route::StoresSearchFiltersCategory);
 
+    // Stores search with facets route
+    router.add_route(r"^/stores/search/facets$", || Route::StoresSearchFacets);
+
+    // Stores search by distance route
+    router.add_route(r"^/stores/search/nearby$", || Route::StoresSearchNearby);
+
     // Stores auto complete route
     router.add_route(r"^/stores/auto_complete$", || Route::StoresAutoComplete);
 
@@ -229,9 +450,20 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Products by ids routes
     router.add_route(r"^/products/search_by_ids$", || Route::ProductsByIds);
 
+    // Prices of cart lines in the currencies of the request headers
+    router.add_route(r"^/products/convert_prices$", || Route::ProductsConvertPrices);
+
     // Product Store Id Routes
     router.add_route(r"^/products/store_id$", || Route::ProductStoreId);
 
+    // Product by vendor code of the store Routes
+    router.add_route(r"^/products/by_vendor_code$", || Route::ProductByVendorCode);
+
+    // Products/by_gtin/:code route
+    router.add_route_with_params(r"^/products/by_gtin/([0-9X]+)$", |params| {
+        params.get(0).map(|code| code.to_string()).map(Route::ProductsByGtin)
+    });
+
     // Products/:id route
     router.add_route_with_params(r"^/products/(\d+)$", |params| {
         params
@@ -257,6 +489,33 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::SellerProductPrice)
     });
 
+    // Products/:id/decrement_stock route
+    router.add_route_with_params(r"^/products/(\d+)/decrement_stock$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(ProductId)
+            .map(Route::ProductDecrementStock)
+    });
+
+    // Products/:id/photos route
+    router.add_route_with_params(r"^/products/(\d+)/photos$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(ProductId)
+            .map(Route::ProductPhotos)
+    });
+
+    // Products/:id/photos/order route
+    router.add_route_with_params(r"^/products/(\d+)/photos/order$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(ProductId)
+            .map(Route::ProductPhotosOrder)
+    });
+
     // Products/by_base_product/:id route
     router.add_route_with_params(r"^/products/by_base_product/(\d+)$", |params| {
         params
@@ -284,6 +543,15 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::ProductAttributes)
     });
 
+    // Products/:id/attributes/grouped route
+    router.add_route_with_params(r"^/products/(\d+)/attributes/grouped$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(ProductId)
+            .map(Route::ProductGroupedAttributes)
+    });
+
     router.add_route_with_params(r"^/products/(\d+)/validate_update$", |params| {
         params
             .get(0)
@@ -329,6 +597,79 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::BaseProductCustomAttributes)
     });
 
+    // Base products/:id/history route
+    router.add_route_with_params(r"^/base_products/(\d+)/history$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(BaseProductId)
+            .map(Route::BaseProductHistory)
+    });
+
+    // Base products/:id/restore route
+    router.add_route_with_params(r"^/base_products/(\d+)/restore$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductRestore)
+    });
+
+    // Base products/:id/duplicate route
+    router.add_route_with_params(r"^/base_products/(\d+)/duplicate$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductDuplicate)
+    });
+
+    // Base products/:id/transfer route
+    router.add_route_with_params(r"^/base_products/(\d+)/transfer$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductTransfer)
+    });
+
+    // Base products/:id/products/prices route
+    router.add_route_with_params(r"^/base_products/(\d+)/products/prices$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductProductsPrices)
+    });
+
+    // Base products/:id/products/order route
+    router.add_route_with_params(r"^/base_products/(\d+)/products/order$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductProductsOrder)
+    });
+
+    // Base products/:id/related route
+    router.add_route_with_params(r"^/base_products/(\d+)/related$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductRelated)
+    });
+
+    // Base products/:id/publish route
+    router.add_route_with_params(r"^/base_products/(\d+)/publish$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductPublishById)
+    });
+
+    // Base products/:id/unpublish route
+    router.add_route_with_params(r"^/base_products/(\d+)/unpublish$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductUnpublish)
+    });
+
     // Base products/:id/update_view route
     router.add_route_with_params(r"^/base_products/(\d+)/update_view$", |params| {
         params
@@ -372,6 +713,9 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // BaseProducts Search route
     router.add_route(r"^/base_products/search$", || Route::BaseProductsSearch);
 
+    // BaseProducts Search with continuation token route
+    router.add_route(r"^/base_products/search/after$", || Route::BaseProductsSearchAfter);
+
     // BaseProducts auto complete route
     router.add_route(r"^/base_products/auto_complete$", || Route::BaseProductsAutoComplete);
 
@@ -381,6 +725,17 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // BaseProducts with most viewed
     router.add_route(r"^/base_products/most_viewed$", || Route::BaseProductsMostViewed);
 
+    // BaseProducts weighted random sample
+    router.add_route(r"^/base_products/sample$", || Route::BaseProductsSample);
+
+    // Public product card for third-party sites
+    router.add_route_with_params(r"^/embed/base_products/(\d+)$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::EmbedBaseProduct)
+    });
+
     // BaseProducts search filters price route
     router.add_route(r"^/base_products/search/filters/price$", || Route::BaseProductsSearchFiltersPrice);
 
@@ -397,6 +752,12 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // BaseProducts search filters count route
     router.add_route(r"^/base_products/search/filters/count$", || Route::BaseProductsSearchFiltersCount);
 
+    // Search facet values route
+    router.add_route(r"^/search/facet_values$", || Route::SearchFacetValues);
+
+    // BaseProducts match by attributes route
+    router.add_route(r"^/base_products/match$", || Route::BaseProductsMatch);
+
     // Change moderation status by moderator
     router.add_route(r"^/base_products/moderate$", || Route::BaseProductModerate);
 
@@ -426,6 +787,13 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::BaseProductDraft)
     });
 
+    router.add_route_with_params(r"^/base_products/(\d+)/decline_details$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<BaseProductId>().ok())
+            .map(Route::BaseProductDeclineDetails)
+    });
+
     // CategoryReplace
     router.add_route(r"^/base_products/replace_category$", || Route::BaseProductsCategoryReplace);
 
@@ -435,6 +803,22 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // CustomAttributes Routes
     router.add_route(r"^/custom_attributes$", || Route::CustomAttributes);
 
+    // Internal route for rolling back store created by saga
+    router.add_route_with_params(r"^/internal/compensations/stores/(.+)$", |params| {
+        params
+            .get(0)
+            .and_then(|saga_id| saga_id.parse::<SagaId>().ok())
+            .map(Route::CompensationStore)
+    });
+
+    // Internal route for rolling back base products created by saga
+    router.add_route_with_params(r"^/internal/compensations/base_products/(.+)$", |params| {
+        params
+            .get(0)
+            .and_then(|saga_id| saga_id.parse::<SagaId>().ok())
+            .map(Route::CompensationBaseProducts)
+    });
+
     // Attributes/:id route
     router.add_route_with_params(r"^/custom_attributes/(\d+)$", |params| {
         params
@@ -446,6 +830,9 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Coupons Routes
     router.add_route(r"^/coupons$", || Route::Coupons);
 
+    // Coupons of all stores for moderators
+    router.add_route(r"^/admin/coupons$", || Route::AdminCoupons);
+
     // Generate code coupon
     router.add_route(r"^/coupons/generate_code$", || Route::CouponsGenerateCode);
 
@@ -505,6 +892,17 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::BaseProductsByCoupon)
     });
 
+    // Internal route for redeeming coupon by saga
+    router.add_route(r"^/internal/coupons/redeem$", || Route::CouponsRedeem);
+
+    // Internal route for voiding coupon redemption by token
+    router.add_route_with_params(r"^/internal/coupons/redemptions/([a-zA-Z0-9-]+)$", |params| {
+        params
+            .get(0)
+            .and_then(|token| token.parse::<Uuid>().ok())
+            .map(Route::CouponRedemption)
+    });
+
     // Attributes/:id route
     router.add_route_with_params(r"^/attributes/(\d+)$", |params| {
         params
@@ -513,6 +911,14 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::Attribute)
     });
 
+    // Attributes/:id/schema route
+    router.add_route_with_params(r"^/attributes/(\d+)/schema$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<AttributeId>().ok())
+            .map(Route::AttributeSchema)
+    });
+
     // AttributeValue/:attribute_value_id
     router.add_route_with_params(r"^/attributes/values/(\d+)$", |params| {
         params
@@ -521,6 +927,31 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(|attr_value_id| Route::AttributeValue(attr_value_id))
     });
 
+    // Attribute groups routes
+    router.add_route(r"^/attributes/groups$", || Route::AttributeGroups);
+
+    // Attribute groups/:id route
+    router.add_route_with_params(r"^/attributes/groups/(\d+)$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::AttributeGroup)
+    });
+
+    // Attribute groups/:id/attributes route
+    router.add_route_with_params(r"^/attributes/groups/(\d+)/attributes$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::AttributeGroupAttributes)
+    });
+
+    // AttributeValue translations import route
+    router.add_route(r"^/attributes/values/translations/import$", || Route::AttributeValueTranslationsImport);
+
+    // AttributeValue translations import in background job route
+    router.add_route(r"^/attributes/values/translations/import_jobs$", || Route::AttributeValueTranslationsImportJob);
+
     // Attributes/:attribute_id/values route
     router.add_route_with_params(r"^/attributes/(\d+)/values$", |params| {
         params
@@ -532,9 +963,18 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Categories Routes
     router.add_route(r"^/categories$", || Route::Categories);
 
+    // Diff of exported categories tree against the live one
+    router.add_route(r"^/admin/categories/diff$", || Route::CategoriesDiff);
+
     // Categories only with products Routes
     router.add_route(r"^/categories/with_products$", || Route::CategoriesWithProducts);
 
+    // Breadcrumb paths to categories of base products
+    router.add_route(r"^/categories/paths$", || Route::CategoryPaths);
+
+    // Merging of categories
+    router.add_route(r"^/categories/merge$", || Route::CategoriesMerge);
+
     // Categories/:id route
     router.add_route_with_params(r"^/categories/(\d+)$", |params| {
         params
@@ -543,6 +983,14 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::Category)
     });
 
+    // Categories/:id/move route
+    router.add_route_with_params(r"^/categories/(\d+)/move$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<CategoryId>().ok())
+            .map(Route::CategoryMove)
+    });
+
     // Categories/by-slug/:slug route
     router.add_route_with_params(r"^/categories/by-slug/(.+)$", |params| {
         params.get(0).map(|slug| Route::CategoryBySlug(CategorySlug(slug.to_string())))
@@ -559,11 +1007,45 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::CategoryAttr)
     });
 
+    // Categories/:id/moderation_checklist route
+    router.add_route_with_params(r"^/categories/(\d+)/moderation_checklist$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<CategoryId>().ok())
+            .map(Route::CategoryModerationChecklist)
+    });
+
+    // Categories/:id/promotions route
+    router.add_route_with_params(r"^/categories/(\d+)/promotions$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<CategoryId>().ok())
+            .map(Route::CategoryPromotions)
+    });
+
+    // Categories/:id/promotions/:promotion_id route
+    router.add_route_with_params(r"^/categories/(\d+)/promotions/(\d+)$", |params| {
+        let category_id = params.get(0).and_then(|string_id| string_id.parse::<CategoryId>().ok())?;
+        let promotion_id = params.get(1).and_then(|string_id| string_id.parse::<i32>().ok())?;
+        Some(Route::CategoryPromotion(category_id, promotion_id))
+    });
+
+    // Moderation_checklist_items/:id route
+    router.add_route_with_params(r"^/moderation_checklist_items/(\d+)$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::ModerationChecklistItem)
+    });
+
     // Currency exchange Routes
     router.add_route(r"^/currency_exchange$", || Route::CurrencyExchange);
+    router.add_route(r"^/currencies/meta$", || Route::CurrenciesMeta);
 
     // Wizard store Routes
     router.add_route(r"^/wizard_stores$", || Route::WizardStores);
+    router.add_route(r"^/wizard_stores/stats$", || Route::WizardStoresStats);
+    router.add_route(r"^/wizard_stores/categories$", || Route::WizardStoreCategories);
 
     // Moderator Product Comments Routes
     router.add_route(r"^/moderator_product_comments$", || Route::ModeratorProductComments);
@@ -577,6 +1059,15 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::ModeratorBaseProductComment)
     });
 
+    // Moderator Product Comment/:base_product_id/history Route
+    router.add_route_with_params(r"^/moderator_product_comments/(\d+)/history$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(BaseProductId)
+            .map(Route::ModeratorBaseProductCommentsHistory)
+    });
+
     // Moderator Store Comments Routes
     router.add_route(r"^/moderator_store_comments$", || Route::ModeratorStoreComments);
 
@@ -589,6 +1080,18 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::ModeratorStoreComment)
     });
 
+    // Moderator Store Comment/:store_id/history Route
+    router.add_route_with_params(r"^/moderator_store_comments/(\d+)/history$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::ModeratorStoreCommentsHistory)
+    });
+
+    // Moderator Comments search Route
+    router.add_route(r"^/moderator/comments$", || Route::ModeratorComments);
+
     // Moderator Store search
     router.add_route(r"^/stores/moderator_search$", || Route::ModeratorStoreSearch);
 
@@ -601,6 +1104,212 @@ pub fn create_route_parser() -> RouteParser<Route> {
             .map(Route::StorePublish)
     });
 
+    // Stores/:id/preview_currency_change route
+    router.add_route_with_params(r"^/stores/(\d+)/preview_currency_change$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StorePreviewCurrencyChange)
+    });
+
+    // Stores/:id/quota route
+    router.add_route_with_params(r"^/stores/(\d+)/quota$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreQuota)
+    });
+
+    // Internal routes for consumers following catalog changes
+    router.add_route(r"^/internal/sync/state$", || Route::SyncState);
+    router.add_route(r"^/internal/sync/entities$", || Route::SyncEntities);
+
+    // Internal routes for carts reserving stock and orders service reconciling reservations
+    router.add_route(r"^/internal/stock/reservations$", || Route::StockReservations);
+    router.add_route(r"^/internal/stock/reconcile$", || Route::StockReconcile);
+
+    // Search synonyms routes
+    router.add_route(r"^/search/synonyms$", || Route::SearchSynonyms);
+    router.add_route_with_params(r"^/search/synonyms/(\d+)$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::SearchSynonym)
+    });
+
+    // Replays of products index rebuilds
+    router.add_route_with_params(r"^/admin/elastic/migrate/(\d+)/verification$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::IndexMigrationVerification)
+    });
+
+    // Retention job run on demand
+    router.add_route(r"^/admin/retention$", || Route::Retention);
+
+    // Listings routes
+    router.add_route(r"^/listings$", || Route::Listings);
+    router.add_route_with_params(r"^/listings/(\d+)/offers$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::ListingOffers)
+    });
+
+    // Jobs/:id route
+    router.add_route_with_params(r"^/jobs/(\d+)$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::Job)
+    });
+
+    // Jobs/:id/cancel route
+    router.add_route_with_params(r"^/jobs/(\d+)/cancel$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(Route::JobCancel)
+    });
+
+    // Internal route for billing plan of the store
+    router.add_route_with_params(r"^/internal/stores/(\d+)/quota_plan$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreQuotaPlan)
+    });
+
+    // Stores/:id/onboarding route
+    router.add_route_with_params(r"^/stores/(\d+)/onboarding$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreOnboarding)
+    });
+
+    // Internal route for onboarding facts of the store kept by billing and delivery
+    router.add_route_with_params(r"^/internal/stores/(\d+)/onboarding$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreOnboardingFacts)
+    });
+
+    // Stores/:id/franchise route
+    router.add_route_with_params(r"^/stores/(\d+)/franchise$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreFranchise)
+    });
+
+    // Stores/:id/vacation route
+    router.add_route_with_params(r"^/stores/(\d+)/vacation$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreVacation)
+    });
+
+    // Stores/:id/verify route
+    router.add_route_with_params(r"^/stores/(\d+)/verify$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreVerify)
+    });
+
+    // Stores/:id/transfer_ownership route
+    router.add_route_with_params(r"^/stores/(\d+)/transfer_ownership$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreTransferOwnership)
+    });
+
+    // Stores/:id/clone route
+    router.add_route_with_params(r"^/stores/(\d+)/clone$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreClone)
+    });
+
+    // Stores/:id/catalog_health route
+    router.add_route_with_params(r"^/stores/(\d+)/catalog_health$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreCatalogHealth)
+    });
+
+    // Stores/:id/statistics route
+    router.add_route_with_params(r"^/stores/(\d+)/statistics$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreStatistics)
+    });
+
+    // Stores/:id/feed.rss route
+    router.add_route_with_params(r"^/stores/(\d+)/feed\.rss$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreFeedRss)
+    });
+
+    // Stores/:id/sitemap.xml route
+    router.add_route_with_params(r"^/stores/(\d+)/sitemap\.xml$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreSitemap)
+    });
+
+    // Stores/:id/verification route
+    router.add_route_with_params(r"^/stores/(\d+)/verification$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreVerification)
+    });
+
+    // Stores/:id/verification/confirm route
+    router.add_route_with_params(r"^/stores/(\d+)/verification/confirm$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreVerificationConfirm)
+    });
+
+    // Stores/:id/contact route
+    router.add_route_with_params(r"^/stores/(\d+)/contact$", |params| {
+        params
+            .get(0)
+            .and_then(|string_id| string_id.parse::<i32>().ok())
+            .map(StoreId)
+            .map(Route::StoreContact)
+    });
+
     // Stores/:id/draft route
     router.add_route_with_params(r"^/stores/(\d+)/draft$", |params| {
         params
@@ -613,6 +1322,9 @@ pub fn create_route_parser() -> RouteParser<Route> {
     // Moderator Base Product search
     router.add_route(r"^/base_products/moderator_search$", || Route::ModeratorBaseProductSearch);
 
+    // Moderator category reassignment of base products
+    router.add_route(r"^/moderator/base_products/reassign_category$", || Route::ModeratorCategoryReassignment);
+
     // BaseProducts/publish route
     router.add_route(r"^/base_products/publish$", || Route::BaseProductPublish);
 
@@ -638,5 +1350,18 @@ pub fn create_route_parser() -> RouteParser<Route> {
     });
     router.add_route(r"^/catalog$", || Route::Catalog);
 
+    #[cfg(feature = "chaos")]
+    add_chaos_routes(&mut router);
+
     router
 }
+
+/// Fault injection routes, they exist only in builds with `chaos` feature
+#[cfg(feature = "chaos")]
+fn add_chaos_routes(router: &mut RouteParser<Route>) {
+    router.add_route(r"^/chaos/faults$", || Route::ChaosFaults);
+
+    router.add_route_with_params(r"^/chaos/faults/(\w+)$", |params| {
+        params.get(0).and_then(|layer| layer.parse::<FaultLayer>().ok()).map(Route::ChaosFault)
+    });
+}
@@ -0,0 +1,256 @@
+//! Soft throttling of anonymous search requests.
+//!
+//! Unlike a hard rate limiter nothing is rejected here: clients that keep hitting
+//! search endpoints without authorization get randomized, progressively growing
+//! response delays, and after a higher threshold the response is marked with
+//! `X-Captcha-Required` header, so the gateway can challenge the client.
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::{future, Future};
+use hyper::{
+    self,
+    header::Authorization,
+    server::{Request, Response, Service},
+};
+use rand::{thread_rng, Rng};
+use tokio_core::reactor::{Handle, Timeout};
+
+use config::SearchThrottle as SearchThrottleConfig;
+
+pub const CAPTCHA_REQUIRED_HEADER: &'static str = "X-Captcha-Required";
+
+pub const SEARCH_PATH_PREFIXES: &[&str] = &[
+    "/stores/search",
+    "/stores/auto_complete",
+    "/base_products/search",
+    "/base_products/auto_complete",
+];
+
+/// Stale clients are cleaned up only when there are more of them than this
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrottleDecision {
+    pub delay: Option<Duration>,
+    pub captcha_required: bool,
+}
+
+impl ThrottleDecision {
+    /// Computes throttling for the client that made `hits` search requests in the current window
+    pub fn new(config: &SearchThrottleConfig, hits: u32) -> Self {
+        let delay = if hits > config.delay_threshold {
+            let delay_ms = config.delay_step_ms.saturating_mul(u64::from(hits - config.delay_threshold));
+            Some(Duration::from_millis(delay_ms.min(config.max_delay_ms)))
+        } else {
+            None
+        };
+
+        Self {
+            delay,
+            captcha_required: hits > config.captcha_threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClientWindow {
+    started_at: Instant,
+    hits: u32,
+}
+
+/// Search hits of anonymous clients, shared by all connections
+pub struct SearchThrottleState {
+    config: SearchThrottleConfig,
+    clients: Mutex<HashMap<String, ClientWindow>>,
+}
+
+impl SearchThrottleState {
+    pub fn new(config: SearchThrottleConfig) -> Self {
+        Self {
+            config,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn register_hit(&self, client: String) -> ThrottleDecision {
+        let window = Duration::from_secs(self.config.window_s);
+        let now = Instant::now();
+
+        let hits = match self.clients.lock() {
+            Ok(mut clients) => {
+                if clients.len() > MAX_TRACKED_CLIENTS {
+                    clients.retain(|_, client_window| now.duration_since(client_window.started_at) < window);
+                }
+
+                let client_window = clients.entry(client).or_insert(ClientWindow { started_at: now, hits: 0 });
+                if now.duration_since(client_window.started_at) >= window {
+                    *client_window = ClientWindow { started_at: now, hits: 0 };
+                }
+                client_window.hits = client_window.hits.saturating_add(1);
+                client_window.hits
+            }
+            Err(e) => {
+                error!("Search throttle state is poisoned: {}", e);
+                0
+            }
+        };
+
+        ThrottleDecision::new(&self.config, hits)
+    }
+}
+
+/// Wraps application and throttles anonymous search requests, all other requests are passed as is
+pub struct SearchThrottling<S> {
+    inner: Rc<S>,
+    handle: Handle,
+    state: Option<Arc<SearchThrottleState>>,
+}
+
+impl<S> SearchThrottling<S> {
+    pub fn new(inner: S, handle: Handle, state: Option<Arc<SearchThrottleState>>) -> Self {
+        Self {
+            inner: Rc::new(inner),
+            handle,
+            state,
+        }
+    }
+}
+
+impl<S> Service for SearchThrottling<S>
+where
+    S: Service<Request = Request, Response = Response, Error = hyper::Error> + 'static,
+    S::Future: 'static,
+{
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error>>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let decision = match self.state {
+            Some(ref state) if is_anonymous_search(&req) => {
+                client_key(&req, &state.config.trusted_proxies).map(|client| state.register_hit(client))
+            }
+            _ => None,
+        };
+
+        let decision = match decision {
+            Some(decision) => decision,
+            None => return Box::new(self.inner.call(req)),
+        };
+
+        let delay: Box<Future<Item = (), Error = hyper::Error>> = match decision
+            .delay
+            .map(randomize_delay)
+            .and_then(|delay| Timeout::new(delay, &self.handle).ok())
+        {
+            Some(timeout) => Box::new(timeout.map_err(hyper::Error::from)),
+            None => Box::new(future::ok(())),
+        };
+
+        // request is dispatched only after the delay, so throttled clients do not load the search meanwhile
+        let inner = self.inner.clone();
+        Box::new(delay.and_then(move |_| inner.call(req)).map(move |mut response| {
+            if decision.captcha_required {
+                response.headers_mut().set_raw(CAPTCHA_REQUIRED_HEADER, "true");
+            }
+            response
+        }))
+    }
+}
+
+fn is_anonymous_search(req: &Request) -> bool {
+    req.headers().get::<Authorization<String>>().is_none() && SEARCH_PATH_PREFIXES.iter().any(|prefix| req.path().starts_with(prefix))
+}
+
+/// Client address, `X-Forwarded-For` is read only when the request came from a trusted proxy
+fn client_key(req: &Request, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let peer = req.remote_addr().map(|addr: SocketAddr| addr.ip())?;
+    let forwarded_for = req
+        .headers()
+        .get_raw("X-Forwarded-For")
+        .map(|raw| raw.iter().map(|line| String::from_utf8_lossy(line).into_owned()).collect::<Vec<_>>().join(","))
+        .unwrap_or_default();
+
+    Some(forwarded_client(peer, &forwarded_for, trusted_proxies))
+}
+
+/// Walks `X-Forwarded-For` from the right, every trusted proxy appends the address it got the request from,
+/// so the first entry not added by a trusted proxy is the client. Entries left of it can be forged by the client
+fn forwarded_client(peer: IpAddr, forwarded_for: &str, trusted_proxies: &[IpAddr]) -> String {
+    let mut client = peer.to_string();
+    if !trusted_proxies.contains(&peer) {
+        return client;
+    }
+
+    for entry in forwarded_for.rsplit(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        client = entry.to_string();
+        match entry.parse::<IpAddr>() {
+            Ok(ip) if trusted_proxies.contains(&ip) => continue,
+            _ => break,
+        }
+    }
+    client
+}
+
+/// Jitters delay between a half and the full value, so thresholds are harder to probe
+fn randomize_delay(delay: Duration) -> Duration {
+    let delay_ms = delay.as_secs() * 1000 + u64::from(delay.subsec_nanos() / 1_000_000);
+    if delay_ms == 0 {
+        return delay;
+    }
+    Duration::from_millis(thread_rng().gen_range(delay_ms / 2, delay_ms + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_decision() {
+        let config = SearchThrottleConfig {
+            window_s: 60,
+            delay_threshold: 10,
+            delay_step_ms: 100,
+            max_delay_ms: 1000,
+            captcha_threshold: 50,
+            trusted_proxies: vec![],
+        };
+
+        assert_eq!(
+            ThrottleDecision::new(&config, 10),
+            ThrottleDecision {
+                delay: None,
+                captcha_required: false,
+            }
+        );
+        assert_eq!(ThrottleDecision::new(&config, 13).delay, Some(Duration::from_millis(300)));
+        assert_eq!(
+            ThrottleDecision::new(&config, 51),
+            ThrottleDecision {
+                delay: Some(Duration::from_millis(1000)),
+                captcha_required: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_forwarded_client() {
+        let gateway: IpAddr = "10.0.0.2".parse().unwrap();
+        let balancer: IpAddr = "10.0.0.3".parse().unwrap();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let trusted_proxies = vec![gateway, balancer];
+
+        // peer is not a proxy, the header is set by the client itself
+        assert_eq!(forwarded_client(client, "198.51.100.1", &trusted_proxies), "203.0.113.7");
+        // forged entries left of the address added by the gateway are ignored
+        assert_eq!(forwarded_client(gateway, "198.51.100.1, 203.0.113.7", &trusted_proxies), "203.0.113.7");
+        assert_eq!(forwarded_client(gateway, "203.0.113.7, 10.0.0.3", &trusted_proxies), "203.0.113.7");
+        assert_eq!(forwarded_client(gateway, "", &trusted_proxies), "10.0.0.2");
+        assert_eq!(forwarded_client(gateway, "203.0.113.7", &[]), "10.0.0.2");
+    }
+}
@@ -1,6 +1,17 @@
 use std::collections::HashMap;
 use std::iter::FromIterator;
 
+use failure::{Error as FailureError, Fail};
+use futures::Future;
+use serde::Serialize;
+use stq_http::controller::ControllerFuture;
+use stq_http::request_util::serialize_future;
+
+use config::PageSize;
+use controller::context::ApiVersion;
+use errors::Error;
+use models::WithMoney;
+
 /// Splits query string to key-value pairs. See `macros::parse_query` for more sophisticated parsing.
 // TODO: Cover more complex cases, e.g. `from=count=10`
 pub fn query_params(query: &str) -> HashMap<&str, &str> {
@@ -9,3 +20,44 @@ pub fn query_params(query: &str) -> HashMap<&str, &str> {
         (params.next().unwrap(), params.next().unwrap_or(""))
     }))
 }
+
+/// Applies page size limits of the endpoint to `count` query parameter
+pub fn page_count(page_size: PageSize, count: Option<i64>) -> Result<i64, FailureError> {
+    match count {
+        None => Ok(page_size.default),
+        Some(count) if count > 0 && count <= page_size.max => Ok(count),
+        Some(count) => Err(format_err!("Page size {} is out of range", count)
+            .context(Error::Validate(
+                validation_errors!({"count": ["range" => format!("Count must be between 1 and {}", page_size.max)]}),
+            ))
+            .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_count() {
+        let page_size = PageSize { default: 20, max: 100 };
+
+        assert_eq!(page_count(page_size, None).unwrap(), 20);
+        assert_eq!(page_count(page_size, Some(100)).unwrap(), 100);
+        assert!(page_count(page_size, Some(0)).is_err());
+        assert!(page_count(page_size, Some(10_000)).is_err());
+    }
+}
+
+/// Serializes products in the shape of the API version, see `ApiVersion::V2` for endpoints returning `Money`
+pub fn serialize_products<P, F>(api_version: ApiVersion, products: F) -> ControllerFuture
+where
+    P: WithMoney + Serialize + Send + 'static,
+    F: Future<Item = P, Error = FailureError> + 'static,
+{
+    if api_version.has_money() {
+        serialize_future(products.map(WithMoney::with_money))
+    } else {
+        serialize_future(products)
+    }
+}
@@ -0,0 +1,52 @@
+//! Content type of the responses that are not json, e.g. store feeds.
+//! `Application` marks every response as json, so the header is replaced after it.
+use futures::Future;
+use hyper::{
+    self,
+    header::ContentType,
+    server::{Request, Response, Service},
+};
+
+/// Response content types by path suffix
+const XML_CONTENT_TYPES: &[(&str, &str)] = &[(".rss", "application/rss+xml; charset=utf-8"), (".xml", "application/xml; charset=utf-8")];
+
+/// Wraps application and sets xml content type for successful responses of xml endpoints
+pub struct XmlContentType<S> {
+    inner: S,
+}
+
+impl<S> XmlContentType<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S> Service for XmlContentType<S>
+where
+    S: Service<Request = Request, Response = Response, Error = hyper::Error>,
+    S::Future: 'static,
+{
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error>>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let content_type = XML_CONTENT_TYPES
+            .iter()
+            .find(|&&(suffix, _)| req.path().ends_with(suffix))
+            .map(|&(_, content_type)| content_type);
+
+        let response = self.inner.call(req);
+
+        match content_type {
+            Some(content_type) => Box::new(response.map(move |mut response| {
+                if response.status().is_success() {
+                    response.headers_mut().set(ContentType(content_type.parse().unwrap()));
+                }
+                response
+            })),
+            None => Box::new(response),
+        }
+    }
+}
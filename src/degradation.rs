@@ -0,0 +1,68 @@
+//! Degradation of the dependencies.
+//!
+//! When a dependency fails and a fallback is used instead, affected responses list it in `degraded` field,
+//! so clients can show a notice. Degraded responses are counted, counters are served with `/metrics/degradation`.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Degradation {
+    /// Every elastic node is evicted or optional search features failed
+    Search,
+    /// Exchange rates are missing, prices are left in seller currency
+    Currency,
+}
+
+static SEARCH_DEGRADED: AtomicUsize = AtomicUsize::new(0);
+static CURRENCY_DEGRADED: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of degraded responses per dependency since the start of the instance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DegradationStats {
+    pub search: usize,
+    pub currency: usize,
+}
+
+pub fn stats() -> DegradationStats {
+    DegradationStats {
+        search: SEARCH_DEGRADED.load(Ordering::Relaxed),
+        currency: CURRENCY_DEGRADED.load(Ordering::Relaxed),
+    }
+}
+
+/// Counts the response built with fallbacks of the dependencies
+pub fn record(endpoint: &str, degraded: &[Degradation]) {
+    if degraded.is_empty() {
+        return;
+    }
+
+    warn!("Response of {} is degraded: {:?}", endpoint, degraded);
+    for degradation in degraded {
+        let counter = match *degradation {
+            Degradation::Search => &SEARCH_DEGRADED,
+            Degradation::Currency => &CURRENCY_DEGRADED,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Adds the degradation if it is not listed yet
+pub fn add(degraded: &mut Vec<Degradation>, degradation: Degradation) {
+    if !degraded.contains(&degradation) {
+        degraded.push(degradation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_degraded_responses() {
+        let before = stats();
+        record("test", &[Degradation::Currency]);
+        record("test", &[]);
+        let after = stats();
+        assert!(after.currency >= before.currency + 1);
+    }
+}
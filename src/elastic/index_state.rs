@@ -0,0 +1,110 @@
+//! IndexState repo, reads how far elastic indices got in following the database and the schema versions
+use errors::Error;
+use failure::Fail;
+use futures::Future;
+use hyper::header::{ContentLength, ContentType, Headers};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+
+use super::log_elastic_resp;
+use chaos::{inject_future, FaultLayer};
+use models::{CountResponse, ElasticIndex, SearchResponse, ELASTIC_SCHEMA_VERSION};
+use repos::types::RepoFuture;
+
+/// IndexState repository, responsible for sync state of elastic indices
+pub struct IndexStateElasticImpl {
+    pub client_handle: ClientHandle,
+    pub elastic_address: String,
+}
+
+pub trait IndexStateElastic {
+    /// Max `kafka_update_no` of documents in the index, `None` for an empty index
+    fn max_update_no(&self, index: ElasticIndex) -> RepoFuture<Option<i32>>;
+
+    /// Number of documents in the index written with a schema version older than the current one
+    fn outdated_count(&self, index: ElasticIndex) -> RepoFuture<u64>;
+}
+
+impl IndexStateElasticImpl {
+    pub fn new(client_handle: ClientHandle, elastic_address: String) -> Self {
+        Self {
+            client_handle,
+            elastic_address,
+        }
+    }
+}
+
+impl IndexStateElastic for IndexStateElasticImpl {
+    fn max_update_no(&self, index: ElasticIndex) -> RepoFuture<Option<i32>> {
+        let query = json!({
+            "size": 0,
+            "aggregations": {
+                "max_update_no": {
+                    "max": {
+                        "field": "kafka_update_no"
+                    }
+                }
+            }
+        })
+        .to_string();
+
+        let url = format!("http://{}/{}/_search", self.elastic_address, index);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+        trace!("max_update_no query = '{}'", query);
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<SearchResponse<serde_json::Value>>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(|res| {
+                    res.aggs_raw()
+                        .and_then(|aggs| aggs["max_update_no"]["value"].as_f64())
+                        .map(|value| value as i32)
+                })
+                .map_err(move |e| {
+                    e.context(format!("Max update number of {} index error occurred.", index))
+                        .context(Error::ElasticSearch)
+                        .into()
+                }),
+        )
+    }
+
+    fn outdated_count(&self, index: ElasticIndex) -> RepoFuture<u64> {
+        // Documents without `schema_version` are outdated too
+        let query = json!({
+            "query": {
+                "bool": {
+                    "must_not": {
+                        "range": {
+                            "schema_version": {
+                                "gte": ELASTIC_SCHEMA_VERSION
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .to_string();
+
+        let url = format!("http://{}/{}/_count", self.elastic_address, index);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+        trace!("outdated_count query = '{}'", query);
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<CountResponse>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(|res| res.get_count())
+                .map_err(move |e| {
+                    e.context(format!("Outdated documents count of {} index error occurred.", index))
+                        .context(Error::ElasticSearch)
+                        .into()
+                }),
+        )
+    }
+}
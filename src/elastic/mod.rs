@@ -1,12 +1,26 @@
 //! Elastic search modules
+pub mod index_state;
+pub mod pool;
 pub mod products;
 pub mod stores;
+pub mod synonyms;
 
+pub use self::index_state::*;
+pub use self::pool::*;
 pub use self::products::*;
 pub use self::stores::*;
+pub use self::synonyms::*;
 
 use std::fmt::Debug;
 
+use isolang::Language;
+use serde_json;
+
+use validation_messages::DEFAULT_LANGUAGE;
+
+/// Completion field of names in the default language
+const DEFAULT_SUGGEST_FIELD: &str = "suggest";
+
 pub fn log_elastic_req<T: Debug>(item: &T) {
     debug!("Searching in elastic {:?}.", item);
 }
@@ -14,3 +28,49 @@ pub fn log_elastic_req<T: Debug>(item: &T) {
 pub fn log_elastic_resp<T: Debug>(item: &T) {
     trace!("Result of searching in elastic {:?}.", item)
 }
+
+/// Completion field of names in the language, e.g. `suggest_ru`, names in the default language are in `suggest`
+pub fn language_suggest_field(language: Language) -> Option<String> {
+    if language == DEFAULT_LANGUAGE {
+        return None;
+    }
+    language.to_639_1().map(|code| format!("{}_{}", DEFAULT_SUGGEST_FIELD, code))
+}
+
+/// Name suggesters with the same completion options, `name-suggest-language` searches names in the request language
+/// and `name-suggest` searches default language names, which are the fallback when the language has no suggestions
+pub fn name_suggesters(prefix: &str, completion: serde_json::Value, language: Language) -> serde_json::Value {
+    let mut suggesters = serde_json::Map::new();
+    if let Some(field) = language_suggest_field(language) {
+        let mut language_completion = completion.clone();
+        language_completion["field"] = json!(field);
+        suggesters.insert(
+            "name-suggest-language".to_string(),
+            json!({ "prefix": prefix, "completion": language_completion }),
+        );
+    }
+
+    let mut default_completion = completion;
+    default_completion["field"] = json!(DEFAULT_SUGGEST_FIELD);
+    suggesters.insert("name-suggest".to_string(), json!({ "prefix": prefix, "completion": default_completion }));
+    serde_json::Value::Object(suggesters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_suggesters() {
+        let completion = json!({ "size": 5, "skip_duplicates": true });
+
+        let suggesters = name_suggesters("ph", completion.clone(), Language::Rus);
+        assert_eq!(suggesters["name-suggest-language"]["completion"]["field"], "suggest_ru");
+        assert_eq!(suggesters["name-suggest"]["completion"]["field"], "suggest");
+        assert_eq!(suggesters["name-suggest"]["completion"]["size"], 5);
+
+        let suggesters = name_suggesters("ph", completion, DEFAULT_LANGUAGE);
+        assert!(suggesters.get("name-suggest-language").is_none());
+        assert_eq!(suggesters["name-suggest"]["prefix"], "ph");
+    }
+}
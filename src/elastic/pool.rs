@@ -0,0 +1,142 @@
+//! Pool of elastic nodes. Addresses are given out round-robin, nodes that failed
+//! health check are skipped until their eviction ends. When every node is evicted
+//! they are used anyway, a request to a possibly dead node is better than no request.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use config::Config;
+
+struct ElasticNode {
+    address: String,
+    evicted_until: Mutex<Option<Instant>>,
+}
+
+impl ElasticNode {
+    fn is_available(&self, now: Instant) -> bool {
+        match self.evicted_until.lock() {
+            Ok(evicted_until) => evicted_until.map(|until| until <= now).unwrap_or(true),
+            Err(e) => {
+                error!("Elastic node state is poisoned: {}", e);
+                true
+            }
+        }
+    }
+
+    fn set_evicted_until(&self, until: Option<Instant>) {
+        match self.evicted_until.lock() {
+            Ok(mut evicted_until) => *evicted_until = until,
+            Err(e) => error!("Elastic node state is poisoned: {}", e),
+        }
+    }
+}
+
+pub struct ElasticPool {
+    nodes: Vec<ElasticNode>,
+    next: AtomicUsize,
+    eviction: Duration,
+}
+
+impl ElasticPool {
+    pub fn new(addresses: Vec<String>, eviction: Duration) -> Self {
+        assert!(!addresses.is_empty(), "Elastic pool needs at least one node");
+        Self {
+            nodes: addresses
+                .into_iter()
+                .map(|address| ElasticNode {
+                    address,
+                    evicted_until: Mutex::new(None),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            eviction,
+        }
+    }
+
+    /// Nodes of `[elastic]` section, `server.elastic` if the section is missing
+    pub fn from_config(config: &Config) -> Self {
+        match config.elastic {
+            Some(ref elastic) if !elastic.nodes.is_empty() => Self::new(elastic.nodes.clone(), Duration::from_secs(elastic.eviction_s)),
+            _ => Self::new(vec![config.server.elastic.clone()], Duration::from_secs(0)),
+        }
+    }
+
+    /// Address of the next available node
+    pub fn address(&self) -> String {
+        let now = Instant::now();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.nodes.len())
+            .map(|shift| &self.nodes[start.wrapping_add(shift) % self.nodes.len()])
+            .find(|node| node.is_available(now))
+            .unwrap_or(&self.nodes[start % self.nodes.len()])
+            .address
+            .clone()
+    }
+
+    /// Every node is evicted, requests go to nodes that failed health check
+    pub fn is_degraded(&self) -> bool {
+        let now = Instant::now();
+        !self.nodes.iter().any(|node| node.is_available(now))
+    }
+
+    pub fn addresses(&self) -> Vec<String> {
+        self.nodes.iter().map(|node| node.address.clone()).collect()
+    }
+
+    /// Stops giving out the node for the eviction time
+    pub fn evict(&self, address: &str) {
+        if let Some(node) = self.nodes.iter().find(|node| node.address == address) {
+            warn!("Elastic node {} is evicted for {:?}", address, self.eviction);
+            node.set_evicted_until(Some(Instant::now() + self.eviction));
+        }
+    }
+
+    /// Returns the node to rotation before its eviction ends
+    pub fn restore(&self, address: &str) {
+        if let Some(node) = self.nodes.iter().find(|node| node.address == address) {
+            node.set_evicted_until(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> ElasticPool {
+        ElasticPool::new(
+            vec!["es-1:9200".to_string(), "es-2:9200".to_string(), "es-3:9200".to_string()],
+            Duration::from_secs(60),
+        )
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let pool = pool();
+        let addresses = (0..4).map(|_| pool.address()).collect::<Vec<_>>();
+        assert_eq!(addresses, vec!["es-1:9200", "es-2:9200", "es-3:9200", "es-1:9200"]);
+    }
+
+    #[test]
+    fn test_evicted_node_is_skipped_until_restored() {
+        let pool = pool();
+        pool.evict("es-2:9200");
+        let addresses = (0..3).map(|_| pool.address()).collect::<Vec<_>>();
+        assert_eq!(addresses, vec!["es-1:9200", "es-3:9200", "es-3:9200"]);
+
+        pool.restore("es-2:9200");
+        assert_eq!(pool.address(), "es-1:9200");
+        assert_eq!(pool.address(), "es-2:9200");
+    }
+
+    #[test]
+    fn test_all_nodes_evicted() {
+        let pool = pool();
+        assert!(!pool.is_degraded());
+        for address in pool.addresses() {
+            pool.evict(&address);
+        }
+        assert!(pool.is_degraded());
+        assert_eq!(pool.address(), "es-1:9200");
+    }
+}
@@ -1,31 +1,62 @@
 //! ProductsSearch repo, presents CRUD operations with db for users
+use std::collections::HashSet;
+
 use errors::Error;
-use failure::Fail;
-use futures::Future;
+use failure::{Error as FailureError, Fail};
+use futures::{future, Future};
 use hyper::header::{ContentLength, ContentType, Headers};
 use hyper::Method;
+use isolang::Language;
 use serde_json;
 
 use stq_http::client::ClientHandle;
 use stq_static_resources::ModerationStatus;
-use stq_types::{CategoryId, ProductId};
+use stq_types::{BaseProductId, CategoryId, ProductId, StoreId};
 
-use super::{log_elastic_req, log_elastic_resp};
+use super::{log_elastic_req, log_elastic_resp, name_suggesters};
+use chaos::{inject_future, FaultLayer};
+use config::{AutoComplete, SearchBoosts};
 use models::*;
 use repos::types::RepoFuture;
 
+/// Stores matching the query by name whose products get `store_name` boost
+const MAX_BOOSTED_STORES_COUNT: i64 = 100;
+
 /// ProductsSearch repository, responsible for handling products
 pub struct ProductsElasticImpl {
     pub client_handle: ClientHandle,
     pub elastic_address: String,
+    pub boosts: SearchBoosts,
 }
 
 pub trait ProductsElastic {
     /// Find specific product by name limited by `count` parameters
-    fn auto_complete(&self, name: AutoCompleteProductName, count: i32, offset: i32) -> RepoFuture<Vec<String>>;
+    fn auto_complete(&self, name: AutoCompleteProductName, count: i32, offset: i32, language: Language) -> RepoFuture<Vec<String>>;
+
+    /// Auto complete tolerating typos, completion suggestions go first and
+    /// names of products matched by the fuzzy query fill the rest
+    fn auto_complete_fuzzy(
+        &self,
+        name: AutoCompleteProductName,
+        count: i32,
+        fuzziness: AutoComplete,
+        language: Language,
+    ) -> RepoFuture<Vec<String>>;
 
     /// Find specific product by name limited by `count` parameters
-    fn search_by_name(&self, prod: SearchProductsByName, count: i32, offset: i32) -> RepoFuture<Vec<ElasticProduct>>;
+    fn search_by_name(&self, prod: SearchProductsByName, count: i32, offset: i32) -> RepoFuture<SearchResult<ElasticProduct>>;
+
+    /// Find specific product by name starting after the hit the token was issued for.
+    /// Returns token of the next page while the page is full
+    fn search_by_name_after(
+        &self,
+        prod: SearchProductsByName,
+        count: i32,
+        after: Option<SearchAfterToken>,
+    ) -> RepoFuture<(Vec<ElasticProduct>, Option<SearchAfterToken>)>;
+
+    /// Alternative spellings of the name that find some products, best first
+    fn suggest(&self, name: String, count: i32) -> RepoFuture<Vec<String>>;
 
     /// Find product by views limited by `count` and `offset` parameters
     fn search_most_viewed(&self, prod: MostViewedProducts, count: i32, offset: i32) -> RepoFuture<Vec<ElasticProduct>>;
@@ -33,6 +64,15 @@ pub trait ProductsElastic {
     /// Find product by dicount pattern limited by `count` and `offset` parameters
     fn search_most_discount(&self, prod: MostDiscountProducts, count: i32, offset: i32) -> RepoFuture<Vec<ElasticProduct>>;
 
+    /// Find published products of the category with names like `names`, except the `exclude` ones
+    fn more_like_this(
+        &self,
+        names: Vec<String>,
+        category_id: CategoryId,
+        exclude: Vec<BaseProductId>,
+        count: i32,
+    ) -> RepoFuture<Vec<ElasticProduct>>;
+
     /// Find all categories ids where prod exist
     fn aggregate_categories(&self, name: String) -> RepoFuture<Vec<CategoryId>>;
 
@@ -48,9 +88,84 @@ impl ProductsElasticImpl {
         Self {
             client_handle,
             elastic_address,
+            boosts: SearchBoosts::default(),
         }
     }
 
+    /// Scores search by name with `boosts` instead of equal weights of the fields
+    pub fn with_boosts(self, boosts: SearchBoosts) -> Self {
+        Self { boosts, ..self }
+    }
+
+    /// Ids of published stores whose name matches the query, used to boost their products
+    fn find_store_ids_by_name(&self, name: &str) -> RepoFuture<Vec<StoreId>> {
+        let query = json!({
+            "size": MAX_BOOSTED_STORES_COUNT,
+            "query": {
+                "bool": {
+                    "must": {
+                        "nested": {
+                            "path": "name",
+                            "query": {
+                                "match": {
+                                    "name.text": {
+                                        "query": name,
+                                        "fuzziness": "AUTO"
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "filter": [{ "term": {"status": "published"}}]
+                }
+            }
+        })
+        .to_string();
+
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Store);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+        trace!("find_store_ids_by_name query = '{}'", query);
+        let name = name.to_string();
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(|res| res.into_documents().map(|store| store.id).collect())
+                .map_err(move |e| {
+                    e.context(format!("Find stores by name error occurred. Name: {:?}", name))
+                        .context(Error::ElasticSearch)
+                        .into()
+                }),
+        )
+    }
+
+    /// Bool query of search by name with products of stores matching the name boosted.
+    /// Shared by offset and `search_after` pagination, so pages of both are scored the same
+    fn search_by_name_query(&self, prod: &SearchProductsByName) -> RepoFuture<serde_json::Map<String, serde_json::Value>> {
+        let product_name = prod.name.to_lowercase();
+        let mut query_map = ProductsElasticImpl::create_search_by_name_query(prod, &self.boosts);
+
+        let store_boost = self.boosts.store_name;
+        if store_boost <= 0.0 || product_name.is_empty() {
+            return Box::new(future::ok(query_map));
+        }
+
+        Box::new(self.find_store_ids_by_name(&product_name).then(move |store_ids| {
+            let store_ids = store_ids.unwrap_or_else(|e| {
+                error!("Products are searched without store name boost: {:?}", e);
+                vec![]
+            });
+            // Store name only affects the score, products are still matched by their own fields
+            if !store_ids.is_empty() {
+                query_map.insert("should".to_string(), json!([{ "terms": {"store_id": store_ids, "boost": store_boost}}]));
+            }
+            Ok::<_, FailureError>(query_map)
+        }))
+    }
+
     fn create_products_from_search_response(res: SearchResponse<ElasticProduct>) -> Vec<ElasticProduct> {
         let mut prods = vec![];
         for hit in res.into_hits() {
@@ -81,7 +196,7 @@ impl ProductsElasticImpl {
                 prods.push(prod);
             }
         }
-        prods
+        readable_documents(prods)
     }
 
     fn create_variants_map_filters(options: &Option<ProductsSearchOptions>) -> serde_json::Map<String, serde_json::Value> {
@@ -178,15 +293,22 @@ impl ProductsElasticImpl {
         });
         variants_filters.push(variant_exists);
 
+        // variants without quantity do not track stock and stay visible, as do documents of the previous schema version
+        let variant_available = json!({
+            "bool": {
+                "should": [
+                    {"range": {"variants.quantity": {"gt": 0}}},
+                    {"term": {"variants.pre_order": true}},
+                    {"bool": {"must_not": {"exists": {"field": "variants.quantity"}}}}
+                ]
+            }
+        });
+        variants_filters.push(variant_available);
+
         if let Some(options) = options.clone() {
             if let Some(sort_by) = options.sort_by {
                 if sort_by == ProductsSorting::Discount {
-                    let variant_discount_exists = json!({
-                        "exists": {
-                            "field": "variants.discount"
-                        }
-                    });
-                    variants_filters.push(variant_discount_exists);
+                    variants_filters.extend(ProductsElasticImpl::create_active_discount_filters());
                 }
             }
         }
@@ -200,6 +322,31 @@ impl ProductsElasticImpl {
         variants_map
     }
 
+    /// Filters of variants with a discount applied now. Documents of the previous schema version
+    /// have no discount window, their discounts stay applied until reindex
+    fn create_active_discount_filters() -> Vec<serde_json::Value> {
+        vec![
+            json!({ "exists": { "field": "variants.discount" } }),
+            json!({ "range": { "variants.discount": { "gt": 0 } } }),
+            json!({
+                "bool": {
+                    "should": [
+                        {"range": {"variants.discount_starts_at": {"lte": "now"}}},
+                        {"bool": {"must_not": {"exists": {"field": "variants.discount_starts_at"}}}}
+                    ]
+                }
+            }),
+            json!({
+                "bool": {
+                    "should": [
+                        {"range": {"variants.discount_ends_at": {"gt": "now"}}},
+                        {"bool": {"must_not": {"exists": {"field": "variants.discount_ends_at"}}}}
+                    ]
+                }
+            }),
+        ]
+    }
+
     fn create_category_filter(options: Option<ProductsSearchOptions>) -> Option<serde_json::Value> {
         options.and_then(|o| o.categories_ids).map(|ids| {
             json!({
@@ -224,6 +371,58 @@ impl ProductsElasticImpl {
         })
     }
 
+    /// Products of stores on vacation are not found, documents indexed before vacations existed have no flag
+    fn create_vacation_filter() -> serde_json::Value {
+        json!({
+            "bool": {"must_not": {"term": {"store_is_on_vacation": true}}}
+        })
+    }
+
+    /// Drafts are not found, `published_at` is null until the owner publishes the base product
+    fn create_published_filter() -> serde_json::Value {
+        json!({
+            "exists": {"field": "published_at"}
+        })
+    }
+
+    fn create_suggest_store_context(name: &AutoCompleteProductName) -> serde_json::Value {
+        if let Some(store_id) = name.store_id {
+            if let Some(status) = name.status {
+                json!([format!("{}_{}", store_id, status)]) // workaround because elastic doesn't afford to ANY contexts
+            } else {
+                let statuses: Vec<String> = ModerationStatus::enum_iter().map(|m| format!("{}_{}", store_id, m)).collect();
+                json!(statuses)
+            }
+        } else {
+            if let Some(status) = name.status {
+                let status = format!("{}", status);
+                json!([status])
+            } else {
+                json!([])
+            }
+        }
+    }
+
+    /// Texts of name translations that matched the query, read from `name` inner hits
+    fn matched_names_from_search_response(res: &SearchResponse<ElasticProduct>) -> Vec<String> {
+        let mut names = vec![];
+        for hit in res.hits() {
+            let translations = hit
+                .inner_hits()
+                .as_ref()
+                .and_then(|inner_hits| inner_hits.get("name"))
+                .and_then(|name| name["hits"]["hits"].as_array());
+            if let Some(translations) = translations {
+                for translation in translations {
+                    if let Some(text) = translation["_source"]["text"].as_str() {
+                        names.push(text.to_string());
+                    }
+                }
+            }
+        }
+        names
+    }
+
     fn create_sorting(options: Option<ProductsSearchOptions>) -> Vec<serde_json::Value> {
         let mut sorting: Vec<serde_json::Value> = vec![];
         if let Some(options) = options {
@@ -255,24 +454,56 @@ impl ProductsElasticImpl {
                             "mode" :  "max",
                             "order" : "desc",
                             "nested": {
-                                "path": "variants"
+                                "path": "variants",
+                                "filter": {
+                                    "bool": {
+                                        "filter": ProductsElasticImpl::create_active_discount_filters()
+                                    }
+                                }
                             }
                         }
                     }),
                 };
                 sorting.push(sort);
+            } else if let Some(store_order) = options.store_order {
+                sorting.push(ProductsElasticImpl::create_store_order_sorting(store_order));
+                sorting.push(json!({ "_score" : { "order" : "desc" } }));
             }
         }
         sorting
     }
-}
 
-impl ProductsElastic for ProductsElasticImpl {
-    /// Find specific products by name limited by `count` parameters
-    fn search_by_name(&self, prod: SearchProductsByName, count: i32, offset: i32) -> RepoFuture<Vec<ElasticProduct>> {
-        log_elastic_req(&prod);
+    /// Sorts products in storefront order of the store, products without position go last
+    fn create_store_order_sorting(store_order: Vec<BaseProductId>) -> serde_json::Value {
+        let unranked = store_order.len();
+        let ranks = store_order
+            .into_iter()
+            .enumerate()
+            .map(|(rank, base_product_id)| (base_product_id.to_string(), json!(rank)))
+            .collect::<serde_json::Map<_, _>>();
+        json!({
+            "_script" : {
+                "type" : "number",
+                "order" : "asc",
+                "script" : {
+                    "source" : r###"
+                        def id = String.valueOf(doc['id'].value);
+                        return params.ranks.containsKey(id) ? params.ranks[id] : params.unranked;
+                    "###,
+                    "lang" : "painless",
+                    "params" : {
+                        "ranks" : ranks,
+                        "unranked" : unranked
+                    }
+                }
+            }
+        })
+    }
+
+    /// Bool query matching products by name and search options
+    pub fn create_search_by_name_query(prod: &SearchProductsByName, boosts: &SearchBoosts) -> serde_json::Map<String, serde_json::Value> {
         let product_name = prod.name.to_lowercase();
-        let name_query = fuzzy_search_by_name_query(&product_name);
+        let name_query = fuzzy_search_by_name_query(&product_name, boosts);
 
         let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
         if !product_name.is_empty() {
@@ -334,38 +565,115 @@ impl ProductsElastic for ProductsElasticImpl {
             filters.push(json!({ "term": {"store_status": status.to_string()}}));
         }
 
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
         query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
+        query_map
+    }
+}
 
+impl ProductsElastic for ProductsElasticImpl {
+    /// Find specific products by name limited by `count` parameters
+    fn search_by_name(&self, prod: SearchProductsByName, count: i32, offset: i32) -> RepoFuture<SearchResult<ElasticProduct>> {
+        log_elastic_req(&prod);
         let sorting = ProductsElasticImpl::create_sorting(prod.options.clone());
 
-        let query = json!({
-            "from" : offset, "size" : count,
-            "query": {
-                "bool" : query_map
-            },
-            "sort" : sorting
-        })
-        .to_string();
+        let client_handle = self.client_handle.clone();
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Product);
+        Box::new(self.search_by_name_query(&prod).and_then(move |query_map| {
+            let query = json!({
+                "from" : offset, "size" : count,
+                "query": {
+                    "bool" : query_map
+                },
+                "sort" : sorting
+            })
+            .to_string();
+
+            let mut headers = Headers::new();
+            headers.set(ContentType::json());
+            headers.set(ContentLength(query.len() as u64));
+            trace!("search_by_name query = '{}'", query);
+            inject_future(
+                FaultLayer::Elastic,
+                client_handle
+                    .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
+                    .inspect(|ref res| log_elastic_resp(res))
+                    .map(|res| {
+                        let (total, took_ms) = (res.total(), res.took());
+                        SearchResult::new(total, took_ms, ProductsElasticImpl::create_products_from_search_response(res))
+                    })
+                    .map_err(move |e| {
+                        e.context(format!(
+                            "Search product by name error occurred. Prod: {:?}, count: {:?}, offset: {:?}",
+                            prod, count, offset
+                        ))
+                        .context(Error::ElasticSearch)
+                        .into()
+                    }),
+            )
+        }))
+    }
+
+    /// Find specific product by name starting after the hit the token was issued for.
+    /// Returns token of the next page while the page is full
+    fn search_by_name_after(
+        &self,
+        prod: SearchProductsByName,
+        count: i32,
+        after: Option<SearchAfterToken>,
+    ) -> RepoFuture<(Vec<ElasticProduct>, Option<SearchAfterToken>)> {
+        log_elastic_req(&prod);
 
+        // search_after needs a total order, so ties are broken by id
+        let mut sorting = ProductsElasticImpl::create_sorting(prod.options.clone());
+        if sorting.is_empty() {
+            sorting.push(json!({ "_score" : { "order" : "desc" } }));
+        }
+        sorting.push(json!({ "id" : { "order" : "asc" } }));
+
+        let client_handle = self.client_handle.clone();
         let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Product);
-        let mut headers = Headers::new();
-        headers.set(ContentType::json());
-        headers.set(ContentLength(query.len() as u64));
-        trace!("search_by_name query = '{}'", query);
-        Box::new(
-            self.client_handle
-                .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
-                .inspect(|ref res| log_elastic_resp(res))
-                .map(ProductsElasticImpl::create_products_from_search_response)
-                .map_err(move |e| {
-                    e.context(format!(
-                        "Search product by name error occurred. Prod: {:?}, count: {:?}, offset: {:?}",
-                        prod, count, offset
-                    ))
-                    .context(Error::ElasticSearch)
-                    .into()
-                }),
-        )
+        Box::new(self.search_by_name_query(&prod).and_then(move |query_map| {
+            let mut query = json!({
+                "size" : count,
+                "query": {
+                    "bool" : query_map
+                },
+                "sort" : sorting
+            });
+            if let Some(ref after) = after {
+                query["search_after"] = serde_json::Value::Array(after.0.clone());
+            }
+            let query = query.to_string();
+
+            let mut headers = Headers::new();
+            headers.set(ContentType::json());
+            headers.set(ContentLength(query.len() as u64));
+            trace!("search_by_name_after query = '{}'", query);
+            inject_future(
+                FaultLayer::Elastic,
+                client_handle
+                    .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
+                    .inspect(|ref res| log_elastic_resp(res))
+                    .map(move |res| {
+                        let next = if res.hits().count() == count as usize {
+                            res.hits().last().and_then(|hit| hit.sort().clone()).map(SearchAfterToken)
+                        } else {
+                            None
+                        };
+                        (ProductsElasticImpl::create_products_from_search_response(res), next)
+                    })
+                    .map_err(move |e| {
+                        e.context(format!(
+                            "Search product by name after token error occurred. Prod: {:?}, count: {:?}, after: {:?}",
+                            prod, count, after
+                        ))
+                        .context(Error::ElasticSearch)
+                        .into()
+                    }),
+            )
+        }))
     }
 
     /// Find product by views limited by `count` and `offset` parameters
@@ -409,6 +717,8 @@ impl ProductsElastic for ProductsElasticImpl {
             filters.push(json!({ "term": {"store_status": status.to_string()}}));
         }
 
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
         query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
 
         let query = json!({
@@ -425,7 +735,8 @@ impl ProductsElastic for ProductsElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("search_most_viewed query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -452,11 +763,7 @@ impl ProductsElastic for ProductsElasticImpl {
                 "path": "variants",
                 "query": {
                     "bool": {
-                        "filter": {
-                            "exists": {
-                                "field": "variants.discount"
-                            }
-                        }
+                        "filter": ProductsElasticImpl::create_active_discount_filters()
                     }
                 }
             }
@@ -470,16 +777,7 @@ impl ProductsElastic for ProductsElasticImpl {
                 "path":"variants",
                 "query":{
                     "bool": {
-                        "filter": [
-                            { "exists": {
-                                "field": "variants.discount"
-                            }},
-                            { "range": {
-                                "variants.discount": {
-                                    "gt": 0
-                                }
-                            }}
-                        ]
+                        "filter": ProductsElasticImpl::create_active_discount_filters()
                     }
                 },
                 "inner_hits": {
@@ -509,6 +807,8 @@ impl ProductsElastic for ProductsElasticImpl {
             filters.push(json!({ "term": {"store_status": status.to_string()}}));
         }
 
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
         query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
 
         let query = json!({
@@ -521,7 +821,12 @@ impl ProductsElastic for ProductsElasticImpl {
                     "mode" :  "max",
                     "order" : "desc",
                     "nested": {
-                        "path": "variants"
+                        "path": "variants",
+                        "filter": {
+                            "bool": {
+                                "filter": ProductsElasticImpl::create_active_discount_filters()
+                            }
+                        }
                     }
                 }
             }]
@@ -533,7 +838,8 @@ impl ProductsElastic for ProductsElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("search_most_discount query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -549,40 +855,151 @@ impl ProductsElastic for ProductsElasticImpl {
         )
     }
 
-    fn auto_complete(&self, name: AutoCompleteProductName, count: i32, _offset: i32) -> RepoFuture<Vec<String>> {
-        log_elastic_req(&name);
-        let product_name = name.name.to_lowercase();
+    fn more_like_this(
+        &self,
+        names: Vec<String>,
+        category_id: CategoryId,
+        exclude: Vec<BaseProductId>,
+        count: i32,
+    ) -> RepoFuture<Vec<ElasticProduct>> {
+        log_elastic_req(&names);
 
-        let store = if let Some(store_id) = name.store_id {
-            if let Some(status) = name.status {
-                json!([format!("{}_{}", store_id, status)]) // workaround because elastic doesn't afford to ANY contexts
-            } else {
-                let statuses: Vec<String> = ModerationStatus::enum_iter().map(|m| format!("{}_{}", store_id, m)).collect();
-                json!(statuses)
-            }
-        } else {
-            if let Some(status) = name.status {
-                let status = format!("{}", status);
-                json!([status])
-            } else {
-                json!([])
+        // Product names are rare in the index, so single occurrences of terms are enough
+        let query = json!({
+            "size": count,
+            "query": {
+                "bool": {
+                    "must": {
+                        "nested": {
+                            "path": "name",
+                            "query": {
+                                "more_like_this": {
+                                    "fields": ["name.text"],
+                                    "like": names,
+                                    "min_term_freq": 1,
+                                    "min_doc_freq": 1
+                                }
+                            }
+                        }
+                    },
+                    "filter": [
+                        { "term": {"category_id": category_id}},
+                        { "term": {"status": ModerationStatus::Published.to_string()}},
+                        { "term": {"store_status": ModerationStatus::Published.to_string()}},
+                        ProductsElasticImpl::create_vacation_filter(),
+                        ProductsElasticImpl::create_published_filter()
+                    ],
+                    "must_not": [{ "terms": {"id": exclude}}]
+                }
             }
-        };
+        })
+        .to_string();
+
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Product);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+        trace!("more_like_this query = '{}'", query);
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(ProductsElasticImpl::create_products_from_search_response)
+                .map_err(move |e| {
+                    e.context(format!(
+                        "Search products like {:?} error occurred. Category: {}, count: {}",
+                        names, category_id, count
+                    ))
+                    .context(Error::ElasticSearch)
+                    .into()
+                }),
+        )
+    }
 
-        let suggest = json!({
-            "name-suggest" : {
-                "prefix" : product_name,
-                "completion" : {
-                    "field" : "suggest",
-                    "size" : count,
-                    "skip_duplicates": true,
-                    "fuzzy": true,
-                    "contexts": {
-                        "store_and_status": store
+    fn suggest(&self, name: String, count: i32) -> RepoFuture<Vec<String>> {
+        log_elastic_req(&name);
+        let text = name.to_lowercase();
+        // Collate drops corrections that would find nothing again
+        let query = json!({
+            "size": 0,
+            "suggest": {
+                "did-you-mean": {
+                    "text": text,
+                    "phrase": {
+                        "field": "name.text",
+                        "size": count,
+                        "max_errors": 2,
+                        "direct_generator": [{
+                            "field": "name.text",
+                            "suggest_mode": "always"
+                        }],
+                        "collate": {
+                            "query": {
+                                "source": {
+                                    "nested": {
+                                        "path": "name",
+                                        "query": {
+                                            "match": {
+                                                "name.text": {
+                                                    "query": "{{suggestion}}",
+                                                    "operator": "and"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                            "prune": false
+                        }
                     }
                 }
             }
+        })
+        .to_string();
+
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Product);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+        trace!("suggest query = '{}'", query);
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<serde_json::Value>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(|res| {
+                    res["suggest"]["did-you-mean"]
+                        .as_array()
+                        .into_iter()
+                        .flat_map(|entries| entries.iter())
+                        .filter_map(|entry| entry["options"].as_array())
+                        .flat_map(|options| options.iter())
+                        .filter_map(|option| option["text"].as_str().map(|text| text.to_string()))
+                        .collect()
+                })
+                .map_err(move |e| {
+                    e.context(format!("Suggest product name error occurred. Name: {:?}, count: {}", name, count))
+                        .context(Error::ElasticSearch)
+                        .into()
+                }),
+        )
+    }
+
+    fn auto_complete(&self, name: AutoCompleteProductName, count: i32, _offset: i32, language: Language) -> RepoFuture<Vec<String>> {
+        log_elastic_req(&name);
+        let product_name = name.name.to_lowercase();
+        let store = ProductsElasticImpl::create_suggest_store_context(&name);
+
+        let completion = json!({
+            "size" : count,
+            "skip_duplicates": true,
+            "fuzzy": true,
+            "contexts": {
+                "store_and_status": store
+            }
         });
+        let suggest = name_suggesters(&product_name, completion, language);
 
         let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
         query_map.insert("_source".to_string(), serde_json::Value::Bool(false));
@@ -593,7 +1010,8 @@ impl ProductsElastic for ProductsElasticImpl {
         let mut headers = Headers::new();
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -609,11 +1027,97 @@ impl ProductsElastic for ProductsElasticImpl {
         )
     }
 
+    fn auto_complete_fuzzy(
+        &self,
+        name: AutoCompleteProductName,
+        count: i32,
+        fuzziness: AutoComplete,
+        language: Language,
+    ) -> RepoFuture<Vec<String>> {
+        log_elastic_req(&name);
+        let product_name = name.name.to_lowercase();
+        let store = ProductsElasticImpl::create_suggest_store_context(&name);
+
+        let completion = json!({
+            "size" : count,
+            "skip_duplicates": true,
+            "fuzzy": {
+                "fuzziness": fuzziness.fuzziness,
+                "prefix_length": fuzziness.prefix_length,
+                "min_length": fuzziness.min_length
+            },
+            "contexts": {
+                "store_and_status": store
+            }
+        });
+        let suggest = name_suggesters(&product_name, completion, language);
+
+        let mut filters: Vec<serde_json::Value> = vec![];
+        if let Some(store_id) = name.store_id {
+            filters.push(json!({ "term": {"store_id": store_id}}));
+        }
+        if let Some(status) = name.status {
+            filters.push(json!({ "term": {"status": status.to_string()}}));
+        }
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
+
+        let query = json!({
+            "size": count,
+            "_source": false,
+            "query": {
+                "bool": {
+                    "must": {
+                        "nested": {
+                            "path": "name",
+                            "query": {
+                                "match": {
+                                    "name.text": {
+                                        "query": product_name,
+                                        "fuzziness": fuzziness.fuzziness,
+                                        "prefix_length": fuzziness.prefix_length
+                                    }
+                                }
+                            },
+                            "inner_hits": {
+                                "size": 1,
+                                "_source": ["name.text"]
+                            }
+                        }
+                    },
+                    "filter": filters
+                }
+            },
+            "suggest": suggest
+        })
+        .to_string();
+        trace!("auto_complete_fuzzy query = '{}'", query);
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Product);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(move |res| {
+                    let matched_names = ProductsElasticImpl::matched_names_from_search_response(&res);
+                    merge_auto_complete_names(res.suggested_texts(), matched_names, count as usize)
+                })
+                .map_err(move |e| {
+                    e.context(format!("Fuzzy auto complete product name error occurred. Name: {:?}, count: {}", name, count))
+                        .context(Error::ElasticSearch)
+                        .into()
+                }),
+        )
+    }
+
     /// Find all categories ids where prod exist
     fn aggregate_categories(&self, name: String) -> RepoFuture<Vec<CategoryId>> {
         log_elastic_req(&name);
         let name = name.to_lowercase();
-        let name_query = fuzzy_search_by_name_query(&name);
+        let name_query = fuzzy_search_by_name_query(&name, &self.boosts);
 
         let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
         if !name.is_empty() {
@@ -623,6 +1127,8 @@ impl ProductsElastic for ProductsElasticImpl {
         let mut filters: Vec<serde_json::Value> = vec![];
         filters.push(json!({ "term": {"status": "published"}}));
         filters.push(json!({ "term": {"store_status": "published"}}));
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
         query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
 
         let query = json!({
@@ -645,7 +1151,8 @@ impl ProductsElastic for ProductsElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("aggregate_categories query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -672,7 +1179,7 @@ impl ProductsElastic for ProductsElasticImpl {
         log_elastic_req(&prod);
         let product_name = prod.name.to_lowercase();
 
-        let name_query = fuzzy_search_by_name_query(&product_name);
+        let name_query = fuzzy_search_by_name_query(&product_name, &self.boosts);
 
         let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
         if !product_name.is_empty() {
@@ -699,6 +1206,8 @@ impl ProductsElastic for ProductsElasticImpl {
             filters.push(json!({ "term": {"store_status": status.to_string()}}));
         }
 
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
         query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
 
         let currency_map = prod.options.clone().and_then(|o| o.currency_map);
@@ -772,7 +1281,8 @@ impl ProductsElastic for ProductsElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("aggregate_price query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticProduct>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -800,7 +1310,7 @@ impl ProductsElastic for ProductsElasticImpl {
         log_elastic_req(&prod);
         let product_name = prod.name.to_lowercase();
 
-        let name_query = fuzzy_search_by_name_query(&product_name);
+        let name_query = fuzzy_search_by_name_query(&product_name, &self.boosts);
 
         let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
         if !product_name.is_empty() {
@@ -830,6 +1340,8 @@ impl ProductsElastic for ProductsElasticImpl {
 
         filters.push(json!({ "term": {"store_status": "published"}}));
 
+        filters.push(ProductsElasticImpl::create_vacation_filter());
+        filters.push(ProductsElasticImpl::create_published_filter());
         query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
 
         let query = json!({
@@ -844,7 +1356,8 @@ impl ProductsElastic for ProductsElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("count query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<CountResponse>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -858,7 +1371,7 @@ impl ProductsElastic for ProductsElasticImpl {
     }
 }
 
-fn fuzzy_search_by_name_query(name: &str) -> serde_json::Value {
+fn fuzzy_search_by_name_query(name: &str, boosts: &SearchBoosts) -> serde_json::Value {
     json!({
         "bool" : {
             "should" : [
@@ -868,7 +1381,8 @@ fn fuzzy_search_by_name_query(name: &str) -> serde_json::Value {
                         "match": {
                             "name.text":{
                                 "query":name,
-                                "fuzziness":"AUTO"
+                                "fuzziness":"AUTO",
+                                "boost": boosts.name
                             }
                         }
                     }
@@ -877,7 +1391,10 @@ fn fuzzy_search_by_name_query(name: &str) -> serde_json::Value {
                     "path": "short_description",
                     "query": {
                         "match": {
-                            "short_description.text": name
+                            "short_description.text": {
+                                "query": name,
+                                "boost": boosts.short_description
+                            }
                         }
                     }
                 }},
@@ -885,7 +1402,10 @@ fn fuzzy_search_by_name_query(name: &str) -> serde_json::Value {
                     "path": "long_description",
                     "query": {
                         "match": {
-                            "long_description.text": name
+                            "long_description.text": {
+                                "query": name,
+                                "boost": boosts.long_description
+                            }
                         }
                     }
                 }}
@@ -893,3 +1413,14 @@ fn fuzzy_search_by_name_query(name: &str) -> serde_json::Value {
         }
     })
 }
+
+/// Suggested names followed by matched ones, without case-insensitive duplicates
+fn merge_auto_complete_names(suggested: Vec<String>, matched: Vec<String>, count: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    suggested
+        .into_iter()
+        .chain(matched)
+        .filter(|name| seen.insert(name.to_lowercase()))
+        .take(count)
+        .collect()
+}
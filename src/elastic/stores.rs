@@ -4,15 +4,23 @@ use failure::Fail;
 use futures::Future;
 use hyper::header::{ContentLength, ContentType, Headers};
 use hyper::Method;
+use isolang::Language;
 use serde_json;
 use stq_http::client::ClientHandle;
 
 use stq_types::CategoryId;
 
-use super::{log_elastic_req, log_elastic_resp};
-use models::{CountResponse, ElasticIndex, ElasticStore, SearchResponse, SearchStore, StoresSearchOptions};
+use super::{log_elastic_req, log_elastic_resp, name_suggesters};
+use chaos::{inject_future, FaultLayer};
+use models::{
+    readable_documents, CountResponse, ElasticIndex, ElasticStore, ElasticStoresWithFacets, SearchResponse, SearchStore,
+    SearchStoresNearby, StoresSearchOptions, VersionedDocument,
+};
 use repos::types::RepoFuture;
 
+/// Score added to stores verified by moderators, they go first among equally matching stores
+const VERIFIED_STORE_BOOST: f64 = 2.0;
+
 /// StoresSearch repository, responsible for handling stores
 pub struct StoresElasticImpl {
     pub client_handle: ClientHandle,
@@ -22,6 +30,8 @@ pub struct StoresElasticImpl {
 pub trait StoresElastic {
     /// Find specific store by name limited by `count` parameters
     fn find_by_name(&self, search_store: SearchStore, count: i32, offset: i32) -> RepoFuture<Vec<ElasticStore>>;
+    /// Find stores by name with country and category facets
+    fn find_by_name_with_facets(&self, search_store: SearchStore, count: i32, offset: i32) -> RepoFuture<ElasticStoresWithFacets>;
     /// Search count of stores by name
     fn search_count(&self, search_store: SearchStore) -> RepoFuture<i32>;
     /// Aggregate countries
@@ -29,7 +39,9 @@ pub trait StoresElastic {
     /// Aggregate categories
     fn aggregate_categories(&self, search_store: SearchStore) -> RepoFuture<Vec<CategoryId>>;
     /// Auto complete
-    fn auto_complete(&self, name: String, count: i32, offset: i32) -> RepoFuture<Vec<String>>;
+    fn auto_complete(&self, name: String, count: i32, offset: i32, language: Language) -> RepoFuture<Vec<String>>;
+    /// Find stores within radius ordered by distance, returns stores with distance in kilometers
+    fn search_nearby(&self, search: SearchStoresNearby, count: i32, offset: i32) -> RepoFuture<Vec<(ElasticStore, Option<f64>)>>;
 }
 
 impl StoresElasticImpl {
@@ -81,6 +93,7 @@ impl StoresElastic for StoresElasticImpl {
         if !store_name.is_empty() {
             query_map.insert("must".to_string(), name_query);
         }
+        query_map.insert("should".to_string(), verified_store_boost_query());
 
         let mut filters = StoresElasticImpl::create_elastic_filters(search_store.options.clone());
         filters.push(json!({ "term": {"status": "published"}}));
@@ -108,6 +121,7 @@ impl StoresElastic for StoresElasticImpl {
                     "bool" : query_map
                 },
                 "sort" : [
+                    "_score",
                     { "rating" : { "order" : "desc"} }
                 ]
             })
@@ -128,11 +142,12 @@ impl StoresElastic for StoresElasticImpl {
         headers.set(ContentLength(query.len() as u64));
 
         trace!("find_by_name query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
-                .map(|res| res.into_documents().collect::<Vec<ElasticStore>>())
+                .map(|res| readable_documents(res.into_documents()))
                 .map_err(move |e| {
                     e.context(format!(
                         "Search store by name error occurred. Store: {:?}, count: {:?}, offset: {:?}",
@@ -144,25 +159,166 @@ impl StoresElastic for StoresElasticImpl {
         )
     }
 
+    /// Find stores by name with country and category facets. Selected options go to `post_filter`,
+    /// so they narrow down the hits but not the facets
+    fn find_by_name_with_facets(&self, search_store: SearchStore, count: i32, offset: i32) -> RepoFuture<ElasticStoresWithFacets> {
+        log_elastic_req(&search_store);
+        let store_name = search_store.name.to_lowercase();
+
+        let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
+        if !store_name.is_empty() {
+            query_map.insert("must".to_string(), fuzzy_search_by_name_query(&store_name));
+        }
+        query_map.insert("should".to_string(), verified_store_boost_query());
+        let filters = vec![
+            json!({ "term": {"status": "published"}}),
+            json!({
+                "nested":{
+                    "path": "product_categories",
+                    "query": { "bool": { "filter": { "exists": { "field": "product_categories" } } } }
+                }
+            }),
+        ];
+        query_map.insert("filter".to_string(), serde_json::Value::Array(filters));
+
+        let options_filters = StoresElasticImpl::create_elastic_filters(search_store.options.clone());
+
+        let mut query = json!({
+            "from" : offset, "size" : count,
+            "query": {
+                "bool" : query_map
+            },
+            "post_filter": {
+                "bool": { "filter": options_filters }
+            },
+            "aggregations": {
+                "countries": {
+                    "terms": { "field": "country.keyword" }
+                },
+                "product_categories" : {
+                    "nested" : {
+                        "path" : "product_categories"
+                    },
+                    "aggs" : {
+                        "categories" : { "terms" : { "field" : "product_categories.category_id" } },
+                    }
+                }
+            }
+        });
+        if store_name.is_empty() {
+            query["sort"] = json!(["_score", { "rating" : { "order" : "desc"} }]);
+        }
+        let query = query.to_string();
+
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Store);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+
+        trace!("find_by_name_with_facets query = '{}'", query);
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(|res| {
+                    let total_count = res.total();
+                    let aggregations = res.aggs_raw().cloned();
+                    let stores = readable_documents(res.into_documents());
+                    ElasticStoresWithFacets::new(stores, total_count, aggregations.as_ref())
+                })
+                .map_err(move |e| {
+                    e.context(format!(
+                        "Search store by name with facets error occurred. Store: {:?}, count: {:?}, offset: {:?}",
+                        search_store, count, offset
+                    ))
+                    .context(Error::ElasticSearch)
+                    .into()
+                }),
+        )
+    }
+
+    /// Find stores within radius ordered by distance, returns stores with distance in kilometers
+    fn search_nearby(&self, search: SearchStoresNearby, count: i32, offset: i32) -> RepoFuture<Vec<(ElasticStore, Option<f64>)>> {
+        log_elastic_req(&search);
+        let point = json!({ "lat": search.latitude, "lon": search.longitude });
+
+        let query = json!({
+            "from" : offset, "size" : count,
+            "query": {
+                "bool" : {
+                    "filter": [
+                        { "term": {"status": "published"}},
+                        {
+                            "geo_distance": {
+                                "distance": format!("{}km", search.radius_km),
+                                "location": point
+                            }
+                        }
+                    ]
+                }
+            },
+            "sort" : [
+                {
+                    "_geo_distance" : {
+                        "location" : point,
+                        "order" : "asc",
+                        "unit" : "km"
+                    }
+                }
+            ]
+        })
+        .to_string();
+
+        let url = format!("http://{}/{}/_search", self.elastic_address, ElasticIndex::Store);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(query.len() as u64));
+
+        trace!("search_nearby query = '{}'", query);
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map(|res| {
+                    res.into_hits()
+                        .filter_map(|hit| {
+                            let distance = hit
+                                .sort()
+                                .as_ref()
+                                .and_then(|sort| sort.first())
+                                .and_then(|distance| distance.as_f64());
+                            hit.into_document().map(|store| (store, distance))
+                        })
+                        .filter(|&(ref store, _)| store.is_readable())
+                        .collect()
+                })
+                .map_err(move |e| {
+                    e.context(format!(
+                        "Search stores nearby error occurred. Search: {:?}, count: {:?}, offset: {:?}",
+                        search, count, offset
+                    ))
+                    .context(Error::ElasticSearch)
+                    .into()
+                }),
+        )
+    }
+
     /// Auto Complete
-    fn auto_complete(&self, name: String, count: i32, _offset: i32) -> RepoFuture<Vec<String>> {
+    fn auto_complete(&self, name: String, count: i32, _offset: i32, language: Language) -> RepoFuture<Vec<String>> {
         log_elastic_req(&name);
         let name = name.to_lowercase();
 
-        let suggest = json!({
-            "name-suggest" : {
-                "prefix" : name,
-                "completion" : {
-                    "field" : "suggest",
-                    "size" : count,
-                    "skip_duplicates": true,
-                    "fuzzy": true,
-                    "contexts": {
-                        "status": "published"
-                    }
-                }
+        let completion = json!({
+            "size" : count,
+            "skip_duplicates": true,
+            "fuzzy": true,
+            "contexts": {
+                "status": "published"
             }
         });
+        let suggest = name_suggesters(&name, completion, language);
 
         let mut query_map = serde_json::Map::<String, serde_json::Value>::new();
         query_map.insert("_source".to_string(), serde_json::Value::Bool(false));
@@ -174,7 +330,8 @@ impl StoresElastic for StoresElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("auto_complete query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -218,7 +375,8 @@ impl StoresElastic for StoresElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("search_count query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<CountResponse>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -267,7 +425,8 @@ impl StoresElastic for StoresElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("aggregate_countries query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -344,7 +503,8 @@ impl StoresElastic for StoresElasticImpl {
         headers.set(ContentType::json());
         headers.set(ContentLength(query.len() as u64));
         trace!("aggregate_categories query = '{}'", query);
-        Box::new(
+        inject_future(
+            FaultLayer::Elastic,
             self.client_handle
                 .request::<SearchResponse<ElasticStore>>(Method::Post, url, Some(query), Some(headers))
                 .inspect(|ref res| log_elastic_resp(res))
@@ -370,6 +530,17 @@ impl StoresElastic for StoresElasticImpl {
     }
 }
 
+fn verified_store_boost_query() -> serde_json::Value {
+    json!({
+        "term": {
+            "verification_status": {
+                "value": "verified",
+                "boost": VERIFIED_STORE_BOOST
+            }
+        }
+    })
+}
+
 fn fuzzy_search_by_name_query(name: &str) -> serde_json::Value {
     json!({
         "nested" : {
@@ -0,0 +1,266 @@
+//! Synonyms repo, keeps synonym token filter of the products index in sync with search_synonyms table.
+//! Analysis settings can be changed only on a closed index, so instead of touching the live index
+//! a new index is created with the current mapping and the new rules, filled with `_reindex`
+//! and then takes over the `products` alias. Searches keep being served by the old index meanwhile,
+//! documents written to the old index during the reindex reach the new one with their next update.
+//! Before the switch popular queries are replayed against both indices to compare their result counts.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use errors::Error;
+use failure::Fail;
+use futures::future;
+use futures::Future;
+use hyper::header::{ContentLength, ContentType, Headers};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+
+use super::{log_elastic_resp, ProductsElasticImpl};
+use chaos::{inject_future, FaultLayer};
+use config::SearchBoosts;
+use models::{ElasticIndex, QueryReplay, SearchProductsByName};
+use repos::types::RepoFuture;
+
+/// Token filter with synonym rules, used only by the search analyzer
+pub const PRODUCT_SYNONYMS_FILTER: &str = "product_synonyms";
+
+/// Search-time analyzer of product names, expands query terms with synonyms
+pub const PRODUCT_SEARCH_ANALYZER: &str = "product_search";
+
+/// Fields of the products mapping searched with `PRODUCT_SEARCH_ANALYZER`
+const SYNONYM_FIELDS: &[&str] = &["/properties/name/properties/text"];
+
+/// Synonyms repository, responsible for analysis settings of products index
+#[derive(Clone)]
+pub struct SynonymsElasticImpl {
+    pub client_handle: ClientHandle,
+    pub elastic_address: String,
+}
+
+pub trait SynonymsElastic {
+    /// Creates products index with the synonym rules and fills it from the current one, returns names of the current and the new index
+    fn build_products_index(&self, rules: Vec<String>) -> RepoFuture<(String, String)>;
+
+    /// Counts products found by search by name with the queries in both indices
+    fn replay_queries(&self, old_index: String, new_index: String, queries: Vec<String>) -> RepoFuture<Vec<QueryReplay>>;
+
+    /// Moves `products` alias to the new index and removes the old one
+    fn switch_products_index(&self, old_index: String, new_index: String) -> RepoFuture<()>;
+}
+
+impl SynonymsElasticImpl {
+    pub fn new(client_handle: ClientHandle, elastic_address: String) -> Self {
+        Self {
+            client_handle,
+            elastic_address,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str, body: Option<String>) -> RepoFuture<serde_json::Value> {
+        let url = format!("http://{}/{}", self.elastic_address, path);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(body.as_ref().map(|body| body.len()).unwrap_or(0) as u64));
+        let path = path.to_string();
+        inject_future(
+            FaultLayer::Elastic,
+            self.client_handle
+                .request::<serde_json::Value>(method, url, body, Some(headers))
+                .inspect(|ref res| log_elastic_resp(res))
+                .map_err(move |e| {
+                    e.context(format!("Request {} of products index error occurred.", path))
+                        .context(Error::ElasticSearch)
+                        .into()
+                }),
+        )
+    }
+
+    /// Name of the index behind `products` alias, or the index itself when it was created without alias
+    fn current_index(&self) -> RepoFuture<String> {
+        Box::new(
+            self.request(Method::Get, &format!("{}/_alias", ElasticIndex::Product), None)
+                .and_then(|res| {
+                    res.as_object()
+                        .and_then(|indices| indices.keys().next().cloned())
+                        .ok_or_else(|| format_err!("Products index not found").context(Error::ElasticSearch).into())
+                }),
+        )
+    }
+
+    /// Number of products found by search by name with the query in the index
+    fn query_count(&self, index: &str, query: &str) -> RepoFuture<u64> {
+        let search = SearchProductsByName {
+            name: query.to_string(),
+            options: None,
+        };
+        // boosts change only scores of the products, not their number
+        let query_map = ProductsElasticImpl::create_search_by_name_query(&search, &SearchBoosts::default());
+        let body = json!({
+            "size": 0,
+            "query": { "bool": query_map }
+        });
+        Box::new(
+            self.request(Method::Post, &format!("{}/_search", index), Some(body.to_string()))
+                .map(|res| res["hits"]["total"].as_u64().unwrap_or_default()),
+        )
+    }
+}
+
+impl SynonymsElastic for SynonymsElasticImpl {
+    fn build_products_index(&self, rules: Vec<String>) -> RepoFuture<(String, String)> {
+        let new_index = format!(
+            "{}_{}",
+            ElasticIndex::Product,
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+        );
+        info!("Rebuild products index into {} with {} synonym rules", new_index, rules.len());
+
+        let elastic = self.clone();
+        Box::new(self.current_index().and_then(move |old_index| {
+            let settings = elastic.request(Method::Get, &format!("{}/_settings", old_index), None);
+            let mapping = elastic.request(Method::Get, &format!("{}/_mapping", old_index), None);
+            settings
+                .join(mapping)
+                .and_then({
+                    let elastic = elastic.clone();
+                    let old_index = old_index.clone();
+                    let new_index = new_index.clone();
+                    move |(settings, mapping)| {
+                        let body = synonyms_index_body(&settings[&old_index]["settings"], &mapping[&old_index]["mappings"], rules);
+                        trace!("update_product_synonyms index = '{}'", body);
+                        elastic.request(Method::Put, &new_index, Some(body.to_string()))
+                    }
+                })
+                .and_then({
+                    let old_index = old_index.clone();
+                    let new_index = new_index.clone();
+                    move |_| {
+                        let body = json!({
+                            "source": { "index": old_index },
+                            "dest": { "index": new_index }
+                        });
+                        elastic.request(Method::Post, "_reindex?wait_for_completion=true", Some(body.to_string()))
+                    }
+                })
+                .map(move |_| (old_index, new_index))
+        }))
+    }
+
+    fn replay_queries(&self, old_index: String, new_index: String, queries: Vec<String>) -> RepoFuture<Vec<QueryReplay>> {
+        info!("Replay {} queries against {} and {}", queries.len(), old_index, new_index);
+
+        let replays = queries
+            .into_iter()
+            .map(|query| {
+                let old_count = self.query_count(&old_index, &query);
+                let new_count = self.query_count(&new_index, &query);
+                old_count
+                    .join(new_count)
+                    .map(move |(old_count, new_count)| QueryReplay::new(query, old_count, new_count))
+            })
+            .collect::<Vec<_>>();
+        Box::new(future::join_all(replays))
+    }
+
+    fn switch_products_index(&self, old_index: String, new_index: String) -> RepoFuture<()> {
+        // alias moves and the old index goes away in one atomic step
+        let body = json!({
+            "actions": [
+                { "add": { "index": new_index, "alias": ElasticIndex::Product.to_string() } },
+                { "remove_index": { "index": old_index } }
+            ]
+        });
+        Box::new(self.request(Method::Post, "_aliases", Some(body.to_string())).and_then(|res| {
+            if res["acknowledged"].as_bool().unwrap_or(false) {
+                Ok(())
+            } else {
+                Err(format_err!("Switch of products alias was not acknowledged")
+                    .context(Error::ElasticSearch)
+                    .into())
+            }
+        }))
+    }
+}
+
+/// Body creating products index with the settings and mapping of the current one and the new synonym rules
+pub fn synonyms_index_body(settings: &serde_json::Value, mapping: &serde_json::Value, rules: Vec<String>) -> serde_json::Value {
+    let mut analysis = settings["index"]["analysis"].clone();
+    if !analysis.is_object() {
+        analysis = json!({});
+    }
+    analysis["filter"][PRODUCT_SYNONYMS_FILTER] = json!({
+        "type": "synonym_graph",
+        "synonyms": rules
+    });
+    analysis["analyzer"][PRODUCT_SEARCH_ANALYZER] = json!({
+        "type": "custom",
+        "tokenizer": "standard",
+        "filter": ["lowercase", PRODUCT_SYNONYMS_FILTER]
+    });
+
+    let mut mapping = mapping.clone();
+    if mapping.get("properties").is_some() {
+        set_search_analyzer(&mut mapping);
+    } else if let Some(types) = mapping.as_object_mut() {
+        // indices of elastic 6 keep properties under the mapping type
+        for type_mapping in types.values_mut() {
+            set_search_analyzer(type_mapping);
+        }
+    }
+
+    json!({
+        "settings": {
+            "index": {
+                "number_of_shards": settings["index"]["number_of_shards"].clone(),
+                "number_of_replicas": settings["index"]["number_of_replicas"].clone(),
+                "analysis": analysis
+            }
+        },
+        "mappings": mapping
+    })
+}
+
+fn set_search_analyzer(mapping: &mut serde_json::Value) {
+    for field in SYNONYM_FIELDS {
+        if let Some(field_mapping) = mapping.pointer_mut(field) {
+            field_mapping["search_analyzer"] = json!(PRODUCT_SEARCH_ANALYZER);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synonyms_index_body() {
+        let settings = json!({
+            "index": {
+                "number_of_shards": "5",
+                "number_of_replicas": "1",
+                "uuid": "old-uuid",
+                "analysis": {"analyzer": {"autocomplete": {"tokenizer": "standard"}}}
+            }
+        });
+        let mapping = json!({
+            "properties": {
+                "name": {"type": "nested", "properties": {"lang": {"type": "keyword"}, "text": {"type": "text"}}}
+            }
+        });
+
+        let body = synonyms_index_body(&settings, &mapping, vec!["sneakers, trainers".to_string()]);
+
+        assert_eq!(body["settings"]["index"]["uuid"], json!(null));
+        assert_eq!(body["settings"]["index"]["number_of_shards"], json!("5"));
+        assert_eq!(body["settings"]["index"]["analysis"]["analyzer"]["autocomplete"]["tokenizer"], json!("standard"));
+        assert_eq!(
+            body["settings"]["index"]["analysis"]["filter"][PRODUCT_SYNONYMS_FILTER]["synonyms"],
+            json!(["sneakers, trainers"])
+        );
+        assert_eq!(
+            body["mappings"]["properties"]["name"]["properties"]["text"]["search_analyzer"],
+            json!(PRODUCT_SEARCH_ANALYZER)
+        );
+        assert_eq!(body["mappings"]["properties"]["name"]["properties"]["lang"]["search_analyzer"], json!(null));
+    }
+}
@@ -14,8 +14,13 @@ pub enum Error {
     Parse,
     #[fail(display = "Validation error")]
     Validate(ValidationErrors),
+    /// Validation errors serialized with messages in the language of the request
+    #[fail(display = "Validation error")]
+    LocalizedValidate(serde_json::Value),
     #[fail(display = "Server is refusing to fullfil the request")]
     Forbidden,
+    #[fail(display = "Too many requests")]
+    TooManyRequests,
     #[fail(display = "R2D2 connection error")]
     Connection,
     #[fail(display = "Elastic search error")]
@@ -28,10 +33,11 @@ impl Codeable for Error {
     fn code(&self) -> StatusCode {
         match *self {
             Error::NotFound => StatusCode::NotFound,
-            Error::Validate(_) => StatusCode::BadRequest,
+            Error::Validate(_) | Error::LocalizedValidate(_) => StatusCode::BadRequest,
             Error::Parse => StatusCode::UnprocessableEntity,
             Error::Connection | Error::ElasticSearch | Error::Internal => StatusCode::InternalServerError,
             Error::Forbidden => StatusCode::Forbidden,
+            Error::TooManyRequests => StatusCode::TooManyRequests,
         }
     }
 }
@@ -40,6 +46,7 @@ impl PayloadCarrier for Error {
     fn payload(&self) -> Option<serde_json::Value> {
         match *self {
             Error::Validate(ref e) => serde_json::to_value(e.clone()).ok(),
+            Error::LocalizedValidate(ref payload) => Some(payload.clone()),
             _ => None,
         }
     }
@@ -15,6 +15,7 @@ extern crate config as config_crate;
 extern crate diesel;
 #[macro_use]
 extern crate failure;
+extern crate fallible_iterator;
 extern crate futures;
 extern crate futures_cpupool;
 extern crate hyper;
@@ -26,8 +27,10 @@ extern crate lazy_static;
 #[macro_use]
 extern crate log;
 extern crate num_traits;
+extern crate postgres;
 extern crate r2d2;
 extern crate r2d2_redis;
+extern crate rand;
 extern crate regex;
 extern crate reqwest;
 extern crate rust_decimal;
@@ -59,17 +62,21 @@ extern crate uuid;
 
 #[macro_use]
 pub mod macros;
+pub mod chaos;
 pub mod config;
 pub mod controller;
+pub mod degradation;
 pub mod elastic;
 pub mod errors;
 pub mod loaders;
 pub mod models;
+pub mod notifiers;
 pub mod repos;
 #[rustfmt::skip]
 pub mod schema;
 pub mod sentry_integration;
 pub mod services;
+pub mod validation_messages;
 
 use std::process;
 use std::sync::Arc;
@@ -86,14 +93,28 @@ use stq_cache::cache::{redis::RedisCache, Cache, NullCache, TypedCache};
 use stq_http::controller::Application;
 use tokio_core::reactor::Core;
 
-use config::{Config, ATTRIBUTE_CACHE_NAMESPACE, CATEGORY_CACHE_NAMESPACE, ROLES_CACHE_NAMESPACE};
+use config::{
+    Config, ATTRIBUTE_CACHE_NAMESPACE, CATALOG_HEALTH_CACHE_NAMESPACE, CATEGORY_CACHE_NAMESPACE, DEFAULT_PRODUCT_CACHE_CAPACITY,
+    ORDERS_REFERENCE_CHECK, PRODUCT_CACHE_NAMESPACE, ROLES_CACHE_NAMESPACE, STORE_FEED_CACHE_NAMESPACE, STORE_PROFILE_CACHE_NAMESPACE,
+};
 use controller::context::StaticContext;
+use controller::embed::EmbedHeaders;
+use controller::freshness::{IndexFreshnessHeader, IndexFreshnessState};
+use controller::throttling::{SearchThrottleState, SearchThrottling};
+use controller::xml::XmlContentType;
 use errors::Error;
-use loaders::ticker;
+use loaders::{
+    cache_invalidation, elastic_health, index_freshness, interrupted_jobs, retention, stock_reservations, ticker, wizard_cleanup,
+};
 use repos::acl::RolesCacheImpl;
 use repos::attributes::AttributeCacheImpl;
+use repos::catalog_health::CatalogHealthCacheImpl;
 use repos::categories::CategoryCacheImpl;
+use repos::memory_cache::MemoryCache;
+use repos::products::ProductCacheImpl;
 use repos::repo_factory::ReposFactoryImpl;
+use repos::store_feed::StoreFeedCacheImpl;
+use repos::store_profile::StoreProfileCacheImpl;
 
 /// Starts new web service from provided `Config`
 pub fn start_server<F: FnOnce() + 'static>(config: Config, port: &Option<String>, callback: F) {
@@ -109,11 +130,19 @@ pub fn start_server<F: FnOnce() + 'static>(config: Config, port: &Option<String>
 
     // Prepare database pool
     let database_url: String = config.server.database.parse().expect("Database URL must be set in configuration");
-    let db_manager = ConnectionManager::<PgConnection>::new(database_url);
+    let db_manager = ConnectionManager::<PgConnection>::new(database_url.clone());
     let db_pool = r2d2::Pool::builder()
         .build(db_manager)
         .expect("Failed to create DB connection pool");
 
+    // Reads go to the primary database when there is no replica
+    let db_replica_pool = match config.server.database_replica {
+        Some(ref replica_url) => r2d2::Pool::builder()
+            .build(ConnectionManager::<PgConnection>::new(replica_url.clone()))
+            .expect("Failed to create DB replica connection pool"),
+        None => db_pool.clone(),
+    };
+
     let thread_count = config.server.thread_count;
 
     // Prepare CPU pool
@@ -125,9 +154,21 @@ pub fn start_server<F: FnOnce() + 'static>(config: Config, port: &Option<String>
         format!("{}:{}", config.server.host, port).parse().expect("Could not parse address")
     };
 
+    if let Some(ref shadow_reads) = config.shadow_reads {
+        repos::shadow_read::enable(shadow_reads.queries.clone());
+    }
+
     // Prepare caches
-    let (roles_cache, category_cache, attribute_cache) = match &config.server.redis {
-        Some(redis_url) => {
+    let (
+        roles_cache,
+        category_cache,
+        attribute_cache,
+        catalog_health_cache,
+        store_profile_cache,
+        store_feed_cache,
+        product_cache,
+    ) = match (&config.server.redis, &config.cache_invalidation) {
+        (Some(redis_url), _) => {
             // Prepare Redis pool
             let redis_url: String = redis_url.parse().expect("Redis URL must be set in configuration");
             let redis_manager = RedisConnectionManager::new(redis_url.as_ref()).expect("Failed to create Redis connection manager");
@@ -152,19 +193,154 @@ pub fn start_server<F: FnOnce() + 'static>(config: Config, port: &Option<String>
             )) as Box<dyn Cache<_, Error = _> + Send + Sync>;
             let attribute_cache = AttributeCacheImpl::new(attribute_cache_backend);
 
-            (roles_cache, category_cache, attribute_cache)
+            let catalog_health_cache_backend = Box::new(TypedCache::new(
+                RedisCache::new(redis_pool.clone(), CATALOG_HEALTH_CACHE_NAMESPACE.to_string()).with_ttl(ttl),
+            )) as Box<dyn Cache<_, Error = _> + Send + Sync>;
+            let catalog_health_cache = CatalogHealthCacheImpl::new(catalog_health_cache_backend);
+
+            let store_profile_cache_backend = Box::new(TypedCache::new(
+                RedisCache::new(redis_pool.clone(), STORE_PROFILE_CACHE_NAMESPACE.to_string()).with_ttl(ttl),
+            )) as Box<dyn Cache<_, Error = _> + Send + Sync>;
+            let store_profile_cache = StoreProfileCacheImpl::new(store_profile_cache_backend);
+
+            let store_feed_cache_backend = Box::new(TypedCache::new(
+                RedisCache::new(redis_pool.clone(), STORE_FEED_CACHE_NAMESPACE.to_string()).with_ttl(ttl),
+            )) as Box<dyn Cache<_, Error = _> + Send + Sync>;
+            let store_feed_cache = StoreFeedCacheImpl::new(store_feed_cache_backend);
+
+            let product_cache_backend = Box::new(TypedCache::new(
+                RedisCache::new(redis_pool.clone(), PRODUCT_CACHE_NAMESPACE.to_string()).with_ttl(ttl),
+            )) as Box<dyn Cache<_, Error = _> + Send + Sync>;
+            let product_cache = ProductCacheImpl::new(product_cache_backend);
+
+            (
+                roles_cache,
+                category_cache,
+                attribute_cache,
+                catalog_health_cache,
+                store_profile_cache,
+                store_feed_cache,
+                product_cache,
+            )
+        }
+        (None, Some(cache_invalidation)) => {
+            let roles_cache = MemoryCache::new();
+            let category_cache = MemoryCache::new();
+            let attribute_cache = MemoryCache::new();
+            let product_cache =
+                MemoryCache::with_capacity(cache_invalidation.product_cache_capacity.unwrap_or(DEFAULT_PRODUCT_CACHE_CAPACITY));
+
+            // Every instance drops entries changed by the others
+            cache_invalidation::start(cache_invalidation::CacheInvalidationContext {
+                database_url,
+                reconnect_interval: Duration::from_secs(cache_invalidation.reconnect_interval_s),
+                roles_cache: Arc::new(roles_cache.clone()),
+                category_cache: Arc::new(category_cache.clone()),
+                attribute_cache: Arc::new(attribute_cache.clone()),
+                product_cache: Arc::new(product_cache.clone()),
+            });
+
+            (
+                RolesCacheImpl::new(Box::new(roles_cache) as Box<_>),
+                CategoryCacheImpl::new(Box::new(category_cache) as Box<_>),
+                AttributeCacheImpl::new(Box::new(attribute_cache) as Box<_>),
+                CatalogHealthCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+                StoreProfileCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+                StoreFeedCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+                ProductCacheImpl::new(Box::new(product_cache) as Box<_>),
+            )
         }
-        None => (
+        (None, None) => (
             RolesCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
             CategoryCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
             AttributeCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+            CatalogHealthCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+            StoreProfileCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+            StoreFeedCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
+            ProductCacheImpl::new(Box::new(NullCache::new()) as Box<_>),
         ),
     };
 
     // Repo factory
-    let repo_factory = ReposFactoryImpl::new(roles_cache, category_cache, attribute_cache);
+    let repo_factory = ReposFactoryImpl::new(
+        roles_cache,
+        category_cache,
+        attribute_cache,
+        catalog_health_cache,
+        store_profile_cache,
+        store_feed_cache,
+        product_cache,
+    );
+
+    // Search throttling state is shared by all connections
+    let search_throttle = config.search_throttle.clone().map(|c| Arc::new(SearchThrottleState::new(c)));
+
+    let config = Arc::new(config);
+    let context = StaticContext::new(
+        db_pool.clone(),
+        db_replica_pool,
+        cpu_pool.clone(),
+        client_handle.clone(),
+        config.clone(),
+        repo_factory,
+    );
 
-    let context = StaticContext::new(db_pool, cpu_pool, client_handle, Arc::new(config), repo_factory);
+    // Elastic nodes are checked in background, unhealthy ones get no requests for a while
+    if let Some(ref elastic_config) = config.elastic {
+        let ctx = elastic_health::ElasticHealthContext {
+            client_handle: client_handle.clone(),
+            elastic_pool: context.elastic_pool.clone(),
+            interval: Duration::from_secs(elastic_config.health_check_interval_s),
+        };
+        handle.spawn(elastic_health::run(ctx, &handle));
+    }
+
+    // Index freshness is checked in background and sent with search responses
+    let index_freshness = config.index_freshness.clone().map(|index_freshness_config| {
+        let state = Arc::new(IndexFreshnessState::default());
+        let ctx = index_freshness::IndexFreshnessContext {
+            db_pool: db_pool.clone(),
+            thread_pool: cpu_pool.clone(),
+            client_handle: client_handle.clone(),
+            elastic_pool: context.elastic_pool.clone(),
+            interval: Duration::from_secs(index_freshness_config.interval_s),
+            state: state.clone(),
+        };
+        handle.spawn(index_freshness::run(ctx, &handle));
+        state
+    });
+
+    // Abandoned store wizards are reminded about and archived in background
+    if let Some(wizard_cleanup_config) = config.wizard_cleanup.clone() {
+        let ctx = wizard_cleanup::WizardCleanupContext {
+            db_pool: db_pool.clone(),
+            thread_pool: cpu_pool.clone(),
+            client_handle: client_handle.clone(),
+            config: config.clone(),
+            wizard_cleanup: wizard_cleanup_config,
+        };
+        handle.spawn(wizard_cleanup::run(ctx, &handle));
+    }
+
+    // Expired stock reservations of abandoned carts are removed in background
+    let stock_reservations_ctx = stock_reservations::StockReservationsContext {
+        db_pool: db_pool.clone(),
+        thread_pool: cpu_pool.clone(),
+        interval: Duration::from_secs(config.stock_reservations().interval_s),
+    };
+    handle.spawn(stock_reservations::run(stock_reservations_ctx, &handle));
+
+    // Jobs interrupted by a restart are failed, otherwise they would stay running forever
+    let jobs_config = config.jobs();
+    let interrupted_jobs_ctx = interrupted_jobs::InterruptedJobsContext {
+        db_pool: db_pool.clone(),
+        thread_pool: cpu_pool.clone(),
+        interrupted_after: Duration::from_secs(jobs_config.interrupted_after_s),
+        interval: Duration::from_secs(jobs_config.interval_s),
+    };
+    handle.spawn(interrupted_jobs::run(interrupted_jobs_ctx, &handle));
+
+    let handle_throttle = handle.clone();
 
     let serve = Http::new()
         .serve_addr_handle(&address, &handle, move || {
@@ -172,7 +348,11 @@ pub fn start_server<F: FnOnce() + 'static>(config: Config, port: &Option<String>
             let controller = controller::ControllerImpl::new(context.clone());
             let app = Application::<Error>::new(controller);
 
-            Ok(app)
+            Ok(SearchThrottling::new(
+                IndexFreshnessHeader::new(XmlContentType::new(EmbedHeaders::new(app)), index_freshness.clone()),
+                (*handle_throttle).clone(),
+                search_throttle.clone(),
+            ))
         })
         .unwrap_or_else(|why| {
             error!("Http Server Initialization Error: {}", why);
@@ -255,3 +435,29 @@ pub fn start_ticker(config: Config) -> impl Future<Item = (), Error = FailureErr
 
     ticker::run(ctx)
 }
+
+pub fn start_retention(config: Config) -> impl Future<Item = (), Error = FailureError> {
+    let Config { server, client, retention, .. } = config;
+    let retention = retention.expect("Retention config not found");
+    let deletion_enabled = retention.deletion_enabled();
+    if !retention.dry_run && !deletion_enabled {
+        warn!(
+            "Retention job runs dry, deletion is disabled until \"{}\" reference check is configured",
+            ORDERS_REFERENCE_CHECK
+        );
+    }
+
+    // Prepare database pool
+    let database_url = server.database.parse::<String>().expect("Failed to parse database URL");
+    let db_manager = ConnectionManager::<PgConnection>::new(database_url);
+    let db_pool = r2d2::Pool::builder().build(db_manager).expect("Failed to create connection pool");
+
+    let ctx = retention::RetentionContext {
+        db_pool,
+        interval: Duration::from_secs(retention.interval_s),
+        thread_pool: CpuPool::new(retention.thread_count),
+        run: retention::RetentionRun::new(&retention, &client).expect("Failed to create HTTP client"),
+    };
+
+    retention::run(ctx)
+}
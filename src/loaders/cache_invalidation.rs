@@ -0,0 +1,95 @@
+//! Cache invalidation listener, drops entries of in-process caches changed by any instance.
+//! Database triggers notify `cache_invalidation` channel with `<table>:<cache key>` payload on every change
+//! of categories, attributes, products and roles. Notifications sent while the listener reconnects are lost,
+//! so all caches are cleared every time it (re)connects.
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use failure::Error as FailureError;
+use failure::Fail;
+use fallible_iterator::FallibleIterator;
+use postgres::{Connection, TlsMode};
+use sentry::integrations::failure::capture_error;
+
+use repos::memory_cache::InvalidateCache;
+
+pub const CACHE_INVALIDATION_CHANNEL: &'static str = "cache_invalidation";
+
+#[derive(Clone)]
+pub struct CacheInvalidationContext {
+    pub database_url: String,
+    pub reconnect_interval: Duration,
+    pub roles_cache: Arc<InvalidateCache>,
+    pub category_cache: Arc<InvalidateCache>,
+    pub attribute_cache: Arc<InvalidateCache>,
+    pub product_cache: Arc<InvalidateCache>,
+}
+
+/// Starts the listener on its own thread, postgres notifications are read with blocking calls
+pub fn start(ctx: CacheInvalidationContext) {
+    let spawned = thread::Builder::new()
+        .name("cache-invalidation".to_string())
+        .spawn(move || run(&ctx));
+    if let Err(err) = spawned {
+        error!("Cache invalidation listener failed to start: {}", err);
+    }
+}
+
+fn run(ctx: &CacheInvalidationContext) {
+    loop {
+        if let Err(err) = listen(ctx) {
+            let err = FailureError::from(err.context("Cache invalidation listener lost database connection"));
+            error!("{:?}", &err);
+            capture_error(&err);
+        }
+
+        info!("Cache invalidation listener reconnects in {:?}", ctx.reconnect_interval);
+        thread::sleep(ctx.reconnect_interval);
+    }
+}
+
+/// Listens until the connection breaks
+fn listen(ctx: &CacheInvalidationContext) -> Result<(), FailureError> {
+    let conn = Connection::connect(ctx.database_url.as_str(), TlsMode::None)?;
+    conn.execute(&format!("LISTEN {}", CACHE_INVALIDATION_CHANNEL), &[])?;
+    info!("Cache invalidation listener is connected");
+    clear_all(ctx);
+
+    let notifications = conn.notifications();
+    let mut notifications = notifications.blocking_iter();
+    while let Some(notification) = notifications.next()? {
+        invalidate(ctx, &notification.payload);
+    }
+
+    Ok(())
+}
+
+fn invalidate(ctx: &CacheInvalidationContext, payload: &str) {
+    debug!("Cache invalidation notification '{}'", payload);
+
+    let mut parts = payload.splitn(2, ':');
+    let (table, key) = match (parts.next(), parts.next()) {
+        (Some(table), Some(key)) => (table, key),
+        _ => {
+            warn!("Malformed cache invalidation notification '{}', all caches are cleared", payload);
+            return clear_all(ctx);
+        }
+    };
+
+    match table {
+        // Category tree is cached as a whole
+        "categories" | "cat_attr_values" => ctx.category_cache.clear(),
+        "attributes" | "attribute_values" => ctx.attribute_cache.invalidate(key),
+        "products" => ctx.product_cache.invalidate(key),
+        "user_roles" => ctx.roles_cache.invalidate(key),
+        _ => warn!("Cache invalidation notification of unknown table '{}'", table),
+    }
+}
+
+fn clear_all(ctx: &CacheInvalidationContext) {
+    ctx.roles_cache.clear();
+    ctx.category_cache.clear();
+    ctx.attribute_cache.clear();
+    ctx.product_cache.clear();
+}
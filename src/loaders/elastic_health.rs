@@ -0,0 +1,49 @@
+//! Elastic health check job, evicts nodes that don't answer from the pool and returns them once they do
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{future, Future, Stream};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+use tokio_core::reactor::{Handle, Interval};
+
+use elastic::ElasticPool;
+
+#[derive(Clone)]
+pub struct ElasticHealthContext {
+    pub client_handle: ClientHandle,
+    pub elastic_pool: Arc<ElasticPool>,
+    pub interval: Duration,
+}
+
+pub fn run(ctx: ElasticHealthContext, handle: &Handle) -> impl Future<Item = (), Error = ()> {
+    future::result(Interval::new(ctx.interval, handle))
+        .map_err(|err| error!("Elastic health check job failed to start: {}", err))
+        .and_then(move |interval| {
+            interval
+                .map_err(|err| error!("Elastic health check job stopped: {}", err))
+                .for_each(move |_| {
+                    let checks = ctx.elastic_pool.addresses().into_iter().map(|address| check_node(&ctx, address));
+                    future::join_all(checks).map(|_| ())
+                })
+        })
+}
+
+/// Node is healthy when it answers cluster health request, status of the cluster doesn't matter here
+fn check_node(ctx: &ElasticHealthContext, address: String) -> impl Future<Item = (), Error = ()> {
+    let elastic_pool = ctx.elastic_pool.clone();
+    let url = format!("http://{}/_cluster/health?local=true", address);
+    ctx.client_handle
+        .request::<serde_json::Value>(Method::Get, url, None, None)
+        .then(move |res| {
+            match res {
+                Ok(_) => elastic_pool.restore(&address),
+                Err(err) => {
+                    error!("Elastic node {} health check failed: {}", address, err);
+                    elastic_pool.evict(&address);
+                }
+            };
+            future::ok(())
+        })
+}
@@ -0,0 +1,107 @@
+//! Index freshness job, periodically checks whether elastic indices caught up with the database.
+//! Update numbers are read from the database first, so once indices have them,
+//! every change made before the check started is searchable.
+//! Documents written with older schema versions are counted as well, they are reported until reindexed.
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use diesel::{pg::PgConnection, r2d2::ConnectionManager};
+use failure::Error as FailureError;
+use futures::{future, Future, Stream};
+use futures_cpupool::CpuPool;
+use r2d2::Pool;
+use sentry::integrations::failure::capture_error;
+use stq_http::client::ClientHandle;
+use tokio_core::reactor::{Handle, Interval};
+
+use controller::freshness::{is_index_synced, IndexFreshnessState};
+use elastic::{ElasticPool, IndexStateElastic, IndexStateElasticImpl};
+use models::{ElasticIndex, SyncEntityType, SyncState, ELASTIC_SCHEMA_VERSION};
+use repos::acl::legacy_acl::SystemACL;
+use repos::sync_state::{SyncStateRepo, SyncStateRepoImpl};
+
+#[derive(Clone)]
+pub struct IndexFreshnessContext {
+    pub db_pool: Pool<ConnectionManager<PgConnection>>,
+    pub thread_pool: CpuPool,
+    pub client_handle: ClientHandle,
+    pub elastic_pool: Arc<ElasticPool>,
+    pub interval: Duration,
+    pub state: Arc<IndexFreshnessState>,
+}
+
+pub fn run(ctx: IndexFreshnessContext, handle: &Handle) -> impl Future<Item = (), Error = ()> {
+    future::result(Interval::new(ctx.interval, handle))
+        .map_err(FailureError::from)
+        .and_then(move |interval| {
+            interval.map_err(FailureError::from).for_each(move |_| {
+                let state = ctx.state.clone();
+                check_indices(&ctx).then(move |res| {
+                    match res {
+                        Ok(Some(synced_at)) => state.mark_synced(synced_at),
+                        Ok(None) => debug!("Elastic indices are behind the database"),
+                        Err(err) => {
+                            let err = FailureError::from(err.context("An error occurred while checking index freshness"));
+                            error!("{:?}", &err);
+                            capture_error(&err);
+                        }
+                    };
+
+                    future::ok::<_, FailureError>(())
+                })
+            })
+        })
+        .map_err(|err| error!("Index freshness job stopped: {:?}", err))
+}
+
+/// Returns start time of the check if stores and products indices have all updates of the database
+fn check_indices(ctx: &IndexFreshnessContext) -> impl Future<Item = Option<SystemTime>, Error = FailureError> {
+    let started_at = SystemTime::now();
+    let db_pool = ctx.db_pool.clone();
+    let elastic = IndexStateElasticImpl::new(ctx.client_handle.clone(), ctx.elastic_pool.address());
+
+    ctx.thread_pool
+        .spawn_fn(move || {
+            let conn = db_pool.get().map_err(FailureError::from)?;
+            let repo = SyncStateRepoImpl::new(&*conn, Box::new(SystemACL::default()));
+            repo.get_state()
+        })
+        .and_then(move |sync_state| {
+            let outdated_counts = elastic
+                .outdated_count(ElasticIndex::Store)
+                .join(elastic.outdated_count(ElasticIndex::Product));
+            elastic
+                .max_update_no(ElasticIndex::Store)
+                .join(elastic.max_update_no(ElasticIndex::Product))
+                .join(outdated_counts)
+                .map(move |((stores_update_no, products_update_no), (outdated_stores, outdated_products))| {
+                    warn_outdated(ElasticIndex::Store, outdated_stores);
+                    warn_outdated(ElasticIndex::Product, outdated_products);
+
+                    let synced = is_index_synced(max_update_no(&sync_state, SyncEntityType::Store), stores_update_no)
+                        && is_index_synced(max_update_no(&sync_state, SyncEntityType::BaseProduct), products_update_no);
+                    if synced {
+                        Some(started_at)
+                    } else {
+                        None
+                    }
+                })
+        })
+}
+
+fn warn_outdated(index: ElasticIndex, outdated_count: u64) {
+    if outdated_count > 0 {
+        warn!(
+            "{} documents of {} index need reindex to schema version {}",
+            outdated_count, index, ELASTIC_SCHEMA_VERSION
+        );
+    }
+}
+
+fn max_update_no(sync_state: &SyncState, entity_type: SyncEntityType) -> Option<i32> {
+    sync_state
+        .entities
+        .iter()
+        .find(|entity| entity.entity_type == entity_type)
+        .and_then(|entity| entity.max_update_no)
+}
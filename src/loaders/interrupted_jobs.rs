@@ -0,0 +1,69 @@
+//! Interrupted jobs job, fails background jobs whose work was lost with a restarted instance.
+//! Running jobs save progress after every item or batch, so a job not updated for `interrupted_after`
+//! has no instance working on it anymore, while jobs of the other running instances are left alone.
+use std::time::{Duration, SystemTime};
+
+use diesel::{pg::PgConnection, r2d2::ConnectionManager};
+use failure::Error as FailureError;
+use futures::{future, Future, Stream};
+use futures_cpupool::CpuPool;
+use r2d2::Pool;
+use sentry::integrations::failure::capture_error;
+use tokio_core::reactor::{Handle, Interval};
+
+use repos::acl::legacy_acl::SystemACL;
+use repos::jobs::{JobsRepo, JobsRepoImpl};
+
+#[derive(Clone)]
+pub struct InterruptedJobsContext {
+    pub db_pool: Pool<ConnectionManager<PgConnection>>,
+    pub thread_pool: CpuPool,
+    pub interrupted_after: Duration,
+    pub interval: Duration,
+}
+
+/// Fails interrupted jobs right at startup and then every `interval`
+pub fn run(ctx: InterruptedJobsContext, handle: &Handle) -> impl Future<Item = (), Error = ()> {
+    let startup_ctx = ctx.clone();
+    future::result(Interval::new(ctx.interval, handle))
+        .map_err(FailureError::from)
+        .and_then(move |interval| {
+            log_result(fail_interrupted(&startup_ctx)).and_then(move |_| {
+                interval
+                    .map_err(FailureError::from)
+                    .for_each(move |_| log_result(fail_interrupted(&ctx)))
+            })
+        })
+        .map_err(|err| error!("Interrupted jobs job stopped: {:?}", err))
+}
+
+fn log_result<F: Future<Item = usize, Error = FailureError>>(work: F) -> impl Future<Item = (), Error = FailureError> {
+    work.then(|res| {
+        match res {
+            Ok(failed) => {
+                if failed > 0 {
+                    info!("Interrupted jobs job failed {} jobs", failed);
+                }
+            }
+            Err(err) => {
+                let err = FailureError::from(err.context("An error occurred while failing interrupted jobs"));
+                error!("{:?}", &err);
+                capture_error(&err);
+            }
+        };
+
+        future::ok::<_, FailureError>(())
+    })
+}
+
+/// Fails jobs not updated for `interrupted_after`, returns the number of failed jobs
+fn fail_interrupted(ctx: &InterruptedJobsContext) -> impl Future<Item = usize, Error = FailureError> {
+    let db_pool = ctx.db_pool.clone();
+    let idle_since = SystemTime::now() - ctx.interrupted_after;
+
+    ctx.thread_pool.spawn_fn(move || {
+        let conn = db_pool.get().map_err(FailureError::from)?;
+        let repo = JobsRepoImpl::new(&*conn, Box::new(SystemACL::default()));
+        repo.fail_interrupted(idle_since).map(|jobs| jobs.len())
+    })
+}
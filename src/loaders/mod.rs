@@ -1,6 +1,13 @@
+pub mod cache_invalidation;
+pub mod elastic_health;
+pub mod index_freshness;
+pub mod interrupted_jobs;
+pub mod retention;
 pub mod rocket_models;
 mod rocket_retail;
 pub mod services;
+pub mod stock_reservations;
 pub mod ticker;
+pub mod wizard_cleanup;
 
 pub use self::rocket_retail::*;
@@ -0,0 +1,223 @@
+//! Retention job, hard-deletes products and stores deactivated long ago.
+//! Variants go first, base products and stores are deleted once nothing is left under them,
+//! so an entity kept because of a reference also keeps its parents.
+//! In dry run the deletions are made in a transaction which is rolled back, so the report counts them exactly.
+//! Superusers run the job on demand with `POST /admin/retention`, see `services::retention`.
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::{pg::PgConnection, r2d2::ConnectionManager, Connection};
+use failure::Error as FailureError;
+use futures::{future, Future, Stream};
+use futures_cpupool::CpuPool;
+use r2d2::Pool;
+use reqwest;
+use sentry::integrations::failure::capture_error;
+use serde_json;
+use tokio::timer::Interval;
+
+use stq_types::{ProductId, StoreId};
+
+use config::{Client, ReferenceCheck, Retention};
+use models::{split_blocked, ReferenceCheckRequest, ReferenceCheckResponse, RetentionEntityType, RetentionReport};
+use repos::retention::{RetentionRepo, RetentionRepoImpl};
+
+/// Products referenced by coupon redemptions are kept with this reason
+const COUPON_REDEMPTIONS_REFERENCE: &str = "coupon_redemptions";
+
+#[derive(Clone)]
+pub struct RetentionContext {
+    pub db_pool: Pool<ConnectionManager<PgConnection>>,
+    pub interval: Duration,
+    pub thread_pool: CpuPool,
+    pub run: RetentionRun,
+}
+
+/// Settings of one run of the job
+#[derive(Clone)]
+pub struct RetentionRun {
+    pub http_client: reqwest::Client,
+    /// Entities deactivated earlier than this are deleted
+    pub retention_period: Duration,
+    pub batch_size: usize,
+    pub reference_checks: Vec<ReferenceCheck>,
+    /// Deletions are rolled back, only the report is logged
+    pub dry_run: bool,
+}
+
+impl RetentionRun {
+    /// Run is dry unless deletion is enabled, see `Retention::deletion_enabled`
+    pub fn new(retention: &Retention, client: &Client) -> Result<Self, FailureError> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(client.http_timeout_ms))
+            .build()?;
+
+        Ok(Self {
+            http_client,
+            retention_period: Duration::from_secs(retention.deactivated_days * 24 * 60 * 60),
+            batch_size: retention.batch_size,
+            reference_checks: retention.reference_checks.clone(),
+            dry_run: !retention.deletion_enabled(),
+        })
+    }
+}
+
+pub fn run(ctx: RetentionContext) -> impl Future<Item = (), Error = FailureError> {
+    Interval::new(Instant::now(), ctx.interval)
+        .map_err(FailureError::from)
+        .fold(ctx, |ctx, _| {
+            info!("Started removing deactivated entities");
+            let job_ctx = ctx.clone();
+            ctx.thread_pool
+                .spawn_fn(move || {
+                    let conn = job_ctx.db_pool.get().map_err(FailureError::from)?;
+                    remove_deactivated(&*conn, &job_ctx.run)
+                })
+                .then(|res| {
+                    match res {
+                        Ok(report) => log_report(&report),
+                        Err(err) => {
+                            let err = FailureError::from(err.context("An error occurred while removing deactivated entities"));
+                            error!("{:?}", &err);
+                            capture_error(&err);
+                        }
+                    };
+
+                    future::ok::<_, FailureError>(ctx)
+                })
+        })
+        .map(|_| ())
+}
+
+/// Deletes deactivated entities, in dry run the deletions are rolled back
+pub fn remove_deactivated<T>(conn: &T, ctx: &RetentionRun) -> Result<RetentionReport, FailureError>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    let repo = RetentionRepoImpl::new(conn);
+    if !ctx.dry_run {
+        return delete_deactivated(ctx, &repo);
+    }
+
+    let mut report = None;
+    let rolled_back = conn.transaction::<(), FailureError, _>(|| {
+        report = Some(delete_deactivated(ctx, &repo)?);
+        Err(format_err!("Dry run of retention job is rolled back"))
+    });
+    match report {
+        Some(report) => Ok(RetentionReport { dry_run: true, ..report }),
+        None => rolled_back.map(|_| RetentionReport::default()),
+    }
+}
+
+fn delete_deactivated(ctx: &RetentionRun, repo: &RetentionRepo) -> Result<RetentionReport, FailureError> {
+    let deactivated_before = SystemTime::now() - ctx.retention_period;
+    let batch_size = ctx.batch_size.max(1);
+    let mut report = RetentionReport::default();
+
+    for batch in repo.find_deactivated_products(deactivated_before)?.chunks(batch_size) {
+        let mut references = repo
+            .find_redeemed_products(batch.to_vec())?
+            .into_iter()
+            .map(|product_id| (product_id.0, COUPON_REDEMPTIONS_REFERENCE.to_string()))
+            .collect::<HashMap<_, _>>();
+        let ids = batch.iter().map(|product_id| product_id.0).collect::<Vec<_>>();
+        check_references(ctx, RetentionEntityType::Product, &ids, &mut references);
+
+        let (deletable, blocked) = split_blocked(RetentionEntityType::Product, ids, &references);
+        report.blocked.extend(blocked);
+        if !deletable.is_empty() {
+            let deleted = repo.delete_products(deletable.into_iter().map(ProductId).collect())?;
+            report.deleted_products.extend(deleted);
+        }
+    }
+
+    for batch in repo.find_deactivated_base_products(deactivated_before)?.chunks(batch_size) {
+        let deleted = repo.delete_base_products(batch.to_vec())?;
+        report.deleted_base_products.extend(deleted);
+    }
+
+    for batch in repo.find_deactivated_stores(deactivated_before)?.chunks(batch_size) {
+        let mut references = HashMap::new();
+        let ids = batch.iter().map(|store_id| store_id.0).collect::<Vec<_>>();
+        check_references(ctx, RetentionEntityType::Store, &ids, &mut references);
+
+        let (deletable, blocked) = split_blocked(RetentionEntityType::Store, ids, &references);
+        report.blocked.extend(blocked);
+        if !deletable.is_empty() {
+            let deleted = repo.delete_stores(deletable.into_iter().map(StoreId).collect())?;
+            report.deleted_stores.extend(deleted);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Asks every hook which of the ids are still referenced and adds them to `references`.
+/// If a hook fails, all ids it was asked about are kept until the next run.
+fn check_references(ctx: &RetentionRun, entity_type: RetentionEntityType, ids: &[i32], references: &mut HashMap<i32, String>) {
+    for check in &ctx.reference_checks {
+        let unchecked = ids.iter().filter(|id| !references.contains_key(id)).cloned().collect::<Vec<_>>();
+        if unchecked.is_empty() {
+            return;
+        }
+
+        let request = ReferenceCheckRequest {
+            entity_type,
+            ids: unchecked.clone(),
+        };
+        match request_references(&ctx.http_client, &check.url, &request) {
+            Ok(response) => {
+                for id in response.referenced_ids {
+                    references.entry(id).or_insert_with(|| check.name.clone());
+                }
+            }
+            Err(err) => {
+                let err = FailureError::from(err.context(format!("Reference check {} failed", check.name)));
+                error!("{:?}", &err);
+                capture_error(&err);
+                for id in unchecked {
+                    references.insert(id, check.name.clone());
+                }
+            }
+        }
+    }
+}
+
+fn request_references(
+    http_client: &reqwest::Client,
+    url: &str,
+    request: &ReferenceCheckRequest,
+) -> Result<ReferenceCheckResponse, FailureError> {
+    http_client
+        .post(url)
+        .json(request)
+        .send()
+        .and_then(|res| res.error_for_status())
+        .and_then(|mut res| res.json::<ReferenceCheckResponse>())
+        .map_err(FailureError::from)
+}
+
+fn log_report(report: &RetentionReport) {
+    if report.dry_run {
+        info!(
+            "Dry run of removing deactivated entities: {}",
+            serde_json::to_string(&report.impact()).unwrap_or_default()
+        );
+    }
+    info!(
+        "Finished removing deactivated entities: {} products, {} base products, {} stores deleted",
+        report.deleted_products.len(),
+        report.deleted_base_products.len(),
+        report.deleted_stores.len()
+    );
+    if !report.blocked.is_empty() {
+        warn!(
+            "{} deactivated entities are still referenced and were not deleted: {}",
+            report.blocked.len(),
+            serde_json::to_string(&report.blocked).unwrap_or_default()
+        );
+    }
+}
@@ -249,6 +249,7 @@ impl RocketRetailProduct {
             name,
             description: Some(description),
             vendor: Some(store_name),
+            barcode: product.gtin.clone(),
             model: Some(product.vendor_code),
             url: create_product_url(cluster, base.store_id, base.id, product.id),
             price: product.price.into(),
@@ -323,6 +324,7 @@ impl ToXMLElement for RocketRetailProduct {
             dimensions,
             downloadable,
             age,
+            barcode,
             params,
             ..
         } = self;
@@ -359,7 +361,8 @@ impl ToXMLElement for RocketRetailProduct {
             .with_child_option_text("weight", weight)
             .with_child_option_text("dimensions", dimensions)
             .with_child_option_text("downloadable", downloadable)
-            .with_child_option_text("age", age);
+            .with_child_option_text("age", age)
+            .with_child_option_text("barcode", barcode);
 
         for param in params.into_iter() {
             elm = elm.with_child(param.to_xml());
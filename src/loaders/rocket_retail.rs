@@ -23,7 +23,7 @@ use models::{CatalogWithAttributes, Category, Visibility};
 use repos::legacy_acl::SystemACL;
 use repos::{
     categories::{category_cache::CategoryCacheImpl, CategoriesRepo},
-    BaseProductsRepo, BaseProductsRepoImpl, CategoriesRepoImpl, StoresRepo, StoresRepoImpl,
+    BaseProductsReadRepo, BaseProductsRepoImpl, CategoriesRepoImpl, StoresReadRepo, StoresRepoImpl,
 };
 
 use loaders::rocket_models::{RocketRetailCatalog, RocketRetailCategory, RocketRetailProduct, RocketRetailShop, ToXMLDocument};
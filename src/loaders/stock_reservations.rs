@@ -0,0 +1,58 @@
+//! Stock reservations job, removes reservations of abandoned carts once they expire.
+//! Expired reservations are not counted even before the removal, so the job only keeps the table small
+use std::time::{Duration, SystemTime};
+
+use diesel::{pg::PgConnection, r2d2::ConnectionManager};
+use failure::Error as FailureError;
+use futures::{future, Future, Stream};
+use futures_cpupool::CpuPool;
+use r2d2::Pool;
+use sentry::integrations::failure::capture_error;
+use tokio_core::reactor::{Handle, Interval};
+
+use repos::acl::legacy_acl::SystemACL;
+use repos::stock_reservations::{StockReservationsRepo, StockReservationsRepoImpl};
+
+#[derive(Clone)]
+pub struct StockReservationsContext {
+    pub db_pool: Pool<ConnectionManager<PgConnection>>,
+    pub thread_pool: CpuPool,
+    pub interval: Duration,
+}
+
+pub fn run(ctx: StockReservationsContext, handle: &Handle) -> impl Future<Item = (), Error = ()> {
+    future::result(Interval::new(ctx.interval, handle))
+        .map_err(FailureError::from)
+        .and_then(move |interval| {
+            interval.map_err(FailureError::from).for_each(move |_| {
+                delete_expired(&ctx).then(|res| {
+                    match res {
+                        Ok(deleted) => {
+                            if deleted > 0 {
+                                info!("Stock reservations job deleted {} expired reservations", deleted);
+                            }
+                        }
+                        Err(err) => {
+                            let err = FailureError::from(err.context("An error occurred while deleting expired stock reservations"));
+                            error!("{:?}", &err);
+                            capture_error(&err);
+                        }
+                    };
+
+                    future::ok::<_, FailureError>(())
+                })
+            })
+        })
+        .map_err(|err| error!("Stock reservations job stopped: {:?}", err))
+}
+
+/// Deletes expired reservations, returns the number of deleted reservations
+fn delete_expired(ctx: &StockReservationsContext) -> impl Future<Item = usize, Error = FailureError> {
+    let db_pool = ctx.db_pool.clone();
+
+    ctx.thread_pool.spawn_fn(move || {
+        let conn = db_pool.get().map_err(FailureError::from)?;
+        let repo = StockReservationsRepoImpl::new(&*conn, Box::new(SystemACL::default()));
+        repo.delete_expired(SystemTime::now()).map(|reservations| reservations.len())
+    })
+}
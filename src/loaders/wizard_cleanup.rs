@@ -0,0 +1,102 @@
+//! Wizard cleanup job, reminds owners about abandoned store wizards and archives them later.
+//! Wizards are marked as reminded before the events are sent, so a failed delivery is only logged
+//! and never repeated, while several running instances do not remind twice.
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use diesel::{pg::PgConnection, r2d2::ConnectionManager};
+use failure::Error as FailureError;
+use futures::{future, Future, Stream};
+use futures_cpupool::CpuPool;
+use r2d2::Pool;
+use sentry::integrations::failure::capture_error;
+use stq_http::client::ClientHandle;
+use tokio_core::reactor::{Handle, Interval};
+
+use config::{Config, WizardCleanup};
+use notifiers::create_wizard_reminder_sender;
+use repos::acl::legacy_acl::SystemACL;
+use repos::wizard_stores::{WizardStoresRepo, WizardStoresRepoImpl};
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Clone)]
+pub struct WizardCleanupContext {
+    pub db_pool: Pool<ConnectionManager<PgConnection>>,
+    pub thread_pool: CpuPool,
+    pub client_handle: ClientHandle,
+    pub config: Arc<Config>,
+    pub wizard_cleanup: WizardCleanup,
+}
+
+pub fn run(ctx: WizardCleanupContext, handle: &Handle) -> impl Future<Item = (), Error = ()> {
+    future::result(Interval::new(Duration::from_secs(ctx.wizard_cleanup.interval_s), handle))
+        .map_err(FailureError::from)
+        .and_then(move |interval| {
+            interval.map_err(FailureError::from).for_each(move |_| {
+                remind(&ctx).join(archive(&ctx)).then(|res| {
+                    match res {
+                        Ok((reminded, archived)) => {
+                            if reminded > 0 || archived > 0 {
+                                info!("Wizard cleanup reminded {} and archived {} wizard stores", reminded, archived);
+                            }
+                        }
+                        Err(err) => {
+                            let err = FailureError::from(err.context("An error occurred while cleaning up wizard stores"));
+                            error!("{:?}", &err);
+                            capture_error(&err);
+                        }
+                    };
+
+                    future::ok::<_, FailureError>(())
+                })
+            })
+        })
+        .map_err(|err| error!("Wizard cleanup job stopped: {:?}", err))
+}
+
+/// Sends reminders about wizards untouched for `remind_after_days`, returns the number of reminded wizards
+fn remind(ctx: &WizardCleanupContext) -> impl Future<Item = usize, Error = FailureError> {
+    let db_pool = ctx.db_pool.clone();
+    let untouched_since = days_ago(ctx.wizard_cleanup.remind_after_days);
+    let batch_size = ctx.wizard_cleanup.batch_size;
+    let sender = create_wizard_reminder_sender(&ctx.config, ctx.client_handle.clone());
+
+    ctx.thread_pool
+        .spawn_fn(move || {
+            let conn = db_pool.get().map_err(FailureError::from)?;
+            let repo = WizardStoresRepoImpl::new(&*conn, Box::new(SystemACL::default()));
+            repo.mark_reminded(untouched_since, batch_size)
+        })
+        .and_then(move |wizard_stores| {
+            let reminded = wizard_stores.len();
+            let sends = wizard_stores.into_iter().map(|wizard_store| {
+                sender.send_reminder(wizard_store).then(|res| {
+                    if let Err(e) = res {
+                        error!("Sending wizard store reminder failed: {}", e);
+                    }
+                    Ok(())
+                })
+            });
+
+            future::join_all(sends.collect::<Vec<_>>()).map(move |_| reminded)
+        })
+}
+
+/// Archives wizards untouched for `archive_after_days`, returns the number of archived wizards
+fn archive(ctx: &WizardCleanupContext) -> impl Future<Item = usize, Error = FailureError> {
+    let db_pool = ctx.db_pool.clone();
+    let untouched_since = days_ago(ctx.wizard_cleanup.archive_after_days);
+    let batch_size = ctx.wizard_cleanup.batch_size;
+
+    ctx.thread_pool.spawn_fn(move || {
+        let conn = db_pool.get().map_err(FailureError::from)?;
+        let repo = WizardStoresRepoImpl::new(&*conn, Box::new(SystemACL::default()));
+        repo.archive_untouched(untouched_since, batch_size)
+            .map(|wizard_stores| wizard_stores.len())
+    })
+}
+
+fn days_ago(days: u64) -> SystemTime {
+    SystemTime::now() - Duration::from_secs(days * SECONDS_IN_DAY)
+}
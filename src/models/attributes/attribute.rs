@@ -36,6 +36,8 @@ pub struct AttributeMetaField {
     pub values: Option<Vec<String>>,
     pub translated_values: Option<Vec<Vec<Translation>>>,
     pub ui_element: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Validate, PartialEq)]
@@ -1,8 +1,15 @@
+use validator::Validate;
+
+use models::SearchProductsByName;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AttributeFilter {
     pub id: i32,
     pub equal: Option<EqualFilter>,
     pub range: Option<RangeFilter>,
+    /// Equal filter is cut to the facet limit of the attribute, the rest of values are paged separately
+    #[serde(default)]
+    pub more_available: bool,
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug, Hash, PartialEq)]
@@ -35,3 +42,53 @@ impl RangeFilter {
         }
     }
 }
+
+/// Payload for paging through values of a single attribute facet of the search
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct SearchFacetValues {
+    pub search: SearchProductsByName,
+    pub attribute_id: i32,
+    #[validate(range(min = "0"))]
+    pub offset: i32,
+    #[validate(range(min = "1", max = "100"))]
+    pub count: i32,
+}
+
+/// Values of the attribute facet in alphabetical order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FacetValues {
+    pub attribute_id: i32,
+    pub values: Vec<String>,
+    pub more_available: bool,
+}
+
+impl FacetValues {
+    /// Page of sorted values starting from `offset`
+    pub fn new(attribute_id: i32, values: Vec<String>, offset: usize, count: usize) -> Self {
+        let more_available = values.len() > offset + count;
+        let values = values.into_iter().skip(offset).take(count).collect();
+        Self {
+            attribute_id,
+            values,
+            more_available,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_facet_values_page() {
+        let values = vec!["blue".to_string(), "green".to_string(), "red".to_string()];
+
+        let page = FacetValues::new(1, values.clone(), 0, 2);
+        assert_eq!(page.values, vec!["blue".to_string(), "green".to_string()]);
+        assert!(page.more_available);
+
+        let page = FacetValues::new(1, values, 2, 2);
+        assert_eq!(page.values, vec!["red".to_string()]);
+        assert!(!page.more_available);
+    }
+}
@@ -0,0 +1,181 @@
+//! Groups of attributes shown as sections of the product page, like "Dimensions" or "Care"
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use serde_json;
+use validator::Validate;
+
+use stq_types::AttributeId;
+
+use models::attributes::attribute::AttrValue;
+use models::validation_rules::*;
+use schema::{attribute_group_attributes, attribute_groups};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "attribute_groups"]
+pub struct AttributeGroup {
+    pub id: i32,
+    pub name: serde_json::Value,
+    pub position: i32,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Validate, Debug)]
+#[table_name = "attribute_groups"]
+pub struct NewAttributeGroup {
+    #[validate(custom = "validate_translation")]
+    pub name: serde_json::Value,
+    pub position: i32,
+}
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Validate, Debug)]
+#[table_name = "attribute_groups"]
+pub struct UpdateAttributeGroup {
+    #[validate(custom = "validate_translation")]
+    pub name: Option<serde_json::Value>,
+    pub position: Option<i32>,
+}
+
+/// Attribute belongs to one group at most
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "attribute_group_attributes"]
+pub struct AttributeGroupAttribute {
+    pub id: i32,
+    pub group_id: i32,
+    pub attribute_id: AttributeId,
+    pub position: i32,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "attribute_group_attributes"]
+pub struct NewAttributeGroupAttribute {
+    pub group_id: i32,
+    pub attribute_id: AttributeId,
+    pub position: i32,
+}
+
+/// Replaces attributes of the group, order of `attribute_ids` is their order on the product page
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SetAttributeGroupAttributes {
+    pub attribute_ids: Vec<AttributeId>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AttributeGroupWithAttributes {
+    #[serde(flatten)]
+    pub group: AttributeGroup,
+    pub attribute_ids: Vec<AttributeId>,
+}
+
+impl AttributeGroupWithAttributes {
+    pub fn new(group: AttributeGroup, links: &[AttributeGroupAttribute]) -> Self {
+        let mut links: Vec<&AttributeGroupAttribute> = links.iter().filter(|link| link.group_id == group.id).collect();
+        links.sort_by_key(|link| link.position);
+        Self {
+            attribute_ids: links.into_iter().map(|link| link.attribute_id).collect(),
+            group,
+        }
+    }
+}
+
+/// Section of the product page, attributes without group go to the last section with no `group`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GroupedAttrValues {
+    pub group: Option<AttributeGroup>,
+    pub attributes: Vec<AttrValue>,
+}
+
+/// Splits attribute values into groups ordered by group position, empty groups are skipped
+pub fn group_attr_values(
+    mut groups: Vec<AttributeGroup>,
+    links: &[AttributeGroupAttribute],
+    values: Vec<AttrValue>,
+) -> Vec<GroupedAttrValues> {
+    groups.sort_by_key(|group| (group.position, group.id));
+    let links_by_attribute: HashMap<AttributeId, &AttributeGroupAttribute> = links.iter().map(|link| (link.attribute_id, link)).collect();
+
+    let mut grouped: HashMap<i32, Vec<(i32, AttrValue)>> = HashMap::new();
+    let mut ungrouped = vec![];
+    for value in values {
+        match links_by_attribute.get(&value.attr_id) {
+            Some(link) => grouped.entry(link.group_id).or_insert_with(Vec::new).push((link.position, value)),
+            None => ungrouped.push(value),
+        }
+    }
+
+    let mut result: Vec<GroupedAttrValues> = groups
+        .into_iter()
+        .filter_map(|group| {
+            grouped.remove(&group.id).map(|mut values| {
+                values.sort_by_key(|&(position, _)| position);
+                GroupedAttrValues {
+                    group: Some(group),
+                    attributes: values.into_iter().map(|(_, value)| value).collect(),
+                }
+            })
+        })
+        .collect();
+
+    if !ungrouped.is_empty() {
+        result.push(GroupedAttrValues {
+            group: None,
+            attributes: ungrouped,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stq_types::AttributeValueCode;
+
+    fn group(id: i32, position: i32) -> AttributeGroup {
+        AttributeGroup {
+            id,
+            name: json!([{"lang": "en", "text": "Group"}]),
+            position,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    fn link(group_id: i32, attribute_id: i32, position: i32) -> AttributeGroupAttribute {
+        AttributeGroupAttribute {
+            id: attribute_id,
+            group_id,
+            attribute_id: AttributeId(attribute_id),
+            position,
+        }
+    }
+
+    fn value(attribute_id: i32) -> AttrValue {
+        AttrValue {
+            attr_id: AttributeId(attribute_id),
+            attr_value_id: None,
+            value: AttributeValueCode("value".to_string()),
+            meta_field: None,
+        }
+    }
+
+    #[test]
+    fn test_group_attr_values() {
+        let groups = vec![group(1, 2), group(2, 1), group(3, 0)];
+        let links = vec![link(1, 10, 1), link(1, 11, 0), link(2, 12, 0)];
+        let values = vec![value(10), value(11), value(12), value(13)];
+
+        let result = group_attr_values(groups, &links, values);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].group.as_ref().map(|group| group.id), Some(2));
+        assert_eq!(result[1].group.as_ref().map(|group| group.id), Some(1));
+        assert_eq!(
+            result[1].attributes.iter().map(|value| value.attr_id).collect::<Vec<_>>(),
+            vec![AttributeId(11), AttributeId(10)]
+        );
+        assert!(result[2].group.is_none());
+        assert_eq!(result[2].attributes[0].attr_id, AttributeId(13));
+    }
+}
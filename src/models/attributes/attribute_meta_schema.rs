@@ -0,0 +1,186 @@
+//! Schemas of `meta_field` of attributes. Meta field tells frontends how to show and fill in the attribute,
+//! which of its fields are allowed depends on the attribute type
+use std::borrow::Cow;
+
+use serde_json;
+use validator::{ValidationError, ValidationErrors};
+
+use stq_static_resources::{AttributeType, Translation};
+
+/// UI elements of string attributes
+pub const STR_UI_ELEMENTS: &'static [&'static str] = &["Combobox", "Checkbox", "ColorPicker", "Input"];
+
+/// UI elements of numeric attributes
+pub const FLOAT_UI_ELEMENTS: &'static [&'static str] = &["Input", "Range"];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetaFieldKind {
+    /// List of strings
+    StringList,
+    /// List of values, each value is a list of its translations
+    TranslationsList,
+    /// One of the `allowed` strings
+    Enum,
+    String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MetaFieldSchema {
+    pub name: &'static str,
+    pub kind: MetaFieldKind,
+    /// Strings allowed in `enum` fields, empty for other kinds
+    pub allowed: Vec<&'static str>,
+    pub description: &'static str,
+}
+
+/// Fields allowed in `meta_field` of the attribute, fields are optional and may be null
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AttributeMetaSchema {
+    pub value_type: AttributeType,
+    pub fields: Vec<MetaFieldSchema>,
+}
+
+impl AttributeMetaSchema {
+    pub fn new(value_type: AttributeType) -> Self {
+        let is_numeric = value_type != AttributeType::Str;
+        let mut fields = vec![
+            MetaFieldSchema {
+                name: "values",
+                kind: MetaFieldKind::StringList,
+                allowed: vec![],
+                description: "Values the attribute takes, numbers for numeric attributes",
+            },
+            MetaFieldSchema {
+                name: "translated_values",
+                kind: MetaFieldKind::TranslationsList,
+                allowed: vec![],
+                description: "Values the attribute takes with their translations, set instead of `values`",
+            },
+            MetaFieldSchema {
+                name: "ui_element",
+                kind: MetaFieldKind::Enum,
+                allowed: if is_numeric { FLOAT_UI_ELEMENTS.to_vec() } else { STR_UI_ELEMENTS.to_vec() },
+                description: "Element the attribute is filled in with",
+            },
+        ];
+        if is_numeric {
+            fields.push(MetaFieldSchema {
+                name: "unit",
+                kind: MetaFieldKind::String,
+                allowed: vec![],
+                description: "Unit of the values, like `cm`",
+            });
+        }
+
+        Self { value_type, fields }
+    }
+
+    /// Checks `meta_field` against the schema, errors are reported under `meta_field` with the name of the wrong field
+    pub fn validate(&self, meta_field: &serde_json::Value) -> Result<(), ValidationErrors> {
+        let mut errors = vec![];
+        match *meta_field {
+            serde_json::Value::Null => {}
+            serde_json::Value::Object(ref map) => {
+                for (name, value) in map {
+                    match self.fields.iter().find(|field| field.name == name.as_str()) {
+                        Some(field) => errors.extend(self.field_error(field, value)),
+                        None => errors.push(meta_field_error(name, "unknown", "Field is not in the schema of the attribute type")),
+                    }
+                }
+                let is_set = |name: &str| map.get(name).map(|value| !value.is_null()).unwrap_or(false);
+                if is_set("values") && is_set("translated_values") {
+                    errors.push(meta_field_error(
+                        "translated_values",
+                        "exclusive",
+                        "Either values or translated_values are set",
+                    ));
+                }
+            }
+            _ => errors.push(meta_field_error("meta_field", "object", "Meta field must be an object")),
+        }
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let mut validation_errors = ValidationErrors::new();
+        for error in errors {
+            validation_errors.add("meta_field", error);
+        }
+        Err(validation_errors)
+    }
+
+    fn field_error(&self, field: &MetaFieldSchema, value: &serde_json::Value) -> Option<ValidationError> {
+        if value.is_null() {
+            return None;
+        }
+        match field.kind {
+            MetaFieldKind::StringList => match serde_json::from_value::<Vec<String>>(value.clone()) {
+                Err(_) => Some(meta_field_error(field.name, "type", "Value must be a list of strings")),
+                Ok(ref values) if self.value_type != AttributeType::Str && values.iter().any(|v| v.parse::<f64>().is_err()) => {
+                    Some(meta_field_error(field.name, "number", "Values of numeric attribute must be numbers"))
+                }
+                Ok(_) => None,
+            },
+            MetaFieldKind::TranslationsList => match serde_json::from_value::<Vec<Vec<Translation>>>(value.clone()) {
+                Err(_) => Some(meta_field_error(field.name, "type", "Value must be a list of translations")),
+                Ok(_) => None,
+            },
+            MetaFieldKind::Enum => match value.as_str() {
+                Some(value) if field.allowed.contains(&value) => None,
+                _ => Some(meta_field_error(field.name, "enum", "Value is not allowed for the attribute type")),
+            },
+            MetaFieldKind::String => match value.as_str() {
+                Some(_) => None,
+                None => Some(meta_field_error(field.name, "type", "Value must be a string")),
+            },
+        }
+    }
+}
+
+fn meta_field_error(field: &str, code: &'static str, message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::from(message));
+    error.add_param("field".into(), &field);
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_str_meta_field() {
+        let schema = AttributeMetaSchema::new(AttributeType::Str);
+
+        assert!(schema.validate(&serde_json::Value::Null).is_ok());
+        assert!(schema
+            .validate(&json!({"values": ["45", "46"], "translated_values": null, "ui_element": "Combobox"}))
+            .is_ok());
+
+        let errors = schema.validate(&json!({"values": [45], "ui_element": "Range", "unit": "cm"})).unwrap_err();
+        let errors = serde_json::to_value(errors).unwrap();
+        let mut codes = errors["meta_field"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|error| error["code"].as_str())
+            .collect::<Vec<_>>();
+        codes.sort();
+        assert_eq!(codes, vec!["enum", "type", "unknown"]);
+
+        assert!(schema.validate(&json!(["45"])).is_err());
+        assert!(schema
+            .validate(&json!({"values": ["45"], "translated_values": [[{"lang": "en", "text": "45"}]]}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_float_meta_field() {
+        let schema = AttributeMetaSchema::new(AttributeType::Float);
+
+        assert!(schema.validate(&json!({"values": ["1.5", "2"], "ui_element": "Range", "unit": "kg"})).is_ok());
+        assert!(schema.validate(&json!({"values": ["XL"]})).is_err());
+        assert!(schema.validate(&json!({"ui_element": "ColorPicker"})).is_err());
+    }
+}
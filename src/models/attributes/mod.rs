@@ -3,10 +3,16 @@
 
 pub mod attribute;
 pub mod attribute_filter;
+pub mod attribute_group;
+pub mod attribute_meta_schema;
 pub mod attribute_product;
 pub mod attribute_values;
+pub mod translation_import;
 
 pub use self::attribute::*;
 pub use self::attribute_filter::*;
+pub use self::attribute_group::*;
+pub use self::attribute_meta_schema::*;
 pub use self::attribute_product::*;
 pub use self::attribute_values::*;
+pub use self::translation_import::*;
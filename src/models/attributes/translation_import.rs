@@ -0,0 +1,181 @@
+//! Bulk import of attribute value translations from csv with `value_id,lang,text` rows
+use serde_json;
+
+use stq_static_resources::{Language, Translation};
+use stq_types::AttributeValueId;
+
+/// Parsed row of the import, `line` is 1-based line of the csv file
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranslationImportRow {
+    pub line: usize,
+    pub value_id: AttributeValueId,
+    pub translation: Translation,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationImportStatus {
+    Ok,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TranslationImportRowReport {
+    pub line: usize,
+    pub value_id: Option<AttributeValueId>,
+    pub status: TranslationImportStatus,
+    pub error: Option<String>,
+}
+
+/// Translations are saved only when every row is valid, otherwise `applied` is false
+/// and the rows with errors are reported
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TranslationImportReport {
+    pub applied: bool,
+    pub updated_values: usize,
+    pub rows: Vec<TranslationImportRowReport>,
+}
+
+impl TranslationImportRowReport {
+    pub fn ok(line: usize, value_id: AttributeValueId) -> Self {
+        Self {
+            line,
+            value_id: Some(value_id),
+            status: TranslationImportStatus::Ok,
+            error: None,
+        }
+    }
+
+    pub fn error(line: usize, value_id: Option<AttributeValueId>, error: String) -> Self {
+        Self {
+            line,
+            value_id,
+            status: TranslationImportStatus::Error,
+            error: Some(error),
+        }
+    }
+}
+
+/// Parses csv rows, header line starting with `value_id` is skipped.
+/// Rows that can not be parsed are returned as error reports
+pub fn parse_translations_csv(data: &str) -> (Vec<TranslationImportRow>, Vec<TranslationImportRowReport>) {
+    let mut rows = vec![];
+    let mut errors = vec![];
+
+    for (line, fields) in split_csv(data) {
+        if fields.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        if line == 1 && fields[0].trim() == "value_id" {
+            continue;
+        }
+        match parse_row(line, &fields) {
+            Ok(row) => rows.push(row),
+            Err((value_id, error)) => errors.push(TranslationImportRowReport::error(line, value_id, error)),
+        }
+    }
+
+    (rows, errors)
+}
+
+/// Sets translation of `lang`, other languages are kept
+pub fn merge_translation(translations: Option<&serde_json::Value>, translation: Translation) -> serde_json::Value {
+    let mut translations = translations
+        .and_then(|translations| serde_json::from_value::<Vec<Translation>>(translations.clone()).ok())
+        .unwrap_or_default();
+    translations.retain(|existing| existing.lang != translation.lang);
+    translations.push(translation);
+    serde_json::to_value(translations).unwrap_or_else(|_| json!([]))
+}
+
+fn parse_row(line: usize, fields: &[String]) -> Result<TranslationImportRow, (Option<AttributeValueId>, String)> {
+    if fields.len() != 3 {
+        return Err((None, format!("Expected 3 columns, found {}", fields.len())));
+    }
+
+    let value_id = fields[0]
+        .trim()
+        .parse::<i32>()
+        .map(AttributeValueId)
+        .map_err(|_| (None, format!("Invalid value id '{}'", fields[0])))?;
+
+    let lang = serde_json::from_value::<Language>(serde_json::Value::String(fields[1].trim().to_string()))
+        .map_err(|_| (Some(value_id), format!("Unknown language '{}'", fields[1])))?;
+
+    let text = fields[2].trim().to_string();
+    if text.is_empty() {
+        return Err((Some(value_id), "Text must not be empty".to_string()));
+    }
+
+    Ok(TranslationImportRow {
+        line,
+        value_id,
+        translation: Translation { lang, text },
+    })
+}
+
+/// Splits csv into records with their starting lines. Quoted fields may contain commas,
+/// line breaks and doubled quotes
+fn split_csv(data: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = vec![];
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(::std::mem::replace(&mut field, String::new())),
+            '\n' if !in_quotes => {
+                fields.push(::std::mem::replace(&mut field, String::new()));
+                records.push((record_line, ::std::mem::replace(&mut fields, vec![])));
+                line += 1;
+                record_line = line;
+            }
+            '\r' if !in_quotes => {}
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_translations_csv() {
+        let data = "value_id,lang,text\n1,en,Red\n2,ru,\"Красный, тёмный\"\n\nx,en,Blue\n3,xx,Green\n4,de,\"Say \"\"hi\"\"\"\r\n";
+        let (rows, errors) = parse_translations_csv(data);
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].value_id, AttributeValueId(1));
+        assert_eq!(rows[1].translation.text, "Красный, тёмный");
+        assert_eq!(rows[2].translation.text, "Say \"hi\"");
+        assert_eq!(rows[2].line, 7);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, 5);
+        assert_eq!(errors[1].value_id, Some(AttributeValueId(3)));
+    }
+}
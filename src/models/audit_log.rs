@@ -0,0 +1,35 @@
+//! Model audit_log
+use std::time::SystemTime;
+
+use serde_json;
+
+use stq_types::{BaseProductId, UserId};
+
+use schema::audit_log;
+
+/// Request made by superuser on behalf of another user or change of the base product
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "audit_log"]
+pub struct AuditLogEntry {
+    pub id: i32,
+    pub actor_user_id: UserId,
+    pub effective_user_id: UserId,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub created_at: SystemTime,
+    pub base_product_id: Option<BaseProductId>,
+    /// `BaseProductChange` of the base product
+    pub change: Option<serde_json::Value>,
+}
+
+/// Payload for recording impersonated request or change of the base product
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "audit_log"]
+pub struct NewAuditLogEntry {
+    pub actor_user_id: UserId,
+    pub effective_user_id: UserId,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub base_product_id: Option<BaseProductId>,
+    pub change: Option<serde_json::Value>,
+}
@@ -8,6 +8,7 @@ pub enum Resource {
     ProductAttrs,
     Attributes,
     AttributeValues,
+    AttributeGroups,
     Stores,
     UserRoles,
     Categories,
@@ -21,6 +22,25 @@ pub enum Resource {
     CouponScopeBaseProducts,
     CouponScopeCategories,
     UsedCoupons,
+    CouponRedemptions,
+    AuditLog,
+    CatalogHealth,
+    Jobs,
+    StoreVerificationCodes,
+    StoreFaqs,
+    SyncState,
+    SearchSynonyms,
+    IndexMigrations,
+    Listings,
+    PriceRules,
+    ModerationChecklists,
+    StockReservations,
+    StoreCategories,
+    StoreProductPositions,
+    CategoryPromotions,
+    StoreStatistics,
+    ImpactReports,
+    BaseProductHistory,
 }
 
 impl fmt::Display for Resource {
@@ -31,6 +51,7 @@ impl fmt::Display for Resource {
             Resource::ProductAttrs => write!(f, "prod attrs"),
             Resource::Attributes => write!(f, "attributes"),
             Resource::AttributeValues => write!(f, "attribute_values"),
+            Resource::AttributeGroups => write!(f, "attribute_groups"),
             Resource::Stores => write!(f, "stores"),
             Resource::UserRoles => write!(f, "user roles"),
             Resource::CategoryAttrs => write!(f, "cat attrs"),
@@ -44,6 +65,25 @@ impl fmt::Display for Resource {
             Resource::CouponScopeBaseProducts => write!(f, "coupon_scope_base_products"),
             Resource::CouponScopeCategories => write!(f, "coupon_scope_categories"),
             Resource::UsedCoupons => write!(f, "used_coupons"),
+            Resource::CouponRedemptions => write!(f, "coupon_redemptions"),
+            Resource::AuditLog => write!(f, "audit_log"),
+            Resource::CatalogHealth => write!(f, "catalog_health"),
+            Resource::Jobs => write!(f, "jobs"),
+            Resource::StoreVerificationCodes => write!(f, "store_verification_codes"),
+            Resource::StoreFaqs => write!(f, "store_faqs"),
+            Resource::SyncState => write!(f, "sync_state"),
+            Resource::SearchSynonyms => write!(f, "search_synonyms"),
+            Resource::IndexMigrations => write!(f, "index_migrations"),
+            Resource::Listings => write!(f, "listings"),
+            Resource::PriceRules => write!(f, "price_rules"),
+            Resource::ModerationChecklists => write!(f, "moderation_checklists"),
+            Resource::StockReservations => write!(f, "stock_reservations"),
+            Resource::StoreCategories => write!(f, "store_categories"),
+            Resource::StoreProductPositions => write!(f, "store_product_positions"),
+            Resource::CategoryPromotions => write!(f, "category_promotions"),
+            Resource::StoreStatistics => write!(f, "store_statistics"),
+            Resource::ImpactReports => write!(f, "impact_reports"),
+            Resource::BaseProductHistory => write!(f, "base_product_history"),
         }
     }
 }
@@ -8,6 +8,8 @@ use stq_static_resources::ModerationStatus;
 pub enum Rule {
     Any,
     ModerationStatus(ModerationStatus),
+    /// Objects deactivated by their owners
+    Deactivated,
 }
 
 impl fmt::Display for Rule {
@@ -15,6 +17,7 @@ impl fmt::Display for Rule {
         match *self {
             Rule::Any => write!(f, "any"),
             Rule::ModerationStatus(status) => write!(f, "status: {}", status),
+            Rule::Deactivated => write!(f, "deactivated"),
         }
     }
 }
@@ -1,15 +1,20 @@
 //! Module containing base_product model for query, insert, update
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use failure::Error as FailureError;
 use serde_json;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationErrors};
 
 use stq_static_resources::{Currency, ModerationStatus};
-use stq_types::{AttributeId, BaseProductId, BaseProductSlug, CategoryId, ProductId, ProductPrice, StoreId};
+use stq_types::{AttributeId, BaseProductId, BaseProductSlug, CategoryId, ProductId, ProductPrice, SagaId, StoreId};
 
+use degradation::Degradation;
 use models::validation_rules::*;
-use models::{NewProductWithAttributes, Product, ProductWithAttributes, Store};
+use models::{
+    AttrValue, ChecklistResult, CustomerPrice, ElasticIndex, ImpactReport, Job, NewProductWithAttributes, Product, ProductWithAttributes,
+    SearchAfterToken, Store, VersionedDocument,
+};
 
 use schema::base_products;
 
@@ -41,6 +46,16 @@ pub struct BaseProductRaw {
     pub height_cm: i32,
     pub weight_g: i32,
     pub store_status: ModerationStatus,
+    pub saga_id: Option<SagaId>,
+    pub published_at: Option<SystemTime>,
+    /// Lowest price of active variants in `currency`, kept up to date by products repo
+    pub min_price: Option<ProductPrice>,
+    /// Highest price of active variants in `currency`, kept up to date by products repo
+    pub max_price: Option<ProductPrice>,
+    /// Copied from the store, products of vacationing stores are excluded from search
+    pub store_is_on_vacation: bool,
+    /// Some of package dimensions were missing on creation and were taken from store defaults
+    pub dimensions_inherited: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -69,6 +84,16 @@ pub struct BaseProduct {
     pub volume_cubic_cm: Option<i32>,
     pub weight_g: Option<i32>,
     pub store_status: ModerationStatus,
+    pub saga_id: Option<SagaId>,
+    pub published_at: Option<SystemTime>,
+    /// Lowest price of active variants in `currency`, kept up to date by products repo
+    pub min_price: Option<ProductPrice>,
+    /// Highest price of active variants in `currency`, kept up to date by products repo
+    pub max_price: Option<ProductPrice>,
+    /// Copied from the store, products of vacationing stores are excluded from search
+    pub store_is_on_vacation: bool,
+    /// Some of package dimensions were missing on creation and were taken from store defaults
+    pub dimensions_inherited: bool,
 }
 
 impl BaseProduct {
@@ -102,6 +127,12 @@ impl From<BaseProductRaw> for BaseProduct {
             height_cm,
             weight_g,
             store_status,
+            saga_id,
+            published_at,
+            min_price,
+            max_price,
+            store_is_on_vacation,
+            dimensions_inherited,
         } = raw;
 
         let length_cm = if length_cm > 0 { Some(length_cm) } else { None };
@@ -139,6 +170,12 @@ impl From<BaseProductRaw> for BaseProduct {
             volume_cubic_cm,
             weight_g,
             store_status,
+            saga_id,
+            published_at,
+            min_price,
+            max_price,
+            store_is_on_vacation,
+            dimensions_inherited,
         }
     }
 }
@@ -172,6 +209,30 @@ pub struct NewBaseProduct {
     pub weight_g: Option<i32>,
     pub uuid: Uuid,
     pub store_status: Option<ModerationStatus>,
+    pub store_is_on_vacation: Option<bool>,
+    /// Set by the service when package dimensions are filled in from store defaults
+    pub dimensions_inherited: Option<bool>,
+    pub saga_id: Option<SagaId>,
+}
+
+impl NewBaseProduct {
+    /// Fills in package dimensions missing in the payload from store defaults and marks the product if any of them were taken
+    pub fn inherit_dimensions(&mut self, store: &Store) {
+        let mut inherited = false;
+        {
+            let mut inherit = |value: &mut Option<i32>, default: Option<i32>| {
+                if value.is_none() && default.is_some() {
+                    *value = default;
+                    inherited = true;
+                }
+            };
+            inherit(&mut self.length_cm, store.default_length_cm);
+            inherit(&mut self.width_cm, store.default_width_cm);
+            inherit(&mut self.height_cm, store.default_height_cm);
+            inherit(&mut self.weight_g, store.default_weight_g);
+        }
+        self.dimensions_inherited = Some(inherited);
+    }
 }
 
 /// Payload for creating base product with variants
@@ -213,6 +274,72 @@ pub struct UpdateBaseProduct {
     pub weight_g: Option<i32>,
 }
 
+/// Update of one of the base products of the store updated in bulk
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BaseProductBulkUpdate {
+    pub id: BaseProductId,
+    #[serde(flatten)]
+    pub payload: UpdateBaseProduct,
+}
+
+/// Results of the bulk update, nothing is applied unless every update is valid
+#[derive(Serialize, Clone, Debug)]
+pub struct BaseProductsBulkUpdateResult {
+    pub applied: bool,
+    pub items: Vec<BaseProductBulkUpdateItemResult>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BaseProductBulkUpdateItemResult {
+    pub id: BaseProductId,
+    /// Updated base product, only present when the updates are applied
+    pub base_product: Option<BaseProduct>,
+    pub errors: Option<ValidationErrors>,
+}
+
+/// Filter of active base products of the store deactivated in bulk, base products matching all its conditions are deactivated
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BaseProductsBulkDeactivation {
+    pub category_id: Option<CategoryId>,
+    pub created_before: Option<SystemTime>,
+    /// Variants without stock as reported by warehouses, base products match when all their variants are listed
+    pub out_of_stock_product_ids: Option<Vec<ProductId>>,
+    /// Only counts matching base products and their variants, nothing is deactivated. Also set by `?dry_run=true`
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BaseProductsBulkDeactivationResult {
+    pub base_products_count: usize,
+    pub products_count: usize,
+    /// Job deactivating the base products, absent in dry run
+    pub job: Option<Job>,
+    /// What the job changes, present in dry run only
+    pub impact: Option<ImpactReport>,
+}
+
+impl BaseProductsBulkDeactivationResult {
+    pub fn dry_run(base_products_count: usize, products_count: usize) -> Self {
+        let mut impact = ImpactReport::default();
+        impact.add_rows("base_products", base_products_count as i64);
+        impact.add_rows("products", products_count as i64);
+        impact.add_documents(ElasticIndex::Product, base_products_count as i64);
+        Self {
+            base_products_count,
+            products_count,
+            job: None,
+            impact: Some(impact),
+        }
+    }
+}
+
+/// Payload for moving base product with its variants to another store of the brand. For superusers
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TransferBaseProduct {
+    pub store_id: StoreId,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ElasticProduct {
     pub id: BaseProductId,
@@ -224,6 +351,14 @@ pub struct ElasticProduct {
     pub variants: Vec<ElasticVariant>,
     pub category_id: i32,
     pub matched_variants_ids: Option<Vec<ProductId>>,
+    #[serde(default)]
+    pub schema_version: u32,
+}
+
+impl VersionedDocument for ElasticProduct {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -246,14 +381,134 @@ pub struct BaseProductWithVariants {
     #[serde(flatten)]
     pub base_product: BaseProduct,
     pub variants: Vec<Product>,
+    /// Prices of the cheapest and the most expensive active variants in customer currency,
+    /// built from `min_price` and `max_price` of the base product, absent without active variants
+    #[serde(default)]
+    pub customer_price_range: Option<CustomerPriceRange>,
 }
 
 impl BaseProductWithVariants {
     pub fn new(base_product: BaseProduct, variants: Vec<Product>) -> Self {
-        Self { base_product, variants }
+        Self {
+            base_product,
+            variants,
+            customer_price_range: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomerPriceRange {
+    pub min_price: CustomerPrice,
+    pub max_price: CustomerPrice,
+}
+
+/// Variant with customer price and attribute values
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VariantDetails {
+    #[serde(flatten)]
+    pub product: Product,
+    pub attributes: Vec<AttrValue>,
+}
+
+impl From<ProductWithAttributes> for VariantDetails {
+    fn from(other: ProductWithAttributes) -> Self {
+        let attributes = other
+            .attributes
+            .into_iter()
+            .map(|(prod_attr, _)| AttrValue {
+                attr_id: prod_attr.attr_id,
+                attr_value_id: prod_attr.attr_value_id,
+                value: prod_attr.value,
+                meta_field: prod_attr.meta_field,
+            })
+            .collect();
+
+        Self {
+            product: Product::from(other.product),
+            attributes,
+        }
     }
 }
 
+/// Base product with variants and their attributes, returned by batch fetch by ids
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BaseProductDetails {
+    #[serde(flatten)]
+    pub base_product: BaseProduct,
+    pub variants: Vec<VariantDetails>,
+}
+
+impl From<CatalogWithAttributes> for BaseProductDetails {
+    fn from(other: CatalogWithAttributes) -> Self {
+        Self {
+            base_product: other.base_product,
+            variants: other.variants.into_iter().map(VariantDetails::from).collect(),
+        }
+    }
+}
+
+/// Page of search results, `next_token` is passed back to get the next page
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BaseProductsSearchPage {
+    pub items: Vec<BaseProductWithVariants>,
+    pub next_token: Option<String>,
+    /// Dependencies the page was built without
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<Degradation>,
+}
+
+/// Position after the last base product of the page ordered by `created_at` and id.
+/// Unlike id offsets it stays valid when base products are deactivated between pages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaseProductsCursor {
+    pub created_at: SystemTime,
+    pub id: BaseProductId,
+}
+
+impl BaseProductsCursor {
+    pub fn new(base_product: &BaseProduct) -> Self {
+        Self {
+            created_at: base_product.created_at,
+            id: base_product.id,
+        }
+    }
+
+    /// Cursor is opaque for clients, it keeps `created_at` in microseconds like postgres does
+    pub fn encode(&self) -> String {
+        let since_epoch = self.created_at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let micros = since_epoch.as_secs() * 1_000_000 + u64::from(since_epoch.subsec_micros());
+        SearchAfterToken(vec![json!(micros), json!(self.id.0)]).encode()
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, FailureError> {
+        let token = SearchAfterToken::decode(cursor)?;
+        match (token.0.get(0).and_then(|v| v.as_u64()), token.0.get(1).and_then(|v| v.as_i64())) {
+            (Some(micros), Some(id)) if token.0.len() == 2 => Ok(Self {
+                created_at: UNIX_EPOCH + Duration::new(micros / 1_000_000, (micros % 1_000_000) as u32 * 1000),
+                id: BaseProductId(id as i32),
+            }),
+            _ => Err(format_err!("Invalid base products cursor")),
+        }
+    }
+}
+
+/// Start of the base products list page
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BaseProductsListFrom {
+    /// Base products with id not less than given, ordered by id
+    Id(BaseProductId),
+    /// Base products after the cursor ordered by `created_at` and id, from the start if cursor is absent
+    Cursor(Option<BaseProductsCursor>),
+}
+
+/// Page of base products, `next_cursor` is passed back to get the next page
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BaseProductsPage {
+    pub items: Vec<BaseProduct>,
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CatalogWithAttributes {
     pub base_product: BaseProduct,
@@ -271,6 +526,7 @@ pub struct ModeratorBaseProductSearchTerms {
     pub name: Option<String>,
     pub store_id: Option<i32>,
     pub state: Option<ModerationStatus>,
+    pub category_id: Option<CategoryId>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -283,15 +539,57 @@ pub struct ModeratorBaseProductSearchResults {
 pub struct BaseProductModerate {
     pub base_product_id: BaseProductId,
     pub status: ModerationStatus,
+    /// Answers to the moderation checklist of the category, accepted when declining
+    #[serde(default)]
+    pub checklist: Vec<ChecklistResult>,
 }
 
 #[derive(Default, Serialize, Deserialize, Insertable, AsChangeset, Debug)]
 #[table_name = "base_products"]
 pub struct ServiceUpdateBaseProduct {
     pub store_status: Option<ModerationStatus>,
+    pub store_is_on_vacation: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct GetBaseProducts {
     pub ids: Vec<BaseProductId>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use repos::repo_factory::tests::{create_store, MOCK_BASE_PRODUCT_NAME_JSON, MOCK_STORE_NAME_JSON};
+    use services::base_products::tests::create_new_base_product;
+
+    #[test]
+    fn test_base_products_cursor() {
+        let cursor = BaseProductsCursor {
+            created_at: UNIX_EPOCH + Duration::new(1_580_000_000, 123_456_000),
+            id: BaseProductId(42),
+        };
+
+        assert_eq!(BaseProductsCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(BaseProductsCursor::decode(&SearchAfterToken(vec![json!("x")]).encode()).is_err());
+    }
+
+    #[test]
+    fn test_inherit_dimensions() {
+        let mut store = create_store(StoreId(1), serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+        store.default_length_cm = Some(30);
+        store.default_weight_g = Some(500);
+
+        let mut new_base_product = create_new_base_product(MOCK_BASE_PRODUCT_NAME_JSON);
+        new_base_product.length_cm = None;
+        new_base_product.weight_g = Some(100);
+        new_base_product.inherit_dimensions(&store);
+        assert_eq!(new_base_product.length_cm, Some(30));
+        assert_eq!(new_base_product.weight_g, Some(100));
+        assert_eq!(new_base_product.dimensions_inherited, Some(true));
+
+        new_base_product.dimensions_inherited = None;
+        new_base_product.inherit_dimensions(&store);
+        assert_eq!(new_base_product.dimensions_inherited, Some(false));
+    }
+}
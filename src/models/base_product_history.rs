@@ -0,0 +1,175 @@
+//! Change history of base products, entries are kept in the audit log
+use std::time::SystemTime;
+
+use serde_json;
+
+use stq_static_resources::ModerationStatus;
+use stq_types::{BaseProductId, ProductId, ProductPrice, UserId};
+
+use models::{AuditLogEntry, BaseProduct, NewAuditLogEntry};
+
+/// Change of the base product or of its variants
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BaseProductChange {
+    Updated {
+        fields: Vec<String>,
+    },
+    StatusChanged {
+        old_status: ModerationStatus,
+        new_status: ModerationStatus,
+    },
+    PriceChanged {
+        product_id: ProductId,
+        old_price: ProductPrice,
+        new_price: ProductPrice,
+    },
+    PhotosAdded {
+        product_id: ProductId,
+        urls: Vec<String>,
+    },
+}
+
+impl BaseProductChange {
+    /// Changes between two states of the base product, status is reported separately from other fields
+    pub fn between(old: &BaseProduct, new: &BaseProduct) -> Vec<BaseProductChange> {
+        let checks = vec![
+            ("name", old.name != new.name),
+            ("short_description", old.short_description != new.short_description),
+            ("long_description", old.long_description != new.long_description),
+            ("seo_title", old.seo_title != new.seo_title),
+            ("seo_description", old.seo_description != new.seo_description),
+            ("category_id", old.category_id != new.category_id),
+            ("slug", old.slug != new.slug),
+            ("currency", old.currency != new.currency),
+            ("length_cm", old.length_cm != new.length_cm),
+            ("width_cm", old.width_cm != new.width_cm),
+            ("height_cm", old.height_cm != new.height_cm),
+            ("weight_g", old.weight_g != new.weight_g),
+        ];
+        let fields = checks
+            .into_iter()
+            .filter(|&(_, changed)| changed)
+            .map(|(field, _)| field.to_string())
+            .collect::<Vec<_>>();
+
+        let mut changes = vec![];
+        if !fields.is_empty() {
+            changes.push(BaseProductChange::Updated { fields });
+        }
+        if old.status != new.status {
+            changes.push(BaseProductChange::StatusChanged {
+                old_status: old.status,
+                new_status: new.status,
+            });
+        }
+        changes
+    }
+
+    /// Text shown to sellers
+    pub fn description(&self) -> String {
+        match *self {
+            BaseProductChange::Updated { ref fields } => format!("Changed {}", fields.join(", ")),
+            BaseProductChange::StatusChanged { old_status, new_status } => format!("Status changed from {} to {}", old_status, new_status),
+            BaseProductChange::PriceChanged {
+                product_id,
+                old_price,
+                new_price,
+            } => format!("Price of variant {} changed from {} to {}", product_id, old_price.0, new_price.0),
+            BaseProductChange::PhotosAdded { product_id, ref urls } => format!("{} photo(s) added to variant {}", urls.len(), product_id),
+        }
+    }
+}
+
+/// Change of the base product made by the user, superuser is set when the user was impersonated
+#[derive(Clone, Debug)]
+pub struct NewBaseProductChange {
+    pub base_product_id: BaseProductId,
+    pub changed_by: UserId,
+    pub impersonated_by: Option<UserId>,
+    pub change: BaseProductChange,
+}
+
+impl NewBaseProductChange {
+    pub fn to_audit_log_entry(&self) -> NewAuditLogEntry {
+        NewAuditLogEntry {
+            actor_user_id: self.impersonated_by.unwrap_or(self.changed_by),
+            effective_user_id: self.changed_by,
+            method: None,
+            path: None,
+            base_product_id: Some(self.base_product_id),
+            change: serde_json::to_value(&self.change).ok(),
+        }
+    }
+}
+
+/// Entry of the base product history
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BaseProductHistoryEntry {
+    pub id: i32,
+    pub base_product_id: BaseProductId,
+    pub changed_by: UserId,
+    pub impersonated_by: Option<UserId>,
+    pub created_at: SystemTime,
+    pub change: BaseProductChange,
+    pub description: String,
+}
+
+impl BaseProductHistoryEntry {
+    /// Entry of the audit log describing the base product change, other entries are skipped
+    pub fn from_audit_log(entry: AuditLogEntry) -> Option<Self> {
+        let base_product_id = entry.base_product_id?;
+        let change = entry.change.and_then(|change| serde_json::from_value::<BaseProductChange>(change).ok())?;
+        let impersonated_by = if entry.actor_user_id != entry.effective_user_id {
+            Some(entry.actor_user_id)
+        } else {
+            None
+        };
+
+        Some(Self {
+            id: entry.id,
+            base_product_id,
+            changed_by: entry.effective_user_id,
+            impersonated_by,
+            created_at: entry.created_at,
+            description: change.description(),
+            change,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_product_change_entry() {
+        let change = NewBaseProductChange {
+            base_product_id: BaseProductId(1),
+            changed_by: UserId(2),
+            impersonated_by: Some(UserId(1)),
+            change: BaseProductChange::PriceChanged {
+                product_id: ProductId(3),
+                old_price: ProductPrice(10.0),
+                new_price: ProductPrice(12.5),
+            },
+        };
+        let new_entry = change.to_audit_log_entry();
+        let entry = AuditLogEntry {
+            id: 1,
+            actor_user_id: new_entry.actor_user_id,
+            effective_user_id: new_entry.effective_user_id,
+            method: new_entry.method,
+            path: new_entry.path,
+            created_at: SystemTime::now(),
+            base_product_id: new_entry.base_product_id,
+            change: new_entry.change,
+        };
+
+        let history_entry = BaseProductHistoryEntry::from_audit_log(entry).unwrap();
+        assert_eq!(history_entry.changed_by, UserId(2));
+        assert_eq!(history_entry.impersonated_by, Some(UserId(1)));
+        assert_eq!(history_entry.change, change.change);
+        assert_eq!(history_entry.description, "Price of variant 3 changed from 10 to 12.5");
+    }
+}
@@ -0,0 +1,111 @@
+//! Catalog health report of the store, lists products that need seller's attention.
+use std::time::{Duration, SystemTime};
+
+use serde_json;
+
+use stq_static_resources::ModerationStatus;
+use stq_types::{BaseProductId, ProductId, StoreId};
+
+use models::{BaseProduct, RawProduct, Store};
+
+/// Prices that were not changed for this number of days are reported as stale
+pub const DEFAULT_STALE_PRICE_DAYS: u64 = 90;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CatalogHealthReport {
+    pub store_id: StoreId,
+    pub base_products_count: usize,
+    pub products_count: usize,
+    /// Products without main photo
+    pub missing_photos: Vec<ProductId>,
+    /// Base products without name or short description in the default language of the store
+    pub missing_translations: Vec<BaseProductId>,
+    /// Products which price was not changed for `stale_price_days`
+    pub stale_prices: Vec<ProductId>,
+    pub stale_price_days: u64,
+    /// Products with tracked stock and no units left, pre-ordered ones are not reported
+    #[serde(default)]
+    pub out_of_stock: Vec<ProductId>,
+    /// Base products declined by moderator
+    pub declined: Vec<BaseProductId>,
+    pub generated_at: SystemTime,
+}
+
+impl CatalogHealthReport {
+    /// Builds report in one pass over active base products of the store and their variants
+    pub fn new(store: &Store, base_products: &[BaseProduct], products: &[RawProduct], stale_price_days: u64, now: SystemTime) -> Self {
+        let stale_after = Duration::from_secs(stale_price_days * 24 * 60 * 60);
+
+        let mut missing_translations = vec![];
+        let mut declined = vec![];
+        for base_product in base_products {
+            if !has_translation(&base_product.name, &store.default_language)
+                || !has_translation(&base_product.short_description, &store.default_language)
+            {
+                missing_translations.push(base_product.id);
+            }
+            if base_product.status == ModerationStatus::Decline {
+                declined.push(base_product.id);
+            }
+        }
+
+        let mut missing_photos = vec![];
+        let mut stale_prices = vec![];
+        let mut out_of_stock = vec![];
+        for product in products {
+            if product.photo_main.as_ref().map(|photo| photo.is_empty()).unwrap_or(true) {
+                missing_photos.push(product.id);
+            }
+            let is_stale = now
+                .duration_since(product.price_updated_at)
+                .map(|elapsed| elapsed >= stale_after)
+                .unwrap_or(false);
+            if is_stale {
+                stale_prices.push(product.id);
+            }
+            if product.quantity == Some(0) && !product.pre_order {
+                out_of_stock.push(product.id);
+            }
+        }
+
+        Self {
+            store_id: store.id,
+            base_products_count: base_products.len(),
+            products_count: products.len(),
+            missing_photos,
+            missing_translations,
+            stale_prices,
+            stale_price_days,
+            out_of_stock,
+            declined,
+            generated_at: now,
+        }
+    }
+}
+
+/// Translations are stored as `[{"lang": "en", "text": "..."}]`
+pub fn has_translation(translations: &serde_json::Value, lang: &str) -> bool {
+    translations
+        .as_array()
+        .map(|translations| {
+            translations.iter().any(|translation| {
+                translation["lang"].as_str() == Some(lang) && translation["text"].as_str().map(|text| !text.is_empty()).unwrap_or(false)
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_translation() {
+        let translations = json!([{"lang": "en", "text": "Name"}, {"lang": "ru", "text": ""}]);
+
+        assert!(has_translation(&translations, "en"));
+        assert!(!has_translation(&translations, "ru"));
+        assert!(!has_translation(&translations, "de"));
+        assert!(!has_translation(&json!("Name"), "en"));
+    }
+}
@@ -2,9 +2,12 @@
 //! modules of the app
 //! EAV model categories
 pub mod category_attribute;
+pub mod path;
+pub mod tree_diff;
 
 use std::cmp::Ordering;
 
+use diesel::sql_types::{BigInt, Integer};
 use serde_json;
 use uuid::Uuid;
 use validator::Validate;
@@ -12,6 +15,8 @@ use validator::Validate;
 use stq_types::{BaseProductId, CategoryId, CategorySlug};
 
 pub use self::category_attribute::*;
+pub use self::path::*;
+pub use self::tree_diff::*;
 use models::validation_rules::*;
 use models::Attribute;
 use schema::categories;
@@ -100,6 +105,9 @@ pub struct Category {
     pub children: Vec<Category>,
     pub attributes: Vec<Attribute>,
     pub slug: CategorySlug,
+    /// Published base products of the category and its descendants, counted only in the tree with products
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub products_count: Option<i64>,
 }
 
 impl Category {
@@ -118,6 +126,7 @@ impl Default for Category {
             parent_id: None,
             attributes: vec![],
             slug: CategorySlug(String::default()),
+            products_count: None,
         }
     }
 }
@@ -134,6 +143,7 @@ impl<'a> From<&'a RawCategory> for Category {
             level: cat.level,
             attributes: vec![],
             slug: cat.slug.clone(),
+            products_count: None,
         }
     }
 }
@@ -150,10 +160,20 @@ impl From<RawCategory> for Category {
             level: cat.level,
             attributes: vec![],
             slug: cat.slug,
+            products_count: None,
         }
     }
 }
 
+/// Published base products of the category, read by grouped query
+#[derive(QueryableByName, Clone, Debug, PartialEq)]
+pub struct CategoryProductsCount {
+    #[sql_type = "Integer"]
+    pub category_id: CategoryId,
+    #[sql_type = "BigInt"]
+    pub products_count: i64,
+}
+
 /// Payload for replace category
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CategoryReplacePayload {
@@ -161,3 +181,16 @@ pub struct CategoryReplacePayload {
     pub new_category: CategoryId,
     pub base_product_ids: Option<Vec<BaseProductId>>,
 }
+
+/// Payload for moving category with its subtree under another parent, `CategoryId(0)` is the root
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MoveCategory {
+    pub parent_id: CategoryId,
+}
+
+/// Payload for merging the source category into the target one, the source category is deleted
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MergeCategories {
+    pub source_id: CategoryId,
+    pub target_id: CategoryId,
+}
@@ -0,0 +1,103 @@
+//! Breadcrumb paths of categories, from the top level category down to the category itself
+use std::collections::HashMap;
+
+use serde_json;
+
+use stq_types::{BaseProductId, CategoryId, CategorySlug};
+
+use super::Category;
+
+/// Language of the name used when the category has no translation in the requested one
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// One level of the breadcrumb path
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CategoryPathItem {
+    pub id: CategoryId,
+    pub slug: CategorySlug,
+    pub name: String,
+}
+
+/// Breadcrumb path to the category of the base product
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BaseProductCategoryPath {
+    pub base_product_id: BaseProductId,
+    pub category_id: CategoryId,
+    pub path: Vec<CategoryPathItem>,
+}
+
+/// Paths of all categories of the tree in one pass, the root category is left out of the paths
+pub fn category_paths(root: &Category, lang: &str) -> HashMap<CategoryId, Vec<CategoryPathItem>> {
+    let mut paths = HashMap::new();
+    for child in &root.children {
+        add_paths(child, lang, &mut vec![], &mut paths);
+    }
+    paths
+}
+
+fn add_paths(category: &Category, lang: &str, path: &mut Vec<CategoryPathItem>, paths: &mut HashMap<CategoryId, Vec<CategoryPathItem>>) {
+    path.push(CategoryPathItem {
+        id: category.id,
+        slug: category.slug.clone(),
+        name: localized_name(&category.name, lang),
+    });
+    paths.insert(category.id, path.clone());
+    for child in &category.children {
+        add_paths(child, lang, path, paths);
+    }
+    path.pop();
+}
+
+fn localized_name(translations: &serde_json::Value, lang: &str) -> String {
+    let translations = match translations.as_array() {
+        Some(translations) => translations,
+        None => return String::new(),
+    };
+    let text_in = |lang: &str| {
+        translations
+            .iter()
+            .find(|translation| translation["lang"].as_str() == Some(lang))
+            .and_then(|translation| translation["text"].as_str())
+    };
+    text_in(lang)
+        .or_else(|| text_in(FALLBACK_LANGUAGE))
+        .or_else(|| translations.iter().filter_map(|translation| translation["text"].as_str()).next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i32, name: &str, children: Vec<Category>) -> Category {
+        Category {
+            id: CategoryId(id),
+            name: serde_json::from_str(name).unwrap(),
+            children,
+            slug: CategorySlug(format!("category-{}", id)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_category_paths() {
+        let phones = category(
+            3,
+            r#"[{"lang": "en", "text": "Phones"}, {"lang": "ru", "text": "Телефоны"}]"#,
+            vec![],
+        );
+        let laptops = category(4, r#"[{"lang": "en", "text": "Laptops"}]"#, vec![]);
+        let electronics = category(2, r#"[{"lang": "de", "text": "Elektronik"}]"#, vec![phones, laptops]);
+        let root = category(0, r#"[{"lang": "en", "text": "root"}]"#, vec![electronics]);
+
+        let paths = category_paths(&root, "ru");
+
+        assert_eq!(paths.len(), 3);
+        let names = |id: i32| paths[&CategoryId(id)].iter().map(|item| item.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(2), vec!["Elektronik"]);
+        assert_eq!(names(3), vec!["Elektronik", "Телефоны"]);
+        assert_eq!(names(4), vec!["Elektronik", "Laptops"]);
+        assert_eq!(paths[&CategoryId(3)][0].slug, CategorySlug("category-2".to_string()));
+    }
+}
@@ -0,0 +1,137 @@
+//! Diff between two category trees. Ids differ between environments,
+//! so categories are matched by slug and attributes by uuid.
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json;
+use uuid::Uuid;
+
+use stq_types::CategorySlug;
+
+use super::Category;
+
+/// Category which exists only in one of the trees
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CategoryDiffNode {
+    pub slug: CategorySlug,
+    pub name: serde_json::Value,
+    pub parent_slug: Option<CategorySlug>,
+    pub level: i32,
+}
+
+/// Category which has another parent in the exported tree
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CategoryMove {
+    pub slug: CategorySlug,
+    pub old_parent_slug: Option<CategorySlug>,
+    pub new_parent_slug: Option<CategorySlug>,
+}
+
+/// Attributes that are bound to the category in one tree only
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CategoryAttributesChange {
+    pub slug: CategorySlug,
+    pub added_attributes: Vec<Uuid>,
+    pub removed_attributes: Vec<Uuid>,
+}
+
+/// Changes needed to turn the live tree into the exported one
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CategoryTreeDiff {
+    pub added: Vec<CategoryDiffNode>,
+    pub removed: Vec<CategoryDiffNode>,
+    pub moved: Vec<CategoryMove>,
+    pub attributes_changed: Vec<CategoryAttributesChange>,
+}
+
+impl CategoryTreeDiff {
+    pub fn new(live: &Category, exported: &Category) -> Self {
+        let live = flatten(live);
+        let exported = flatten(exported);
+
+        let added = exported
+            .iter()
+            .filter(|&(slug, _)| !live.contains_key(slug))
+            .map(|(_, node)| node.diff_node())
+            .collect();
+
+        let removed = live
+            .iter()
+            .filter(|&(slug, _)| !exported.contains_key(slug))
+            .map(|(_, node)| node.diff_node())
+            .collect();
+
+        let mut moved = vec![];
+        let mut attributes_changed = vec![];
+        for (slug, new_node) in &exported {
+            let old_node = match live.get(slug) {
+                Some(old_node) => old_node,
+                None => continue,
+            };
+
+            if old_node.parent_slug != new_node.parent_slug {
+                moved.push(CategoryMove {
+                    slug: new_node.category.slug.clone(),
+                    old_parent_slug: old_node.parent_slug.map(|slug| CategorySlug(slug.to_string())),
+                    new_parent_slug: new_node.parent_slug.map(|slug| CategorySlug(slug.to_string())),
+                });
+            }
+
+            let added_attributes: Vec<Uuid> = new_node.attributes.difference(&old_node.attributes).cloned().collect();
+            let removed_attributes: Vec<Uuid> = old_node.attributes.difference(&new_node.attributes).cloned().collect();
+            if !added_attributes.is_empty() || !removed_attributes.is_empty() {
+                attributes_changed.push(CategoryAttributesChange {
+                    slug: new_node.category.slug.clone(),
+                    added_attributes,
+                    removed_attributes,
+                });
+            }
+        }
+
+        Self {
+            added,
+            removed,
+            moved,
+            attributes_changed,
+        }
+    }
+}
+
+struct FlatCategory<'a> {
+    category: &'a Category,
+    parent_slug: Option<&'a str>,
+    attributes: BTreeSet<Uuid>,
+}
+
+impl<'a> FlatCategory<'a> {
+    fn diff_node(&self) -> CategoryDiffNode {
+        CategoryDiffNode {
+            slug: self.category.slug.clone(),
+            name: self.category.name.clone(),
+            parent_slug: self.parent_slug.map(|slug| CategorySlug(slug.to_string())),
+            level: self.category.level,
+        }
+    }
+}
+
+/// Collects all categories except the root one, which has no slug
+fn flatten(root: &Category) -> BTreeMap<&str, FlatCategory> {
+    let mut result = BTreeMap::new();
+    for child in &root.children {
+        add_category(child, None, &mut result);
+    }
+    result
+}
+
+fn add_category<'a>(category: &'a Category, parent_slug: Option<&'a str>, result: &mut BTreeMap<&'a str, FlatCategory<'a>>) {
+    result.insert(
+        &category.slug.0,
+        FlatCategory {
+            category,
+            parent_slug,
+            attributes: category.attributes.iter().map(|attr| attr.uuid).collect(),
+        },
+    );
+    for child in &category.children {
+        add_category(child, Some(&category.slug.0), result);
+    }
+}
@@ -0,0 +1,161 @@
+//! Promotional slots of categories. Admins schedule banners shown on the category page,
+//! so merchandising campaigns go live without frontend deploys
+use std::time::SystemTime;
+
+use validator::{Validate, ValidationErrors};
+
+use stq_types::CategoryId;
+
+use models::validation_rules::*;
+use schema::category_promotions;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable, PartialEq)]
+#[table_name = "category_promotions"]
+pub struct CategoryPromotion {
+    pub id: i32,
+    pub category_id: CategoryId,
+    pub banner_url: String,
+    /// Absolute url or path on the site the banner leads to
+    pub target_url: String,
+    /// Slots of the category are shown in ascending order of positions
+    pub position: i32,
+    /// Banner is shown from this moment, missing start means it is shown right away
+    pub starts_at: Option<SystemTime>,
+    /// Banner is not shown from this moment, missing end means it is shown till changed
+    pub ends_at: Option<SystemTime>,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl CategoryPromotion {
+    /// Checks that `now` is within the schedule of the promotion
+    pub fn is_live(&self, now: SystemTime) -> bool {
+        self.starts_at.map(|starts_at| starts_at <= now).unwrap_or(true) && self.ends_at.map(|ends_at| now < ends_at).unwrap_or(true)
+    }
+}
+
+/// Payload for creating promotion, category is taken from the path
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct NewCategoryPromotionPayload {
+    #[validate(url(message = "Invalid url format"))]
+    pub banner_url: String,
+    #[validate(custom = "validate_not_empty")]
+    pub target_url: String,
+    #[serde(default)]
+    pub position: i32,
+    pub starts_at: Option<SystemTime>,
+    pub ends_at: Option<SystemTime>,
+}
+
+impl NewCategoryPromotionPayload {
+    /// Checks that the promotion ends after it starts
+    pub fn validate_schedule(&self) -> Result<(), ValidationErrors> {
+        validate_promotion_schedule(self.starts_at, self.ends_at)
+    }
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "category_promotions"]
+pub struct NewCategoryPromotion {
+    pub category_id: CategoryId,
+    pub banner_url: String,
+    pub target_url: String,
+    pub position: i32,
+    pub starts_at: Option<SystemTime>,
+    pub ends_at: Option<SystemTime>,
+}
+
+impl NewCategoryPromotion {
+    pub fn new(category_id: CategoryId, payload: NewCategoryPromotionPayload) -> Self {
+        Self {
+            category_id,
+            banner_url: payload.banner_url,
+            target_url: payload.target_url,
+            position: payload.position,
+            starts_at: payload.starts_at,
+            ends_at: payload.ends_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Validate, Debug)]
+#[table_name = "category_promotions"]
+pub struct UpdateCategoryPromotion {
+    #[validate(url(message = "Invalid url format"))]
+    pub banner_url: Option<String>,
+    #[validate(custom = "validate_not_empty")]
+    pub target_url: Option<String>,
+    pub position: Option<i32>,
+    pub starts_at: Option<SystemTime>,
+    pub ends_at: Option<SystemTime>,
+}
+
+impl UpdateCategoryPromotion {
+    /// Checks that the updated promotion ends after it starts, bounds missing in the payload are taken from the promotion
+    pub fn validate_schedule(&self, promotion: &CategoryPromotion) -> Result<(), ValidationErrors> {
+        validate_promotion_schedule(self.starts_at.or(promotion.starts_at), self.ends_at.or(promotion.ends_at))
+    }
+}
+
+fn validate_promotion_schedule(starts_at: Option<SystemTime>, ends_at: Option<SystemTime>) -> Result<(), ValidationErrors> {
+    match (starts_at, ends_at) {
+        (Some(starts_at), Some(ends_at)) if ends_at <= starts_at => Err(validation_errors!({
+            "ends_at": ["range" => "Promotion must end after it starts"]
+        })),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn create_promotion(starts_at: Option<SystemTime>, ends_at: Option<SystemTime>) -> CategoryPromotion {
+        CategoryPromotion {
+            id: 1,
+            category_id: CategoryId(1),
+            banner_url: "https://example.com/banner.png".to_string(),
+            target_url: "/categories/1".to_string(),
+            position: 0,
+            starts_at,
+            ends_at,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_is_live() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+
+        assert!(create_promotion(None, None).is_live(now));
+        assert!(create_promotion(Some(now - hour), Some(now + hour)).is_live(now));
+        assert!(!create_promotion(Some(now + hour), None).is_live(now));
+        assert!(!create_promotion(None, Some(now)).is_live(now));
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let promotion = create_promotion(Some(now), Some(now + hour));
+
+        let update = UpdateCategoryPromotion {
+            banner_url: None,
+            target_url: None,
+            position: None,
+            starts_at: Some(now + hour),
+            ends_at: None,
+        };
+        assert!(update.validate_schedule(&promotion).is_err());
+
+        let update = UpdateCategoryPromotion {
+            ends_at: Some(now + hour + hour),
+            ..update
+        };
+        assert!(update.validate_schedule(&promotion).is_ok());
+    }
+}
@@ -0,0 +1,19 @@
+//! Models for moving base products to another category in bulk
+use stq_types::{CategoryId, StoreId};
+
+/// Filter of base products which category should be replaced, they are moved by `CategoryReassignment` job
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CategoryReassignment {
+    pub store_id: Option<StoreId>,
+    pub current_category_id: Option<CategoryId>,
+    /// Part of the base product name in any language
+    pub name_pattern: Option<String>,
+    pub new_category_id: CategoryId,
+}
+
+impl CategoryReassignment {
+    /// Reassignment must be limited by at least one filter, otherwise the whole catalog is moved
+    pub fn has_filter(&self) -> bool {
+        self.store_id.is_some() || self.current_category_id.is_some() || self.name_pattern.as_ref().map(|p| !p.is_empty()).unwrap_or(false)
+    }
+}
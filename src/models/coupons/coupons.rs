@@ -1,4 +1,5 @@
 //! Model coupons
+use std::str::FromStr;
 use std::time::SystemTime;
 
 use validator::Validate;
@@ -68,8 +69,37 @@ pub enum CouponScope {
     BaseProducts,
 }
 
+impl FromStr for CouponScope {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "store" => Ok(CouponScope::Store),
+            "categories" => Ok(CouponScope::Categories),
+            "base_products" => Ok(CouponScope::BaseProducts),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct CouponsSearchCodePayload {
     pub code: CouponCode,
     pub store_id: StoreId,
 }
+
+/// Filters of coupons of all stores listed to the marketplace ops team, missing filters match every coupon
+#[derive(Clone, Debug, Default)]
+pub struct AdminCouponsSearchTerms {
+    pub store_id: Option<StoreId>,
+    pub is_active: Option<bool>,
+    /// Coupons expiring within the number of days from now, coupons without expiry never match
+    pub expiring_within_days: Option<u32>,
+    pub scope: Option<CouponScope>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AdminCouponsSearchResults {
+    pub coupons: Vec<Coupon>,
+    pub total_count: u32,
+}
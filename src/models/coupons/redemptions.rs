@@ -0,0 +1,40 @@
+//! Model coupon_redemptions
+use std::time::SystemTime;
+
+use uuid::Uuid;
+
+use stq_types::{CouponCode, CouponId, ProductId, StoreId, UserId};
+
+use schema::coupon_redemptions;
+
+/// Coupon usage recorded by the internal redeem endpoint,
+/// `token` is returned to the caller to void the usage on rollback
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "coupon_redemptions"]
+#[primary_key(token)]
+pub struct CouponRedemption {
+    pub token: Uuid,
+    pub coupon_id: CouponId,
+    pub user_id: UserId,
+    pub product_id: ProductId,
+    pub created_at: SystemTime,
+}
+
+/// Payload for creating coupon redemption
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "coupon_redemptions"]
+pub struct NewCouponRedemption {
+    pub token: Uuid,
+    pub coupon_id: CouponId,
+    pub user_id: UserId,
+    pub product_id: ProductId,
+}
+
+/// Payload for redeeming coupon for user's product
+#[derive(Deserialize, Clone, Debug)]
+pub struct RedeemCouponPayload {
+    pub code: CouponCode,
+    pub store_id: StoreId,
+    pub user_id: UserId,
+    pub product_id: ProductId,
+}
@@ -0,0 +1,45 @@
+//! Models for previewing store currency change
+use stq_static_resources::currency_type::CurrencyType;
+use stq_static_resources::Currency;
+use stq_types::{BaseProductId, ExchangeRate, ProductId, ProductPrice};
+
+/// Number of decimal places kept in fiat prices
+pub const FIAT_PRICE_PRECISION: i32 = 2;
+/// Number of decimal places kept in crypto prices
+pub const CRYPTO_PRICE_PRECISION: i32 = 8;
+
+/// Payload for previewing currency change of the store
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PreviewCurrencyChange {
+    pub currency: Currency,
+}
+
+/// Price of the store product before and after currency change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CurrencyChangePreview {
+    pub base_product_id: BaseProductId,
+    pub product_id: ProductId,
+    pub old_price: ProductPrice,
+    pub old_currency: Currency,
+    pub new_price: ProductPrice,
+    pub new_currency: Currency,
+}
+
+/// Number of decimal places kept in prices of the currency
+pub fn price_precision(currency: Currency) -> i32 {
+    match currency.currency_type() {
+        CurrencyType::Fiat => FIAT_PRICE_PRECISION,
+        CurrencyType::Crypto => CRYPTO_PRICE_PRECISION,
+    }
+}
+
+/// Rounds price with the rounding rules of the currency
+pub fn round_price(price: ProductPrice, currency: Currency) -> ProductPrice {
+    let multiplier = 10f64.powi(price_precision(currency));
+    ProductPrice((price.0 * multiplier).round() / multiplier)
+}
+
+/// Converts price with exchange rate of the old currency to the new one and rounds the result
+pub fn convert_price(price: ProductPrice, rate: ExchangeRate, new_currency: Currency) -> ProductPrice {
+    round_price(ProductPrice(price.0 / rate.0), new_currency)
+}
@@ -0,0 +1,61 @@
+//! Formatting metadata of currencies, frontends and receipts format prices with it
+use stq_static_resources::currency_type::CurrencyType;
+use stq_static_resources::Currency;
+
+use models::{CRYPTO_PRICE_PRECISION, FIAT_PRICE_PRECISION};
+
+/// Symbols of currencies by code, currencies missing here are shown with their code
+const CURRENCY_SYMBOLS: &[(&str, &str, SymbolPosition)] = &[
+    ("USD", "$", SymbolPosition::Before),
+    ("EUR", "€", SymbolPosition::Before),
+    ("RUB", "₽", SymbolPosition::After),
+    ("BTC", "₿", SymbolPosition::Before),
+    ("ETH", "Ξ", SymbolPosition::Before),
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolPosition {
+    /// `$1.50`
+    Before,
+    /// `1.50 ₽`
+    After,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CurrencyMeta {
+    pub currency: Currency,
+    pub symbol: String,
+    pub symbol_position: SymbolPosition,
+    /// Prices of the currency are rounded to this number of decimal places
+    pub decimal_places: i32,
+    pub is_crypto: bool,
+}
+
+impl CurrencyMeta {
+    pub fn new(currency: Currency) -> Self {
+        let code = currency.code();
+        let (symbol, symbol_position) = CURRENCY_SYMBOLS
+            .iter()
+            .find(|(symbol_code, _, _)| symbol_code.eq_ignore_ascii_case(code))
+            .map(|(_, symbol, position)| (symbol.to_string(), *position))
+            .unwrap_or_else(|| (code.to_uppercase(), SymbolPosition::After));
+        let (decimal_places, is_crypto) = match currency.currency_type() {
+            CurrencyType::Fiat => (FIAT_PRICE_PRECISION, false),
+            CurrencyType::Crypto => (CRYPTO_PRICE_PRECISION, true),
+        };
+
+        Self {
+            currency,
+            symbol,
+            symbol_position,
+            decimal_places,
+            is_crypto,
+        }
+    }
+
+    /// Metadata of every currency prices are kept in
+    pub fn all() -> Vec<Self> {
+        Currency::enum_iter().map(CurrencyMeta::new).collect()
+    }
+}
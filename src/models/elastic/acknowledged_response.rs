@@ -0,0 +1,10 @@
+#[derive(Deserialize, Debug)]
+pub struct AcknowledgedResponse {
+    acknowledged: bool,
+}
+
+impl AcknowledgedResponse {
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged
+    }
+}
@@ -1,16 +1,25 @@
 //! Elastic search models
 use std::fmt;
 
+pub mod acknowledged_response;
 pub mod count_response;
 pub mod index_response;
+pub mod schema_version;
+pub mod search_after;
 pub mod search_response;
+pub mod search_result;
 pub mod shards;
 
+pub use self::acknowledged_response::*;
 pub use self::count_response::*;
 pub use self::index_response::*;
+pub use self::schema_version::*;
+pub use self::search_after::*;
 pub use self::search_response::*;
+pub use self::search_result::*;
 pub use self::shards::*;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ElasticIndex {
     Store,
     Product,
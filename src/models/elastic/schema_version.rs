@@ -0,0 +1,63 @@
+//! Layout version of documents in elastic indices.
+//! Every document carries `schema_version`, it is bumped with every mapping change,
+//! so documents needing reindex are found by it. Readers accept documents one version back,
+//! indices keep serving searches while they are reindexed after the deploy.
+
+/// Documents indexed before versioning have no `schema_version` and are read as version 0
+pub const ELASTIC_SCHEMA_VERSION: u32 = 5;
+
+pub trait VersionedDocument {
+    fn schema_version(&self) -> u32;
+
+    /// Documents of the current and the previous schema versions are readable
+    fn is_readable(&self) -> bool {
+        let version = self.schema_version();
+        version <= ELASTIC_SCHEMA_VERSION && version + 1 >= ELASTIC_SCHEMA_VERSION
+    }
+}
+
+/// Drops documents readers can not interpret, they are found again after reindex
+pub fn readable_documents<T, I>(documents: I) -> Vec<T>
+where
+    T: VersionedDocument,
+    I: IntoIterator<Item = T>,
+{
+    documents
+        .into_iter()
+        .filter(|document| {
+            let readable = document.is_readable();
+            if !readable {
+                warn!(
+                    "Elastic document of schema version {} is skipped, current version is {}",
+                    document.schema_version(),
+                    ELASTIC_SCHEMA_VERSION
+                );
+            }
+            readable
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Document(u32);
+
+    impl VersionedDocument for Document {
+        fn schema_version(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_readable_documents() {
+        let documents = vec![
+            Document(ELASTIC_SCHEMA_VERSION + 1),
+            Document(ELASTIC_SCHEMA_VERSION),
+            Document(ELASTIC_SCHEMA_VERSION - 1),
+        ];
+        let versions = readable_documents(documents).into_iter().map(|d| d.0).collect::<Vec<_>>();
+        assert_eq!(versions, vec![ELASTIC_SCHEMA_VERSION, ELASTIC_SCHEMA_VERSION - 1]);
+    }
+}
@@ -0,0 +1,53 @@
+//! Continuation token for paging through search results past the `from` + `size` window of elastic
+use std::fmt::Write;
+
+use failure::Error as FailureError;
+use serde_json::{self, Value};
+
+/// Sort values of the last hit of the page, passed to elastic as `search_after`.
+/// Encoded as hex of json, so the token is url safe and opaque for clients.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchAfterToken(pub Vec<Value>);
+
+impl SearchAfterToken {
+    pub fn encode(&self) -> String {
+        let json = Value::Array(self.0.clone()).to_string();
+        let mut token = String::with_capacity(json.len() * 2);
+        for byte in json.as_bytes() {
+            let _ = write!(token, "{:02x}", byte);
+        }
+        token
+    }
+
+    pub fn decode(token: &str) -> Result<Self, FailureError> {
+        if token.len() % 2 != 0 {
+            return Err(format_err!("Invalid search token length"));
+        }
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| {
+                token
+                    .get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or(format_err!("Invalid search token"))
+            })
+            .collect::<Result<Vec<u8>, FailureError>>()?;
+        let values = serde_json::from_slice::<Vec<Value>>(&bytes)?;
+        Ok(SearchAfterToken(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_after_token() {
+        let token = SearchAfterToken(vec![json!(1.5), json!("name"), json!(42)]);
+        let encoded = token.encode();
+
+        assert_eq!(SearchAfterToken::decode(&encoded).unwrap(), token);
+        assert!(SearchAfterToken::decode("zz").is_err());
+        assert!(SearchAfterToken::decode("abc").is_err());
+    }
+}
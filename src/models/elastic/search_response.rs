@@ -217,6 +217,7 @@ pub struct Hit<T> {
     routing: Option<String>,
     inner_hits: Option<Map<String, Value>>,
     fields: Option<Map<String, Value>>,
+    sort: Option<Vec<Value>>,
 }
 
 impl<T> Hit<T> {
@@ -257,6 +258,11 @@ impl<T> Hit<T> {
     pub fn fields(&self) -> &Option<Map<String, Value>> {
         &self.fields
     }
+
+    /** Sort values of the hit, present when the search is sorted. */
+    pub fn sort(&self) -> &Option<Vec<Value>> {
+        &self.sort
+    }
 }
 
 /** Type Struct to hold a generic `serde_json::Value` tree of the aggregation results. */
@@ -401,6 +407,9 @@ impl<'a> Iterator for Aggs<'a> {
 pub struct Suggest<T> {
     #[serde(rename = "name-suggest")]
     inner: Vec<SuggestWrapper<T>>,
+    /// Suggestions in the request language, missing for the default language
+    #[serde(rename = "name-suggest-language", default)]
+    language: Vec<SuggestWrapper<T>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -465,8 +474,17 @@ impl<T> Suggest<T> {
         self.inner.iter().flat_map(|wrapper| wrapper.get_documents()).collect::<Vec<&T>>()
     }
 
-    /** Get vector of source document. */
+    /** Get suggested texts in the request language, default language ones when there are none. */
     pub fn get_suggested_text(&self) -> Vec<String> {
+        let language = self
+            .language
+            .iter()
+            .flat_map(|wrapper| wrapper.get_suggested_text())
+            .collect::<Vec<String>>();
+        if !language.is_empty() {
+            return language;
+        }
+
         self.inner
             .iter()
             .flat_map(|wrapper| wrapper.get_suggested_text())
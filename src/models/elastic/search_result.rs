@@ -0,0 +1,29 @@
+//! Page of search results with metadata needed to paginate
+use degradation::Degradation;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchResult<T> {
+    /// Number of documents matching the query, not only the ones of the page
+    pub total: u64,
+    /// Time elastic took to process the query
+    pub took_ms: u64,
+    pub items: Vec<T>,
+    /// Alternative queries, only looked up when nothing was found
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// Dependencies the results were built without
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded: Vec<Degradation>,
+}
+
+impl<T> SearchResult<T> {
+    pub fn new(total: u64, took_ms: u64, items: Vec<T>) -> Self {
+        Self {
+            total,
+            took_ms,
+            items,
+            suggestions: vec![],
+            degraded: vec![],
+        }
+    }
+}
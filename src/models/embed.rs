@@ -0,0 +1,60 @@
+//! Product cards embedded by third-party sites.
+//! Cards are read without the user's ACL and prices are not converted, so any cache in between can share them.
+use std::cmp::Ordering;
+
+use serde_json;
+
+use stq_static_resources::Currency;
+use stq_types::{BaseProductId, BaseProductSlug, ProductPrice, StoreId};
+
+use models::{BaseProduct, RawProduct, Store};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EmbedStore {
+    pub id: StoreId,
+    pub slug: String,
+    pub name: serde_json::Value,
+    pub logo: Option<String>,
+}
+
+/// Base product with the price and photo of its cheapest variant, prices are in the seller currency
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EmbedBaseProduct {
+    pub id: BaseProductId,
+    pub slug: BaseProductSlug,
+    pub name: serde_json::Value,
+    pub price: Option<ProductPrice>,
+    pub discount: Option<f64>,
+    pub currency: Currency,
+    pub photo: Option<String>,
+    pub store: EmbedStore,
+}
+
+impl EmbedBaseProduct {
+    pub fn new(base_product: BaseProduct, variants: Vec<RawProduct>, store: Store) -> Self {
+        let cheapest = variants
+            .into_iter()
+            .filter(|variant| variant.is_active)
+            .min_by(|left, right| discounted_price(left).partial_cmp(&discounted_price(right)).unwrap_or(Ordering::Equal));
+
+        Self {
+            id: base_product.id,
+            slug: base_product.slug,
+            name: base_product.name,
+            price: cheapest.as_ref().map(|variant| variant.price),
+            discount: cheapest.as_ref().and_then(|variant| variant.discount),
+            currency: base_product.currency,
+            photo: cheapest.and_then(|variant| variant.photo_main),
+            store: EmbedStore {
+                id: store.id,
+                slug: store.slug,
+                name: store.name,
+                logo: store.logo,
+            },
+        }
+    }
+}
+
+fn discounted_price(variant: &RawProduct) -> f64 {
+    variant.price.0 * (1.0 - variant.discount.unwrap_or_default())
+}
@@ -0,0 +1,47 @@
+//! Module containing events published to the social feed service
+use serde_json;
+
+use stq_types::{BaseProductId, ProductId, StoreId};
+
+/// Number of published products that is announced as a store milestone
+pub const PRODUCTS_MILESTONE: i32 = 10;
+
+/// Kind of the feed event, used to switch event types on and off in config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedEventKind {
+    StorePublished,
+    ProductsMilestone,
+    BigDiscountAdded,
+}
+
+/// Structured event sent to the social feed service
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedEvent {
+    StorePublished {
+        store_id: StoreId,
+        store_slug: String,
+        name: serde_json::Value,
+    },
+    ProductsMilestone {
+        store_id: StoreId,
+        base_product_id: BaseProductId,
+        published_products: i32,
+    },
+    BigDiscountAdded {
+        store_id: StoreId,
+        base_product_id: BaseProductId,
+        product_id: ProductId,
+        discount: f64,
+    },
+}
+
+impl FeedEvent {
+    pub fn kind(&self) -> FeedEventKind {
+        match self {
+            FeedEvent::StorePublished { .. } => FeedEventKind::StorePublished,
+            FeedEvent::ProductsMilestone { .. } => FeedEventKind::ProductsMilestone,
+            FeedEvent::BigDiscountAdded { .. } => FeedEventKind::BigDiscountAdded,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Impact reports of destructive operations, returned instead of the operation result with `?dry_run=true`
+use std::collections::BTreeMap;
+
+use diesel::sql_types::{BigInt, VarChar};
+
+use models::ElasticIndex;
+
+/// Rows of the table changed by the operation, read by counting query
+#[derive(QueryableByName, Clone, Debug, PartialEq)]
+pub struct AffectedRows {
+    #[sql_type = "VarChar"]
+    pub table_name: String,
+    #[sql_type = "BigInt"]
+    pub rows: i64,
+}
+
+/// Everything the operation changes, tables and indexes left untouched are missing
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ImpactReport {
+    /// Changed rows by table, cascades included
+    pub tables: BTreeMap<String, i64>,
+    /// Changed documents by elastic index
+    pub elastic_documents: BTreeMap<String, i64>,
+}
+
+impl ImpactReport {
+    pub fn new(affected_rows: Vec<AffectedRows>) -> Self {
+        let mut report = Self::default();
+        for affected in affected_rows {
+            report.add_rows(&affected.table_name, affected.rows);
+        }
+        report
+    }
+
+    pub fn add_rows(&mut self, table_name: &str, rows: i64) {
+        if rows > 0 {
+            *self.tables.entry(table_name.to_string()).or_insert(0) += rows;
+        }
+    }
+
+    pub fn add_documents(&mut self, index: ElasticIndex, documents: i64) {
+        if documents > 0 {
+            *self.elastic_documents.entry(index.to_string()).or_insert(0) += documents;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impact_report() {
+        let mut report = ImpactReport::new(vec![
+            AffectedRows {
+                table_name: "stores".to_string(),
+                rows: 1,
+            },
+            AffectedRows {
+                table_name: "coupons".to_string(),
+                rows: 0,
+            },
+        ]);
+        report.add_rows("stores", 1);
+        report.add_documents(ElasticIndex::Product, 3);
+        report.add_documents(ElasticIndex::Store, 0);
+
+        assert_eq!(report.tables.len(), 1);
+        assert_eq!(report.tables["stores"], 2);
+        assert_eq!(report.elastic_documents.len(), 1);
+        assert_eq!(report.elastic_documents["products"], 3);
+    }
+}
@@ -0,0 +1,113 @@
+//! Rebuilds of products index, popular queries are replayed against the old and the new index
+//! before the new one takes over the alias, so mapping regressions show up in result counts
+use std::time::SystemTime;
+
+use serde_json;
+
+use schema::index_migrations;
+
+/// Result counts of the query in the old and the new index
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QueryReplay {
+    pub query: String,
+    pub old_count: u64,
+    pub new_count: u64,
+    /// Negative when the new index finds less products
+    pub delta: i64,
+}
+
+impl QueryReplay {
+    pub fn new(query: String, old_count: u64, new_count: u64) -> Self {
+        Self {
+            query,
+            old_count,
+            new_count,
+            delta: new_count as i64 - old_count as i64,
+        }
+    }
+
+    /// Query found products in the old index and finds none in the new one
+    pub fn is_lost(&self) -> bool {
+        self.old_count > 0 && self.new_count == 0
+    }
+}
+
+#[derive(Debug, Queryable, Clone)]
+pub struct RawIndexMigration {
+    pub id: i32,
+    pub old_index: String,
+    pub new_index: String,
+    pub replays: serde_json::Value,
+    pub created_at: SystemTime,
+    pub switched_at: Option<SystemTime>,
+}
+
+/// Replays of the migration, `switched_at` is missing until the new index takes over the alias
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IndexMigration {
+    pub id: i32,
+    pub old_index: String,
+    pub new_index: String,
+    pub replays: Vec<QueryReplay>,
+    pub created_at: SystemTime,
+    pub switched_at: Option<SystemTime>,
+}
+
+impl From<RawIndexMigration> for IndexMigration {
+    fn from(raw: RawIndexMigration) -> Self {
+        Self {
+            id: raw.id,
+            old_index: raw.old_index,
+            new_index: raw.new_index,
+            replays: serde_json::from_value(raw.replays).unwrap_or_default(),
+            created_at: raw.created_at,
+            switched_at: raw.switched_at,
+        }
+    }
+}
+
+#[derive(Insertable, Clone, Debug)]
+#[table_name = "index_migrations"]
+pub struct NewIndexMigration {
+    pub old_index: String,
+    pub new_index: String,
+    pub replays: serde_json::Value,
+}
+
+impl NewIndexMigration {
+    pub fn new(old_index: String, new_index: String, replays: &[QueryReplay]) -> Self {
+        Self {
+            old_index,
+            new_index,
+            replays: serde_json::to_value(replays).unwrap_or_else(|_| json!([])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_migration_replays() {
+        let replays = vec![
+            QueryReplay::new("sneakers".to_string(), 10, 12),
+            QueryReplay::new("jacket".to_string(), 5, 0),
+        ];
+        assert_eq!(replays[0].delta, 2);
+        assert!(!replays[0].is_lost());
+        assert_eq!(replays[1].delta, -5);
+        assert!(replays[1].is_lost());
+
+        let new_migration = NewIndexMigration::new("products_1".to_string(), "products_2".to_string(), &replays);
+        let migration = IndexMigration::from(RawIndexMigration {
+            id: 1,
+            old_index: new_migration.old_index,
+            new_index: new_migration.new_index,
+            replays: new_migration.replays,
+            created_at: SystemTime::now(),
+            switched_at: None,
+        });
+        assert_eq!(migration.replays, replays);
+    }
+}
@@ -0,0 +1,139 @@
+//! Models for long-running background jobs, clients poll them by id
+use std::time::SystemTime;
+
+use serde_json;
+
+use stq_types::UserId;
+
+use schema::jobs;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, DieselTypes)]
+pub enum JobKind {
+    AttributeValueTranslationsImport,
+    BaseProductsBulkDeactivation,
+    CategoryReassignment,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, DieselTypes)]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Finished,
+    Failed,
+    Canceled,
+}
+
+impl JobStatus {
+    pub fn is_done(self) -> bool {
+        match self {
+            JobStatus::Pending | JobStatus::Running => false,
+            JobStatus::Finished | JobStatus::Failed | JobStatus::Canceled => true,
+        }
+    }
+}
+
+/// Error of a single item of the job, `item` tells which one, e.g. a line of imported file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct JobItemError {
+    pub item: String,
+    pub message: String,
+}
+
+/// Background job, `processed` of `total` items shows its progress
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "jobs"]
+pub struct Job {
+    pub id: i32,
+    pub user_id: UserId,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub total: i32,
+    pub processed: i32,
+    pub errors: serde_json::Value,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl Job {
+    pub fn progress_percent(&self) -> u8 {
+        if self.status == JobStatus::Finished {
+            return 100;
+        }
+        if self.total <= 0 {
+            return 0;
+        }
+        (i64::from(self.processed.min(self.total)) * 100 / i64::from(self.total)) as u8
+    }
+}
+
+/// Job as it is shown to the clients polling it
+#[derive(Debug, Serialize, Clone)]
+pub struct JobReport {
+    #[serde(flatten)]
+    pub job: Job,
+    pub progress_percent: u8,
+}
+
+impl From<Job> for JobReport {
+    fn from(job: Job) -> Self {
+        Self {
+            progress_percent: job.progress_percent(),
+            job,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "jobs"]
+pub struct NewJob {
+    pub user_id: UserId,
+    pub kind: JobKind,
+    pub status: JobStatus,
+}
+
+impl NewJob {
+    pub fn new(user_id: UserId, kind: JobKind) -> Self {
+        Self {
+            user_id,
+            kind,
+            status: JobStatus::Pending,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Debug, Default)]
+#[table_name = "jobs"]
+pub struct UpdateJob {
+    pub status: Option<JobStatus>,
+    pub total: Option<i32>,
+    pub processed: Option<i32>,
+    pub errors: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_percent() {
+        let mut job = Job {
+            id: 1,
+            user_id: UserId(1),
+            kind: JobKind::AttributeValueTranslationsImport,
+            status: JobStatus::Running,
+            total: 0,
+            processed: 0,
+            errors: serde_json::Value::Array(vec![]),
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        };
+        assert_eq!(job.progress_percent(), 0);
+
+        job.total = 3;
+        job.processed = 1;
+        assert_eq!(job.progress_percent(), 33);
+
+        job.status = JobStatus::Finished;
+        assert_eq!(job.progress_percent(), 100);
+    }
+}
@@ -0,0 +1,130 @@
+//! Listings group identical products of different stores into one page of offers.
+//! Members of a listing are not stored, they are the published variants matching its attributes.
+use std::cmp::Ordering;
+use std::time::SystemTime;
+
+use serde_json;
+
+use stq_types::{BaseProductId, CategoryId, ProductId, StoreId};
+
+use models::{AttributeFingerprint, CustomerPrice, MatchAttrValue, ProductMatchPayload};
+use schema::listings;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "listings"]
+pub struct Listing {
+    pub id: i32,
+    pub category_id: CategoryId,
+    pub attributes: serde_json::Value,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl Listing {
+    pub fn fingerprint(&self) -> AttributeFingerprint {
+        let attributes = serde_json::from_value::<Vec<MatchAttrValue>>(self.attributes.clone()).unwrap_or_default();
+        AttributeFingerprint::new(attributes.iter().map(|attribute| (attribute.attr_id, attribute.value.0.as_str())))
+    }
+}
+
+/// Payload for creating listings, attributes are normalized so equal sets are stored equally
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "listings"]
+pub struct NewListing {
+    pub category_id: CategoryId,
+    pub attributes: serde_json::Value,
+}
+
+impl From<ProductMatchPayload> for NewListing {
+    fn from(other: ProductMatchPayload) -> Self {
+        Self {
+            category_id: other.category_id,
+            attributes: serde_json::to_value(other.fingerprint().to_attr_values()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Variant of some store matching the listing
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ListingOffer {
+    pub store_id: StoreId,
+    pub store_rating: f64,
+    pub base_product_id: BaseProductId,
+    pub product_id: ProductId,
+    pub customer_price: CustomerPrice,
+}
+
+/// Best offers of the listing, `offers_count` and `min_price` are computed over all offers
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ListingOffers {
+    pub listing: Listing,
+    pub offers_count: usize,
+    pub min_price: Option<CustomerPrice>,
+    pub offers: Vec<ListingOffer>,
+}
+
+impl ListingOffers {
+    pub fn new(listing: Listing, mut offers: Vec<ListingOffer>, count: usize) -> Self {
+        offers.sort_by(compare_offers);
+        let offers_count = offers.len();
+        let min_price = offers.first().map(|offer| offer.customer_price.clone());
+        offers.truncate(count);
+
+        Self {
+            listing,
+            offers_count,
+            min_price,
+            offers,
+        }
+    }
+}
+
+/// Cheaper offers go first, offers of better rated stores win among equal prices
+fn compare_offers(left: &ListingOffer, right: &ListingOffer) -> Ordering {
+    left.customer_price
+        .price
+        .0
+        .partial_cmp(&right.customer_price.price.0)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| right.store_rating.partial_cmp(&left.store_rating).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use stq_static_resources::Currency;
+    use stq_types::ProductPrice;
+
+    use super::*;
+
+    fn offer(product_id: i32, price: f64, store_rating: f64) -> ListingOffer {
+        ListingOffer {
+            store_id: StoreId(product_id),
+            store_rating,
+            base_product_id: BaseProductId(product_id),
+            product_id: ProductId(product_id),
+            customer_price: CustomerPrice {
+                price: ProductPrice(price),
+                currency: Currency::STQ,
+            },
+        }
+    }
+
+    #[test]
+    fn test_best_offers() {
+        let listing = Listing {
+            id: 1,
+            category_id: CategoryId(1),
+            attributes: json!([]),
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        };
+        let offers = ListingOffers::new(listing, vec![offer(1, 1200.0, 5.0), offer(2, 950.0, 3.0), offer(3, 950.0, 4.5)], 2);
+
+        assert_eq!(offers.offers_count, 3);
+        assert_eq!(offers.min_price.map(|price| price.price.0), Some(950.0));
+        assert_eq!(
+            offers.offers.iter().map(|offer| offer.product_id).collect::<Vec<_>>(),
+            vec![ProductId(3), ProductId(2)]
+        );
+    }
+}
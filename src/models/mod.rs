@@ -2,37 +2,113 @@
 //! modules of the app
 
 pub mod attributes;
+pub mod audit_log;
 pub mod authorization;
 pub mod base_product;
+pub mod base_product_history;
+pub mod catalog_health;
 pub mod category;
+pub mod category_promotion;
+pub mod category_reassignment;
 pub mod coupons;
+pub mod currency_change;
 pub mod currency_exchange;
+pub mod currency_meta;
 pub mod custom_attributes;
 pub mod elastic;
+pub mod embed;
+pub mod feed_event;
+pub mod impact_report;
+pub mod index_migration;
+pub mod job;
+pub mod listing;
+pub mod moderation_checklist;
+pub mod moderator_comment;
 pub mod moderator_product_comment;
 pub mod moderator_store_comment;
+pub mod money;
 pub mod pagination;
+pub mod price_rule;
 pub mod product;
+pub mod product_match;
+pub mod product_photo;
+pub mod product_view;
+pub mod retention;
+pub mod search_suggestion;
+pub mod search_synonym;
+pub mod stock_reservation;
 pub mod store;
+pub mod store_category;
+pub mod store_contact;
+pub mod store_faq;
+pub mod store_feed;
+pub mod store_onboarding;
+pub mod store_opening_hours;
+pub mod store_product_position;
+pub mod store_slug_history;
+pub mod store_statistics;
+pub mod store_profile;
+pub mod store_quota;
+pub mod store_verification;
+pub mod sync_state;
 pub mod user_role;
 pub mod validation_rules;
+pub mod version;
 pub mod visibility;
 pub mod wizard_store;
 
 pub use self::attributes::*;
+pub use self::audit_log::*;
 pub use self::authorization::*;
 pub use self::base_product::*;
+pub use self::base_product_history::*;
+pub use self::catalog_health::*;
 pub use self::category::*;
+pub use self::category_promotion::*;
+pub use self::category_reassignment::*;
 pub use self::coupons::*;
+pub use self::currency_change::*;
 pub use self::currency_exchange::*;
+pub use self::currency_meta::*;
 pub use self::custom_attributes::*;
 pub use self::elastic::*;
+pub use self::embed::*;
+pub use self::feed_event::*;
+pub use self::impact_report::*;
+pub use self::index_migration::*;
+pub use self::job::*;
+pub use self::listing::*;
+pub use self::moderation_checklist::*;
+pub use self::moderator_comment::*;
 pub use self::moderator_product_comment::*;
 pub use self::moderator_store_comment::*;
+pub use self::money::*;
 pub use self::pagination::*;
+pub use self::price_rule::*;
 pub use self::product::*;
+pub use self::product_match::*;
+pub use self::product_photo::*;
+pub use self::product_view::*;
+pub use self::retention::*;
+pub use self::search_suggestion::*;
+pub use self::search_synonym::*;
+pub use self::stock_reservation::*;
 pub use self::store::*;
+pub use self::store_category::*;
+pub use self::store_contact::*;
+pub use self::store_faq::*;
+pub use self::store_feed::*;
+pub use self::store_onboarding::*;
+pub use self::store_opening_hours::*;
+pub use self::store_product_position::*;
+pub use self::store_slug_history::*;
+pub use self::store_statistics::*;
+pub use self::store_profile::*;
+pub use self::store_quota::*;
+pub use self::store_verification::*;
+pub use self::sync_state::*;
 pub use self::user_role::*;
 pub use self::validation_rules::*;
+pub use self::version::*;
 pub use self::visibility::*;
 pub use self::wizard_store::*;
@@ -0,0 +1,154 @@
+//! Moderation checklists. Admins define items per category, moderators answer them when declining
+//! a base product and its owner sees the unmet ones. Items of parent categories apply to the children
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use validator::Validate;
+
+use stq_static_resources::ModerationStatus;
+use stq_types::{BaseProductId, CategoryId, UserId};
+
+use models::validation_rules::*;
+use models::{ModeratorProductComments, RawCategory};
+use schema::base_product_checklist_results;
+use schema::moderation_checklist_items;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable, PartialEq)]
+#[table_name = "moderation_checklist_items"]
+pub struct ModerationChecklistItem {
+    pub id: i32,
+    pub category_id: CategoryId,
+    /// What the moderator checks, e.g. `CE certification photo present`
+    pub description: String,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+/// Payload for creating checklist item, category is taken from the path
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct NewModerationChecklistItemPayload {
+    #[validate(custom = "validate_not_empty")]
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "moderation_checklist_items"]
+pub struct NewModerationChecklistItem {
+    pub category_id: CategoryId,
+    pub description: String,
+}
+
+impl NewModerationChecklistItem {
+    pub fn new(category_id: CategoryId, payload: NewModerationChecklistItemPayload) -> Self {
+        Self {
+            category_id,
+            description: payload.description,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Validate, Debug)]
+#[table_name = "moderation_checklist_items"]
+pub struct UpdateModerationChecklistItem {
+    #[validate(custom = "validate_not_empty")]
+    pub description: Option<String>,
+}
+
+/// Answer of the moderator to a single checklist item
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ChecklistResult {
+    pub item_id: i32,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "base_product_checklist_results"]
+pub struct BaseProductChecklistResult {
+    pub id: i32,
+    pub base_product_id: BaseProductId,
+    pub item_id: i32,
+    pub passed: bool,
+    pub moderator_id: UserId,
+    pub created_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "base_product_checklist_results"]
+pub struct NewBaseProductChecklistResult {
+    pub base_product_id: BaseProductId,
+    pub item_id: i32,
+    pub passed: bool,
+    pub moderator_id: UserId,
+}
+
+impl NewBaseProductChecklistResult {
+    pub fn new(base_product_id: BaseProductId, moderator_id: UserId, result: ChecklistResult) -> Self {
+        Self {
+            base_product_id,
+            item_id: result.item_id,
+            passed: result.passed,
+            moderator_id,
+        }
+    }
+}
+
+/// Why the base product was declined, shown to its owner
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeclineDetails {
+    pub base_product_id: BaseProductId,
+    pub status: ModerationStatus,
+    /// Latest comment of the moderator
+    pub comment: Option<ModeratorProductComments>,
+    /// Items failed at the latest decline
+    pub unmet_items: Vec<ModerationChecklistItem>,
+}
+
+/// Category and all its parents, checklist items of these categories apply to its base products
+pub fn category_with_parents(categories: &[RawCategory], category_id: CategoryId) -> Vec<CategoryId> {
+    let parents = categories
+        .iter()
+        .map(|category| (category.id, category.parent_id))
+        .collect::<HashMap<_, _>>();
+
+    let mut result = vec![category_id];
+    let mut current = category_id;
+    while let Some(&Some(parent_id)) = parents.get(&current) {
+        if result.contains(&parent_id) {
+            break;
+        }
+        result.push(parent_id);
+        current = parent_id;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stq_types::CategorySlug;
+    use uuid::Uuid;
+
+    fn create_category(id: i32, parent_id: Option<i32>) -> RawCategory {
+        RawCategory {
+            id: CategoryId(id),
+            name: json!([]),
+            parent_id: parent_id.map(CategoryId),
+            level: 1,
+            meta_field: None,
+            is_active: true,
+            uuid: Uuid::new_v4(),
+            slug: CategorySlug(format!("category-{}", id)),
+        }
+    }
+
+    #[test]
+    fn test_category_with_parents() {
+        let categories = vec![create_category(1, None), create_category(2, Some(1)), create_category(3, Some(2))];
+        assert_eq!(
+            category_with_parents(&categories, CategoryId(3)),
+            vec![CategoryId(3), CategoryId(2), CategoryId(1)]
+        );
+        assert_eq!(category_with_parents(&categories, CategoryId(1)), vec![CategoryId(1)]);
+        assert_eq!(category_with_parents(&categories, CategoryId(4)), vec![CategoryId(4)]);
+    }
+}
@@ -0,0 +1,55 @@
+//! Moderator comments of base products and stores together, for searching the comment history
+use std::time::SystemTime;
+
+use stq_types::{BaseProductId, StoreId, UserId};
+
+use models::{ModeratorProductComments, ModeratorStoreComments};
+
+/// Comment left by the moderator on a base product or a store
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModeratorComment {
+    pub id: i32,
+    pub base_product_id: Option<BaseProductId>,
+    pub store_id: Option<StoreId>,
+    pub moderator_id: UserId,
+    pub moderator_name: Option<String>,
+    pub comments: String,
+    pub created_at: SystemTime,
+}
+
+impl From<ModeratorProductComments> for ModeratorComment {
+    fn from(comment: ModeratorProductComments) -> Self {
+        Self {
+            id: comment.id,
+            base_product_id: Some(comment.base_product_id),
+            store_id: None,
+            moderator_id: comment.moderator_id,
+            moderator_name: comment.moderator_name,
+            comments: comment.comments,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+impl From<ModeratorStoreComments> for ModeratorComment {
+    fn from(comment: ModeratorStoreComments) -> Self {
+        Self {
+            id: comment.id,
+            base_product_id: None,
+            store_id: Some(comment.store_id),
+            moderator_id: comment.moderator_id,
+            moderator_name: comment.moderator_name,
+            comments: comment.comments,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+/// Filters of the comment search, missing filters match every comment
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModeratorCommentsSearchTerms {
+    /// Part of the moderator name, comments without the name are not matched
+    pub author: Option<String>,
+    /// Part of the comment text
+    pub text: Option<String>,
+}
@@ -14,6 +14,8 @@ pub struct ModeratorProductComments {
     pub base_product_id: BaseProductId,
     pub comments: String,
     pub created_at: SystemTime,
+    /// Name of the moderator when the comment was left, older comments have none
+    pub moderator_name: Option<String>,
 }
 
 /// Payload for creating wizard_stores
@@ -23,4 +25,6 @@ pub struct NewModeratorProductComments {
     pub moderator_id: UserId,
     pub base_product_id: BaseProductId,
     pub comments: String,
+    #[serde(default)]
+    pub moderator_name: Option<String>,
 }
@@ -14,6 +14,8 @@ pub struct ModeratorStoreComments {
     pub store_id: StoreId,
     pub comments: String,
     pub created_at: SystemTime,
+    /// Name of the moderator when the comment was left, older comments have none
+    pub moderator_name: Option<String>,
 }
 
 /// Payload for creating wizard_stores
@@ -23,4 +25,6 @@ pub struct NewModeratorStoreComments {
     pub moderator_id: UserId,
     pub store_id: StoreId,
     pub comments: String,
+    #[serde(default)]
+    pub moderator_name: Option<String>,
 }
@@ -0,0 +1,43 @@
+//! Money values of API responses, amounts are integers in minor units of the currency,
+//! so clients do not accumulate rounding errors of floats
+use stq_static_resources::Currency;
+use stq_types::ProductPrice;
+
+use models::price_precision;
+
+/// Amount of money, e.g. `{"amount_minor_units": 1050, "currency": "USD"}` is 10.50 USD
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Money {
+    pub amount_minor_units: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Price rounded with the rounding rules of the currency
+    pub fn from_price(price: ProductPrice, currency: Currency) -> Self {
+        let multiplier = 10f64.powi(price_precision(currency));
+        Self {
+            amount_minor_units: (price.0 * multiplier).round() as i64,
+            currency,
+        }
+    }
+
+    pub fn to_price(&self) -> ProductPrice {
+        ProductPrice(self.amount_minor_units as f64 / 10f64.powi(price_precision(self.currency)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_money_from_price() {
+        let money = Money::from_price(ProductPrice(0.1 + 0.2), Currency::USD);
+        assert_eq!(money.amount_minor_units, 30);
+        assert_eq!(money.to_price(), ProductPrice(0.3));
+
+        let money = Money::from_price(ProductPrice(0.123456789), Currency::STQ);
+        assert_eq!(money.amount_minor_units, 12_345_679);
+    }
+}
@@ -0,0 +1,343 @@
+//! Price rules of the store. A rule changes customer prices of products matching all its conditions,
+//! so owners mark down slow-moving products without repricing them by hand
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use validator::Validate;
+
+use stq_types::{BaseProductId, CategoryId, ProductId, ProductPrice, StoreId};
+
+use models::validation_rules::*;
+use models::{round_price, CustomerPrice};
+use schema::price_rules;
+
+/// Id of the rule tried in the preview, it is not saved
+pub const UNSAVED_PRICE_RULE_ID: i32 = 0;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "price_rules"]
+pub struct PriceRule {
+    pub id: i32,
+    pub store_id: StoreId,
+    pub name: String,
+    pub category_id: Option<CategoryId>,
+    pub max_stock: Option<i32>,
+    pub max_views: Option<i32>,
+    /// Change of the price in percents, negative for markdowns
+    pub percent: i32,
+    pub position: i32,
+    pub is_active: bool,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl PriceRule {
+    /// Conditions of the rule, missing ones match every product
+    pub fn conditions(&self) -> Vec<Box<PriceCondition>> {
+        let mut conditions: Vec<Box<PriceCondition>> = vec![];
+        if let Some(category_id) = self.category_id {
+            conditions.push(Box::new(CategoryCondition(category_id)));
+        }
+        if let Some(max_stock) = self.max_stock {
+            conditions.push(Box::new(MaxStockCondition(max_stock)));
+        }
+        if let Some(max_views) = self.max_views {
+            conditions.push(Box::new(MaxViewsCondition(max_views)));
+        }
+        conditions
+    }
+
+    pub fn matches(&self, subject: &PriceRuleSubject) -> bool {
+        self.is_active && self.conditions().iter().all(|condition| condition.matches(subject))
+    }
+}
+
+/// Facts about the product variant rules are evaluated against
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PriceRuleSubject {
+    pub category_id: CategoryId,
+    pub views: i32,
+    /// Units in stock of the variant, unknown when its stock is not tracked
+    pub stock: Option<i32>,
+}
+
+/// Condition of the price rule, new kinds of conditions are added by implementing it
+pub trait PriceCondition {
+    fn matches(&self, subject: &PriceRuleSubject) -> bool;
+}
+
+pub struct CategoryCondition(pub CategoryId);
+
+impl PriceCondition for CategoryCondition {
+    fn matches(&self, subject: &PriceRuleSubject) -> bool {
+        subject.category_id == self.0
+    }
+}
+
+/// Stock at or below the level, unknown stock does not match
+pub struct MaxStockCondition(pub i32);
+
+impl PriceCondition for MaxStockCondition {
+    fn matches(&self, subject: &PriceRuleSubject) -> bool {
+        subject.stock.map(|stock| stock <= self.0).unwrap_or(false)
+    }
+}
+
+/// Views at or below the number
+pub struct MaxViewsCondition(pub i32);
+
+impl PriceCondition for MaxViewsCondition {
+    fn matches(&self, subject: &PriceRuleSubject) -> bool {
+        subject.views <= self.0
+    }
+}
+
+/// Change of the price made by the matching rule
+pub trait PriceAction {
+    fn apply(&self, price: ProductPrice) -> ProductPrice;
+}
+
+/// Raises price by percents, negative percents lower it
+pub struct PercentAdjustment(pub i32);
+
+impl PriceAction for PercentAdjustment {
+    fn apply(&self, price: ProductPrice) -> ProductPrice {
+        ProductPrice(price.0 * f64::from(100 + self.0) / 100.0)
+    }
+}
+
+/// Active rules of the stores in evaluation order, the first matching rule of the store changes the price
+#[derive(Clone, Debug, Default)]
+pub struct PriceRuleEngine {
+    rules: HashMap<StoreId, Vec<PriceRule>>,
+    /// Deeper markdowns are cut to this one
+    max_markdown_percent: Option<i32>,
+}
+
+impl PriceRuleEngine {
+    pub fn new(rules: Vec<PriceRule>, max_markdown_percent: Option<i32>) -> Self {
+        let mut by_store = HashMap::<StoreId, Vec<PriceRule>>::new();
+        for rule in rules {
+            by_store.entry(rule.store_id).or_insert_with(Vec::new).push(rule);
+        }
+        for rules in by_store.values_mut() {
+            rules.sort_by_key(|rule| (rule.position, rule.id));
+        }
+
+        Self {
+            rules: by_store,
+            max_markdown_percent,
+        }
+    }
+
+    pub fn find_rule(&self, store_id: StoreId, subject: &PriceRuleSubject) -> Option<&PriceRule> {
+        self.rules
+            .get(&store_id)
+            .and_then(|rules| rules.iter().find(|rule| rule.matches(subject)))
+    }
+
+    /// Returns the price changed by the first matching rule of the store and id of the rule
+    pub fn adjust(&self, store_id: StoreId, subject: &PriceRuleSubject, price: CustomerPrice) -> (CustomerPrice, Option<i32>) {
+        match self.find_rule(store_id, subject) {
+            Some(rule) => {
+                let percent = match self.max_markdown_percent {
+                    Some(max_markdown_percent) => rule.percent.max(-max_markdown_percent),
+                    None => rule.percent,
+                };
+                let adjusted = CustomerPrice {
+                    price: round_price(PercentAdjustment(percent).apply(price.price), price.currency),
+                    currency: price.currency,
+                };
+                (adjusted, Some(rule.id))
+            }
+            None => (price, None),
+        }
+    }
+
+    /// Returns bounds the prices of variants between `min_price` and `max_price` get, when stocks of the variants
+    /// are unknown. Every stock level the rules of the store tell apart is tried, so the bounds hold for any variant
+    pub fn adjust_range(
+        &self,
+        store_id: StoreId,
+        subject: &PriceRuleSubject,
+        min_price: CustomerPrice,
+        max_price: CustomerPrice,
+    ) -> (CustomerPrice, CustomerPrice) {
+        let mut stocks = vec![None];
+        if let Some(rules) = self.rules.get(&store_id) {
+            stocks.extend(rules.iter().filter_map(|rule| rule.max_stock).map(Some));
+        }
+
+        let mut range = (min_price.clone(), max_price.clone());
+        for stock in stocks {
+            let subject = PriceRuleSubject { stock, ..subject.clone() };
+            let (min, _) = self.adjust(store_id, &subject, min_price.clone());
+            let (max, _) = self.adjust(store_id, &subject, max_price.clone());
+            if stock.is_none() || min.price.0 < range.0.price.0 {
+                range.0 = min;
+            }
+            if stock.is_none() || max.price.0 > range.1.price.0 {
+                range.1 = max;
+            }
+        }
+        range
+    }
+}
+
+/// Payload for creating price rule, store is taken from the path
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct NewPriceRulePayload {
+    #[validate(custom = "validate_not_empty")]
+    pub name: String,
+    pub category_id: Option<CategoryId>,
+    pub max_stock: Option<i32>,
+    pub max_views: Option<i32>,
+    #[validate(range(min = "-90", max = "100"))]
+    pub percent: i32,
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "price_rules"]
+pub struct NewPriceRule {
+    pub store_id: StoreId,
+    pub name: String,
+    pub category_id: Option<CategoryId>,
+    pub max_stock: Option<i32>,
+    pub max_views: Option<i32>,
+    pub percent: i32,
+    pub position: i32,
+}
+
+impl NewPriceRule {
+    pub fn new(store_id: StoreId, payload: NewPriceRulePayload) -> Self {
+        Self {
+            store_id,
+            name: payload.name,
+            category_id: payload.category_id,
+            max_stock: payload.max_stock,
+            max_views: payload.max_views,
+            percent: payload.percent,
+            position: payload.position,
+        }
+    }
+}
+
+/// Payload for updating price rule, rules with other conditions are created anew
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Validate, Debug)]
+#[table_name = "price_rules"]
+pub struct UpdatePriceRule {
+    #[validate(custom = "validate_not_empty")]
+    pub name: Option<String>,
+    #[validate(range(min = "-90", max = "100"))]
+    pub percent: Option<i32>,
+    pub position: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+/// Stock of the product variant passed to the preview, overrides the tracked quantity of the variant
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProductStock {
+    pub product_id: ProductId,
+    pub quantity: i32,
+}
+
+/// Payload for previewing price rules of the store, `rule` is tried together with the saved rules without being saved
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct PreviewPriceRules {
+    #[validate]
+    pub rule: Option<NewPriceRulePayload>,
+    #[serde(default)]
+    pub stocks: Vec<ProductStock>,
+}
+
+/// Customer price of the store product before and after price rules, the tried rule has `UNSAVED_PRICE_RULE_ID`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PriceRulePreview {
+    pub base_product_id: BaseProductId,
+    pub product_id: ProductId,
+    pub price_rule_id: Option<i32>,
+    pub old_price: CustomerPrice,
+    pub new_price: CustomerPrice,
+}
+
+#[cfg(test)]
+mod tests {
+    use stq_static_resources::Currency;
+
+    use super::*;
+
+    fn create_rule(id: i32, position: i32, percent: i32) -> PriceRule {
+        PriceRule {
+            id,
+            store_id: StoreId(1),
+            name: "markdown".to_string(),
+            category_id: None,
+            max_stock: None,
+            max_views: Some(10),
+            percent,
+            position,
+            is_active: true,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_price_rule_engine() {
+        let price = CustomerPrice {
+            price: ProductPrice(100.0),
+            currency: Currency::USD,
+        };
+        let mut subject = PriceRuleSubject {
+            category_id: CategoryId(12),
+            views: 5,
+            stock: None,
+        };
+
+        let mut stock_rule = create_rule(1, 0, -50);
+        stock_rule.max_stock = Some(3);
+        let engine = PriceRuleEngine::new(vec![create_rule(2, 2, -10), create_rule(3, 1, -20), stock_rule], Some(30));
+
+        let (adjusted, rule_id) = engine.adjust(StoreId(1), &subject, price.clone());
+        assert_eq!(rule_id, Some(3));
+        assert_eq!(adjusted.price, ProductPrice(80.0));
+
+        subject.stock = Some(2);
+        let (adjusted, rule_id) = engine.adjust(StoreId(1), &subject, price.clone());
+        assert_eq!(rule_id, Some(1));
+        assert_eq!(adjusted.price, ProductPrice(70.0));
+
+        subject.views = 11;
+        let (adjusted, rule_id) = engine.adjust(StoreId(2), &subject, price);
+        assert_eq!(rule_id, None);
+        assert_eq!(adjusted.price, ProductPrice(100.0));
+    }
+
+    #[test]
+    fn test_price_rule_engine_range() {
+        let min_price = CustomerPrice {
+            price: ProductPrice(100.0),
+            currency: Currency::USD,
+        };
+        let max_price = CustomerPrice {
+            price: ProductPrice(200.0),
+            currency: Currency::USD,
+        };
+        let subject = PriceRuleSubject {
+            category_id: CategoryId(12),
+            views: 5,
+            stock: None,
+        };
+
+        let mut stock_rule = create_rule(1, 0, -50);
+        stock_rule.max_stock = Some(3);
+        let engine = PriceRuleEngine::new(vec![create_rule(2, 1, -10), stock_rule], None);
+
+        // the cheapest variant can be low in stock, the most expensive one is not
+        let (min, max) = engine.adjust_range(StoreId(1), &subject, min_price, max_price);
+        assert_eq!(min.price, ProductPrice(50.0));
+        assert_eq!(max.price, ProductPrice(180.0));
+    }
+}
@@ -2,15 +2,16 @@
 use std::collections::HashMap;
 use std::time::SystemTime;
 
+use serde::Serialize;
 use serde_json;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationErrors};
 
 use stq_static_resources::{Currency, ModerationStatus};
 use stq_types::{BaseProductId, CategoryId, ExchangeRate, ProductId, ProductPrice, Quantity, StoreId};
 
 use models::validation_rules::*;
-use models::{AttrValue, Attribute, AttributeFilter, BaseProductRaw, ProdAttr, RangeFilter};
+use models::{round_price, AttrValue, Attribute, AttributeFilter, BaseProductRaw, Money, ProdAttr, RangeFilter};
 use schema::products;
 
 /// Payload for querying products
@@ -36,6 +37,39 @@ pub struct RawProduct {
     pub pre_order: bool,
     pub pre_order_days: i32,
     pub uuid: Uuid,
+    /// Units in stock, orders take them with `decrement_stock`. Stock of the variant is not tracked when it is absent
+    pub quantity: Option<i32>,
+    /// Variants of the base product are shown in ascending order of positions
+    pub position: i32,
+    /// Variant shown first on the product page, one per base product
+    pub is_default: bool,
+    /// Discount is applied from this moment, missing start means it is applied right away
+    pub discount_starts_at: Option<SystemTime>,
+    /// Discount is not applied from this moment, missing end means it is applied till changed
+    pub discount_ends_at: Option<SystemTime>,
+    /// GTIN, EAN, UPC or ISBN of the variant, unique among active products of the store
+    pub gtin: Option<String>,
+    /// Last change of the price or its currency, set by the database
+    pub price_updated_at: SystemTime,
+    /// Variant was deactivated by `deactivate_by_base_product`, restoring the base product activates it again
+    #[serde(default)]
+    pub deactivated_with_base_product: bool,
+}
+
+impl RawProduct {
+    /// Checks that `now` is within the discount window
+    pub fn is_discount_active(&self, now: SystemTime) -> bool {
+        self.discount_starts_at.map(|starts_at| starts_at <= now).unwrap_or(true)
+            && self.discount_ends_at.map(|ends_at| now < ends_at).unwrap_or(true)
+    }
+
+    /// Drops the discount outside of its window, so readers never see stale or upcoming sales
+    pub fn without_inactive_discount(mut self, now: SystemTime) -> Self {
+        if !self.is_discount_active(now) {
+            self.discount = None;
+        }
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -72,6 +106,95 @@ impl From<RawProduct> for Product {
     }
 }
 
+/// Product as returned by API version 2, prices are `Money` instead of floats
+#[derive(Serialize, Clone, Debug)]
+pub struct ProductWithMoney {
+    pub id: ProductId,
+    pub is_active: bool,
+    pub discount: Option<f64>,
+    pub photo_main: Option<String>,
+    pub cashback: Option<f64>,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+    pub base_product_id: BaseProductId,
+    pub additional_photos: Option<serde_json::Value>,
+    /// Seller price
+    pub price: Money,
+    pub vendor_code: String,
+    pub kafka_update_no: i32,
+    pub pre_order: bool,
+    pub pre_order_days: i32,
+    pub uuid: Uuid,
+    pub quantity: Option<i32>,
+    pub position: i32,
+    pub is_default: bool,
+    pub discount_starts_at: Option<SystemTime>,
+    pub discount_ends_at: Option<SystemTime>,
+    pub gtin: Option<String>,
+    pub customer_price: Money,
+}
+
+impl From<Product> for ProductWithMoney {
+    fn from(other: Product) -> Self {
+        let Product { product, customer_price } = other;
+        Self {
+            id: product.id,
+            is_active: product.is_active,
+            discount: product.discount,
+            photo_main: product.photo_main,
+            cashback: product.cashback,
+            created_at: product.created_at,
+            updated_at: product.updated_at,
+            base_product_id: product.base_product_id,
+            additional_photos: product.additional_photos,
+            price: Money::from_price(product.price, product.currency),
+            vendor_code: product.vendor_code,
+            kafka_update_no: product.kafka_update_no,
+            pre_order: product.pre_order,
+            pre_order_days: product.pre_order_days,
+            uuid: product.uuid,
+            quantity: product.quantity,
+            position: product.position,
+            is_default: product.is_default,
+            discount_starts_at: product.discount_starts_at,
+            discount_ends_at: product.discount_ends_at,
+            gtin: product.gtin,
+            customer_price: Money::from_price(customer_price.price, customer_price.currency),
+        }
+    }
+}
+
+/// Responses with products, which prices are `Money` under API version 2
+pub trait WithMoney {
+    type Output: Serialize + Send + 'static;
+
+    fn with_money(self) -> Self::Output;
+}
+
+impl WithMoney for Product {
+    type Output = ProductWithMoney;
+
+    fn with_money(self) -> Self::Output {
+        ProductWithMoney::from(self)
+    }
+}
+
+impl WithMoney for Option<Product> {
+    type Output = Option<ProductWithMoney>;
+
+    fn with_money(self) -> Self::Output {
+        self.map(ProductWithMoney::from)
+    }
+}
+
+impl WithMoney for Vec<Product> {
+    type Output = Vec<ProductWithMoney>;
+
+    fn with_money(self) -> Self::Output {
+        self.into_iter().map(ProductWithMoney::from).collect()
+    }
+}
+
 /// Payload for creating products
 #[derive(Serialize, Deserialize, Insertable, Validate, Clone, Debug)]
 #[table_name = "products"]
@@ -92,6 +215,10 @@ pub struct NewProduct {
     pub pre_order: Option<bool>,
     pub pre_order_days: Option<i32>,
     pub uuid: Uuid,
+    #[validate(range(min = "0"))]
+    pub quantity: Option<i32>,
+    #[validate(custom = "validate_gtin")]
+    pub gtin: Option<String>,
 }
 
 /// Payload for creating products
@@ -112,6 +239,10 @@ pub struct NewProductWithoutCurrency {
     pub pre_order: Option<bool>,
     pub pre_order_days: Option<i32>,
     pub uuid: Uuid,
+    #[validate(range(min = "0"))]
+    pub quantity: Option<i32>,
+    #[validate(custom = "validate_gtin")]
+    pub gtin: Option<String>,
 }
 
 impl From<(NewProductWithoutCurrency, Currency)> for NewProduct {
@@ -128,6 +259,8 @@ impl From<(NewProductWithoutCurrency, Currency)> for NewProduct {
             pre_order: other.0.pre_order,
             pre_order_days: other.0.pre_order_days,
             uuid: other.0.uuid,
+            quantity: other.0.quantity,
+            gtin: other.0.gtin,
         }
     }
 }
@@ -158,6 +291,106 @@ pub struct UpdateProduct {
     pub currency: Option<Currency>,
     pub pre_order: Option<bool>,
     pub pre_order_days: Option<i32>,
+    #[validate(range(min = "0"))]
+    pub quantity: Option<i32>,
+    pub discount_starts_at: Option<SystemTime>,
+    pub discount_ends_at: Option<SystemTime>,
+    #[validate(custom = "validate_gtin")]
+    pub gtin: Option<String>,
+}
+
+impl UpdateProduct {
+    /// Checks that the discount window of the updated product ends after it starts,
+    /// bounds missing in the payload are taken from the product
+    pub fn validate_discount_window(&self, product: &RawProduct) -> Result<(), ValidationErrors> {
+        let starts_at = self.discount_starts_at.or(product.discount_starts_at);
+        let ends_at = self.discount_ends_at.or(product.discount_ends_at);
+        match (starts_at, ends_at) {
+            (Some(starts_at), Some(ends_at)) if ends_at <= starts_at => Err(validation_errors!({
+                "discount_ends_at": ["range" => "Discount must end after it starts"]
+            })),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Error code returned when product has less units in stock than requested
+pub const NOT_ENOUGH_STOCK: &'static str = "NOT_ENOUGH_STOCK";
+
+/// Payload of the orders saga taking units of the product from stock
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct DecrementStock {
+    #[validate(range(min = "1"))]
+    pub quantity: i32,
+    /// Cart of the order, its reservation of the product is consumed by the order
+    #[serde(default)]
+    pub cart_id: Option<Uuid>,
+}
+
+/// Cart line of the checkout asking for the price in the currency of the request headers
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct ConvertPrice {
+    pub product_id: ProductId,
+    #[validate(range(min = "1"))]
+    pub quantity: i32,
+}
+
+/// Price of the cart line, `price` is `seller_price` divided by `rate`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConvertedPrice {
+    pub product_id: ProductId,
+    pub quantity: i32,
+    pub seller_price: ProductPrice,
+    pub seller_currency: Currency,
+    pub price: ProductPrice,
+    pub currency: Currency,
+    pub rate: ExchangeRate,
+}
+
+/// Variants with less units in stock are listed as low stock when the seller gives no threshold
+pub const DEFAULT_LOW_STOCK_THRESHOLD: i32 = 5;
+
+/// Active variant of the store running out of stock
+#[derive(Serialize, Deserialize, Queryable, Clone, Debug)]
+pub struct LowStockProduct {
+    pub product_id: ProductId,
+    pub base_product_id: BaseProductId,
+    pub vendor_code: String,
+    pub quantity: i32,
+    pub base_product_name: serde_json::Value,
+}
+
+/// Payload for ordering all active variants of the base product,
+/// the current default variant is kept when `default_product_id` is absent
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReorderProducts {
+    pub product_ids: Vec<ProductId>,
+    pub default_product_id: Option<ProductId>,
+}
+
+/// Payload for changing prices of all active variants of the base product, either `price` or `percent` is given
+#[derive(Serialize, Deserialize, Validate, Clone, Debug, Default)]
+pub struct UpdateProductPrices {
+    #[validate(custom = "validate_non_negative_price")]
+    pub price: Option<ProductPrice>,
+    /// Change of the price in percents, negative for markdowns
+    #[validate(range(min = "-99.0", max = "1000.0"))]
+    pub percent: Option<f64>,
+}
+
+impl UpdateProductPrices {
+    pub fn is_single_change(&self) -> bool {
+        self.price.is_some() != self.percent.is_some()
+    }
+
+    /// New price of the variant rounded with the rules of its currency
+    pub fn apply(&self, old_price: ProductPrice, currency: Currency) -> ProductPrice {
+        match (self.price, self.percent) {
+            (Some(price), _) => round_price(price, currency),
+            (None, Some(percent)) => round_price(ProductPrice(old_price.0 * (100.0 + percent) / 100.0), currency),
+            (None, None) => old_price,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -176,6 +409,9 @@ pub struct ProductsSearchOptions {
     pub categories_ids: Option<Vec<CategoryId>>,
     pub sort_by: Option<ProductsSorting>,
     pub status: Option<ModerationStatus>,
+    /// Storefront order of products of the store, set by the service when searching in a store
+    #[serde(skip)]
+    pub store_order: Option<Vec<BaseProductId>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -231,3 +467,73 @@ impl ProductWithAttributes {
 pub struct GetProducts {
     pub ids: Vec<ProductId>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn create_product(discount_starts_at: Option<SystemTime>, discount_ends_at: Option<SystemTime>) -> RawProduct {
+        RawProduct {
+            id: ProductId(1),
+            is_active: true,
+            discount: Some(0.3),
+            photo_main: None,
+            cashback: None,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+            base_product_id: BaseProductId(1),
+            additional_photos: None,
+            price: ProductPrice(100.0),
+            vendor_code: "vendor_code".to_string(),
+            currency: Currency::STQ,
+            kafka_update_no: 0,
+            pre_order: false,
+            pre_order_days: 0,
+            uuid: Uuid::new_v4(),
+            quantity: Some(10),
+            position: 0,
+            is_default: false,
+            discount_starts_at,
+            discount_ends_at,
+            gtin: None,
+            price_updated_at: SystemTime::now(),
+            deactivated_with_base_product: false,
+        }
+    }
+
+    #[test]
+    fn test_without_inactive_discount() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(60 * 60);
+
+        let open_window = create_product(None, None).without_inactive_discount(now);
+        assert_eq!(open_window.discount, Some(0.3));
+        let running = create_product(Some(now - hour), Some(now + hour)).without_inactive_discount(now);
+        assert_eq!(running.discount, Some(0.3));
+        let expired = create_product(None, Some(now - hour)).without_inactive_discount(now);
+        assert_eq!(expired.discount, None);
+        let upcoming = create_product(Some(now + hour), None).without_inactive_discount(now);
+        assert_eq!(upcoming.discount, None);
+    }
+
+    #[test]
+    fn test_validate_discount_window() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(60 * 60);
+        let product = create_product(Some(now), None);
+
+        let payload = UpdateProduct {
+            discount_ends_at: Some(now + hour),
+            ..Default::default()
+        };
+        assert!(payload.validate_discount_window(&product).is_ok());
+
+        let payload = UpdateProduct {
+            discount_ends_at: Some(now - hour),
+            ..Default::default()
+        };
+        assert!(payload.validate_discount_window(&product).is_err());
+    }
+}
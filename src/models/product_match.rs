@@ -0,0 +1,111 @@
+//! Matching of identical products sold by different stores.
+//!
+//! Variants are identical when they belong to the same category and have the same set of attribute values,
+//! the set is compared as a fingerprint that does not depend on the order of values, their case and surrounding spaces.
+use validator::Validate;
+
+use stq_types::{AttributeId, AttributeValueCode, CategoryId};
+
+use models::validation_rules::*;
+use models::ProdAttr;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchAttrValue {
+    pub attr_id: AttributeId,
+    pub value: AttributeValueCode,
+}
+
+/// Payload of products matching, variants must have exactly these attribute values
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct ProductMatchPayload {
+    pub category_id: CategoryId,
+    #[validate(custom = "validate_match_attributes")]
+    pub attributes: Vec<MatchAttrValue>,
+}
+
+impl ProductMatchPayload {
+    pub fn fingerprint(&self) -> AttributeFingerprint {
+        AttributeFingerprint::new(self.attributes.iter().map(|attribute| (attribute.attr_id, attribute.value.0.as_str())))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttributeFingerprint(Vec<(i32, String)>);
+
+impl AttributeFingerprint {
+    pub fn new<'a, I: IntoIterator<Item = (AttributeId, &'a str)>>(values: I) -> Self {
+        let mut values = values
+            .into_iter()
+            .map(|(attr_id, value)| (attr_id.0, value.trim().to_lowercase()))
+            .collect::<Vec<_>>();
+        values.sort();
+        values.dedup();
+        AttributeFingerprint(values)
+    }
+
+    pub fn from_prod_attrs<'a, I: IntoIterator<Item = &'a ProdAttr>>(prod_attrs: I) -> Self {
+        Self::new(prod_attrs.into_iter().map(|prod_attr| (prod_attr.attr_id, prod_attr.value.0.as_str())))
+    }
+
+    pub fn attribute_ids(&self) -> Vec<AttributeId> {
+        self.0.iter().map(|&(attr_id, _)| AttributeId(attr_id)).collect()
+    }
+
+    /// Normalized attribute values ordered by attribute id
+    pub fn to_attr_values(&self) -> Vec<MatchAttrValue> {
+        self.0
+            .iter()
+            .map(|&(attr_id, ref value)| MatchAttrValue {
+                attr_id: AttributeId(attr_id),
+                value: AttributeValueCode(value.clone()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr_value(attr_id: i32, value: &str) -> MatchAttrValue {
+        MatchAttrValue {
+            attr_id: AttributeId(attr_id),
+            value: AttributeValueCode(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order_and_case() {
+        let first = ProductMatchPayload {
+            category_id: CategoryId(1),
+            attributes: vec![attr_value(1, "Red"), attr_value(2, " XL")],
+        };
+        let second = ProductMatchPayload {
+            category_id: CategoryId(1),
+            attributes: vec![attr_value(2, "xl"), attr_value(1, "red ")],
+        };
+        let other = ProductMatchPayload {
+            category_id: CategoryId(1),
+            attributes: vec![attr_value(1, "red")],
+        };
+
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        assert_ne!(first.fingerprint(), other.fingerprint());
+        assert_eq!(first.fingerprint().attribute_ids(), vec![AttributeId(1), AttributeId(2)]);
+    }
+
+    #[test]
+    fn test_validate_attributes() {
+        let empty = ProductMatchPayload {
+            category_id: CategoryId(1),
+            attributes: vec![],
+        };
+        let duplicated = ProductMatchPayload {
+            category_id: CategoryId(1),
+            attributes: vec![attr_value(1, "red"), attr_value(1, "blue")],
+        };
+
+        assert!(empty.validate().is_err());
+        assert!(duplicated.validate().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Photos of product variants in gallery order
+use std::time::SystemTime;
+
+use validator::Validate;
+
+use stq_types::ProductId;
+
+use models::validation_rules::*;
+use schema::product_photos;
+
+/// Product variant has no more photos than this
+pub const MAX_PRODUCT_PHOTOS: usize = 10;
+
+/// Error code returned when photos of the product would exceed `MAX_PRODUCT_PHOTOS`
+pub const TOO_MANY_PHOTOS: &'static str = "TOO_MANY_PHOTOS";
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "product_photos"]
+pub struct ProductPhoto {
+    pub id: i32,
+    pub product_id: ProductId,
+    pub url: String,
+    /// Place of the photo in the gallery, starting with 0
+    pub position: i32,
+    pub created_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "product_photos"]
+pub struct NewProductPhoto {
+    pub product_id: ProductId,
+    pub url: String,
+    pub position: i32,
+}
+
+impl NewProductPhoto {
+    /// Photos of the product placed in the order of urls, starting with `first_position`
+    pub fn from_urls(product_id: ProductId, urls: Vec<String>, first_position: i32) -> Vec<Self> {
+        urls.into_iter()
+            .enumerate()
+            .map(|(index, url)| NewProductPhoto {
+                product_id,
+                url,
+                position: first_position + index as i32,
+            })
+            .collect()
+    }
+}
+
+/// Payload for adding photos to the end of the gallery
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct AddProductPhotos {
+    #[validate(custom = "validate_photo_urls")]
+    pub urls: Vec<String>,
+}
+
+/// Payload for removing photos from the gallery, urls missing in the gallery are ignored
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct RemoveProductPhotos {
+    #[validate(custom = "validate_photo_urls")]
+    pub urls: Vec<String>,
+}
+
+/// Payload for reordering the gallery, it lists every photo of the product in the new order
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct ReorderProductPhotos {
+    #[validate(custom = "validate_photo_urls")]
+    pub urls: Vec<String>,
+}
@@ -0,0 +1,63 @@
+//! Model product_views, a view is counted once per viewer per day
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use stq_types::{BaseProductId, UserId};
+
+use schema::product_views;
+
+/// Who views the product, anonymous viewers are told apart by the token they send
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub enum Viewer {
+    User(UserId),
+    Anonymous(String),
+}
+
+impl Viewer {
+    /// Signed in user is preferred over the token, requests with neither are not counted
+    pub fn new(user_id: Option<UserId>, token: Option<String>) -> Option<Self> {
+        match (user_id, token) {
+            (Some(user_id), _) => Some(Viewer::User(user_id)),
+            (None, Some(token)) => {
+                let token = token.trim().to_string();
+                if token.is_empty() {
+                    None
+                } else {
+                    Some(Viewer::Anonymous(token))
+                }
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Tokens are not stored, only their hashes
+    pub fn hash(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        Hash::hash(self, &mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "product_views"]
+pub struct NewProductView {
+    pub base_product_id: BaseProductId,
+    pub viewer_hash: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewer() {
+        assert_eq!(Viewer::new(Some(UserId(1)), Some("token".to_string())), Some(Viewer::User(UserId(1))));
+        assert_eq!(Viewer::new(None, Some(" ".to_string())), None);
+        assert_eq!(Viewer::new(None, None), None);
+
+        let user = Viewer::User(UserId(1)).hash();
+        let anonymous = Viewer::Anonymous("1".to_string()).hash();
+        assert_ne!(user, anonymous);
+        assert_eq!(user, Viewer::User(UserId(1)).hash());
+    }
+}
@@ -0,0 +1,117 @@
+//! Models of the retention job, which hard-deletes long-deactivated products and stores
+use std::collections::HashMap;
+
+use stq_types::{BaseProductId, ProductId, StoreId};
+
+use models::ImpactReport;
+
+/// Entities other services may still reference
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionEntityType {
+    Store,
+    Product,
+}
+
+/// Body posted to reference check hooks of other services
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReferenceCheckRequest {
+    pub entity_type: RetentionEntityType,
+    pub ids: Vec<i32>,
+}
+
+/// Response of reference check hook, ids still referenced by open orders, stocks, etc.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ReferenceCheckResponse {
+    pub referenced_ids: Vec<i32>,
+}
+
+/// Entity kept in the database because something still references it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BlockedDeletion {
+    pub entity_type: RetentionEntityType,
+    pub id: i32,
+    /// Name of the hook or the table holding the reference
+    pub blocked_by: String,
+}
+
+/// Result of one run of the retention job
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RetentionReport {
+    pub deleted_products: Vec<ProductId>,
+    pub deleted_base_products: Vec<BaseProductId>,
+    pub deleted_stores: Vec<StoreId>,
+    pub blocked: Vec<BlockedDeletion>,
+    /// Deletions of the run were rolled back
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl RetentionReport {
+    /// Deleted rows by table, elastic is not affected as deactivated entities are not indexed
+    pub fn impact(&self) -> ImpactReport {
+        let mut impact = ImpactReport::default();
+        impact.add_rows("products", self.deleted_products.len() as i64);
+        impact.add_rows("base_products", self.deleted_base_products.len() as i64);
+        impact.add_rows("stores", self.deleted_stores.len() as i64);
+        impact
+    }
+}
+
+/// Splits candidates into deletable ids and blocked deletions, `references` maps id to the referencing source
+pub fn split_blocked(
+    entity_type: RetentionEntityType,
+    ids: Vec<i32>,
+    references: &HashMap<i32, String>,
+) -> (Vec<i32>, Vec<BlockedDeletion>) {
+    let mut deletable = vec![];
+    let mut blocked = vec![];
+    for id in ids {
+        match references.get(&id) {
+            Some(blocked_by) => blocked.push(BlockedDeletion {
+                entity_type,
+                id,
+                blocked_by: blocked_by.clone(),
+            }),
+            None => deletable.push(id),
+        }
+    }
+    (deletable, blocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_blocked() {
+        let references = vec![(2, "orders".to_string())].into_iter().collect::<HashMap<_, _>>();
+
+        let (deletable, blocked) = split_blocked(RetentionEntityType::Product, vec![1, 2, 3], &references);
+
+        assert_eq!(deletable, vec![1, 3]);
+        assert_eq!(
+            blocked,
+            vec![BlockedDeletion {
+                entity_type: RetentionEntityType::Product,
+                id: 2,
+                blocked_by: "orders".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_retention_report_impact() {
+        let report = RetentionReport {
+            deleted_products: vec![ProductId(1), ProductId(2)],
+            deleted_stores: vec![StoreId(1)],
+            ..Default::default()
+        };
+
+        let impact = report.impact();
+
+        assert_eq!(impact.tables.len(), 2);
+        assert_eq!(impact.tables["products"], 2);
+        assert_eq!(impact.tables["stores"], 1);
+    }
+}
@@ -0,0 +1,112 @@
+//! Suggestions of the main search bar, besides product names it offers categories and attribute values
+use serde_json;
+
+use stq_types::{AttributeId, AttributeValueCode, AttributeValueId, CategoryId, CategorySlug};
+
+use models::{AttributeValue, Category};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchSuggestion {
+    Product {
+        name: String,
+    },
+    Category {
+        category_id: CategoryId,
+        slug: CategorySlug,
+        name: serde_json::Value,
+    },
+    AttributeValue {
+        attribute_id: AttributeId,
+        attribute_value_id: AttributeValueId,
+        code: AttributeValueCode,
+        translations: Option<serde_json::Value>,
+    },
+}
+
+impl<'a> From<&'a Category> for SearchSuggestion {
+    fn from(category: &'a Category) -> Self {
+        SearchSuggestion::Category {
+            category_id: category.id,
+            slug: category.slug.clone(),
+            name: category.name.clone(),
+        }
+    }
+}
+
+impl From<AttributeValue> for SearchSuggestion {
+    fn from(value: AttributeValue) -> Self {
+        SearchSuggestion::AttributeValue {
+            attribute_id: value.attr_id,
+            attribute_value_id: value.id,
+            code: value.code,
+            translations: value.translations,
+        }
+    }
+}
+
+/// Active categories which name contains `name` in any language, parents go before children
+pub fn find_categories_by_name<'a>(root: &'a Category, name: &str, count: usize) -> Vec<&'a Category> {
+    let name = name.to_lowercase();
+    let mut result = vec![];
+    let mut stack = root.children.iter().rev().collect::<Vec<_>>();
+    while let Some(category) = stack.pop() {
+        if result.len() >= count {
+            break;
+        }
+        if !category.is_active {
+            continue;
+        }
+        if category_name_contains(category, &name) {
+            result.push(category);
+        }
+        stack.extend(category.children.iter().rev());
+    }
+    result
+}
+
+/// Category names are stored as `[{"lang": "en", "text": "..."}]`
+fn category_name_contains(category: &Category, name: &str) -> bool {
+    category
+        .name
+        .as_array()
+        .map(|translations| {
+            translations
+                .iter()
+                .filter_map(|translation| translation["text"].as_str())
+                .any(|text| text.to_lowercase().contains(name))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i32, name: &str, children: Vec<Category>) -> Category {
+        Category {
+            id: CategoryId(id),
+            name: json!([{"lang": "en", "text": name}]),
+            slug: CategorySlug(id.to_string()),
+            children,
+            ..Category::default()
+        }
+    }
+
+    #[test]
+    fn test_find_categories_by_name() {
+        let root = Category {
+            children: vec![
+                category(1, "Shoes", vec![category(2, "Running shoes", vec![]), category(3, "Boots", vec![])]),
+                category(4, "Bags", vec![]),
+            ],
+            ..Category::default()
+        };
+
+        let found = find_categories_by_name(&root, "shoe", 10).into_iter().map(|c| c.id).collect::<Vec<_>>();
+        assert_eq!(found, vec![CategoryId(1), CategoryId(2)]);
+
+        let found = find_categories_by_name(&root, "shoe", 1).into_iter().map(|c| c.id).collect::<Vec<_>>();
+        assert_eq!(found, vec![CategoryId(1)]);
+    }
+}
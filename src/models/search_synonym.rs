@@ -0,0 +1,64 @@
+//! Synonyms of the product search, every group of terms is matched as the same word
+use std::time::SystemTime;
+
+use validator::Validate;
+
+use models::validation_rules::*;
+use schema::search_synonyms;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable, PartialEq)]
+#[table_name = "search_synonyms"]
+pub struct SearchSynonym {
+    pub id: i32,
+    pub terms: Vec<String>,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl SearchSynonym {
+    /// Rule of elastic `synonym` token filter in Solr format, e.g. `sneakers, trainers`
+    pub fn to_rule(&self) -> String {
+        self.terms.iter().map(|term| term.trim().to_lowercase()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Validate, Debug)]
+#[table_name = "search_synonyms"]
+pub struct NewSearchSynonym {
+    #[validate(custom = "validate_synonym_terms")]
+    pub terms: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rule() {
+        let synonym = SearchSynonym {
+            id: 1,
+            terms: vec!["Sneakers".to_string(), " trainers ".to_string()],
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        };
+        assert_eq!(synonym.to_rule(), "sneakers, trainers");
+    }
+
+    #[test]
+    fn test_validate_terms() {
+        let valid = NewSearchSynonym {
+            terms: vec!["sneakers".to_string(), "trainers".to_string()],
+        };
+        assert!(valid.validate().is_ok());
+
+        let single = NewSearchSynonym {
+            terms: vec!["sneakers".to_string()],
+        };
+        assert!(single.validate().is_err());
+
+        let with_separator = NewSearchSynonym {
+            terms: vec!["sneakers, shoes".to_string(), "trainers".to_string()],
+        };
+        assert!(with_separator.validate().is_err());
+    }
+}
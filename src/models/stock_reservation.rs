@@ -0,0 +1,57 @@
+//! Units of products held by carts. A reservation holds the units till `expires_at`,
+//! expired reservations are not counted and are removed by the stock reservations job
+use std::time::SystemTime;
+
+use uuid::Uuid;
+use validator::Validate;
+
+use stq_types::ProductId;
+
+use schema::stock_reservations;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "stock_reservations"]
+pub struct StockReservation {
+    pub id: i32,
+    pub cart_id: Uuid,
+    pub product_id: ProductId,
+    pub quantity: i32,
+    pub expires_at: SystemTime,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+/// Reservation of the cart, replaces the previous reservation of the product by the same cart
+#[derive(Serialize, Deserialize, Insertable, AsChangeset, Clone, Debug)]
+#[table_name = "stock_reservations"]
+pub struct NewStockReservation {
+    pub cart_id: Uuid,
+    pub product_id: ProductId,
+    pub quantity: i32,
+    pub expires_at: SystemTime,
+}
+
+/// Payload of the carts service reserving units of the product
+#[derive(Serialize, Deserialize, Validate, Clone, Debug, PartialEq)]
+pub struct ReserveStock {
+    pub cart_id: Uuid,
+    pub product_id: ProductId,
+    #[validate(range(min = "1"))]
+    pub quantity: i32,
+}
+
+/// Authoritative state of reservations sent by the orders service,
+/// reservations missing in it are released
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReconcileStock {
+    pub reservations: Vec<ReserveStock>,
+}
+
+/// Result of the reconciliation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StockReconciliation {
+    /// Reservations kept or created with the quantities of the orders service, their expiry is renewed
+    pub reserved: usize,
+    /// Reservations missing in the state of the orders service
+    pub released: usize,
+}
@@ -1,6 +1,8 @@
 //! Module containg store model for query, insert, update
 use std::time::SystemTime;
 
+use chrono::{DateTime, Utc};
+use diesel::sql_types::{BigInt, Integer};
 use serde_json;
 use uuid::Uuid;
 use validator::Validate;
@@ -9,7 +11,7 @@ use stq_static_resources::ModerationStatus;
 use stq_types::{Alpha3, CategoryId, SagaId, StoreId, UserId};
 
 use models::validation_rules::*;
-use models::BaseProductWithVariants;
+use models::{parse_utc_offset, BaseProductWithVariants, OpeningHours, VerificationChannel, VersionedDocument, ELASTIC_SCHEMA_VERSION};
 use schema::stores;
 
 /// Payload for querying stores
@@ -50,11 +52,64 @@ pub struct Store {
     pub country_code: Option<Alpha3>,
     pub uuid: Uuid,
     pub saga_id: Option<SagaId>,
+    pub quota_plan: Option<String>,
+    pub email_verified: bool,
+    pub phone_verified: bool,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Pushed by billing when the store gets payout details
+    pub payout_info_present: bool,
+    /// Pushed by delivery when the store gets a shipping profile
+    pub shipping_profile_set: bool,
+    /// Set by superusers, owners of franchise stores clone them into regional variants
+    pub franchise: bool,
+    /// Sales are paused by the owner, products of the store are not found by search
+    pub is_on_vacation: bool,
+    /// Shown to buyers while the store is on vacation
+    pub vacation_message: Option<String>,
+    /// Serialized `OpeningHours` in local time of the store
+    pub opening_hours: Option<serde_json::Value>,
+    /// UTC offset of the store like `+03:00`
+    pub timezone: Option<String>,
+    /// Set by moderators
+    pub verification_status: StoreVerificationStatus,
+    /// Badges given by moderators, list of strings
+    pub badges: serde_json::Value,
+    /// Package dimensions new base products of the store get when they are created without them
+    pub default_length_cm: Option<i32>,
+    pub default_width_cm: Option<i32>,
+    pub default_height_cm: Option<i32>,
+    pub default_weight_g: Option<i32>,
 }
 
 impl Store {
     pub const MAX_LENGTH_SHORT_DESCRIPTION: u64 = 170;
     pub const MAX_LENGTH_LONG_DESCRIPTION: u64 = 8000;
+
+    /// Store is verified when it has at least one contact and all its contacts are confirmed
+    pub fn is_verified(&self) -> bool {
+        let email_verified = self.email.is_none() || self.email_verified;
+        let phone_verified = self.phone.is_none() || self.phone_verified;
+        (self.email.is_some() || self.phone.is_some()) && email_verified && phone_verified
+    }
+
+    /// Checks opening hours of the store at the moment, `None` when the store has no opening hours or timezone
+    pub fn is_open_at(&self, now: DateTime<Utc>) -> Option<bool> {
+        let opening_hours = self
+            .opening_hours
+            .clone()
+            .and_then(|opening_hours| serde_json::from_value::<OpeningHours>(opening_hours).ok())?;
+        let offset = self.timezone.as_ref().and_then(|timezone| parse_utc_offset(timezone))?;
+        Some(opening_hours.is_open_at(now.with_timezone(&offset).naive_local()))
+    }
+
+    /// Email or phone of the store codes are sent to
+    pub fn contact(&self, channel: VerificationChannel) -> Option<String> {
+        match channel {
+            VerificationChannel::Email => self.email.clone(),
+            VerificationChannel::Phone => self.phone.clone(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -62,18 +117,102 @@ pub struct ElasticStore {
     pub id: StoreId,
     pub user_id: UserId,
     pub name: serde_json::Value,
+    /// Indexed as `geo_point`, absent for stores without coordinates
+    #[serde(default)]
+    pub location: Option<GeoPoint>,
+    /// Verified stores are boosted in search
+    #[serde(default)]
+    pub verification_status: StoreVerificationStatus,
+    #[serde(default)]
+    pub badges: serde_json::Value,
+    #[serde(default)]
+    pub schema_version: u32,
+}
+
+impl VersionedDocument for ElasticStore {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
 }
 
 impl From<Store> for ElasticStore {
     fn from(store: Store) -> Self {
+        let location = match (store.latitude, store.longitude) {
+            (Some(lat), Some(lon)) => Some(GeoPoint { lat, lon }),
+            _ => None,
+        };
+
         Self {
             id: store.id,
             user_id: store.user_id,
             name: store.name,
+            location,
+            verification_status: store.verification_status,
+            badges: store.badges,
+            schema_version: ELASTIC_SCHEMA_VERSION,
         }
     }
 }
 
+/// Elastic `geo_point` in object format
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Payload for searching published stores within `radius_km` from the point
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct SearchStoresNearby {
+    #[validate(range(min = "-90.0", max = "90.0"))]
+    pub latitude: f64,
+    #[validate(range(min = "-180.0", max = "180.0"))]
+    pub longitude: f64,
+    #[validate(range(min = "0.0", max = "20000.0"))]
+    pub radius_km: f64,
+}
+
+/// Store with its opening status at the moment of the request
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreWithOpeningStatus {
+    #[serde(flatten)]
+    pub store: Store,
+    /// Missing when the store has no opening hours or timezone
+    pub is_open_now: Option<bool>,
+}
+
+impl StoreWithOpeningStatus {
+    pub fn new(store: Store, now: DateTime<Utc>) -> Self {
+        let is_open_now = store.is_open_at(now);
+        Self { store, is_open_now }
+    }
+}
+
+/// Store found by nearby search, closest stores go first
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreWithDistance {
+    #[serde(flatten)]
+    pub store: Store,
+    pub distance_km: Option<f64>,
+}
+
+/// Active base products of the store, read by grouped query
+#[derive(QueryableByName, Clone, Debug, PartialEq)]
+pub struct StoreProductsCount {
+    #[sql_type = "Integer"]
+    pub store_id: StoreId,
+    #[sql_type = "BigInt"]
+    pub products_count: i64,
+}
+
+/// Store with count of its active base products, for saga and orders services
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreWithProductsCount {
+    #[serde(flatten)]
+    pub store: Store,
+    pub products_count: i64,
+}
+
 /// Payload for creating stores
 #[derive(Serialize, Deserialize, Insertable, Validate, Clone, Debug)]
 #[table_name = "stores"]
@@ -110,8 +249,105 @@ pub struct NewStore {
     pub street_number: Option<String>,
     pub place_id: Option<String>,
     pub country_code: Option<Alpha3>,
+    #[validate(range(min = "-90.0", max = "90.0"))]
+    pub latitude: Option<f64>,
+    #[validate(range(min = "-180.0", max = "180.0"))]
+    pub longitude: Option<f64>,
     pub uuid: Uuid,
     pub saga_id: Option<SagaId>,
+    #[validate(custom = "validate_opening_hours")]
+    pub opening_hours: Option<serde_json::Value>,
+    #[validate(custom = "validate_timezone")]
+    pub timezone: Option<String>,
+    #[validate(range(min = "0", max = "1000"))]
+    pub default_length_cm: Option<i32>,
+    #[validate(range(min = "0", max = "1000"))]
+    pub default_width_cm: Option<i32>,
+    #[validate(range(min = "0", max = "1000"))]
+    pub default_height_cm: Option<i32>,
+    #[validate(range(min = "0", max = "1000000"))]
+    pub default_weight_g: Option<i32>,
+}
+
+impl NewStore {
+    /// Draft copy of the store settings under the new slug, owned by the same user
+    pub fn clone_of(store: &Store, slug: String) -> Self {
+        Self {
+            name: store.name.clone(),
+            user_id: store.user_id,
+            short_description: store.short_description.clone(),
+            long_description: store.long_description.clone(),
+            slug,
+            cover: store.cover.clone(),
+            logo: store.logo.clone(),
+            phone: store.phone.clone(),
+            email: store.email.clone(),
+            address: store.address.clone(),
+            facebook_url: store.facebook_url.clone(),
+            twitter_url: store.twitter_url.clone(),
+            instagram_url: store.instagram_url.clone(),
+            default_language: store.default_language.clone(),
+            slogan: store.slogan.clone(),
+            country: store.country.clone(),
+            administrative_area_level_1: store.administrative_area_level_1.clone(),
+            administrative_area_level_2: store.administrative_area_level_2.clone(),
+            locality: store.locality.clone(),
+            political: store.political.clone(),
+            postal_code: store.postal_code.clone(),
+            route: store.route.clone(),
+            street_number: store.street_number.clone(),
+            place_id: store.place_id.clone(),
+            country_code: store.country_code.clone(),
+            latitude: store.latitude,
+            longitude: store.longitude,
+            uuid: Uuid::new_v4(),
+            saga_id: None,
+            opening_hours: store.opening_hours.clone(),
+            timezone: store.timezone.clone(),
+            default_length_cm: store.default_length_cm,
+            default_width_cm: store.default_width_cm,
+            default_height_cm: store.default_height_cm,
+            default_weight_g: store.default_weight_g,
+        }
+    }
+}
+
+/// Payload for cloning the store into a new draft store
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct CloneStore {
+    #[validate(custom = "validate_slug")]
+    pub slug: String,
+    /// Copies active base products with their variants too
+    #[serde(default)]
+    pub copy_catalog: bool,
+}
+
+/// Store created by cloning, delivery copies shipping profiles of `source_store_id` to it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreClone {
+    pub store: Store,
+    pub source_store_id: StoreId,
+    pub copied_base_products: i32,
+}
+
+/// Payload for marking the store as franchise. For superusers
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SetStoreFranchise {
+    pub franchise: bool,
+}
+
+/// Payload for handing the store over to another user
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TransferStoreOwnership {
+    pub user_id: UserId,
+}
+
+/// Payload for pausing and resuming sales of the store
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct SetStoreVacation {
+    pub is_on_vacation: bool,
+    #[validate(length(min = "1", max = "500"))]
+    pub vacation_message: Option<String>,
 }
 
 /// Payload for updating users
@@ -149,6 +385,22 @@ pub struct UpdateStore {
     pub street_number: Option<String>,
     pub place_id: Option<String>,
     pub country_code: Option<Alpha3>,
+    #[validate(range(min = "-90.0", max = "90.0"))]
+    pub latitude: Option<f64>,
+    #[validate(range(min = "-180.0", max = "180.0"))]
+    pub longitude: Option<f64>,
+    #[validate(custom = "validate_opening_hours")]
+    pub opening_hours: Option<serde_json::Value>,
+    #[validate(custom = "validate_timezone")]
+    pub timezone: Option<String>,
+    #[validate(range(min = "0", max = "1000"))]
+    pub default_length_cm: Option<i32>,
+    #[validate(range(min = "0", max = "1000"))]
+    pub default_width_cm: Option<i32>,
+    #[validate(range(min = "0", max = "1000"))]
+    pub default_height_cm: Option<i32>,
+    #[validate(range(min = "0", max = "1000000"))]
+    pub default_weight_g: Option<i32>,
 }
 
 #[derive(Default, Serialize, Deserialize, Insertable, AsChangeset, Debug)]
@@ -249,6 +501,24 @@ impl ServiceUpdateStore {
             ..ServiceUpdateStore::default()
         }
     }
+
+    /// Categories used by the cloned store without its products, counts grow as products are added
+    pub fn copy_product_categories(source: Option<serde_json::Value>) -> Self {
+        let product_categories = source
+            .and_then(|prod_cats| serde_json::from_value::<Vec<ProductCategories>>(prod_cats).ok())
+            .map(|prod_cats| {
+                prod_cats
+                    .into_iter()
+                    .map(|pc| ProductCategories {
+                        category_id: pc.category_id,
+                        count: 0,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .and_then(|prod_cats| serde_json::to_value(prod_cats).ok());
+
+        Self { product_categories }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -263,6 +533,73 @@ pub struct StoresSearchOptions {
     pub country: Option<String>,
 }
 
+/// Value of a facet and number of found stores having it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FacetBucket<T> {
+    pub value: T,
+    pub count: u64,
+}
+
+/// Page of found stores with facets over all stores matching the name.
+/// Facets ignore the selected options, so other countries and categories can still be offered
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchStoreWithFacets {
+    pub stores: Vec<Store>,
+    pub total_count: u64,
+    pub countries: Vec<FacetBucket<String>>,
+    pub categories: Vec<FacetBucket<CategoryId>>,
+}
+
+/// Result of faceted stores search in elastic
+#[derive(Clone, Debug)]
+pub struct ElasticStoresWithFacets {
+    pub stores: Vec<ElasticStore>,
+    pub total_count: u64,
+    pub countries: Vec<FacetBucket<String>>,
+    pub categories: Vec<FacetBucket<CategoryId>>,
+}
+
+impl ElasticStoresWithFacets {
+    /// Reads `countries` terms aggregation and `product_categories.categories` nested one
+    pub fn new(stores: Vec<ElasticStore>, total_count: u64, aggregations: Option<&serde_json::Value>) -> Self {
+        let (countries, categories) = match aggregations {
+            Some(aggregations) => (
+                facet_buckets(&aggregations["countries"], |key| key.as_str().map(|country| country.to_string())),
+                facet_buckets(&aggregations["product_categories"]["categories"], |key| {
+                    key.as_i64().map(|id| CategoryId(id as i32))
+                }),
+            ),
+            None => (vec![], vec![]),
+        };
+
+        Self {
+            stores,
+            total_count,
+            countries,
+            categories,
+        }
+    }
+}
+
+fn facet_buckets<T, F>(aggregation: &serde_json::Value, parse_key: F) -> Vec<FacetBucket<T>>
+where
+    F: Fn(&serde_json::Value) -> Option<T>,
+{
+    aggregation["buckets"]
+        .as_array()
+        .map(|buckets| {
+            buckets
+                .iter()
+                .filter_map(|bucket| {
+                    let value = parse_key(&bucket["key"])?;
+                    let count = bucket["doc_count"].as_u64()?;
+                    Some(FacetBucket { value, count })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ProductCategories {
     pub category_id: CategoryId,
@@ -306,3 +643,58 @@ pub struct StoreModerate {
     pub store_id: StoreId,
     pub status: ModerationStatus,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elastic_stores_with_facets() {
+        let aggregations = json!({
+            "countries": {"buckets": [{"key": "RUS", "doc_count": 3}, {"key": "USA", "doc_count": 1}]},
+            "product_categories": {
+                "doc_count": 10,
+                "categories": {"buckets": [{"key": 12, "doc_count": 2}]}
+            }
+        });
+
+        let result = ElasticStoresWithFacets::new(vec![], 4, Some(&aggregations));
+
+        assert_eq!(
+            result.countries,
+            vec![
+                FacetBucket {
+                    value: "RUS".to_string(),
+                    count: 3
+                },
+                FacetBucket {
+                    value: "USA".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(
+            result.categories,
+            vec![FacetBucket {
+                value: CategoryId(12),
+                count: 2
+            }]
+        );
+    }
+    #[test]
+    fn test_copy_product_categories() {
+        let source = json!([{"category_id": 12, "count": 5}, {"category_id": 14, "count": 1}]);
+
+        let copy = ServiceUpdateStore::copy_product_categories(Some(source));
+        assert_eq!(
+            copy.product_categories,
+            Some(json!([{"category_id": 12, "count": 0}, {"category_id": 14, "count": 0}]))
+        );
+
+        let added = ServiceUpdateStore::add_category_to_product_categories(copy.product_categories, CategoryId(12));
+        assert_eq!(
+            added.product_categories,
+            Some(json!([{"category_id": 12, "count": 1}, {"category_id": 14, "count": 0}]))
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! Selling categories of the store. Categories are declared in the store wizard,
+//! base products of other categories need an expansion request approved by moderators
+use std::time::SystemTime;
+
+use stq_types::{CategoryId, StoreId, UserId};
+
+use schema::store_categories;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, DieselTypes)]
+pub enum StoreCategoryStatus {
+    /// Declared by the owner in the store wizard
+    Declared,
+    /// Expansion to the category is waiting for moderators
+    Requested,
+    Approved,
+    Rejected,
+}
+
+impl StoreCategoryStatus {
+    /// Base products of the category can be created by the store
+    pub fn is_allowed(self) -> bool {
+        match self {
+            StoreCategoryStatus::Declared | StoreCategoryStatus::Approved => true,
+            StoreCategoryStatus::Requested | StoreCategoryStatus::Rejected => false,
+        }
+    }
+}
+
+/// Category of the first level the store sells in or asks to sell in
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "store_categories"]
+pub struct StoreCategory {
+    pub id: i32,
+    pub store_id: StoreId,
+    pub category_id: CategoryId,
+    pub status: StoreCategoryStatus,
+    /// Moderator who approved or rejected the expansion
+    pub moderator_id: Option<UserId>,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "store_categories"]
+pub struct NewStoreCategory {
+    pub store_id: StoreId,
+    pub category_id: CategoryId,
+    pub status: StoreCategoryStatus,
+}
+
+/// Payload of the store wizard step, replaces categories declared before
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeclareStoreCategories {
+    pub category_ids: Vec<CategoryId>,
+}
+
+/// Payload of the store owner asking to sell in one more category
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NewCategoryExpansion {
+    pub category_id: CategoryId,
+}
+
+/// Decision of the moderator on the expansion request, either `Approved` or `Rejected`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModerateCategoryExpansion {
+    pub status: StoreCategoryStatus,
+}
+
+/// Error code returned when base product is created in a category the store does not sell in
+pub const CATEGORY_NOT_DECLARED: &'static str = "CATEGORY_NOT_DECLARED";
@@ -0,0 +1,29 @@
+//! Messages users send to stores through the contact form
+use stq_types::{StoreId, UserId};
+
+/// Payload for contacting the store
+#[derive(Serialize, Deserialize, Validate, Clone, Debug)]
+pub struct StoreContactMessage {
+    #[validate(length(min = "1", max = "100"))]
+    pub name: String,
+    /// Address the store replies to
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+    #[validate(length(min = "1", max = "100"))]
+    pub subject: Option<String>,
+    #[validate(length(min = "1", max = "3000"))]
+    pub text: String,
+}
+
+/// Message relayed to the notifications microservice, it is delivered to the store owner
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreContactNotification {
+    pub store_id: StoreId,
+    pub store_user_id: UserId,
+    pub store_email: Option<String>,
+    pub sender_id: UserId,
+    pub sender_name: String,
+    pub sender_email: String,
+    pub subject: Option<String>,
+    pub text: String,
+}
@@ -0,0 +1,63 @@
+//! Frequently asked questions of the store, shown on the store page
+use std::time::SystemTime;
+
+use serde_json;
+use validator::Validate;
+
+use stq_types::StoreId;
+
+use models::validation_rules::*;
+use schema::store_faqs;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "store_faqs"]
+pub struct StoreFaq {
+    pub id: i32,
+    pub store_id: StoreId,
+    pub question: serde_json::Value,
+    pub answer: serde_json::Value,
+    pub position: i32,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+/// Payload for creating question, store is taken from the path
+#[derive(Serialize, Deserialize, Clone, Validate, Debug)]
+pub struct NewStoreFaqPayload {
+    #[validate(custom = "validate_translation")]
+    pub question: serde_json::Value,
+    #[validate(custom = "validate_translation")]
+    pub answer: serde_json::Value,
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "store_faqs"]
+pub struct NewStoreFaq {
+    pub store_id: StoreId,
+    pub question: serde_json::Value,
+    pub answer: serde_json::Value,
+    pub position: i32,
+}
+
+impl NewStoreFaq {
+    pub fn new(store_id: StoreId, payload: NewStoreFaqPayload) -> Self {
+        Self {
+            store_id,
+            question: payload.question,
+            answer: payload.answer,
+            position: payload.position,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Validate, Debug)]
+#[table_name = "store_faqs"]
+pub struct UpdateStoreFaq {
+    #[validate(custom = "validate_translation")]
+    pub question: Option<serde_json::Value>,
+    #[validate(custom = "validate_translation")]
+    pub answer: Option<serde_json::Value>,
+    pub position: Option<i32>,
+}
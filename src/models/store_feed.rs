@@ -0,0 +1,180 @@
+//! Feed of recently published products of the store, rendered as RSS and sitemap
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use failure::Error as FailureError;
+use failure::Fail;
+use serde_json;
+use treexml::{Document, Element, ElementBuilder, XmlVersion};
+
+use stq_types::{BaseProductId, StoreId};
+
+use errors::Error;
+use models::{BaseProduct, Store};
+
+/// Number of the latest published products included in the feed
+pub const STORE_FEED_SIZE: i64 = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreFeedItem {
+    pub base_product_id: BaseProductId,
+    pub name: serde_json::Value,
+    pub short_description: serde_json::Value,
+    pub updated_at: SystemTime,
+}
+
+/// Translations are kept as is, so one cached feed is rendered in any language
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreFeed {
+    pub store_id: StoreId,
+    pub name: serde_json::Value,
+    pub short_description: serde_json::Value,
+    pub default_language: String,
+    pub items: Vec<StoreFeedItem>,
+    pub generated_at: SystemTime,
+}
+
+impl StoreFeed {
+    pub fn new(store: Store, base_products: Vec<BaseProduct>, now: SystemTime) -> Self {
+        let items = base_products
+            .into_iter()
+            .map(|base_product| StoreFeedItem {
+                base_product_id: base_product.id,
+                name: base_product.name,
+                short_description: base_product.short_description,
+                updated_at: base_product.updated_at,
+            })
+            .collect();
+
+        Self {
+            store_id: store.id,
+            name: store.name,
+            short_description: store.short_description,
+            default_language: store.default_language,
+            items,
+            generated_at: now,
+        }
+    }
+
+    /// Renders RSS 2.0 channel with titles in `lang`, links lead to the storefront at `storefront_url`
+    pub fn to_rss(&self, storefront_url: &str, lang: &str) -> Result<String, FailureError> {
+        let store_url = format!("{}/store/{}", storefront_url, self.store_id);
+
+        let mut channel = ElementBuilder::new("channel").element();
+        channel.children.push(text_element("title", self.translation(&self.name, lang)));
+        channel.children.push(text_element("link", store_url.clone()));
+        channel
+            .children
+            .push(text_element("description", self.translation(&self.short_description, lang)));
+        channel.children.push(text_element("language", lang.to_string()));
+        channel
+            .children
+            .push(text_element("lastBuildDate", DateTime::<Utc>::from(self.generated_at).to_rfc2822()));
+
+        for item in &self.items {
+            let link = format!("{}/products/{}", store_url, item.base_product_id);
+            let mut element = ElementBuilder::new("item").element();
+            element.children.push(text_element("title", self.translation(&item.name, lang)));
+            element.children.push(text_element("link", link.clone()));
+            element.children.push(text_element("guid", link));
+            element
+                .children
+                .push(text_element("description", self.translation(&item.short_description, lang)));
+            element
+                .children
+                .push(text_element("pubDate", DateTime::<Utc>::from(item.updated_at).to_rfc2822()));
+            channel.children.push(element);
+        }
+
+        let mut rss = ElementBuilder::new("rss").attr("version", "2.0").element();
+        rss.children.push(channel);
+        write_document(rss)
+    }
+
+    /// Renders sitemap with the store page and pages of the feed products
+    pub fn to_sitemap(&self, storefront_url: &str) -> Result<String, FailureError> {
+        let store_url = format!("{}/store/{}", storefront_url, self.store_id);
+
+        let mut urlset = ElementBuilder::new("urlset")
+            .attr("xmlns", "http://www.sitemaps.org/schemas/sitemap/0.9")
+            .element();
+        urlset.children.push(url_element(store_url.clone(), self.generated_at));
+        for item in &self.items {
+            let link = format!("{}/products/{}", store_url, item.base_product_id);
+            urlset.children.push(url_element(link, item.updated_at));
+        }
+        write_document(urlset)
+    }
+
+    /// Text in `lang`, falls back to the default language of the store and then to any translation
+    fn translation(&self, translations: &serde_json::Value, lang: &str) -> String {
+        let translations = match translations.as_array() {
+            Some(translations) => translations,
+            None => return String::new(),
+        };
+        let text_in = |lang: &str| {
+            translations
+                .iter()
+                .find(|translation| translation["lang"].as_str() == Some(lang))
+                .and_then(|translation| translation["text"].as_str())
+        };
+        text_in(lang)
+            .or_else(|| text_in(&self.default_language))
+            .or_else(|| translations.iter().filter_map(|translation| translation["text"].as_str()).next())
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+fn text_element(name: &str, text: String) -> Element {
+    ElementBuilder::new(name).text(text).element()
+}
+
+fn url_element(loc: String, last_modified: SystemTime) -> Element {
+    let mut url = ElementBuilder::new("url").element();
+    url.children.push(text_element("loc", loc));
+    url.children
+        .push(text_element("lastmod", DateTime::<Utc>::from(last_modified).format("%Y-%m-%d").to_string()));
+    url
+}
+
+fn write_document(root: Element) -> Result<String, FailureError> {
+    let document = Document {
+        encoding: "UTF-8".to_string(),
+        root: Some(root),
+        version: XmlVersion::Version10,
+    };
+    let mut data: Vec<u8> = vec![];
+    document
+        .write(&mut data)
+        .map_err(|e| e.context("Failed to write xml document").context(Error::Parse))?;
+    String::from_utf8(data).map_err(|e| e.context("Xml document is not valid utf-8").context(Error::Parse).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_feed_rss() {
+        let feed = StoreFeed {
+            store_id: StoreId(1),
+            name: json!([{"lang": "en", "text": "Store"}]),
+            short_description: json!([{"lang": "en", "text": "Gadgets"}]),
+            default_language: "en".to_string(),
+            items: vec![StoreFeedItem {
+                base_product_id: BaseProductId(7),
+                name: json!([{"lang": "en", "text": "Phone"}, {"lang": "ru", "text": "Телефон"}]),
+                short_description: json!([{"lang": "en", "text": "Smart"}]),
+                updated_at: SystemTime::now(),
+            }],
+            generated_at: SystemTime::now(),
+        };
+
+        let rss = feed.to_rss("https://shop.test", "ru").unwrap();
+
+        assert!(rss.contains("<title>Телефон</title>"));
+        assert!(rss.contains("<title>Store</title>"));
+        assert!(rss.contains("<link>https://shop.test/store/1/products/7</link>"));
+    }
+}
@@ -0,0 +1,67 @@
+//! Onboarding checklist of the store, computed from the store and its products on every request
+use stq_types::StoreId;
+
+use models::{has_translation, Store};
+use schema::stores;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// Name and short description in the default language, logo and country are set
+    ProfileFilled,
+    EmailVerified,
+    /// At least one product is published
+    ProductPublished,
+    PayoutInfoPresent,
+    ShippingProfileSet,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OnboardingItem {
+    pub step: OnboardingStep,
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoreOnboarding {
+    pub store_id: StoreId,
+    /// Steps in the order they are shown to the seller
+    pub items: Vec<OnboardingItem>,
+    pub is_completed: bool,
+}
+
+impl StoreOnboarding {
+    pub fn new(store: &Store, published_products_count: i32) -> Self {
+        let profile_filled = has_translation(&store.name, &store.default_language)
+            && has_translation(&store.short_description, &store.default_language)
+            && store.logo.as_ref().map(|logo| !logo.is_empty()).unwrap_or(false)
+            && store.country.is_some();
+        let email_verified = store.email.is_some() && store.email_verified;
+
+        let items: Vec<_> = vec![
+            (OnboardingStep::ProfileFilled, profile_filled),
+            (OnboardingStep::EmailVerified, email_verified),
+            (OnboardingStep::ProductPublished, published_products_count > 0),
+            (OnboardingStep::PayoutInfoPresent, store.payout_info_present),
+            (OnboardingStep::ShippingProfileSet, store.shipping_profile_set),
+        ]
+        .into_iter()
+        .map(|(step, done)| OnboardingItem { step, done })
+        .collect();
+
+        Self {
+            store_id: store.id,
+            is_completed: items.iter().all(|item| item.done),
+            items,
+        }
+    }
+}
+
+/// Payload pushed from billing and delivery when the store payout details or shipping profile change
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Debug)]
+#[table_name = "stores"]
+pub struct SetStoreOnboarding {
+    pub payout_info_present: Option<bool>,
+    pub shipping_profile_set: Option<bool>,
+}
+
@@ -0,0 +1,139 @@
+//! Opening hours of the store. Intervals are in local time of the store,
+//! timezone of the store is kept as a UTC offset like `+03:00`
+use chrono::{Datelike, FixedOffset, NaiveDateTime, NaiveTime, Weekday};
+
+/// Weekdays in the order of `OpeningHours` fields
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Format of interval bounds, `09:30`
+pub const OPENING_TIME_FORMAT: &'static str = "%H:%M";
+
+/// Hours the store is open at, one interval is `opens_at` inclusive to `closes_at` exclusive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OpeningInterval {
+    pub opens_at: String,
+    pub closes_at: String,
+}
+
+impl OpeningInterval {
+    /// Bounds of the interval, `None` when they are not in `OPENING_TIME_FORMAT`
+    pub fn parse(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let opens_at = NaiveTime::parse_from_str(&self.opens_at, OPENING_TIME_FORMAT).ok()?;
+        let closes_at = NaiveTime::parse_from_str(&self.closes_at, OPENING_TIME_FORMAT).ok()?;
+        Some((opens_at, closes_at))
+    }
+}
+
+/// Opening intervals per weekday, the store is closed on days without intervals
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OpeningHours {
+    #[serde(default)]
+    pub monday: Vec<OpeningInterval>,
+    #[serde(default)]
+    pub tuesday: Vec<OpeningInterval>,
+    #[serde(default)]
+    pub wednesday: Vec<OpeningInterval>,
+    #[serde(default)]
+    pub thursday: Vec<OpeningInterval>,
+    #[serde(default)]
+    pub friday: Vec<OpeningInterval>,
+    #[serde(default)]
+    pub saturday: Vec<OpeningInterval>,
+    #[serde(default)]
+    pub sunday: Vec<OpeningInterval>,
+}
+
+impl OpeningHours {
+    pub fn intervals(&self, weekday: Weekday) -> &[OpeningInterval] {
+        match weekday {
+            Weekday::Mon => &self.monday,
+            Weekday::Tue => &self.tuesday,
+            Weekday::Wed => &self.wednesday,
+            Weekday::Thu => &self.thursday,
+            Weekday::Fri => &self.friday,
+            Weekday::Sat => &self.saturday,
+            Weekday::Sun => &self.sunday,
+        }
+    }
+
+    /// Checks that the store is open at the local time of the store
+    pub fn is_open_at(&self, local: NaiveDateTime) -> bool {
+        let time = local.time();
+        self.intervals(local.weekday())
+            .iter()
+            .filter_map(OpeningInterval::parse)
+            .any(|(opens_at, closes_at)| opens_at <= time && time < closes_at)
+    }
+}
+
+/// Parses UTC offset of the store timezone, `+03:00` or `-05:30`
+pub fn parse_utc_offset(timezone: &str) -> Option<FixedOffset> {
+    let bytes = timezone.as_bytes();
+    if bytes.len() != 6 || bytes[3] != b':' || ![1, 2, 4, 5].iter().all(|&i| bytes[i].is_ascii_digit()) {
+        return None;
+    }
+    let sign = match bytes[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let hours = timezone[1..3].parse::<i32>().ok()?;
+    let minutes = timezone[4..6].parse::<i32>().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::*;
+
+    fn interval(opens_at: &str, closes_at: &str) -> OpeningInterval {
+        OpeningInterval {
+            opens_at: opens_at.to_string(),
+            closes_at: closes_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_open_at() {
+        let opening_hours = OpeningHours {
+            monday: vec![interval("09:00", "13:00"), interval("14:00", "18:00")],
+            ..Default::default()
+        };
+        // 2020-03-02 is monday
+        let monday = NaiveDate::from_ymd(2020, 3, 2);
+
+        assert!(opening_hours.is_open_at(monday.and_hms(9, 0, 0)));
+        assert!(!opening_hours.is_open_at(monday.and_hms(13, 30, 0)));
+        assert!(!opening_hours.is_open_at(monday.and_hms(18, 0, 0)));
+        assert!(!opening_hours.is_open_at(NaiveDate::from_ymd(2020, 3, 3).and_hms(10, 0, 0)));
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("+03:00"), Some(FixedOffset::east(3 * 3600)));
+        assert_eq!(parse_utc_offset("-05:30"), Some(FixedOffset::west(5 * 3600 + 30 * 60)));
+        assert_eq!(parse_utc_offset("03:00"), None);
+        assert_eq!(parse_utc_offset("+25:00"), None);
+        assert_eq!(parse_utc_offset("Europe/Moscow"), None);
+
+        // 07:00 UTC is 10:00 in Moscow
+        let now = Utc.ymd(2020, 3, 2).and_hms(7, 0, 0);
+        let local = now.with_timezone(&parse_utc_offset("+03:00").unwrap()).naive_local();
+        assert_eq!(local, NaiveDate::from_ymd(2020, 3, 2).and_hms(10, 0, 0));
+    }
+}
@@ -0,0 +1,124 @@
+//! Manual order of products on the storefront, set by the store manager
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use stq_types::{BaseProductId, StoreId};
+
+use schema::store_product_positions;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "store_product_positions"]
+pub struct StoreProductPosition {
+    pub id: i32,
+    pub store_id: StoreId,
+    pub base_product_id: BaseProductId,
+    pub position: i32,
+    pub pinned: bool,
+    pub created_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "store_product_positions"]
+pub struct NewStoreProductPosition {
+    pub store_id: StoreId,
+    pub base_product_id: BaseProductId,
+    pub position: i32,
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreProductsOrderItem {
+    pub base_product_id: BaseProductId,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Payload for ordering products of the store, products are ranked in the listed order.
+/// The previous order is replaced, products missing in the list go after the ranked ones
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SetStoreProductsOrder {
+    pub products: Vec<StoreProductsOrderItem>,
+}
+
+impl SetStoreProductsOrder {
+    pub fn into_positions(self, store_id: StoreId) -> Vec<NewStoreProductPosition> {
+        self.products
+            .into_iter()
+            .enumerate()
+            .map(|(position, item)| NewStoreProductPosition {
+                store_id,
+                base_product_id: item.base_product_id,
+                position: position as i32,
+                pinned: item.pinned,
+            })
+            .collect()
+    }
+}
+
+/// Sorts base products in storefront order: pinned products go first, then the other ranked ones,
+/// products without position follow ordered by id
+pub fn sort_in_storefront_order(base_product_ids: &mut [BaseProductId], positions: &[StoreProductPosition]) {
+    let ranks = positions
+        .iter()
+        .map(|position| (position.base_product_id.0, (!position.pinned, position.position)))
+        .collect::<HashMap<_, _>>();
+    base_product_ids.sort_by_key(|base_product_id| match ranks.get(&base_product_id.0) {
+        Some(&(not_pinned, position)) => (false, not_pinned, position, base_product_id.0),
+        None => (true, true, 0, base_product_id.0),
+    });
+}
+
+/// Page of base products in storefront order, the page starts at `from`.
+/// When `from` is not among the products, the page starts at the first product with greater id
+pub fn storefront_page(base_product_ids: Vec<BaseProductId>, from: BaseProductId, count: i32) -> Vec<BaseProductId> {
+    let start = base_product_ids
+        .iter()
+        .position(|base_product_id| *base_product_id == from)
+        .or_else(|| base_product_ids.iter().position(|base_product_id| base_product_id.0 >= from.0))
+        .unwrap_or_else(|| base_product_ids.len());
+    base_product_ids.into_iter().skip(start).take(count.max(0) as usize).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    fn position(base_product_id: i32, position: i32, pinned: bool) -> StoreProductPosition {
+        StoreProductPosition {
+            id: base_product_id,
+            store_id: StoreId(1),
+            base_product_id: BaseProductId(base_product_id),
+            position,
+            pinned,
+            created_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_sort_in_storefront_order() {
+        let mut base_product_ids = (1..7).map(BaseProductId).collect::<Vec<_>>();
+        let positions = vec![position(5, 0, false), position(2, 1, true), position(4, 2, false)];
+
+        sort_in_storefront_order(&mut base_product_ids, &positions);
+
+        let sorted = base_product_ids.into_iter().map(|id| id.0).collect::<Vec<_>>();
+        assert_eq!(sorted, vec![2, 5, 4, 1, 3, 6]);
+    }
+
+    #[test]
+    fn test_storefront_page() {
+        let base_product_ids = vec![2, 5, 4, 1, 3, 6].into_iter().map(BaseProductId).collect::<Vec<_>>();
+        let page = |from, count| {
+            storefront_page(base_product_ids.clone(), BaseProductId(from), count)
+                .into_iter()
+                .map(|id| id.0)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(page(5, 3), vec![5, 4, 1]);
+        assert_eq!(page(0, 2), vec![2, 5]);
+        assert_eq!(page(7, 2), Vec::<i32>::new());
+    }
+}
@@ -0,0 +1,54 @@
+//! Public store page data, collected in one response
+use std::time::SystemTime;
+
+use serde_json;
+
+use models::{ProductCategories, Store};
+
+/// Number of categories shown on the store page
+pub const STORE_PROFILE_TOP_CATEGORIES: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreBadge {
+    /// All contacts of the store are confirmed
+    Verified,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreProfile {
+    pub store: Store,
+    pub published_products_count: i64,
+    pub rating: f64,
+    pub badges: Vec<StoreBadge>,
+    pub member_since: SystemTime,
+    /// Categories with the most products, in descending order
+    pub top_categories: Vec<ProductCategories>,
+}
+
+impl StoreProfile {
+    pub fn new(store: Store, published_products_count: i64) -> Self {
+        let mut badges = vec![];
+        if store.is_verified() {
+            badges.push(StoreBadge::Verified);
+        }
+
+        let mut top_categories = store
+            .product_categories
+            .clone()
+            .and_then(|categories| serde_json::from_value::<Vec<ProductCategories>>(categories).ok())
+            .unwrap_or_default();
+        top_categories.retain(|category| category.count > 0);
+        top_categories.sort_by(|a, b| b.count.cmp(&a.count));
+        top_categories.truncate(STORE_PROFILE_TOP_CATEGORIES);
+
+        Self {
+            rating: store.rating,
+            member_since: store.created_at,
+            badges,
+            published_products_count,
+            top_categories,
+            store,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Models for store product quotas
+use config::ProductQuota;
+use stq_types::StoreId;
+
+/// Error code returned when store has no room for new base products
+pub const QUOTA_EXCEEDED: &'static str = "QUOTA_EXCEEDED";
+
+/// Product quota usage of the store
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreQuota {
+    pub store_id: StoreId,
+    pub plan: Option<String>,
+    /// `None` means the store can have any number of products
+    pub limit: Option<i32>,
+    pub used: i32,
+}
+
+impl StoreQuota {
+    pub fn new(store_id: StoreId, quota_plan: Option<String>, config: Option<&ProductQuota>, used: i32) -> Self {
+        let plan = quota_plan.or_else(|| config.map(|c| c.default_plan.clone()));
+        let limit = match (config, plan.as_ref()) {
+            (Some(config), Some(plan)) => config.plans.get(plan).cloned(),
+            _ => None,
+        };
+
+        Self {
+            store_id,
+            plan,
+            limit,
+            used,
+        }
+    }
+
+    pub fn is_exceeded_by(&self, new_products: i32) -> bool {
+        self.limit.map(|limit| self.used + new_products > limit).unwrap_or(false)
+    }
+}
+
+/// Payload pushed from billing when store plan changes
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SetStoreQuotaPlan {
+    pub plan: Option<String>,
+}
@@ -0,0 +1,48 @@
+//! Previous slugs of stores, links with an old slug lead to the current slug of the store
+use std::time::SystemTime;
+
+use stq_types::StoreId;
+
+use models::{Store, StoreWithOpeningStatus};
+use schema::store_slug_history;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "store_slug_history"]
+pub struct StoreSlugHistory {
+    pub id: i32,
+    pub store_id: StoreId,
+    pub slug: String,
+    pub created_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "store_slug_history"]
+pub struct NewStoreSlugHistory {
+    pub store_id: StoreId,
+    pub slug: String,
+}
+
+/// Store requested by the old slug, frontends redirect to `moved_to` with 301
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreMoved {
+    pub store_id: StoreId,
+    /// Current slug of the store
+    pub moved_to: String,
+}
+
+impl From<Store> for StoreMoved {
+    fn from(store: Store) -> Self {
+        Self {
+            store_id: store.id,
+            moved_to: store.slug,
+        }
+    }
+}
+
+/// Store found by the slug, either the current or one of the previous ones
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum StoreBySlug {
+    Found(StoreWithOpeningStatus),
+    Moved(StoreMoved),
+}
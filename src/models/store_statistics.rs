@@ -0,0 +1,102 @@
+//! Aggregate statistics of the store catalog shown on the store dashboard
+use diesel::sql_types::{BigInt, Double, VarChar};
+
+use stq_static_resources::ModerationStatus;
+use stq_types::StoreId;
+
+/// Active base products of the store with the same moderation status, read by grouped query
+#[derive(QueryableByName, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BaseProductsStatusGroup {
+    #[sql_type = "VarChar"]
+    pub status: ModerationStatus,
+    #[sql_type = "BigInt"]
+    pub base_products: i64,
+    #[sql_type = "BigInt"]
+    pub views: i64,
+    /// Base products having rating
+    #[sql_type = "BigInt"]
+    pub rated: i64,
+    #[sql_type = "Double"]
+    pub rating_sum: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusCount {
+    pub status: ModerationStatus,
+    pub count: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoreStatistics {
+    pub store_id: StoreId,
+    /// Counts of active base products by moderation status, statuses without base products are missing
+    pub base_products_by_status: Vec<StatusCount>,
+    pub base_products: i64,
+    pub total_views: i64,
+    /// Average rating of rated base products, missing when nothing is rated
+    pub average_rating: Option<f64>,
+    /// Active coupons which are not expired
+    pub active_coupons: i64,
+    /// Active variants of active base products
+    pub variants: i64,
+}
+
+impl StoreStatistics {
+    pub fn new(store_id: StoreId, groups: Vec<BaseProductsStatusGroup>, active_coupons: i64, variants: i64) -> Self {
+        let base_products = groups.iter().map(|group| group.base_products).sum();
+        let total_views = groups.iter().map(|group| group.views).sum();
+        let rated: i64 = groups.iter().map(|group| group.rated).sum();
+        let rating_sum: f64 = groups.iter().map(|group| group.rating_sum).sum();
+        let average_rating = if rated > 0 { Some(rating_sum / rated as f64) } else { None };
+
+        Self {
+            store_id,
+            base_products_by_status: groups
+                .into_iter()
+                .map(|group| StatusCount {
+                    status: group.status,
+                    count: group.base_products,
+                })
+                .collect(),
+            base_products,
+            total_views,
+            average_rating,
+            active_coupons,
+            variants,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_statistics() {
+        let groups = vec![
+            BaseProductsStatusGroup {
+                status: ModerationStatus::Published,
+                base_products: 3,
+                views: 120,
+                rated: 2,
+                rating_sum: 9.0,
+            },
+            BaseProductsStatusGroup {
+                status: ModerationStatus::Draft,
+                base_products: 2,
+                views: 0,
+                rated: 0,
+                rating_sum: 0.0,
+            },
+        ];
+
+        let statistics = StoreStatistics::new(StoreId(1), groups, 1, 7);
+        assert_eq!(statistics.base_products, 5);
+        assert_eq!(statistics.total_views, 120);
+        assert_eq!(statistics.average_rating, Some(4.5));
+        assert_eq!(statistics.base_products_by_status.len(), 2);
+
+        let statistics = StoreStatistics::new(StoreId(1), vec![], 0, 0);
+        assert_eq!(statistics.average_rating, None);
+    }
+}
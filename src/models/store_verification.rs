@@ -0,0 +1,128 @@
+//! Models for confirming store email and phone
+use std::time::{Duration, SystemTime};
+
+use rand::{thread_rng, Rng};
+
+use stq_types::StoreId;
+
+use schema::store_verification_codes;
+
+/// Verification code expires after this number of seconds
+pub const VERIFICATION_CODE_TTL_S: u64 = 15 * 60;
+/// Verification code is discarded after this number of wrong guesses
+pub const MAX_VERIFICATION_ATTEMPTS: i32 = 5;
+/// New code of the store contact can be sent after this number of seconds since the previous one
+pub const VERIFICATION_RESEND_COOLDOWN_S: u64 = 60;
+/// Window in seconds limiting the number of codes sent to the store contact
+pub const VERIFICATION_CODES_WINDOW_S: u64 = 24 * 60 * 60;
+/// Codes sent to the store contact within `VERIFICATION_CODES_WINDOW_S`,
+/// together with `MAX_VERIFICATION_ATTEMPTS` it bounds the guesses per day
+pub const MAX_VERIFICATION_CODES_PER_WINDOW: i64 = 5;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, DieselTypes)]
+pub enum VerificationChannel {
+    Email,
+    Phone,
+}
+
+/// Verification of the store by moderators, verified stores are boosted in search
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, DieselTypes)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreVerificationStatus {
+    Unverified,
+    /// Documents of the store are checked by moderators
+    Pending,
+    Verified,
+}
+
+impl Default for StoreVerificationStatus {
+    fn default() -> Self {
+        StoreVerificationStatus::Unverified
+    }
+}
+
+/// Payload for verifying the store. For moderators
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VerifyStore {
+    pub verification_status: StoreVerificationStatus,
+    /// Badges shown on the store page like `official_brand`
+    #[serde(default)]
+    pub badges: Vec<String>,
+}
+
+/// Code sent to the store contact
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
+#[table_name = "store_verification_codes"]
+pub struct StoreVerificationCode {
+    pub id: i32,
+    pub store_id: StoreId,
+    pub channel: VerificationChannel,
+    /// Email or phone the code was sent to, the contact may be changed while code is pending
+    pub destination: String,
+    pub code: String,
+    pub attempts: i32,
+    pub expires_at: SystemTime,
+    pub created_at: SystemTime,
+}
+
+impl StoreVerificationCode {
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at <= now || self.attempts >= MAX_VERIFICATION_ATTEMPTS
+    }
+
+    /// Compares the code in constant time, so response times do not reveal matching digits
+    pub fn matches(&self, code: &str) -> bool {
+        let expected = self.code.as_bytes();
+        let code = code.as_bytes();
+        expected.len() == code.len() && expected.iter().zip(code).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+    }
+
+    /// Whether a new code can be sent to the contact after this one
+    pub fn is_cooling_down(&self, now: SystemTime) -> bool {
+        self.created_at + Duration::from_secs(VERIFICATION_RESEND_COOLDOWN_S) > now
+    }
+}
+
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "store_verification_codes"]
+pub struct NewStoreVerificationCode {
+    pub store_id: StoreId,
+    pub channel: VerificationChannel,
+    pub destination: String,
+    pub code: String,
+    pub expires_at: SystemTime,
+}
+
+impl NewStoreVerificationCode {
+    /// Generates new six digit code
+    pub fn new(store_id: StoreId, channel: VerificationChannel, destination: String) -> Self {
+        let code = format!("{:06}", thread_rng().gen_range(0, 1_000_000));
+        Self {
+            store_id,
+            channel,
+            destination,
+            code,
+            expires_at: SystemTime::now() + Duration::from_secs(VERIFICATION_CODE_TTL_S),
+        }
+    }
+}
+
+/// Payload for sending verification code
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SendStoreVerification {
+    pub channel: VerificationChannel,
+}
+
+/// Sent code is not returned, only the time it is valid till
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StoreVerificationSent {
+    pub channel: VerificationChannel,
+    pub expires_at: SystemTime,
+}
+
+/// Payload for confirming store contact with received code
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConfirmStoreVerification {
+    pub channel: VerificationChannel,
+    pub code: String,
+}
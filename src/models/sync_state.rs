@@ -0,0 +1,87 @@
+//! Models for downstream consumers that follow catalog changes by `kafka_update_no`
+use std::fmt;
+use std::str::FromStr;
+
+use models::{BaseProduct, RawProduct, Store};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncEntityType {
+    Store,
+    BaseProduct,
+    Product,
+}
+
+impl SyncEntityType {
+    pub fn all() -> Vec<SyncEntityType> {
+        vec![SyncEntityType::Store, SyncEntityType::BaseProduct, SyncEntityType::Product]
+    }
+}
+
+impl FromStr for SyncEntityType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "store" => Ok(SyncEntityType::Store),
+            "base_product" => Ok(SyncEntityType::BaseProduct),
+            "product" => Ok(SyncEntityType::Product),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for SyncEntityType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SyncEntityType::Store => write!(f, "store"),
+            SyncEntityType::BaseProduct => write!(f, "base_product"),
+            SyncEntityType::Product => write!(f, "product"),
+        }
+    }
+}
+
+/// Max `kafka_update_no` and number of rows of the entity type, inactive rows included
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncEntityState {
+    pub entity_type: SyncEntityType,
+    pub max_update_no: Option<i32>,
+    pub count: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncState {
+    pub entities: Vec<SyncEntityState>,
+    /// Schema version the indexer writes into elastic documents
+    pub schema_version: u32,
+}
+
+/// Range of `kafka_update_no` to backfill, both bounds are inclusive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyncRange {
+    pub from_no: i32,
+    pub to_no: Option<i32>,
+}
+
+/// Entities with `kafka_update_no` in the requested range ordered by `kafka_update_no` and id
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", content = "items", rename_all = "snake_case")]
+pub enum SyncEntities {
+    Store(Vec<Store>),
+    BaseProduct(Vec<BaseProduct>),
+    Product(Vec<RawProduct>),
+}
+
+impl SyncEntities {
+    pub fn len(&self) -> usize {
+        match *self {
+            SyncEntities::Store(ref items) => items.len(),
+            SyncEntities::BaseProduct(ref items) => items.len(),
+            SyncEntities::Product(ref items) => items.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
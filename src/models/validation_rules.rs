@@ -9,7 +9,9 @@ use validator::validate_length;
 use validator::ValidationError;
 use validator::Validator;
 
-use models::{BaseProduct, Coupon, Store};
+use models::{
+    parse_utc_offset, BaseProduct, Coupon, MatchAttrValue, OpeningHours, Store, CRYPTO_PRICE_PRECISION, MAX_PRODUCT_PHOTOS, WEEKDAYS,
+};
 use stq_static_resources::Translation;
 use stq_types::{CouponCode, ProductPrice};
 
@@ -69,6 +71,73 @@ pub fn validate_not_empty<T: AsRef<str>>(val: T) -> Result<(), ValidationError>
     }
 }
 
+/// Synonym needs at least two distinct terms, separators of the synonym rule format are not allowed inside terms
+pub fn validate_synonym_terms(terms: &[String]) -> Result<(), ValidationError> {
+    let mut distinct = terms.iter().map(|term| term.trim().to_lowercase()).collect::<Vec<_>>();
+    distinct.sort();
+    distinct.dedup();
+
+    if distinct.len() < 2 {
+        return Err(ValidationError {
+            code: Cow::from("terms"),
+            message: Some(Cow::from("Synonym must have at least two different terms.")),
+            params: HashMap::new(),
+        });
+    }
+
+    if distinct.iter().any(|term| term.is_empty() || term.contains(',') || term.contains("=>")) {
+        return Err(ValidationError {
+            code: Cow::from("terms"),
+            message: Some(Cow::from("Terms must not be empty or contain ',' and '=>'.")),
+            params: HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Photos of the product are given by distinct non empty urls, at most `MAX_PRODUCT_PHOTOS` of them
+pub fn validate_photo_urls(urls: &[String]) -> Result<(), ValidationError> {
+    let mut distinct = urls.iter().map(|url| url.trim()).collect::<Vec<_>>();
+    distinct.sort();
+    distinct.dedup();
+
+    if distinct.is_empty() || distinct.len() != urls.len() || distinct.iter().any(|url| url.is_empty()) {
+        return Err(ValidationError {
+            code: Cow::from("urls"),
+            message: Some(Cow::from("Urls must not be empty or contain the same url twice.")),
+            params: HashMap::new(),
+        });
+    }
+
+    if urls.len() > MAX_PRODUCT_PHOTOS {
+        return Err(ValidationError {
+            code: Cow::from("urls"),
+            message: Some(Cow::from("Too many photos of the product.")),
+            params: HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Products are matched by at least one attribute, every attribute is given once
+pub fn validate_match_attributes(attributes: &[MatchAttrValue]) -> Result<(), ValidationError> {
+    let mut attr_ids = attributes.iter().map(|attribute| attribute.attr_id.0).collect::<Vec<_>>();
+    attr_ids.sort();
+    attr_ids.dedup();
+
+    if attr_ids.is_empty() || attr_ids.len() != attributes.len() {
+        return Err(ValidationError {
+            code: Cow::from("attributes"),
+            message: Some(Cow::from("Attributes must not be empty or contain the same attribute twice.")),
+            params: HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
+
 pub fn validate_non_negative<T: Into<f64>>(val: T) -> Result<(), ValidationError> {
     if val.into() > 0f64 {
         Ok(())
@@ -81,7 +150,16 @@ pub fn validate_non_negative<T: Into<f64>>(val: T) -> Result<(), ValidationError
     }
 }
 
+/// Prices also have to fit into `Money` minor units of every currency
 pub fn validate_non_negative_price(price: &ProductPrice) -> Result<(), ValidationError> {
+    let max_price = i64::max_value() as f64 / 10f64.powi(CRYPTO_PRICE_PRECISION);
+    if !price.0.is_finite() || price.0 >= max_price {
+        return Err(ValidationError {
+            code: Cow::from("value"),
+            message: Some(Cow::from("Price is too large.")),
+            params: HashMap::new(),
+        });
+    }
     validate_non_negative(price.0)
 }
 
@@ -213,20 +291,133 @@ pub fn validate_translation(text: &serde_json::Value) -> Result<(), ValidationEr
 }
 
 pub fn validate_urls(text: &serde_json::Value) -> Result<(), ValidationError> {
-    serde_json::from_value::<Vec<String>>(text.clone()).map_err(|_| ValidationError {
+    let urls = serde_json::from_value::<Vec<String>>(text.clone()).map_err(|_| ValidationError {
         code: Cow::from("urls"),
         message: Some(Cow::from("Invalid format of urls. Must be json array of strings.")),
         params: HashMap::new(),
     })?;
 
+    if urls.len() > MAX_PRODUCT_PHOTOS {
+        return Err(ValidationError {
+            code: Cow::from("urls"),
+            message: Some(Cow::from("Too many photos of the product.")),
+            params: HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that opening hours of every weekday are valid intervals in `HH:MM` format which do not overlap
+pub fn validate_opening_hours(value: &serde_json::Value) -> Result<(), ValidationError> {
+    let invalid = |message: &'static str| ValidationError {
+        code: Cow::from("opening_hours"),
+        message: Some(Cow::from(message)),
+        params: HashMap::new(),
+    };
+
+    let opening_hours = serde_json::from_value::<OpeningHours>(value.clone())
+        .map_err(|_| invalid("Invalid format of opening hours. Must be intervals per weekday."))?;
+
+    for weekday in WEEKDAYS.iter() {
+        let mut intervals = opening_hours
+            .intervals(*weekday)
+            .iter()
+            .map(|interval| interval.parse().ok_or_else(|| invalid("Opening hours must be in HH:MM format.")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if intervals.iter().any(|&(opens_at, closes_at)| opens_at >= closes_at) {
+            return Err(invalid("Store must open before it closes."));
+        }
+
+        intervals.sort();
+        if intervals.windows(2).any(|pair| pair[1].0 < pair[0].1) {
+            return Err(invalid("Opening hours of a weekday must not overlap."));
+        }
+    }
+
     Ok(())
 }
 
+/// Checks that timezone is a UTC offset like `+03:00`
+pub fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    match parse_utc_offset(timezone) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError {
+            code: Cow::from("timezone"),
+            message: Some(Cow::from("Timezone must be UTC offset in +HH:MM format.")),
+            params: HashMap::new(),
+        }),
+    }
+}
+
+/// Checks product identifiers Google Shopping accepts: GTIN-8, UPC (GTIN-12), EAN and ISBN-13 (GTIN-13), GTIN-14 and ISBN-10
+pub fn validate_gtin<T: AsRef<str>>(val: T) -> Result<(), ValidationError> {
+    let code = val.as_ref();
+    if is_valid_gtin(code) || is_valid_isbn10(code) {
+        Ok(())
+    } else {
+        Err(ValidationError {
+            code: Cow::from("gtin"),
+            message: Some(Cow::from("Invalid GTIN, EAN or ISBN code.")),
+            params: HashMap::new(),
+        })
+    }
+}
+
+fn is_valid_gtin(code: &str) -> bool {
+    let digits = match code.chars().map(|c| c.to_digit(10)).collect::<Option<Vec<u32>>>() {
+        Some(ref digits) if [8, 12, 13, 14].contains(&digits.len()) => digits.clone(),
+        _ => return false,
+    };
+
+    match digits.split_last() {
+        Some((check_digit, body)) => {
+            // weights 3 and 1 alternate starting from the digit next to the check digit
+            let sum: u32 = body
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, digit)| if i % 2 == 0 { digit * 3 } else { *digit })
+                .sum();
+            (10 - sum % 10) % 10 == *check_digit
+        }
+        None => false,
+    }
+}
+
+fn is_valid_isbn10(code: &str) -> bool {
+    if code.len() != 10 {
+        return false;
+    }
+
+    // check digit 10 is written as X
+    let values = code
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i == 9 && c == 'X' { Some(10) } else { c.to_digit(10) })
+        .collect::<Option<Vec<u32>>>();
+
+    match values {
+        Some(values) => values.iter().enumerate().map(|(i, value)| (10 - i as u32) * value).sum::<u32>() % 11 == 0,
+        None => false,
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
 
     use models::*;
     use stq_static_resources::*;
+    use stq_types::ProductPrice;
+
+    #[test]
+    fn test_validate_non_negative_price() {
+        assert!(validate_non_negative_price(&ProductPrice(10.5)).is_ok());
+        assert!(validate_non_negative_price(&ProductPrice(-1.0)).is_err());
+        assert!(validate_non_negative_price(&ProductPrice(::std::f64::NAN)).is_err());
+        assert!(validate_non_negative_price(&ProductPrice(1e12)).is_err());
+    }
 
     #[test]
     fn test_store_valid_short_description() {
@@ -301,4 +492,48 @@ pub mod tests {
             Err(_) => true,
         });
     }
+
+    #[test]
+    fn test_validate_gtin() {
+        assert!(validate_gtin("4006381333931").is_ok());
+        assert!(validate_gtin("036000291452").is_ok());
+        assert!(validate_gtin("9780306406157").is_ok());
+        assert!(validate_gtin("0306406152").is_ok());
+        assert!(validate_gtin("080442957X").is_ok());
+
+        assert!(validate_gtin("4006381333932").is_err());
+        assert!(validate_gtin("400638133393").is_err());
+        assert!(validate_gtin("40063813339a1").is_err());
+        assert!(validate_gtin("").is_err());
+    }
+
+    #[test]
+    fn test_validate_opening_hours() {
+        let valid = json!({
+            "monday": [{"opens_at": "09:00", "closes_at": "13:00"}, {"opens_at": "14:00", "closes_at": "18:00"}],
+            "saturday": [{"opens_at": "10:00", "closes_at": "16:00"}]
+        });
+        assert!(validate_opening_hours(&valid).is_ok());
+
+        let overlapping = json!({
+            "monday": [{"opens_at": "09:00", "closes_at": "13:00"}, {"opens_at": "12:00", "closes_at": "18:00"}]
+        });
+        assert!(validate_opening_hours(&overlapping).is_err());
+
+        let reversed = json!({"monday": [{"opens_at": "18:00", "closes_at": "09:00"}]});
+        assert!(validate_opening_hours(&reversed).is_err());
+
+        let unknown_day = json!({"mon": [{"opens_at": "09:00", "closes_at": "18:00"}]});
+        assert!(validate_opening_hours(&unknown_day).is_err());
+
+        let wrong_format = json!({"monday": [{"opens_at": "9am", "closes_at": "6pm"}]});
+        assert!(validate_opening_hours(&wrong_format).is_err());
+    }
+
+    #[test]
+    fn test_validate_timezone() {
+        assert!(validate_timezone("+03:00").is_ok());
+        assert!(validate_timezone("-05:30").is_ok());
+        assert!(validate_timezone("UTC").is_err());
+    }
 }
@@ -0,0 +1,33 @@
+//! Build and config of the running instance, lets on-call check what is deployed where
+use chrono::{TimeZone, Utc};
+
+use config::Config;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VersionInfo {
+    pub version: String,
+    pub git_commit: String,
+    pub built_at: Option<String>,
+    pub features: Vec<String>,
+    pub config_checksum: String,
+}
+
+impl VersionInfo {
+    pub fn new(config: &Config) -> Self {
+        let mut features = config.enabled_features().into_iter().map(String::from).collect::<Vec<_>>();
+        if cfg!(feature = "chaos") {
+            features.push("chaos".to_string());
+        }
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: env!("GIT_COMMIT").to_string(),
+            built_at: env!("BUILD_TIMESTAMP")
+                .parse::<i64>()
+                .ok()
+                .map(|timestamp| Utc.timestamp(timestamp, 0).to_rfc3339()),
+            features,
+            config_checksum: config.checksum(),
+        }
+    }
+}
@@ -4,6 +4,8 @@ use std::str::FromStr;
 pub enum Visibility {
     Active,
     Published,
+    /// Deactivated by the owner, shown to the owner only
+    Deactivated,
 }
 
 impl FromStr for Visibility {
@@ -13,6 +15,7 @@ impl FromStr for Visibility {
         match s.to_ascii_lowercase().as_ref() {
             "active" => Ok(Visibility::Active),
             "published" => Ok(Visibility::Published),
+            "deactivated" => Ok(Visibility::Deactivated),
             _ => Err(()),
         }
     }
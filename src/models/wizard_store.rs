@@ -1,13 +1,17 @@
 //! Module containing wizard_stores model for query, insert, update
+use std::time::SystemTime;
+
+use serde_json;
 use validator::Validate;
 
 use stq_types::{Alpha3, StoreId, UserId};
 
 use models::validation_rules::*;
+use schema::archived_wizard_stores;
 use schema::wizard_stores;
 
 /// Payload for querying wizard_stores
-#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable, Default)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone, Identifiable)]
 #[table_name = "wizard_stores"]
 pub struct WizardStore {
     pub id: i32,
@@ -29,6 +33,11 @@ pub struct WizardStore {
     pub place_id: Option<String>,
     pub completed: bool,
     pub country_code: Option<Alpha3>,
+    pub created_at: SystemTime,
+    /// Last change made by the owner, reminders do not touch it
+    pub updated_at: SystemTime,
+    /// Set when the owner was reminded about the abandoned wizard, cleared by the next change
+    pub reminded_at: Option<SystemTime>,
 }
 
 /// Payload for creating wizard_stores
@@ -60,3 +69,31 @@ pub struct UpdateWizardStore {
     pub place_id: Option<String>,
     pub country_code: Option<Alpha3>,
 }
+
+/// Wizard removed after being abandoned, its data is kept for the funnel analysis
+#[derive(Serialize, Deserialize, Insertable, Clone, Debug)]
+#[table_name = "archived_wizard_stores"]
+pub struct NewArchivedWizardStore {
+    pub wizard_store_id: i32,
+    pub user_id: UserId,
+    pub data: serde_json::Value,
+}
+
+impl NewArchivedWizardStore {
+    pub fn new(wizard_store: &WizardStore) -> Self {
+        Self {
+            wizard_store_id: wizard_store.id,
+            user_id: wizard_store.user_id,
+            data: serde_json::to_value(wizard_store).unwrap_or_default(),
+        }
+    }
+}
+
+/// Store wizards funnel, `in_progress` includes the reminded ones
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WizardStoresStats {
+    pub completed: i64,
+    pub in_progress: i64,
+    pub reminded: i64,
+    pub archived: i64,
+}
@@ -0,0 +1,56 @@
+//! Notifiers push events about stores and products to other microservices
+pub mod social_feed;
+pub mod store_contact;
+pub mod verification;
+pub mod wizard_reminder;
+
+pub use self::social_feed::*;
+pub use self::store_contact::*;
+pub use self::verification::*;
+pub use self::wizard_reminder::*;
+
+use failure::Error as FailureError;
+use futures::{future, stream, Future, Stream};
+use stq_http::client::ClientHandle;
+
+use config::Config;
+use models::FeedEvent;
+use repos::types::RepoFuture;
+
+/// Notifier is responsible for delivering feed events
+pub trait Notifier {
+    /// Sends event, events switched off in config are skipped
+    fn notify(&self, event: FeedEvent) -> RepoFuture<()>;
+}
+
+/// Notifier used when the social feed is not configured
+#[derive(Default)]
+pub struct NullNotifier;
+
+impl Notifier for NullNotifier {
+    fn notify(&self, event: FeedEvent) -> RepoFuture<()> {
+        trace!("Social feed is not configured, skipping event {:?}", event);
+        Box::new(future::ok(()))
+    }
+}
+
+/// Creates notifier according to the `social_feed` config section
+pub fn create_notifier(config: &Config, client_handle: ClientHandle) -> Box<Notifier> {
+    match config.social_feed.clone() {
+        Some(social_feed) => Box::new(SocialFeedNotifier::new(client_handle, social_feed)) as Box<Notifier>,
+        None => Box::new(NullNotifier::default()) as Box<Notifier>,
+    }
+}
+
+/// Sends events one by one, next event is sent after the previous one is delivered.
+/// Delivery errors are only logged, so that a failing feed service never breaks the original request.
+pub fn send_events(notifier: Box<Notifier>, events: Vec<FeedEvent>) -> Box<Future<Item = (), Error = FailureError>> {
+    Box::new(stream::iter_ok::<_, FailureError>(events).for_each(move |event| {
+        notifier.notify(event).then(|res| {
+            if let Err(e) = res {
+                error!("Sending event to social feed failed: {}", e);
+            }
+            Ok(())
+        })
+    }))
+}
@@ -0,0 +1,60 @@
+//! SocialFeedNotifier sends events to the social feed microservice
+use failure::Fail;
+use futures::{future, Future};
+use hyper::header::{ContentLength, ContentType, Headers};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+
+use super::Notifier;
+use chaos::{inject_future, FaultLayer};
+use config::SocialFeed;
+use models::{FeedEvent, FeedEventKind};
+use repos::types::RepoFuture;
+
+pub struct SocialFeedNotifier {
+    pub client_handle: ClientHandle,
+    pub config: SocialFeed,
+}
+
+impl SocialFeedNotifier {
+    pub fn new(client_handle: ClientHandle, config: SocialFeed) -> Self {
+        Self { client_handle, config }
+    }
+
+    fn is_enabled(&self, kind: FeedEventKind) -> bool {
+        match kind {
+            FeedEventKind::StorePublished => self.config.store_published,
+            FeedEventKind::ProductsMilestone => self.config.products_milestone,
+            FeedEventKind::BigDiscountAdded => self.config.big_discount_added,
+        }
+    }
+}
+
+impl Notifier for SocialFeedNotifier {
+    fn notify(&self, event: FeedEvent) -> RepoFuture<()> {
+        if !self.is_enabled(event.kind()) {
+            debug!("Social feed event {:?} is switched off", event.kind());
+            return Box::new(future::ok(()));
+        }
+
+        let body = match serde_json::to_string(&event) {
+            Ok(body) => body,
+            Err(e) => return Box::new(future::err(e.context("Serializing social feed event failed").into())),
+        };
+
+        let url = format!("{}/events", self.config.url);
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(body.len() as u64));
+
+        debug!("Sending event to social feed: {}", body);
+        inject_future(
+            FaultLayer::HttpClient,
+            self.client_handle
+                .request::<serde_json::Value>(Method::Post, url, Some(body), Some(headers))
+                .map(|_| ())
+                .map_err(move |e| e.context(format!("Sending event {:?} to social feed failed", event)).into()),
+        )
+    }
+}
@@ -0,0 +1,73 @@
+//! StoreContactSender relays messages of users to store owners through the notifications microservice
+use failure::Fail;
+use futures::{future, Future};
+use hyper::header::{ContentLength, ContentType, Headers};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+
+use chaos::{inject_future, FaultLayer};
+use config::Config;
+use errors::Error;
+use models::StoreContactNotification;
+use notifiers::NotificationsSender;
+use repos::types::RepoFuture;
+
+pub trait StoreContactSender {
+    /// Sends message to the owner of the store
+    fn send_message(&self, notification: StoreContactNotification) -> RepoFuture<()>;
+}
+
+/// Creates sender according to the `notifications` config section
+pub fn create_store_contact_sender(config: &Config, client_handle: ClientHandle) -> Box<StoreContactSender> {
+    match config.notifications.clone() {
+        Some(notifications) => Box::new(NotificationsSender::new(client_handle, notifications)) as Box<StoreContactSender>,
+        None => Box::new(NullStoreContactSender::default()) as Box<StoreContactSender>,
+    }
+}
+
+/// Sender used when the notifications service is not configured. Messages can not be
+/// delivered, so the user gets an error instead of thinking the store got the message
+#[derive(Default)]
+pub struct NullStoreContactSender;
+
+impl StoreContactSender for NullStoreContactSender {
+    fn send_message(&self, notification: StoreContactNotification) -> RepoFuture<()> {
+        Box::new(future::err(
+            format_err!(
+                "Notifications service is not configured, message to store {} is not sent",
+                notification.store_id
+            )
+            .context(Error::Internal)
+            .into(),
+        ))
+    }
+}
+
+impl StoreContactSender for NotificationsSender {
+    fn send_message(&self, notification: StoreContactNotification) -> RepoFuture<()> {
+        let url = format!("{}/stores/contact", self.config.url);
+        let store_id = notification.store_id;
+        let sender_id = notification.sender_id;
+
+        let body = match serde_json::to_string(&notification) {
+            Ok(body) => body,
+            Err(e) => return Box::new(future::err(e.context(Error::Internal).into())),
+        };
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(body.len() as u64));
+
+        debug!("Sending message of user {} to store {}", sender_id, store_id);
+        inject_future(
+            FaultLayer::HttpClient,
+            self.client_handle
+                .request::<serde_json::Value>(Method::Post, url, Some(body), Some(headers))
+                .map(|_| ())
+                .map_err(move |e| {
+                    e.context(format!("Sending message of user {} to store {} failed", sender_id, store_id))
+                        .into()
+                }),
+        )
+    }
+}
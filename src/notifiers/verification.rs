@@ -0,0 +1,80 @@
+//! VerificationSender delivers store verification codes through the notifications microservice
+use failure::Fail;
+use futures::{future, Future};
+use hyper::header::{ContentLength, ContentType, Headers};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+
+use chaos::{inject_future, FaultLayer};
+use config::{Config, Notifications};
+use models::VerificationChannel;
+use repos::types::RepoFuture;
+
+pub trait VerificationSender {
+    /// Sends code to the email or phone
+    fn send_code(&self, channel: VerificationChannel, destination: String, code: String) -> RepoFuture<()>;
+}
+
+/// Creates sender according to the `notifications` config section
+pub fn create_verification_sender(config: &Config, client_handle: ClientHandle) -> Box<VerificationSender> {
+    match config.notifications.clone() {
+        Some(notifications) => Box::new(NotificationsSender::new(client_handle, notifications)) as Box<VerificationSender>,
+        None => Box::new(NullVerificationSender::default()) as Box<VerificationSender>,
+    }
+}
+
+/// Sender used when the notifications service is not configured, codes are never delivered
+#[derive(Default)]
+pub struct NullVerificationSender;
+
+impl VerificationSender for NullVerificationSender {
+    fn send_code(&self, channel: VerificationChannel, destination: String, _code: String) -> RepoFuture<()> {
+        warn!(
+            "Notifications service is not configured, verification code for {:?} {} is not sent",
+            channel, destination
+        );
+        Box::new(future::ok(()))
+    }
+}
+
+pub struct NotificationsSender {
+    pub client_handle: ClientHandle,
+    pub config: Notifications,
+}
+
+impl NotificationsSender {
+    pub fn new(client_handle: ClientHandle, config: Notifications) -> Self {
+        Self { client_handle, config }
+    }
+}
+
+impl VerificationSender for NotificationsSender {
+    fn send_code(&self, channel: VerificationChannel, destination: String, code: String) -> RepoFuture<()> {
+        let url = match channel {
+            VerificationChannel::Email => format!("{}/stores/verification/email", self.config.url),
+            VerificationChannel::Phone => format!("{}/stores/verification/sms", self.config.url),
+        };
+
+        let body = json!({
+            "destination": destination,
+            "code": code,
+        })
+        .to_string();
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(body.len() as u64));
+
+        debug!("Sending verification code for {:?} {}", channel, destination);
+        inject_future(
+            FaultLayer::HttpClient,
+            self.client_handle
+                .request::<serde_json::Value>(Method::Post, url, Some(body), Some(headers))
+                .map(|_| ())
+                .map_err(move |e| {
+                    e.context(format!("Sending verification code for {:?} {} failed", channel, destination))
+                        .into()
+                }),
+        )
+    }
+}
@@ -0,0 +1,67 @@
+//! WizardReminderSender asks the notifications microservice to remind owners about abandoned store wizards
+use failure::Fail;
+use futures::{future, Future};
+use hyper::header::{ContentLength, ContentType, Headers};
+use hyper::Method;
+use serde_json;
+use stq_http::client::ClientHandle;
+
+use chaos::{inject_future, FaultLayer};
+use config::Config;
+use models::WizardStore;
+use notifiers::NotificationsSender;
+use repos::types::RepoFuture;
+
+pub trait WizardReminderSender {
+    /// Sends reminder event to the owner of the wizard
+    fn send_reminder(&self, wizard_store: WizardStore) -> RepoFuture<()>;
+}
+
+/// Creates sender according to the `notifications` config section
+pub fn create_wizard_reminder_sender(config: &Config, client_handle: ClientHandle) -> Box<WizardReminderSender> {
+    match config.notifications.clone() {
+        Some(notifications) => Box::new(NotificationsSender::new(client_handle, notifications)) as Box<WizardReminderSender>,
+        None => Box::new(NullWizardReminderSender::default()) as Box<WizardReminderSender>,
+    }
+}
+
+/// Sender used when the notifications service is not configured, wizards are still marked as reminded
+#[derive(Default)]
+pub struct NullWizardReminderSender;
+
+impl WizardReminderSender for NullWizardReminderSender {
+    fn send_reminder(&self, wizard_store: WizardStore) -> RepoFuture<()> {
+        warn!(
+            "Notifications service is not configured, reminder about wizard {} of user {} is not sent",
+            wizard_store.id, wizard_store.user_id
+        );
+        Box::new(future::ok(()))
+    }
+}
+
+impl WizardReminderSender for NotificationsSender {
+    fn send_reminder(&self, wizard_store: WizardStore) -> RepoFuture<()> {
+        let url = format!("{}/stores/wizard/reminder", self.config.url);
+        let wizard_store_id = wizard_store.id;
+
+        let body = json!({
+            "user_id": wizard_store.user_id,
+            "wizard_store_id": wizard_store.id,
+            "name": wizard_store.name,
+            "updated_at": wizard_store.updated_at,
+        })
+        .to_string();
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        headers.set(ContentLength(body.len() as u64));
+
+        debug!("Sending reminder about wizard {}", wizard_store_id);
+        inject_future(
+            FaultLayer::HttpClient,
+            self.client_handle
+                .request::<serde_json::Value>(Method::Post, url, Some(body), Some(headers))
+                .map(|_| ())
+                .map_err(move |e| e.context(format!("Sending reminder about wizard {} failed", wizard_store_id)).into()),
+        )
+    }
+}
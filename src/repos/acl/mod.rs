@@ -76,6 +76,7 @@ impl ApplicationAcl {
             vec![
                 permission!(Resource::Attributes),
                 permission!(Resource::AttributeValues),
+                permission!(Resource::AttributeGroups),
                 permission!(Resource::BaseProducts),
                 permission!(Resource::Categories),
                 permission!(Resource::CategoryAttrs),
@@ -92,6 +93,25 @@ impl ApplicationAcl {
                 permission!(Resource::CouponScopeBaseProducts),
                 permission!(Resource::CouponScopeCategories),
                 permission!(Resource::UsedCoupons),
+                permission!(Resource::CouponRedemptions),
+                permission!(Resource::AuditLog),
+                permission!(Resource::CatalogHealth),
+                permission!(Resource::Jobs),
+                permission!(Resource::StoreVerificationCodes),
+                permission!(Resource::StoreFaqs),
+                permission!(Resource::SyncState),
+                permission!(Resource::SearchSynonyms),
+                permission!(Resource::IndexMigrations),
+                permission!(Resource::Listings),
+                permission!(Resource::PriceRules),
+                permission!(Resource::ModerationChecklists),
+                permission!(Resource::StockReservations),
+                permission!(Resource::StoreCategories),
+                permission!(Resource::StoreProductPositions),
+                permission!(Resource::CategoryPromotions),
+                permission!(Resource::StoreStatistics),
+                permission!(Resource::ImpactReports),
+                permission!(Resource::BaseProductHistory),
             ],
         );
         hash.insert(
@@ -99,6 +119,7 @@ impl ApplicationAcl {
             vec![
                 permission!(Resource::Attributes, Action::Read),
                 permission!(Resource::AttributeValues, Action::Read),
+                permission!(Resource::AttributeGroups, Action::Read),
                 permission!(Resource::BaseProducts, Action::Create, Scope::Owned),
                 permission!(Resource::BaseProducts, Action::Delete, Scope::Owned),
                 permission!(
@@ -209,6 +230,17 @@ impl ApplicationAcl {
                     Rule::ModerationStatus(ModerationStatus::Published)
                 ),
                 permission!(Resource::UserRoles, Action::Read, Scope::Owned),
+                permission!(Resource::CatalogHealth, Action::Read, Scope::Owned),
+                permission!(Resource::StoreStatistics, Action::Read, Scope::Owned),
+                permission!(Resource::BaseProductHistory, Action::All, Scope::Owned),
+                permission!(Resource::StoreVerificationCodes, Action::All, Scope::Owned),
+                permission!(Resource::StoreFaqs, Action::All, Scope::Owned),
+                permission!(Resource::StoreFaqs, Action::Read),
+                permission!(Resource::StoreCategories, Action::Create, Scope::Owned),
+                permission!(Resource::StoreCategories, Action::Read, Scope::Owned),
+                permission!(Resource::StoreCategories, Action::Delete, Scope::Owned),
+                permission!(Resource::StoreProductPositions, Action::All, Scope::Owned),
+                permission!(Resource::StoreProductPositions, Action::Read),
                 permission!(Resource::WizardStores, Action::All, Scope::Owned),
                 permission!(Resource::WizardStores, Action::Read),
                 permission!(Resource::Coupons, Action::All, Scope::Owned),
@@ -218,6 +250,11 @@ impl ApplicationAcl {
                 permission!(Resource::CouponScopeCategories, Action::All, Scope::Owned),
                 permission!(Resource::CouponScopeCategories, Action::Read),
                 permission!(Resource::UsedCoupons, Action::Read),
+                permission!(Resource::Listings, Action::Read),
+                permission!(Resource::Jobs, Action::All, Scope::Owned),
+                permission!(Resource::PriceRules, Action::All, Scope::Owned),
+                permission!(Resource::ModerationChecklists, Action::Read),
+                permission!(Resource::CategoryPromotions, Action::Read),
             ],
         );
 
@@ -225,9 +262,17 @@ impl ApplicationAcl {
             StoresRole::Moderator,
             vec![
                 permission!(Resource::BaseProducts),
+                permission!(Resource::BaseProductHistory),
                 permission!(Resource::ModeratorProductComments),
                 permission!(Resource::ModeratorStoreComments),
                 permission!(Resource::Stores),
+                permission!(Resource::CatalogHealth, Action::Read),
+                permission!(Resource::Coupons, Action::Read),
+                permission!(Resource::Jobs, Action::All, Scope::Owned),
+                permission!(Resource::Listings),
+                permission!(Resource::ModerationChecklists, Action::Read),
+                permission!(Resource::ModerationChecklists, Action::Moderate),
+                permission!(Resource::StoreCategories),
             ],
         );
 
@@ -236,8 +281,10 @@ impl ApplicationAcl {
             vec![
                 permission!(Resource::Attributes),
                 permission!(Resource::AttributeValues),
+                permission!(Resource::AttributeGroups),
                 permission!(Resource::Categories),
                 permission!(Resource::CategoryAttrs),
+                permission!(Resource::ModerationChecklists),
             ],
         );
 
@@ -310,16 +357,22 @@ impl<T> Acl<Resource, Action, Scope, Rule, FailureError, T> for UnauthorizedAcl
                 | Resource::ProductAttrs
                 | Resource::Attributes
                 | Resource::AttributeValues
+                | Resource::AttributeGroups
                 | Resource::CurrencyExchange
                 | Resource::WizardStores
                 | Resource::ModeratorProductComments
                 | Resource::ModeratorStoreComments
+                | Resource::StoreFaqs
+                | Resource::StoreProductPositions
+                | Resource::CategoryPromotions
+                | Resource::Listings
                 | Resource::CategoryAttrs => Ok(true),
 
                 Resource::Stores | Resource::BaseProducts => match rule {
                     Some(value) => match value {
                         Rule::Any => Ok(true),
                         Rule::ModerationStatus(status) => Ok(status == ModerationStatus::Published),
+                        Rule::Deactivated => Ok(false),
                     },
                     _ => Ok(true),
                 },
@@ -382,6 +435,24 @@ mod tests {
             place_id: None,
             kafka_update_no: 0,
             uuid: uuid::Uuid::new_v4(),
+            quota_plan: None,
+            email_verified: false,
+            phone_verified: false,
+            latitude: None,
+            longitude: None,
+            payout_info_present: false,
+            shipping_profile_set: false,
+            franchise: false,
+            is_on_vacation: false,
+            vacation_message: None,
+            opening_hours: None,
+            timezone: None,
+            default_length_cm: None,
+            default_width_cm: None,
+            default_height_cm: None,
+            default_weight_g: None,
+            verification_status: StoreVerificationStatus::Unverified,
+            badges: json!([]),
         }
     }
 
@@ -0,0 +1,177 @@
+//! Repo for attribute_groups and attribute_group_attributes tables
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{AttributeId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::attribute_group_attributes::dsl as GroupAttributes;
+use schema::attribute_groups::dsl as Groups;
+
+/// AttributeGroups repository, responsible for handling attribute_groups table
+pub struct AttributeGroupsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<AttributeGroup>>,
+}
+
+pub trait AttributeGroupsRepo {
+    /// Returns all groups ordered by position
+    fn list(&self) -> RepoResult<Vec<AttributeGroup>>;
+
+    /// Find specific group by id
+    fn find(&self, group_id: i32) -> RepoResult<Option<AttributeGroup>>;
+
+    /// Creates new group
+    fn create(&self, payload: NewAttributeGroup) -> RepoResult<AttributeGroup>;
+
+    /// Updates specific group
+    fn update(&self, group_id: i32, payload: UpdateAttributeGroup) -> RepoResult<AttributeGroup>;
+
+    /// Deletes specific group, its attributes become ungrouped
+    fn delete(&self, group_id: i32) -> RepoResult<AttributeGroup>;
+
+    /// Returns attributes of all groups
+    fn list_attributes(&self) -> RepoResult<Vec<AttributeGroupAttribute>>;
+
+    /// Replaces attributes of the group, attributes are moved out of their previous groups
+    fn set_attributes(&self, group_id: i32, attribute_ids: Vec<AttributeId>) -> RepoResult<Vec<AttributeGroupAttribute>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> AttributeGroupsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<AttributeGroup>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> AttributeGroupsRepo
+    for AttributeGroupsRepoImpl<'a, T>
+{
+    /// Returns all groups ordered by position
+    fn list(&self) -> RepoResult<Vec<AttributeGroup>> {
+        debug!("List attribute groups.");
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Read, self, None)?;
+
+        Groups::attribute_groups
+            .order((Groups::position, Groups::id))
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("List attribute groups error occurred").into())
+    }
+
+    /// Find specific group by id
+    fn find(&self, group_id: i32) -> RepoResult<Option<AttributeGroup>> {
+        debug!("Find attribute group {}.", group_id);
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Read, self, None)?;
+
+        Groups::attribute_groups
+            .find(group_id)
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Find attribute group {} error occurred", group_id)).into())
+    }
+
+    /// Creates new group
+    fn create(&self, payload: NewAttributeGroup) -> RepoResult<AttributeGroup> {
+        debug!("Create attribute group {:?}.", payload);
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Create, self, None)?;
+
+        diesel::insert_into(Groups::attribute_groups)
+            .values(&payload)
+            .get_result::<AttributeGroup>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Create attribute group {:?} error occurred", payload)).into())
+    }
+
+    /// Updates specific group
+    fn update(&self, group_id: i32, payload: UpdateAttributeGroup) -> RepoResult<AttributeGroup> {
+        debug!("Update attribute group {} with payload {:?}.", group_id, payload);
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Update, self, None)?;
+
+        diesel::update(Groups::attribute_groups.filter(Groups::id.eq(group_id)))
+            .set(&payload)
+            .get_result::<AttributeGroup>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Update attribute group {} with payload {:?} error occurred", group_id, payload))
+                    .into()
+            })
+    }
+
+    /// Deletes specific group, its attributes become ungrouped
+    fn delete(&self, group_id: i32) -> RepoResult<AttributeGroup> {
+        debug!("Delete attribute group {}.", group_id);
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Delete, self, None)?;
+
+        diesel::delete(Groups::attribute_groups.filter(Groups::id.eq(group_id)))
+            .get_result::<AttributeGroup>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Delete attribute group {} error occurred", group_id)).into())
+    }
+
+    /// Returns attributes of all groups
+    fn list_attributes(&self) -> RepoResult<Vec<AttributeGroupAttribute>> {
+        debug!("List attributes of attribute groups.");
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Read, self, None)?;
+
+        GroupAttributes::attribute_group_attributes
+            .order((GroupAttributes::group_id, GroupAttributes::position))
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("List attributes of attribute groups error occurred").into())
+    }
+
+    /// Replaces attributes of the group, attributes are moved out of their previous groups
+    fn set_attributes(&self, group_id: i32, attribute_ids: Vec<AttributeId>) -> RepoResult<Vec<AttributeGroupAttribute>> {
+        debug!("Set attributes {:?} of attribute group {}.", attribute_ids, group_id);
+        acl::check(&*self.acl, Resource::AttributeGroups, Action::Update, self, None)?;
+
+        let payload: Vec<NewAttributeGroupAttribute> = attribute_ids
+            .iter()
+            .enumerate()
+            .map(|(position, attribute_id)| NewAttributeGroupAttribute {
+                group_id,
+                attribute_id: *attribute_id,
+                position: position as i32,
+            })
+            .collect();
+
+        let previous = GroupAttributes::attribute_group_attributes.filter(
+            GroupAttributes::group_id
+                .eq(group_id)
+                .or(GroupAttributes::attribute_id.eq_any(attribute_ids.clone())),
+        );
+        diesel::delete(previous)
+            .execute(self.db_conn)
+            .and_then(|_| {
+                diesel::insert_into(GroupAttributes::attribute_group_attributes)
+                    .values(&payload)
+                    .get_results::<AttributeGroupAttribute>(self.db_conn)
+            })
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Set attributes {:?} of attribute group {} error occurred", attribute_ids, group_id))
+                    .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, AttributeGroup>
+    for AttributeGroupsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&AttributeGroup>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
@@ -3,7 +3,8 @@ use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_dsl::RunQueryDsl;
-use diesel::sql_types::Bool;
+use diesel::dsl::sql;
+use diesel::sql_types::{Bool, VarChar};
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
@@ -23,6 +24,10 @@ pub trait AttributeValuesRepo {
     fn get(&self, attribute_value_id: AttributeValueId) -> RepoResult<Option<AttributeValue>>;
     fn find(&self, attr_id: AttributeId, code: AttributeValueCode) -> RepoResult<Option<AttributeValue>>;
     fn find_many(&self, search_terms: AttributeValuesSearchTerms) -> RepoResult<Vec<AttributeValue>>;
+    /// Finds values by ids, locking their rows until the end of the transaction
+    fn find_many_for_update(&self, ids: Vec<AttributeValueId>) -> RepoResult<Vec<AttributeValue>>;
+    /// Finds values which code or any translation contains `name`, limited by `count`
+    fn search_by_name(&self, name: String, count: i64) -> RepoResult<Vec<AttributeValue>>;
     fn update(&self, id: AttributeValueId, update: UpdateAttributeValue) -> RepoResult<AttributeValue>;
     fn delete(&self, id: AttributeValueId) -> RepoResult<AttributeValue>;
 }
@@ -117,6 +122,47 @@ where
             })
     }
 
+    fn find_many_for_update(&self, ids: Vec<AttributeValueId>) -> RepoResult<Vec<AttributeValue>> {
+        debug!("Find attribute values for update by ids {:?}.", ids);
+        // rows are locked in the order of ids, so concurrent imports can not deadlock
+        attribute_values
+            .filter(id.eq_any(ids))
+            .order_by(id)
+            .for_update()
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|results: Vec<AttributeValue>| {
+                for result in results.iter() {
+                    acl::check(&*self.acl, Resource::AttributeValues, Action::Update, self, Some(result))?;
+                }
+                Ok(results)
+            })
+            .map_err(|e: FailureError| e.context("Find attribute values for update by ids error occurred").into())
+    }
+
+    fn search_by_name(&self, name: String, count: i64) -> RepoResult<Vec<AttributeValue>> {
+        debug!("Search attribute values by name {}, count {}.", name, count);
+        let contains_name = sql::<Bool>("(code ILIKE concat('%', ")
+            .bind::<VarChar, _>(name.clone())
+            .sql(", '%') OR translations::text ILIKE concat('%', ")
+            .bind::<VarChar, _>(name.clone())
+            .sql(", '%'))");
+
+        attribute_values
+            .filter(contains_name)
+            .order_by(id)
+            .limit(count)
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|results: Vec<AttributeValue>| {
+                for result in results.iter() {
+                    acl::check(&*self.acl, Resource::AttributeValues, Action::Read, self, Some(result))?;
+                }
+                Ok(results)
+            })
+            .map_err(move |e: FailureError| e.context(format!("Search attribute values by name {} error occurred", name)).into())
+    }
+
     fn update(&self, id_arg: AttributeValueId, update: UpdateAttributeValue) -> RepoResult<AttributeValue> {
         debug!("Changing attribute value {}  - {:?}.", id_arg, update);
         let res = attribute_values.find(id_arg).get_result(self.db_conn)?;
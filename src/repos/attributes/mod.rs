@@ -48,6 +48,9 @@ pub trait AttributesRepo {
 
     /// Deletes specific attribute
     fn delete(&self, attribute_id_arg: AttributeId) -> RepoResult<()>;
+
+    /// Removes attribute from cache, used after bulk changes of its values
+    fn invalidate_cache(&self, attribute_id_arg: AttributeId);
 }
 
 impl<'a, C, T> AttributesRepoImpl<'a, C, T>
@@ -156,6 +159,12 @@ where
 
         Ok(())
     }
+
+    /// Removes attribute from cache, used after bulk changes of its values
+    fn invalidate_cache(&self, attribute_id_arg: AttributeId) {
+        debug!("Invalidating attribute cache with id {}", attribute_id_arg);
+        self.cache.remove(attribute_id_arg);
+    }
 }
 
 impl<'a, C, T> CheckScope<Scope, Attribute> for AttributesRepoImpl<'a, C, T>
@@ -0,0 +1,62 @@
+//! Repo for audit_log table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::audit_log::dsl as DslAuditLog;
+
+/// AuditLog repository, responsible for handling audit_log table
+pub struct AuditLogRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<AuditLogEntry>>,
+}
+
+pub trait AuditLogRepo {
+    /// Records new audit log entry
+    fn create(&self, payload: NewAuditLogEntry) -> RepoResult<AuditLogEntry>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> AuditLogRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<AuditLogEntry>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> AuditLogRepo for AuditLogRepoImpl<'a, T> {
+    /// Records new audit log entry
+    fn create(&self, payload: NewAuditLogEntry) -> RepoResult<AuditLogEntry> {
+        debug!("Create new audit log entry {:?}.", payload);
+
+        acl::check(&*self.acl, Resource::AuditLog, Action::Create, self, None)?;
+
+        let query = diesel::insert_into(DslAuditLog::audit_log).values(&payload);
+        query
+            .get_result::<AuditLogEntry>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Creates new audit log entry: {:?} error occurred", payload))
+                    .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, AuditLogEntry>
+    for AuditLogRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&AuditLogEntry>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|entry| entry.actor_user_id == user_id).unwrap_or(false),
+        }
+    }
+}
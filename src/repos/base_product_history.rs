@@ -0,0 +1,108 @@
+//! Base product history repo, changes of base products are kept in the audit_log table
+use std::collections::BTreeSet;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{BaseProductId, StoreId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{AuditLogEntry, BaseProductHistoryEntry, NewAuditLogEntry, NewBaseProductChange, Store};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::audit_log::dsl as AuditLog;
+use schema::base_products::dsl as BaseProducts;
+use schema::stores::dsl as Stores;
+
+/// Base product history repository, access is checked against the store of the base product
+pub struct BaseProductHistoryRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Store>>,
+}
+
+pub trait BaseProductHistoryRepo {
+    /// Records changes of base products
+    fn record(&self, changes: Vec<NewBaseProductChange>) -> RepoResult<()>;
+
+    /// Returns changes of the base product, the latest first
+    fn list(&self, base_product_id: BaseProductId) -> RepoResult<Vec<BaseProductHistoryEntry>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> BaseProductHistoryRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Store>>) -> Self {
+        Self { db_conn, acl }
+    }
+
+    fn store_of(&self, base_product_id: BaseProductId) -> RepoResult<Store> {
+        let store_id = BaseProducts::base_products
+            .filter(BaseProducts::id.eq(base_product_id))
+            .select(BaseProducts::store_id)
+            .get_result::<StoreId>(self.db_conn)
+            .map_err(Error::from)?;
+
+        Stores::stores
+            .filter(Stores::id.eq(store_id))
+            .get_result::<Store>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> BaseProductHistoryRepo
+    for BaseProductHistoryRepoImpl<'a, T>
+{
+    /// Records changes of base products
+    fn record(&self, changes: Vec<NewBaseProductChange>) -> RepoResult<()> {
+        debug!("Record {} changes of base products.", changes.len());
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let base_product_ids = changes.iter().map(|change| change.base_product_id).collect::<BTreeSet<_>>();
+        for base_product_id in base_product_ids {
+            let store = self.store_of(base_product_id)?;
+            acl::check(&*self.acl, Resource::BaseProductHistory, Action::Create, self, Some(&store))?;
+        }
+
+        let entries = changes.iter().map(NewBaseProductChange::to_audit_log_entry).collect::<Vec<NewAuditLogEntry>>();
+        diesel::insert_into(AuditLog::audit_log)
+            .values(&entries)
+            .execute(self.db_conn)
+            .map(|_| ())
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Record changes of base products {:?} error occurred", changes)).into())
+    }
+
+    /// Returns changes of the base product, the latest first
+    fn list(&self, base_product_id: BaseProductId) -> RepoResult<Vec<BaseProductHistoryEntry>> {
+        debug!("List changes of base product {}.", base_product_id);
+
+        let store = self.store_of(base_product_id)?;
+        acl::check(&*self.acl, Resource::BaseProductHistory, Action::Read, self, Some(&store))?;
+
+        AuditLog::audit_log
+            .filter(AuditLog::base_product_id.eq(base_product_id))
+            .order(AuditLog::id.desc())
+            .load::<AuditLogEntry>(self.db_conn)
+            .map(|entries| entries.into_iter().filter_map(BaseProductHistoryEntry::from_audit_log).collect())
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("List changes of base product {} error occurred", base_product_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, Store>
+    for BaseProductHistoryRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&Store>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|store| store.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::collections::{BTreeMap, HashMap};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::SystemTime;
 
 use diesel;
 use diesel::connection::AnsiTransactionManager;
@@ -8,14 +10,14 @@ use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_dsl::LoadQuery;
 use diesel::query_dsl::RunQueryDsl;
-use diesel::sql_types::{Bool, VarChar};
+use diesel::sql_types::{Bool, Double, Integer, VarChar};
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
 use failure::Fail;
 
 use stq_static_resources::ModerationStatus;
-use stq_types::{BaseProductId, BaseProductSlug, CategoryId, ProductId, StoreId, UserId};
+use stq_types::{AttributeId, BaseProductId, BaseProductSlug, CategoryId, ProductId, SagaId, StoreId, UserId};
 
 use models::*;
 
@@ -23,12 +25,15 @@ use errors;
 use repos::{
     acl,
     legacy_acl::*,
+    shadow_read::shadow_read,
     types::{RepoAcl, RepoResult},
 };
 use schema::attributes::dsl as DslAttributes;
 use schema::base_products::dsl::*;
 use schema::prod_attr_values::dsl as DslProdAttr;
+use schema::product_views::dsl as DslProductViews;
 use schema::products::dsl as Products;
+use schema::store_product_positions::dsl as StoreProductPositions;
 use schema::stores::dsl as Stores;
 
 /// BaseProducts repository, responsible for handling base_products
@@ -43,11 +48,33 @@ pub struct BaseProductsSearchTerms {
     pub category_id: Option<CategoryId>,
     pub category_ids: Option<Vec<CategoryId>>,
     pub store_id: Option<StoreId>,
+    pub created_before: Option<SystemTime>,
 }
 
 type FilterBaseProductExpr = Box<BoxableExpression<base_products, Pg, SqlType = Bool>>;
 
-pub trait BaseProductsRepo {
+/// Base products visible to customers: published by moderation in published store and not hidden by the owner
+fn published_filter() -> FilterBaseProductExpr {
+    Box::new(
+        is_active
+            .eq(true)
+            .and(status.eq(ModerationStatus::Published))
+            .and(store_status.eq(ModerationStatus::Published))
+            .and(published_at.is_not_null()),
+    )
+}
+
+/// Deactivated base products are read by their owners only
+fn read_rule(base_product: &BaseProduct) -> Rule {
+    if base_product.is_active {
+        Rule::ModerationStatus(base_product.status)
+    } else {
+        Rule::Deactivated
+    }
+}
+
+/// Queries of base products, they can be served by a read replica
+pub trait BaseProductsReadRepo {
     /// Get base_product count
     fn count(&self, visibility: Visibility) -> RepoResult<i64>;
 
@@ -64,21 +91,27 @@ pub trait BaseProductsRepo {
 
     /// Find base_products by ids
     fn find_many(&self, base_product_ids: Vec<BaseProductId>) -> RepoResult<Vec<BaseProduct>>;
+
     /// Find specific base product by ID and filters
     fn find_by_filters(&self, base_product_id: BaseProductId, filters: BaseProductsSearchTerms) -> RepoResult<Option<BaseProduct>>;
+
     /// Search many products by search terms
     fn search(&self, search_terms: BaseProductsSearchTerms) -> RepoResult<Vec<BaseProduct>>;
 
     /// Returns list of base_products, limited by `from` and `count` parameters
-    fn list(&self, from: BaseProductId, count: i32, visibility: Visibility) -> RepoResult<Vec<BaseProduct>>;
+    fn list(&self, from: BaseProductsListFrom, count: i32, visibility: Visibility) -> RepoResult<Vec<BaseProduct>>;
 
     /// Returns most viewed list of base_products, limited by `from` and `offset` parameters
     fn most_viewed(&self, search_product: MostViewedProducts, count: i32, offset: i32) -> RepoResult<Vec<BaseProductWithVariants>>;
 
+    /// Returns random published base_products, products with higher rating and views are more likely to get in
+    fn sample(&self, categories_ids: Option<Vec<CategoryId>>, count: i32) -> RepoResult<Vec<BaseProductWithVariants>>;
+
     /// Returns most discount list of base_products, limited by `from` and `offset` parameters
     fn most_discount(&self, search_product: MostDiscountProducts, count: i32, offset: i32) -> RepoResult<Vec<BaseProductWithVariants>>;
 
-    /// Returns list of base_products by store id and exclude base_product_id_arg, limited by 10
+    /// Returns list of base_products by store id and exclude base_product_id_arg, limited by `from` and `count`.
+    /// Products go in storefront order set by the store manager, `from` is the id of the first product of the page
     fn get_products_of_the_store(
         &self,
         store_id: StoreId,
@@ -91,36 +124,73 @@ pub trait BaseProductsRepo {
     /// Counts products by store id
     fn count_with_store_id(&self, store_id: StoreId, visibility: Visibility) -> RepoResult<i32>;
 
+    /// Checks that slug already exists
+    fn slug_exists(&self, slug_arg: String) -> RepoResult<bool>;
+
+    /// Convert data from elastic to PG models
+    fn convert_from_elastic(&self, el_products: Vec<ElasticProduct>) -> RepoResult<Vec<BaseProductWithVariants>>;
+
+    /// Search base product limited by pagination parameters
+    fn moderator_search(
+        &self,
+        pagination_params: PaginationParams<BaseProductId>,
+        term: ModeratorBaseProductSearchTerms,
+    ) -> RepoResult<ModeratorBaseProductSearchResults>;
+
+    /// Getting all base products with variants
+    fn get_all_catalog(&self) -> RepoResult<Vec<CatalogWithAttributes>>;
+
+    /// Find many base products by ids with active variants and their attributes
+    fn find_many_with_variants(&self, base_product_ids: Vec<BaseProductId>) -> RepoResult<Vec<CatalogWithAttributes>>;
+
+    /// Find published base products of the category with variants having exactly the attribute values of the fingerprint
+    fn find_by_attribute_fingerprint(
+        &self,
+        category_id: CategoryId,
+        fingerprint: AttributeFingerprint,
+    ) -> RepoResult<Vec<CatalogWithAttributes>>;
+
+    /// Find published base products of the same category sharing the most attribute values with the base product,
+    /// products without shared values are not returned
+    fn find_related(&self, base_product_id: BaseProductId, count: i32) -> RepoResult<Vec<BaseProductWithVariants>>;
+}
+
+/// Changes of base products, they always go to the primary database
+pub trait BaseProductsWriteRepo {
     /// Creates new base_product
     fn create(&self, payload: NewBaseProduct) -> RepoResult<BaseProduct>;
 
     /// Updates specific base_product
     fn update(&self, base_product_id: BaseProductId, payload: UpdateBaseProduct) -> RepoResult<BaseProduct>;
 
-    /// Update views on specific base_product
-    fn update_views(&self, base_product_id: BaseProductId) -> RepoResult<Option<BaseProduct>>;
+    /// Update views on specific base_product, a view is counted once per viewer per day
+    fn update_views(&self, base_product_id: BaseProductId, viewer_hash: Option<String>) -> RepoResult<Option<BaseProduct>>;
 
-    /// Update views on specific base_product by slug
-    fn update_views_by_slug(&self, store_id: StoreId, base_product_slug: BaseProductSlug) -> RepoResult<Option<BaseProduct>>;
+    /// Update views on specific base_product by slug, a view is counted once per viewer per day
+    fn update_views_by_slug(
+        &self,
+        store_id: StoreId,
+        base_product_slug: BaseProductSlug,
+        viewer_hash: Option<String>,
+    ) -> RepoResult<Option<BaseProduct>>;
 
     /// Deactivates specific base_product
     fn deactivate(&self, base_product_id: BaseProductId) -> RepoResult<BaseProduct>;
 
-    /// Deactivates base_products by store_id
-    fn deactivate_by_store(&self, store_id: StoreId) -> RepoResult<Vec<BaseProduct>>;
+    /// Activates deactivated base_product, its `kafka_update_no` is bumped to get it indexed again
+    fn activate(&self, base_product_id: BaseProductId) -> RepoResult<BaseProduct>;
 
-    /// Checks that slug already exists
-    fn slug_exists(&self, slug_arg: String) -> RepoResult<bool>;
+    /// Shows base_product to customers or hides it from them, published base products have `published_at`
+    fn set_published(&self, base_product_id: BaseProductId, published: bool) -> RepoResult<BaseProduct>;
 
-    /// Convert data from elastic to PG models
-    fn convert_from_elastic(&self, el_products: Vec<ElasticProduct>) -> RepoResult<Vec<BaseProductWithVariants>>;
+    /// Moves base_product to the store, its `kafka_update_no` is bumped to get it indexed again
+    fn set_store(&self, base_product_id: BaseProductId, store: &Store) -> RepoResult<BaseProduct>;
 
-    /// Search base product limited by pagination parameters
-    fn moderator_search(
-        &self,
-        pagination_params: PaginationParams<BaseProductId>,
-        term: ModeratorBaseProductSearchTerms,
-    ) -> RepoResult<ModeratorBaseProductSearchResults>;
+    /// Deactivates base_products by store_id
+    fn deactivate_by_store(&self, store_id: StoreId) -> RepoResult<Vec<BaseProduct>>;
+
+    /// Deactivates base_products created under saga ID
+    fn deactivate_by_saga_id(&self, saga_id: SagaId) -> RepoResult<Vec<BaseProduct>>;
 
     /// Set moderation status for base_product_ids
     fn set_moderation_statuses(&self, base_product_ids: Vec<BaseProductId>, status: ModerationStatus) -> RepoResult<Vec<BaseProduct>>;
@@ -140,11 +210,13 @@ pub trait BaseProductsRepo {
 
     /// Replace category in base products
     fn replace_category(&self, payload: CategoryReplacePayload) -> RepoResult<Vec<BaseProduct>>;
-
-    /// Getting all base products with variants
-    fn get_all_catalog(&self) -> RepoResult<Vec<CatalogWithAttributes>>;
 }
 
+/// Both halves of the base products repo, implemented for everything implementing them
+pub trait BaseProductsRepo: BaseProductsReadRepo + BaseProductsWriteRepo {}
+
+impl<R: BaseProductsReadRepo + BaseProductsWriteRepo> BaseProductsRepo for R {}
+
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> BaseProductsRepoImpl<'a, T> {
     pub fn new(db_conn: &'a T, acl: Box<RepoAcl<BaseProduct>>) -> Self {
         Self { db_conn, acl }
@@ -153,9 +225,52 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
     fn execute_query<Ty: Send + 'static, U: LoadQuery<T, Ty> + Send + 'static>(&self, query: U) -> RepoResult<Ty> {
         query.get_result::<Ty>(self.db_conn).map_err(|e| Error::from(e).into())
     }
+
+    /// Increments views unless the viewer has already viewed the product today, views without viewer are not counted
+    fn count_view(&self, base_product: Option<BaseProductRaw>, viewer_hash: Option<String>) -> RepoResult<Option<BaseProduct>> {
+        let (base_product, viewer_hash) = match (base_product, viewer_hash) {
+            (Some(base_product), Some(viewer_hash)) => (base_product, viewer_hash),
+            (base_product, _) => return Ok(base_product.map(BaseProduct::from)),
+        };
+
+        let new_view = NewProductView {
+            base_product_id: base_product.id,
+            viewer_hash,
+        };
+        let inserted = diesel::insert_into(DslProductViews::product_views)
+            .values(&new_view)
+            .on_conflict_do_nothing()
+            .execute(self.db_conn)
+            .map_err(Error::from)?;
+        if inserted == 0 {
+            return Ok(Some(BaseProduct::from(base_product)));
+        }
+
+        let query = diesel::update(base_products.find(base_product.id)).set(views.eq(views + 1));
+        self.execute_query::<BaseProductRaw, _>(query).map(BaseProduct::from).map(Some)
+    }
+
+    /// Rewrite of `count_with_store_id` with the count converted by the database, checked with shadow reads
+    fn count_with_store_id_rewritten(&self, store_id_arg: StoreId, visibility: Visibility) -> RepoResult<i32> {
+        let query = base_products
+            .filter(store_id.eq(store_id_arg))
+            .select(sql::<Integer>("count(*)::int4"))
+            .into_boxed();
+
+        let query = match visibility {
+            Visibility::Active => query.filter(is_active.eq(true)),
+            Visibility::Published => query.filter(published_filter()),
+            Visibility::Deactivated => query.filter(is_active.eq(false)),
+        };
+
+        query.get_result::<i32>(self.db_conn).map_err(|e| {
+            e.context(format!("Counts products by store id: {} error occurred", store_id_arg))
+                .into()
+        })
+    }
 }
 
-impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> BaseProductsRepo
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> BaseProductsReadRepo
     for BaseProductsRepoImpl<'a, T>
 {
     /// Get base_product count
@@ -164,14 +279,8 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
 
         let query = match visibility {
             Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
-            Visibility::Published => base_products
-                .filter(
-                    is_active
-                        .eq(true)
-                        .and(status.eq(ModerationStatus::Published))
-                        .and(store_status.eq(ModerationStatus::Published)),
-                )
-                .into_boxed(),
+            Visibility::Published => base_products.filter(published_filter()).into_boxed(),
+            Visibility::Deactivated => base_products.filter(is_active.eq(false)).into_boxed(),
         };
 
         acl::check(&*self.acl, Resource::BaseProducts, Action::Read, self, None)
@@ -189,14 +298,8 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
 
         let query = match visibility {
             Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
-            Visibility::Published => base_products
-                .filter(
-                    is_active
-                        .eq(true)
-                        .and(status.eq(ModerationStatus::Published))
-                        .and(store_status.eq(ModerationStatus::Published)),
-                )
-                .into_boxed(),
+            Visibility::Published => base_products.filter(published_filter()).into_boxed(),
+            Visibility::Deactivated => base_products.filter(is_active.eq(false)).into_boxed(),
         };
 
         query
@@ -212,7 +315,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::BaseProducts,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(base_product.status),
+                        read_rule(base_product),
                         Some(base_product),
                     )?;
                 };
@@ -239,14 +342,8 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
 
         let query = match visibility {
             Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
-            Visibility::Published => base_products
-                .filter(
-                    is_active
-                        .eq(true)
-                        .and(status.eq(ModerationStatus::Published))
-                        .and(store_status.eq(ModerationStatus::Published)),
-                )
-                .into_boxed(),
+            Visibility::Published => base_products.filter(published_filter()).into_boxed(),
+            Visibility::Deactivated => base_products.filter(is_active.eq(false)).into_boxed(),
         };
 
         query
@@ -263,7 +360,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::BaseProducts,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(base_product.status),
+                        read_rule(base_product),
                         Some(base_product),
                     )?;
                 };
@@ -350,66 +447,53 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
     fn count_with_store_id(&self, store_id_arg: StoreId, visibility: Visibility) -> RepoResult<i32> {
         debug!("Counts products with store id {}, visibility = {:?}", store_id_arg, visibility);
 
-        let query = match visibility {
-            Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
-            Visibility::Published => base_products
-                .filter(
-                    is_active
-                        .eq(true)
-                        .and(status.eq(ModerationStatus::Published))
-                        .and(store_status.eq(ModerationStatus::Published)),
-                )
-                .into_boxed(),
-        };
+        let old = || -> RepoResult<i32> {
+            let query = match visibility {
+                Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
+                Visibility::Published => base_products.filter(published_filter()).into_boxed(),
+                Visibility::Deactivated => base_products.filter(is_active.eq(false)).into_boxed(),
+            };
 
-        query
-            .filter(store_id.eq(store_id_arg))
-            .count()
-            .get_result(self.db_conn)
-            .optional()
-            .map(|count: Option<i64>| if let Some(count) = count { count as i32 } else { 0 })
-            .map_err(|e| {
-                e.context(format!("Counts products by store id: {} error occurred", store_id_arg))
-                    .into()
-            })
-    }
+            query
+                .filter(store_id.eq(store_id_arg))
+                .count()
+                .get_result(self.db_conn)
+                .optional()
+                .map(|count: Option<i64>| if let Some(count) = count { count as i32 } else { 0 })
+                .map_err(|e| {
+                    e.context(format!("Counts products by store id: {} error occurred", store_id_arg))
+                        .into()
+                })
+        };
 
-    /// Creates new base_product
-    fn create(&self, payload: NewBaseProduct) -> RepoResult<BaseProduct> {
-        debug!("Create base product {:?}.", payload);
-        let query_base_product = diesel::insert_into(base_products).values(&payload);
-        query_base_product
-            .get_result::<BaseProductRaw>(self.db_conn)
-            .map(BaseProduct::from)
-            .map_err(|e| Error::from(e).into())
-            .and_then(|base_prod| {
-                acl::check(&*self.acl, Resource::BaseProducts, Action::Create, self, Some(&base_prod)).and_then(|_| Ok(base_prod))
-            })
-            .map_err(|e: FailureError| e.context(format!("Creates new base_product {:?} error occurred", payload)).into())
+        shadow_read(
+            self.db_conn,
+            "base_products.count_with_store_id",
+            &(store_id_arg, visibility),
+            old,
+            || self.count_with_store_id_rewritten(store_id_arg, visibility),
+        )
     }
 
     /// Returns list of base_products, limited by `from` and `count` parameters
-    fn list(&self, from: BaseProductId, count: i32, visibility: Visibility) -> RepoResult<Vec<BaseProduct>> {
-        debug!(
-            "Find in base products with ids from {} count {} with visibility = {:?}",
-            from, count, visibility
-        );
+    fn list(&self, from: BaseProductsListFrom, count: i32, visibility: Visibility) -> RepoResult<Vec<BaseProduct>> {
+        debug!("Find in base products from {:?} count {} with visibility = {:?}", from, count, visibility);
 
-        let query = match visibility {
+        let mut query = match visibility {
             Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
-            Visibility::Published => base_products
-                .filter(
-                    is_active
-                        .eq(true)
-                        .and(status.eq(ModerationStatus::Published))
-                        .and(store_status.eq(ModerationStatus::Published)),
-                )
-                .into_boxed(),
+            Visibility::Published => base_products.filter(published_filter()).into_boxed(),
+            Visibility::Deactivated => base_products.filter(is_active.eq(false)).into_boxed(),
+        };
+
+        query = match from {
+            BaseProductsListFrom::Id(from_id) => query.filter(id.ge(from_id)).order(id),
+            BaseProductsListFrom::Cursor(None) => query.order((created_at, id)),
+            BaseProductsListFrom::Cursor(Some(cursor)) => query
+                .filter(created_at.gt(cursor.created_at).or(created_at.eq(cursor.created_at).and(id.gt(cursor.id))))
+                .order((created_at, id)),
         };
 
         query
-            .filter(id.ge(from))
-            .order(id)
             .limit(count.into())
             .get_results::<BaseProductRaw>(self.db_conn)
             .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
@@ -421,19 +505,13 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::BaseProducts,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(base_product.status),
+                        read_rule(base_product),
                         Some(base_product),
                     )?;
                 }
                 Ok(base_products_res)
             })
-            .map_err(|e: FailureError| {
-                e.context(format!(
-                    "Find in base products with ids from {} count {} error occurred",
-                    from, count
-                ))
-                .into()
-            })
+            .map_err(|e: FailureError| e.context(format!("Find in base products from {:?} count {} error occurred", from, count)).into())
     }
 
     /// Returns list of base_products by store id and skip skip_base_product_id, limited by from and count
@@ -451,15 +529,9 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
         );
 
         let mut query = match visibility {
-            Visibility::Active => base_products.filter(is_active.eq(true)).into_boxed(),
-            Visibility::Published => base_products
-                .filter(
-                    is_active
-                        .eq(true)
-                        .and(status.eq(ModerationStatus::Published))
-                        .and(store_status.eq(ModerationStatus::Published)),
-                )
-                .into_boxed(),
+            Visibility::Active => base_products.select(id).filter(is_active.eq(true)).into_boxed(),
+            Visibility::Published => base_products.select(id).filter(published_filter()).into_boxed(),
+            Visibility::Deactivated => base_products.select(id).filter(is_active.eq(false)).into_boxed(),
         };
 
         query = query.filter(store_id.eq(store_id_arg));
@@ -468,12 +540,30 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             query = query.filter(id.ne(skip_base_product_id));
         }
 
-        query = query.filter(id.ge(from)).order(id).limit(count.into());
+        let page_ids = query
+            .order(id)
+            .get_results::<BaseProductId>(self.db_conn)
+            .and_then(|mut store_base_product_ids| {
+                let positions = StoreProductPositions::store_product_positions
+                    .filter(StoreProductPositions::store_id.eq(store_id_arg))
+                    .get_results::<StoreProductPosition>(self.db_conn)?;
+                sort_in_storefront_order(&mut store_base_product_ids, &positions);
+                Ok(storefront_page(store_base_product_ids, from, count))
+            })
+            .map_err(|e| Error::from(e).into());
 
-        query
-            .get_results::<BaseProductRaw>(self.db_conn)
-            .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
-            .map_err(|e| Error::from(e).into())
+        page_ids
+            .and_then(|page_ids: Vec<BaseProductId>| {
+                base_products
+                    .filter(id.eq_any(page_ids.clone()))
+                    .get_results::<BaseProductRaw>(self.db_conn)
+                    .map(|raw_base_products| {
+                        let mut base_products_res = raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>();
+                        base_products_res.sort_by_key(|base_product| page_ids.iter().position(|page_id| *page_id == base_product.id));
+                        base_products_res
+                    })
+                    .map_err(|e| Error::from(e).into())
+            })
             .and_then(|base_products_res: Vec<BaseProduct>| {
                 for base_product in &base_products_res {
                     acl::check_with_rule(
@@ -481,7 +571,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::BaseProducts,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(base_product.status),
+                        read_rule(base_product),
                         Some(base_product),
                     )?;
                 }
@@ -496,127 +586,6 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             })
     }
 
-    /// Updates specific base_product
-    fn update(&self, base_product_id_arg: BaseProductId, payload: UpdateBaseProduct) -> RepoResult<BaseProduct> {
-        debug!("Updating base product with id {} and payload {:?}.", base_product_id_arg, payload);
-        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
-            .map(BaseProduct::from)
-            .and_then(|base_product| {
-                acl::check_with_rule(
-                    &*self.acl,
-                    Resource::BaseProducts,
-                    Action::Update,
-                    self,
-                    Rule::ModerationStatus(base_product.status),
-                    Some(&base_product),
-                )
-            })
-            .and_then(|_| {
-                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(true));
-
-                let query = diesel::update(filter).set(&payload);
-
-                query
-                    .get_result::<BaseProductRaw>(self.db_conn)
-                    .map(BaseProduct::from)
-                    .map_err(|e| Error::from(e).into())
-            })
-            .map_err(|e: FailureError| {
-                e.context(format!(
-                    "Updating base product with id {} and payload {:?} failed.",
-                    base_product_id_arg, payload
-                ))
-                .into()
-            })
-    }
-
-    /// Update views on specific base_product
-    fn update_views(&self, base_product_id_arg: BaseProductId) -> RepoResult<Option<BaseProduct>> {
-        debug!("Updating views of base product with id {}.", base_product_id_arg);
-        let filter = base_products
-            .filter(id.eq(base_product_id_arg))
-            .filter(is_active.eq(true))
-            .filter(status.eq(ModerationStatus::Published));
-        let query = diesel::update(filter).set(views.eq(views + 1));
-        query
-            .get_result::<BaseProductRaw>(self.db_conn)
-            .map(BaseProduct::from)
-            .optional()
-            .map_err(|e| Error::from(e).into())
-            .map_err(|e: FailureError| {
-                e.context(format!("Updating views of base product with id {} failed", base_product_id_arg))
-                    .into()
-            })
-    }
-
-    /// Update views on specific base_product by slug
-    fn update_views_by_slug(&self, store_id_arg: StoreId, base_product_slug: BaseProductSlug) -> RepoResult<Option<BaseProduct>> {
-        debug!("Updating views of base product with slug {}.", base_product_slug);
-        let filter = base_products
-            .filter(slug.eq(&base_product_slug))
-            .filter(is_active.eq(true))
-            .filter(status.eq(ModerationStatus::Published))
-            .filter(store_id.eq(&store_id_arg));
-        let query = diesel::update(filter).set(views.eq(views + 1));
-        query
-            .get_result::<BaseProductRaw>(self.db_conn)
-            .map(BaseProduct::from)
-            .optional()
-            .map_err(|e| Error::from(e).into())
-            .map_err(|e: FailureError| {
-                e.context(format!("Updating views of base product with slug {} failed", base_product_slug))
-                    .into()
-            })
-    }
-
-    /// Deactivates specific base_product
-    fn deactivate(&self, base_product_id_arg: BaseProductId) -> RepoResult<BaseProduct> {
-        debug!("Deactivate base product with id {}.", base_product_id_arg);
-        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
-            .map(BaseProduct::from)
-            .and_then(|base_product| acl::check(&*self.acl, Resource::BaseProducts, Action::Delete, self, Some(&base_product)))
-            .and_then(|_| {
-                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(true));
-                let query = diesel::update(filter).set(is_active.eq(false));
-                self.execute_query::<BaseProductRaw, _>(query).map(BaseProduct::from)
-            })
-            .map_err(|e: FailureError| {
-                e.context(format!("Deactivate base product with id {} failed", base_product_id_arg))
-                    .into()
-            })
-    }
-
-    /// Deactivates base_products by store_id
-    fn deactivate_by_store(&self, store_id_arg: StoreId) -> RepoResult<Vec<BaseProduct>> {
-        debug!("Deactivate base products by store id {}.", store_id_arg);
-
-        let query = base_products.filter(store_id.eq(store_id_arg));
-
-        query
-            .get_results::<BaseProductRaw>(self.db_conn)
-            .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
-            .map_err(|e| Error::from(e).into())
-            .and_then(|results: Vec<BaseProduct>| {
-                for base_product in &results {
-                    acl::check(&*self.acl, Resource::BaseProducts, Action::Delete, self, Some(base_product))?;
-                }
-
-                Ok(results)
-            })
-            .and_then(|_| {
-                let filtered = base_products.filter(store_id.eq(store_id_arg)).filter(is_active.eq(true));
-                let query_update = diesel::update(filtered).set(is_active.eq(false));
-                query_update
-                    .get_results::<BaseProductRaw>(self.db_conn)
-                    .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
-                    .map_err(|e| Error::from(e).into())
-            })
-            .map_err(|e: FailureError| {
-                e.context(format!("Deactivate base products by store_id {} failed", store_id_arg))
-                    .into()
-            })
-    }
-
     /// Checks that slug already exists
     fn slug_exists(&self, slug_arg: String) -> RepoResult<bool> {
         debug!("Check if store slug {} exists.", slug_arg);
@@ -643,7 +612,8 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                     .map(|(n, id_arg)| (id_arg, n))
                     .collect::<HashMap<_, _>>();
 
-                let base_products_query = base_products.filter(id.eq_any(base_products_ids));
+                // Products hidden by owners are not filtered out by elastic
+                let base_products_query = base_products.filter(id.eq_any(base_products_ids)).filter(published_at.is_not_null());
                 let base_products_list = base_products_query.get_results::<BaseProductRaw>(self.db_conn)?;
 
                 // sorting in elastic order
@@ -669,10 +639,10 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                     })
                     .collect::<Vec<ProductId>>();
 
+                // only matched variants are loaded, price range of the base product comes from its own columns
                 let variants = RawProduct::belonging_to(&base_products_list)
-                    .get_results(self.db_conn)?
-                    .into_iter()
-                    .filter(|prod: &RawProduct| variants_ids.iter().any(|id_arg| *id_arg == prod.id))
+                    .filter(Products::id.eq_any(variants_ids))
+                    .get_results::<RawProduct>(self.db_conn)?
                     .grouped_by(&base_products_list);
 
                 Ok(base_products_list
@@ -696,6 +666,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                 let mut base_products_query = base_products
                     .filter(is_active.eq(true))
                     .filter(status.eq(ModerationStatus::Published))
+                    .filter(published_at.is_not_null())
                     .into_boxed();
 
                 if let Some(options) = search_product.options {
@@ -736,33 +707,73 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .map_err(|e: FailureError| e.context("Querying for most viewed base products failed").into())
     }
 
-    /// Returns most discount list of base_products, limited by `from` and `count` parameters
-    fn most_discount(&self, search_product: MostDiscountProducts, count: i32, offset: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+    /// Weighted sampling without replacement (Efraimidis-Spirakis): every row gets key `u^(1/weight)` for uniform `u`
+    /// and rows with the largest keys are taken, so postgres only keeps `count` rows instead of shuffling the table.
+    fn sample(&self, categories_ids: Option<Vec<CategoryId>>, count: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
         acl::check(&*self.acl, Resource::BaseProducts, Action::Read, self, None)
             .and_then(|_| {
-                debug!("Querying for most discount products.");
+                debug!("Sampling {} base products of categories {:?}.", count, categories_ids);
 
-                let products_query = Products::products
-                    .filter(Products::is_active.eq(true))
-                    .filter(Products::discount.is_not_null())
-                    .order_by(Products::discount.desc())
-                    .offset(offset.into())
-                    .limit(count.into());
+                let mut base_products_query = base_products.filter(published_filter()).into_boxed();
 
-                let variants = products_query.get_results::<RawProduct>(self.db_conn)?;
+                if let Some(categories_ids) = categories_ids {
+                    base_products_query = base_products_query.filter(category_id.eq_any(categories_ids));
+                }
 
-                let base_products_ids = variants.iter().map(|p| p.base_product_id).collect::<Vec<BaseProductId>>();
+                // ln(u) / weight orders rows the same way as u^(1/weight), 1 - random() is never zero
+                let base_products_list = base_products_query
+                    .order_by(sql::<Double>("ln(1 - random()) / (1 + rating + ln(1 + views)) DESC"))
+                    .limit(count.into())
+                    .get_results::<BaseProductRaw>(self.db_conn)?;
 
-                let hashed_ids = base_products_ids
-                    .clone()
-                    .into_iter()
-                    .enumerate()
+                let variants = RawProduct::belonging_to(&base_products_list)
+                    .filter(Products::is_active.eq(true))
+                    .get_results::<RawProduct>(self.db_conn)?
+                    .grouped_by(&base_products_list);
+
+                Ok(base_products_list
+                    .into_iter()
+                    .zip(variants)
+                    .map(|(base, vars)| {
+                        let vars = vars.into_iter().map(Product::from).collect();
+                        BaseProductWithVariants::new(BaseProduct::from(base), vars)
+                    })
+                    .collect())
+            })
+            .map_err(|e: FailureError| e.context("Sampling base products failed").into())
+    }
+
+    /// Returns most discount list of base_products, limited by `from` and `count` parameters
+    fn most_discount(&self, search_product: MostDiscountProducts, count: i32, offset: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+        acl::check(&*self.acl, Resource::BaseProducts, Action::Read, self, None)
+            .and_then(|_| {
+                debug!("Querying for most discount products.");
+
+                let now = SystemTime::now();
+                let products_query = Products::products
+                    .filter(Products::is_active.eq(true))
+                    .filter(Products::discount.is_not_null())
+                    .filter(Products::discount_starts_at.is_null().or(Products::discount_starts_at.le(now)))
+                    .filter(Products::discount_ends_at.is_null().or(Products::discount_ends_at.gt(now)))
+                    .order_by(Products::discount.desc())
+                    .offset(offset.into())
+                    .limit(count.into());
+
+                let variants = products_query.get_results::<RawProduct>(self.db_conn)?;
+
+                let base_products_ids = variants.iter().map(|p| p.base_product_id).collect::<Vec<BaseProductId>>();
+
+                let hashed_ids = base_products_ids
+                    .clone()
+                    .into_iter()
+                    .enumerate()
                     .map(|(n, id_arg)| (id_arg, n))
                     .collect::<HashMap<_, _>>();
 
                 let mut base_products_query = base_products
                     .filter(id.eq_any(base_products_ids))
                     .filter(status.eq(ModerationStatus::Published))
+                    .filter(published_at.is_not_null())
                     .into_boxed();
 
                 if let Some(options) = search_product.options {
@@ -884,6 +895,476 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             })
     }
 
+    /// Getting all base products with variants
+    fn get_all_catalog(&self) -> RepoResult<Vec<CatalogWithAttributes>> {
+        debug!("Getting all base products with variants.");
+
+        let all_base_products = base_products
+            .filter(published_filter())
+            .order(id)
+            .get_results::<BaseProductRaw>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Getting all base products with variants."))?;
+
+        let all_products = RawProduct::belonging_to(&all_base_products)
+            .filter(Products::is_active.eq(true))
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Getting all variants."))?
+            .grouped_by(&all_base_products);
+
+        all_base_products
+            .into_iter()
+            .zip(all_products)
+            .map(|(base_raw, variants): (BaseProductRaw, Vec<RawProduct>)| {
+                let base = BaseProduct::from(base_raw);
+                let prod_ids = variants.iter().map(|v| v.id).collect::<Vec<ProductId>>();
+
+                let query = DslProdAttr::prod_attr_values
+                    .filter(DslProdAttr::prod_id.eq_any(prod_ids))
+                    .inner_join(DslAttributes::attributes);
+
+                query
+                    .get_results::<(ProdAttr, Attribute)>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+                    .and_then(|attributes| {
+                        let mut variants_attributes = vec![];
+                        for variant in variants {
+                            let search_attributes = attributes.clone();
+                            let prod_attributes =
+                                search_attributes
+                                    .into_iter()
+                                    .filter(|v| v.0.prod_id == variant.id)
+                                    .collect::<Vec<(ProdAttr, Attribute)>>();
+                            let product = ProductWithAttributes::new(variant, prod_attributes);
+
+                            variants_attributes.push(product);
+                        }
+
+                        Ok(CatalogWithAttributes::new(base, variants_attributes))
+                    })
+            })
+            .collect::<RepoResult<Vec<_>>>()
+    }
+
+    fn find_many_with_variants(&self, base_product_ids: Vec<BaseProductId>) -> RepoResult<Vec<CatalogWithAttributes>> {
+        debug!("Find many base products with variants, count = {}", base_product_ids.len());
+
+        let raw_base_products = base_products
+            .filter(id.eq_any(base_product_ids))
+            .get_results::<BaseProductRaw>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|results: Vec<BaseProductRaw>| {
+                for raw_base_product in results.iter() {
+                    let base_product = BaseProduct::from(raw_base_product.clone());
+                    acl::check_with_rule(
+                        &*self.acl,
+                        Resource::BaseProducts,
+                        Action::Read,
+                        self,
+                        Rule::ModerationStatus(base_product.status),
+                        Some(&base_product),
+                    )?;
+                }
+                Ok(results)
+            })
+            .map_err(|e: FailureError| e.context("Find many base products with variants error occurred"))?;
+
+        let all_variants = RawProduct::belonging_to(&raw_base_products)
+            .filter(Products::is_active.eq(true))
+            .get_results::<RawProduct>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find variants of many base products error occurred"))?;
+
+        let variant_ids = all_variants.iter().map(|variant| variant.id).collect::<Vec<ProductId>>();
+        let mut attributes_by_variant = HashMap::<ProductId, Vec<(ProdAttr, Attribute)>>::new();
+        DslProdAttr::prod_attr_values
+            .filter(DslProdAttr::prod_id.eq_any(variant_ids))
+            .inner_join(DslAttributes::attributes)
+            .get_results::<(ProdAttr, Attribute)>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find attributes of many base products error occurred"))?
+            .into_iter()
+            .for_each(|attribute| attributes_by_variant.entry(attribute.0.prod_id).or_insert_with(Vec::new).push(attribute));
+
+        let variants_by_base_product = all_variants.grouped_by(&raw_base_products);
+        Ok(raw_base_products
+            .into_iter()
+            .zip(variants_by_base_product)
+            .map(|(raw_base_product, variants)| {
+                let variants = variants
+                    .into_iter()
+                    .map(|variant| {
+                        let attributes = attributes_by_variant.remove(&variant.id).unwrap_or_default();
+                        ProductWithAttributes::new(variant, attributes)
+                    })
+                    .collect();
+                CatalogWithAttributes::new(BaseProduct::from(raw_base_product), variants)
+            })
+            .collect())
+    }
+
+    fn find_by_attribute_fingerprint(
+        &self,
+        category_id_arg: CategoryId,
+        fingerprint: AttributeFingerprint,
+    ) -> RepoResult<Vec<CatalogWithAttributes>> {
+        debug!("Find base products of category {:?} by attribute fingerprint {:?}", category_id_arg, fingerprint);
+
+        let first_attr_id = match fingerprint.attribute_ids().into_iter().next() {
+            Some(first_attr_id) => first_attr_id,
+            None => return Ok(vec![]),
+        };
+
+        // Values are compared after normalization, so the query only narrows down candidates by attribute
+        let candidate_ids = DslProdAttr::prod_attr_values
+            .inner_join(base_products)
+            .filter(DslProdAttr::attr_id.eq(first_attr_id))
+            .filter(category_id.eq(category_id_arg))
+            .filter(is_active.eq(true))
+            .filter(status.eq(ModerationStatus::Published))
+            .filter(store_status.eq(ModerationStatus::Published))
+            .filter(published_at.is_not_null())
+            .select(id)
+            .distinct()
+            .get_results::<BaseProductId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find base products by attribute fingerprint error occurred"))?;
+
+        let mut matched = self
+            .find_many_with_variants(candidate_ids)?
+            .into_iter()
+            .filter_map(|mut catalog| {
+                catalog.variants.retain(|variant| {
+                    AttributeFingerprint::from_prod_attrs(variant.attributes.iter().map(|&(ref prod_attr, _)| prod_attr)) == fingerprint
+                });
+                if catalog.variants.is_empty() {
+                    None
+                } else {
+                    Some(catalog)
+                }
+            })
+            .collect::<Vec<_>>();
+        matched.sort_by_key(|catalog| catalog.base_product.id.0);
+        Ok(matched)
+    }
+
+    fn find_related(&self, base_product_id_arg: BaseProductId, count: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+        acl::check(&*self.acl, Resource::BaseProducts, Action::Read, self, None)
+            .and_then(|_| {
+                debug!("Find {} base products related to base product {}.", count, base_product_id_arg);
+
+                let base_product = base_products
+                    .find(base_product_id_arg)
+                    .get_result::<BaseProductRaw>(self.db_conn)
+                    .map_err(Error::from)?;
+                let attr_values = DslProdAttr::prod_attr_values
+                    .filter(DslProdAttr::base_prod_id.eq(base_product_id_arg))
+                    .select((DslProdAttr::attr_id, DslProdAttr::value))
+                    .get_results::<(AttributeId, String)>(self.db_conn)?
+                    .into_iter()
+                    .collect::<HashSet<_>>();
+                let attr_ids = attr_values
+                    .iter()
+                    .map(|&(attr_id, _)| attr_id)
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                if attr_ids.is_empty() {
+                    return Ok(vec![]);
+                }
+
+                // The query narrows down candidates by attribute, shared values are counted here
+                let candidates = DslProdAttr::prod_attr_values
+                    .inner_join(base_products)
+                    .filter(DslProdAttr::attr_id.eq_any(attr_ids))
+                    .filter(category_id.eq(base_product.category_id))
+                    .filter(id.ne(base_product_id_arg))
+                    .filter(is_active.eq(true))
+                    .filter(status.eq(ModerationStatus::Published))
+                    .filter(store_status.eq(ModerationStatus::Published))
+                    .filter(published_at.is_not_null())
+                    .select((DslProdAttr::base_prod_id, DslProdAttr::attr_id, DslProdAttr::value))
+                    .distinct()
+                    .get_results::<(BaseProductId, AttributeId, String)>(self.db_conn)?;
+
+                let mut shared_values = HashMap::<BaseProductId, usize>::new();
+                for (candidate_id, attr_id, value) in candidates {
+                    if attr_values.contains(&(attr_id, value)) {
+                        *shared_values.entry(candidate_id).or_insert(0) += 1;
+                    }
+                }
+                let mut ranked = shared_values.into_iter().collect::<Vec<_>>();
+                ranked.sort_by_key(|&(candidate_id, shared)| (Reverse(shared), candidate_id.0));
+                let related_ids = ranked
+                    .into_iter()
+                    .take(count as usize)
+                    .map(|(candidate_id, _)| candidate_id)
+                    .collect::<Vec<_>>();
+
+                let mut base_products_list = base_products
+                    .filter(id.eq_any(related_ids.clone()))
+                    .get_results::<BaseProductRaw>(self.db_conn)?;
+                base_products_list.sort_by_key(|base| related_ids.iter().position(|related_id| *related_id == base.id));
+
+                let variants = RawProduct::belonging_to(&base_products_list)
+                    .filter(Products::is_active.eq(true))
+                    .get_results::<RawProduct>(self.db_conn)?
+                    .grouped_by(&base_products_list);
+
+                Ok(base_products_list
+                    .into_iter()
+                    .zip(variants)
+                    .map(|(base, vars)| {
+                        let vars = vars.into_iter().map(Product::from).collect();
+                        BaseProductWithVariants::new(BaseProduct::from(base), vars)
+                    })
+                    .collect())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find base products related to base product {} error occurred", base_product_id_arg))
+                    .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> BaseProductsWriteRepo
+    for BaseProductsRepoImpl<'a, T>
+{
+    /// Creates new base_product
+    fn create(&self, payload: NewBaseProduct) -> RepoResult<BaseProduct> {
+        debug!("Create base product {:?}.", payload);
+        let query_base_product = diesel::insert_into(base_products).values(&payload);
+        query_base_product
+            .get_result::<BaseProductRaw>(self.db_conn)
+            .map(BaseProduct::from)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|base_prod| {
+                acl::check(&*self.acl, Resource::BaseProducts, Action::Create, self, Some(&base_prod)).and_then(|_| Ok(base_prod))
+            })
+            .map_err(|e: FailureError| e.context(format!("Creates new base_product {:?} error occurred", payload)).into())
+    }
+
+    /// Updates specific base_product
+    fn update(&self, base_product_id_arg: BaseProductId, payload: UpdateBaseProduct) -> RepoResult<BaseProduct> {
+        debug!("Updating base product with id {} and payload {:?}.", base_product_id_arg, payload);
+        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
+            .map(BaseProduct::from)
+            .and_then(|base_product| {
+                acl::check_with_rule(
+                    &*self.acl,
+                    Resource::BaseProducts,
+                    Action::Update,
+                    self,
+                    Rule::ModerationStatus(base_product.status),
+                    Some(&base_product),
+                )
+            })
+            .and_then(|_| {
+                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(true));
+
+                let query = diesel::update(filter).set(&payload);
+
+                query
+                    .get_result::<BaseProductRaw>(self.db_conn)
+                    .map(BaseProduct::from)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Updating base product with id {} and payload {:?} failed.",
+                    base_product_id_arg, payload
+                ))
+                .into()
+            })
+    }
+
+    /// Update views on specific base_product, a view is counted once per viewer per day
+    fn update_views(&self, base_product_id_arg: BaseProductId, viewer_hash: Option<String>) -> RepoResult<Option<BaseProduct>> {
+        debug!("Updating views of base product with id {}.", base_product_id_arg);
+        let query = base_products
+            .filter(id.eq(base_product_id_arg))
+            .filter(is_active.eq(true))
+            .filter(status.eq(ModerationStatus::Published))
+            .filter(published_at.is_not_null());
+        query
+            .get_result::<BaseProductRaw>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|base_product| self.count_view(base_product, viewer_hash))
+            .map_err(|e: FailureError| {
+                e.context(format!("Updating views of base product with id {} failed", base_product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Update views on specific base_product by slug, a view is counted once per viewer per day
+    fn update_views_by_slug(
+        &self,
+        store_id_arg: StoreId,
+        base_product_slug: BaseProductSlug,
+        viewer_hash: Option<String>,
+    ) -> RepoResult<Option<BaseProduct>> {
+        debug!("Updating views of base product with slug {}.", base_product_slug);
+        let query = base_products
+            .filter(slug.eq(&base_product_slug))
+            .filter(is_active.eq(true))
+            .filter(status.eq(ModerationStatus::Published))
+            .filter(published_at.is_not_null())
+            .filter(store_id.eq(&store_id_arg));
+        query
+            .get_result::<BaseProductRaw>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|base_product| self.count_view(base_product, viewer_hash))
+            .map_err(|e: FailureError| {
+                e.context(format!("Updating views of base product with slug {} failed", base_product_slug))
+                    .into()
+            })
+    }
+
+    /// Deactivates specific base_product
+    fn deactivate(&self, base_product_id_arg: BaseProductId) -> RepoResult<BaseProduct> {
+        debug!("Deactivate base product with id {}.", base_product_id_arg);
+        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
+            .map(BaseProduct::from)
+            .and_then(|base_product| acl::check(&*self.acl, Resource::BaseProducts, Action::Delete, self, Some(&base_product)))
+            .and_then(|_| {
+                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(true));
+                let query = diesel::update(filter).set(is_active.eq(false));
+                self.execute_query::<BaseProductRaw, _>(query).map(BaseProduct::from)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate base product with id {} failed", base_product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Activates deactivated base_product, its `kafka_update_no` is bumped to get it indexed again
+    fn activate(&self, base_product_id_arg: BaseProductId) -> RepoResult<BaseProduct> {
+        debug!("Activate base product with id {}.", base_product_id_arg);
+        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
+            .map(BaseProduct::from)
+            .and_then(|base_product| acl::check(&*self.acl, Resource::BaseProducts, Action::Delete, self, Some(&base_product)))
+            .and_then(|_| {
+                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(false));
+                let query = diesel::update(filter).set((is_active.eq(true), kafka_update_no.eq(kafka_update_no + 1)));
+                self.execute_query::<BaseProductRaw, _>(query).map(BaseProduct::from)
+            })
+            .map_err(|e: FailureError| e.context(format!("Activate base product with id {} failed", base_product_id_arg)).into())
+    }
+
+    /// Shows base_product to customers or hides it from them, published base products have `published_at`
+    fn set_published(&self, base_product_id_arg: BaseProductId, published: bool) -> RepoResult<BaseProduct> {
+        debug!("Set published {} for base product with id {}.", published, base_product_id_arg);
+        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
+            .map(BaseProduct::from)
+            .and_then(|base_product| {
+                acl::check_with_rule(
+                    &*self.acl,
+                    Resource::BaseProducts,
+                    Action::Update,
+                    self,
+                    Rule::ModerationStatus(base_product.status),
+                    Some(&base_product),
+                )
+            })
+            .and_then(|_| {
+                let published_at_arg = if published { Some(SystemTime::now()) } else { None };
+                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(true));
+                let query = diesel::update(filter).set((published_at.eq(published_at_arg), kafka_update_no.eq(kafka_update_no + 1)));
+                self.execute_query::<BaseProductRaw, _>(query).map(BaseProduct::from)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set published {} for base product with id {} failed", published, base_product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Moves base_product to the store, its `kafka_update_no` is bumped to get it indexed again
+    fn set_store(&self, base_product_id_arg: BaseProductId, store: &Store) -> RepoResult<BaseProduct> {
+        debug!("Move base product with id {} to store with id {}.", base_product_id_arg, store.id);
+        self.execute_query::<BaseProductRaw, _>(base_products.find(base_product_id_arg))
+            .map(BaseProduct::from)
+            .and_then(|base_product| acl::check(&*self.acl, Resource::BaseProducts, Action::Update, self, Some(&base_product)))
+            .and_then(|_| {
+                let filter = base_products.filter(id.eq(base_product_id_arg)).filter(is_active.eq(true));
+                let query = diesel::update(filter).set((
+                    store_id.eq(store.id),
+                    store_status.eq(store.status),
+                    store_is_on_vacation.eq(store.is_on_vacation),
+                    kafka_update_no.eq(kafka_update_no + 1),
+                ));
+                self.execute_query::<BaseProductRaw, _>(query).map(BaseProduct::from)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Move base product with id {} to store with id {} failed", base_product_id_arg, store.id))
+                    .into()
+            })
+    }
+
+    /// Deactivates base_products by store_id
+    fn deactivate_by_store(&self, store_id_arg: StoreId) -> RepoResult<Vec<BaseProduct>> {
+        debug!("Deactivate base products by store id {}.", store_id_arg);
+
+        let query = base_products.filter(store_id.eq(store_id_arg));
+
+        query
+            .get_results::<BaseProductRaw>(self.db_conn)
+            .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
+            .map_err(|e| Error::from(e).into())
+            .and_then(|results: Vec<BaseProduct>| {
+                for base_product in &results {
+                    acl::check(&*self.acl, Resource::BaseProducts, Action::Delete, self, Some(base_product))?;
+                }
+
+                Ok(results)
+            })
+            .and_then(|_| {
+                let filtered = base_products.filter(store_id.eq(store_id_arg)).filter(is_active.eq(true));
+                let query_update = diesel::update(filtered).set(is_active.eq(false));
+                query_update
+                    .get_results::<BaseProductRaw>(self.db_conn)
+                    .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate base products by store_id {} failed", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Deactivates base_products created under saga ID
+    fn deactivate_by_saga_id(&self, saga_id_arg: SagaId) -> RepoResult<Vec<BaseProduct>> {
+        debug!("Deactivate base products by saga id {}.", saga_id_arg);
+
+        let query = base_products.filter(saga_id.eq(saga_id_arg)).filter(is_active.eq(true));
+
+        query
+            .get_results::<BaseProductRaw>(self.db_conn)
+            .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
+            .map_err(|e| Error::from(e).into())
+            .and_then(|results: Vec<BaseProduct>| {
+                for base_product in &results {
+                    acl::check(&*self.acl, Resource::BaseProducts, Action::Delete, self, Some(base_product))?;
+                }
+
+                Ok(results)
+            })
+            .and_then(|_| {
+                let filtered = base_products.filter(saga_id.eq(saga_id_arg)).filter(is_active.eq(true));
+                let query_update = diesel::update(filtered).set(is_active.eq(false));
+                query_update
+                    .get_results::<BaseProductRaw>(self.db_conn)
+                    .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate base products by saga id {} failed", saga_id_arg))
+                    .into()
+            })
+    }
+
     /// Set moderation status for base_product
     fn set_moderation_statuses(&self, base_product_ids: Vec<BaseProductId>, status_arg: ModerationStatus) -> RepoResult<Vec<BaseProduct>> {
         let query = base_products.filter(id.eq_any(base_product_ids.clone()));
@@ -1023,60 +1504,6 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             })
             .map_err(|e: FailureError| e.context("Replace category in base products error occurred").into())
     }
-
-    /// Getting all base products with variants
-    fn get_all_catalog(&self) -> RepoResult<Vec<CatalogWithAttributes>> {
-        debug!("Getting all base products with variants.");
-
-        let all_base_products = base_products
-            .filter(is_active.eq(true))
-            .filter(status.eq(ModerationStatus::Published))
-            .filter(store_status.eq(ModerationStatus::Published))
-            .order(id)
-            .get_results::<BaseProductRaw>(self.db_conn)
-            .map_err(|e| Error::from(e).into())
-            .map_err(|e: FailureError| e.context("Getting all base products with variants."))?;
-
-        let all_products = RawProduct::belonging_to(&all_base_products)
-            .filter(Products::is_active.eq(true))
-            .get_results(self.db_conn)
-            .map_err(|e| Error::from(e).into())
-            .map_err(|e: FailureError| e.context("Getting all variants."))?
-            .grouped_by(&all_base_products);
-
-        all_base_products
-            .into_iter()
-            .zip(all_products)
-            .map(|(base_raw, variants): (BaseProductRaw, Vec<RawProduct>)| {
-                let base = BaseProduct::from(base_raw);
-                let prod_ids = variants.iter().map(|v| v.id).collect::<Vec<ProductId>>();
-
-                let query = DslProdAttr::prod_attr_values
-                    .filter(DslProdAttr::prod_id.eq_any(prod_ids))
-                    .inner_join(DslAttributes::attributes);
-
-                query
-                    .get_results::<(ProdAttr, Attribute)>(self.db_conn)
-                    .map_err(|e| Error::from(e).into())
-                    .and_then(|attributes| {
-                        let mut variants_attributes = vec![];
-                        for variant in variants {
-                            let search_attributes = attributes.clone();
-                            let prod_attributes =
-                                search_attributes
-                                    .into_iter()
-                                    .filter(|v| v.0.prod_id == variant.id)
-                                    .collect::<Vec<(ProdAttr, Attribute)>>();
-                            let product = ProductWithAttributes::new(variant, prod_attributes);
-
-                            variants_attributes.push(product);
-                        }
-
-                        Ok(CatalogWithAttributes::new(base, variants_attributes))
-                    })
-            })
-            .collect::<RepoResult<Vec<_>>>()
-    }
 }
 
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, BaseProduct>
@@ -1117,6 +1544,10 @@ fn by_moderator_search_terms(term: &ModeratorBaseProductSearchTerms) -> Box<Boxa
         expr = Box::new(expr.and(status.eq(term_state)));
     }
 
+    if let Some(term_category_id) = term.category_id {
+        expr = Box::new(expr.and(category_id.eq(term_category_id)));
+    }
+
     expr
 }
 
@@ -1140,6 +1571,10 @@ impl From<BaseProductsSearchTerms> for FilterBaseProductExpr {
             query = Box::new(query.and(store_id.eq(store_id_filter)));
         }
 
+        if let Some(created_before_filter) = search.created_before {
+            query = Box::new(query.and(created_at.lt(created_before_filter)));
+        }
+
         query
     }
 }
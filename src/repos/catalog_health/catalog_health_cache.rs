@@ -0,0 +1,11 @@
+//! CatalogHealthCache caches catalog health reports of the stores, building a report reads the whole catalog of the store
+use stq_types::StoreId;
+
+use models::CatalogHealthReport;
+use repos::ttl_cache::{TtlCacheImpl, TtlCached};
+
+pub type CatalogHealthCacheImpl<C> = TtlCacheImpl<StoreId, CatalogHealthReport, C>;
+
+impl TtlCached for CatalogHealthReport {
+    const CACHE_NAME: &'static str = "CatalogHealthCache";
+}
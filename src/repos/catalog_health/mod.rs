@@ -0,0 +1,197 @@
+//! Catalog health repo, builds catalog health reports of the stores
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+use stq_cache::cache::Cache;
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{BaseProduct, BaseProductRaw, CatalogHealthReport, RawProduct, Store};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::base_products::dsl as BaseProducts;
+use schema::products::dsl as Products;
+use schema::stores::dsl as Stores;
+
+pub mod catalog_health_cache;
+
+pub use self::catalog_health_cache::*;
+
+/// Catalog health repository, reads stores, base_products and products tables
+pub struct CatalogHealthRepoImpl<'a, C, T>
+where
+    C: Cache<CatalogHealthReport>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Store>>,
+    pub cache: Arc<CatalogHealthCacheImpl<C>>,
+}
+
+pub trait CatalogHealthRepo {
+    /// Returns catalog health report of the active store, cached report is reused
+    /// while it was built with the same `stale_price_days`
+    fn get(&self, store_id: StoreId, stale_price_days: u64) -> RepoResult<Option<CatalogHealthReport>>;
+
+    /// Drops cached report of the store, the report is built again on the next request
+    fn invalidate(&self, store_id: StoreId);
+}
+
+impl<'a, C, T> CatalogHealthRepoImpl<'a, C, T>
+where
+    C: Cache<CatalogHealthReport>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Store>>, cache: Arc<CatalogHealthCacheImpl<C>>) -> Self {
+        Self { db_conn, acl, cache }
+    }
+
+    fn build_report(&self, store: &Store, stale_price_days: u64) -> RepoResult<CatalogHealthReport> {
+        let base_products = BaseProducts::base_products
+            .filter(BaseProducts::store_id.eq(store.id))
+            .filter(BaseProducts::is_active.eq(true))
+            .order(BaseProducts::id)
+            .get_results::<BaseProductRaw>(self.db_conn)
+            .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
+            .map_err(Error::from)?;
+
+        let base_product_ids: Vec<_> = base_products.iter().map(|base_product| base_product.id).collect();
+        let products = Products::products
+            .filter(Products::base_product_id.eq_any(base_product_ids))
+            .filter(Products::is_active.eq(true))
+            .order(Products::id)
+            .get_results::<RawProduct>(self.db_conn)
+            .map_err(Error::from)?;
+
+        Ok(CatalogHealthReport::new(
+            store,
+            &base_products,
+            &products,
+            stale_price_days,
+            SystemTime::now(),
+        ))
+    }
+}
+
+impl<'a, C, T> CatalogHealthRepo for CatalogHealthRepoImpl<'a, C, T>
+where
+    C: Cache<CatalogHealthReport>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    /// Returns catalog health report of the active store, cached report is reused
+    /// while it was built with the same `stale_price_days`
+    fn get(&self, store_id: StoreId, stale_price_days: u64) -> RepoResult<Option<CatalogHealthReport>> {
+        debug!("Get catalog health report of store {}, stale price days {}.", store_id, stale_price_days);
+
+        let store = Stores::stores
+            .filter(Stores::id.eq(store_id))
+            .filter(Stores::is_active.eq(true))
+            .get_result::<Store>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Get catalog health report of store {} error occurred", store_id)))?;
+
+        let store = match store {
+            Some(store) => store,
+            None => return Ok(None),
+        };
+
+        acl::check(&*self.acl, Resource::CatalogHealth, Action::Read, self, Some(&store))?;
+
+        if let Some(report) = self.cache.get(&store_id) {
+            if report.stale_price_days == stale_price_days {
+                return Ok(Some(report));
+            }
+        }
+
+        self.build_report(&store, stale_price_days)
+            .map(|report| {
+                self.cache.set(&store_id, report.clone());
+                Some(report)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Build catalog health report of store {} error occurred", store_id))
+                    .into()
+            })
+    }
+
+    /// Drops cached report of the store, the report is built again on the next request
+    fn invalidate(&self, store_id: StoreId) {
+        debug!("Invalidate catalog health report of store {}.", store_id);
+
+        self.cache.remove(&store_id);
+    }
+}
+
+impl<'a, C, T> CheckScope<Scope, Store> for CatalogHealthRepoImpl<'a, C, T>
+where
+    C: Cache<CatalogHealthReport>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&Store>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|store| store.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel;
+    use diesel::result::Error as DieselError;
+    use stq_cache::cache::NullCache;
+    use stq_types::ProductPrice;
+
+    use super::*;
+    use repos::legacy_acl::SystemACL;
+    use repos::repo_factory::tests::*;
+
+    #[test]
+    #[ignore]
+    fn test_catalog_health_report() {
+        let conn = create_db_connection();
+        conn.test_transaction::<_, DieselError, _>(|| {
+            let fixture = create_db_fixture(&conn);
+            let repo = CatalogHealthRepoImpl::new(
+                &conn,
+                Box::new(SystemACL::default()) as Box<RepoAcl<Store>>,
+                Arc::new(CatalogHealthCacheImpl::new(NullCache::new())),
+            );
+
+            let report = repo.get(fixture.store.id, 30).unwrap().unwrap();
+            assert_eq!(report.base_products_count, 1);
+            assert_eq!(report.products_count, 1);
+            assert_eq!(report.missing_photos, vec![fixture.product.id]);
+            assert_eq!(report.missing_translations, vec![fixture.base_product.id]);
+            assert_eq!(report.out_of_stock, vec![fixture.product.id]);
+            assert!(report.stale_prices.is_empty());
+
+            // price changed long ago stays stale while other fields of the variant change
+            diesel::sql_query("UPDATE products SET price_updated_at = now() - interval '60 days' WHERE id = $1")
+                .bind::<diesel::sql_types::Integer, _>(fixture.product.id.0)
+                .execute(&conn)?;
+            diesel::update(Products::products.filter(Products::id.eq(fixture.product.id)))
+                .set(Products::photo_main.eq("photo.png"))
+                .execute(&conn)?;
+            let report = repo.get(fixture.store.id, 30).unwrap().unwrap();
+            assert!(report.missing_photos.is_empty());
+            assert_eq!(report.stale_prices, vec![fixture.product.id]);
+
+            diesel::update(Products::products.filter(Products::id.eq(fixture.product.id)))
+                .set(Products::price.eq(ProductPrice(20f64)))
+                .execute(&conn)?;
+            let report = repo.get(fixture.store.id, 30).unwrap().unwrap();
+            assert!(report.stale_prices.is_empty());
+            Ok(())
+        });
+    }
+}
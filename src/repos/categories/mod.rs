@@ -8,6 +8,7 @@ use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::VarChar;
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
@@ -17,7 +18,7 @@ use stq_static_resources::ModerationStatus;
 use stq_types::{AttributeId, CategoryId, CategorySlug, UserId};
 
 use models::authorization::*;
-use models::{Attribute, BaseProductRaw, CatAttr, Category, InsertCategory, NewCategory, RawCategory, UpdateCategory};
+use models::{Attribute, CatAttr, Category, CategoryProductsCount, InsertCategory, NewCategory, RawCategory, UpdateCategory};
 use repos::acl;
 use repos::legacy_acl::CheckScope;
 use repos::types::{RepoAcl, RepoResult};
@@ -61,6 +62,9 @@ pub trait CategoriesRepo {
     /// Deletes specific categories
     fn delete_all(&self, category_ids_arg: &[CategoryId]) -> RepoResult<()>;
 
+    /// Moves category with its subtree under another parent, levels of the whole subtree are recomputed
+    fn move_category(&self, category_id_arg: CategoryId, new_parent_id: CategoryId) -> RepoResult<Category>;
+
     /// Returns all categories as a tree
     fn get_all_categories(&self) -> RepoResult<Category>;
 
@@ -70,6 +74,9 @@ pub trait CategoriesRepo {
 
     /// Returns all raw categories
     fn get_raw_categories(&self) -> RepoResult<Vec<RawCategory>>;
+
+    /// Removes categories tree from cache, used after changes committed outside of the repo
+    fn invalidate_cache(&self);
 }
 
 impl<'a, C, T> CategoriesRepoImpl<'a, C, T>
@@ -263,6 +270,63 @@ where
         Ok(())
     }
 
+    /// Moves category with its subtree under another parent, levels of the whole subtree are recomputed
+    fn move_category(&self, category_id_arg: CategoryId, new_parent_id: CategoryId) -> RepoResult<Category> {
+        debug!("Moving category with id {} under category {}.", category_id_arg, new_parent_id);
+        acl::check(&*self.acl, Resource::Categories, Action::Update, self, None)?;
+        self.cache.remove();
+
+        let moved = self
+            .db_conn
+            .transaction::<Category, FailureError, _>(|| {
+                let cats = categories.filter(is_active.eq(true)).load::<RawCategory>(self.db_conn)?;
+                let levels = subtree_levels(&cats, category_id_arg, new_parent_id)?;
+                let subtree_ids = levels.iter().map(|&(category_id, _)| category_id).collect::<Vec<_>>();
+                let product_category_ids = BaseProducts::base_products
+                    .filter(BaseProducts::category_id.eq_any(subtree_ids))
+                    .select(BaseProducts::category_id)
+                    .distinct()
+                    .get_results::<CategoryId>(self.db_conn)?;
+                check_product_categories_levels(&levels, &product_category_ids)?;
+
+                diesel::update(categories.filter(id.eq(category_id_arg)))
+                    .set(parent_id.eq(new_parent_id))
+                    .execute(self.db_conn)?;
+                for level_arg in 1..=Category::MAX_LEVEL_NESTING {
+                    let ids = levels
+                        .iter()
+                        .filter(|&&(_, subtree_level)| subtree_level == level_arg)
+                        .map(|&(category_id, _)| category_id)
+                        .collect::<Vec<_>>();
+                    if !ids.is_empty() {
+                        diesel::update(categories.filter(id.eq_any(ids)))
+                            .set(level.eq(level_arg))
+                            .execute(self.db_conn)?;
+                    }
+                }
+
+                let cats = categories.filter(is_active.eq(true)).load::<RawCategory>(self.db_conn)?;
+                let mut result: Category = cats
+                    .iter()
+                    .find(|cat| cat.id == category_id_arg)
+                    .ok_or_else(|| format_err!("Category with id {} not found", category_id_arg).context(Error::NotFound))?
+                    .into();
+                result.children = create_tree(&cats, Some(category_id_arg));
+                Ok(result)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Moving category with id {} under category {} error occurred",
+                    category_id_arg, new_parent_id
+                ))
+                .into()
+            });
+
+        // Tree could have been cached again by concurrent requests while the transaction was running
+        self.cache.remove();
+        moved
+    }
+
     fn get_raw_categories(&self) -> RepoResult<Vec<RawCategory>> {
         acl::check(&*self.acl, Resource::Categories, Action::Read, self, None)
             .and_then(|_| {
@@ -323,38 +387,39 @@ where
             .and_then(|_| {
                 let cat_hash = self.get_categories_hash()?;
 
-                let data: Vec<(RawCategory, Option<BaseProductRaw>)> = categories
+                let counts = diesel::sql_query(
+                    "SELECT category_id, COUNT(*) AS products_count FROM base_products \
+                     WHERE is_active AND status = $1 AND published_at IS NOT NULL GROUP BY category_id",
+                )
+                .bind::<VarChar, _>(ModerationStatus::Published)
+                .load::<CategoryProductsCount>(self.db_conn)?
+                .into_iter()
+                .map(|count| (count.category_id, count.products_count))
+                .collect::<HashMap<_, _>>();
+
+                let cats: Vec<RawCategory> = categories
                     .filter(is_active.eq(true))
-                    .left_join(
-                        BaseProducts::base_products.on(BaseProducts::is_active.eq(true).and(
-                            BaseProducts::status
-                                .eq(ModerationStatus::Published)
-                                .and(id.eq(BaseProducts::category_id)),
-                        )),
-                    )
-                    .load(self.db_conn)?;
-
-                let mut cats: Vec<RawCategory> = data
+                    .load::<RawCategory>(self.db_conn)?
                     .into_iter()
-                    .filter_map(|(cat, base_product)| match (cat.level, base_product) {
-                        (cat_level, Some(_)) if cat_level == CATEGORY_LEVEL3 => Some(cat),
-                        (cat_level, _) if cat_level < CATEGORY_LEVEL3 => Some(cat),
-                        _ => None,
-                    })
+                    .filter(|cat| cat.level < CATEGORY_LEVEL3 || (cat.level == CATEGORY_LEVEL3 && counts.contains_key(&cat.id)))
                     .collect();
 
-                cats.sort();
-                cats.dedup();
-
                 let mut root = Category::default();
                 let children = create_tree(&cats, Some(root.id));
                 root.children = children;
                 set_attributes(&mut root, &cat_hash);
+                set_products_count(&mut root, &counts);
 
                 Ok(root)
             })
             .map_err(|e: FailureError| e.context("Get `get_all_categories_with_products` error occurred").into())
     }
+
+    /// Removes categories tree from cache, used after changes committed outside of the repo
+    fn invalidate_cache(&self) {
+        debug!("Invalidating categories cache");
+        self.cache.remove();
+    }
 }
 
 fn create_tree(cats: &[RawCategory], parent_id_arg: Option<CategoryId>) -> Vec<Category> {
@@ -370,6 +435,81 @@ fn create_tree(cats: &[RawCategory], parent_id_arg: Option<CategoryId>) -> Vec<C
     branch
 }
 
+/// New levels of the category and all its descendants after moving the category under `new_parent_id`.
+/// Fails if the new parent is inside the subtree or the subtree gets deeper than `Category::MAX_LEVEL_NESTING`
+pub fn subtree_levels(cats: &[RawCategory], category_id_arg: CategoryId, new_parent_id: CategoryId) -> RepoResult<Vec<(CategoryId, i32)>> {
+    if !cats.iter().any(|cat| cat.id == category_id_arg) {
+        return Err(format_err!("Category with id {} not found", category_id_arg)
+            .context(Error::NotFound)
+            .into());
+    }
+
+    let parent_level = if new_parent_id == CategoryId(0) {
+        0
+    } else {
+        cats.iter()
+            .find(|cat| cat.id == new_parent_id)
+            .map(|cat| cat.level)
+            .ok_or_else(|| format_err!("Parent category with id {} not found", new_parent_id).context(Error::NotFound))?
+    };
+
+    let mut levels = vec![];
+    let mut current = vec![category_id_arg];
+    let mut current_level = parent_level + 1;
+    while !current.is_empty() {
+        if current.contains(&new_parent_id) {
+            return Err(format_err!("Category {} can not be moved under its own subtree", category_id_arg)
+                .context(Error::Validate(
+                    validation_errors!({"parent_id": ["parent_id" => "Category can not be moved under itself or its children"]}),
+                ))
+                .into());
+        }
+        if current_level > Category::MAX_LEVEL_NESTING {
+            return Err(format_err!(
+                "Category {} is too deep under category {} (max level: {})",
+                category_id_arg,
+                new_parent_id,
+                Category::MAX_LEVEL_NESTING
+            )
+            .context(Error::Validate(
+                validation_errors!({"parent_id": ["parent_id" => "Subtree of the category does not fit under the parent"]}),
+            ))
+            .into());
+        }
+
+        levels.extend(current.iter().map(|&category_id| (category_id, current_level)));
+        current = cats
+            .iter()
+            .filter(|cat| cat.parent_id.map(|parent| current.contains(&parent)).unwrap_or(false))
+            .map(|cat| cat.id)
+            .collect();
+        current_level += 1;
+    }
+
+    Ok(levels)
+}
+
+/// Base products are kept in categories of `Category::MAX_LEVEL_NESTING`, so a move must not change levels of their categories
+pub fn check_product_categories_levels(levels: &[(CategoryId, i32)], product_category_ids: &[CategoryId]) -> RepoResult<()> {
+    let moved_category = levels
+        .iter()
+        .find(|&&(category_id, subtree_level)| product_category_ids.contains(&category_id) && subtree_level != Category::MAX_LEVEL_NESTING);
+    if let Some(&(category_id, subtree_level)) = moved_category {
+        return Err(format_err!(
+            "Category {} with base products would be moved to level {} (required level: {})",
+            category_id,
+            subtree_level,
+            Category::MAX_LEVEL_NESTING
+        )
+        .context(Error::Validate(
+            validation_errors!({"parent_id": ["parent_id" => "Categories with products must stay at the last level"]}),
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
 pub fn remove_unused_categories(mut cat: Category, used_categories_ids: &[CategoryId]) -> Category {
     let mut children = vec![];
     for cat_child in cat.children {
@@ -469,6 +609,16 @@ pub fn get_all_children_till_the_end(cat: Category) -> Vec<Category> {
     }
 }
 
+/// Sets products count of the category and all its descendants, counts include products of descendants
+pub fn set_products_count<S: BuildHasher>(cat: &mut Category, counts: &HashMap<CategoryId, i64, S>) -> i64 {
+    let mut products_count = counts.get(&cat.id).cloned().unwrap_or(0);
+    for child in &mut cat.children {
+        products_count += set_products_count(child, counts);
+    }
+    cat.products_count = Some(products_count);
+    products_count
+}
+
 pub fn set_attributes<S: BuildHasher>(cat: &mut Category, attrs_hash: &HashMap<CategoryId, Vec<Attribute>, S>) {
     if cat.children.is_empty() {
         let attributes = attrs_hash.get(&cat.id).cloned();
@@ -498,6 +648,7 @@ mod tests {
     use super::*;
     use models::*;
     use serde_json;
+    use uuid::Uuid;
 
     fn create_mock_category(id_: CategoryId, parent_id_: CategoryId, level_: i32) -> Category {
         Category {
@@ -510,6 +661,7 @@ mod tests {
             parent_id: Some(parent_id_),
             attributes: vec![],
             slug: CategorySlug("1".to_string()),
+            products_count: None,
         }
     }
 
@@ -558,6 +710,7 @@ mod tests {
             parent_id: None,
             attributes: vec![],
             slug: CategorySlug("1".to_string()),
+            products_count: None,
         }
     }
 
@@ -573,6 +726,7 @@ mod tests {
             parent_id: None,
             attributes: vec![],
             slug: CategorySlug("1".to_string()),
+            products_count: None,
         };
         let level_ = get_child_category_level(lvl1_category);
         assert_eq!(Some(2), level_.ok());
@@ -590,11 +744,81 @@ mod tests {
             parent_id: None,
             attributes: vec![],
             slug: CategorySlug("1".to_string()),
+            products_count: None,
         };
         let level_ = get_child_category_level(lvl3_category);
         assert!(level_.is_err());
     }
 
+    fn create_raw_category(id_: i32, parent_id_: i32, level_: i32) -> RawCategory {
+        RawCategory {
+            id: CategoryId(id_),
+            name: serde_json::from_str("{}").unwrap(),
+            parent_id: Some(CategoryId(parent_id_)),
+            level: level_,
+            meta_field: None,
+            is_active: true,
+            uuid: Uuid::new_v4(),
+            slug: CategorySlug(id_.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_subtree_levels() {
+        let cats = vec![
+            create_raw_category(1, 0, 1),
+            create_raw_category(2, 1, 2),
+            create_raw_category(3, 2, 3),
+            create_raw_category(4, 0, 1),
+        ];
+
+        let levels = subtree_levels(&cats, CategoryId(2), CategoryId(0)).unwrap();
+        assert_eq!(levels, vec![(CategoryId(2), 1), (CategoryId(3), 2)]);
+        let levels = subtree_levels(&cats, CategoryId(2), CategoryId(4)).unwrap();
+        assert_eq!(levels, vec![(CategoryId(2), 2), (CategoryId(3), 3)]);
+
+        assert!(subtree_levels(&cats, CategoryId(1), CategoryId(3)).is_err());
+        assert!(subtree_levels(&cats, CategoryId(1), CategoryId(4)).is_err());
+        assert!(subtree_levels(&cats, CategoryId(5), CategoryId(0)).is_err());
+    }
+
+    #[test]
+    fn test_check_product_categories_levels() {
+        let cats = vec![
+            create_raw_category(1, 0, 1),
+            create_raw_category(2, 1, 2),
+            create_raw_category(3, 2, 3),
+            create_raw_category(4, 0, 1),
+            create_raw_category(5, 4, 2),
+        ];
+
+        let levels = subtree_levels(&cats, CategoryId(2), CategoryId(4)).unwrap();
+        assert!(check_product_categories_levels(&levels, &[CategoryId(3)]).is_ok());
+        let levels = subtree_levels(&cats, CategoryId(3), CategoryId(5)).unwrap();
+        assert!(check_product_categories_levels(&levels, &[CategoryId(3)]).is_ok());
+
+        let levels = subtree_levels(&cats, CategoryId(2), CategoryId(0)).unwrap();
+        assert!(check_product_categories_levels(&levels, &[]).is_ok());
+        assert!(check_product_categories_levels(&levels, &[CategoryId(3)]).is_err());
+        let levels = subtree_levels(&cats, CategoryId(3), CategoryId(4)).unwrap();
+        assert!(check_product_categories_levels(&levels, &[CategoryId(3)]).is_err());
+    }
+
+    #[test]
+    fn test_set_products_count() {
+        let mut category = create_mock_categories();
+        let counts = vec![(CategoryId(400), 2), (CategoryId(401), 3), (CATEGORY_ID_LEVEL3_FOR_TEST, 5)]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(set_products_count(&mut category, &counts), 10);
+        assert_eq!(category.products_count, Some(10));
+        let cat_2_1 = get_category(&category, CATEGORY_ID_LEVEL2_WITH_2CHILDREN).unwrap();
+        assert_eq!(cat_2_1.products_count, Some(8));
+        let cat_3_1 = get_category(&category, CategoryId(401)).unwrap();
+        assert_eq!(cat_3_1.products_count, Some(3));
+    }
+
     #[test]
     fn test_unused_categories() {
         let mut cat = Category::default();
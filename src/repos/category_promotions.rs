@@ -0,0 +1,134 @@
+//! Repo for category_promotions table
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{CategoryId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{CategoryPromotion, NewCategoryPromotion, UpdateCategoryPromotion};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::category_promotions::dsl::*;
+
+/// CategoryPromotions repository, responsible for handling category_promotions table
+pub struct CategoryPromotionsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<CategoryPromotion>>,
+}
+
+pub trait CategoryPromotionsRepo {
+    /// Returns promotions of the category ordered by position, only live ones when `live_only` is set
+    fn list(&self, category_id: CategoryId, live_only: bool) -> RepoResult<Vec<CategoryPromotion>>;
+
+    /// Find specific promotion
+    fn find(&self, promotion_id: i32) -> RepoResult<Option<CategoryPromotion>>;
+
+    /// Creates new promotion
+    fn create(&self, payload: NewCategoryPromotion) -> RepoResult<CategoryPromotion>;
+
+    /// Updates specific promotion
+    fn update(&self, promotion_id: i32, payload: UpdateCategoryPromotion) -> RepoResult<Option<CategoryPromotion>>;
+
+    /// Deletes specific promotion
+    fn delete(&self, promotion_id: i32) -> RepoResult<Option<CategoryPromotion>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CategoryPromotionsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<CategoryPromotion>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CategoryPromotionsRepo
+    for CategoryPromotionsRepoImpl<'a, T>
+{
+    /// Returns promotions of the category ordered by position, only live ones when `live_only` is set
+    fn list(&self, category_id_arg: CategoryId, live_only: bool) -> RepoResult<Vec<CategoryPromotion>> {
+        debug!("List promotions of category {}, live only: {}.", category_id_arg, live_only);
+        acl::check(&*self.acl, Resource::CategoryPromotions, Action::Read, self, None)?;
+
+        let mut query = category_promotions.filter(category_id.eq(category_id_arg)).into_boxed();
+        if live_only {
+            let now = SystemTime::now();
+            query = query
+                .filter(starts_at.is_null().or(starts_at.le(now)))
+                .filter(ends_at.is_null().or(ends_at.gt(now)));
+        }
+
+        query
+            .order((position, id))
+            .get_results::<CategoryPromotion>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("List promotions of category {} error occurred", category_id_arg)).into())
+    }
+
+    /// Find specific promotion
+    fn find(&self, promotion_id: i32) -> RepoResult<Option<CategoryPromotion>> {
+        debug!("Find category promotion {}.", promotion_id);
+        acl::check(&*self.acl, Resource::CategoryPromotions, Action::Read, self, None)?;
+
+        category_promotions
+            .find(promotion_id)
+            .get_result::<CategoryPromotion>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Find category promotion {} error occurred", promotion_id)).into())
+    }
+
+    /// Creates new promotion
+    fn create(&self, payload: NewCategoryPromotion) -> RepoResult<CategoryPromotion> {
+        debug!("Create category promotion {:?}.", payload);
+        acl::check(&*self.acl, Resource::CategoryPromotions, Action::Create, self, None)?;
+
+        diesel::insert_into(category_promotions)
+            .values(&payload)
+            .get_result::<CategoryPromotion>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Create category promotion {:?} error occurred", payload)).into())
+    }
+
+    /// Updates specific promotion
+    fn update(&self, promotion_id: i32, payload: UpdateCategoryPromotion) -> RepoResult<Option<CategoryPromotion>> {
+        debug!("Update category promotion {} with {:?}.", promotion_id, payload);
+        acl::check(&*self.acl, Resource::CategoryPromotions, Action::Update, self, None)?;
+
+        diesel::update(category_promotions.filter(id.eq(promotion_id)))
+            .set(&payload)
+            .get_result::<CategoryPromotion>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Update category promotion {} error occurred", promotion_id)).into())
+    }
+
+    /// Deletes specific promotion
+    fn delete(&self, promotion_id: i32) -> RepoResult<Option<CategoryPromotion>> {
+        debug!("Delete category promotion {}.", promotion_id);
+        acl::check(&*self.acl, Resource::CategoryPromotions, Action::Delete, self, None)?;
+
+        diesel::delete(category_promotions.filter(id.eq(promotion_id)))
+            .get_result::<CategoryPromotion>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Delete category promotion {} error occurred", promotion_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, CategoryPromotion>
+    for CategoryPromotionsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&CategoryPromotion>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime};
+
 use diesel;
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
@@ -42,9 +44,19 @@ pub trait CouponsRepo {
     /// Get coupon by code
     fn get_by_code(&self, code_arg: CouponCode, store_id_arg: StoreId) -> RepoResult<Option<Coupon>>;
 
+    /// Get coupon by code, locking the row until the end of the transaction
+    fn get_by_code_for_update(&self, code_arg: CouponCode, store_id_arg: StoreId) -> RepoResult<Option<Coupon>>;
+
     /// Search coupons
     fn find_by(&self, search: CouponSearch) -> RepoResult<Vec<Coupon>>;
 
+    /// Search coupons of all stores, newest first
+    fn admin_search(
+        &self,
+        pagination_params: PaginationParams<CouponId>,
+        terms: AdminCouponsSearchTerms,
+    ) -> RepoResult<AdminCouponsSearchResults>;
+
     /// Update coupon
     fn update(&self, id_arg: CouponId, payload: UpdateCoupon) -> RepoResult<Coupon>;
 
@@ -139,6 +151,36 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             })
     }
 
+    /// Get coupon by code, locking the row until the end of the transaction
+    fn get_by_code_for_update(&self, code_arg: CouponCode, store_id_arg: StoreId) -> RepoResult<Option<Coupon>> {
+        debug!(
+            "Find in coupon for update by coupon code: {} and store id: {}.",
+            code_arg, store_id_arg
+        );
+        let query = Coupons::coupons
+            .filter(Coupons::code.eq(&code_arg))
+            .filter(Coupons::store_id.eq(store_id_arg))
+            .for_update();
+        query
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|value: Option<Coupon>| {
+                if let Some(value) = value.as_ref() {
+                    acl::check(&*self.acl, Resource::Coupons, Action::Update, self, Some(value))?;
+                };
+
+                Ok(value)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Find in coupon for update by coupon code: {} and store id: {}.",
+                    code_arg, store_id_arg
+                ))
+                .into()
+            })
+    }
+
     /// Search coupons
     fn find_by(&self, search: CouponSearch) -> RepoResult<Vec<Coupon>> {
         debug!("Get coupons by search: {:?}.", search);
@@ -162,6 +204,65 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .map_err(|e: FailureError| e.context("Search coupons failed.").into())
     }
 
+    /// Search coupons of all stores, newest first
+    fn admin_search(
+        &self,
+        pagination_params: PaginationParams<CouponId>,
+        terms: AdminCouponsSearchTerms,
+    ) -> RepoResult<AdminCouponsSearchResults> {
+        debug!("Search coupons of all stores by {:?} with {:?}.", terms, pagination_params);
+        let PaginationParams {
+            direction,
+            limit,
+            ordering,
+            skip,
+            start,
+        } = pagination_params;
+        let now = SystemTime::now();
+
+        let total_count_query = Coupons::coupons.filter(by_admin_search_terms(&terms, now)).count();
+
+        let mut query = Coupons::coupons.filter(by_admin_search_terms(&terms, now)).into_boxed();
+
+        if let Some(from_id) = start {
+            query = match direction {
+                Direction::Forward => query.filter(Coupons::id.gt(from_id)),
+                Direction::Reverse => query.filter(Coupons::id.lt(from_id)),
+            };
+        }
+
+        if skip > 0 {
+            query = query.offset(skip);
+        }
+
+        if limit > 0 {
+            query = query.limit(limit);
+        }
+
+        query = match ordering {
+            Ordering::Ascending => query.order(Coupons::id.asc()),
+            Ordering::Descending => query.order(Coupons::id.desc()),
+        };
+
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|coupons: Vec<Coupon>| {
+                for coupon in &coupons {
+                    acl::check(&*self.acl, Resource::Coupons, Action::Read, self, Some(coupon))?;
+                }
+
+                total_count_query
+                    .get_result::<i64>(self.db_conn)
+                    .map(move |total_count| AdminCouponsSearchResults {
+                        coupons,
+                        total_count: total_count as u32,
+                    })
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| e.context(format!("Search coupons of all stores by {:?} failed.", terms)).into())
+    }
+
     /// Update coupon
     fn update(&self, id_arg: CouponId, payload: UpdateCoupon) -> RepoResult<Coupon> {
         debug!("Updating coupon with id {} and payload {:?}.", id_arg, payload);
@@ -227,3 +328,29 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
         }
     }
 }
+
+fn by_admin_search_terms(
+    terms: &AdminCouponsSearchTerms,
+    now: SystemTime,
+) -> Box<BoxableExpression<Coupons::coupons, Pg, SqlType = Bool>> {
+    let mut expr: Box<BoxableExpression<Coupons::coupons, Pg, SqlType = Bool>> = Box::new(true.into_sql::<Bool>());
+
+    if let Some(store_id_arg) = terms.store_id {
+        expr = Box::new(expr.and(Coupons::store_id.eq(store_id_arg)));
+    }
+
+    if let Some(is_active_arg) = terms.is_active {
+        expr = Box::new(expr.and(Coupons::is_active.eq(is_active_arg)));
+    }
+
+    if let Some(days) = terms.expiring_within_days {
+        let expiring_till = now + Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+        expr = Box::new(expr.and(Coupons::expired_at.gt(now)).and(Coupons::expired_at.le(expiring_till)));
+    }
+
+    if let Some(scope_arg) = terms.scope.clone() {
+        expr = Box::new(expr.and(Coupons::scope.eq(scope_arg)));
+    }
+
+    expr
+}
@@ -0,0 +1,114 @@
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use errors::Error;
+use failure::Error as FailureError;
+use uuid::Uuid;
+
+use stq_types::UserId;
+
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::coupon_redemptions::dsl as DslCouponRedemptions;
+
+/// CouponRedemptions repository, responsible for handling coupon_redemptions table
+pub struct CouponRedemptionsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<CouponRedemption>>,
+}
+
+pub trait CouponRedemptionsRepo {
+    /// Creates new coupon redemption
+    fn create(&self, payload: NewCouponRedemption) -> RepoResult<CouponRedemption>;
+
+    /// Get coupon redemption by token
+    fn find(&self, token_arg: Uuid) -> RepoResult<Option<CouponRedemption>>;
+
+    /// Delete coupon redemption by token
+    fn delete(&self, token_arg: Uuid) -> RepoResult<CouponRedemption>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CouponRedemptionsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<CouponRedemption>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CouponRedemptionsRepo
+    for CouponRedemptionsRepoImpl<'a, T>
+{
+    /// Creates new coupon redemption
+    fn create(&self, payload: NewCouponRedemption) -> RepoResult<CouponRedemption> {
+        debug!("Create new coupon redemption {:?}.", payload);
+
+        let query = diesel::insert_into(DslCouponRedemptions::coupon_redemptions).values(&payload);
+        query
+            .get_result::<CouponRedemption>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|value| {
+                acl::check(&*self.acl, Resource::CouponRedemptions, Action::Create, self, Some(&value))?;
+
+                Ok(value)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Creates new coupon redemption: {:?} error occurred", payload))
+                    .into()
+            })
+    }
+
+    /// Get coupon redemption by token
+    fn find(&self, token_arg: Uuid) -> RepoResult<Option<CouponRedemption>> {
+        debug!("Find coupon redemption with token {}.", token_arg);
+
+        let query = DslCouponRedemptions::coupon_redemptions.filter(DslCouponRedemptions::token.eq(token_arg));
+        query
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|value: Option<CouponRedemption>| {
+                if let Some(value) = value.as_ref() {
+                    acl::check(&*self.acl, Resource::CouponRedemptions, Action::Read, self, Some(value))?;
+                };
+
+                Ok(value)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find coupon redemption by token: {} error occurred", token_arg))
+                    .into()
+            })
+    }
+
+    /// Delete coupon redemption by token
+    fn delete(&self, token_arg: Uuid) -> RepoResult<CouponRedemption> {
+        debug!("Delete coupon redemption with token {}.", token_arg);
+
+        acl::check(&*self.acl, Resource::CouponRedemptions, Action::Delete, self, None)?;
+
+        let filtered = DslCouponRedemptions::coupon_redemptions.filter(DslCouponRedemptions::token.eq(token_arg));
+        let query = diesel::delete(filtered);
+
+        query
+            .get_result::<CouponRedemption>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Delete coupon redemption by token: {} error occurred", token_arg))
+                    .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, CouponRedemption>
+    for CouponRedemptionsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&CouponRedemption>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|redemption| redemption.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
@@ -32,6 +32,9 @@ pub trait CouponScopeBaseProductsRepo {
 
     /// Delete coupon for scope base products
     fn delete(&self, id_arg: CouponId, base_product_arg: BaseProductId) -> RepoResult<CouponScopeBaseProducts>;
+
+    /// Delete base product from scopes of all coupons
+    fn delete_by_base_product(&self, base_product_arg: BaseProductId) -> RepoResult<Vec<CouponScopeBaseProducts>>;
 }
 
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CouponScopeBaseProductsRepoImpl<'a, T> {
@@ -106,6 +109,27 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                 .into()
             })
     }
+
+    /// Delete base product from scopes of all coupons
+    fn delete_by_base_product(&self, base_product_arg: BaseProductId) -> RepoResult<Vec<CouponScopeBaseProducts>> {
+        debug!("Delete records for base_product_id: {}.", base_product_arg);
+        let filtered = DslCouponScope::coupon_scope_base_products.filter(DslCouponScope::base_product_id.eq(&base_product_arg));
+
+        acl::check(&*self.acl, Resource::CouponScopeBaseProducts, Action::Delete, self, None)?;
+
+        let query = diesel::delete(filtered);
+
+        query
+            .get_results::<CouponScopeBaseProducts>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Delete records coupon scope for base product, base_product_id: {} error occurred",
+                    base_product_arg
+                ))
+                .into()
+            })
+    }
 }
 
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, CouponScopeBaseProducts>
@@ -0,0 +1,223 @@
+//! Impact reports repo, counts rows destructive operations delete, cascades included
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::{Array, Integer};
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{AttributeId, CategoryId, StoreId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{AffectedRows, ElasticIndex, ImpactReport};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+
+/// Rows deleted with the categories, `$1` is the array of category ids
+const CATEGORIES_DELETION_QUERY: &str = "\
+    SELECT 'categories' AS table_name, COUNT(*) AS rows FROM categories WHERE id = ANY($1) \
+    UNION ALL SELECT 'cat_attr_values', COUNT(*) FROM cat_attr_values WHERE cat_id = ANY($1) \
+    UNION ALL SELECT 'category_promotions', COUNT(*) FROM category_promotions WHERE category_id = ANY($1) \
+    UNION ALL SELECT 'moderation_checklist_items', COUNT(*) FROM moderation_checklist_items WHERE category_id = ANY($1) \
+    UNION ALL SELECT 'price_rules', COUNT(*) FROM price_rules WHERE category_id = ANY($1) \
+    UNION ALL SELECT 'store_categories', COUNT(*) FROM store_categories WHERE category_id = ANY($1)";
+
+/// Rows deleted with the attribute, `$1` is the attribute id
+const ATTRIBUTE_DELETION_QUERY: &str = "\
+    SELECT 'attributes' AS table_name, COUNT(*) AS rows FROM attributes WHERE id = $1 \
+    UNION ALL SELECT 'attribute_group_attributes', COUNT(*) FROM attribute_group_attributes WHERE attribute_id = $1";
+
+/// Rows deleted with the store, `$1` is the store id
+const STORE_DELETION_QUERY: &str = "\
+    WITH store_base_products AS (SELECT id FROM base_products WHERE store_id = $1), \
+    store_products AS (SELECT id FROM products WHERE base_product_id IN (SELECT id FROM store_base_products)) \
+    SELECT 'stores' AS table_name, COUNT(*) AS rows FROM stores WHERE id = $1 \
+    UNION ALL SELECT 'base_products', COUNT(*) FROM store_base_products \
+    UNION ALL SELECT 'products', COUNT(*) FROM store_products \
+    UNION ALL SELECT 'prod_attr_values', COUNT(*) FROM prod_attr_values WHERE base_prod_id IN (SELECT id FROM store_base_products) \
+    UNION ALL SELECT 'custom_attributes', COUNT(*) FROM custom_attributes WHERE base_product_id IN (SELECT id FROM store_base_products) \
+    UNION ALL SELECT 'product_photos', COUNT(*) FROM product_photos WHERE product_id IN (SELECT id FROM store_products) \
+    UNION ALL SELECT 'product_views', COUNT(*) FROM product_views WHERE base_product_id IN (SELECT id FROM store_base_products) \
+    UNION ALL SELECT 'stock_reservations', COUNT(*) FROM stock_reservations WHERE product_id IN (SELECT id FROM store_products) \
+    UNION ALL SELECT 'moderator_product_comments', COUNT(*) FROM moderator_product_comments \
+        WHERE base_product_id IN (SELECT id FROM store_base_products) \
+    UNION ALL SELECT 'audit_log', COUNT(*) FROM audit_log WHERE base_product_id IN (SELECT id FROM store_base_products) \
+    UNION ALL SELECT 'moderator_store_comments', COUNT(*) FROM moderator_store_comments WHERE store_id = $1 \
+    UNION ALL SELECT 'coupons', COUNT(*) FROM coupons WHERE store_id = $1 \
+    UNION ALL SELECT 'price_rules', COUNT(*) FROM price_rules WHERE store_id = $1 \
+    UNION ALL SELECT 'store_categories', COUNT(*) FROM store_categories WHERE store_id = $1 \
+    UNION ALL SELECT 'store_faqs', COUNT(*) FROM store_faqs WHERE store_id = $1 \
+    UNION ALL SELECT 'store_product_positions', COUNT(*) FROM store_product_positions WHERE store_id = $1 \
+    UNION ALL SELECT 'store_slug_history', COUNT(*) FROM store_slug_history WHERE store_id = $1 \
+    UNION ALL SELECT 'store_verification_codes', COUNT(*) FROM store_verification_codes WHERE store_id = $1 \
+    UNION ALL SELECT 'wizard_stores', COUNT(*) FROM wizard_stores WHERE store_id = $1";
+
+/// Impact reports repository, only reads the tables
+pub struct ImpactReportsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<ImpactReport>>,
+}
+
+pub trait ImpactReportsRepo {
+    /// Returns what deleting the categories changes
+    fn categories_deletion(&self, category_ids: &[CategoryId]) -> RepoResult<ImpactReport>;
+
+    /// Returns what deleting the attribute changes
+    fn attribute_deletion(&self, attribute_id: AttributeId) -> RepoResult<ImpactReport>;
+
+    /// Returns what deleting the store changes, its document and documents of its base products leave elastic
+    fn store_deletion(&self, store_id: StoreId) -> RepoResult<ImpactReport>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> ImpactReportsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<ImpactReport>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> ImpactReportsRepo
+    for ImpactReportsRepoImpl<'a, T>
+{
+    /// Returns what deleting the categories changes
+    fn categories_deletion(&self, category_ids: &[CategoryId]) -> RepoResult<ImpactReport> {
+        debug!("Count rows deleted with categories {:?}.", category_ids);
+        acl::check(&*self.acl, Resource::ImpactReports, Action::Read, self, None)?;
+
+        let ids = category_ids.iter().map(|category_id| category_id.0).collect::<Vec<_>>();
+        diesel::sql_query(CATEGORIES_DELETION_QUERY)
+            .bind::<Array<Integer>, _>(ids)
+            .load::<AffectedRows>(self.db_conn)
+            .map(ImpactReport::new)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Count rows deleted with categories {:?} error occurred", category_ids)).into())
+    }
+
+    /// Returns what deleting the attribute changes
+    fn attribute_deletion(&self, attribute_id: AttributeId) -> RepoResult<ImpactReport> {
+        debug!("Count rows deleted with attribute {}.", attribute_id);
+        acl::check(&*self.acl, Resource::ImpactReports, Action::Read, self, None)?;
+
+        diesel::sql_query(ATTRIBUTE_DELETION_QUERY)
+            .bind::<Integer, _>(attribute_id.0)
+            .load::<AffectedRows>(self.db_conn)
+            .map(ImpactReport::new)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Count rows deleted with attribute {} error occurred", attribute_id)).into())
+    }
+
+    /// Returns what deleting the store changes, its document and documents of its base products leave elastic
+    fn store_deletion(&self, store_id: StoreId) -> RepoResult<ImpactReport> {
+        debug!("Count rows deleted with store {}.", store_id);
+        acl::check(&*self.acl, Resource::ImpactReports, Action::Read, self, None)?;
+
+        diesel::sql_query(STORE_DELETION_QUERY)
+            .bind::<Integer, _>(store_id.0)
+            .load::<AffectedRows>(self.db_conn)
+            .map(|affected_rows| {
+                let mut report = ImpactReport::new(affected_rows);
+                let stores = report.tables.get("stores").cloned().unwrap_or(0);
+                let base_products = report.tables.get("base_products").cloned().unwrap_or(0);
+                report.add_documents(ElasticIndex::Store, stores);
+                report.add_documents(ElasticIndex::Product, base_products);
+                report
+            })
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Count rows deleted with store {} error occurred", store_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, ImpactReport>
+    for ImpactReportsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&ImpactReport>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use diesel::result::Error as DieselError;
+    use serde_json;
+    use stq_cache::cache::NullCache;
+    use stq_static_resources::AttributeType;
+    use uuid::Uuid;
+
+    use super::*;
+    use models::NewAttribute;
+    use repos::legacy_acl::SystemACL;
+    use repos::repo_factory::tests::*;
+    use repos::{AttributeCacheImpl, AttributesRepo, AttributesRepoImpl};
+
+    #[test]
+    #[ignore]
+    fn test_store_deletion() {
+        let conn = create_db_connection();
+        conn.test_transaction::<_, DieselError, _>(|| {
+            let fixture = create_db_fixture(&conn);
+            let repo = ImpactReportsRepoImpl::new(&conn, Box::new(SystemACL::default()) as Box<RepoAcl<ImpactReport>>);
+
+            let report = repo.store_deletion(fixture.store.id).unwrap();
+
+            assert_eq!(report.tables["stores"], 1);
+            assert_eq!(report.tables["base_products"], 1);
+            assert_eq!(report.tables["products"], 1);
+            assert!(!report.tables.contains_key("coupons"));
+            assert_eq!(report.elastic_documents["stores"], 1);
+            assert_eq!(report.elastic_documents["products"], 1);
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_categories_deletion() {
+        let conn = create_db_connection();
+        conn.test_transaction::<_, DieselError, _>(|| {
+            let fixture = create_db_fixture(&conn);
+            let repo = ImpactReportsRepoImpl::new(&conn, Box::new(SystemACL::default()) as Box<RepoAcl<ImpactReport>>);
+
+            let report = repo.categories_deletion(&[fixture.category.id]).unwrap();
+
+            assert_eq!(report.tables["categories"], 1);
+            assert_eq!(report.tables.len(), 1);
+            assert!(report.elastic_documents.is_empty());
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_attribute_deletion() {
+        let conn = create_db_connection();
+        conn.test_transaction::<_, DieselError, _>(|| {
+            let attributes_repo = AttributesRepoImpl::new(
+                &conn,
+                Box::new(SystemACL::default()) as Box<RepoAcl<_>>,
+                Arc::new(AttributeCacheImpl::new(NullCache::new())),
+            );
+            let attribute = attributes_repo
+                .create(NewAttribute {
+                    name: serde_json::from_str(r##"[{"lang": "en","text": "color"}]"##).unwrap(),
+                    value_type: AttributeType::Str,
+                    meta_field: None,
+                    uuid: Uuid::new_v4(),
+                })
+                .unwrap();
+            let repo = ImpactReportsRepoImpl::new(&conn, Box::new(SystemACL::default()) as Box<RepoAcl<ImpactReport>>);
+
+            let report = repo.attribute_deletion(attribute.id).unwrap();
+
+            assert_eq!(report.tables["attributes"], 1);
+            assert!(!report.tables.contains_key("attribute_group_attributes"));
+            Ok(())
+        });
+    }
+}
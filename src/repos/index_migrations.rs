@@ -0,0 +1,98 @@
+//! Repo for index_migrations table
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::authorization::*;
+use models::{IndexMigration, NewIndexMigration, RawIndexMigration};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::index_migrations::dsl::*;
+
+/// IndexMigrations repository, responsible for replays of products index rebuilds
+pub struct IndexMigrationsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<IndexMigration>>,
+}
+
+pub trait IndexMigrationsRepo {
+    /// Saves replays of the rebuilt index
+    fn create(&self, payload: NewIndexMigration) -> RepoResult<IndexMigration>;
+
+    /// Marks migration as the one whose index took over the alias
+    fn set_switched(&self, migration_id: i32) -> RepoResult<IndexMigration>;
+
+    /// Returns specific migration
+    fn find(&self, migration_id: i32) -> RepoResult<Option<IndexMigration>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> IndexMigrationsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<IndexMigration>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> IndexMigrationsRepo
+    for IndexMigrationsRepoImpl<'a, T>
+{
+    /// Saves replays of the rebuilt index
+    fn create(&self, payload: NewIndexMigration) -> RepoResult<IndexMigration> {
+        debug!("Create index migration {:?}.", payload);
+        acl::check(&*self.acl, Resource::IndexMigrations, Action::Create, self, None)?;
+
+        diesel::insert_into(index_migrations)
+            .values(&payload)
+            .get_result::<RawIndexMigration>(self.db_conn)
+            .map(IndexMigration::from)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Create index migration {:?} error occurred", payload)).into())
+    }
+
+    /// Marks migration as the one whose index took over the alias
+    fn set_switched(&self, migration_id: i32) -> RepoResult<IndexMigration> {
+        debug!("Set index migration {} switched.", migration_id);
+        acl::check(&*self.acl, Resource::IndexMigrations, Action::Update, self, None)?;
+
+        diesel::update(index_migrations.filter(id.eq(migration_id)))
+            .set(switched_at.eq(SystemTime::now()))
+            .get_result::<RawIndexMigration>(self.db_conn)
+            .map(IndexMigration::from)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Set index migration {} switched error occurred", migration_id)).into())
+    }
+
+    /// Returns specific migration
+    fn find(&self, migration_id: i32) -> RepoResult<Option<IndexMigration>> {
+        debug!("Find index migration {}.", migration_id);
+        acl::check(&*self.acl, Resource::IndexMigrations, Action::Read, self, None)?;
+
+        index_migrations
+            .filter(id.eq(migration_id))
+            .get_result::<RawIndexMigration>(self.db_conn)
+            .optional()
+            .map(|migration| migration.map(IndexMigration::from))
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Find index migration {} error occurred", migration_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, IndexMigration>
+    for IndexMigrationsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&IndexMigration>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Repo for jobs table
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::jobs::dsl as DslJobs;
+
+/// Jobs repository, responsible for handling jobs table
+pub struct JobsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Job>>,
+}
+
+pub trait JobsRepo {
+    /// Creates new job
+    fn create(&self, payload: NewJob) -> RepoResult<Job>;
+
+    /// Find specific job
+    fn find(&self, job_id: i32) -> RepoResult<Option<Job>>;
+
+    /// Updates status and progress of the job, canceled jobs are returned unchanged
+    fn update(&self, job_id: i32, payload: UpdateJob) -> RepoResult<Job>;
+
+    /// Fails pending and running jobs not updated since `idle_since`, their work was interrupted by a restart
+    fn fail_interrupted(&self, idle_since: SystemTime) -> RepoResult<Vec<Job>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> JobsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Job>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> JobsRepo for JobsRepoImpl<'a, T> {
+    /// Creates new job
+    fn create(&self, payload: NewJob) -> RepoResult<Job> {
+        debug!("Create new job {:?}.", payload);
+
+        acl::check(&*self.acl, Resource::Jobs, Action::Create, self, None)?;
+
+        let query = diesel::insert_into(DslJobs::jobs).values(&payload);
+        query
+            .get_result::<Job>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Creates new job: {:?} error occurred", payload)).into())
+    }
+
+    /// Find specific job
+    fn find(&self, job_id: i32) -> RepoResult<Option<Job>> {
+        debug!("Find job {}.", job_id);
+
+        DslJobs::jobs
+            .find(job_id)
+            .get_result::<Job>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|job: Option<Job>| {
+                if let Some(ref job) = job {
+                    acl::check(&*self.acl, Resource::Jobs, Action::Read, self, Some(job))?;
+                }
+                Ok(job)
+            })
+            .map_err(|e: FailureError| e.context(format!("Find job {} error occurred", job_id)).into())
+    }
+
+    /// Updates status and progress of the job, canceled jobs are returned unchanged
+    fn update(&self, job_id: i32, payload: UpdateJob) -> RepoResult<Job> {
+        debug!("Update job {} with {:?}.", job_id, payload);
+
+        DslJobs::jobs
+            .find(job_id)
+            .get_result::<Job>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|job: Job| {
+                acl::check(&*self.acl, Resource::Jobs, Action::Update, self, Some(&job))?;
+                Ok(job)
+            })
+            .and_then(|job| {
+                if job.status == JobStatus::Canceled {
+                    return Ok(job);
+                }
+
+                let filter = DslJobs::jobs
+                    .filter(DslJobs::id.eq(job_id))
+                    .filter(DslJobs::status.ne(JobStatus::Canceled));
+                diesel::update(filter)
+                    .set(&payload)
+                    .get_result::<Job>(self.db_conn)
+                    .optional()
+                    .map_err(|e| Error::from(e).into())
+                    .map(|updated| {
+                        // Nothing is updated when the job is canceled in the meantime
+                        updated.unwrap_or(Job {
+                            status: JobStatus::Canceled,
+                            ..job
+                        })
+                    })
+            })
+            .map_err(|e: FailureError| e.context(format!("Update job {} with {:?} error occurred", job_id, payload)).into())
+    }
+
+    /// Fails pending and running jobs not updated since `idle_since`, their work was interrupted by a restart
+    fn fail_interrupted(&self, idle_since: SystemTime) -> RepoResult<Vec<Job>> {
+        debug!("Fail jobs not updated since {:?}.", idle_since);
+
+        acl::check(&*self.acl, Resource::Jobs, Action::Update, self, None)?;
+
+        let filter = DslJobs::jobs
+            .filter(DslJobs::status.eq_any(vec![JobStatus::Pending, JobStatus::Running]))
+            .filter(DslJobs::updated_at.lt(idle_since));
+        diesel::update(filter)
+            .set(&UpdateJob {
+                status: Some(JobStatus::Failed),
+                ..Default::default()
+            })
+            .get_results::<Job>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Fail interrupted jobs error occurred").into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, Job> for JobsRepoImpl<'a, T> {
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&Job>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|job| job.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Repo for listings table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::authorization::*;
+use models::{Listing, NewListing};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::listings::dsl::*;
+
+/// Listings repository, responsible for handling listings table
+pub struct ListingsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Listing>>,
+}
+
+pub trait ListingsRepo {
+    /// Find specific listing by id
+    fn find(&self, listing_id: i32) -> RepoResult<Option<Listing>>;
+
+    /// Find listing with the same category and attributes
+    fn find_by_attributes(&self, payload: &NewListing) -> RepoResult<Option<Listing>>;
+
+    /// Creates new listing
+    fn create(&self, payload: NewListing) -> RepoResult<Listing>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> ListingsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Listing>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> ListingsRepo for ListingsRepoImpl<'a, T> {
+    /// Find specific listing by id
+    fn find(&self, listing_id: i32) -> RepoResult<Option<Listing>> {
+        debug!("Find listing {}.", listing_id);
+        acl::check(&*self.acl, Resource::Listings, Action::Read, self, None)?;
+
+        listings
+            .filter(id.eq(listing_id))
+            .get_result::<Listing>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Find listing {} error occurred", listing_id)).into())
+    }
+
+    /// Find listing with the same category and attributes
+    fn find_by_attributes(&self, payload: &NewListing) -> RepoResult<Option<Listing>> {
+        debug!("Find listing by attributes {:?}.", payload);
+        acl::check(&*self.acl, Resource::Listings, Action::Read, self, None)?;
+
+        listings
+            .filter(category_id.eq(payload.category_id))
+            .filter(attributes.eq(&payload.attributes))
+            .get_result::<Listing>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Find listing by attributes {:?} error occurred", payload)).into())
+    }
+
+    /// Creates new listing
+    fn create(&self, payload: NewListing) -> RepoResult<Listing> {
+        debug!("Create listing {:?}.", payload);
+        acl::check(&*self.acl, Resource::Listings, Action::Create, self, None)?;
+
+        diesel::insert_into(listings)
+            .values(&payload)
+            .get_result::<Listing>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Create listing {:?} error occurred", payload)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, Listing>
+    for ListingsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&Listing>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
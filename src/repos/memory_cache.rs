@@ -0,0 +1,151 @@
+//! MemoryCache keeps values in the process, instances drop changed entries on database notifications
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use stq_cache::cache::Cache;
+
+/// Entries are shared by clones, so the notifications listener can drop them while repos use the cache.
+/// It never fails, `E` only matches the error type of the other cache backends
+pub struct MemoryCache<T, E> {
+    entries: Arc<RwLock<Entries<T>>>,
+    capacity: Option<usize>,
+    error: PhantomData<fn() -> E>,
+}
+
+/// `order` lists keys from the oldest one, it is kept only for caches with capacity
+struct Entries<T> {
+    values: HashMap<String, T>,
+    order: VecDeque<String>,
+}
+
+impl<T, E> MemoryCache<T, E> {
+    pub fn new() -> Self {
+        MemoryCache {
+            entries: Arc::new(RwLock::new(Entries {
+                values: HashMap::new(),
+                order: VecDeque::new(),
+            })),
+            capacity: None,
+            error: PhantomData,
+        }
+    }
+
+    /// Cache of at most `capacity` entries, the oldest entries are dropped first
+    pub fn with_capacity(capacity: usize) -> Self {
+        MemoryCache {
+            capacity: Some(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Values are plain copies of db rows, so entries stay usable after a panic poisoned the lock
+    fn read(&self) -> RwLockReadGuard<Entries<T>> {
+        self.entries.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<Entries<T>> {
+        self.entries.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn remove_entry(&self, key: &str) -> bool {
+        let mut entries = self.write();
+        let removed = entries.values.remove(key).is_some();
+        if removed && self.capacity.is_some() {
+            entries.order.retain(|ordered_key| ordered_key != key);
+        }
+        removed
+    }
+}
+
+impl<T, E> Clone for MemoryCache<T, E> {
+    fn clone(&self) -> Self {
+        MemoryCache {
+            entries: self.entries.clone(),
+            capacity: self.capacity,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone, E> Cache<T> for MemoryCache<T, E> {
+    type Error = E;
+
+    fn get(&self, key: &str) -> Result<Option<T>, Self::Error> {
+        Ok(self.read().values.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: T) -> Result<(), Self::Error> {
+        let mut entries = self.write();
+        let is_new = entries.values.insert(key.to_string(), value).is_none();
+        if let Some(capacity) = self.capacity {
+            if is_new {
+                entries.order.push_back(key.to_string());
+            }
+            while entries.values.len() > capacity {
+                match entries.order.pop_front() {
+                    Some(oldest) => entries.values.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<bool, Self::Error> {
+        Ok(self.remove_entry(key))
+    }
+}
+
+/// Cache the notifications listener drops entries of
+pub trait InvalidateCache: Send + Sync {
+    /// Drops entry at the key
+    fn invalidate(&self, key: &str);
+    /// Drops all entries
+    fn clear(&self);
+}
+
+impl<T: Send + Sync, E> InvalidateCache for MemoryCache<T, E> {
+    fn invalidate(&self, key: &str) {
+        self.remove_entry(key);
+    }
+
+    fn clear(&self) {
+        let mut entries = self.write();
+        entries.values.clear();
+        entries.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_entries() {
+        let cache = MemoryCache::<i32, ()>::new();
+        let listener_cache = cache.clone();
+        cache.set("1", 10).unwrap();
+        cache.set("2", 20).unwrap();
+
+        listener_cache.invalidate("1");
+        assert_eq!(cache.get("1"), Ok(None));
+        assert_eq!(cache.get("2"), Ok(Some(20)));
+
+        listener_cache.clear();
+        assert_eq!(cache.get("2"), Ok(None));
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest_entries() {
+        let cache = MemoryCache::<i32, ()>::with_capacity(2);
+        cache.set("1", 10).unwrap();
+        cache.set("2", 20).unwrap();
+        cache.set("1", 11).unwrap();
+        cache.set("3", 30).unwrap();
+
+        assert_eq!(cache.get("1"), Ok(None));
+        assert_eq!(cache.get("2"), Ok(Some(20)));
+        assert_eq!(cache.get("3"), Ok(Some(30)));
+    }
+}
@@ -1,37 +1,86 @@
 //! Repos is a module responsible for interacting with postgres db
 #[macro_use]
 pub mod acl;
+pub mod attribute_groups;
 pub mod attribute_values;
 pub mod attributes;
+pub mod audit_log;
+pub mod base_product_history;
 pub mod base_products;
+pub mod catalog_health;
 pub mod categories;
+pub mod category_promotions;
 pub mod coupons;
 pub mod currency_exchange;
 pub mod custom_attributes;
+pub mod impact_reports;
+pub mod index_migrations;
+pub mod jobs;
+pub mod listings;
+pub mod memory_cache;
+pub mod moderation_checklists;
 pub mod moderator_product;
 pub mod moderator_store;
+pub mod price_rules;
 pub mod product_attrs;
 pub mod products;
 pub mod repo_factory;
+pub mod retention;
+pub mod search_synonyms;
+pub mod shadow_read;
+pub mod stock_reservations;
+pub mod store_categories;
+pub mod store_faqs;
+pub mod store_feed;
+pub mod store_product_positions;
+pub mod store_profile;
+pub mod store_statistics;
+pub mod store_verification_codes;
 pub mod stores;
+pub mod sync_state;
+pub mod ttl_cache;
 pub mod types;
 pub mod user_roles;
 pub mod wizard_stores;
 
 pub use self::acl::*;
+pub use self::attribute_groups::*;
 pub use self::attribute_values::*;
 pub use self::attributes::*;
+pub use self::audit_log::*;
+pub use self::base_product_history::*;
 pub use self::base_products::*;
+pub use self::catalog_health::*;
 pub use self::categories::*;
+pub use self::category_promotions::*;
 pub use self::coupons::*;
 pub use self::currency_exchange::*;
 pub use self::custom_attributes::*;
+pub use self::impact_reports::*;
+pub use self::index_migrations::*;
+pub use self::jobs::*;
+pub use self::listings::*;
+pub use self::memory_cache::*;
+pub use self::moderation_checklists::*;
 pub use self::moderator_product::*;
 pub use self::moderator_store::*;
+pub use self::price_rules::*;
 pub use self::product_attrs::*;
 pub use self::products::*;
 pub use self::repo_factory::*;
+pub use self::retention::*;
+pub use self::search_synonyms::*;
+pub use self::stock_reservations::*;
+pub use self::store_categories::*;
+pub use self::store_faqs::*;
+pub use self::store_feed::*;
+pub use self::store_product_positions::*;
+pub use self::store_profile::*;
+pub use self::store_statistics::*;
+pub use self::store_verification_codes::*;
 pub use self::stores::*;
+pub use self::sync_state::*;
+pub use self::ttl_cache::*;
 pub use self::types::*;
 pub use self::user_roles::*;
 pub use self::wizard_stores::*;
@@ -0,0 +1,177 @@
+//! Repo for moderation_checklist_items and base_product_checklist_results tables
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{BaseProductId, CategoryId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{
+    BaseProductChecklistResult, ModerationChecklistItem, NewBaseProductChecklistResult, NewModerationChecklistItem,
+    UpdateModerationChecklistItem,
+};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::base_product_checklist_results::dsl as ChecklistResults;
+use schema::moderation_checklist_items::dsl::*;
+
+/// ModerationChecklists repository, responsible for handling checklist items and results of moderation
+pub struct ModerationChecklistsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<ModerationChecklistItem>>,
+}
+
+pub trait ModerationChecklistsRepo {
+    /// Returns items of the categories ordered by id
+    fn list_items(&self, category_ids: Vec<CategoryId>) -> RepoResult<Vec<ModerationChecklistItem>>;
+
+    /// Creates new item
+    fn create_item(&self, payload: NewModerationChecklistItem) -> RepoResult<ModerationChecklistItem>;
+
+    /// Updates specific item
+    fn update_item(&self, item_id: i32, payload: UpdateModerationChecklistItem) -> RepoResult<Option<ModerationChecklistItem>>;
+
+    /// Deletes specific item together with its results
+    fn delete_item(&self, item_id: i32) -> RepoResult<Option<ModerationChecklistItem>>;
+
+    /// Replaces results of the base product with the new ones
+    fn replace_results(
+        &self,
+        base_product_id: BaseProductId,
+        payload: Vec<NewBaseProductChecklistResult>,
+    ) -> RepoResult<Vec<BaseProductChecklistResult>>;
+
+    /// Returns items the base product failed
+    fn find_unmet_items(&self, base_product_id: BaseProductId) -> RepoResult<Vec<ModerationChecklistItem>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> ModerationChecklistsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<ModerationChecklistItem>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> ModerationChecklistsRepo
+    for ModerationChecklistsRepoImpl<'a, T>
+{
+    /// Returns items of the categories ordered by id
+    fn list_items(&self, category_ids: Vec<CategoryId>) -> RepoResult<Vec<ModerationChecklistItem>> {
+        debug!("List moderation checklist items of categories {:?}.", category_ids);
+        acl::check(&*self.acl, Resource::ModerationChecklists, Action::Read, self, None)?;
+
+        moderation_checklist_items
+            .filter(category_id.eq_any(&category_ids))
+            .order(id)
+            .get_results::<ModerationChecklistItem>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("List moderation checklist items of categories {:?} error occurred", category_ids))
+                    .into()
+            })
+    }
+
+    /// Creates new item
+    fn create_item(&self, payload: NewModerationChecklistItem) -> RepoResult<ModerationChecklistItem> {
+        debug!("Create moderation checklist item {:?}.", payload);
+        acl::check(&*self.acl, Resource::ModerationChecklists, Action::Create, self, None)?;
+
+        diesel::insert_into(moderation_checklist_items)
+            .values(&payload)
+            .get_result::<ModerationChecklistItem>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Create moderation checklist item {:?} error occurred", payload)).into())
+    }
+
+    /// Updates specific item
+    fn update_item(&self, item_id: i32, payload: UpdateModerationChecklistItem) -> RepoResult<Option<ModerationChecklistItem>> {
+        debug!("Update moderation checklist item {} with {:?}.", item_id, payload);
+        acl::check(&*self.acl, Resource::ModerationChecklists, Action::Update, self, None)?;
+
+        diesel::update(moderation_checklist_items.filter(id.eq(item_id)))
+            .set(&payload)
+            .get_result::<ModerationChecklistItem>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Update moderation checklist item {} error occurred", item_id)).into())
+    }
+
+    /// Deletes specific item together with its results
+    fn delete_item(&self, item_id: i32) -> RepoResult<Option<ModerationChecklistItem>> {
+        debug!("Delete moderation checklist item {}.", item_id);
+        acl::check(&*self.acl, Resource::ModerationChecklists, Action::Delete, self, None)?;
+
+        diesel::delete(moderation_checklist_items.filter(id.eq(item_id)))
+            .get_result::<ModerationChecklistItem>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Delete moderation checklist item {} error occurred", item_id)).into())
+    }
+
+    /// Replaces results of the base product with the new ones
+    fn replace_results(
+        &self,
+        base_product_id_arg: BaseProductId,
+        payload: Vec<NewBaseProductChecklistResult>,
+    ) -> RepoResult<Vec<BaseProductChecklistResult>> {
+        debug!("Replace moderation checklist results of base product {} with {:?}.", base_product_id_arg, payload);
+        acl::check(&*self.acl, Resource::ModerationChecklists, Action::Moderate, self, None)?;
+
+        self.db_conn
+            .transaction::<Vec<BaseProductChecklistResult>, FailureError, _>(|| {
+                let filter =
+                    ChecklistResults::base_product_checklist_results.filter(ChecklistResults::base_product_id.eq(base_product_id_arg));
+                diesel::delete(filter).execute(self.db_conn).map_err(Error::from)?;
+
+                diesel::insert_into(ChecklistResults::base_product_checklist_results)
+                    .values(&payload)
+                    .get_results::<BaseProductChecklistResult>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Replace moderation checklist results of base product {} error occurred",
+                    base_product_id_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Returns items the base product failed
+    fn find_unmet_items(&self, base_product_id_arg: BaseProductId) -> RepoResult<Vec<ModerationChecklistItem>> {
+        debug!("Find unmet moderation checklist items of base product {}.", base_product_id_arg);
+        acl::check(&*self.acl, Resource::ModerationChecklists, Action::Read, self, None)?;
+
+        moderation_checklist_items
+            .inner_join(ChecklistResults::base_product_checklist_results)
+            .filter(ChecklistResults::base_product_id.eq(base_product_id_arg))
+            .filter(ChecklistResults::passed.eq(false))
+            .select((id, category_id, description, created_at, updated_at))
+            .order(id)
+            .get_results::<ModerationChecklistItem>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Find unmet moderation checklist items of base product {} error occurred",
+                    base_product_id_arg
+                ))
+                .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, ModerationChecklistItem>
+    for ModerationChecklistsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&ModerationChecklistItem>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Moderator product comments repo, presents CRUD operations with db for moderator product comments
 use diesel;
 use diesel::connection::AnsiTransactionManager;
+use diesel::dsl::sql;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::{Bool, VarChar};
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
@@ -11,7 +13,7 @@ use failure::Error as FailureError;
 use stq_types::{BaseProductId, UserId};
 
 use models::authorization::*;
-use models::{ModeratorProductComments, NewModeratorProductComments};
+use models::{ModeratorCommentsSearchTerms, ModeratorProductComments, NewModeratorProductComments};
 use repos::acl;
 use repos::legacy_acl::*;
 use repos::types::{RepoAcl, RepoResult};
@@ -27,6 +29,12 @@ pub trait ModeratorProductRepo {
     /// Find comments by base_product ID
     fn find_by_base_product_id(&self, base_product_id: BaseProductId) -> RepoResult<Option<ModeratorProductComments>>;
 
+    /// Returns comments of the product, newest first
+    fn list_by_base_product_id(&self, base_product_id: BaseProductId, skip: i64, count: i64) -> RepoResult<Vec<ModeratorProductComments>>;
+
+    /// Searches comments of all products, newest first. Only moderators search comments
+    fn search(&self, terms: ModeratorCommentsSearchTerms, count: i64) -> RepoResult<Vec<ModeratorProductComments>>;
+
     /// Creates new comment
     fn create(&self, payload: NewModeratorProductComments) -> RepoResult<ModeratorProductComments>;
 }
@@ -63,6 +71,52 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             })
     }
 
+    /// Returns comments of the product, newest first
+    fn list_by_base_product_id(
+        &self,
+        base_product_id_arg: BaseProductId,
+        skip: i64,
+        count: i64,
+    ) -> RepoResult<Vec<ModeratorProductComments>> {
+        debug!("List moderator comments for base product id {}, skip {} count {}.", base_product_id_arg, skip, count);
+        let query = moderator_product_comments
+            .filter(base_product_id.eq(base_product_id_arg))
+            .order_by(id.desc())
+            .offset(skip)
+            .limit(count);
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|comments: Vec<ModeratorProductComments>| {
+                for comment in &comments {
+                    acl::check(&*self.acl, Resource::ModeratorProductComments, Action::Read, self, Some(comment))?;
+                }
+                Ok(comments)
+            })
+            .map_err(|e: FailureError| e.context(format!("List moderator comments for base product id {}", base_product_id_arg)).into())
+    }
+
+    /// Searches comments of all products, newest first. Only moderators search comments
+    fn search(&self, terms: ModeratorCommentsSearchTerms, count: i64) -> RepoResult<Vec<ModeratorProductComments>> {
+        debug!("Search moderator product comments by {:?}, count {}.", terms, count);
+        acl::check(&*self.acl, Resource::ModeratorProductComments, Action::Moderate, self, None)
+            .and_then(|_| {
+                let mut query = moderator_product_comments.into_boxed();
+                if let Some(author) = terms.author.clone() {
+                    query = query.filter(sql::<Bool>("moderator_name ILIKE concat('%', ").bind::<VarChar, _>(author).sql(", '%')"));
+                }
+                if let Some(text) = terms.text.clone() {
+                    query = query.filter(sql::<Bool>("comments ILIKE concat('%', ").bind::<VarChar, _>(text).sql(", '%')"));
+                }
+                query
+                    .order_by((created_at.desc(), id.desc()))
+                    .limit(count)
+                    .get_results(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| e.context(format!("Search moderator product comments by {:?}", terms)).into())
+    }
+
     /// Creates new comment
     fn create(&self, payload: NewModeratorProductComments) -> RepoResult<ModeratorProductComments> {
         debug!("Create moderator comments for base product {:?}.", payload);
@@ -1,9 +1,11 @@
 //! Moderator product comments repo, presents CRUD operations with db for moderator product comments
 use diesel;
 use diesel::connection::AnsiTransactionManager;
+use diesel::dsl::sql;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::{Bool, VarChar};
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
@@ -11,7 +13,7 @@ use failure::Error as FailureError;
 use stq_types::{StoreId, UserId};
 
 use models::authorization::*;
-use models::{ModeratorStoreComments, NewModeratorStoreComments};
+use models::{ModeratorCommentsSearchTerms, ModeratorStoreComments, NewModeratorStoreComments};
 use repos::acl;
 use repos::legacy_acl::*;
 use repos::types::{RepoAcl, RepoResult};
@@ -27,6 +29,12 @@ pub trait ModeratorStoreRepo {
     /// Find comments by store ID
     fn find_by_store_id(&self, store_id: StoreId) -> RepoResult<Option<ModeratorStoreComments>>;
 
+    /// Returns comments of the store, newest first
+    fn list_by_store_id(&self, store_id: StoreId, skip: i64, count: i64) -> RepoResult<Vec<ModeratorStoreComments>>;
+
+    /// Searches comments of all stores, newest first. Only moderators search comments
+    fn search(&self, terms: ModeratorCommentsSearchTerms, count: i64) -> RepoResult<Vec<ModeratorStoreComments>>;
+
     /// Creates new comment
     fn create(&self, payload: NewModeratorStoreComments) -> RepoResult<ModeratorStoreComments>;
 }
@@ -60,6 +68,47 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .map_err(|e: FailureError| e.context(format!("Find moderator comments for store id {}", store_id_arg)).into())
     }
 
+    /// Returns comments of the store, newest first
+    fn list_by_store_id(&self, store_id_arg: StoreId, skip: i64, count: i64) -> RepoResult<Vec<ModeratorStoreComments>> {
+        debug!("List moderator comments for store id {}, skip {} count {}.", store_id_arg, skip, count);
+        let query = moderator_store_comments
+            .filter(store_id.eq(store_id_arg))
+            .order_by(id.desc())
+            .offset(skip)
+            .limit(count);
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|comments: Vec<ModeratorStoreComments>| {
+                for comment in &comments {
+                    acl::check(&*self.acl, Resource::ModeratorStoreComments, Action::Read, self, Some(comment))?;
+                }
+                Ok(comments)
+            })
+            .map_err(|e: FailureError| e.context(format!("List moderator comments for store id {}", store_id_arg)).into())
+    }
+
+    /// Searches comments of all stores, newest first. Only moderators search comments
+    fn search(&self, terms: ModeratorCommentsSearchTerms, count: i64) -> RepoResult<Vec<ModeratorStoreComments>> {
+        debug!("Search moderator store comments by {:?}, count {}.", terms, count);
+        acl::check(&*self.acl, Resource::ModeratorStoreComments, Action::Moderate, self, None)
+            .and_then(|_| {
+                let mut query = moderator_store_comments.into_boxed();
+                if let Some(author) = terms.author.clone() {
+                    query = query.filter(sql::<Bool>("moderator_name ILIKE concat('%', ").bind::<VarChar, _>(author).sql(", '%')"));
+                }
+                if let Some(text) = terms.text.clone() {
+                    query = query.filter(sql::<Bool>("comments ILIKE concat('%', ").bind::<VarChar, _>(text).sql(", '%')"));
+                }
+                query
+                    .order_by((created_at.desc(), id.desc()))
+                    .limit(count)
+                    .get_results(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| e.context(format!("Search moderator store comments by {:?}", terms)).into())
+    }
+
     /// Creates new comment
     fn create(&self, payload: NewModeratorStoreComments) -> RepoResult<ModeratorStoreComments> {
         debug!("Create moderator comments for store {:?}.", payload);
@@ -0,0 +1,185 @@
+//! Repo for price_rules table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::price_rules::dsl::*;
+use schema::stores::dsl as Stores;
+
+/// PriceRules repository, responsible for handling price_rules table
+pub struct PriceRulesRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<PriceRule>>,
+}
+
+pub trait PriceRulesRepo {
+    /// Returns rules of the store in evaluation order
+    fn list(&self, store_id: StoreId) -> RepoResult<Vec<PriceRule>>;
+
+    /// Returns active rules of the stores in evaluation order
+    fn list_active(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<PriceRule>>;
+
+    /// Find specific rule of the store
+    fn find(&self, store_id: StoreId, price_rule_id: i32) -> RepoResult<Option<PriceRule>>;
+
+    /// Creates new rule
+    fn create(&self, payload: NewPriceRule) -> RepoResult<PriceRule>;
+
+    /// Updates specific rule
+    fn update(&self, price_rule_id: i32, payload: UpdatePriceRule) -> RepoResult<PriceRule>;
+
+    /// Deletes specific rule
+    fn delete(&self, price_rule_id: i32) -> RepoResult<PriceRule>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> PriceRulesRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<PriceRule>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> PriceRulesRepo for PriceRulesRepoImpl<'a, T> {
+    /// Returns rules of the store in evaluation order
+    fn list(&self, store_id_arg: StoreId) -> RepoResult<Vec<PriceRule>> {
+        debug!("List price rules of store {}.", store_id_arg);
+
+        price_rules
+            .filter(store_id.eq(store_id_arg))
+            .order((position, id))
+            .get_results::<PriceRule>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|rules: Vec<PriceRule>| {
+                for rule in &rules {
+                    acl::check(&*self.acl, Resource::PriceRules, Action::Read, self, Some(rule))?;
+                }
+                Ok(rules)
+            })
+            .map_err(|e: FailureError| e.context(format!("List price rules of store {} error occurred", store_id_arg)).into())
+    }
+
+    /// Returns active rules of the stores in evaluation order
+    fn list_active(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<PriceRule>> {
+        debug!("List active price rules of stores {:?}.", store_ids);
+
+        price_rules
+            .filter(store_id.eq_any(store_ids.clone()))
+            .filter(is_active.eq(true))
+            .order((store_id, position, id))
+            .get_results::<PriceRule>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|rules: Vec<PriceRule>| {
+                for rule in &rules {
+                    acl::check(&*self.acl, Resource::PriceRules, Action::Read, self, Some(rule))?;
+                }
+                Ok(rules)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("List active price rules of stores {:?} error occurred", store_ids))
+                    .into()
+            })
+    }
+
+    /// Find specific rule of the store
+    fn find(&self, store_id_arg: StoreId, price_rule_id: i32) -> RepoResult<Option<PriceRule>> {
+        debug!("Find price rule {} of store {}.", price_rule_id, store_id_arg);
+
+        price_rules
+            .filter(id.eq(price_rule_id).and(store_id.eq(store_id_arg)))
+            .get_result::<PriceRule>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|rule: Option<PriceRule>| {
+                if let Some(ref rule) = rule {
+                    acl::check(&*self.acl, Resource::PriceRules, Action::Read, self, Some(rule))?;
+                }
+                Ok(rule)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find price rule {} of store {} error occurred", price_rule_id, store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Creates new rule
+    fn create(&self, payload: NewPriceRule) -> RepoResult<PriceRule> {
+        debug!("Create price rule {:?}.", payload);
+
+        diesel::insert_into(price_rules)
+            .values(&payload)
+            .get_result::<PriceRule>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|rule| acl::check(&*self.acl, Resource::PriceRules, Action::Create, self, Some(&rule)).and_then(|_| Ok(rule)))
+            .map_err(|e: FailureError| e.context(format!("Create price rule {:?} error occurred", payload)).into())
+    }
+
+    /// Updates specific rule
+    fn update(&self, price_rule_id: i32, payload: UpdatePriceRule) -> RepoResult<PriceRule> {
+        debug!("Update price rule {} with payload {:?}.", price_rule_id, payload);
+
+        price_rules
+            .find(price_rule_id)
+            .get_result::<PriceRule>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|rule| acl::check(&*self.acl, Resource::PriceRules, Action::Update, self, Some(&rule)))
+            .and_then(|_| {
+                diesel::update(price_rules.filter(id.eq(price_rule_id)))
+                    .set(&payload)
+                    .get_result::<PriceRule>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Update price rule {} with payload {:?} error occurred", price_rule_id, payload))
+                    .into()
+            })
+    }
+
+    /// Deletes specific rule
+    fn delete(&self, price_rule_id: i32) -> RepoResult<PriceRule> {
+        debug!("Delete price rule {}.", price_rule_id);
+
+        price_rules
+            .find(price_rule_id)
+            .get_result::<PriceRule>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|rule| acl::check(&*self.acl, Resource::PriceRules, Action::Delete, self, Some(&rule)))
+            .and_then(|_| {
+                diesel::delete(price_rules.filter(id.eq(price_rule_id)))
+                    .get_result::<PriceRule>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| e.context(format!("Delete price rule {} error occurred", price_rule_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, PriceRule>
+    for PriceRulesRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id_arg: UserId, scope: &Scope, obj: Option<&PriceRule>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => {
+                if let Some(rule) = obj {
+                    Stores::stores
+                        .find(rule.store_id)
+                        .get_result::<Store>(self.db_conn)
+                        .map(|store| store.user_id == user_id_arg)
+                        .ok()
+                        .unwrap_or(false)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
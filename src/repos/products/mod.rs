@@ -0,0 +1,923 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::dsl::{max, min};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::LoadQuery;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use errors::Error;
+use failure::Error as FailureError;
+use serde_json;
+
+use stq_cache::cache::Cache;
+use stq_static_resources::{Currency, ModerationStatus};
+use stq_types::{BaseProductId, ProductId, ProductPrice, StoreId, UserId};
+
+use models::{
+    BaseProductRaw, LowStockProduct, NewProduct, NewProductPhoto, ProductPhoto, RawProduct, Store, UpdateProduct, UpdateProductPrices,
+};
+use repos::legacy_acl::*;
+use schema::base_products::dsl as BaseProducts;
+use schema::product_photos::dsl as ProductPhotos;
+use schema::products::dsl::*;
+use schema::stores::dsl as Stores;
+
+use models::authorization::*;
+use repos::acl;
+use repos::types::{RepoAcl, RepoResult};
+
+pub mod product_cache;
+
+pub use self::product_cache::*;
+
+/// Products repository, responsible for handling products
+pub struct ProductsRepoImpl<'a, C, T>
+where
+    C: Cache<RawProduct>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<RawProduct>>,
+    pub cache: Arc<ProductCacheImpl<C>>,
+}
+
+#[derive(Debug, Default)]
+pub struct ProductFilters {
+    pub is_active: Option<bool>,
+}
+
+/// Queries of products, they can be served by a read replica
+pub trait ProductsReadRepo {
+    /// Find specific product by ID, active products are cached until they change
+    fn find(&self, product_id: ProductId) -> RepoResult<Option<RawProduct>>;
+
+    /// Find specific product by ID with additional filters
+    fn find_by_filters(&self, product_id: ProductId, filters: ProductFilters) -> RepoResult<Option<RawProduct>>;
+
+    /// Find specific product by IDs
+    fn find_many(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<RawProduct>>;
+
+    /// Returns list of products, limited by `from` and `count` parameters
+    fn list(&self, from: i32, count: i32) -> RepoResult<Vec<RawProduct>>;
+
+    /// Returns list of products with base id in the order of their positions
+    fn find_with_base_id(&self, base_id: BaseProductId) -> RepoResult<Vec<RawProduct>>;
+
+    /// Returns list of products with base ids in the order of their positions
+    fn find_with_base_ids(&self, base_ids: Vec<BaseProductId>) -> RepoResult<Vec<RawProduct>>;
+
+    /// Find active product of the store by vendor code
+    fn find_by_vendor_code(&self, store_id: StoreId, code: &str) -> RepoResult<Option<RawProduct>>;
+
+    /// Find active products of published base products by GTIN, stores may sell the same item
+    fn find_by_gtin(&self, gtin: &str) -> RepoResult<Vec<RawProduct>>;
+
+    /// Returns active products of the store with less units in stock than the threshold, out of stock ones first
+    fn find_low_stock(&self, store_id: StoreId, threshold: i32) -> RepoResult<Vec<LowStockProduct>>;
+
+    /// Returns photos of the product in gallery order
+    fn find_photos(&self, product_id: ProductId) -> RepoResult<Vec<ProductPhoto>>;
+}
+
+/// Changes of products, they always go to the primary database
+pub trait ProductsWriteRepo {
+    /// Creates new product, price range of its base product is updated in the same transaction
+    fn create(&self, payload: NewProduct) -> RepoResult<RawProduct>;
+
+    /// Updates specific product
+    fn update(&self, product_id: ProductId, payload: UpdateProduct) -> RepoResult<RawProduct>;
+
+    /// Deactivates specific product
+    fn deactivate(&self, product_id: ProductId) -> RepoResult<RawProduct>;
+
+    /// Find active product by ID bypassing the cache, locking its row until the end of the transaction
+    fn find_for_update(&self, product_id: ProductId) -> RepoResult<Option<RawProduct>>;
+
+    /// Takes units from stock of the active product, `None` when it has less units in stock
+    fn decrement_quantity(&self, product_id: ProductId, quantity: i32) -> RepoResult<Option<RawProduct>>;
+
+    /// Deactivates specific product
+    fn deactivate_by_base_product(&self, base_product_id: BaseProductId) -> RepoResult<Vec<RawProduct>>;
+
+    /// Activates products deactivated together with the base product by `deactivate_by_base_product`
+    fn activate_by_base_product(&self, base_product_id: BaseProductId) -> RepoResult<Vec<RawProduct>>;
+
+    /// Update currency on all products with base_product_id
+    fn update_currency(&self, currency: Currency, base_product_id: BaseProductId) -> RepoResult<usize>;
+
+    /// Changes prices of all active products with base_product_id in one transaction
+    fn update_prices_by_base_product(&self, base_product_id: BaseProductId, payload: UpdateProductPrices) -> RepoResult<Vec<RawProduct>>;
+
+    /// Sets positions of products with base_product_id in the given order and makes the default product the only default one
+    fn set_order(
+        &self,
+        base_product_id: BaseProductId,
+        product_ids: Vec<ProductId>,
+        default_product_id: Option<ProductId>,
+    ) -> RepoResult<Vec<RawProduct>>;
+
+    /// Replaces photos of the product with urls in the given order
+    fn set_photos(&self, product_id: ProductId, urls: Vec<String>) -> RepoResult<Vec<ProductPhoto>>;
+}
+
+/// Both halves of the products repo, implemented for everything implementing them
+pub trait ProductsRepo: ProductsReadRepo + ProductsWriteRepo {}
+
+impl<R: ProductsReadRepo + ProductsWriteRepo> ProductsRepo for R {}
+
+impl<'a, C, T> ProductsRepoImpl<'a, C, T>
+where
+    C: Cache<RawProduct>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<RawProduct>>, cache: Arc<ProductCacheImpl<C>>) -> Self {
+        Self { db_conn, acl, cache }
+    }
+
+    fn execute_query<Ty: Send + 'static, U: LoadQuery<T, Ty> + Send + 'static>(&self, query: U) -> RepoResult<Ty> {
+        query.get_result::<Ty>(self.db_conn).map_err(|e| Error::from(e).into())
+    }
+
+    /// Sets `min_price` and `max_price` of the base product from its active products,
+    /// base products without active products get no price range
+    fn update_price_range(&self, base_product_id_arg: BaseProductId) -> RepoResult<()> {
+        let (min_price, max_price) = products
+            .filter(base_product_id.eq(base_product_id_arg))
+            .filter(is_active.eq(true))
+            .select((min(price), max(price)))
+            .get_result::<(Option<ProductPrice>, Option<ProductPrice>)>(self.db_conn)
+            .map_err(Error::from)?;
+
+        let filter = BaseProducts::base_products.filter(BaseProducts::id.eq(base_product_id_arg));
+        diesel::update(filter)
+            .set((BaseProducts::min_price.eq(min_price), BaseProducts::max_price.eq(max_price)))
+            .execute(self.db_conn)
+            .map(|_| ())
+            .map_err(|e| Error::from(e).into())
+    }
+
+    /// New products are shown after other products of their base product
+    fn move_to_last_position(&self, product: RawProduct) -> RepoResult<RawProduct> {
+        let last_position = products
+            .filter(base_product_id.eq(product.base_product_id))
+            .filter(id.ne(product.id))
+            .select(max(position))
+            .get_result::<Option<i32>>(self.db_conn)
+            .map_err(Error::from)?;
+
+        match last_position {
+            Some(last_position) => diesel::update(products.filter(id.eq(product.id)))
+                .set(position.eq(last_position + 1))
+                .get_result::<RawProduct>(self.db_conn)
+                .map_err(|e| Error::from(e).into()),
+            None => Ok(product),
+        }
+    }
+
+    /// Sets `additional_photos` of the products from their galleries in `product_photos`
+    fn with_photos(&self, mut products_arg: Vec<RawProduct>) -> RepoResult<Vec<RawProduct>> {
+        if products_arg.is_empty() {
+            return Ok(products_arg);
+        }
+
+        let product_ids = products_arg.iter().map(|product| product.id).collect::<Vec<_>>();
+        let photos = ProductPhotos::product_photos
+            .filter(ProductPhotos::product_id.eq_any(product_ids))
+            .order((ProductPhotos::product_id, ProductPhotos::position))
+            .get_results::<ProductPhoto>(self.db_conn)
+            .map_err(Error::from)?;
+
+        let mut urls_by_product = HashMap::<ProductId, Vec<String>>::new();
+        for photo in photos {
+            urls_by_product.entry(photo.product_id).or_insert_with(Vec::new).push(photo.url);
+        }
+        for product in &mut products_arg {
+            product.additional_photos = urls_by_product.remove(&product.id).map(serde_json::Value::from);
+        }
+        Ok(products_arg)
+    }
+
+    /// Drops discounts outside of their windows, it is done on every read as cached products outlive the windows
+    fn with_active_discounts(products_arg: Vec<RawProduct>) -> Vec<RawProduct> {
+        let now = SystemTime::now();
+        products_arg
+            .into_iter()
+            .map(|product| product.without_inactive_discount(now))
+            .collect()
+    }
+
+    fn with_photo(&self, product: Option<RawProduct>) -> RepoResult<Option<RawProduct>> {
+        match product {
+            Some(product) => self.with_photos(vec![product]).map(|mut products_res| products_res.pop()),
+            None => Ok(None),
+        }
+    }
+
+    /// Replaces gallery of the product, `additional_photos` column is kept equal to it
+    /// for the queries reading products table directly
+    fn replace_photos(&self, product_id_arg: ProductId, urls: Vec<String>) -> RepoResult<Vec<ProductPhoto>> {
+        let mut distinct_urls: Vec<String> = vec![];
+        for url in urls {
+            if !distinct_urls.contains(&url) {
+                distinct_urls.push(url);
+            }
+        }
+
+        diesel::delete(ProductPhotos::product_photos.filter(ProductPhotos::product_id.eq(product_id_arg)))
+            .execute(self.db_conn)
+            .map_err(Error::from)?;
+        diesel::update(products.filter(id.eq(product_id_arg)))
+            .set(additional_photos.eq(serde_json::Value::from(distinct_urls.clone())))
+            .execute(self.db_conn)
+            .map_err(Error::from)?;
+
+        if distinct_urls.is_empty() {
+            return Ok(vec![]);
+        }
+        let new_photos = NewProductPhoto::from_urls(product_id_arg, distinct_urls, 0);
+        diesel::insert_into(ProductPhotos::product_photos)
+            .values(&new_photos)
+            .get_results::<ProductPhoto>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+    }
+
+    /// Keeps gallery of the product equal to `additional_photos` given on create and update
+    fn with_given_photos(&self, product: RawProduct, photos: &Option<serde_json::Value>) -> RepoResult<RawProduct> {
+        if let Some(ref photos) = *photos {
+            let urls = serde_json::from_value::<Vec<String>>(photos.clone()).unwrap_or_default();
+            self.replace_photos(product.id, urls)?;
+        }
+        self.with_photos(vec![product]).map(|mut products_res| products_res.remove(0))
+    }
+}
+
+impl<'a, C, T> ProductsReadRepo for ProductsRepoImpl<'a, C, T>
+where
+    C: Cache<RawProduct>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    /// Find specific product by ID, active products are cached until they change
+    // TODO: use `find_by_filters`
+    fn find(&self, product_id_arg: ProductId) -> RepoResult<Option<RawProduct>> {
+        debug!("Find in product with id {}.", product_id_arg);
+        let product: RepoResult<Option<RawProduct>> = match self.cache.get(product_id_arg) {
+            Some(product) => Ok(Some(product)),
+            None => products
+                .find(product_id_arg)
+                .filter(is_active.eq(true))
+                .get_result(self.db_conn)
+                .optional()
+                .map_err(|e| Error::from(e).into())
+                .and_then(|product: Option<RawProduct>| self.with_photo(product))
+                .map(|product: Option<RawProduct>| {
+                    if let Some(ref product) = product {
+                        self.cache.set(product_id_arg, product.clone());
+                    }
+                    product
+                }),
+        };
+
+        product
+            .and_then(|product: Option<RawProduct>| {
+                if let Some(ref product) = product {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(product))?;
+                };
+                Ok(product.map(|product| product.without_inactive_discount(SystemTime::now())))
+            })
+            .map_err(|e: FailureError| e.context(format!("Find product with id: {} error occurred", product_id_arg)).into())
+    }
+
+    /// Find specific product by ID with additional filters
+    fn find_by_filters(&self, product_id_arg: ProductId, filters_arg: ProductFilters) -> RepoResult<Option<RawProduct>> {
+        debug!("Find in product with id {} by filters {:?}.", product_id_arg, filters_arg);
+        let mut query = products.find(product_id_arg).into_boxed();
+
+        if let Some(filter_is_active) = filters_arg.is_active {
+            query = query.filter(is_active.eq(filter_is_active));
+        }
+
+        query
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|product: Option<RawProduct>| self.with_photo(product))
+            .and_then(|product: Option<RawProduct>| {
+                if let Some(ref product) = product {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(product))?;
+                };
+                Ok(product.map(|product| product.without_inactive_discount(SystemTime::now())))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find product with id: {} by filters error occurred", product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Find specific product by IDs
+    fn find_many(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<RawProduct>> {
+        debug!("Find in products {:?}.", product_ids);
+        let query = products.filter(id.eq_any(product_ids.clone())).filter(is_active.eq(true)).order(id);
+
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|products_res: Vec<RawProduct>| self.with_photos(products_res))
+            .and_then(|products_res: Vec<RawProduct>| {
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(&product))?;
+                }
+                Ok(Self::with_active_discounts(products_res))
+            })
+            .map_err(move |e: FailureError| e.context(format!("Find in products {:?} error occurred.", product_ids)).into())
+    }
+
+    /// Returns list of products, limited by `from` and `count` parameters
+    fn list(&self, from: i32, count: i32) -> RepoResult<Vec<RawProduct>> {
+        debug!("Find in products from {} count {}.", from, count);
+        let query = products
+            .filter(is_active.eq(true))
+            .filter(id.ge(from))
+            .order(id)
+            .limit(count.into());
+
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|products_res: Vec<RawProduct>| self.with_photos(products_res))
+            .and_then(|products_res: Vec<RawProduct>| {
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(&product))?;
+                }
+                Ok(Self::with_active_discounts(products_res))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find in products from {} count {} error occurred.", from, count))
+                    .into()
+            })
+    }
+
+    /// Returns list of products with base id
+    fn find_with_base_id(&self, base_id_arg: BaseProductId) -> RepoResult<Vec<RawProduct>> {
+        debug!("Find in products with base product id {}.", base_id_arg);
+        let query = products
+            .filter(base_product_id.eq(base_id_arg))
+            .filter(is_active.eq(true))
+            .order_by((position, id));
+
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|products_res: Vec<RawProduct>| self.with_photos(products_res))
+            .and_then(|products_res: Vec<RawProduct>| {
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(&product))?;
+                }
+                Ok(Self::with_active_discounts(products_res))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find in products with id {} error occurred.", base_id_arg))
+                    .into()
+            })
+    }
+
+    /// Returns list of products with base ids
+    fn find_with_base_ids(&self, base_ids: Vec<BaseProductId>) -> RepoResult<Vec<RawProduct>> {
+        debug!("Find in products with base ids.");
+
+        let query = products
+            .filter(base_product_id.eq_any(base_ids))
+            .filter(is_active.eq(true))
+            .order_by((base_product_id, position, id));
+
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|products_res: Vec<RawProduct>| self.with_photos(products_res))
+            .and_then(|products_res: Vec<RawProduct>| {
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(&product))?;
+                }
+                Ok(Self::with_active_discounts(products_res))
+            })
+            .map_err(|e: FailureError| e.context(format!("Find in products with ids error occurred.")).into())
+    }
+
+    /// Find active product of the store by vendor code, codes are unique among active products of the store
+    fn find_by_vendor_code(&self, store_id_arg: StoreId, code: &str) -> RepoResult<Option<RawProduct>> {
+        debug!("Find in products with vendor code '{}' of store {}.", code, store_id_arg);
+
+        let query = products
+            .inner_join(BaseProducts::base_products)
+            .filter(BaseProducts::store_id.eq(store_id_arg))
+            .filter(BaseProducts::is_active.eq(true))
+            .filter(is_active.eq(true))
+            .filter(vendor_code.eq(code))
+            .order_by(id);
+
+        query
+            .first::<(RawProduct, BaseProductRaw)>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|result| self.with_photos(result.into_iter().map(|(product, _)| product).collect()))
+            .map(Self::with_active_discounts)
+            .and_then(|mut products_res: Vec<RawProduct>| {
+                let product = products_res.pop();
+                if let Some(ref product) = product {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(product))?;
+                }
+                Ok(product)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find in products with vendor code '{}' of store {} error occurred.", code, store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Find active products of published base products by GTIN, stores may sell the same item
+    fn find_by_gtin(&self, gtin_arg: &str) -> RepoResult<Vec<RawProduct>> {
+        debug!("Find in products with GTIN '{}'.", gtin_arg);
+
+        let query = products
+            .inner_join(BaseProducts::base_products)
+            // same conditions as `published_filter` of base products repo, boxed filter does not fit the join
+            .filter(BaseProducts::is_active.eq(true))
+            .filter(BaseProducts::status.eq(ModerationStatus::Published))
+            .filter(BaseProducts::store_status.eq(ModerationStatus::Published))
+            .filter(BaseProducts::published_at.is_not_null())
+            .filter(is_active.eq(true))
+            .filter(gtin.eq(gtin_arg))
+            .order_by(id);
+
+        query
+            .get_results::<(RawProduct, BaseProductRaw)>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|results| self.with_photos(results.into_iter().map(|(product, _)| product).collect()))
+            .map(Self::with_active_discounts)
+            .and_then(|products_res: Vec<RawProduct>| {
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(product))?;
+                }
+                Ok(products_res)
+            })
+            .map_err(|e: FailureError| e.context(format!("Find in products with GTIN '{}' error occurred.", gtin_arg)).into())
+    }
+
+    /// Returns active products of the store with less units in stock than the threshold, out of stock ones first
+    fn find_low_stock(&self, store_id_arg: StoreId, threshold: i32) -> RepoResult<Vec<LowStockProduct>> {
+        debug!("Find in products of store {} with less than {} units in stock.", store_id_arg, threshold);
+        acl::check(&*self.acl, Resource::Products, Action::Read, self, None)?;
+
+        let query = products
+            .inner_join(BaseProducts::base_products)
+            .filter(BaseProducts::store_id.eq(store_id_arg))
+            .filter(BaseProducts::is_active.eq(true))
+            .filter(is_active.eq(true))
+            .filter(quantity.lt(threshold))
+            .select((id, base_product_id, vendor_code, quantity, BaseProducts::name))
+            .order_by((quantity, id));
+
+        // variants with untracked stock never pass the threshold filter
+        query
+            .get_results::<(ProductId, BaseProductId, String, Option<i32>, serde_json::Value)>(self.db_conn)
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| LowStockProduct {
+                        product_id: row.0,
+                        base_product_id: row.1,
+                        vendor_code: row.2,
+                        quantity: row.3.unwrap_or_default(),
+                        base_product_name: row.4,
+                    })
+                    .collect()
+            })
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Find in products of store {} with less than {} units in stock error occurred.",
+                    store_id_arg, threshold
+                ))
+                .into()
+            })
+    }
+
+    /// Returns photos of the product in gallery order
+    fn find_photos(&self, product_id_arg: ProductId) -> RepoResult<Vec<ProductPhoto>> {
+        debug!("Find photos of product with id {}.", product_id_arg);
+        self.execute_query(products.find(product_id_arg))
+            .and_then(|product: RawProduct| acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(&product)))
+            .and_then(|_| {
+                ProductPhotos::product_photos
+                    .filter(ProductPhotos::product_id.eq(product_id_arg))
+                    .order(ProductPhotos::position)
+                    .get_results::<ProductPhoto>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find photos of product with id {} error occurred.", product_id_arg))
+                    .into()
+            })
+    }
+}
+
+impl<'a, C, T> ProductsWriteRepo for ProductsRepoImpl<'a, C, T>
+where
+    C: Cache<RawProduct>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    /// Creates new product, price range of its base product is updated in the same transaction
+    fn create(&self, payload: NewProduct) -> RepoResult<RawProduct> {
+        debug!("Create products {:?}.", payload);
+        let query_product = diesel::insert_into(products).values(&payload);
+        self.db_conn
+            .transaction(|| {
+                query_product
+                    .get_result::<RawProduct>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+                    .and_then(|prod| acl::check(&*self.acl, Resource::Products, Action::Create, self, Some(&prod)).and_then(|_| Ok(prod)))
+                    .and_then(|prod| self.move_to_last_position(prod))
+                    .and_then(|prod| self.update_price_range(prod.base_product_id).map(|_| prod))
+                    .and_then(|prod| self.with_given_photos(prod, &payload.additional_photos))
+            })
+            .map_err(|e: FailureError| e.context(format!("Create products {:?} error occurred.", payload)).into())
+    }
+
+    /// Updates specific product
+    fn update(&self, product_id_arg: ProductId, payload: UpdateProduct) -> RepoResult<RawProduct> {
+        debug!("Updating product with id {} and payload {:?}.", product_id_arg, payload);
+        self.db_conn
+            .transaction(|| {
+                self.execute_query(products.find(product_id_arg))
+                    .and_then(|product: RawProduct| acl::check(&*self.acl, Resource::Products, Action::Update, self, Some(&product)))
+                    .and_then(|_| {
+                        let filter = products.filter(id.eq(product_id_arg)).filter(is_active.eq(true));
+
+                        let query = diesel::update(filter).set(&payload);
+                        query.get_result::<RawProduct>(self.db_conn).map_err(|e| Error::from(e).into())
+                    })
+                    .and_then(|product| self.update_price_range(product.base_product_id).map(|_| product))
+                    .and_then(|product| self.with_given_photos(product, &payload.additional_photos))
+            })
+            .map(|product| {
+                self.cache.remove(product_id_arg);
+                product
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Updating product with id {} and payload {:?} error occurred.",
+                    product_id_arg, payload
+                ))
+                .into()
+            })
+    }
+
+    /// Deactivates specific product
+    fn deactivate(&self, product_id_arg: ProductId) -> RepoResult<RawProduct> {
+        debug!("Deactivate product with id {}.", product_id_arg);
+        self.db_conn
+            .transaction(|| {
+                self.execute_query(products.find(product_id_arg))
+                    .and_then(|product: RawProduct| acl::check(&*self.acl, Resource::Products, Action::Delete, self, Some(&product)))
+                    .and_then(|_| {
+                        let filter = products.filter(id.eq(product_id_arg)).filter(is_active.eq(true));
+                        let query = diesel::update(filter).set(is_active.eq(false));
+                        self.execute_query(query)
+                    })
+                    .and_then(|product: RawProduct| self.update_price_range(product.base_product_id).map(|_| product))
+            })
+            .map(|product: RawProduct| {
+                self.cache.remove(product_id_arg);
+                product
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate product with id {} error occurred.", product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Find active product by ID bypassing the cache, locking its row until the end of the transaction
+    fn find_for_update(&self, product_id_arg: ProductId) -> RepoResult<Option<RawProduct>> {
+        debug!("Find in product for update with id {}.", product_id_arg);
+        products
+            .find(product_id_arg)
+            .filter(is_active.eq(true))
+            .for_update()
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|product: Option<RawProduct>| {
+                if let Some(ref product) = product {
+                    acl::check(&*self.acl, Resource::Products, Action::Update, self, Some(product))?;
+                };
+                Ok(product)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find in product for update with id {} error occurred.", product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Takes units from stock of the active product, `None` when it has less units in stock
+    fn decrement_quantity(&self, product_id_arg: ProductId, quantity_arg: i32) -> RepoResult<Option<RawProduct>> {
+        debug!("Decrement quantity of product with id {} by {}.", product_id_arg, quantity_arg);
+        self.execute_query(products.find(product_id_arg))
+            .and_then(|product: RawProduct| acl::check(&*self.acl, Resource::Products, Action::Update, self, Some(&product)))
+            .and_then(|_| {
+                // stock is checked by the update itself, so concurrent orders can not take the same units,
+                // untracked stock stays NULL after the update
+                let filter = products
+                    .filter(id.eq(product_id_arg))
+                    .filter(is_active.eq(true))
+                    .filter(quantity.is_null().or(quantity.ge(quantity_arg)));
+                diesel::update(filter)
+                    .set(quantity.eq(quantity - quantity_arg))
+                    .get_result::<RawProduct>(self.db_conn)
+                    .optional()
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map(|product: Option<RawProduct>| {
+                self.cache.remove(product_id_arg);
+                product
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Decrement quantity of product with id {} error occurred.", product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Deactivates specific product
+    fn deactivate_by_base_product(&self, base_product_id_arg: BaseProductId) -> RepoResult<Vec<RawProduct>> {
+        debug!("Deactivate products by base product id {}.", base_product_id_arg);
+
+        let query = products.filter(base_product_id.eq(base_product_id_arg));
+
+        self.db_conn
+            .transaction(|| {
+                query
+                    .get_results(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+                    .and_then(|results: Vec<RawProduct>| {
+                        for product in &results {
+                            acl::check(&*self.acl, Resource::Products, Action::Delete, self, Some(product))?;
+                        }
+
+                        Ok(results)
+                    })
+                    .and_then(|_| {
+                        let filtered = products.filter(base_product_id.eq(base_product_id_arg)).filter(is_active.eq(true));
+                        let query_update = diesel::update(filtered).set((is_active.eq(false), deactivated_with_base_product.eq(true)));
+                        query_update.get_results(self.db_conn).map_err(|e| Error::from(e).into())
+                    })
+                    .and_then(|results: Vec<RawProduct>| self.update_price_range(base_product_id_arg).map(|_| results))
+            })
+            .map(|results: Vec<RawProduct>| {
+                for product in &results {
+                    self.cache.remove(product.id);
+                }
+                results
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate products by base_product_id {} failed", base_product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Activates products deactivated together with the base product by `deactivate_by_base_product`
+    fn activate_by_base_product(&self, base_product_id_arg: BaseProductId) -> RepoResult<Vec<RawProduct>> {
+        debug!("Activate products by base product id {}.", base_product_id_arg);
+
+        let query = products
+            .filter(base_product_id.eq(base_product_id_arg))
+            .filter(is_active.eq(false))
+            .filter(deactivated_with_base_product.eq(true));
+
+        self.db_conn
+            .transaction(|| {
+                query
+                    .get_results(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+                    .and_then(|results: Vec<RawProduct>| {
+                        for product in &results {
+                            acl::check(&*self.acl, Resource::Products, Action::Delete, self, Some(product))?;
+                        }
+
+                        Ok(results)
+                    })
+                    .and_then(|_| {
+                        let filtered = products
+                            .filter(base_product_id.eq(base_product_id_arg))
+                            .filter(is_active.eq(false))
+                            .filter(deactivated_with_base_product.eq(true));
+                        let query_update = diesel::update(filtered).set((
+                            is_active.eq(true),
+                            deactivated_with_base_product.eq(false),
+                            kafka_update_no.eq(kafka_update_no + 1),
+                        ));
+                        query_update.get_results(self.db_conn).map_err(|e| Error::from(e).into())
+                    })
+                    .and_then(|results: Vec<RawProduct>| self.update_price_range(base_product_id_arg).map(|_| results))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Activate products by base_product_id {} failed", base_product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Update currency on all product with base_product_id
+    fn update_currency(&self, currency_arg: Currency, base_product_id_arg: BaseProductId) -> RepoResult<usize> {
+        debug!(
+            "Setting currency {} on all product with base_product_id {}.",
+            currency_arg, base_product_id_arg
+        );
+
+        let query = products.filter(base_product_id.eq(base_product_id_arg)).filter(is_active.eq(true));
+
+        query
+            .get_results(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|products_res: Vec<RawProduct>| {
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Read, self, Some(&product))?;
+                }
+                Ok(products_res)
+            })
+            .and_then(|products_res| {
+                let updated = diesel::update(products)
+                    .filter(base_product_id.eq(base_product_id_arg))
+                    .filter(is_active.eq(true))
+                    .set(currency.eq(currency_arg))
+                    .execute(self.db_conn)
+                    .map_err(Error::from)?;
+                for product in &products_res {
+                    self.cache.remove(product.id);
+                }
+                Ok(updated)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Setting currency {} on all product with base_product_id {} error occurred.",
+                    currency_arg, base_product_id_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Changes prices of all active products with base_product_id in one transaction
+    fn update_prices_by_base_product(
+        &self,
+        base_product_id_arg: BaseProductId,
+        payload: UpdateProductPrices,
+    ) -> RepoResult<Vec<RawProduct>> {
+        debug!(
+            "Update prices of products with base_product_id {} with payload {:?}.",
+            base_product_id_arg, payload
+        );
+
+        let query = products.filter(base_product_id.eq(base_product_id_arg)).filter(is_active.eq(true));
+
+        self.db_conn
+            .transaction(|| {
+                let products_res = query.get_results::<RawProduct>(self.db_conn).map_err(Error::from)?;
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Update, self, Some(product))?;
+                }
+
+                let mut updated = vec![];
+                for product in products_res {
+                    // prices are rounded by currency of every variant, so they are updated one by one
+                    let new_price = payload.apply(product.price, product.currency);
+                    let product = diesel::update(products.filter(id.eq(product.id)))
+                        .set(price.eq(new_price))
+                        .get_result::<RawProduct>(self.db_conn)
+                        .map_err(Error::from)?;
+                    updated.push(product);
+                }
+
+                self.update_price_range(base_product_id_arg)?;
+                self.with_photos(updated)
+            })
+            .map(|results: Vec<RawProduct>| {
+                for product in &results {
+                    self.cache.remove(product.id);
+                }
+                results
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Update prices of products with base_product_id {} error occurred.",
+                    base_product_id_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Sets positions of products with base_product_id in the given order and makes the default product the only default one
+    fn set_order(
+        &self,
+        base_product_id_arg: BaseProductId,
+        product_ids: Vec<ProductId>,
+        default_product_id: Option<ProductId>,
+    ) -> RepoResult<Vec<RawProduct>> {
+        debug!(
+            "Set order {:?} with default {:?} of products with base_product_id {}.",
+            product_ids, default_product_id, base_product_id_arg
+        );
+
+        self.db_conn
+            .transaction(|| {
+                let products_res = products
+                    .filter(base_product_id.eq(base_product_id_arg))
+                    .get_results::<RawProduct>(self.db_conn)
+                    .map_err(Error::from)?;
+                for product in &products_res {
+                    acl::check(&*self.acl, Resource::Products, Action::Update, self, Some(product))?;
+                }
+
+                for (new_position, product_id_arg) in product_ids.into_iter().enumerate() {
+                    diesel::update(products.filter(id.eq(product_id_arg)).filter(base_product_id.eq(base_product_id_arg)))
+                        .set(position.eq(new_position as i32))
+                        .execute(self.db_conn)
+                        .map_err(Error::from)?;
+                }
+
+                if let Some(default_product_id) = default_product_id {
+                    // the previous default is cleared first, the unique index allows one default per base product
+                    diesel::update(products.filter(base_product_id.eq(base_product_id_arg)).filter(id.ne(default_product_id)))
+                        .set(is_default.eq(false))
+                        .execute(self.db_conn)
+                        .map_err(Error::from)?;
+                    diesel::update(products.filter(id.eq(default_product_id)).filter(base_product_id.eq(base_product_id_arg)))
+                        .set(is_default.eq(true))
+                        .execute(self.db_conn)
+                        .map_err(Error::from)?;
+                }
+
+                for product in &products_res {
+                    self.cache.remove(product.id);
+                }
+
+                let ordered = products
+                    .filter(base_product_id.eq(base_product_id_arg))
+                    .filter(is_active.eq(true))
+                    .order_by((position, id))
+                    .get_results::<RawProduct>(self.db_conn)
+                    .map_err(Error::from)?;
+                self.with_photos(ordered)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Set order of products with base_product_id {} error occurred.",
+                    base_product_id_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Replaces photos of the product with urls in the given order
+    fn set_photos(&self, product_id_arg: ProductId, urls: Vec<String>) -> RepoResult<Vec<ProductPhoto>> {
+        debug!("Set photos of product with id {} to {:?}.", product_id_arg, urls);
+        self.db_conn
+            .transaction(|| {
+                self.execute_query(products.find(product_id_arg))
+                    .and_then(|product: RawProduct| acl::check(&*self.acl, Resource::Products, Action::Update, self, Some(&product)))
+                    .and_then(|_| self.replace_photos(product_id_arg, urls.clone()))
+            })
+            .map(|photos| {
+                self.cache.remove(product_id_arg);
+                photos
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set photos of product with id {} error occurred.", product_id_arg))
+                    .into()
+            })
+    }
+}
+
+impl<'a, C, T> CheckScope<Scope, RawProduct> for ProductsRepoImpl<'a, C, T>
+where
+    C: Cache<RawProduct>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&RawProduct>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => {
+                if let Some(product) = obj {
+                    BaseProducts::base_products
+                        .filter(BaseProducts::id.eq(product.base_product_id))
+                        .inner_join(Stores::stores)
+                        .get_result::<(BaseProductRaw, Store)>(self.db_conn)
+                        .map(|(_, s)| s.user_id == user_id)
+                        .ok()
+                        .unwrap_or(false)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! ProductCache caches active products by id for the hot product pages.
+//! Rows are the same for every language and currency, customer prices are computed from them per request
+use failure::Fail;
+use stq_cache::cache::Cache;
+use stq_types::ProductId;
+
+use models::RawProduct;
+
+pub struct ProductCacheImpl<C>
+where
+    C: Cache<RawProduct>,
+{
+    cache: C,
+}
+
+impl<C> ProductCacheImpl<C>
+where
+    C: Cache<RawProduct>,
+{
+    pub fn new(cache: C) -> Self {
+        ProductCacheImpl { cache }
+    }
+
+    pub fn get(&self, id: ProductId) -> Option<RawProduct> {
+        debug!("Getting a product from ProductCache at key '{}'", id);
+
+        self.cache.get(id.to_string().as_str()).unwrap_or_else(|err| {
+            let err = err.context(format!("Failed to get a product from ProductCache at key '{}'", id));
+            error!("{}", err);
+            None
+        })
+    }
+
+    pub fn remove(&self, id: ProductId) -> bool {
+        debug!("Removing a product from ProductCache at key '{}'", id);
+
+        self.cache.remove(id.to_string().as_str()).unwrap_or_else(|err| {
+            let err = err.context(format!("Failed to remove a product from ProductCache at key '{}'", id));
+            error!("{}", err);
+            false
+        })
+    }
+
+    pub fn set(&self, id: ProductId, product: RawProduct) {
+        debug!("Setting a product in ProductCache at key '{}'", id);
+
+        self.cache.set(id.to_string().as_str(), product).unwrap_or_else(|err| {
+            let err = err.context(format!("Failed to set a product in ProductCache at key '{}'", id));
+            error!("{}", err);
+        })
+    }
+}
@@ -12,6 +12,7 @@ use repos::*;
 pub trait ReposFactory<C: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static>: Clone + Send + 'static {
     fn create_attributes_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AttributesRepo + 'a>;
     fn create_attribute_values_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AttributeValuesRepo + 'a>;
+    fn create_attribute_groups_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AttributeGroupsRepo + 'a>;
     fn create_categories_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CategoriesRepo + 'a>;
     fn create_category_attrs_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CategoryAttrsRepo + 'a>;
     fn create_base_product_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductsRepo + 'a>;
@@ -27,46 +28,108 @@ pub trait ReposFactory<C: Connection<Backend = Pg, TransactionManager = AnsiTran
     fn create_user_roles_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<UserRolesRepo + 'a>;
     fn create_coupon_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CouponsRepo + 'a>;
     fn create_coupon_scope_base_products_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CouponScopeBaseProductsRepo + 'a>;
+    fn create_coupon_scope_categories_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CouponScopeCategoriesRepo + 'a>;
     fn create_used_coupons_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<UsedCouponsRepo + 'a>;
+    fn create_coupon_redemptions_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CouponRedemptionsRepo + 'a>;
+    fn create_audit_log_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AuditLogRepo + 'a>;
+    fn create_catalog_health_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CatalogHealthRepo + 'a>;
+    fn create_store_verification_codes_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreVerificationCodesRepo + 'a>;
+    fn create_store_profile_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreProfileRepo + 'a>;
+    fn create_store_feed_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreFeedRepo + 'a>;
+    fn create_sync_state_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<SyncStateRepo + 'a>;
+    fn create_store_faqs_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreFaqsRepo + 'a>;
+    fn create_store_categories_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreCategoriesRepo + 'a>;
+    fn create_store_product_positions_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreProductPositionsRepo + 'a>;
+    fn create_search_synonyms_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<SearchSynonymsRepo + 'a>;
+    fn create_index_migrations_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<IndexMigrationsRepo + 'a>;
+    fn create_listings_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ListingsRepo + 'a>;
+    fn create_jobs_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<JobsRepo + 'a>;
+    fn create_price_rules_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<PriceRulesRepo + 'a>;
+    fn create_price_rules_repo_with_sys_acl<'a>(&self, db_conn: &'a C) -> Box<PriceRulesRepo + 'a>;
+    fn create_moderation_checklists_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ModerationChecklistsRepo + 'a>;
+    fn create_stock_reservations_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StockReservationsRepo + 'a>;
+    fn create_category_promotions_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CategoryPromotionsRepo + 'a>;
+    fn create_store_statistics_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreStatisticsRepo + 'a>;
+    fn create_impact_reports_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ImpactReportsRepo + 'a>;
+    fn create_base_product_history_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductHistoryRepo + 'a>;
+    // Read halves are created on connections of `Service::spawn_on_replica`, write halves need the primary one
+    fn create_base_product_read_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductsReadRepo + 'a>;
+    fn create_base_product_write_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductsWriteRepo + 'a>;
+    fn create_product_read_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ProductsReadRepo + 'a>;
+    fn create_product_write_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ProductsWriteRepo + 'a>;
+    fn create_stores_read_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoresReadRepo + 'a>;
+    fn create_stores_write_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoresWriteRepo + 'a>;
 }
 
-pub struct ReposFactoryImpl<C1, C2, C3>
+pub struct ReposFactoryImpl<C1, C2, C3, C4, C5, C6, C7>
 where
     C1: Cache<Vec<StoresRole>>,
     C2: CacheSingle<Category>,
     C3: Cache<Attribute>,
+    C4: Cache<CatalogHealthReport>,
+    C5: Cache<StoreProfile>,
+    C6: Cache<StoreFeed>,
+    C7: Cache<RawProduct>,
 {
     roles_cache: Arc<RolesCacheImpl<C1>>,
     category_cache: Arc<CategoryCacheImpl<C2>>,
     attribute_cache: Arc<AttributeCacheImpl<C3>>,
+    catalog_health_cache: Arc<CatalogHealthCacheImpl<C4>>,
+    store_profile_cache: Arc<StoreProfileCacheImpl<C5>>,
+    store_feed_cache: Arc<StoreFeedCacheImpl<C6>>,
+    product_cache: Arc<ProductCacheImpl<C7>>,
 }
 
-impl<C1, C2, C3> Clone for ReposFactoryImpl<C1, C2, C3>
+impl<C1, C2, C3, C4, C5, C6, C7> Clone for ReposFactoryImpl<C1, C2, C3, C4, C5, C6, C7>
 where
     C1: Cache<Vec<StoresRole>>,
     C2: CacheSingle<Category>,
     C3: Cache<Attribute>,
+    C4: Cache<CatalogHealthReport>,
+    C5: Cache<StoreProfile>,
+    C6: Cache<StoreFeed>,
+    C7: Cache<RawProduct>,
 {
     fn clone(&self) -> Self {
         Self {
             roles_cache: self.roles_cache.clone(),
             category_cache: self.category_cache.clone(),
             attribute_cache: self.attribute_cache.clone(),
+            catalog_health_cache: self.catalog_health_cache.clone(),
+            store_profile_cache: self.store_profile_cache.clone(),
+            store_feed_cache: self.store_feed_cache.clone(),
+            product_cache: self.product_cache.clone(),
         }
     }
 }
 
-impl<C1, C2, C3> ReposFactoryImpl<C1, C2, C3>
+impl<C1, C2, C3, C4, C5, C6, C7> ReposFactoryImpl<C1, C2, C3, C4, C5, C6, C7>
 where
     C1: Cache<Vec<StoresRole>> + Send + Sync + 'static,
     C2: CacheSingle<Category> + Send + Sync + 'static,
     C3: Cache<Attribute> + Send + Sync + 'static,
+    C4: Cache<CatalogHealthReport> + Send + Sync + 'static,
+    C5: Cache<StoreProfile> + Send + Sync + 'static,
+    C6: Cache<StoreFeed> + Send + Sync + 'static,
+    C7: Cache<RawProduct> + Send + Sync + 'static,
 {
-    pub fn new(roles_cache: RolesCacheImpl<C1>, category_cache: CategoryCacheImpl<C2>, attribute_cache: AttributeCacheImpl<C3>) -> Self {
+    pub fn new(
+        roles_cache: RolesCacheImpl<C1>,
+        category_cache: CategoryCacheImpl<C2>,
+        attribute_cache: AttributeCacheImpl<C3>,
+        catalog_health_cache: CatalogHealthCacheImpl<C4>,
+        store_profile_cache: StoreProfileCacheImpl<C5>,
+        store_feed_cache: StoreFeedCacheImpl<C6>,
+        product_cache: ProductCacheImpl<C7>,
+    ) -> Self {
         Self {
             roles_cache: Arc::new(roles_cache),
             category_cache: Arc::new(category_cache),
             attribute_cache: Arc::new(attribute_cache),
+            catalog_health_cache: Arc::new(catalog_health_cache),
+            store_profile_cache: Arc::new(store_profile_cache),
+            store_feed_cache: Arc::new(store_feed_cache),
+            product_cache: Arc::new(product_cache),
         }
     }
 
@@ -93,12 +156,16 @@ where
     }
 }
 
-impl<C, C1, C2, C3> ReposFactory<C> for ReposFactoryImpl<C1, C2, C3>
+impl<C, C1, C2, C3, C4, C5, C6, C7> ReposFactory<C> for ReposFactoryImpl<C1, C2, C3, C4, C5, C6, C7>
 where
     C: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
     C1: Cache<Vec<StoresRole>> + Send + Sync + 'static,
     C2: CacheSingle<Category> + Send + Sync + 'static,
     C3: Cache<Attribute> + Send + Sync + 'static,
+    C4: Cache<CatalogHealthReport> + Send + Sync + 'static,
+    C5: Cache<StoreProfile> + Send + Sync + 'static,
+    C6: Cache<StoreFeed> + Send + Sync + 'static,
+    C7: Cache<RawProduct> + Send + Sync + 'static,
 {
     fn create_attributes_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AttributesRepo + 'a> {
         let acl = self.get_acl(db_conn, user_id);
@@ -108,6 +175,10 @@ where
         let acl = self.get_acl(db_conn, user_id);
         Box::new(AttributeValuesRepoImpl::new(db_conn, acl)) as Box<AttributeValuesRepo>
     }
+    fn create_attribute_groups_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AttributeGroupsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(AttributeGroupsRepoImpl::new(db_conn, acl)) as Box<AttributeGroupsRepo>
+    }
     fn create_categories_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CategoriesRepo + 'a> {
         let acl = self.get_acl(db_conn, user_id);
         Box::new(CategoriesRepoImpl::new(db_conn, acl, self.category_cache.clone())) as Box<CategoriesRepo>
@@ -122,7 +193,7 @@ where
     }
     fn create_product_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ProductsRepo + 'a> {
         let acl = self.get_acl(db_conn, user_id);
-        Box::new(ProductsRepoImpl::new(db_conn, acl)) as Box<ProductsRepo>
+        Box::new(ProductsRepoImpl::new(db_conn, acl, self.product_cache.clone())) as Box<ProductsRepo>
     }
     fn create_product_attrs_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ProductAttrsRepo + 'a> {
         let acl = self.get_acl(db_conn, user_id);
@@ -174,27 +245,150 @@ where
         Box::new(CouponScopeBaseProductsRepoImpl::new(db_conn, acl)) as Box<CouponScopeBaseProductsRepo>
     }
 
+    fn create_coupon_scope_categories_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CouponScopeCategoriesRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(CouponScopeCategoriesRepoImpl::new(db_conn, acl)) as Box<CouponScopeCategoriesRepo>
+    }
+
     fn create_used_coupons_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<UsedCouponsRepo + 'a> {
         let acl = self.get_acl(db_conn, user_id);
         Box::new(UsedCouponsRepoImpl::new(db_conn, acl)) as Box<UsedCouponsRepo>
     }
+
+    fn create_coupon_redemptions_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CouponRedemptionsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(CouponRedemptionsRepoImpl::new(db_conn, acl)) as Box<CouponRedemptionsRepo>
+    }
+
+    fn create_audit_log_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<AuditLogRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(AuditLogRepoImpl::new(db_conn, acl)) as Box<AuditLogRepo>
+    }
+    fn create_catalog_health_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CatalogHealthRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(CatalogHealthRepoImpl::new(db_conn, acl, self.catalog_health_cache.clone())) as Box<CatalogHealthRepo>
+    }
+    fn create_store_verification_codes_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreVerificationCodesRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreVerificationCodesRepoImpl::new(db_conn, acl)) as Box<StoreVerificationCodesRepo>
+    }
+    fn create_store_profile_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreProfileRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreProfileRepoImpl::new(db_conn, acl, self.store_profile_cache.clone())) as Box<StoreProfileRepo>
+    }
+    fn create_store_feed_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreFeedRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreFeedRepoImpl::new(db_conn, acl, self.store_feed_cache.clone())) as Box<StoreFeedRepo>
+    }
+    fn create_sync_state_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<SyncStateRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(SyncStateRepoImpl::new(db_conn, acl)) as Box<SyncStateRepo>
+    }
+    fn create_store_faqs_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreFaqsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreFaqsRepoImpl::new(db_conn, acl)) as Box<StoreFaqsRepo>
+    }
+    fn create_store_categories_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreCategoriesRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreCategoriesRepoImpl::new(db_conn, acl)) as Box<StoreCategoriesRepo>
+    }
+    fn create_store_product_positions_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreProductPositionsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreProductPositionsRepoImpl::new(db_conn, acl)) as Box<StoreProductPositionsRepo>
+    }
+    fn create_search_synonyms_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<SearchSynonymsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(SearchSynonymsRepoImpl::new(db_conn, acl)) as Box<SearchSynonymsRepo>
+    }
+    fn create_index_migrations_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<IndexMigrationsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(IndexMigrationsRepoImpl::new(db_conn, acl)) as Box<IndexMigrationsRepo>
+    }
+    fn create_listings_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ListingsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(ListingsRepoImpl::new(db_conn, acl)) as Box<ListingsRepo>
+    }
+    fn create_jobs_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<JobsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(JobsRepoImpl::new(db_conn, acl)) as Box<JobsRepo>
+    }
+    fn create_price_rules_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<PriceRulesRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(PriceRulesRepoImpl::new(db_conn, acl)) as Box<PriceRulesRepo>
+    }
+    fn create_price_rules_repo_with_sys_acl<'a>(&self, db_conn: &'a C) -> Box<PriceRulesRepo + 'a> {
+        Box::new(PriceRulesRepoImpl::new(
+            db_conn,
+            Box::new(SystemACL::default()) as Box<RepoAcl<PriceRule>>,
+        )) as Box<PriceRulesRepo>
+    }
+    fn create_moderation_checklists_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ModerationChecklistsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(ModerationChecklistsRepoImpl::new(db_conn, acl)) as Box<ModerationChecklistsRepo>
+    }
+    fn create_stock_reservations_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StockReservationsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StockReservationsRepoImpl::new(db_conn, acl)) as Box<StockReservationsRepo>
+    }
+    fn create_category_promotions_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<CategoryPromotionsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(CategoryPromotionsRepoImpl::new(db_conn, acl)) as Box<CategoryPromotionsRepo>
+    }
+    fn create_store_statistics_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoreStatisticsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoreStatisticsRepoImpl::new(db_conn, acl)) as Box<StoreStatisticsRepo>
+    }
+    fn create_impact_reports_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ImpactReportsRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(ImpactReportsRepoImpl::new(db_conn, acl)) as Box<ImpactReportsRepo>
+    }
+    fn create_base_product_history_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductHistoryRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(BaseProductHistoryRepoImpl::new(db_conn, acl)) as Box<BaseProductHistoryRepo>
+    }
+    fn create_base_product_read_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductsReadRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(BaseProductsRepoImpl::new(db_conn, acl)) as Box<BaseProductsReadRepo>
+    }
+    fn create_base_product_write_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<BaseProductsWriteRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(BaseProductsRepoImpl::new(db_conn, acl)) as Box<BaseProductsWriteRepo>
+    }
+    fn create_product_read_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ProductsReadRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(ProductsRepoImpl::new(db_conn, acl, self.product_cache.clone())) as Box<ProductsReadRepo>
+    }
+    fn create_product_write_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<ProductsWriteRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(ProductsRepoImpl::new(db_conn, acl, self.product_cache.clone())) as Box<ProductsWriteRepo>
+    }
+    fn create_stores_read_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoresReadRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoresRepoImpl::new(db_conn, acl)) as Box<StoresReadRepo>
+    }
+    fn create_stores_write_repo<'a>(&self, db_conn: &'a C, user_id: Option<UserId>) -> Box<StoresWriteRepo + 'a> {
+        let acl = self.get_acl(db_conn, user_id);
+        Box::new(StoresRepoImpl::new(db_conn, acl)) as Box<StoresWriteRepo>
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
 
     use errors::Error as MyError;
+    use std::cell::{Cell, RefCell};
     use std::collections::HashMap;
     use std::collections::HashSet;
     use std::error::Error;
     use std::fmt;
     use std::sync::Arc;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
 
     use diesel::connection::AnsiTransactionManager;
     use diesel::connection::SimpleConnection;
     use diesel::deserialize::QueryableByName;
     use diesel::pg::Pg;
+    use diesel::pg::PgConnection;
     use diesel::query_builder::AsQuery;
     use diesel::query_builder::QueryFragment;
     use diesel::query_builder::QueryId;
@@ -208,6 +402,7 @@ pub mod tests {
     use r2d2;
     use r2d2::ManageConnection;
     use serde_json;
+    use stq_cache::cache::NullCache;
     use tokio_core::reactor::Handle;
 
     use stq_http;
@@ -217,8 +412,11 @@ pub mod tests {
     use config::Config;
     use controller::context::*;
     use models::*;
+    use repos::legacy_acl::SystemACL;
     use repos::*;
+    use services;
     use services::*;
+    use validation_messages::DEFAULT_LANGUAGE;
 
     pub const MOCK_REPO_FACTORY: ReposFactoryMock = ReposFactoryMock {};
     pub static MOCK_USER_ID: UserId = UserId(1);
@@ -228,11 +426,16 @@ pub mod tests {
     pub static MOCK_STORE_NAME_JSON: &'static str = r##"[{"lang": "de","text": "Store"}]"##;
     pub static MOCK_STORE_NAME: &'static str = "store";
     pub static MOCK_STORE_SLUG: &'static str = "{}";
+    pub static MOCK_OLD_STORE_SLUG: &'static str = "old-slug";
     pub static MOCK_BASE_PRODUCT_NAME_JSON: &'static str = r##"[{"lang": "en","text": "base product"}]"##;
 
     pub static MOCK_COUPON_ID: CouponId = CouponId(1);
     pub static MOCK_STORE_ID: StoreId = StoreId(1);
     pub static MOCK_COUPON_CODE: &'static str = "ASD";
+    /// Code of the mock coupon limited to `MOCK_COUPON_CATEGORY_ID` category
+    pub static MOCK_CATEGORIES_COUPON_CODE: &'static str = "CATEGORIES";
+    pub static MOCK_COUPON_CATEGORY_ID: CategoryId = CategoryId(10);
+    pub static MOCK_GTIN: &'static str = "4006381333931";
 
     pub fn create_service(
         user_id: Option<UserId>,
@@ -247,8 +450,8 @@ pub mod tests {
         let client_handle = client.handle();
         let client_stream = client.stream();
         handle.spawn(client_stream.for_each(|_| Ok(())));
-        let static_context = StaticContext::new(db_pool, cpu_pool, client_handle, Arc::new(config), MOCK_REPO_FACTORY);
-        let dynamic_context = DynamicContext::new(user_id, Currency::STQ, Currency::USD, String::default());
+        let static_context = StaticContext::new(db_pool.clone(), db_pool, cpu_pool, client_handle, Arc::new(config), MOCK_REPO_FACTORY);
+        let dynamic_context = DynamicContext::new(user_id, Currency::STQ, Currency::USD, String::default(), DEFAULT_LANGUAGE);
 
         Service::new(static_context, dynamic_context)
     }
@@ -263,6 +466,9 @@ pub mod tests {
         fn create_attribute_values_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<AttributeValuesRepo + 'a> {
             Box::new(AttributeValuesRepoMock::default()) as Box<AttributeValuesRepo>
         }
+        fn create_attribute_groups_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<AttributeGroupsRepo + 'a> {
+            Box::new(AttributeGroupsRepoMock::default()) as Box<AttributeGroupsRepo>
+        }
         fn create_categories_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<CategoriesRepo + 'a> {
             Box::new(CategoriesRepoMock::default()) as Box<CategoriesRepo>
         }
@@ -312,10 +518,121 @@ pub mod tests {
         ) -> Box<CouponScopeBaseProductsRepo + 'a> {
             Box::new(CouponScopeBaseProductsRepoMock::default()) as Box<CouponScopeBaseProductsRepo>
         }
+        fn create_coupon_scope_categories_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<CouponScopeCategoriesRepo + 'a> {
+            Box::new(CouponScopeCategoriesRepoMock::default()) as Box<CouponScopeCategoriesRepo>
+        }
 
         fn create_used_coupons_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<UsedCouponsRepo + 'a> {
             Box::new(UsedCouponsRepoMock::default()) as Box<UsedCouponsRepo>
         }
+
+        fn create_coupon_redemptions_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<CouponRedemptionsRepo + 'a> {
+            Box::new(CouponRedemptionsRepoMock::default()) as Box<CouponRedemptionsRepo>
+        }
+
+        fn create_audit_log_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<AuditLogRepo + 'a> {
+            Box::new(AuditLogRepoMock::default()) as Box<AuditLogRepo>
+        }
+
+        fn create_catalog_health_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<CatalogHealthRepo + 'a> {
+            Box::new(CatalogHealthRepoMock::default()) as Box<CatalogHealthRepo>
+        }
+
+
+        fn create_store_verification_codes_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreVerificationCodesRepo + 'a> {
+            Box::new(StoreVerificationCodesRepoMock::default()) as Box<StoreVerificationCodesRepo>
+        }
+
+        fn create_store_profile_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreProfileRepo + 'a> {
+            Box::new(StoreProfileRepoMock::default()) as Box<StoreProfileRepo>
+        }
+
+        fn create_store_feed_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreFeedRepo + 'a> {
+            Box::new(StoreFeedRepoMock::default()) as Box<StoreFeedRepo>
+        }
+
+        fn create_sync_state_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<SyncStateRepo + 'a> {
+            Box::new(SyncStateRepoMock::default()) as Box<SyncStateRepo>
+        }
+
+        fn create_store_faqs_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreFaqsRepo + 'a> {
+            Box::new(StoreFaqsRepoMock::default()) as Box<StoreFaqsRepo>
+        }
+
+        fn create_store_categories_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreCategoriesRepo + 'a> {
+            Box::new(StoreCategoriesRepoMock::default()) as Box<StoreCategoriesRepo>
+        }
+
+        fn create_store_product_positions_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreProductPositionsRepo + 'a> {
+            Box::new(StoreProductPositionsRepoMock::default()) as Box<StoreProductPositionsRepo>
+        }
+
+        fn create_search_synonyms_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<SearchSynonymsRepo + 'a> {
+            Box::new(SearchSynonymsRepoMock::default()) as Box<SearchSynonymsRepo>
+        }
+
+        fn create_index_migrations_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<IndexMigrationsRepo + 'a> {
+            Box::new(IndexMigrationsRepoMock::default()) as Box<IndexMigrationsRepo>
+        }
+
+        fn create_listings_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<ListingsRepo + 'a> {
+            Box::new(ListingsRepoMock::default()) as Box<ListingsRepo>
+        }
+
+        fn create_jobs_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<JobsRepo + 'a> {
+            Box::new(JobsRepoMock::default()) as Box<JobsRepo>
+        }
+
+        fn create_price_rules_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<PriceRulesRepo + 'a> {
+            Box::new(PriceRulesRepoMock::default()) as Box<PriceRulesRepo>
+        }
+
+        fn create_price_rules_repo_with_sys_acl<'a>(&self, _db_conn: &'a C) -> Box<PriceRulesRepo + 'a> {
+            Box::new(PriceRulesRepoMock::default()) as Box<PriceRulesRepo>
+        }
+
+        fn create_moderation_checklists_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<ModerationChecklistsRepo + 'a> {
+            Box::new(ModerationChecklistsRepoMock::default()) as Box<ModerationChecklistsRepo>
+        }
+
+        fn create_stock_reservations_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StockReservationsRepo + 'a> {
+            Box::new(StockReservationsRepoMock::default()) as Box<StockReservationsRepo>
+        }
+
+        fn create_category_promotions_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<CategoryPromotionsRepo + 'a> {
+            Box::new(CategoryPromotionsRepoMock::default()) as Box<CategoryPromotionsRepo>
+        }
+
+        fn create_store_statistics_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoreStatisticsRepo + 'a> {
+            Box::new(StoreStatisticsRepoMock::default()) as Box<StoreStatisticsRepo>
+        }
+
+        fn create_impact_reports_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<ImpactReportsRepo + 'a> {
+            Box::new(ImpactReportsRepoMock::default()) as Box<ImpactReportsRepo>
+        }
+
+        fn create_base_product_history_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<BaseProductHistoryRepo + 'a> {
+            Box::new(BaseProductHistoryRepoMock::default()) as Box<BaseProductHistoryRepo>
+        }
+
+        fn create_base_product_read_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<BaseProductsReadRepo + 'a> {
+            Box::new(BaseProductsRepoMock::default()) as Box<BaseProductsReadRepo>
+        }
+        fn create_base_product_write_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<BaseProductsWriteRepo + 'a> {
+            Box::new(BaseProductsRepoMock::default()) as Box<BaseProductsWriteRepo>
+        }
+        fn create_product_read_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<ProductsReadRepo + 'a> {
+            Box::new(ProductsRepoMock::default()) as Box<ProductsReadRepo>
+        }
+        fn create_product_write_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<ProductsWriteRepo + 'a> {
+            Box::new(ProductsRepoMock::default()) as Box<ProductsWriteRepo>
+        }
+        fn create_stores_read_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoresReadRepo + 'a> {
+            Box::new(StoresRepoMock::default()) as Box<StoresReadRepo>
+        }
+        fn create_stores_write_repo<'a>(&self, _db_conn: &'a C, _user_id: Option<UserId>) -> Box<StoresWriteRepo + 'a> {
+            Box::new(StoresRepoMock::default()) as Box<StoresWriteRepo>
+        }
     }
 
     #[derive(Clone, Default)]
@@ -363,6 +680,8 @@ pub mod tests {
         fn delete(&self, _attribute_id_arg: AttributeId) -> RepoResult<()> {
             Ok(())
         }
+
+        fn invalidate_cache(&self, _attribute_id_arg: AttributeId) {}
     }
 
     #[derive(Clone, Default)]
@@ -405,6 +724,28 @@ pub mod tests {
             }])
         }
 
+        fn find_many_for_update(&self, ids: Vec<AttributeValueId>) -> RepoResult<Vec<AttributeValue>> {
+            Ok(ids
+                .into_iter()
+                .filter(|id| *id == AttributeValueId(1))
+                .map(|id| AttributeValue {
+                    id,
+                    attr_id: AttributeId(1),
+                    code: AttributeValueCode("XXL".to_string()),
+                    translations: None,
+                })
+                .collect())
+        }
+
+        fn search_by_name(&self, _name: String, _count: i64) -> RepoResult<Vec<AttributeValue>> {
+            Ok(vec![AttributeValue {
+                id: AttributeValueId(1),
+                attr_id: AttributeId(1),
+                code: AttributeValueCode("XXL".to_string()),
+                translations: None,
+            }])
+        }
+
         fn update(&self, id: AttributeValueId, update: UpdateAttributeValue) -> RepoResult<AttributeValue> {
             Ok(AttributeValue {
                 id,
@@ -424,6 +765,69 @@ pub mod tests {
         }
     }
 
+    #[derive(Clone, Default)]
+    pub struct AttributeGroupsRepoMock;
+
+    impl AttributeGroupsRepo for AttributeGroupsRepoMock {
+        fn list(&self) -> RepoResult<Vec<AttributeGroup>> {
+            Ok(vec![self.find(1)?.unwrap()])
+        }
+
+        fn find(&self, group_id: i32) -> RepoResult<Option<AttributeGroup>> {
+            Ok(Some(AttributeGroup {
+                id: group_id,
+                name: json!([{"lang": "en", "text": "Dimensions"}]),
+                position: 0,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+            }))
+        }
+
+        fn create(&self, payload: NewAttributeGroup) -> RepoResult<AttributeGroup> {
+            let mut group = self.find(1)?.unwrap();
+            group.name = payload.name;
+            group.position = payload.position;
+            Ok(group)
+        }
+
+        fn update(&self, group_id: i32, payload: UpdateAttributeGroup) -> RepoResult<AttributeGroup> {
+            let mut group = self.find(group_id)?.unwrap();
+            if let Some(name) = payload.name {
+                group.name = name;
+            }
+            if let Some(position) = payload.position {
+                group.position = position;
+            }
+            Ok(group)
+        }
+
+        fn delete(&self, group_id: i32) -> RepoResult<AttributeGroup> {
+            self.find(group_id).map(|group| group.unwrap())
+        }
+
+        fn list_attributes(&self) -> RepoResult<Vec<AttributeGroupAttribute>> {
+            Ok(vec![AttributeGroupAttribute {
+                id: 1,
+                group_id: 1,
+                attribute_id: AttributeId(1),
+                position: 0,
+            }])
+        }
+
+        fn set_attributes(&self, group_id: i32, attribute_ids: Vec<AttributeId>) -> RepoResult<Vec<AttributeGroupAttribute>> {
+            Ok(attribute_ids
+                .into_iter()
+                .enumerate()
+                .map(|(position, attribute_id)| AttributeGroupAttribute {
+                    id: position as i32 + 1,
+                    group_id,
+                    attribute_id,
+                    position: position as i32,
+                })
+                .collect())
+        }
+    }
+
     #[derive(Clone, Default)]
     pub struct CustomAttributesRepoMock;
 
@@ -527,14 +931,15 @@ pub mod tests {
 
         /// Get coupon by code
         fn get_by_code(&self, code_arg: CouponCode, store_id_arg: StoreId) -> RepoResult<Option<Coupon>> {
+            let is_categories_coupon = code_arg.0 == MOCK_CATEGORIES_COUPON_CODE;
             Ok(Some(Coupon {
                 id: MOCK_COUPON_ID,
                 code: code_arg,
                 title: "title".to_string(),
                 store_id: store_id_arg,
-                scope: CouponScope::BaseProducts,
+                scope: if is_categories_coupon { CouponScope::Categories } else { CouponScope::BaseProducts },
                 percent: 0,
-                quantity: 1,
+                quantity: if is_categories_coupon { Coupon::INFINITE } else { 1 },
                 expired_at: None,
                 is_active: true,
                 created_at: SystemTime::now(),
@@ -542,6 +947,11 @@ pub mod tests {
             }))
         }
 
+        /// Get coupon by code, locking the row until the end of the transaction
+        fn get_by_code_for_update(&self, code_arg: CouponCode, store_id_arg: StoreId) -> RepoResult<Option<Coupon>> {
+            self.get_by_code(code_arg, store_id_arg)
+        }
+
         /// Search coupons
         fn find_by(&self, search: CouponSearch) -> RepoResult<Vec<Coupon>> {
             match search {
@@ -561,6 +971,25 @@ pub mod tests {
             }
         }
 
+        /// Search coupons of all stores, newest first
+        fn admin_search(
+            &self,
+            _pagination_params: PaginationParams<CouponId>,
+            terms: AdminCouponsSearchTerms,
+        ) -> RepoResult<AdminCouponsSearchResults> {
+            let coupons = self
+                .list()?
+                .into_iter()
+                .filter(|coupon| terms.store_id.map(|store_id| coupon.store_id == store_id).unwrap_or(true))
+                .filter(|coupon| terms.is_active.map(|is_active| coupon.is_active == is_active).unwrap_or(true))
+                .collect::<Vec<_>>();
+
+            Ok(AdminCouponsSearchResults {
+                total_count: coupons.len() as u32,
+                coupons,
+            })
+        }
+
         /// Update coupon
         fn update(&self, id_arg: CouponId, payload: UpdateCoupon) -> RepoResult<Coupon> {
             Ok(Coupon {
@@ -622,6 +1051,35 @@ pub mod tests {
                 base_product_id: base_product_arg,
             })
         }
+
+        fn delete_by_base_product(&self, _base_product_arg: BaseProductId) -> RepoResult<Vec<CouponScopeBaseProducts>> {
+            Ok(vec![])
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct CouponScopeCategoriesRepoMock;
+
+    impl CouponScopeCategoriesRepo for CouponScopeCategoriesRepoMock {
+        fn create(&self, payload: NewCouponScopeCategories) -> RepoResult<CouponScopeCategories> {
+            Ok(CouponScopeCategories {
+                id: 0,
+                coupon_id: payload.coupon_id,
+                category_id: payload.category_id,
+            })
+        }
+
+        fn find_categories(&self, _id_arg: CouponId) -> RepoResult<Vec<CategoryId>> {
+            Ok(vec![MOCK_COUPON_CATEGORY_ID])
+        }
+
+        fn delete(&self, id_arg: CouponId, category_arg: CategoryId) -> RepoResult<CouponScopeCategories> {
+            Ok(CouponScopeCategories {
+                id: 0,
+                coupon_id: id_arg,
+                category_id: category_arg,
+            })
+        }
     }
 
     #[derive(Clone, Default)]
@@ -670,76 +1128,879 @@ pub mod tests {
     }
 
     #[derive(Clone, Default)]
-    pub struct CategoriesRepoMock;
+    pub struct CouponRedemptionsRepoMock;
 
-    impl CategoriesRepo for CategoriesRepoMock {
-        /// Find specific category by id
-        fn find(&self, id_arg: CategoryId) -> RepoResult<Option<Category>> {
-            Ok(Some(Category {
-                id: id_arg,
-                is_active: true,
-                name: serde_json::from_str("{}").unwrap(),
-                meta_field: None,
-                children: vec![],
-                level: 0,
-                parent_id: Some(CategoryId(id_arg.0 - 1)),
-                attributes: vec![],
-                slug: CategorySlug("1".to_string()),
-            }))
+    impl CouponRedemptionsRepo for CouponRedemptionsRepoMock {
+        fn create(&self, payload: NewCouponRedemption) -> RepoResult<CouponRedemption> {
+            Ok(CouponRedemption {
+                token: payload.token,
+                coupon_id: payload.coupon_id,
+                user_id: payload.user_id,
+                product_id: payload.product_id,
+                created_at: SystemTime::now(),
+            })
         }
 
-        /// Find specific category by id
-        fn find_by_slug(&self, slug: CategorySlug) -> RepoResult<Option<Category>> {
-            Ok(Some(Category {
-                id: CategoryId(2),
-                is_active: true,
-                name: serde_json::from_str("{}").unwrap(),
-                meta_field: None,
-                children: vec![],
-                level: 0,
-                parent_id: Some(CategoryId(1)),
-                attributes: vec![],
-                slug,
+        fn find(&self, token_arg: uuid::Uuid) -> RepoResult<Option<CouponRedemption>> {
+            Ok(Some(CouponRedemption {
+                token: token_arg,
+                coupon_id: MOCK_COUPON_ID,
+                user_id: MOCK_USER_ID,
+                product_id: MOCK_PRODUCT_ID,
+                created_at: SystemTime::now(),
             }))
         }
 
-        /// Creates new category
-        fn create(&self, payload: NewCategory) -> RepoResult<Category> {
-            Ok(Category {
-                id: CategoryId(1),
-                is_active: true,
-                name: payload.name,
-                meta_field: None,
-                children: vec![],
-                level: 0,
-                parent_id: Some(CategoryId(0)),
-                attributes: vec![],
-                slug: CategorySlug("1".to_string()),
+        fn delete(&self, token_arg: uuid::Uuid) -> RepoResult<CouponRedemption> {
+            Ok(CouponRedemption {
+                token: token_arg,
+                coupon_id: MOCK_COUPON_ID,
+                user_id: MOCK_USER_ID,
+                product_id: MOCK_PRODUCT_ID,
+                created_at: SystemTime::now(),
             })
         }
+    }
 
-        /// Updates specific category
-        fn update(&self, category_id_arg: CategoryId, payload: UpdateCategory) -> RepoResult<Category> {
-            Ok(Category {
-                id: category_id_arg,
-                is_active: true,
-                name: payload.name.unwrap(),
-                meta_field: None,
-                children: vec![],
-                level: 0,
-                parent_id: Some(CategoryId(0)),
-                attributes: vec![],
-                slug: CategorySlug("1".to_string()),
+    #[derive(Clone, Default)]
+    pub struct AuditLogRepoMock;
+
+    impl AuditLogRepo for AuditLogRepoMock {
+        fn create(&self, payload: NewAuditLogEntry) -> RepoResult<AuditLogEntry> {
+            Ok(AuditLogEntry {
+                id: 1,
+                actor_user_id: payload.actor_user_id,
+                effective_user_id: payload.effective_user_id,
+                method: payload.method,
+                path: payload.path,
+                created_at: SystemTime::now(),
+                base_product_id: payload.base_product_id,
+                change: payload.change,
             })
         }
+    }
 
-        /// Deletes specific categories
-        fn delete_all(&self, _category_ids_arg: &[CategoryId]) -> RepoResult<()> {
-            Ok(())
+    #[derive(Clone, Default)]
+    pub struct CatalogHealthRepoMock;
+
+    impl CatalogHealthRepo for CatalogHealthRepoMock {
+        fn get(&self, store_id: StoreId, stale_price_days: u64) -> RepoResult<Option<CatalogHealthReport>> {
+            let store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            let products = vec![create_product(MOCK_PRODUCT_ID, MOCK_BASE_PRODUCT_ID)];
+            Ok(Some(CatalogHealthReport::new(
+                &store,
+                &[],
+                &products,
+                stale_price_days,
+                SystemTime::now(),
+            )))
         }
 
-        /// Returns all categories as a tree
-        fn get_all_categories(&self) -> RepoResult<Category> {
+        fn invalidate(&self, _store_id: StoreId) {}
+    }
+
+    #[derive(Clone, Default)]
+    pub struct JobsRepoMock;
+
+    impl JobsRepo for JobsRepoMock {
+        fn create(&self, payload: NewJob) -> RepoResult<Job> {
+            Ok(create_job(1, payload))
+        }
+
+        fn find(&self, job_id: i32) -> RepoResult<Option<Job>> {
+            let mut job = create_job(job_id, NewJob::new(MOCK_USER_ID, JobKind::AttributeValueTranslationsImport));
+            job.status = JobStatus::Running;
+            job.total = 4;
+            job.processed = 1;
+            Ok(Some(job))
+        }
+
+        fn update(&self, job_id: i32, payload: UpdateJob) -> RepoResult<Job> {
+            let mut job = self.find(job_id)?.unwrap();
+            job.status = payload.status.unwrap_or(job.status);
+            job.total = payload.total.unwrap_or(job.total);
+            job.processed = payload.processed.unwrap_or(job.processed);
+            job.errors = payload.errors.unwrap_or(job.errors);
+            Ok(job)
+        }
+
+        fn fail_interrupted(&self, _idle_since: SystemTime) -> RepoResult<Vec<Job>> {
+            Ok(vec![])
+        }
+    }
+
+    pub static MOCK_VERIFICATION_CODE: &'static str = "123456";
+
+    #[derive(Clone, Default)]
+    pub struct StoreVerificationCodesRepoMock;
+
+    impl StoreVerificationCodesRepo for StoreVerificationCodesRepoMock {
+        fn create(&self, payload: NewStoreVerificationCode) -> RepoResult<StoreVerificationCode> {
+            Ok(StoreVerificationCode {
+                id: 1,
+                store_id: payload.store_id,
+                channel: payload.channel,
+                destination: payload.destination,
+                code: payload.code,
+                attempts: 0,
+                expires_at: payload.expires_at,
+                created_at: SystemTime::now(),
+            })
+        }
+
+        fn count_sent_since(&self, _store_id: StoreId, _channel: VerificationChannel, _since: SystemTime) -> RepoResult<i64> {
+            Ok(1)
+        }
+
+        fn find_latest(&self, store_id: StoreId, channel: VerificationChannel) -> RepoResult<Option<StoreVerificationCode>> {
+            let store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            let destination = match channel {
+                VerificationChannel::Email => store.email,
+                VerificationChannel::Phone => store.phone,
+            };
+            let mut payload = NewStoreVerificationCode::new(store_id, channel, destination.unwrap_or_default());
+            payload.code = MOCK_VERIFICATION_CODE.to_string();
+            self.create(payload).map(Some)
+        }
+
+        fn increment_attempts(&self, code_id: i32) -> RepoResult<StoreVerificationCode> {
+            let mut code = self.find_latest(MOCK_STORE_ID, VerificationChannel::Email)?.unwrap();
+            code.id = code_id;
+            code.attempts += 1;
+            Ok(code)
+        }
+
+        fn delete_by_store(&self, _store_id: StoreId, _channel: VerificationChannel) -> RepoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StoreProfileRepoMock;
+
+    thread_local! {
+        /// Profiles cached by `StoreProfileRepoMock`, each build counts one more published product
+        static STORE_PROFILES: RefCell<HashMap<String, StoreProfile>> = RefCell::new(HashMap::new());
+        static STORE_PROFILE_BUILDS: Cell<i64> = Cell::new(0);
+    }
+
+    impl StoreProfileRepo for StoreProfileRepoMock {
+        fn get(&self, store_slug: StoreSlug) -> RepoResult<Option<StoreProfile>> {
+            let profile = STORE_PROFILES.with(|profiles| {
+                profiles
+                    .borrow_mut()
+                    .entry(store_slug.to_string())
+                    .or_insert_with(|| {
+                        let builds = STORE_PROFILE_BUILDS.with(|builds| {
+                            builds.set(builds.get() + 1);
+                            builds.get()
+                        });
+                        let mut store = create_store(MOCK_STORE_ID, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+                        store.slug = store_slug.to_string();
+                        StoreProfile::new(store, builds)
+                    })
+                    .clone()
+            });
+            Ok(Some(profile))
+        }
+
+        fn invalidate(&self, store_slug: StoreSlug) {
+            STORE_PROFILES.with(|profiles| profiles.borrow_mut().remove(&store_slug.to_string()));
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StoreFeedRepoMock;
+
+    impl StoreFeedRepo for StoreFeedRepoMock {
+        fn get(&self, store_id: StoreId) -> RepoResult<Option<StoreFeed>> {
+            let store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            let base_products = BaseProductsRepoMock::default()
+                .find(MOCK_BASE_PRODUCT_ID, Visibility::Published)?
+                .into_iter()
+                .collect();
+            Ok(Some(StoreFeed::new(store, base_products, SystemTime::now())))
+        }
+
+        fn invalidate(&self, _store_id: StoreId) {}
+    }
+
+    #[derive(Clone, Default)]
+    pub struct SyncStateRepoMock;
+
+    impl SyncStateRepo for SyncStateRepoMock {
+        fn get_state(&self) -> RepoResult<SyncState> {
+            Ok(SyncState {
+                entities: SyncEntityType::all()
+                    .into_iter()
+                    .map(|entity_type| SyncEntityState {
+                        entity_type,
+                        max_update_no: Some(1),
+                        count: 1,
+                    })
+                    .collect(),
+                schema_version: ELASTIC_SCHEMA_VERSION,
+            })
+        }
+
+        fn find_entities(&self, entity_type: SyncEntityType, _range: SyncRange, _count: i64) -> RepoResult<SyncEntities> {
+            Ok(match entity_type {
+                SyncEntityType::Store => SyncEntities::Store(vec![create_store(
+                    MOCK_STORE_ID,
+                    serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap(),
+                )]),
+                SyncEntityType::BaseProduct => SyncEntities::BaseProduct(vec![]),
+                SyncEntityType::Product => SyncEntities::Product(vec![]),
+            })
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StoreFaqsRepoMock;
+
+    impl StoreFaqsRepo for StoreFaqsRepoMock {
+        fn list(&self, store_id: StoreId) -> RepoResult<Vec<StoreFaq>> {
+            Ok(vec![self.find(store_id, 1)?.unwrap()])
+        }
+
+        fn find(&self, store_id: StoreId, faq_id: i32) -> RepoResult<Option<StoreFaq>> {
+            Ok(Some(StoreFaq {
+                id: faq_id,
+                store_id,
+                question: json!([{"lang": "en", "text": "Question"}]),
+                answer: json!([{"lang": "en", "text": "Answer"}]),
+                position: 0,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+            }))
+        }
+
+        fn create(&self, payload: NewStoreFaq) -> RepoResult<StoreFaq> {
+            Ok(StoreFaq {
+                id: 1,
+                store_id: payload.store_id,
+                question: payload.question,
+                answer: payload.answer,
+                position: payload.position,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+            })
+        }
+
+        fn update(&self, faq_id: i32, payload: UpdateStoreFaq) -> RepoResult<StoreFaq> {
+            let mut faq = self.find(MOCK_STORE_ID, faq_id)?.unwrap();
+            if let Some(question) = payload.question {
+                faq.question = question;
+            }
+            if let Some(answer) = payload.answer {
+                faq.answer = answer;
+            }
+            if let Some(position) = payload.position {
+                faq.position = position;
+            }
+            Ok(faq)
+        }
+
+        fn delete(&self, faq_id: i32) -> RepoResult<StoreFaq> {
+            self.find(MOCK_STORE_ID, faq_id).map(|faq| faq.unwrap())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StoreProductPositionsRepoMock;
+
+    impl StoreProductPositionsRepo for StoreProductPositionsRepoMock {
+        fn list(&self, _store_id: StoreId) -> RepoResult<Vec<StoreProductPosition>> {
+            Ok(vec![])
+        }
+
+        fn replace(&self, _store_id: StoreId, payload: Vec<NewStoreProductPosition>) -> RepoResult<Vec<StoreProductPosition>> {
+            let mut positions = payload
+                .into_iter()
+                .enumerate()
+                .map(|(index, new_position)| StoreProductPosition {
+                    id: index as i32 + 1,
+                    store_id: new_position.store_id,
+                    base_product_id: new_position.base_product_id,
+                    position: new_position.position,
+                    pinned: new_position.pinned,
+                    created_at: SystemTime::now(),
+                })
+                .collect::<Vec<_>>();
+            positions.sort_by_key(|position| (!position.pinned, position.position));
+            Ok(positions)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct CategoryPromotionsRepoMock;
+
+    /// Mock category has a live promotion and a scheduled one
+    impl CategoryPromotionsRepo for CategoryPromotionsRepoMock {
+        fn list(&self, category_id: CategoryId, live_only: bool) -> RepoResult<Vec<CategoryPromotion>> {
+            let now = SystemTime::now();
+            Ok(vec![create_category_promotion(1, None), create_category_promotion(2, Some(now + Duration::from_secs(3600)))]
+                .into_iter()
+                .filter(|promotion| promotion.category_id == category_id && (!live_only || promotion.is_live(now)))
+                .collect())
+        }
+
+        fn find(&self, promotion_id: i32) -> RepoResult<Option<CategoryPromotion>> {
+            Ok(self.list(CategoryId(1), false)?.into_iter().find(|promotion| promotion.id == promotion_id))
+        }
+
+        fn create(&self, payload: NewCategoryPromotion) -> RepoResult<CategoryPromotion> {
+            Ok(CategoryPromotion {
+                id: 3,
+                category_id: payload.category_id,
+                banner_url: payload.banner_url,
+                target_url: payload.target_url,
+                position: payload.position,
+                starts_at: payload.starts_at,
+                ends_at: payload.ends_at,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+            })
+        }
+
+        fn update(&self, promotion_id: i32, payload: UpdateCategoryPromotion) -> RepoResult<Option<CategoryPromotion>> {
+            Ok(self.find(promotion_id)?.map(|promotion| CategoryPromotion {
+                banner_url: payload.banner_url.unwrap_or(promotion.banner_url),
+                target_url: payload.target_url.unwrap_or(promotion.target_url),
+                position: payload.position.unwrap_or(promotion.position),
+                starts_at: payload.starts_at.or(promotion.starts_at),
+                ends_at: payload.ends_at.or(promotion.ends_at),
+                ..promotion
+            }))
+        }
+
+        fn delete(&self, promotion_id: i32) -> RepoResult<Option<CategoryPromotion>> {
+            self.find(promotion_id)
+        }
+    }
+
+    fn create_category_promotion(id: i32, starts_at: Option<SystemTime>) -> CategoryPromotion {
+        CategoryPromotion {
+            id,
+            category_id: CategoryId(1),
+            banner_url: "https://example.com/banner.png".to_string(),
+            target_url: "/categories/1".to_string(),
+            position: id,
+            starts_at,
+            ends_at: None,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StoreStatisticsRepoMock;
+
+    impl StoreStatisticsRepo for StoreStatisticsRepoMock {
+        fn get(&self, store_id: StoreId) -> RepoResult<Option<StoreStatistics>> {
+            let groups = vec![BaseProductsStatusGroup {
+                status: ModerationStatus::Published,
+                base_products: 1,
+                views: 1,
+                rated: 0,
+                rating_sum: 0.0,
+            }];
+            Ok(Some(StoreStatistics::new(store_id, groups, 1, 1)))
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct ImpactReportsRepoMock;
+
+    impl ImpactReportsRepo for ImpactReportsRepoMock {
+        fn categories_deletion(&self, category_ids: &[CategoryId]) -> RepoResult<ImpactReport> {
+            let mut report = ImpactReport::default();
+            report.add_rows("categories", category_ids.len() as i64);
+            Ok(report)
+        }
+
+        fn attribute_deletion(&self, _attribute_id: AttributeId) -> RepoResult<ImpactReport> {
+            let mut report = ImpactReport::default();
+            report.add_rows("attributes", 1);
+            Ok(report)
+        }
+
+        fn store_deletion(&self, _store_id: StoreId) -> RepoResult<ImpactReport> {
+            let mut report = ImpactReport::default();
+            report.add_rows("stores", 1);
+            report.add_rows("base_products", 1);
+            report.add_documents(ElasticIndex::Store, 1);
+            report.add_documents(ElasticIndex::Product, 1);
+            Ok(report)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct BaseProductHistoryRepoMock;
+
+    impl BaseProductHistoryRepo for BaseProductHistoryRepoMock {
+        fn record(&self, _changes: Vec<NewBaseProductChange>) -> RepoResult<()> {
+            Ok(())
+        }
+
+        fn list(&self, base_product_id: BaseProductId) -> RepoResult<Vec<BaseProductHistoryEntry>> {
+            let change = BaseProductChange::StatusChanged {
+                old_status: ModerationStatus::Draft,
+                new_status: ModerationStatus::Moderation,
+            };
+            Ok(vec![BaseProductHistoryEntry {
+                id: 1,
+                base_product_id,
+                changed_by: MOCK_USER_ID,
+                impersonated_by: None,
+                created_at: SystemTime::now(),
+                description: change.description(),
+                change,
+            }])
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StoreCategoriesRepoMock;
+
+    /// Mock store declared every category, other stores only requested them
+    impl StoreCategoriesRepo for StoreCategoriesRepoMock {
+        fn list(&self, store_id: StoreId) -> RepoResult<Vec<StoreCategory>> {
+            // stores after the second one have not declared their categories yet
+            if store_id.0 > 2 {
+                return Ok(vec![]);
+            }
+            Ok(vec![self.find(store_id, CategoryId(1))?.unwrap()])
+        }
+
+        fn find(&self, store_id: StoreId, category_id: CategoryId) -> RepoResult<Option<StoreCategory>> {
+            let status = if store_id == MOCK_STORE_ID {
+                StoreCategoryStatus::Declared
+            } else {
+                StoreCategoryStatus::Requested
+            };
+            Ok(Some(create_store_category(store_id, category_id, status)))
+        }
+
+        fn replace_declared(&self, store_id: StoreId, category_ids: Vec<CategoryId>) -> RepoResult<Vec<StoreCategory>> {
+            Ok(category_ids
+                .into_iter()
+                .map(|category_id| create_store_category(store_id, category_id, StoreCategoryStatus::Declared))
+                .collect())
+        }
+
+        fn request(&self, store_id: StoreId, category_id: CategoryId) -> RepoResult<StoreCategory> {
+            Ok(create_store_category(store_id, category_id, StoreCategoryStatus::Requested))
+        }
+
+        fn set_status(&self, store_category_id: i32, status: StoreCategoryStatus, moderator_id: UserId) -> RepoResult<StoreCategory> {
+            let mut store_category = create_store_category(StoreId(2), CategoryId(1), status);
+            store_category.id = store_category_id;
+            store_category.moderator_id = Some(moderator_id);
+            Ok(store_category)
+        }
+    }
+
+    fn create_store_category(store_id: StoreId, category_id: CategoryId, status: StoreCategoryStatus) -> StoreCategory {
+        StoreCategory {
+            id: 1,
+            store_id,
+            category_id,
+            status,
+            moderator_id: None,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct PriceRulesRepoMock;
+
+    impl PriceRulesRepo for PriceRulesRepoMock {
+        fn list(&self, store_id: StoreId) -> RepoResult<Vec<PriceRule>> {
+            Ok(vec![self.find(store_id, 1)?.unwrap()])
+        }
+
+        fn list_active(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<PriceRule>> {
+            store_ids.into_iter().map(|store_id| self.find(store_id, 1).map(|rule| rule.unwrap())).collect()
+        }
+
+        fn find(&self, store_id: StoreId, price_rule_id: i32) -> RepoResult<Option<PriceRule>> {
+            Ok(Some(PriceRule {
+                id: price_rule_id,
+                store_id,
+                name: "Slow movers".to_string(),
+                category_id: None,
+                max_stock: None,
+                max_views: Some(10),
+                percent: -20,
+                position: 0,
+                is_active: true,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+            }))
+        }
+
+        fn create(&self, payload: NewPriceRule) -> RepoResult<PriceRule> {
+            Ok(PriceRule {
+                id: 1,
+                store_id: payload.store_id,
+                name: payload.name,
+                category_id: payload.category_id,
+                max_stock: payload.max_stock,
+                max_views: payload.max_views,
+                percent: payload.percent,
+                position: payload.position,
+                is_active: true,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+            })
+        }
+
+        fn update(&self, price_rule_id: i32, payload: UpdatePriceRule) -> RepoResult<PriceRule> {
+            let mut rule = self.find(MOCK_STORE_ID, price_rule_id)?.unwrap();
+            rule.name = payload.name.unwrap_or(rule.name);
+            rule.percent = payload.percent.unwrap_or(rule.percent);
+            rule.position = payload.position.unwrap_or(rule.position);
+            rule.is_active = payload.is_active.unwrap_or(rule.is_active);
+            Ok(rule)
+        }
+
+        fn delete(&self, price_rule_id: i32) -> RepoResult<PriceRule> {
+            self.find(MOCK_STORE_ID, price_rule_id).map(|rule| rule.unwrap())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct SearchSynonymsRepoMock;
+
+    impl SearchSynonymsRepo for SearchSynonymsRepoMock {
+        fn list(&self) -> RepoResult<Vec<SearchSynonym>> {
+            Ok(vec![create_search_synonym(1, vec!["sneakers".to_string(), "trainers".to_string()])])
+        }
+
+        fn create(&self, payload: NewSearchSynonym) -> RepoResult<SearchSynonym> {
+            Ok(create_search_synonym(2, payload.terms))
+        }
+
+        fn delete(&self, synonym_id: i32) -> RepoResult<Option<SearchSynonym>> {
+            Ok(self.list()?.into_iter().find(|synonym| synonym.id == synonym_id))
+        }
+    }
+
+    fn create_search_synonym(id: i32, terms: Vec<String>) -> SearchSynonym {
+        SearchSynonym {
+            id,
+            terms,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct IndexMigrationsRepoMock;
+
+    impl IndexMigrationsRepo for IndexMigrationsRepoMock {
+        fn create(&self, payload: NewIndexMigration) -> RepoResult<IndexMigration> {
+            let mut migration = create_index_migration(1);
+            migration.old_index = payload.old_index;
+            migration.new_index = payload.new_index;
+            migration.replays = serde_json::from_value(payload.replays)?;
+            Ok(migration)
+        }
+
+        fn set_switched(&self, migration_id: i32) -> RepoResult<IndexMigration> {
+            let mut migration = create_index_migration(migration_id);
+            migration.switched_at = Some(SystemTime::now());
+            Ok(migration)
+        }
+
+        fn find(&self, migration_id: i32) -> RepoResult<Option<IndexMigration>> {
+            // only the first migration exists
+            if migration_id == 1 {
+                Ok(Some(create_index_migration(migration_id)))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
+    fn create_index_migration(id: i32) -> IndexMigration {
+        IndexMigration {
+            id,
+            old_index: "products_1".to_string(),
+            new_index: "products_2".to_string(),
+            replays: vec![QueryReplay::new("sneakers".to_string(), 10, 12)],
+            created_at: SystemTime::now(),
+            switched_at: None,
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct ModerationChecklistsRepoMock;
+
+    impl ModerationChecklistsRepo for ModerationChecklistsRepoMock {
+        fn list_items(&self, category_ids: Vec<CategoryId>) -> RepoResult<Vec<ModerationChecklistItem>> {
+            let items = vec![
+                create_moderation_checklist_item(1, CategoryId(1), "CE certification photo present"),
+                create_moderation_checklist_item(2, CategoryId(3), "Battery capacity specified"),
+            ];
+            Ok(items.into_iter().filter(|item| category_ids.contains(&item.category_id)).collect())
+        }
+
+        fn create_item(&self, payload: NewModerationChecklistItem) -> RepoResult<ModerationChecklistItem> {
+            Ok(create_moderation_checklist_item(3, payload.category_id, &payload.description))
+        }
+
+        fn update_item(&self, item_id: i32, payload: UpdateModerationChecklistItem) -> RepoResult<Option<ModerationChecklistItem>> {
+            let description = payload.description.unwrap_or_default();
+            Ok(Some(create_moderation_checklist_item(item_id, CategoryId(1), &description)))
+        }
+
+        fn delete_item(&self, item_id: i32) -> RepoResult<Option<ModerationChecklistItem>> {
+            Ok(self.list_items(vec![CategoryId(1), CategoryId(3)])?.into_iter().find(|item| item.id == item_id))
+        }
+
+        fn replace_results(
+            &self,
+            _base_product_id: BaseProductId,
+            payload: Vec<NewBaseProductChecklistResult>,
+        ) -> RepoResult<Vec<BaseProductChecklistResult>> {
+            Ok(payload
+                .into_iter()
+                .enumerate()
+                .map(|(index, result)| BaseProductChecklistResult {
+                    id: index as i32 + 1,
+                    base_product_id: result.base_product_id,
+                    item_id: result.item_id,
+                    passed: result.passed,
+                    moderator_id: result.moderator_id,
+                    created_at: SystemTime::now(),
+                })
+                .collect())
+        }
+
+        fn find_unmet_items(&self, _base_product_id: BaseProductId) -> RepoResult<Vec<ModerationChecklistItem>> {
+            Ok(vec![create_moderation_checklist_item(1, CategoryId(1), "CE certification photo present")])
+        }
+    }
+
+    fn create_moderation_checklist_item(id: i32, category_id: CategoryId, description: &str) -> ModerationChecklistItem {
+        ModerationChecklistItem {
+            id,
+            category_id,
+            description: description.to_string(),
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    /// Cart holding the only active reservation of the mock, 4 units of the mock product
+    pub fn mock_cart_id() -> uuid::Uuid {
+        uuid::Uuid::parse_str("6a2b1c3e-4f5d-4e6a-8b7c-9d0e1f2a3b4c").unwrap()
+    }
+
+    #[derive(Clone, Default)]
+    pub struct StockReservationsRepoMock;
+
+    impl StockReservationsRepo for StockReservationsRepoMock {
+        fn reserved_quantity(&self, product_id: ProductId, except_cart_id: Option<uuid::Uuid>, now: SystemTime) -> RepoResult<i32> {
+            Ok(self
+                .list_active(now)?
+                .into_iter()
+                .filter(|reservation| reservation.product_id == product_id && Some(reservation.cart_id) != except_cart_id)
+                .map(|reservation| reservation.quantity)
+                .sum())
+        }
+
+        fn list_active(&self, _now: SystemTime) -> RepoResult<Vec<StockReservation>> {
+            Ok(vec![create_stock_reservation(
+                1,
+                NewStockReservation {
+                    cart_id: mock_cart_id(),
+                    product_id: MOCK_PRODUCT_ID,
+                    quantity: 4,
+                    expires_at: SystemTime::now(),
+                },
+            )])
+        }
+
+        fn reserve(&self, payload: NewStockReservation) -> RepoResult<StockReservation> {
+            Ok(create_stock_reservation(2, payload))
+        }
+
+        fn release(&self, reservation_ids: Vec<i32>) -> RepoResult<Vec<StockReservation>> {
+            Ok(self
+                .list_active(SystemTime::now())?
+                .into_iter()
+                .filter(|reservation| reservation_ids.contains(&reservation.id))
+                .collect())
+        }
+
+        fn release_cart(&self, cart_id: uuid::Uuid, product_id: ProductId) -> RepoResult<Vec<StockReservation>> {
+            Ok(self
+                .list_active(SystemTime::now())?
+                .into_iter()
+                .filter(|reservation| reservation.cart_id == cart_id && reservation.product_id == product_id)
+                .collect())
+        }
+
+        fn delete_expired(&self, _now: SystemTime) -> RepoResult<Vec<StockReservation>> {
+            Ok(vec![])
+        }
+    }
+
+    fn create_stock_reservation(id: i32, payload: NewStockReservation) -> StockReservation {
+        StockReservation {
+            id,
+            cart_id: payload.cart_id,
+            product_id: payload.product_id,
+            quantity: payload.quantity,
+            expires_at: payload.expires_at,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct ListingsRepoMock;
+
+    impl ListingsRepo for ListingsRepoMock {
+        fn find(&self, listing_id: i32) -> RepoResult<Option<Listing>> {
+            Ok(Some(create_listing(
+                listing_id,
+                NewListing {
+                    category_id: CategoryId(1),
+                    attributes: json!([{"attr_id": 1, "value": "red"}]),
+                },
+            )))
+        }
+
+        fn find_by_attributes(&self, _payload: &NewListing) -> RepoResult<Option<Listing>> {
+            Ok(None)
+        }
+
+        fn create(&self, payload: NewListing) -> RepoResult<Listing> {
+            Ok(create_listing(1, payload))
+        }
+    }
+
+    fn create_listing(id: i32, payload: NewListing) -> Listing {
+        Listing {
+            id,
+            category_id: payload.category_id,
+            attributes: payload.attributes,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    fn create_job(id: i32, payload: NewJob) -> Job {
+        Job {
+            id,
+            user_id: payload.user_id,
+            kind: payload.kind,
+            status: payload.status,
+            total: 0,
+            processed: 0,
+            errors: serde_json::Value::Array(vec![]),
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct CategoriesRepoMock;
+
+    impl CategoriesRepo for CategoriesRepoMock {
+        /// Find specific category by id
+        fn find(&self, id_arg: CategoryId) -> RepoResult<Option<Category>> {
+            Ok(Some(Category {
+                id: id_arg,
+                is_active: true,
+                name: serde_json::from_str("{}").unwrap(),
+                meta_field: None,
+                children: vec![],
+                // category 3 is the leaf of the mock categories tree
+                level: if id_arg == CategoryId(3) { Category::MAX_LEVEL_NESTING } else { 0 },
+                parent_id: Some(CategoryId(id_arg.0 - 1)),
+                attributes: vec![],
+                slug: CategorySlug("1".to_string()),
+                products_count: None,
+            }))
+        }
+
+        /// Find specific category by id
+        fn find_by_slug(&self, slug: CategorySlug) -> RepoResult<Option<Category>> {
+            Ok(Some(Category {
+                id: CategoryId(2),
+                is_active: true,
+                name: serde_json::from_str("{}").unwrap(),
+                meta_field: None,
+                children: vec![],
+                level: 0,
+                parent_id: Some(CategoryId(1)),
+                attributes: vec![],
+                slug,
+                products_count: None,
+            }))
+        }
+
+        /// Creates new category
+        fn create(&self, payload: NewCategory) -> RepoResult<Category> {
+            Ok(Category {
+                id: CategoryId(1),
+                is_active: true,
+                name: payload.name,
+                meta_field: None,
+                children: vec![],
+                level: 0,
+                parent_id: Some(CategoryId(0)),
+                attributes: vec![],
+                slug: CategorySlug("1".to_string()),
+                products_count: None,
+            })
+        }
+
+        /// Updates specific category
+        fn update(&self, category_id_arg: CategoryId, payload: UpdateCategory) -> RepoResult<Category> {
+            Ok(Category {
+                id: category_id_arg,
+                is_active: true,
+                name: payload.name.unwrap(),
+                meta_field: None,
+                children: vec![],
+                level: 0,
+                parent_id: Some(CategoryId(0)),
+                attributes: vec![],
+                slug: CategorySlug("1".to_string()),
+                products_count: None,
+            })
+        }
+
+        /// Deletes specific categories
+        fn delete_all(&self, _category_ids_arg: &[CategoryId]) -> RepoResult<()> {
+            Ok(())
+        }
+
+        /// Moves category with its subtree under another parent
+        fn move_category(&self, category_id_arg: CategoryId, new_parent_id: CategoryId) -> RepoResult<Category> {
+            Ok(Category {
+                id: category_id_arg,
+                is_active: true,
+                name: serde_json::from_str("{}").unwrap(),
+                meta_field: None,
+                children: vec![],
+                level: 1,
+                parent_id: Some(new_parent_id),
+                attributes: vec![],
+                slug: CategorySlug("1".to_string()),
+                products_count: None,
+            })
+        }
+
+        /// Returns all categories as a tree
+        fn get_all_categories(&self) -> RepoResult<Category> {
             Ok(create_mock_categories())
         }
 
@@ -750,6 +2011,8 @@ pub mod tests {
         fn get_raw_categories(&self) -> RepoResult<Vec<RawCategory>> {
             Ok(create_raw_mock_categories())
         }
+
+        fn invalidate_cache(&self) {}
     }
 
     fn create_mock_categories() -> Category {
@@ -763,6 +2026,7 @@ pub mod tests {
             parent_id: Some(CategoryId(2)),
             attributes: vec![],
             slug: CategorySlug("3".to_string()),
+            products_count: None,
         };
         let cat_2 = Category {
             id: CategoryId(2),
@@ -774,6 +2038,7 @@ pub mod tests {
             parent_id: Some(CategoryId(1)),
             attributes: vec![],
             slug: CategorySlug("2".to_string()),
+            products_count: None,
         };
         let cat_1 = Category {
             id: CategoryId(1),
@@ -785,6 +2050,7 @@ pub mod tests {
             parent_id: Some(CategoryId(0)),
             attributes: vec![],
             slug: CategorySlug("1".to_string()),
+            products_count: None,
         };
         Category {
             id: CategoryId(0),
@@ -796,6 +2062,7 @@ pub mod tests {
             parent_id: None,
             attributes: vec![],
             slug: CategorySlug("0".to_string()),
+            products_count: None,
         }
     }
 
@@ -882,9 +2149,39 @@ pub mod tests {
                 base_product_id,
                 comments: "comments".to_string(),
                 created_at: SystemTime::now(),
+                moderator_name: Some("Moderator".to_string()),
             }))
         }
 
+        fn list_by_base_product_id(
+            &self,
+            base_product_id: BaseProductId,
+            _skip: i64,
+            count: i64,
+        ) -> RepoResult<Vec<ModeratorProductComments>> {
+            Ok((0..count)
+                .map(|index| ModeratorProductComments {
+                    id: index as i32 + 1,
+                    moderator_id: UserId(1),
+                    base_product_id,
+                    comments: "comments".to_string(),
+                    created_at: SystemTime::now(),
+                    moderator_name: Some("Moderator".to_string()),
+                })
+                .collect())
+        }
+
+        fn search(&self, terms: ModeratorCommentsSearchTerms, _count: i64) -> RepoResult<Vec<ModeratorProductComments>> {
+            Ok(vec![ModeratorProductComments {
+                id: 1,
+                moderator_id: UserId(1),
+                base_product_id: MOCK_BASE_PRODUCT_ID,
+                comments: terms.text.unwrap_or_default(),
+                created_at: SystemTime::now(),
+                moderator_name: terms.author,
+            }])
+        }
+
         /// Creates new comment
         fn create(&self, payload: NewModeratorProductComments) -> RepoResult<ModeratorProductComments> {
             Ok(ModeratorProductComments {
@@ -893,6 +2190,7 @@ pub mod tests {
                 base_product_id: payload.base_product_id,
                 comments: payload.comments,
                 created_at: SystemTime::now(),
+                moderator_name: payload.moderator_name,
             })
         }
     }
@@ -908,9 +2206,34 @@ pub mod tests {
                 store_id,
                 comments: "comments".to_string(),
                 created_at: SystemTime::now(),
+                moderator_name: Some("Moderator".to_string()),
             }))
         }
 
+        fn list_by_store_id(&self, store_id: StoreId, _skip: i64, count: i64) -> RepoResult<Vec<ModeratorStoreComments>> {
+            Ok((0..count)
+                .map(|index| ModeratorStoreComments {
+                    id: index as i32 + 1,
+                    moderator_id: UserId(1),
+                    store_id,
+                    comments: "comments".to_string(),
+                    created_at: SystemTime::now(),
+                    moderator_name: Some("Moderator".to_string()),
+                })
+                .collect())
+        }
+
+        fn search(&self, terms: ModeratorCommentsSearchTerms, _count: i64) -> RepoResult<Vec<ModeratorStoreComments>> {
+            Ok(vec![ModeratorStoreComments {
+                id: 1,
+                moderator_id: UserId(1),
+                store_id: MOCK_STORE_ID,
+                comments: terms.text.unwrap_or_default(),
+                created_at: SystemTime::now(),
+                moderator_name: terms.author,
+            }])
+        }
+
         /// Creates new comment
         fn create(&self, payload: NewModeratorStoreComments) -> RepoResult<ModeratorStoreComments> {
             Ok(ModeratorStoreComments {
@@ -919,6 +2242,7 @@ pub mod tests {
                 store_id: payload.store_id,
                 comments: payload.comments,
                 created_at: SystemTime::now(),
+                moderator_name: payload.moderator_name,
             })
         }
     }
@@ -929,18 +2253,12 @@ pub mod tests {
     impl WizardStoresRepo for WizardStoresRepoMock {
         /// Find specific store by user ID
         fn find_by_user_id(&self, user_id: UserId) -> RepoResult<Option<WizardStore>> {
-            Ok(Some(WizardStore {
-                user_id,
-                ..Default::default()
-            }))
+            Ok(Some(create_wizard_store(user_id)))
         }
 
         /// Creates new wizard store
         fn create(&self, user_id: UserId) -> RepoResult<WizardStore> {
-            Ok(WizardStore {
-                user_id,
-                ..Default::default()
-            })
+            Ok(create_wizard_store(user_id))
         }
 
         /// Updates specific wizard store
@@ -965,15 +2283,15 @@ pub mod tests {
                 street_number: payload.street_number,
                 place_id: payload.place_id,
                 completed: false,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                reminded_at: None,
             })
         }
 
         /// Delete specific wizard store
         fn delete(&self, user_id: UserId) -> RepoResult<WizardStore> {
-            Ok(WizardStore {
-                user_id,
-                ..Default::default()
-            })
+            Ok(create_wizard_store(user_id))
         }
 
         fn delete_by_store(&self, _store_id_arg: StoreId) -> RepoResult<()> {
@@ -987,6 +2305,23 @@ pub mod tests {
                 Ok(true)
             }
         }
+
+        fn mark_reminded(&self, _untouched_since: SystemTime, _limit: i64) -> RepoResult<Vec<WizardStore>> {
+            Ok(vec![create_wizard_store(MOCK_USER_ID)])
+        }
+
+        fn archive_untouched(&self, _untouched_since: SystemTime, _limit: i64) -> RepoResult<Vec<WizardStore>> {
+            Ok(vec![create_wizard_store(MOCK_USER_ID)])
+        }
+
+        fn stats(&self) -> RepoResult<WizardStoresStats> {
+            Ok(WizardStoresStats {
+                completed: 3,
+                in_progress: 2,
+                reminded: 1,
+                archived: 1,
+            })
+        }
     }
 
     #[derive(Clone, Default)]
@@ -1024,7 +2359,7 @@ pub mod tests {
     #[derive(Clone, Default)]
     pub struct BaseProductsRepoMock;
 
-    impl BaseProductsRepo for BaseProductsRepoMock {
+    impl BaseProductsReadRepo for BaseProductsRepoMock {
         /// Get base_product count
         fn count(&self, _visibility: Visibility) -> RepoResult<i64> {
             Ok(1)
@@ -1040,36 +2375,6 @@ pub mod tests {
             Ok(None)
         }
 
-        /// Update views on specific base_product by slug
-        fn update_views_by_slug(&self, store_id: StoreId, base_product_slug: BaseProductSlug) -> RepoResult<Option<BaseProduct>> {
-            Ok(Some(BaseProduct {
-                id: MOCK_BASE_PRODUCT_ID,
-                is_active: true,
-                store_id: store_id,
-                name: serde_json::from_str("{}").unwrap(),
-                short_description: serde_json::from_str("{}").unwrap(),
-                long_description: None,
-                seo_title: None,
-                seo_description: None,
-                currency: Currency::STQ,
-                category_id: CategoryId(1),
-                views: 1,
-                created_at: SystemTime::now(),
-                updated_at: SystemTime::now(),
-                rating: 0f64,
-                slug: base_product_slug,
-                status: ModerationStatus::Published,
-                kafka_update_no: 0,
-                uuid: uuid::Uuid::new_v4(),
-                length_cm: Some(60),
-                width_cm: Some(40),
-                height_cm: Some(20),
-                volume_cubic_cm: Some(48000),
-                weight_g: Some(100),
-                store_status: ModerationStatus::Published,
-            }))
-        }
-
         /// Find specific base_product by ID
         fn find(&self, base_product_id: BaseProductId, _visibility: Visibility) -> RepoResult<Option<BaseProduct>> {
             Ok(Some(BaseProduct {
@@ -1097,6 +2402,12 @@ pub mod tests {
                 volume_cubic_cm: Some(48000),
                 weight_g: Some(100),
                 store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
             }))
         }
 
@@ -1129,6 +2440,12 @@ pub mod tests {
                     volume_cubic_cm: Some(48000),
                     weight_g: Some(100),
                     store_status: ModerationStatus::Published,
+                    saga_id: None,
+                    published_at: Some(SystemTime::now()),
+                    min_price: None,
+                    max_price: None,
+                    store_is_on_vacation: false,
+                    dimensions_inherited: false,
                 };
 
                 result.push(val);
@@ -1151,9 +2468,13 @@ pub mod tests {
         }
 
         /// Returns list of base_products, limited by `from` and `count` parameters
-        fn list(&self, from: BaseProductId, count: i32, _visibility: Visibility) -> RepoResult<Vec<BaseProduct>> {
+        fn list(&self, from: BaseProductsListFrom, count: i32, _visibility: Visibility) -> RepoResult<Vec<BaseProduct>> {
+            let from = match from {
+                BaseProductsListFrom::Id(from) => from.0,
+                BaseProductsListFrom::Cursor(cursor) => cursor.map(|cursor| cursor.id.0 + 1).unwrap_or(1),
+            };
             let mut base_products = vec![];
-            for i in from.0..(from.0 + count) {
+            for i in from..(from + count) {
                 let base_product = BaseProduct {
                     id: BaseProductId(i),
                     is_active: true,
@@ -1179,6 +2500,12 @@ pub mod tests {
                     volume_cubic_cm: Some(48000),
                     weight_g: Some(100),
                     store_status: ModerationStatus::Published,
+                    saga_id: None,
+                    published_at: Some(SystemTime::now()),
+                    min_price: None,
+                    max_price: None,
+                    store_is_on_vacation: false,
+                    dimensions_inherited: false,
                 };
                 base_products.push(base_product);
             }
@@ -1192,13 +2519,18 @@ pub mod tests {
             skip_base_product_id: Option<BaseProductId>,
             from: BaseProductId,
             count: i32,
-            _visibility: Visibility,
+            visibility: Visibility,
         ) -> RepoResult<Vec<BaseProduct>> {
             let mut base_products = vec![];
-            for i in (skip_base_product_id.unwrap().0 + from.0)..(skip_base_product_id.unwrap().0 + from.0 + count) {
+            let skip = skip_base_product_id.map(|id| id.0).unwrap_or(0);
+            let is_active = match visibility {
+                Visibility::Deactivated => false,
+                Visibility::Active | Visibility::Published => true,
+            };
+            for i in (skip + from.0)..(skip + from.0 + count) {
                 let base_product = BaseProduct {
                     id: BaseProductId(i),
-                    is_active: true,
+                    is_active,
                     store_id,
                     name: serde_json::from_str("{}").unwrap(),
                     short_description: serde_json::from_str("{}").unwrap(),
@@ -1221,6 +2553,12 @@ pub mod tests {
                     volume_cubic_cm: Some(48000),
                     weight_g: Some(100),
                     store_status: ModerationStatus::Published,
+                    saga_id: None,
+                    published_at: Some(SystemTime::now()),
+                    min_price: None,
+                    max_price: None,
+                    store_is_on_vacation: false,
+                    dimensions_inherited: false,
                 };
                 base_products.push(base_product);
             }
@@ -1236,6 +2574,115 @@ pub mod tests {
             Ok(false)
         }
 
+        fn most_viewed(&self, _prod: MostViewedProducts, _count: i32, _offset: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+            Ok(vec![])
+        }
+
+        fn most_discount(&self, _prod: MostDiscountProducts, _count: i32, _offset: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+            Ok(vec![])
+        }
+
+        fn convert_from_elastic(&self, _el_products: Vec<ElasticProduct>) -> RepoResult<Vec<BaseProductWithVariants>> {
+            Ok(vec![])
+        }
+
+        fn moderator_search(
+            &self,
+            _pagination_params: PaginationParams<BaseProductId>,
+            _term: ModeratorBaseProductSearchTerms,
+        ) -> RepoResult<ModeratorBaseProductSearchResults> {
+            Ok(ModeratorBaseProductSearchResults {
+                total_count: 0,
+                base_products: vec![],
+            })
+        }
+
+        fn get_all_catalog(&self) -> RepoResult<Vec<CatalogWithAttributes>> {
+            Ok(vec![])
+        }
+
+        fn find_many_with_variants(&self, base_product_ids: Vec<BaseProductId>) -> RepoResult<Vec<CatalogWithAttributes>> {
+            let base_products = self.find_many(base_product_ids)?;
+            Ok(base_products
+                .into_iter()
+                .map(|base_product| {
+                    let variant = create_product(ProductId(base_product.id.0), base_product.id);
+                    CatalogWithAttributes::new(base_product, vec![ProductWithAttributes::new(variant, vec![])])
+                })
+                .collect())
+        }
+
+        fn sample(&self, _categories_ids: Option<Vec<CategoryId>>, count: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+            (1..count + 1)
+                .map(|id| {
+                    let base_product = self.find(BaseProductId(id), Visibility::Published)?.unwrap();
+                    Ok(BaseProductWithVariants::new(base_product, vec![]))
+                })
+                .collect()
+        }
+
+        fn find_by_attribute_fingerprint(
+            &self,
+            _category_id: CategoryId,
+            _fingerprint: AttributeFingerprint,
+        ) -> RepoResult<Vec<CatalogWithAttributes>> {
+            Ok(vec![])
+        }
+
+        fn find_related(&self, base_product_id: BaseProductId, count: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
+            (1..count + 2)
+                .filter(|id| *id != base_product_id.0)
+                .take(count as usize)
+                .map(|id| {
+                    let base_product = self.find(BaseProductId(id), Visibility::Published)?.unwrap();
+                    Ok(BaseProductWithVariants::new(base_product, vec![]))
+                })
+                .collect()
+        }
+    }
+
+    impl BaseProductsWriteRepo for BaseProductsRepoMock {
+        /// Update views on specific base_product by slug
+        fn update_views_by_slug(
+            &self,
+            store_id: StoreId,
+            base_product_slug: BaseProductSlug,
+            _viewer_hash: Option<String>,
+        ) -> RepoResult<Option<BaseProduct>> {
+            Ok(Some(BaseProduct {
+                id: MOCK_BASE_PRODUCT_ID,
+                is_active: true,
+                store_id: store_id,
+                name: serde_json::from_str("{}").unwrap(),
+                short_description: serde_json::from_str("{}").unwrap(),
+                long_description: None,
+                seo_title: None,
+                seo_description: None,
+                currency: Currency::STQ,
+                category_id: CategoryId(1),
+                views: 1,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                rating: 0f64,
+                slug: base_product_slug,
+                status: ModerationStatus::Published,
+                kafka_update_no: 0,
+                uuid: uuid::Uuid::new_v4(),
+                length_cm: Some(60),
+                width_cm: Some(40),
+                height_cm: Some(20),
+                volume_cubic_cm: Some(48000),
+                weight_g: Some(100),
+                store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
+            }))
+        }
+
         /// Creates new base_product
         fn create(&self, payload: NewBaseProduct) -> RepoResult<BaseProduct> {
             Ok(BaseProduct {
@@ -1257,29 +2704,146 @@ pub mod tests {
                 status: ModerationStatus::Published,
                 kafka_update_no: 0,
                 uuid: uuid::Uuid::new_v4(),
-                length_cm: payload.length_cm.clone(),
-                width_cm: payload.width_cm.clone(),
-                height_cm: payload.height_cm.clone(),
-                volume_cubic_cm: match (payload.length_cm, payload.width_cm, payload.height_cm) {
-                    (Some(l), Some(w), Some(h)) => Some(l * w * h),
-                    _ => None,
-                },
-                weight_g: payload.weight_g,
+                length_cm: payload.length_cm.clone(),
+                width_cm: payload.width_cm.clone(),
+                height_cm: payload.height_cm.clone(),
+                volume_cubic_cm: match (payload.length_cm, payload.width_cm, payload.height_cm) {
+                    (Some(l), Some(w), Some(h)) => Some(l * w * h),
+                    _ => None,
+                },
+                weight_g: payload.weight_g,
+                store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
+            })
+        }
+
+        /// Updates specific base_product
+        fn update(&self, base_product_id: BaseProductId, payload: UpdateBaseProduct) -> RepoResult<BaseProduct> {
+            Ok(BaseProduct {
+                id: base_product_id,
+                is_active: true,
+                store_id: StoreId(1),
+                name: serde_json::from_str("{}").unwrap(),
+                short_description: serde_json::from_str("{}").unwrap(),
+                long_description: payload.long_description,
+                seo_title: payload.seo_title,
+                seo_description: payload.seo_description,
+                currency: Currency::STQ,
+                category_id: CategoryId(3),
+                views: 1,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                rating: 0f64,
+                slug: BaseProductSlug("slug".to_string()),
+                status: ModerationStatus::Published,
+                kafka_update_no: 0,
+                uuid: uuid::Uuid::new_v4(),
+                length_cm: payload.length_cm.clone(),
+                width_cm: payload.width_cm.clone(),
+                height_cm: payload.height_cm.clone(),
+                volume_cubic_cm: match (payload.length_cm, payload.width_cm, payload.height_cm) {
+                    (Some(l), Some(w), Some(h)) => Some(l * w * h),
+                    _ => None,
+                },
+                weight_g: payload.weight_g,
+                store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
+            })
+        }
+
+        /// Update views on specific base_product
+        fn update_views(&self, base_product_id_arg: BaseProductId, _viewer_hash: Option<String>) -> RepoResult<Option<BaseProduct>> {
+            Ok(Some(BaseProduct {
+                id: base_product_id_arg,
+                is_active: true,
+                store_id: StoreId(1),
+                name: serde_json::from_str("{}").unwrap(),
+                short_description: serde_json::from_str("{}").unwrap(),
+                long_description: None,
+                seo_title: None,
+                seo_description: None,
+                currency: Currency::STQ,
+                category_id: CategoryId(3),
+                views: 100,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                rating: 0f64,
+                slug: BaseProductSlug("slug".to_string()),
+                status: ModerationStatus::Published,
+                kafka_update_no: 0,
+                uuid: uuid::Uuid::new_v4(),
+                length_cm: Some(60),
+                width_cm: Some(40),
+                height_cm: Some(20),
+                volume_cubic_cm: Some(48000),
+                weight_g: Some(100),
+                store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
+            }))
+        }
+
+        /// Deactivates specific base_product
+        fn deactivate(&self, base_product_id: BaseProductId) -> RepoResult<BaseProduct> {
+            Ok(BaseProduct {
+                id: base_product_id,
+                is_active: false,
+                store_id: StoreId(1),
+                name: serde_json::from_str("{}").unwrap(),
+                short_description: serde_json::from_str("{}").unwrap(),
+                long_description: None,
+                seo_title: None,
+                seo_description: None,
+                currency: Currency::STQ,
+                category_id: CategoryId(3),
+                views: 1,
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                rating: 0f64,
+                slug: BaseProductSlug("slug".to_string()),
+                status: ModerationStatus::Published,
+                kafka_update_no: 0,
+                uuid: uuid::Uuid::new_v4(),
+                length_cm: Some(60),
+                width_cm: Some(40),
+                height_cm: Some(20),
+                volume_cubic_cm: Some(48000),
+                weight_g: Some(100),
                 store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
             })
         }
 
-        /// Updates specific base_product
-        fn update(&self, base_product_id: BaseProductId, payload: UpdateBaseProduct) -> RepoResult<BaseProduct> {
+        /// Activates deactivated base_product
+        fn activate(&self, base_product_id: BaseProductId) -> RepoResult<BaseProduct> {
             Ok(BaseProduct {
                 id: base_product_id,
                 is_active: true,
                 store_id: StoreId(1),
                 name: serde_json::from_str("{}").unwrap(),
                 short_description: serde_json::from_str("{}").unwrap(),
-                long_description: payload.long_description,
-                seo_title: payload.seo_title,
-                seo_description: payload.seo_description,
+                long_description: None,
+                seo_title: None,
+                seo_description: None,
                 currency: Currency::STQ,
                 category_id: CategoryId(3),
                 views: 1,
@@ -1290,22 +2854,25 @@ pub mod tests {
                 status: ModerationStatus::Published,
                 kafka_update_no: 0,
                 uuid: uuid::Uuid::new_v4(),
-                length_cm: payload.length_cm.clone(),
-                width_cm: payload.width_cm.clone(),
-                height_cm: payload.height_cm.clone(),
-                volume_cubic_cm: match (payload.length_cm, payload.width_cm, payload.height_cm) {
-                    (Some(l), Some(w), Some(h)) => Some(l * w * h),
-                    _ => None,
-                },
-                weight_g: payload.weight_g,
+                length_cm: Some(60),
+                width_cm: Some(40),
+                height_cm: Some(20),
+                volume_cubic_cm: Some(48000),
+                weight_g: Some(100),
                 store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
             })
         }
 
-        /// Update views on specific base_product
-        fn update_views(&self, base_product_id_arg: BaseProductId) -> RepoResult<Option<BaseProduct>> {
-            Ok(Some(BaseProduct {
-                id: base_product_id_arg,
+        /// Shows or hides base_product
+        fn set_published(&self, base_product_id: BaseProductId, published: bool) -> RepoResult<BaseProduct> {
+            Ok(BaseProduct {
+                id: base_product_id,
                 is_active: true,
                 store_id: StoreId(1),
                 name: serde_json::from_str("{}").unwrap(),
@@ -1315,7 +2882,7 @@ pub mod tests {
                 seo_description: None,
                 currency: Currency::STQ,
                 category_id: CategoryId(3),
-                views: 100,
+                views: 1,
                 created_at: SystemTime::now(),
                 updated_at: SystemTime::now(),
                 rating: 0f64,
@@ -1329,15 +2896,20 @@ pub mod tests {
                 volume_cubic_cm: Some(48000),
                 weight_g: Some(100),
                 store_status: ModerationStatus::Published,
-            }))
+                saga_id: None,
+                published_at: if published { Some(SystemTime::now()) } else { None },
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
+            })
         }
 
-        /// Deactivates specific base_product
-        fn deactivate(&self, base_product_id: BaseProductId) -> RepoResult<BaseProduct> {
+        fn set_store(&self, base_product_id: BaseProductId, store: &Store) -> RepoResult<BaseProduct> {
             Ok(BaseProduct {
                 id: base_product_id,
-                is_active: false,
-                store_id: StoreId(1),
+                is_active: true,
+                store_id: store.id,
                 name: serde_json::from_str("{}").unwrap(),
                 short_description: serde_json::from_str("{}").unwrap(),
                 long_description: None,
@@ -1358,7 +2930,13 @@ pub mod tests {
                 height_cm: Some(20),
                 volume_cubic_cm: Some(48000),
                 weight_g: Some(100),
-                store_status: ModerationStatus::Published,
+                store_status: store.status,
+                saga_id: None,
+                published_at: None,
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: store.is_on_vacation,
+                dimensions_inherited: false,
             })
         }
 
@@ -1388,30 +2966,25 @@ pub mod tests {
                 volume_cubic_cm: Some(48000),
                 weight_g: Some(100),
                 store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
             }])
         }
 
-        fn most_viewed(&self, _prod: MostViewedProducts, _count: i32, _offset: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
-            Ok(vec![])
-        }
-
-        fn most_discount(&self, _prod: MostDiscountProducts, _count: i32, _offset: i32) -> RepoResult<Vec<BaseProductWithVariants>> {
-            Ok(vec![])
-        }
-
-        fn convert_from_elastic(&self, _el_products: Vec<ElasticProduct>) -> RepoResult<Vec<BaseProductWithVariants>> {
-            Ok(vec![])
-        }
-
-        fn moderator_search(
-            &self,
-            _pagination_params: PaginationParams<BaseProductId>,
-            _term: ModeratorBaseProductSearchTerms,
-        ) -> RepoResult<ModeratorBaseProductSearchResults> {
-            Ok(ModeratorBaseProductSearchResults {
-                total_count: 0,
-                base_products: vec![],
-            })
+        fn deactivate_by_saga_id(&self, saga_id: SagaId) -> RepoResult<Vec<BaseProduct>> {
+            let base_products = self
+                .deactivate_by_store(MOCK_STORE_ID)?
+                .into_iter()
+                .map(|mut base_product| {
+                    base_product.saga_id = Some(saga_id);
+                    base_product
+                })
+                .collect();
+            Ok(base_products)
         }
 
         fn set_moderation_statuses(
@@ -1448,6 +3021,12 @@ pub mod tests {
                 volume_cubic_cm: Some(48000),
                 weight_g: Some(100),
                 store_status: ModerationStatus::Published,
+                saga_id: None,
+                published_at: Some(SystemTime::now()),
+                min_price: None,
+                max_price: None,
+                store_is_on_vacation: false,
+                dimensions_inherited: false,
             })
         }
 
@@ -1466,10 +3045,6 @@ pub mod tests {
         fn replace_category(&self, _payload: CategoryReplacePayload) -> RepoResult<Vec<BaseProduct>> {
             Ok(vec![])
         }
-
-        fn get_all_catalog(&self) -> RepoResult<Vec<CatalogWithAttributes>> {
-            Ok(vec![])
-        }
     }
 
     #[derive(Clone, Default)]
@@ -1633,12 +3208,14 @@ pub mod tests {
         fn get_user_ids_by_role(&self, _role_name: StoresRole) -> RepoResult<HashSet<UserId>> {
             Ok(HashSet::new())
         }
+
+        fn invalidate_cache(&self, _user_id_arg: UserId) {}
     }
 
     #[derive(Clone, Default)]
     pub struct StoresRepoMock;
 
-    impl StoresRepo for StoresRepoMock {
+    impl StoresReadRepo for StoresRepoMock {
         fn count(&self, _visibility: Visibility) -> RepoResult<i64> {
             Ok(1)
         }
@@ -1648,7 +3225,18 @@ pub mod tests {
             Ok(Some(store))
         }
 
-        fn find_by_slug(&self, _store_slug: StoreSlug, _visibility: Visibility) -> RepoResult<Option<Store>> {
+        fn find_by_slug(&self, store_slug: StoreSlug, _visibility: Visibility) -> RepoResult<Option<Store>> {
+            if store_slug.0 == MOCK_OLD_STORE_SLUG {
+                return Ok(None);
+            }
+            let store = create_store(MOCK_STORE_ID, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            Ok(Some(store))
+        }
+
+        fn find_by_old_slug(&self, store_slug: StoreSlug, _visibility: Visibility) -> RepoResult<Option<Store>> {
+            if store_slug.0 != MOCK_OLD_STORE_SLUG {
+                return Ok(None);
+            }
             let store = create_store(MOCK_STORE_ID, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
             Ok(Some(store))
         }
@@ -1665,6 +3253,10 @@ pub mod tests {
             Ok(Some(false))
         }
 
+        fn gtin_exists(&self, _store_id: StoreId, gtin: &str) -> RepoResult<Option<bool>> {
+            Ok(Some(gtin == MOCK_GTIN))
+        }
+
         fn list(&self, from: StoreId, count: i32, _visibility: Visibility) -> RepoResult<Vec<Store>> {
             let mut stores = vec![];
             for i in from.0..(from.0 + count) {
@@ -1678,6 +3270,46 @@ pub mod tests {
             self.list(StoreId(1), 10, visibility)
         }
 
+        fn find_many(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<StoreWithProductsCount>> {
+            Ok(store_ids
+                .into_iter()
+                .map(|store_id| StoreWithProductsCount {
+                    store: create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap()),
+                    products_count: 1,
+                })
+                .collect())
+        }
+
+        fn find_by_saga_id(&self, saga_id: SagaId) -> RepoResult<Option<Store>> {
+            let mut store = create_store(StoreId(1), serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            store.saga_id = Some(saga_id);
+            Ok(Some(store))
+        }
+
+        fn get_by_user(&self, _user_id_arg: UserId) -> RepoResult<Option<Store>> {
+            Ok(None)
+        }
+
+        fn moderator_search(
+            &self,
+            pagination_params: PaginationParams<StoreId>,
+            _term: ModeratorStoreSearchTerms,
+        ) -> RepoResult<ModeratorStoreSearchResults> {
+            let mut stores = vec![];
+            let PaginationParams { limit, skip, start, .. } = pagination_params;
+            let from_id = start.unwrap_or(StoreId(1));
+            for i in (from_id.0..).skip(skip as usize).take(limit as usize) {
+                let store = create_store(StoreId(i), serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+                stores.push(store);
+            }
+            Ok(ModeratorStoreSearchResults {
+                total_count: stores.len() as u32,
+                stores,
+            })
+        }
+    }
+
+    impl StoresWriteRepo for StoresRepoMock {
         fn create(&self, payload: NewStore) -> RepoResult<Store> {
             let store = create_store(StoreId(1), payload.name);
             Ok(store)
@@ -1693,6 +3325,10 @@ pub mod tests {
             Ok(store)
         }
 
+        fn find_for_update(&self, store_id: StoreId) -> RepoResult<Option<Store>> {
+            Ok(Some(create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap())))
+        }
+
         fn update_service_fields(&self, store_id_arg: StoreId, _payload: ServiceUpdateStore) -> RepoResult<Store> {
             let store = create_store(store_id_arg, serde_json::from_str("{}").unwrap());
 
@@ -1715,38 +3351,73 @@ pub mod tests {
             Ok(None)
         }
 
-        fn get_by_user(&self, _user_id_arg: UserId) -> RepoResult<Option<Store>> {
-            Ok(None)
-        }
-
         fn delete(&self, _store_id_arg: StoreId) -> RepoResult<()> {
             Ok(())
         }
 
-        fn moderator_search(
-            &self,
-            pagination_params: PaginationParams<StoreId>,
-            _term: ModeratorStoreSearchTerms,
-        ) -> RepoResult<ModeratorStoreSearchResults> {
-            let mut stores = vec![];
-            let PaginationParams { limit, skip, start, .. } = pagination_params;
-            let from_id = start.unwrap_or(StoreId(1));
-            for i in (from_id.0..).skip(skip as usize).take(limit as usize) {
-                let store = create_store(StoreId(i), serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
-                stores.push(store);
-            }
-            Ok(ModeratorStoreSearchResults {
-                total_count: stores.len() as u32,
-                stores,
-            })
-        }
         fn set_moderation_status(&self, store_id_arg: StoreId, _status_arg: ModerationStatus) -> RepoResult<Store> {
             let store = create_store(store_id_arg, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
             Ok(store)
         }
+
+        fn set_quota_plan(&self, store_id: StoreId, plan: Option<String>) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            store.quota_plan = plan;
+            Ok(store)
+        }
+
+        fn set_contact_verified(&self, store_id: StoreId, channel: VerificationChannel) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            match channel {
+                VerificationChannel::Email => store.email_verified = true,
+                VerificationChannel::Phone => store.phone_verified = true,
+            }
+            Ok(store)
+        }
+
+        fn set_onboarding(&self, store_id: StoreId, payload: SetStoreOnboarding) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            if let Some(payout_info_present) = payload.payout_info_present {
+                store.payout_info_present = payout_info_present;
+            }
+            if let Some(shipping_profile_set) = payload.shipping_profile_set {
+                store.shipping_profile_set = shipping_profile_set;
+            }
+            Ok(store)
+        }
+
+        fn set_franchise(&self, store_id: StoreId, franchise: bool) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            store.franchise = franchise;
+            Ok(store)
+        }
+
+        fn set_vacation(&self, store_id: StoreId, is_on_vacation: bool, vacation_message: Option<String>) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            store.is_on_vacation = is_on_vacation;
+            store.vacation_message = vacation_message;
+            Ok(store)
+        }
+
+        fn set_verification(&self, store_id: StoreId, payload: VerifyStore) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            store.verification_status = payload.verification_status;
+            store.badges = serde_json::to_value(payload.badges)?;
+            Ok(store)
+        }
+
+        fn set_owner(&self, store_id: StoreId, user_id: UserId) -> RepoResult<Store> {
+            let mut store = create_store(store_id, serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+            store.user_id = user_id;
+            Ok(store)
+        }
+
+        fn mark_products_milestone(&self, _store_id: StoreId, _published_products: i32) -> RepoResult<bool> {
+            Ok(true)
+        }
     }
 
-    fn create_store(id: StoreId, name: serde_json::Value) -> Store {
+    pub fn create_store(id: StoreId, name: serde_json::Value) -> Store {
         Store {
             id,
             user_id: UserId(1),
@@ -1783,6 +3454,24 @@ pub mod tests {
             place_id: None,
             kafka_update_no: 0,
             uuid: uuid::Uuid::new_v4(),
+            quota_plan: None,
+            email_verified: false,
+            phone_verified: false,
+            latitude: None,
+            longitude: None,
+            payout_info_present: false,
+            shipping_profile_set: false,
+            franchise: false,
+            is_on_vacation: false,
+            vacation_message: None,
+            opening_hours: None,
+            timezone: None,
+            default_length_cm: None,
+            default_width_cm: None,
+            default_height_cm: None,
+            default_weight_g: None,
+            verification_status: StoreVerificationStatus::Unverified,
+            badges: json!([]),
         }
     }
 
@@ -1805,6 +3494,8 @@ pub mod tests {
             slogan: Some("fdsf".to_string()),
             country: None,
             country_code: None,
+            latitude: None,
+            longitude: None,
             administrative_area_level_1: None,
             administrative_area_level_2: None,
             locality: None,
@@ -1814,6 +3505,12 @@ pub mod tests {
             saga_id: None,
             street_number: None,
             place_id: None,
+            opening_hours: None,
+            timezone: None,
+            default_length_cm: None,
+            default_width_cm: None,
+            default_height_cm: None,
+            default_weight_g: None,
             uuid: uuid::Uuid::new_v4(),
         }
     }
@@ -1836,6 +3533,8 @@ pub mod tests {
             slogan: None,
             country: None,
             country_code: None,
+            latitude: None,
+            longitude: None,
             administrative_area_level_1: None,
             administrative_area_level_2: None,
             locality: None,
@@ -1844,13 +3543,19 @@ pub mod tests {
             route: None,
             street_number: None,
             place_id: None,
+            opening_hours: None,
+            timezone: None,
+            default_length_cm: None,
+            default_width_cm: None,
+            default_height_cm: None,
+            default_weight_g: None,
         }
     }
 
     #[derive(Clone, Default)]
     pub struct ProductsRepoMock;
 
-    impl ProductsRepo for ProductsRepoMock {
+    impl ProductsReadRepo for ProductsRepoMock {
         fn find(&self, product_id: ProductId) -> RepoResult<Option<RawProduct>> {
             let product = create_product(product_id, MOCK_BASE_PRODUCT_ID);
             Ok(Some(product))
@@ -1877,6 +3582,29 @@ pub mod tests {
             Ok(products)
         }
 
+        fn find_by_vendor_code(&self, _store_id: StoreId, code: &str) -> RepoResult<Option<RawProduct>> {
+            let product = create_product(MOCK_PRODUCT_ID, MOCK_BASE_PRODUCT_ID);
+            Ok(Some(product).filter(|product| product.vendor_code == code))
+        }
+
+        fn find_by_gtin(&self, gtin: &str) -> RepoResult<Vec<RawProduct>> {
+            let mut product = create_product(MOCK_PRODUCT_ID, MOCK_BASE_PRODUCT_ID);
+            product.gtin = Some(MOCK_GTIN.to_string());
+            Ok(vec![product].into_iter().filter(|product| product.gtin.as_ref().map(|g| g.as_str()) == Some(gtin)).collect())
+        }
+
+        fn find_low_stock(&self, _store_id: StoreId, threshold: i32) -> RepoResult<Vec<LowStockProduct>> {
+            let product = create_product(MOCK_PRODUCT_ID, MOCK_BASE_PRODUCT_ID);
+            let low_stock = LowStockProduct {
+                product_id: product.id,
+                base_product_id: product.base_product_id,
+                vendor_code: product.vendor_code,
+                quantity: product.quantity.unwrap_or_default(),
+                base_product_name: serde_json::from_str(MOCK_BASE_PRODUCT_NAME_JSON).unwrap(),
+            };
+            Ok(vec![low_stock].into_iter().filter(|low_stock| low_stock.quantity < threshold).collect())
+        }
+
         fn list(&self, from: i32, count: i32) -> RepoResult<Vec<RawProduct>> {
             let mut products = vec![];
             for i in from..(from + count) {
@@ -1886,6 +3614,21 @@ pub mod tests {
             Ok(products)
         }
 
+        fn find_many(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<RawProduct>> {
+            let mut products = vec![];
+            for id in product_ids {
+                let product = create_product(id, MOCK_BASE_PRODUCT_ID);
+                products.push(product);
+            }
+            Ok(products)
+        }
+
+        fn find_photos(&self, product_id: ProductId) -> RepoResult<Vec<ProductPhoto>> {
+            Ok(create_product_photos(product_id, vec!["photo-1".to_string(), "photo-2".to_string()]))
+        }
+    }
+
+    impl ProductsWriteRepo for ProductsRepoMock {
         fn create(&self, payload: NewProduct) -> RepoResult<RawProduct> {
             if let Some(base_product_id) = payload.base_product_id {
                 return Ok(create_product(MOCK_PRODUCT_ID, base_product_id));
@@ -1906,24 +3649,80 @@ pub mod tests {
             Ok(product)
         }
 
+        fn find_for_update(&self, product_id: ProductId) -> RepoResult<Option<RawProduct>> {
+            Ok(Some(create_product(product_id, MOCK_BASE_PRODUCT_ID)))
+        }
+
+        fn decrement_quantity(&self, product_id: ProductId, quantity: i32) -> RepoResult<Option<RawProduct>> {
+            let mut product = create_product(product_id, MOCK_BASE_PRODUCT_ID);
+            match product.quantity {
+                Some(in_stock) if in_stock < quantity => return Ok(None),
+                Some(in_stock) => product.quantity = Some(in_stock - quantity),
+                None => {}
+            }
+            Ok(Some(product))
+        }
+
         fn deactivate_by_base_product(&self, base_product_id: BaseProductId) -> RepoResult<Vec<RawProduct>> {
             let mut product = create_product(MOCK_PRODUCT_ID, base_product_id);
             product.is_active = false;
             Ok(vec![product])
         }
 
+        fn activate_by_base_product(&self, base_product_id: BaseProductId) -> RepoResult<Vec<RawProduct>> {
+            Ok(vec![create_product(MOCK_PRODUCT_ID, base_product_id)])
+        }
+
         fn update_currency(&self, _currency_arg: Currency, _base_product_id_arg: BaseProductId) -> RepoResult<usize> {
             Ok(1)
         }
 
-        fn find_many(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<RawProduct>> {
-            let mut products = vec![];
-            for id in product_ids {
-                let product = create_product(id, MOCK_BASE_PRODUCT_ID);
-                products.push(product);
-            }
+        fn update_prices_by_base_product(
+            &self,
+            base_product_id: BaseProductId,
+            payload: UpdateProductPrices,
+        ) -> RepoResult<Vec<RawProduct>> {
+            let mut product = create_product(MOCK_PRODUCT_ID, base_product_id);
+            product.price = payload.apply(product.price, product.currency);
+            Ok(vec![product])
+        }
+
+        fn set_order(
+            &self,
+            base_product_id: BaseProductId,
+            product_ids: Vec<ProductId>,
+            default_product_id: Option<ProductId>,
+        ) -> RepoResult<Vec<RawProduct>> {
+            let products = product_ids
+                .into_iter()
+                .enumerate()
+                .map(|(position, product_id)| {
+                    let mut product = create_product(product_id, base_product_id);
+                    product.position = position as i32;
+                    product.is_default = Some(product_id) == default_product_id;
+                    product
+                })
+                .collect();
             Ok(products)
         }
+
+        fn set_photos(&self, product_id: ProductId, urls: Vec<String>) -> RepoResult<Vec<ProductPhoto>> {
+            Ok(create_product_photos(product_id, urls))
+        }
+    }
+
+    fn create_product_photos(product_id: ProductId, urls: Vec<String>) -> Vec<ProductPhoto> {
+        NewProductPhoto::from_urls(product_id, urls, 0)
+            .into_iter()
+            .enumerate()
+            .map(|(index, photo)| ProductPhoto {
+                id: index as i32 + 1,
+                product_id: photo.product_id,
+                url: photo.url,
+                position: photo.position,
+                created_at: SystemTime::now(),
+            })
+            .collect()
     }
 
     #[derive(Default)]
@@ -2018,6 +3817,33 @@ pub mod tests {
         }
     }
 
+    pub fn create_wizard_store(user_id: UserId) -> WizardStore {
+        WizardStore {
+            id: 1,
+            user_id,
+            store_id: None,
+            name: None,
+            short_description: None,
+            default_language: None,
+            slug: None,
+            country: None,
+            address: None,
+            administrative_area_level_1: None,
+            administrative_area_level_2: None,
+            locality: None,
+            political: None,
+            postal_code: None,
+            route: None,
+            street_number: None,
+            place_id: None,
+            completed: false,
+            country_code: None,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+            reminded_at: None,
+        }
+    }
+
     pub fn create_product(id: ProductId, base_product_id: BaseProductId) -> RawProduct {
         RawProduct {
             id,
@@ -2036,6 +3862,89 @@ pub mod tests {
             pre_order_days: 0,
             kafka_update_no: 0,
             uuid: uuid::Uuid::new_v4(),
+            quantity: Some(10),
+            position: 0,
+            is_default: false,
+            discount_starts_at: None,
+            discount_ends_at: None,
+            gtin: None,
+            price_updated_at: SystemTime::now(),
+            deactivated_with_base_product: false,
+        }
+    }
+
+    /// Rows written by `create_db_fixture`
+    pub struct DbFixture {
+        pub category: Category,
+        pub store: Store,
+        pub base_product: BaseProduct,
+        pub product: RawProduct,
+    }
+
+    /// Connection to the database of the config, tests using it are ignored
+    /// and write their rows inside `test_transaction`, so nothing is left behind
+    pub fn create_db_connection() -> PgConnection {
+        let config = Config::new().expect("Can't load app config!");
+        PgConnection::establish(&config.server.database).expect("Can't connect to the database")
+    }
+
+    /// Category, store with one base product and one variant, written through the repos
+    pub fn create_db_fixture(conn: &PgConnection) -> DbFixture {
+        let categories_repo = CategoriesRepoImpl::new(
+            conn,
+            Box::new(SystemACL::default()) as Box<RepoAcl<Category>>,
+            Arc::new(CategoryCacheImpl::new(NullCache::new())),
+        );
+        let category = categories_repo
+            .create(NewCategory {
+                name: serde_json::from_str(r##"[{"lang": "en","text": "category"}]"##).unwrap(),
+                parent_id: CategoryId(0),
+                meta_field: None,
+                uuid: uuid::Uuid::new_v4(),
+                slug: None,
+            })
+            .unwrap();
+
+        let stores_repo = StoresRepoImpl::new(conn, Box::new(SystemACL::default()) as Box<RepoAcl<Store>>);
+        let mut new_store = services::stores::tests::create_new_store(serde_json::from_str(MOCK_STORE_NAME_JSON_EXISTED).unwrap());
+        new_store.slug = format!("store-{}", new_store.uuid);
+        let store = stores_repo.create(new_store).unwrap();
+
+        let base_products_repo = BaseProductsRepoImpl::new(conn, Box::new(SystemACL::default()) as Box<RepoAcl<BaseProduct>>);
+        let mut new_base_product = services::base_products::tests::create_new_base_product(MOCK_BASE_PRODUCT_NAME_JSON);
+        new_base_product.store_id = store.id;
+        new_base_product.category_id = category.id;
+        new_base_product.slug = Some(format!("base-product-{}", new_base_product.uuid));
+        let base_product = base_products_repo.create(new_base_product).unwrap();
+
+        let products_repo = ProductsRepoImpl::new(
+            conn,
+            Box::new(SystemACL::default()) as Box<RepoAcl<RawProduct>>,
+            Arc::new(ProductCacheImpl::new(NullCache::new())),
+        );
+        let product = products_repo
+            .create(NewProduct {
+                base_product_id: Some(base_product.id),
+                discount: None,
+                photo_main: None,
+                additional_photos: None,
+                vendor_code: "vendor_code".to_string(),
+                cashback: None,
+                price: ProductPrice(10f64),
+                currency: Currency::STQ,
+                pre_order: Some(false),
+                pre_order_days: Some(0),
+                uuid: uuid::Uuid::new_v4(),
+                quantity: Some(0),
+                gtin: None,
+            })
+            .unwrap();
+
+        DbFixture {
+            category,
+            store,
+            base_product,
+            product,
         }
     }
 }
@@ -0,0 +1,173 @@
+//! Retention repo, hard-deletes long-deactivated products, base products and stores.
+//! Used by the retention job only, which runs with system rights, so there are no ACL checks.
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{BaseProductId, ProductId, StoreId};
+
+use errors::Error;
+use repos::types::RepoResult;
+use schema::base_products::dsl as BaseProducts;
+use schema::coupon_redemptions::dsl as CouponRedemptions;
+use schema::products::dsl as Products;
+use schema::stores::dsl as Stores;
+
+/// Retention repository, responsible for removing deactivated entities
+pub struct RetentionRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+}
+
+pub trait RetentionRepo {
+    /// Returns ids of products deactivated before `deactivated_before`
+    fn find_deactivated_products(&self, deactivated_before: SystemTime) -> RepoResult<Vec<ProductId>>;
+
+    /// Returns ids of products referenced by coupon redemptions
+    fn find_redeemed_products(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<ProductId>>;
+
+    /// Deletes products that are still inactive, returns ids of deleted ones
+    fn delete_products(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<ProductId>>;
+
+    /// Returns ids of base products deactivated before `deactivated_before` that have no variants left
+    fn find_deactivated_base_products(&self, deactivated_before: SystemTime) -> RepoResult<Vec<BaseProductId>>;
+
+    /// Deletes base products that are still inactive, returns ids of deleted ones
+    fn delete_base_products(&self, base_product_ids: Vec<BaseProductId>) -> RepoResult<Vec<BaseProductId>>;
+
+    /// Returns ids of stores deactivated before `deactivated_before` that have no base products left
+    fn find_deactivated_stores(&self, deactivated_before: SystemTime) -> RepoResult<Vec<StoreId>>;
+
+    /// Deletes stores that are still inactive, returns ids of deleted ones
+    fn delete_stores(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<StoreId>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> RetentionRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T) -> Self {
+        Self { db_conn }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> RetentionRepo for RetentionRepoImpl<'a, T> {
+    fn find_deactivated_products(&self, deactivated_before: SystemTime) -> RepoResult<Vec<ProductId>> {
+        debug!("Find products deactivated before {:?}.", deactivated_before);
+
+        Products::products
+            .filter(Products::is_active.eq(false))
+            .filter(Products::updated_at.lt(deactivated_before))
+            .select(Products::id)
+            .order(Products::id)
+            .get_results::<ProductId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find deactivated products error occurred").into())
+    }
+
+    fn find_redeemed_products(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<ProductId>> {
+        debug!("Find redeemed products among {} products.", product_ids.len());
+
+        CouponRedemptions::coupon_redemptions
+            .filter(CouponRedemptions::product_id.eq_any(product_ids))
+            .select(CouponRedemptions::product_id)
+            .distinct()
+            .get_results::<i32>(self.db_conn)
+            .map(|ids| ids.into_iter().map(ProductId).collect())
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find redeemed products error occurred").into())
+    }
+
+    fn delete_products(&self, product_ids: Vec<ProductId>) -> RepoResult<Vec<ProductId>> {
+        debug!("Delete {} deactivated products.", product_ids.len());
+
+        let filtered = Products::products
+            .filter(Products::id.eq_any(product_ids))
+            .filter(Products::is_active.eq(false));
+
+        diesel::delete(filtered)
+            .returning(Products::id)
+            .get_results::<ProductId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Delete deactivated products error occurred").into())
+    }
+
+    fn find_deactivated_base_products(&self, deactivated_before: SystemTime) -> RepoResult<Vec<BaseProductId>> {
+        debug!("Find base products deactivated before {:?}.", deactivated_before);
+
+        let candidates = BaseProducts::base_products
+            .filter(BaseProducts::is_active.eq(false))
+            .filter(BaseProducts::updated_at.lt(deactivated_before))
+            .select(BaseProducts::id)
+            .order(BaseProducts::id)
+            .get_results::<BaseProductId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find deactivated base products error occurred"))?;
+
+        let with_variants = Products::products
+            .filter(Products::base_product_id.eq_any(candidates.clone()))
+            .select(Products::base_product_id)
+            .distinct()
+            .get_results::<BaseProductId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find variants of deactivated base products error occurred"))?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        Ok(candidates.into_iter().filter(|id| !with_variants.contains(id)).collect())
+    }
+
+    fn delete_base_products(&self, base_product_ids: Vec<BaseProductId>) -> RepoResult<Vec<BaseProductId>> {
+        debug!("Delete {} deactivated base products.", base_product_ids.len());
+
+        let filtered = BaseProducts::base_products
+            .filter(BaseProducts::id.eq_any(base_product_ids))
+            .filter(BaseProducts::is_active.eq(false));
+
+        diesel::delete(filtered)
+            .returning(BaseProducts::id)
+            .get_results::<BaseProductId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Delete deactivated base products error occurred").into())
+    }
+
+    fn find_deactivated_stores(&self, deactivated_before: SystemTime) -> RepoResult<Vec<StoreId>> {
+        debug!("Find stores deactivated before {:?}.", deactivated_before);
+
+        let candidates = Stores::stores
+            .filter(Stores::is_active.eq(false))
+            .filter(Stores::updated_at.lt(deactivated_before))
+            .select(Stores::id)
+            .order(Stores::id)
+            .get_results::<StoreId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find deactivated stores error occurred"))?;
+
+        let with_base_products = BaseProducts::base_products
+            .filter(BaseProducts::store_id.eq_any(candidates.clone()))
+            .select(BaseProducts::store_id)
+            .distinct()
+            .get_results::<StoreId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Find base products of deactivated stores error occurred"))?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        Ok(candidates.into_iter().filter(|id| !with_base_products.contains(id)).collect())
+    }
+
+    fn delete_stores(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<StoreId>> {
+        debug!("Delete {} deactivated stores.", store_ids.len());
+
+        let filtered = Stores::stores.filter(Stores::id.eq_any(store_ids)).filter(Stores::is_active.eq(false));
+
+        diesel::delete(filtered)
+            .returning(Stores::id)
+            .get_results::<StoreId>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Delete deactivated stores error occurred").into())
+    }
+}
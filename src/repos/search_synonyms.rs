@@ -0,0 +1,92 @@
+//! Repo for search_synonyms table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::authorization::*;
+use models::{NewSearchSynonym, SearchSynonym};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::search_synonyms::dsl::*;
+
+/// SearchSynonyms repository, responsible for handling search_synonyms table
+pub struct SearchSynonymsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<SearchSynonym>>,
+}
+
+pub trait SearchSynonymsRepo {
+    /// Returns all synonyms ordered by id
+    fn list(&self) -> RepoResult<Vec<SearchSynonym>>;
+
+    /// Creates new synonym
+    fn create(&self, payload: NewSearchSynonym) -> RepoResult<SearchSynonym>;
+
+    /// Deletes specific synonym
+    fn delete(&self, synonym_id: i32) -> RepoResult<Option<SearchSynonym>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> SearchSynonymsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<SearchSynonym>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> SearchSynonymsRepo
+    for SearchSynonymsRepoImpl<'a, T>
+{
+    /// Returns all synonyms ordered by id
+    fn list(&self) -> RepoResult<Vec<SearchSynonym>> {
+        debug!("List search synonyms.");
+        acl::check(&*self.acl, Resource::SearchSynonyms, Action::Read, self, None)?;
+
+        search_synonyms
+            .order(id)
+            .get_results::<SearchSynonym>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("List search synonyms error occurred").into())
+    }
+
+    /// Creates new synonym
+    fn create(&self, payload: NewSearchSynonym) -> RepoResult<SearchSynonym> {
+        debug!("Create search synonym {:?}.", payload);
+        acl::check(&*self.acl, Resource::SearchSynonyms, Action::Create, self, None)?;
+
+        diesel::insert_into(search_synonyms)
+            .values(&payload)
+            .get_result::<SearchSynonym>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Create search synonym {:?} error occurred", payload)).into())
+    }
+
+    /// Deletes specific synonym
+    fn delete(&self, synonym_id: i32) -> RepoResult<Option<SearchSynonym>> {
+        debug!("Delete search synonym {}.", synonym_id);
+        acl::check(&*self.acl, Resource::SearchSynonyms, Action::Delete, self, None)?;
+
+        diesel::delete(search_synonyms.filter(id.eq(synonym_id)))
+            .get_result::<SearchSynonym>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Delete search synonym {} error occurred", synonym_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, SearchSynonym>
+    for SearchSynonymsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&SearchSynonym>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
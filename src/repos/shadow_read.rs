@@ -0,0 +1,134 @@
+//! Shadow reads of rewritten repo queries.
+//!
+//! Queries listed in `shadow_reads` config section run both the old and the new implementation,
+//! results are compared and mismatches are logged, clients always get the result of the old one.
+//! The new implementation runs in a savepoint, so its failure does not abort the transaction of the request.
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::sync::RwLock;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use repos::types::RepoResult;
+
+lazy_static! {
+    static ref ENABLED_QUERIES: RwLock<HashSet<String>> = RwLock::new(HashSet::new());
+}
+
+/// Turns shadow reads of the queries on, the previously enabled ones are turned off
+pub fn enable(queries: Vec<String>) {
+    match ENABLED_QUERIES.write() {
+        Ok(mut enabled) => *enabled = queries.into_iter().collect(),
+        Err(e) => error!("Shadow reads state is poisoned: {}", e),
+    }
+}
+
+pub fn is_enabled(query: &str) -> bool {
+    ENABLED_QUERIES.read().map(|enabled| enabled.contains(query)).unwrap_or(false)
+}
+
+/// Returns the result of `old` implementation of the query. When shadow reads of the query are on,
+/// `new` implementation is run too and the results are compared, `context` is logged with mismatches
+pub fn shadow_read<C, T, O, N>(conn: &C, query: &str, context: &Debug, old: O, new: N) -> RepoResult<T>
+where
+    C: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager>,
+    T: PartialEq + Debug,
+    O: FnOnce() -> RepoResult<T>,
+    N: FnOnce() -> RepoResult<T>,
+{
+    let old_result = old();
+    if !is_enabled(query) {
+        return old_result;
+    }
+
+    let new_result = conn.transaction::<T, FailureError, _>(new);
+    report(query, context, &old_result, &new_result);
+    old_result
+}
+
+/// Logs differences of the results, returns `false` on mismatch
+fn report<T: PartialEq + Debug>(query: &str, context: &Debug, old: &RepoResult<T>, new: &RepoResult<T>) -> bool {
+    match (old, new) {
+        (Ok(old), Ok(new)) if old == new => true,
+        (Ok(old), Ok(new)) => {
+            warn!("Shadow read of {} mismatch, context: {:?}, old: {:?}, new: {:?}", query, context, old, new);
+            false
+        }
+        (Ok(old), Err(e)) => {
+            error!("Shadow read of {} failed, context: {:?}, old: {:?}, error: {}", query, context, old, e);
+            false
+        }
+        (Err(e), Ok(new)) => {
+            warn!("Shadow read of {} succeeded where old query failed, context: {:?}, error: {}, new: {:?}", query, context, e, new);
+            false
+        }
+        (Err(_), Err(_)) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use log::{self, Level, LevelFilter, Log, Metadata, Record};
+
+    use super::*;
+    use repos::repo_factory::tests::MockConnection;
+
+    lazy_static! {
+        static ref LOGGED: Mutex<Vec<String>> = Mutex::new(vec![]);
+    }
+
+    /// Keeps warnings and errors, so tests can check what was logged
+    struct CapturingLogger;
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= Level::Warn
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                LOGGED.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger;
+
+    #[test]
+    fn test_report() {
+        let context = "store_id: 1";
+        assert!(report("stores.find", &context, &Ok(1), &Ok(1)));
+        assert!(!report("stores.find", &context, &Ok(1), &Ok(2)));
+        assert!(!report("stores.find", &context, &Ok(1), &Err(format_err!("Syntax error"))));
+        assert!(report::<i32>("stores.find", &context, &Err(format_err!("Timeout")), &Err(format_err!("Timeout"))));
+    }
+
+    #[test]
+    fn test_enable() {
+        enable(vec!["base_products.count_with_store_id".to_string()]);
+        assert!(is_enabled("base_products.count_with_store_id"));
+        assert!(!is_enabled("stores.find"));
+    }
+
+    #[test]
+    fn test_shadow_read_logs_mismatch() {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(LevelFilter::Warn);
+        enable(vec!["base_products.count_with_store_id".to_string()]);
+
+        let conn = MockConnection::default();
+        let result = shadow_read(&conn, "base_products.count_with_store_id", &"store_id: 1", || Ok(3), || Ok(4));
+
+        assert_eq!(result.unwrap(), 3);
+        assert!(LOGGED.lock().unwrap().iter().any(|line| {
+            line.starts_with("Shadow read of base_products.count_with_store_id mismatch") && line.ends_with("old: 3, new: 4")
+        }));
+    }
+}
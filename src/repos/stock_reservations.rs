@@ -0,0 +1,175 @@
+//! Repo for stock_reservations table
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+use uuid::Uuid;
+
+use stq_types::{ProductId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::stock_reservations::dsl::*;
+
+/// StockReservations repository, responsible for handling stock_reservations table
+pub struct StockReservationsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<StockReservation>>,
+}
+
+pub trait StockReservationsRepo {
+    /// Returns units of the product held by reservations not expired at `now`, except the ones of `except_cart_id`
+    fn reserved_quantity(&self, product_id: ProductId, except_cart_id: Option<Uuid>, now: SystemTime) -> RepoResult<i32>;
+
+    /// Returns reservations not expired at `now`
+    fn list_active(&self, now: SystemTime) -> RepoResult<Vec<StockReservation>>;
+
+    /// Creates reservation or replaces the reservation of the product by the same cart
+    fn reserve(&self, payload: NewStockReservation) -> RepoResult<StockReservation>;
+
+    /// Deletes reservations
+    fn release(&self, reservation_ids: Vec<i32>) -> RepoResult<Vec<StockReservation>>;
+
+    /// Deletes reservation of the product held by the cart, its units are taken by the order of the cart
+    fn release_cart(&self, cart_id: Uuid, product_id: ProductId) -> RepoResult<Vec<StockReservation>>;
+
+    /// Deletes reservations expired at `now`
+    fn delete_expired(&self, now: SystemTime) -> RepoResult<Vec<StockReservation>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StockReservationsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<StockReservation>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StockReservationsRepo
+    for StockReservationsRepoImpl<'a, T>
+{
+    /// Returns units of the product held by reservations not expired at `now`, except the ones of `except_cart_id`
+    fn reserved_quantity(&self, product_id_arg: ProductId, except_cart_id: Option<Uuid>, now: SystemTime) -> RepoResult<i32> {
+        debug!("Count reserved units of product {} except cart {:?}.", product_id_arg, except_cart_id);
+
+        acl::check(&*self.acl, Resource::StockReservations, Action::Read, self, None)?;
+
+        let mut query = stock_reservations
+            .filter(product_id.eq(product_id_arg))
+            .filter(expires_at.gt(now))
+            .into_boxed();
+        if let Some(except_cart_id) = except_cart_id {
+            query = query.filter(cart_id.ne(except_cart_id));
+        }
+
+        query
+            .select(quantity)
+            .get_results::<i32>(self.db_conn)
+            .map(|quantities| quantities.into_iter().sum())
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Count reserved units of product {} error occurred", product_id_arg))
+                    .into()
+            })
+    }
+
+    /// Returns reservations not expired at `now`
+    fn list_active(&self, now: SystemTime) -> RepoResult<Vec<StockReservation>> {
+        debug!("List active stock reservations.");
+
+        stock_reservations
+            .filter(expires_at.gt(now))
+            .order(id)
+            .get_results::<StockReservation>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|reservations: Vec<StockReservation>| {
+                for reservation in &reservations {
+                    acl::check(&*self.acl, Resource::StockReservations, Action::Read, self, Some(reservation))?;
+                }
+                Ok(reservations)
+            })
+            .map_err(|e: FailureError| e.context("List active stock reservations error occurred").into())
+    }
+
+    /// Creates reservation or replaces the reservation of the product by the same cart
+    fn reserve(&self, payload: NewStockReservation) -> RepoResult<StockReservation> {
+        debug!("Reserve stock {:?}.", payload);
+
+        acl::check(&*self.acl, Resource::StockReservations, Action::Create, self, None)?;
+
+        diesel::insert_into(stock_reservations)
+            .values(&payload)
+            .on_conflict((cart_id, product_id))
+            .do_update()
+            .set(&payload)
+            .get_result::<StockReservation>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Reserve stock {:?} error occurred", payload)).into())
+    }
+
+    /// Deletes reservations
+    fn release(&self, reservation_ids: Vec<i32>) -> RepoResult<Vec<StockReservation>> {
+        debug!("Release stock reservations {:?}.", reservation_ids);
+
+        acl::check(&*self.acl, Resource::StockReservations, Action::Delete, self, None)?;
+
+        diesel::delete(stock_reservations.filter(id.eq_any(reservation_ids.clone())))
+            .get_results::<StockReservation>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Release stock reservations {:?} error occurred", reservation_ids))
+                    .into()
+            })
+    }
+
+    /// Deletes reservation of the product held by the cart, its units are taken by the order of the cart
+    fn release_cart(&self, cart_id_arg: Uuid, product_id_arg: ProductId) -> RepoResult<Vec<StockReservation>> {
+        debug!("Release stock reservation of product {} by cart {}.", product_id_arg, cart_id_arg);
+
+        acl::check(&*self.acl, Resource::StockReservations, Action::Delete, self, None)?;
+
+        diesel::delete(
+            stock_reservations
+                .filter(cart_id.eq(cart_id_arg))
+                .filter(product_id.eq(product_id_arg)),
+        )
+        .get_results::<StockReservation>(self.db_conn)
+        .map_err(|e| Error::from(e).into())
+        .map_err(|e: FailureError| {
+            e.context(format!(
+                "Release stock reservation of product {} by cart {} error occurred",
+                product_id_arg, cart_id_arg
+            ))
+            .into()
+        })
+    }
+
+    /// Deletes reservations expired at `now`
+    fn delete_expired(&self, now: SystemTime) -> RepoResult<Vec<StockReservation>> {
+        debug!("Delete expired stock reservations.");
+
+        acl::check(&*self.acl, Resource::StockReservations, Action::Delete, self, None)?;
+
+        diesel::delete(stock_reservations.filter(expires_at.le(now)))
+            .get_results::<StockReservation>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context("Delete expired stock reservations error occurred").into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, StockReservation>
+    for StockReservationsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&StockReservation>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
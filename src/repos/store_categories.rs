@@ -0,0 +1,219 @@
+//! Repo for store_categories table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{CategoryId, StoreId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::store_categories::dsl::*;
+use schema::stores::dsl as Stores;
+
+/// StoreCategories repository, responsible for handling store_categories table
+pub struct StoreCategoriesRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<StoreCategory>>,
+}
+
+pub trait StoreCategoriesRepo {
+    /// Returns declared and requested categories of the store
+    fn list(&self, store_id: StoreId) -> RepoResult<Vec<StoreCategory>>;
+
+    /// Find category of the store
+    fn find(&self, store_id: StoreId, category_id: CategoryId) -> RepoResult<Option<StoreCategory>>;
+
+    /// Replaces categories declared by the store, approved and requested ones are kept
+    fn replace_declared(&self, store_id: StoreId, category_ids: Vec<CategoryId>) -> RepoResult<Vec<StoreCategory>>;
+
+    /// Creates expansion request or renews the rejected one
+    fn request(&self, store_id: StoreId, category_id: CategoryId) -> RepoResult<StoreCategory>;
+
+    /// Sets status of the category of the store
+    fn set_status(&self, store_category_id: i32, status: StoreCategoryStatus, moderator_id: UserId) -> RepoResult<StoreCategory>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreCategoriesRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<StoreCategory>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreCategoriesRepo
+    for StoreCategoriesRepoImpl<'a, T>
+{
+    /// Returns declared and requested categories of the store
+    fn list(&self, store_id_arg: StoreId) -> RepoResult<Vec<StoreCategory>> {
+        debug!("List categories of store {}.", store_id_arg);
+
+        store_categories
+            .filter(store_id.eq(store_id_arg))
+            .order(id)
+            .get_results::<StoreCategory>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|categories: Vec<StoreCategory>| {
+                for category in &categories {
+                    acl::check(&*self.acl, Resource::StoreCategories, Action::Read, self, Some(category))?;
+                }
+                Ok(categories)
+            })
+            .map_err(|e: FailureError| e.context(format!("List categories of store {} error occurred", store_id_arg)).into())
+    }
+
+    /// Find category of the store
+    fn find(&self, store_id_arg: StoreId, category_id_arg: CategoryId) -> RepoResult<Option<StoreCategory>> {
+        debug!("Find category {} of store {}.", category_id_arg, store_id_arg);
+
+        store_categories
+            .filter(store_id.eq(store_id_arg).and(category_id.eq(category_id_arg)))
+            .get_result::<StoreCategory>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|category: Option<StoreCategory>| {
+                if let Some(ref category) = category {
+                    acl::check(&*self.acl, Resource::StoreCategories, Action::Read, self, Some(category))?;
+                }
+                Ok(category)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find category {} of store {} error occurred", category_id_arg, store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Replaces categories declared by the store, approved and requested ones are kept
+    fn replace_declared(&self, store_id_arg: StoreId, category_ids: Vec<CategoryId>) -> RepoResult<Vec<StoreCategory>> {
+        debug!("Replace categories declared by store {} with {:?}.", store_id_arg, category_ids);
+
+        let query = store_categories
+            .filter(store_id.eq(store_id_arg))
+            .filter(status.eq(StoreCategoryStatus::Declared))
+            .filter(category_id.ne_all(category_ids.clone()));
+
+        query
+            .clone()
+            .get_results::<StoreCategory>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|removed: Vec<StoreCategory>| {
+                for category in &removed {
+                    acl::check(&*self.acl, Resource::StoreCategories, Action::Delete, self, Some(category))?;
+                }
+                diesel::delete(query).execute(self.db_conn).map_err(|e| Error::from(e).into())
+            })
+            .and_then(|_| {
+                let payload = category_ids
+                    .iter()
+                    .map(|category_id_arg| NewStoreCategory {
+                        store_id: store_id_arg,
+                        category_id: *category_id_arg,
+                        status: StoreCategoryStatus::Declared,
+                    })
+                    .collect::<Vec<_>>();
+                if payload.is_empty() {
+                    return Ok(vec![]);
+                }
+
+                diesel::insert_into(store_categories)
+                    .values(&payload)
+                    .on_conflict((store_id, category_id))
+                    .do_nothing()
+                    .get_results::<StoreCategory>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .and_then(|created: Vec<StoreCategory>| {
+                for category in &created {
+                    acl::check(&*self.acl, Resource::StoreCategories, Action::Create, self, Some(category))?;
+                }
+                self.list(store_id_arg)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Replace categories declared by store {} with {:?} error occurred",
+                    store_id_arg, category_ids
+                ))
+                .into()
+            })
+    }
+
+    /// Creates expansion request or renews the rejected one
+    fn request(&self, store_id_arg: StoreId, category_id_arg: CategoryId) -> RepoResult<StoreCategory> {
+        debug!("Request category {} for store {}.", category_id_arg, store_id_arg);
+
+        let payload = NewStoreCategory {
+            store_id: store_id_arg,
+            category_id: category_id_arg,
+            status: StoreCategoryStatus::Requested,
+        };
+
+        diesel::insert_into(store_categories)
+            .values(&payload)
+            .on_conflict((store_id, category_id))
+            .do_update()
+            .set((status.eq(StoreCategoryStatus::Requested), moderator_id.eq(None::<UserId>)))
+            .get_result::<StoreCategory>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|category| {
+                acl::check(&*self.acl, Resource::StoreCategories, Action::Create, self, Some(&category)).and_then(|_| Ok(category))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Request category {} for store {} error occurred",
+                    category_id_arg, store_id_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Sets status of the category of the store
+    fn set_status(&self, store_category_id: i32, status_arg: StoreCategoryStatus, moderator_id_arg: UserId) -> RepoResult<StoreCategory> {
+        debug!("Set status {:?} of store category {}.", status_arg, store_category_id);
+
+        store_categories
+            .find(store_category_id)
+            .get_result::<StoreCategory>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|category| acl::check(&*self.acl, Resource::StoreCategories, Action::Moderate, self, Some(&category)))
+            .and_then(|_| {
+                diesel::update(store_categories.filter(id.eq(store_category_id)))
+                    .set((status.eq(status_arg), moderator_id.eq(Some(moderator_id_arg))))
+                    .get_result::<StoreCategory>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Set status {:?} of store category {} error occurred",
+                    status_arg, store_category_id
+                ))
+                .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, StoreCategory>
+    for StoreCategoriesRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id_arg: UserId, scope: &Scope, obj: Option<&StoreCategory>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => {
+                if let Some(category) = obj {
+                    Stores::stores
+                        .find(category.store_id)
+                        .get_result::<Store>(self.db_conn)
+                        .map(|store| store.user_id == user_id_arg)
+                        .ok()
+                        .unwrap_or(false)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
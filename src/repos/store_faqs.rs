@@ -0,0 +1,160 @@
+//! Repo for store_faqs table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::store_faqs::dsl::*;
+use schema::stores::dsl as Stores;
+
+/// StoreFaqs repository, responsible for handling store_faqs table
+pub struct StoreFaqsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<StoreFaq>>,
+}
+
+pub trait StoreFaqsRepo {
+    /// Returns questions of the store ordered by position
+    fn list(&self, store_id: StoreId) -> RepoResult<Vec<StoreFaq>>;
+
+    /// Find specific question of the store
+    fn find(&self, store_id: StoreId, faq_id: i32) -> RepoResult<Option<StoreFaq>>;
+
+    /// Creates new question
+    fn create(&self, payload: NewStoreFaq) -> RepoResult<StoreFaq>;
+
+    /// Updates specific question
+    fn update(&self, faq_id: i32, payload: UpdateStoreFaq) -> RepoResult<StoreFaq>;
+
+    /// Deletes specific question
+    fn delete(&self, faq_id: i32) -> RepoResult<StoreFaq>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreFaqsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<StoreFaq>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreFaqsRepo for StoreFaqsRepoImpl<'a, T> {
+    /// Returns questions of the store ordered by position
+    fn list(&self, store_id_arg: StoreId) -> RepoResult<Vec<StoreFaq>> {
+        debug!("List questions of store {}.", store_id_arg);
+
+        store_faqs
+            .filter(store_id.eq(store_id_arg))
+            .order((position, id))
+            .get_results::<StoreFaq>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|faqs: Vec<StoreFaq>| {
+                for faq in &faqs {
+                    acl::check(&*self.acl, Resource::StoreFaqs, Action::Read, self, Some(faq))?;
+                }
+                Ok(faqs)
+            })
+            .map_err(|e: FailureError| e.context(format!("List questions of store {} error occurred", store_id_arg)).into())
+    }
+
+    /// Find specific question of the store
+    fn find(&self, store_id_arg: StoreId, faq_id: i32) -> RepoResult<Option<StoreFaq>> {
+        debug!("Find question {} of store {}.", faq_id, store_id_arg);
+
+        store_faqs
+            .filter(id.eq(faq_id).and(store_id.eq(store_id_arg)))
+            .get_result::<StoreFaq>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|faq: Option<StoreFaq>| {
+                if let Some(ref faq) = faq {
+                    acl::check(&*self.acl, Resource::StoreFaqs, Action::Read, self, Some(faq))?;
+                }
+                Ok(faq)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find question {} of store {} error occurred", faq_id, store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Creates new question
+    fn create(&self, payload: NewStoreFaq) -> RepoResult<StoreFaq> {
+        debug!("Create question {:?}.", payload);
+
+        diesel::insert_into(store_faqs)
+            .values(&payload)
+            .get_result::<StoreFaq>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|faq| acl::check(&*self.acl, Resource::StoreFaqs, Action::Create, self, Some(&faq)).and_then(|_| Ok(faq)))
+            .map_err(|e: FailureError| e.context(format!("Create question {:?} error occurred", payload)).into())
+    }
+
+    /// Updates specific question
+    fn update(&self, faq_id: i32, payload: UpdateStoreFaq) -> RepoResult<StoreFaq> {
+        debug!("Update question {} with payload {:?}.", faq_id, payload);
+
+        store_faqs
+            .find(faq_id)
+            .get_result::<StoreFaq>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|faq| acl::check(&*self.acl, Resource::StoreFaqs, Action::Update, self, Some(&faq)))
+            .and_then(|_| {
+                diesel::update(store_faqs.filter(id.eq(faq_id)))
+                    .set(&payload)
+                    .get_result::<StoreFaq>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Update question {} with payload {:?} error occurred", faq_id, payload))
+                    .into()
+            })
+    }
+
+    /// Deletes specific question
+    fn delete(&self, faq_id: i32) -> RepoResult<StoreFaq> {
+        debug!("Delete question {}.", faq_id);
+
+        store_faqs
+            .find(faq_id)
+            .get_result::<StoreFaq>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|faq| acl::check(&*self.acl, Resource::StoreFaqs, Action::Delete, self, Some(&faq)))
+            .and_then(|_| {
+                diesel::delete(store_faqs.filter(id.eq(faq_id)))
+                    .get_result::<StoreFaq>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| e.context(format!("Delete question {} error occurred", faq_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, StoreFaq>
+    for StoreFaqsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id_arg: UserId, scope: &Scope, obj: Option<&StoreFaq>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => {
+                if let Some(faq) = obj {
+                    Stores::stores
+                        .find(faq.store_id)
+                        .get_result::<Store>(self.db_conn)
+                        .map(|store| store.user_id == user_id_arg)
+                        .ok()
+                        .unwrap_or(false)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Store feed repo, collects recently published products of the stores
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+use stq_cache::cache::Cache;
+use stq_static_resources::ModerationStatus;
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{BaseProduct, BaseProductRaw, Store, StoreFeed, STORE_FEED_SIZE};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::base_products::dsl as BaseProducts;
+use schema::stores::dsl as Stores;
+
+pub mod store_feed_cache;
+
+pub use self::store_feed_cache::*;
+
+/// Store feed repository, reads stores and base_products tables
+pub struct StoreFeedRepoImpl<'a, C, T>
+where
+    C: Cache<StoreFeed>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Store>>,
+    pub cache: Arc<StoreFeedCacheImpl<C>>,
+}
+
+pub trait StoreFeedRepo {
+    /// Returns feed of the published store, cached feed is reused until it is invalidated
+    fn get(&self, store_id: StoreId) -> RepoResult<Option<StoreFeed>>;
+
+    /// Drops cached feed of the store, the feed is built again on the next request
+    fn invalidate(&self, store_id: StoreId);
+}
+
+impl<'a, C, T> StoreFeedRepoImpl<'a, C, T>
+where
+    C: Cache<StoreFeed>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Store>>, cache: Arc<StoreFeedCacheImpl<C>>) -> Self {
+        Self { db_conn, acl, cache }
+    }
+
+    fn build_feed(&self, store: Store) -> RepoResult<StoreFeed> {
+        let base_products = BaseProducts::base_products
+            .filter(BaseProducts::store_id.eq(store.id))
+            .filter(BaseProducts::is_active.eq(true))
+            .filter(BaseProducts::status.eq(ModerationStatus::Published))
+            .filter(BaseProducts::published_at.is_not_null())
+            .order(BaseProducts::updated_at.desc())
+            .limit(STORE_FEED_SIZE)
+            .get_results::<BaseProductRaw>(self.db_conn)
+            .map(|raw_base_products| raw_base_products.into_iter().map(BaseProduct::from).collect::<Vec<_>>())
+            .map_err(Error::from)?;
+
+        Ok(StoreFeed::new(store, base_products, SystemTime::now()))
+    }
+}
+
+impl<'a, C, T> StoreFeedRepo for StoreFeedRepoImpl<'a, C, T>
+where
+    C: Cache<StoreFeed>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    /// Returns feed of the published store, cached feed is reused until it is invalidated
+    fn get(&self, store_id: StoreId) -> RepoResult<Option<StoreFeed>> {
+        debug!("Get feed of store {}.", store_id);
+
+        let store = Stores::stores
+            .filter(Stores::id.eq(store_id))
+            .filter(Stores::is_active.eq(true))
+            .filter(Stores::status.eq(ModerationStatus::Published))
+            .get_result::<Store>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Get feed of store {} error occurred", store_id)))?;
+
+        let store = match store {
+            Some(store) => store,
+            None => return Ok(None),
+        };
+
+        acl::check_with_rule(
+            &*self.acl,
+            Resource::Stores,
+            Action::Read,
+            self,
+            Rule::ModerationStatus(store.status),
+            Some(&store),
+        )?;
+
+        if let Some(feed) = self.cache.get(&store_id) {
+            return Ok(Some(feed));
+        }
+
+        self.build_feed(store)
+            .map(|feed| {
+                self.cache.set(&store_id, feed.clone());
+                Some(feed)
+            })
+            .map_err(|e: FailureError| e.context(format!("Build feed of store {} error occurred", store_id)).into())
+    }
+
+    /// Drops cached feed of the store, the feed is built again on the next request
+    fn invalidate(&self, store_id: StoreId) {
+        debug!("Invalidate feed of store {}.", store_id);
+
+        self.cache.remove(&store_id);
+    }
+}
+
+impl<'a, C, T> CheckScope<Scope, Store> for StoreFeedRepoImpl<'a, C, T>
+where
+    C: Cache<StoreFeed>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&Store>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|store| store.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
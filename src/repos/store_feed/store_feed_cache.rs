@@ -0,0 +1,11 @@
+//! StoreFeedCache caches feeds of the stores until products of the store are moderated
+use stq_types::StoreId;
+
+use models::StoreFeed;
+use repos::ttl_cache::{TtlCacheImpl, TtlCached};
+
+pub type StoreFeedCacheImpl<C> = TtlCacheImpl<StoreId, StoreFeed, C>;
+
+impl TtlCached for StoreFeed {
+    const CACHE_NAME: &'static str = "StoreFeedCache";
+}
@@ -0,0 +1,119 @@
+//! Repo for store_product_positions table
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::store_product_positions::dsl::*;
+use schema::stores::dsl as Stores;
+
+/// StoreProductPositions repository, responsible for handling store_product_positions table
+pub struct StoreProductPositionsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<StoreProductPosition>>,
+}
+
+pub trait StoreProductPositionsRepo {
+    /// Returns positions of products of the store, pinned ones first
+    fn list(&self, store_id: StoreId) -> RepoResult<Vec<StoreProductPosition>>;
+
+    /// Replaces positions of products of the store
+    fn replace(&self, store_id: StoreId, payload: Vec<NewStoreProductPosition>) -> RepoResult<Vec<StoreProductPosition>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreProductPositionsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<StoreProductPosition>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreProductPositionsRepo
+    for StoreProductPositionsRepoImpl<'a, T>
+{
+    /// Returns positions of products of the store, pinned ones first
+    fn list(&self, store_id_arg: StoreId) -> RepoResult<Vec<StoreProductPosition>> {
+        debug!("List product positions of store {}.", store_id_arg);
+
+        store_product_positions
+            .filter(store_id.eq(store_id_arg))
+            .order((pinned.desc(), position, id))
+            .get_results::<StoreProductPosition>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|positions: Vec<StoreProductPosition>| {
+                for store_product_position in &positions {
+                    acl::check(&*self.acl, Resource::StoreProductPositions, Action::Read, self, Some(store_product_position))?;
+                }
+                Ok(positions)
+            })
+            .map_err(|e: FailureError| e.context(format!("List product positions of store {} error occurred", store_id_arg)).into())
+    }
+
+    /// Replaces positions of products of the store
+    fn replace(&self, store_id_arg: StoreId, payload: Vec<NewStoreProductPosition>) -> RepoResult<Vec<StoreProductPosition>> {
+        debug!("Replace product positions of store {} with {:?}.", store_id_arg, payload);
+
+        let removed = diesel::delete(store_product_positions.filter(store_id.eq(store_id_arg)))
+            .get_results::<StoreProductPosition>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|removed: Vec<StoreProductPosition>| {
+                for store_product_position in &removed {
+                    acl::check(&*self.acl, Resource::StoreProductPositions, Action::Delete, self, Some(store_product_position))?;
+                }
+                Ok(removed)
+            });
+
+        removed
+            .and_then(|_| {
+                diesel::insert_into(store_product_positions)
+                    .values(&payload)
+                    .get_results::<StoreProductPosition>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .and_then(|created: Vec<StoreProductPosition>| {
+                for store_product_position in &created {
+                    acl::check(&*self.acl, Resource::StoreProductPositions, Action::Create, self, Some(store_product_position))?;
+                }
+                Ok(created)
+            })
+            .map(|mut created| {
+                created.sort_by_key(|store_product_position| (!store_product_position.pinned, store_product_position.position));
+                created
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Replace product positions of store {} with {:?} error occurred", store_id_arg, payload))
+                    .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, StoreProductPosition>
+    for StoreProductPositionsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id_arg: UserId, scope: &Scope, obj: Option<&StoreProductPosition>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => {
+                if let Some(store_product_position) = obj {
+                    Stores::stores
+                        .find(store_product_position.store_id)
+                        .get_result::<Store>(self.db_conn)
+                        .map(|store| store.user_id == user_id_arg)
+                        .ok()
+                        .unwrap_or(false)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
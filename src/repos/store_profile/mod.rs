@@ -0,0 +1,185 @@
+//! Store profile repo, builds public profiles of the published stores
+use std::sync::Arc;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+use stq_cache::cache::Cache;
+use stq_static_resources::ModerationStatus;
+use stq_types::{StoreSlug, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{Store, StoreProfile};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::base_products::dsl as BaseProducts;
+use schema::stores::dsl as Stores;
+
+pub mod store_profile_cache;
+
+pub use self::store_profile_cache::*;
+
+/// Store profile repository, reads stores and base_products tables
+pub struct StoreProfileRepoImpl<'a, C, T>
+where
+    C: Cache<StoreProfile>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Store>>,
+    pub cache: Arc<StoreProfileCacheImpl<C>>,
+}
+
+pub trait StoreProfileRepo {
+    /// Returns profile of the published store, cached profile is reused until it is invalidated or the cache expires
+    fn get(&self, store_slug: StoreSlug) -> RepoResult<Option<StoreProfile>>;
+
+    /// Drops cached profile at the slug, the profile is built again on the next request
+    fn invalidate(&self, store_slug: StoreSlug);
+}
+
+impl<'a, C, T> StoreProfileRepoImpl<'a, C, T>
+where
+    C: Cache<StoreProfile>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Store>>, cache: Arc<StoreProfileCacheImpl<C>>) -> Self {
+        Self { db_conn, acl, cache }
+    }
+
+    fn build_profile(&self, store: Store) -> RepoResult<StoreProfile> {
+        let published_products_count = BaseProducts::base_products
+            .filter(BaseProducts::store_id.eq(store.id))
+            .filter(BaseProducts::is_active.eq(true))
+            .filter(BaseProducts::status.eq(ModerationStatus::Published))
+            .filter(BaseProducts::published_at.is_not_null())
+            .count()
+            .get_result::<i64>(self.db_conn)
+            .map_err(Error::from)?;
+
+        Ok(StoreProfile::new(store, published_products_count))
+    }
+
+    fn check_read(&self, store: &Store) -> RepoResult<()> {
+        acl::check_with_rule(
+            &*self.acl,
+            Resource::Stores,
+            Action::Read,
+            self,
+            Rule::ModerationStatus(store.status),
+            Some(store),
+        )
+    }
+}
+
+impl<'a, C, T> StoreProfileRepo for StoreProfileRepoImpl<'a, C, T>
+where
+    C: Cache<StoreProfile>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    /// Returns profile of the published store, cached profile is reused until it is invalidated or the cache expires
+    fn get(&self, store_slug: StoreSlug) -> RepoResult<Option<StoreProfile>> {
+        debug!("Get profile of store with slug {}.", store_slug);
+
+        if let Some(profile) = self.cache.get(&store_slug) {
+            self.check_read(&profile.store)?;
+            return Ok(Some(profile));
+        }
+
+        let store = Stores::stores
+            .filter(Stores::slug.eq(&store_slug))
+            .filter(Stores::is_active.eq(true))
+            .filter(Stores::status.eq(ModerationStatus::Published))
+            .get_result::<Store>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Get profile of store with slug {} error occurred", store_slug)))?;
+
+        let store = match store {
+            Some(store) => store,
+            None => return Ok(None),
+        };
+
+        self.check_read(&store)?;
+
+        self.build_profile(store)
+            .map(|profile| {
+                self.cache.set(&store_slug, profile.clone());
+                Some(profile)
+            })
+            .map_err(|e: FailureError| e.context(format!("Build profile of store with slug {} error occurred", store_slug)).into())
+    }
+
+    /// Drops cached profile at the slug, the profile is built again on the next request
+    fn invalidate(&self, store_slug: StoreSlug) {
+        debug!("Invalidate profile of store with slug {}.", store_slug);
+
+        self.cache.remove(&store_slug);
+    }
+}
+
+impl<'a, C, T> CheckScope<Scope, Store> for StoreProfileRepoImpl<'a, C, T>
+where
+    C: Cache<StoreProfile>,
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&Store>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|store| store.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use diesel;
+    use diesel::result::Error as DieselError;
+    use stq_cache::cache::NullCache;
+
+    use super::*;
+    use repos::legacy_acl::SystemACL;
+    use repos::repo_factory::tests::*;
+
+    #[test]
+    #[ignore]
+    fn test_store_profile() {
+        let conn = create_db_connection();
+        conn.test_transaction::<_, DieselError, _>(|| {
+            let fixture = create_db_fixture(&conn);
+            let repo = StoreProfileRepoImpl::new(
+                &conn,
+                Box::new(SystemACL::default()) as Box<RepoAcl<Store>>,
+                Arc::new(StoreProfileCacheImpl::new(NullCache::new())),
+            );
+            let store_slug = StoreSlug(fixture.store.slug.clone());
+
+            // draft store has no public profile
+            assert!(repo.get(store_slug.clone()).unwrap().is_none());
+
+            diesel::update(Stores::stores.filter(Stores::id.eq(fixture.store.id)))
+                .set(Stores::status.eq(ModerationStatus::Published))
+                .execute(&conn)?;
+            let profile = repo.get(store_slug.clone()).unwrap().unwrap();
+            assert_eq!(profile.store.id, fixture.store.id);
+            assert_eq!(profile.published_products_count, 0);
+
+            diesel::update(BaseProducts::base_products.filter(BaseProducts::id.eq(fixture.base_product.id)))
+                .set((
+                    BaseProducts::status.eq(ModerationStatus::Published),
+                    BaseProducts::published_at.eq(SystemTime::now()),
+                ))
+                .execute(&conn)?;
+            let profile = repo.get(store_slug).unwrap().unwrap();
+            assert_eq!(profile.published_products_count, 1);
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,11 @@
+//! StoreProfileCache caches public profiles of the stores by slug
+use stq_types::StoreSlug;
+
+use models::StoreProfile;
+use repos::ttl_cache::{TtlCacheImpl, TtlCached};
+
+pub type StoreProfileCacheImpl<C> = TtlCacheImpl<StoreSlug, StoreProfile, C>;
+
+impl TtlCached for StoreProfile {
+    const CACHE_NAME: &'static str = "StoreProfileCache";
+}
@@ -0,0 +1,111 @@
+//! Store statistics repo, aggregates base_products, products and coupons tables of the store
+use std::time::SystemTime;
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::Integer;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::authorization::*;
+use models::{BaseProductsStatusGroup, Store, StoreStatistics};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::base_products::dsl as BaseProducts;
+use schema::coupons::dsl as Coupons;
+use schema::products::dsl as Products;
+use schema::stores::dsl as Stores;
+
+/// Store statistics repository, responsible for aggregates of the store catalog
+pub struct StoreStatisticsRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<Store>>,
+}
+
+pub trait StoreStatisticsRepo {
+    /// Returns statistics of the active store
+    fn get(&self, store_id: StoreId) -> RepoResult<Option<StoreStatistics>>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreStatisticsRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Store>>) -> Self {
+        Self { db_conn, acl }
+    }
+
+    fn build_statistics(&self, store_id: StoreId) -> RepoResult<StoreStatistics> {
+        let groups = diesel::sql_query(
+            "SELECT status, COUNT(*) AS base_products, COALESCE(SUM(views), 0)::BIGINT AS views, \
+             COUNT(*) FILTER (WHERE rating > 0) AS rated, COALESCE(SUM(rating), 0)::FLOAT8 AS rating_sum \
+             FROM base_products WHERE store_id = $1 AND is_active GROUP BY status ORDER BY status",
+        )
+        .bind::<Integer, _>(store_id.0)
+        .load::<BaseProductsStatusGroup>(self.db_conn)
+        .map_err(Error::from)?;
+
+        let now = SystemTime::now();
+        let active_coupons = Coupons::coupons
+            .filter(Coupons::store_id.eq(store_id))
+            .filter(Coupons::is_active.eq(true))
+            .filter(Coupons::expired_at.is_null().or(Coupons::expired_at.gt(now)))
+            .count()
+            .get_result::<i64>(self.db_conn)
+            .map_err(Error::from)?;
+
+        let variants = Products::products
+            .inner_join(BaseProducts::base_products)
+            .filter(BaseProducts::store_id.eq(store_id))
+            .filter(BaseProducts::is_active.eq(true))
+            .filter(Products::is_active.eq(true))
+            .count()
+            .get_result::<i64>(self.db_conn)
+            .map_err(Error::from)?;
+
+        Ok(StoreStatistics::new(store_id, groups, active_coupons, variants))
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreStatisticsRepo
+    for StoreStatisticsRepoImpl<'a, T>
+{
+    /// Returns statistics of the active store
+    fn get(&self, store_id: StoreId) -> RepoResult<Option<StoreStatistics>> {
+        debug!("Get statistics of store {}.", store_id);
+
+        let store = Stores::stores
+            .filter(Stores::id.eq(store_id))
+            .filter(Stores::is_active.eq(true))
+            .get_result::<Store>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| e.context(format!("Get statistics of store {} error occurred", store_id)))?;
+
+        let store = match store {
+            Some(store) => store,
+            None => return Ok(None),
+        };
+
+        acl::check(&*self.acl, Resource::StoreStatistics, Action::Read, self, Some(&store))?;
+
+        self.build_statistics(store_id)
+            .map(Some)
+            .map_err(|e: FailureError| e.context(format!("Build statistics of store {} error occurred", store_id)).into())
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, Store>
+    for StoreStatisticsRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id: UserId, scope: &Scope, obj: Option<&Store>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => obj.map(|store| store.user_id == user_id).unwrap_or(false),
+        }
+    }
+}
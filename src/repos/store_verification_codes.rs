@@ -0,0 +1,200 @@
+//! Repo for store_verification_codes table
+use std::time::{Duration, SystemTime};
+
+use diesel;
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::{StoreId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::store_verification_codes::dsl::*;
+use schema::stores::dsl as Stores;
+
+/// StoreVerificationCodes repository, responsible for handling store_verification_codes table
+pub struct StoreVerificationCodesRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<StoreVerificationCode>>,
+}
+
+pub trait StoreVerificationCodesRepo {
+    /// Creates new verification code, codes of the channel sent before the rate limit window are removed
+    fn create(&self, payload: NewStoreVerificationCode) -> RepoResult<StoreVerificationCode>;
+
+    /// Counts codes sent to the store contact since `since`
+    fn count_sent_since(&self, store_id: StoreId, channel: VerificationChannel, since: SystemTime) -> RepoResult<i64>;
+
+    /// Find the latest code sent to the store contact
+    fn find_latest(&self, store_id: StoreId, channel: VerificationChannel) -> RepoResult<Option<StoreVerificationCode>>;
+
+    /// Counts wrong guess of the code
+    fn increment_attempts(&self, code_id: i32) -> RepoResult<StoreVerificationCode>;
+
+    /// Removes codes of the store contact
+    fn delete_by_store(&self, store_id: StoreId, channel: VerificationChannel) -> RepoResult<()>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreVerificationCodesRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<StoreVerificationCode>>) -> Self {
+        Self { db_conn, acl }
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoreVerificationCodesRepo
+    for StoreVerificationCodesRepoImpl<'a, T>
+{
+    /// Creates new verification code, codes of the channel sent before the rate limit window are removed
+    fn create(&self, payload: NewStoreVerificationCode) -> RepoResult<StoreVerificationCode> {
+        debug!("Create verification code of store {} for {:?}.", payload.store_id, payload.channel);
+
+        // codes of the window are kept for `count_sent_since`, only the latest one is accepted anyway
+        let window_start = SystemTime::now() - Duration::from_secs(VERIFICATION_CODES_WINDOW_S);
+        let previous = store_verification_codes
+            .filter(store_id.eq(payload.store_id).and(channel.eq(payload.channel)))
+            .filter(created_at.lt(window_start));
+        diesel::delete(previous)
+            .execute(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|_| {
+                diesel::insert_into(store_verification_codes)
+                    .values(&payload)
+                    .get_result::<StoreVerificationCode>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .and_then(|code_res| {
+                acl::check(&*self.acl, Resource::StoreVerificationCodes, Action::Create, self, Some(&code_res)).and_then(|_| Ok(code_res))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Create verification code of store {} for {:?} error occurred.",
+                    payload.store_id, payload.channel
+                ))
+                .into()
+            })
+    }
+
+    /// Counts codes sent to the store contact since `since`
+    fn count_sent_since(&self, store_id_arg: StoreId, channel_arg: VerificationChannel, since: SystemTime) -> RepoResult<i64> {
+        debug!("Count verification codes of store {} for {:?}.", store_id_arg, channel_arg);
+
+        store_verification_codes
+            .filter(store_id.eq(store_id_arg).and(channel.eq(channel_arg)))
+            .filter(created_at.ge(since))
+            .count()
+            .get_result(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Count verification codes of store {} for {:?} error occurred.",
+                    store_id_arg, channel_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Find the latest code sent to the store contact
+    fn find_latest(&self, store_id_arg: StoreId, channel_arg: VerificationChannel) -> RepoResult<Option<StoreVerificationCode>> {
+        debug!("Find latest verification code of store {} for {:?}.", store_id_arg, channel_arg);
+
+        store_verification_codes
+            .filter(store_id.eq(store_id_arg).and(channel.eq(channel_arg)))
+            .order(created_at.desc())
+            .first::<StoreVerificationCode>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|code_res: Option<StoreVerificationCode>| {
+                if let Some(ref code_res) = code_res {
+                    acl::check(&*self.acl, Resource::StoreVerificationCodes, Action::Read, self, Some(code_res))?;
+                }
+                Ok(code_res)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Find latest verification code of store {} for {:?} error occurred.",
+                    store_id_arg, channel_arg
+                ))
+                .into()
+            })
+    }
+
+    /// Counts wrong guess of the code
+    fn increment_attempts(&self, code_id: i32) -> RepoResult<StoreVerificationCode> {
+        debug!("Increment attempts of verification code {}.", code_id);
+
+        store_verification_codes
+            .find(code_id)
+            .get_result::<StoreVerificationCode>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|code_res: StoreVerificationCode| {
+                acl::check(&*self.acl, Resource::StoreVerificationCodes, Action::Update, self, Some(&code_res))
+            })
+            .and_then(|_| {
+                diesel::update(store_verification_codes.filter(id.eq(code_id)))
+                    .set(attempts.eq(attempts + 1))
+                    .get_result::<StoreVerificationCode>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Increment attempts of verification code {} error occurred.", code_id))
+                    .into()
+            })
+    }
+
+    /// Removes codes of the store contact
+    fn delete_by_store(&self, store_id_arg: StoreId, channel_arg: VerificationChannel) -> RepoResult<()> {
+        debug!("Delete verification codes of store {} for {:?}.", store_id_arg, channel_arg);
+
+        store_verification_codes
+            .filter(store_id.eq(store_id_arg).and(channel.eq(channel_arg)))
+            .get_results::<StoreVerificationCode>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|codes: Vec<StoreVerificationCode>| {
+                for code_res in &codes {
+                    acl::check(&*self.acl, Resource::StoreVerificationCodes, Action::Delete, self, Some(code_res))?;
+                }
+                Ok(())
+            })
+            .and_then(|_| {
+                let filtered = store_verification_codes.filter(store_id.eq(store_id_arg).and(channel.eq(channel_arg)));
+                diesel::delete(filtered).execute(self.db_conn).map_err(|e| Error::from(e).into())
+            })
+            .map(|_| ())
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Delete verification codes of store {} for {:?} error occurred.",
+                    store_id_arg, channel_arg
+                ))
+                .into()
+            })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, StoreVerificationCode>
+    for StoreVerificationCodesRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, user_id_arg: UserId, scope: &Scope, obj: Option<&StoreVerificationCode>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => {
+                if let Some(code_res) = obj {
+                    Stores::stores
+                        .find(code_res.store_id)
+                        .get_result::<Store>(self.db_conn)
+                        .map(|store| store.user_id == user_id_arg)
+                        .ok()
+                        .unwrap_or(false)
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 //! Stores repo, presents CRUD operations with db for users
+use std::collections::HashMap;
+
 use diesel;
 use diesel::connection::AnsiTransactionManager;
 use diesel::dsl::exists;
@@ -7,11 +9,12 @@ use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::query_dsl::LoadQuery;
 use diesel::query_dsl::RunQueryDsl;
-use diesel::sql_types::{Bool, VarChar};
+use diesel::sql_types::{Array, Bool, Integer, VarChar};
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
 use failure::Fail;
+use serde_json;
 
 use stq_static_resources::{ModerationStatus, Translation};
 use stq_types::{SagaId, StoreId, StoreSlug, UserId};
@@ -22,6 +25,8 @@ use repos::legacy_acl::*;
 use repos::types::{RepoAcl, RepoResult};
 use schema::base_products::dsl as BaseProducts;
 use schema::products::dsl as Products;
+use schema::store_products_milestones::dsl as ProductsMilestones;
+use schema::store_slug_history::dsl as SlugHistory;
 use schema::stores::dsl::*;
 
 /// Stores repository, responsible for handling stores
@@ -30,7 +35,8 @@ pub struct StoresRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = A
     pub acl: Box<RepoAcl<Store>>,
 }
 
-pub trait StoresRepo {
+/// Queries of stores, they can be served by a read replica
+pub trait StoresReadRepo {
     /// Get store count
     fn count(&self, visibility: Visibility) -> RepoResult<i64>;
 
@@ -40,26 +46,20 @@ pub trait StoresRepo {
     /// Find specific store by slug
     fn find_by_slug(&self, store_slug: StoreSlug, visibility: Visibility) -> RepoResult<Option<Store>>;
 
+    /// Find store by one of its previous slugs
+    fn find_by_old_slug(&self, store_slug: StoreSlug, visibility: Visibility) -> RepoResult<Option<Store>>;
+
     /// Returns list of stores, limited by `from` and `count` parameters
     fn list(&self, from: StoreId, count: i32, visibility: Visibility) -> RepoResult<Vec<Store>>;
 
     /// Returns list of all stores
     fn all(&self, visibility: Visibility) -> RepoResult<Vec<Store>>;
 
-    /// Creates new store
-    fn create(&self, payload: NewStore) -> RepoResult<Store>;
+    /// Returns stores with the ids and counts of their active base products, missing stores are skipped
+    fn find_many(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<StoreWithProductsCount>>;
 
-    /// Updates specific store
-    fn update(&self, store_id: StoreId, payload: UpdateStore) -> RepoResult<Store>;
-
-    /// Deactivates specific store
-    fn deactivate(&self, store_id: StoreId) -> RepoResult<Store>;
-
-    /// Deactivates store by saga ID
-    fn deactivate_by_saga_id(&self, saga_id: SagaId) -> RepoResult<Store>;
-
-    /// Delete store by user id
-    fn delete_by_user(&self, user_id_arg: UserId) -> RepoResult<Option<Store>>;
+    /// Find store created under saga ID, including deactivated one
+    fn find_by_saga_id(&self, saga_id: SagaId) -> RepoResult<Option<Store>>;
 
     /// Get store by user id
     fn get_by_user(&self, user_id_arg: UserId) -> RepoResult<Option<Store>>;
@@ -73,12 +73,36 @@ pub trait StoresRepo {
     /// Checks if vendor code exists across the store
     fn vendor_code_exists(&self, store_id: StoreId, vendor_code: &str) -> RepoResult<Option<bool>>;
 
+    /// Checks if GTIN exists across the store
+    fn gtin_exists(&self, store_id: StoreId, gtin: &str) -> RepoResult<Option<bool>>;
+
     /// Search stores limited by pagination parameters
     fn moderator_search(
         &self,
         pagination_params: PaginationParams<StoreId>,
         term: ModeratorStoreSearchTerms,
     ) -> RepoResult<ModeratorStoreSearchResults>;
+}
+
+/// Changes of stores, they always go to the primary database
+pub trait StoresWriteRepo {
+    /// Creates new store
+    fn create(&self, payload: NewStore) -> RepoResult<Store>;
+
+    /// Updates specific store
+    fn update(&self, store_id: StoreId, payload: UpdateStore) -> RepoResult<Store>;
+
+    /// Find active store by ID, locking its row until the end of the transaction
+    fn find_for_update(&self, store_id: StoreId) -> RepoResult<Option<Store>>;
+
+    /// Deactivates specific store
+    fn deactivate(&self, store_id: StoreId) -> RepoResult<Store>;
+
+    /// Deactivates store by saga ID
+    fn deactivate_by_saga_id(&self, saga_id: SagaId) -> RepoResult<Store>;
+
+    /// Delete store by user id
+    fn delete_by_user(&self, user_id_arg: UserId) -> RepoResult<Option<Store>>;
 
     /// Set moderation status for specific store
     fn set_moderation_status(&self, store_id: StoreId, status: ModerationStatus) -> RepoResult<Store>;
@@ -88,8 +112,37 @@ pub trait StoresRepo {
 
     /// Delete store by id
     fn delete(&self, store_id: StoreId) -> RepoResult<()>;
+
+    /// Sets billing plan the product quota of the store is taken from
+    fn set_quota_plan(&self, store_id: StoreId, plan: Option<String>) -> RepoResult<Store>;
+
+    /// Marks store email or phone as confirmed by the owner
+    fn set_contact_verified(&self, store_id: StoreId, channel: VerificationChannel) -> RepoResult<Store>;
+
+    /// Sets onboarding facts kept by billing and delivery
+    fn set_onboarding(&self, store_id: StoreId, payload: SetStoreOnboarding) -> RepoResult<Store>;
+
+    /// Marks store as franchise, its owner can clone it
+    fn set_franchise(&self, store_id: StoreId, franchise: bool) -> RepoResult<Store>;
+
+    /// Pauses or resumes sales of the store
+    fn set_vacation(&self, store_id: StoreId, is_on_vacation: bool, vacation_message: Option<String>) -> RepoResult<Store>;
+
+    /// Sets verification status and badges of the store
+    fn set_verification(&self, store_id: StoreId, payload: VerifyStore) -> RepoResult<Store>;
+
+    /// Hands the store over to another user
+    fn set_owner(&self, store_id: StoreId, user_id: UserId) -> RepoResult<Store>;
+
+    /// Records that the products milestone of the store is announced, returns false when it was announced before
+    fn mark_products_milestone(&self, store_id: StoreId, published_products: i32) -> RepoResult<bool>;
 }
 
+/// Both halves of the stores repo, implemented for everything implementing them
+pub trait StoresRepo: StoresReadRepo + StoresWriteRepo {}
+
+impl<R: StoresReadRepo + StoresWriteRepo> StoresRepo for R {}
+
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoresRepoImpl<'a, T> {
     pub fn new(db_conn: &'a T, acl: Box<RepoAcl<Store>>) -> Self {
         Self { db_conn, acl }
@@ -98,9 +151,26 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
     fn execute_query<Ty: Send + 'static, U: LoadQuery<T, Ty> + Send + 'static>(&self, query: U) -> RepoResult<Ty> {
         query.get_result::<Ty>(self.db_conn).map_err(|e| Error::from(e).into())
     }
+
+    /// Keeps the previous slug of the store, the slug is taken over if another store had it before
+    fn add_slug_history(&self, store_id_arg: StoreId, old_slug: String) -> RepoResult<()> {
+        diesel::delete(SlugHistory::store_slug_history.filter(SlugHistory::slug.eq(&old_slug)))
+            .execute(self.db_conn)
+            .map_err(Error::from)?;
+
+        let payload = NewStoreSlugHistory {
+            store_id: store_id_arg,
+            slug: old_slug,
+        };
+        diesel::insert_into(SlugHistory::store_slug_history)
+            .values(&payload)
+            .execute(self.db_conn)
+            .map(|_| ())
+            .map_err(|e| Error::from(e).into())
+    }
 }
 
-impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoresRepo for StoresRepoImpl<'a, T> {
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoresReadRepo for StoresRepoImpl<'a, T> {
     /// Get store count
     fn count(&self, visibility: Visibility) -> RepoResult<i64> {
         debug!("Count stores with visibility = {:?}", visibility);
@@ -110,6 +180,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             Visibility::Published => stores
                 .filter(is_active.eq(true).and(status.eq(ModerationStatus::Published)))
                 .into_boxed(),
+            Visibility::Deactivated => stores.filter(is_active.eq(false)).into_boxed(),
         };
 
         acl::check(&*self.acl, Resource::Stores, Action::Read, self, None)
@@ -126,6 +197,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             Visibility::Published => stores
                 .filter(is_active.eq(true).and(status.eq(ModerationStatus::Published)))
                 .into_boxed(),
+            Visibility::Deactivated => stores.filter(is_active.eq(false)).into_boxed(),
         };
 
         query
@@ -140,7 +212,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::Stores,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(store.status),
+                        read_rule(store),
                         Some(store),
                     )?;
                 };
@@ -158,6 +230,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             Visibility::Published => stores
                 .filter(is_active.eq(true).and(status.eq(ModerationStatus::Published)))
                 .into_boxed(),
+            Visibility::Deactivated => stores.filter(is_active.eq(false)).into_boxed(),
         };
 
         query
@@ -172,7 +245,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::Stores,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(store.status),
+                        read_rule(store),
                         Some(store),
                     )?;
                 };
@@ -181,6 +254,23 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .map_err(|e: FailureError| e.context(format!("Find store with slug: {} error occurred", store_slug)).into())
     }
 
+    /// Find store by one of its previous slugs
+    fn find_by_old_slug(&self, store_slug: StoreSlug, visibility: Visibility) -> RepoResult<Option<Store>> {
+        debug!("Find in stores with old slug {}, visibility = {:?}", store_slug, visibility);
+
+        SlugHistory::store_slug_history
+            .filter(SlugHistory::slug.eq(&store_slug))
+            .select(SlugHistory::store_id)
+            .first::<StoreId>(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|store_id_arg| match store_id_arg {
+                Some(store_id_arg) => self.find(store_id_arg, visibility),
+                None => Ok(None),
+            })
+            .map_err(|e: FailureError| e.context(format!("Find store with old slug: {} error occurred", store_slug)).into())
+    }
+
     fn all(&self, visibility: Visibility) -> RepoResult<Vec<Store>> {
         debug!("List all stores");
 
@@ -189,6 +279,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             Visibility::Published => stores
                 .filter(is_active.eq(true).and(status.eq(ModerationStatus::Published)))
                 .into_boxed(),
+            Visibility::Deactivated => stores.filter(is_active.eq(false)).into_boxed(),
         };
 
         query
@@ -201,7 +292,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::Stores,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(store.status),
+                        read_rule(store),
                         Some(store),
                     )?;
                 }
@@ -210,15 +301,51 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .map_err(|e: FailureError| e.context(format!("List all stores error occurred.")).into())
     }
 
-    /// Creates new store
-    fn create(&self, payload: NewStore) -> RepoResult<Store> {
-        debug!("Create store {:?}.", payload);
-        let query_store = diesel::insert_into(stores).values(&payload);
-        query_store
-            .get_result::<Store>(self.db_conn)
+    /// Returns stores with the ids and counts of their active base products, missing stores are skipped
+    fn find_many(&self, store_ids: Vec<StoreId>) -> RepoResult<Vec<StoreWithProductsCount>> {
+        debug!("Find stores {:?} with products count.", store_ids);
+
+        let stores_res = stores
+            .filter(id.eq_any(store_ids.clone()))
+            .order(id)
+            .get_results::<Store>(self.db_conn)
             .map_err(|e| Error::from(e).into())
-            .and_then(|store| acl::check(&*self.acl, Resource::Stores, Action::Create, self, Some(&store)).and_then(|_| Ok(store)))
-            .map_err(|e: FailureError| e.context(format!("Create store {:?} error occurred.", payload)).into())
+            .and_then(|stores_res: Vec<Store>| {
+                for store in &stores_res {
+                    acl::check_with_rule(
+                        &*self.acl,
+                        Resource::Stores,
+                        Action::Read,
+                        self,
+                        read_rule(store),
+                        Some(store),
+                    )?;
+                }
+                Ok(stores_res)
+            })
+            .map_err(|e: FailureError| e.context(format!("Find stores {:?} error occurred.", store_ids)))?;
+
+        let ids = stores_res.iter().map(|store| store.id.0).collect::<Vec<_>>();
+        let counts = diesel::sql_query(
+            "SELECT stores.id AS store_id, COUNT(base_products.id) AS products_count FROM stores \
+             LEFT JOIN base_products ON base_products.store_id = stores.id AND base_products.is_active \
+             WHERE stores.id = ANY($1) GROUP BY stores.id",
+        )
+        .bind::<Array<Integer>, _>(ids)
+        .load::<StoreProductsCount>(self.db_conn)
+        .map_err(|e| Error::from(e).into())
+        .map_err(|e: FailureError| e.context(format!("Count products of stores {:?} error occurred.", store_ids)))?
+        .into_iter()
+        .map(|count| (count.store_id, count.products_count))
+        .collect::<HashMap<_, _>>();
+
+        Ok(stores_res
+            .into_iter()
+            .map(|store| StoreWithProductsCount {
+                products_count: counts.get(&store.id).cloned().unwrap_or(0),
+                store,
+            })
+            .collect())
     }
 
     /// Returns list of stores, limited by `from` and `count` parameters
@@ -230,6 +357,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             Visibility::Published => stores
                 .filter(is_active.eq(true).and(status.eq(ModerationStatus::Published)))
                 .into_boxed(),
+            Visibility::Deactivated => stores.filter(is_active.eq(false)).into_boxed(),
         };
 
         query
@@ -245,7 +373,7 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                         Resource::Stores,
                         Action::Read,
                         self,
-                        Rule::ModerationStatus(store.status),
+                        read_rule(store),
                         Some(store),
                     )?;
                 }
@@ -257,93 +385,27 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             })
     }
 
-    /// Updates specific store
-    fn update(&self, store_id_arg: StoreId, payload: UpdateStore) -> RepoResult<Store> {
-        debug!("Updating store with id {} and payload {:?}.", store_id_arg, payload);
-        self.execute_query(stores.find(store_id_arg))
-            .and_then(|store: Store| {
-                acl::check_with_rule(
-                    &*self.acl,
-                    Resource::Stores,
-                    Action::Update,
-                    self,
-                    Rule::ModerationStatus(store.status),
-                    Some(&store),
-                )
-            })
-            .and_then(|_| {
-                let filter = stores.filter(id.eq(store_id_arg)).filter(is_active.eq(true));
-
-                let query = diesel::update(filter).set(&payload);
-                query.get_result::<Store>(self.db_conn).map_err(|e| Error::from(e).into())
-            })
-            .map_err(|e: FailureError| {
-                e.context(format!(
-                    "Updating store with id {} and payload {:?} error occurred.",
-                    store_id_arg, payload
-                ))
-                .into()
-            })
-    }
-
-    /// Deactivates specific store
-    fn deactivate(&self, store_id_arg: StoreId) -> RepoResult<Store> {
-        debug!("Deactivate store with id {}.", store_id_arg);
-        self.execute_query(stores.find(store_id_arg))
-            .and_then(|store: Store| acl::check(&*self.acl, Resource::Stores, Action::Delete, self, Some(&store)))
-            .and_then(|_| {
-                let filter = stores.filter(id.eq(store_id_arg)).filter(is_active.eq(true));
-                let query = diesel::update(filter).set(is_active.eq(false));
-                self.execute_query(query)
-            })
-            .map_err(|e: FailureError| {
-                e.context(format!("Deactivate store with id {} error occurred.", store_id_arg))
-                    .into()
-            })
-    }
-
-    fn deactivate_by_saga_id(&self, saga_id_arg: SagaId) -> RepoResult<Store> {
-        debug!("Deactivate store with saga ID {}.", saga_id_arg);
+    fn find_by_saga_id(&self, saga_id_arg: SagaId) -> RepoResult<Option<Store>> {
+        debug!("Find store with saga ID {}.", saga_id_arg);
 
         let query = stores.filter(saga_id.eq(saga_id_arg));
 
         query
             .get_result(self.db_conn)
+            .optional()
             .map_err(|e| Error::from(e).into())
-            .and_then(|store| {
-                acl::check(&*self.acl, Resource::Stores, Action::Delete, self, Some(&store))?;
-                let filter = stores.filter(saga_id.eq(saga_id_arg));
-                let query = diesel::update(filter).set(is_active.eq(false));
-                self.execute_query(query)
+            .and_then(|store: Option<Store>| {
+                if let Some(ref store) = store {
+                    acl::check(&*self.acl, Resource::Stores, Action::Read, self, Some(store))?;
+                };
+                Ok(store)
             })
             .map_err(|e: FailureError| {
-                e.context(format!("Deactivate store with saga ID {} error occurred.", saga_id_arg))
+                e.context(format!("Find store with saga ID {} error occurred.", saga_id_arg))
                     .into()
             })
     }
 
-    /// Delete store by user id
-    fn delete_by_user(&self, user_id_arg: UserId) -> RepoResult<Option<Store>> {
-        debug!("Delete store by user id {}.", user_id_arg);
-        let query = stores.filter(user_id.eq(user_id_arg));
-
-        query
-            .get_result(self.db_conn)
-            .optional()
-            .map_err(|e| Error::from(e).into())
-            .and_then(|store_res: Option<Store>| {
-                if let Some(store_res) = store_res {
-                    acl::check(&*self.acl, Resource::Stores, Action::Delete, self, Some(&store_res))?;
-                    let filter = stores.filter(user_id.eq(user_id_arg));
-                    let query = diesel::update(filter).set(is_active.eq(false));
-                    self.execute_query(query).map(Some).map_err(From::from)
-                } else {
-                    Ok(None)
-                }
-            })
-            .map_err(|e: FailureError| e.context(format!("Delete store by user id {}.", user_id_arg)).into())
-    }
-
     /// Get store by user id
     fn get_by_user(&self, user_id_arg: UserId) -> RepoResult<Option<Store>> {
         debug!("get store by user id {}.", user_id_arg);
@@ -430,6 +492,36 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
         })
     }
 
+    /// Checks if GTIN exists across the store
+    fn gtin_exists(&self, store_id: StoreId, gtin: &str) -> RepoResult<Option<bool>> {
+        debug!("Check if GTIN '{}' exists for store '{}'", gtin, store_id);
+
+        {
+            if self.find(store_id, Visibility::Active)?.is_none() {
+                return Ok(None);
+            }
+
+            let gtin_exists_query = diesel::select(exists(
+                BaseProducts::base_products.inner_join(Products::products).filter(
+                    BaseProducts::is_active
+                        .eq(true)
+                        .and(BaseProducts::store_id.eq(store_id))
+                        .and(Products::is_active.eq(true))
+                        .and(Products::gtin.eq(gtin)),
+                ),
+            ));
+
+            gtin_exists_query
+                .get_result::<bool>(self.db_conn)
+                .map(Some)
+                .map_err(|e| Error::from(e).into())
+        }
+        .map_err(move |e: FailureError| {
+            let msg = format!("GTIN '{}' exists in store '{}' error occurred.", gtin, store_id);
+            e.context(msg).into()
+        })
+    }
+
     /// Search stores limited by pagination parameters
     fn moderator_search(
         &self,
@@ -499,6 +591,149 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                 .into()
             })
     }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> StoresWriteRepo for StoresRepoImpl<'a, T> {
+    /// Creates new store
+    fn create(&self, payload: NewStore) -> RepoResult<Store> {
+        debug!("Create store {:?}.", payload);
+        let query_store = diesel::insert_into(stores).values(&payload);
+        query_store
+            .get_result::<Store>(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|store| acl::check(&*self.acl, Resource::Stores, Action::Create, self, Some(&store)).and_then(|_| Ok(store)))
+            .map_err(|e: FailureError| e.context(format!("Create store {:?} error occurred.", payload)).into())
+    }
+
+    /// Updates specific store
+    fn update(&self, store_id_arg: StoreId, payload: UpdateStore) -> RepoResult<Store> {
+        debug!("Updating store with id {} and payload {:?}.", store_id_arg, payload);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| {
+                acl::check_with_rule(
+                    &*self.acl,
+                    Resource::Stores,
+                    Action::Update,
+                    self,
+                    Rule::ModerationStatus(store.status),
+                    Some(&store),
+                )
+                .map(|_| store)
+            })
+            .and_then(|store| {
+                // Changed contact has to be confirmed again
+                let email_verified_arg = store.email_verified && (payload.email.is_none() || payload.email == store.email);
+                let phone_verified_arg = store.phone_verified && (payload.phone.is_none() || payload.phone == store.phone);
+
+                let filter = stores.filter(id.eq(store_id_arg)).filter(is_active.eq(true));
+
+                let query = diesel::update(filter).set((
+                    &payload,
+                    email_verified.eq(email_verified_arg),
+                    phone_verified.eq(phone_verified_arg),
+                ));
+                let updated_store = query.get_result::<Store>(self.db_conn).map_err(Error::from)?;
+                if updated_store.slug != store.slug {
+                    self.add_slug_history(store.id, store.slug)?;
+                }
+                Ok(updated_store)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Updating store with id {} and payload {:?} error occurred.",
+                    store_id_arg, payload
+                ))
+                .into()
+            })
+    }
+
+    /// Find active store by ID, locking its row until the end of the transaction
+    fn find_for_update(&self, store_id_arg: StoreId) -> RepoResult<Option<Store>> {
+        debug!("Find in stores for update with id {}", store_id_arg);
+
+        stores
+            .filter(id.eq(store_id_arg))
+            .filter(is_active.eq(true))
+            .for_update()
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|store: Option<Store>| {
+                if let Some(ref store) = store {
+                    acl::check_with_rule(
+                        &*self.acl,
+                        Resource::Stores,
+                        Action::Read,
+                        self,
+                        read_rule(store),
+                        Some(store),
+                    )?;
+                };
+                Ok(store)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Find store for update with id: {} error occurred", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Deactivates specific store
+    fn deactivate(&self, store_id_arg: StoreId) -> RepoResult<Store> {
+        debug!("Deactivate store with id {}.", store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| acl::check(&*self.acl, Resource::Stores, Action::Delete, self, Some(&store)))
+            .and_then(|_| {
+                let filter = stores.filter(id.eq(store_id_arg)).filter(is_active.eq(true));
+                let query = diesel::update(filter).set(is_active.eq(false));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate store with id {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
+
+    fn deactivate_by_saga_id(&self, saga_id_arg: SagaId) -> RepoResult<Store> {
+        debug!("Deactivate store with saga ID {}.", saga_id_arg);
+
+        let query = stores.filter(saga_id.eq(saga_id_arg));
+
+        query
+            .get_result(self.db_conn)
+            .map_err(|e| Error::from(e).into())
+            .and_then(|store| {
+                acl::check(&*self.acl, Resource::Stores, Action::Delete, self, Some(&store))?;
+                let filter = stores.filter(saga_id.eq(saga_id_arg));
+                let query = diesel::update(filter).set(is_active.eq(false));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Deactivate store with saga ID {} error occurred.", saga_id_arg))
+                    .into()
+            })
+    }
+
+    /// Delete store by user id
+    fn delete_by_user(&self, user_id_arg: UserId) -> RepoResult<Option<Store>> {
+        debug!("Delete store by user id {}.", user_id_arg);
+        let query = stores.filter(user_id.eq(user_id_arg));
+
+        query
+            .get_result(self.db_conn)
+            .optional()
+            .map_err(|e| Error::from(e).into())
+            .and_then(|store_res: Option<Store>| {
+                if let Some(store_res) = store_res {
+                    acl::check(&*self.acl, Resource::Stores, Action::Delete, self, Some(&store_res))?;
+                    let filter = stores.filter(user_id.eq(user_id_arg));
+                    let query = diesel::update(filter).set(is_active.eq(false));
+                    self.execute_query(query).map(Some).map_err(From::from)
+                } else {
+                    Ok(None)
+                }
+            })
+            .map_err(|e: FailureError| e.context(format!("Delete store by user id {}.", user_id_arg)).into())
+    }
 
     /// Set moderation status for specific store
     fn set_moderation_status(&self, store_id_arg: StoreId, status_arg: ModerationStatus) -> RepoResult<Store> {
@@ -555,6 +790,145 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .map_err(|e| e.context(format!("Delete store with id {} error occurred.", store_id_arg)).into())
             .map(|_| ())
     }
+
+    /// Sets billing plan the product quota of the store is taken from
+    fn set_quota_plan(&self, store_id_arg: StoreId, plan: Option<String>) -> RepoResult<Store> {
+        debug!("Set quota plan {:?} for store with id {}.", plan, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| acl::check(&*self.acl, Resource::Stores, Action::Moderate, self, Some(&store)))
+            .and_then(|_| {
+                let filter = stores.filter(id.eq(store_id_arg));
+                let query = diesel::update(filter).set(quota_plan.eq(plan.clone()));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set quota plan for store with id {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Marks store email or phone as confirmed by the owner
+    fn set_contact_verified(&self, store_id_arg: StoreId, channel: VerificationChannel) -> RepoResult<Store> {
+        debug!("Set {:?} verified for store with id {}.", channel, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| acl::check(&*self.acl, Resource::Stores, Action::Update, self, Some(&store)))
+            .and_then(|_| {
+                let filter = stores.filter(id.eq(store_id_arg));
+                match channel {
+                    VerificationChannel::Email => self.execute_query(diesel::update(filter).set(email_verified.eq(true))),
+                    VerificationChannel::Phone => self.execute_query(diesel::update(filter).set(phone_verified.eq(true))),
+                }
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set {:?} verified for store with id {} error occurred.", channel, store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Sets onboarding facts kept by billing and delivery
+    fn set_onboarding(&self, store_id_arg: StoreId, payload: SetStoreOnboarding) -> RepoResult<Store> {
+        debug!("Set onboarding {:?} for store with id {}.", payload, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| {
+                acl::check(&*self.acl, Resource::Stores, Action::Moderate, self, Some(&store))?;
+                if payload.payout_info_present.is_none() && payload.shipping_profile_set.is_none() {
+                    return Ok(store);
+                }
+                let filter = stores.filter(id.eq(store_id_arg));
+                self.execute_query(diesel::update(filter).set(payload))
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set onboarding for store with id {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Marks store as franchise, its owner can clone it
+    fn set_franchise(&self, store_id_arg: StoreId, franchise_arg: bool) -> RepoResult<Store> {
+        debug!("Set franchise {} for store with id {}.", franchise_arg, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| acl::check(&*self.acl, Resource::Stores, Action::Moderate, self, Some(&store)))
+            .and_then(|_| {
+                let filter = stores.filter(id.eq(store_id_arg));
+                let query = diesel::update(filter).set(franchise.eq(franchise_arg));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set franchise for store with id {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Pauses or resumes sales of the store
+    fn set_vacation(&self, store_id_arg: StoreId, is_on_vacation_arg: bool, vacation_message_arg: Option<String>) -> RepoResult<Store> {
+        debug!("Set vacation {} for store with id {}.", is_on_vacation_arg, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| acl::check(&*self.acl, Resource::Stores, Action::Update, self, Some(&store)))
+            .and_then(|_| {
+                let filter = stores.filter(id.eq(store_id_arg));
+                let query = diesel::update(filter).set((is_on_vacation.eq(is_on_vacation_arg), vacation_message.eq(vacation_message_arg)));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set vacation for store with id {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Sets verification status and badges of the store
+    fn set_verification(&self, store_id_arg: StoreId, payload: VerifyStore) -> RepoResult<Store> {
+        debug!("Set verification {:?} for store with id {}.", payload, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| {
+                // owners moderate their stores only by moderation status rules, so `Rule::Any` leaves moderators
+                acl::check_with_rule(&*self.acl, Resource::Stores, Action::Moderate, self, Rule::Any, Some(&store))
+            })
+            .and_then(|_| {
+                let badges_arg = serde_json::to_value(&payload.badges)?;
+                let filter = stores.filter(id.eq(store_id_arg));
+                let query = diesel::update(filter).set((verification_status.eq(payload.verification_status), badges.eq(badges_arg)));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set verification for store with id {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Hands the store over to another user
+    fn set_owner(&self, store_id_arg: StoreId, user_id_arg: UserId) -> RepoResult<Store> {
+        debug!("Set owner {} for store with id {}.", user_id_arg, store_id_arg);
+        self.execute_query(stores.find(store_id_arg))
+            .and_then(|store: Store| acl::check_with_rule(&*self.acl, Resource::Stores, Action::Moderate, self, Rule::Any, Some(&store)))
+            .and_then(|_| {
+                let filter = stores.filter(id.eq(store_id_arg));
+                let query = diesel::update(filter).set(user_id.eq(user_id_arg));
+                self.execute_query(query)
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Set owner {} for store with id {} error occurred.", user_id_arg, store_id_arg))
+                    .into()
+            })
+    }
+
+    /// Records that the products milestone of the store is announced as root, returns false when it was announced before
+    fn mark_products_milestone(&self, store_id_arg: StoreId, published_products: i32) -> RepoResult<bool> {
+        debug!("Mark products milestone of store {} with {} published products.", store_id_arg, published_products);
+
+        diesel::insert_into(ProductsMilestones::store_products_milestones)
+            .values((
+                ProductsMilestones::store_id.eq(store_id_arg),
+                ProductsMilestones::published_products.eq(published_products),
+            ))
+            .on_conflict_do_nothing()
+            .execute(self.db_conn)
+            .map(|inserted| inserted > 0)
+            .map_err(|e| Error::from(e).into())
+            .map_err(|e: FailureError| {
+                e.context(format!("Mark products milestone of store {} error occurred.", store_id_arg))
+                    .into()
+            })
+    }
 }
 
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, Store>
@@ -574,6 +948,15 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
     }
 }
 
+/// Deactivated stores are read by their owners only
+fn read_rule(store: &Store) -> Rule {
+    if store.is_active {
+        Rule::ModerationStatus(store.status)
+    } else {
+        Rule::Deactivated
+    }
+}
+
 fn by_moderator_search_terms(term: &ModeratorStoreSearchTerms) -> Box<BoxableExpression<stores, Pg, SqlType = Bool>> {
     let mut expr: Box<BoxableExpression<stores, Pg, SqlType = Bool>> = Box::new(true.into_sql::<Bool>());
 
@@ -592,3 +975,27 @@ fn by_moderator_search_terms(term: &ModeratorStoreSearchTerms) -> Box<BoxableExp
 
     expr
 }
+
+#[cfg(test)]
+mod tests {
+    use diesel::result::Error as DieselError;
+
+    use super::*;
+    use repos::legacy_acl::SystemACL;
+    use repos::repo_factory::tests::*;
+
+    #[test]
+    #[ignore]
+    fn test_mark_products_milestone() {
+        let conn = create_db_connection();
+        conn.test_transaction::<_, DieselError, _>(|| {
+            let fixture = create_db_fixture(&conn);
+            let repo = StoresRepoImpl::new(&conn, Box::new(SystemACL::default()) as Box<RepoAcl<Store>>);
+
+            assert_eq!(repo.mark_products_milestone(fixture.store.id, 10).unwrap(), true);
+            // the milestone is announced once, even when the count reaches it again
+            assert_eq!(repo.mark_products_milestone(fixture.store.id, 11).unwrap(), false);
+            Ok(())
+        });
+    }
+}
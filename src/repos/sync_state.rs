@@ -0,0 +1,126 @@
+//! SyncState repo, reads `kafka_update_no` of stores, base_products and products tables
+use diesel::connection::AnsiTransactionManager;
+use diesel::dsl::{count_star, max};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::Connection;
+use failure::Error as FailureError;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::authorization::*;
+use models::{
+    BaseProduct, BaseProductRaw, RawProduct, Store, SyncEntities, SyncEntityState, SyncEntityType, SyncRange, SyncState,
+    ELASTIC_SCHEMA_VERSION,
+};
+use repos::acl;
+use repos::legacy_acl::CheckScope;
+use repos::types::{RepoAcl, RepoResult};
+use schema::base_products::dsl as BaseProducts;
+use schema::products::dsl as Products;
+use schema::stores::dsl as Stores;
+
+/// SyncState repository, responsible for catalog snapshot state of downstream consumers
+pub struct SyncStateRepoImpl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> {
+    pub db_conn: &'a T,
+    pub acl: Box<RepoAcl<SyncState>>,
+}
+
+pub trait SyncStateRepo {
+    /// Returns max `kafka_update_no` and count of every entity type
+    fn get_state(&self) -> RepoResult<SyncState>;
+
+    /// Returns entities with `kafka_update_no` in range, inactive ones included
+    fn find_entities(&self, entity_type: SyncEntityType, range: SyncRange, count: i64) -> RepoResult<SyncEntities>;
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> SyncStateRepoImpl<'a, T> {
+    pub fn new(db_conn: &'a T, acl: Box<RepoAcl<SyncState>>) -> Self {
+        Self { db_conn, acl }
+    }
+
+    fn entity_state(&self, entity_type: SyncEntityType) -> RepoResult<SyncEntityState> {
+        let (max_update_no, count) = match entity_type {
+            SyncEntityType::Store => Stores::stores
+                .select((max(Stores::kafka_update_no), count_star()))
+                .get_result::<(Option<i32>, i64)>(self.db_conn),
+            SyncEntityType::BaseProduct => BaseProducts::base_products
+                .select((max(BaseProducts::kafka_update_no), count_star()))
+                .get_result::<(Option<i32>, i64)>(self.db_conn),
+            SyncEntityType::Product => Products::products
+                .select((max(Products::kafka_update_no), count_star()))
+                .get_result::<(Option<i32>, i64)>(self.db_conn),
+        }
+        .map_err(Error::from)?;
+
+        Ok(SyncEntityState {
+            entity_type,
+            max_update_no,
+            count,
+        })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> SyncStateRepo for SyncStateRepoImpl<'a, T> {
+    /// Returns max `kafka_update_no` and count of every entity type
+    fn get_state(&self) -> RepoResult<SyncState> {
+        debug!("Get catalog sync state.");
+        acl::check(&*self.acl, Resource::SyncState, Action::Read, self, None)?;
+
+        SyncEntityType::all()
+            .into_iter()
+            .map(|entity_type| self.entity_state(entity_type))
+            .collect::<RepoResult<Vec<_>>>()
+            .map(|entities| SyncState {
+                entities,
+                schema_version: ELASTIC_SCHEMA_VERSION,
+            })
+            .map_err(|e: FailureError| e.context("Get catalog sync state error occurred").into())
+    }
+
+    /// Returns entities with `kafka_update_no` in range, inactive ones included
+    fn find_entities(&self, entity_type: SyncEntityType, range: SyncRange, count: i64) -> RepoResult<SyncEntities> {
+        debug!("Find {} entities with update numbers {:?}, count {}.", entity_type, range, count);
+        acl::check(&*self.acl, Resource::SyncState, Action::Read, self, None)?;
+
+        let to_no = range.to_no.unwrap_or(i32::max_value());
+        match entity_type {
+            SyncEntityType::Store => Stores::stores
+                .filter(Stores::kafka_update_no.between(range.from_no, to_no))
+                .order((Stores::kafka_update_no, Stores::id))
+                .limit(count)
+                .get_results::<Store>(self.db_conn)
+                .map(SyncEntities::Store),
+            SyncEntityType::BaseProduct => BaseProducts::base_products
+                .filter(BaseProducts::kafka_update_no.between(range.from_no, to_no))
+                .order((BaseProducts::kafka_update_no, BaseProducts::id))
+                .limit(count)
+                .get_results::<BaseProductRaw>(self.db_conn)
+                .map(|raw_base_products| SyncEntities::BaseProduct(raw_base_products.into_iter().map(BaseProduct::from).collect())),
+            SyncEntityType::Product => Products::products
+                .filter(Products::kafka_update_no.between(range.from_no, to_no))
+                .order((Products::kafka_update_no, Products::id))
+                .limit(count)
+                .get_results::<RawProduct>(self.db_conn)
+                .map(SyncEntities::Product),
+        }
+        .map_err(|e| Error::from(e).into())
+        .map_err(|e: FailureError| {
+            e.context(format!("Find {} entities with update numbers {:?} error occurred", entity_type, range))
+                .into()
+        })
+    }
+}
+
+impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, SyncState>
+    for SyncStateRepoImpl<'a, T>
+{
+    fn is_in_scope(&self, _user_id: UserId, scope: &Scope, _obj: Option<&SyncState>) -> bool {
+        match *scope {
+            Scope::All => true,
+            Scope::Owned => false,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! TtlCache keeps reports built from many rows until the backend expires them or the data they are built from changes.
+//! Cache errors are logged and treated as misses, the report is rebuilt then
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use failure::Fail;
+use stq_cache::cache::Cache;
+
+/// Value kept in `TtlCacheImpl`, the name shows up in logs
+pub trait TtlCached {
+    const CACHE_NAME: &'static str;
+}
+
+pub struct TtlCacheImpl<K, T, C>
+where
+    C: Cache<T>,
+{
+    cache: C,
+    phantom: PhantomData<fn(&K) -> T>,
+}
+
+impl<K, T, C> TtlCacheImpl<K, T, C>
+where
+    K: Display,
+    T: TtlCached,
+    C: Cache<T>,
+{
+    pub fn new(cache: C) -> Self {
+        TtlCacheImpl {
+            cache,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<T> {
+        debug!("Getting a value from {} at key '{}'", T::CACHE_NAME, key);
+
+        self.cache.get(key.to_string().as_str()).unwrap_or_else(|err| {
+            let err = err.context(format!("Failed to get a value from {} at key '{}'", T::CACHE_NAME, key));
+            error!("{}", err);
+            None
+        })
+    }
+
+    pub fn remove(&self, key: &K) -> bool {
+        debug!("Removing a value from {} at key '{}'", T::CACHE_NAME, key);
+
+        self.cache.remove(key.to_string().as_str()).unwrap_or_else(|err| {
+            let err = err.context(format!("Failed to remove a value from {} at key '{}'", T::CACHE_NAME, key));
+            error!("{}", err);
+            false
+        })
+    }
+
+    pub fn set(&self, key: &K, value: T) {
+        debug!("Setting a value in {} at key '{}'", T::CACHE_NAME, key);
+
+        self.cache.set(key.to_string().as_str(), value).unwrap_or_else(|err| {
+            let err = err.context(format!("Failed to set a value in {} at key '{}'", T::CACHE_NAME, key));
+            error!("{}", err);
+        })
+    }
+}
@@ -44,6 +44,9 @@ pub trait UserRolesRepo {
 
     /// Returns collection user_id
     fn get_user_ids_by_role(&self, role_name: StoresRole) -> RepoResult<HashSet<UserId>>;
+
+    /// Drops cached roles of the user, they are read from db on the next request
+    fn invalidate_cache(&self, user_id_arg: UserId);
 }
 
 /// Implementation of UserRoles trait
@@ -191,6 +194,11 @@ where
             })
             .map_err(|e: FailureError| e.context(format!("List user ids for role {:?}. error occurred.", role_name)).into())
     }
+
+    /// Drops cached roles of the user, they are read from db on the next request
+    fn invalidate_cache(&self, user_id_arg: UserId) {
+        self.cached_roles.remove(user_id_arg);
+    }
 }
 
 impl<'a, C, T> CheckScope<Scope, UserRole> for UserRolesRepoImpl<'a, C, T>
@@ -9,13 +9,15 @@ use diesel::query_dsl::RunQueryDsl;
 use diesel::Connection;
 use errors::Error;
 use failure::Error as FailureError;
+use std::time::SystemTime;
 use stq_types::{StoreId, UserId};
 
 use models::authorization::*;
-use models::{NewWizardStore, UpdateWizardStore, WizardStore};
+use models::{NewArchivedWizardStore, NewWizardStore, UpdateWizardStore, WizardStore, WizardStoresStats};
 use repos::acl;
 use repos::legacy_acl::*;
 use repos::types::{RepoAcl, RepoResult};
+use schema::archived_wizard_stores::dsl as ArchivedWizardStores;
 use schema::wizard_stores::dsl::*;
 
 /// Wizard stores repository, responsible for handling wizard stores
@@ -42,6 +44,15 @@ pub trait WizardStoresRepo {
 
     /// Check if the wizard already exists
     fn wizard_exists(&self, user_id: UserId) -> RepoResult<bool>;
+
+    /// Marks unfinished wizards untouched since the time as reminded, oldest first, and returns them
+    fn mark_reminded(&self, untouched_since: SystemTime, limit: i64) -> RepoResult<Vec<WizardStore>>;
+
+    /// Moves unfinished wizards untouched since the time to archived_wizard_stores, oldest first, and returns them
+    fn archive_untouched(&self, untouched_since: SystemTime, limit: i64) -> RepoResult<Vec<WizardStore>>;
+
+    /// Counts wizards by stage of the funnel
+    fn stats(&self) -> RepoResult<WizardStoresStats>;
 }
 
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> WizardStoresRepoImpl<'a, T> {
@@ -101,7 +112,8 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
             .and_then(|wizard_store: WizardStore| acl::check(&*self.acl, Resource::WizardStores, Action::Update, self, Some(&wizard_store)))
             .and_then(|_| {
                 let filter = wizard_stores.filter(user_id.eq(user_id_arg));
-                let query = diesel::update(filter).set(&payload);
+                let touched = (updated_at.eq(SystemTime::now()), reminded_at.eq(None::<SystemTime>));
+                let query = diesel::update(filter).set((&payload, touched));
                 query.get_result::<WizardStore>(self.db_conn).map_err(|e| Error::from(e).into())
             })
             .map_err(|e: FailureError| {
@@ -166,6 +178,100 @@ impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager
                     .into()
             })
     }
+
+    /// Marks unfinished wizards untouched since the time as reminded, oldest first, and returns them.
+    /// Rows are claimed by the update, so a wizard is reminded once even when several instances run the job
+    fn mark_reminded(&self, untouched_since: SystemTime, limit: i64) -> RepoResult<Vec<WizardStore>> {
+        debug!("Mark wizard stores untouched since {:?} as reminded, limit {}.", untouched_since, limit);
+
+        acl::check(&*self.acl, Resource::WizardStores, Action::Update, self, None)
+            .and_then(|_| {
+                let untouched_ids = wizard_stores
+                    .select(id)
+                    .filter(completed.eq(false))
+                    .filter(reminded_at.is_null())
+                    .filter(updated_at.lt(untouched_since))
+                    .order(updated_at)
+                    .limit(limit);
+                diesel::update(wizard_stores.filter(id.eq_any(untouched_ids)).filter(reminded_at.is_null()))
+                    .set(reminded_at.eq(SystemTime::now()))
+                    .get_results::<WizardStore>(self.db_conn)
+                    .map_err(|e| Error::from(e).into())
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!(
+                    "Mark wizard stores untouched since {:?} as reminded error occurred.",
+                    untouched_since
+                ))
+                .into()
+            })
+    }
+
+    /// Moves unfinished wizards untouched since the time to archived_wizard_stores, oldest first, and returns them
+    fn archive_untouched(&self, untouched_since: SystemTime, limit: i64) -> RepoResult<Vec<WizardStore>> {
+        debug!("Archive wizard stores untouched since {:?}, limit {}.", untouched_since, limit);
+
+        acl::check(&*self.acl, Resource::WizardStores, Action::Delete, self, None)
+            .and_then(|_| {
+                self.db_conn.transaction::<Vec<WizardStore>, FailureError, _>(|| {
+                    let untouched_ids = wizard_stores
+                        .select(id)
+                        .filter(completed.eq(false))
+                        .filter(updated_at.lt(untouched_since))
+                        .order(updated_at)
+                        .limit(limit);
+                    let archived = diesel::delete(wizard_stores.filter(id.eq_any(untouched_ids)))
+                        .get_results::<WizardStore>(self.db_conn)
+                        .map_err(Error::from)?;
+
+                    let payload = archived.iter().map(NewArchivedWizardStore::new).collect::<Vec<_>>();
+                    diesel::insert_into(ArchivedWizardStores::archived_wizard_stores)
+                        .values(&payload)
+                        .execute(self.db_conn)
+                        .map_err(Error::from)?;
+
+                    Ok(archived)
+                })
+            })
+            .map_err(|e: FailureError| {
+                e.context(format!("Archive wizard stores untouched since {:?} error occurred.", untouched_since))
+                    .into()
+            })
+    }
+
+    /// Counts wizards by stage of the funnel
+    fn stats(&self) -> RepoResult<WizardStoresStats> {
+        debug!("Count wizard stores by funnel stage.");
+
+        acl::check(&*self.acl, Resource::WizardStores, Action::Moderate, self, None)
+            .and_then(|_| {
+                let count_by_completed = |is_completed: bool| {
+                    wizard_stores
+                        .filter(completed.eq(is_completed))
+                        .count()
+                        .get_result::<i64>(self.db_conn)
+                        .map_err(Error::from)
+                };
+                let reminded = wizard_stores
+                    .filter(completed.eq(false))
+                    .filter(reminded_at.is_not_null())
+                    .count()
+                    .get_result::<i64>(self.db_conn)
+                    .map_err(Error::from)?;
+                let archived = ArchivedWizardStores::archived_wizard_stores
+                    .count()
+                    .get_result::<i64>(self.db_conn)
+                    .map_err(Error::from)?;
+
+                Ok(WizardStoresStats {
+                    completed: count_by_completed(true)?,
+                    in_progress: count_by_completed(false)?,
+                    reminded,
+                    archived,
+                })
+            })
+            .map_err(|e: FailureError| e.context("Count wizard stores by funnel stage error occurred.").into())
+    }
 }
 
 impl<'a, T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static> CheckScope<Scope, WizardStore>
@@ -1,3 +1,13 @@
+table! {
+    archived_wizard_stores (id) {
+        id -> Int4,
+        wizard_store_id -> Int4,
+        user_id -> Int4,
+        data -> Jsonb,
+        archived_at -> Timestamp,
+    }
+}
+
 table! {
     attributes (id) {
         id -> Int4,
@@ -17,6 +27,49 @@ table! {
     }
 }
 
+table! {
+    attribute_groups (id) {
+        id -> Int4,
+        name -> Jsonb,
+        position -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    attribute_group_attributes (id) {
+        id -> Int4,
+        group_id -> Int4,
+        attribute_id -> Int4,
+        position -> Int4,
+    }
+}
+
+table! {
+    audit_log (id) {
+        id -> Int4,
+        actor_user_id -> Int4,
+        effective_user_id -> Int4,
+        method -> Nullable<Varchar>,
+        path -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        base_product_id -> Nullable<Int4>,
+        change -> Nullable<Jsonb>,
+    }
+}
+
+table! {
+    base_product_checklist_results (id) {
+        id -> Int4,
+        base_product_id -> Int4,
+        item_id -> Int4,
+        passed -> Bool,
+        moderator_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     base_products (id) {
         id -> Int4,
@@ -42,6 +95,12 @@ table! {
         height_cm -> Int4,
         weight_g -> Int4,
         store_status -> Varchar,
+        saga_id -> Nullable<Uuid>,
+        published_at -> Nullable<Timestamp>,
+        min_price -> Nullable<Float8>,
+        max_price -> Nullable<Float8>,
+        store_is_on_vacation -> Bool,
+        dimensions_inherited -> Bool,
     }
 }
 
@@ -82,6 +141,16 @@ table! {
     }
 }
 
+table! {
+    coupon_redemptions (token) {
+        token -> Uuid,
+        coupon_id -> Int4,
+        user_id -> Int4,
+        product_id -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     coupon_scope_base_products (id) {
         id -> Int4,
@@ -115,6 +184,65 @@ table! {
     }
 }
 
+table! {
+    index_migrations (id) {
+        id -> Int4,
+        old_index -> Varchar,
+        new_index -> Varchar,
+        replays -> Jsonb,
+        created_at -> Timestamp,
+        switched_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    jobs (id) {
+        id -> Int4,
+        user_id -> Int4,
+        kind -> Varchar,
+        status -> Varchar,
+        total -> Int4,
+        processed -> Int4,
+        errors -> Jsonb,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    listings (id) {
+        id -> Int4,
+        category_id -> Int4,
+        attributes -> Jsonb,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    category_promotions (id) {
+        id -> Int4,
+        category_id -> Int4,
+        banner_url -> Varchar,
+        target_url -> Varchar,
+        position -> Int4,
+        starts_at -> Nullable<Timestamp>,
+        ends_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    moderation_checklist_items (id) {
+        id -> Int4,
+        category_id -> Int4,
+        description -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     moderator_product_comments (id) {
         id -> Int4,
@@ -122,6 +250,7 @@ table! {
         base_product_id -> Int4,
         comments -> Varchar,
         created_at -> Timestamp,
+        moderator_name -> Nullable<Varchar>,
     }
 }
 
@@ -132,6 +261,23 @@ table! {
         store_id -> Int4,
         comments -> Varchar,
         created_at -> Timestamp,
+        moderator_name -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    price_rules (id) {
+        id -> Int4,
+        store_id -> Int4,
+        name -> Varchar,
+        category_id -> Nullable<Int4>,
+        max_stock -> Nullable<Int4>,
+        max_views -> Nullable<Int4>,
+        percent -> Int4,
+        position -> Int4,
+        is_active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -148,6 +294,24 @@ table! {
     }
 }
 
+table! {
+    product_photos (id) {
+        id -> Int4,
+        product_id -> Int4,
+        url -> Varchar,
+        position -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    product_views (base_product_id, viewer_hash, day) {
+        base_product_id -> Int4,
+        viewer_hash -> Varchar,
+        day -> Date,
+    }
+}
+
 table! {
     products (id) {
         id -> Int4,
@@ -166,6 +330,23 @@ table! {
         pre_order -> Bool,
         pre_order_days -> Int4,
         uuid -> Uuid,
+        quantity -> Nullable<Int4>,
+        position -> Int4,
+        is_default -> Bool,
+        discount_starts_at -> Nullable<Timestamp>,
+        discount_ends_at -> Nullable<Timestamp>,
+        gtin -> Nullable<Varchar>,
+        price_updated_at -> Timestamp,
+        deactivated_with_base_product -> Bool,
+    }
+}
+
+table! {
+    search_synonyms (id) {
+        id -> Int4,
+        terms -> Array<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -206,6 +387,101 @@ table! {
         country_code -> Nullable<Varchar>,
         uuid -> Uuid,
         saga_id -> Nullable<Uuid>,
+        quota_plan -> Nullable<Varchar>,
+        email_verified -> Bool,
+        phone_verified -> Bool,
+        latitude -> Nullable<Float8>,
+        longitude -> Nullable<Float8>,
+        payout_info_present -> Bool,
+        shipping_profile_set -> Bool,
+        franchise -> Bool,
+        is_on_vacation -> Bool,
+        vacation_message -> Nullable<Varchar>,
+        opening_hours -> Nullable<Jsonb>,
+        timezone -> Nullable<Varchar>,
+        verification_status -> Varchar,
+        badges -> Jsonb,
+        default_length_cm -> Nullable<Int4>,
+        default_width_cm -> Nullable<Int4>,
+        default_height_cm -> Nullable<Int4>,
+        default_weight_g -> Nullable<Int4>,
+    }
+}
+
+table! {
+    stock_reservations (id) {
+        id -> Int4,
+        cart_id -> Uuid,
+        product_id -> Int4,
+        quantity -> Int4,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    store_categories (id) {
+        id -> Int4,
+        store_id -> Int4,
+        category_id -> Int4,
+        status -> Varchar,
+        moderator_id -> Nullable<Int4>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    store_faqs (id) {
+        id -> Int4,
+        store_id -> Int4,
+        question -> Jsonb,
+        answer -> Jsonb,
+        position -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    store_product_positions (id) {
+        id -> Int4,
+        store_id -> Int4,
+        base_product_id -> Int4,
+        position -> Int4,
+        pinned -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    store_products_milestones (store_id) {
+        store_id -> Int4,
+        published_products -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    store_slug_history (id) {
+        id -> Int4,
+        store_id -> Int4,
+        slug -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    store_verification_codes (id) {
+        id -> Int4,
+        store_id -> Int4,
+        channel -> Varchar,
+        destination -> Varchar,
+        code -> Varchar,
+        attempts -> Int4,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
     }
 }
 
@@ -248,14 +524,25 @@ table! {
         place_id -> Nullable<Varchar>,
         completed -> Bool,
         country_code -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        reminded_at -> Nullable<Timestamp>,
     }
 }
 
+joinable!(attribute_group_attributes -> attribute_groups (group_id));
+joinable!(attribute_group_attributes -> attributes (attribute_id));
 joinable!(attribute_values -> attributes (attr_id));
+joinable!(audit_log -> base_products (base_product_id));
+joinable!(base_product_checklist_results -> base_products (base_product_id));
+joinable!(base_product_checklist_results -> moderation_checklist_items (item_id));
 joinable!(base_products -> categories (category_id));
 joinable!(base_products -> stores (store_id));
 joinable!(cat_attr_values -> attributes (attr_id));
 joinable!(cat_attr_values -> categories (cat_id));
+joinable!(category_promotions -> categories (category_id));
+joinable!(coupon_redemptions -> coupons (coupon_id));
+joinable!(coupon_redemptions -> products (product_id));
 joinable!(coupon_scope_base_products -> base_products (base_product_id));
 joinable!(coupon_scope_base_products -> coupons (coupon_id));
 joinable!(coupon_scope_categories -> categories (category_id));
@@ -263,31 +550,68 @@ joinable!(coupon_scope_categories -> coupons (coupon_id));
 joinable!(coupons -> stores (store_id));
 joinable!(custom_attributes -> attributes (attribute_id));
 joinable!(custom_attributes -> base_products (base_product_id));
+joinable!(listings -> categories (category_id));
+joinable!(moderation_checklist_items -> categories (category_id));
 joinable!(moderator_product_comments -> base_products (base_product_id));
 joinable!(moderator_store_comments -> stores (store_id));
+joinable!(price_rules -> categories (category_id));
+joinable!(price_rules -> stores (store_id));
 joinable!(prod_attr_values -> attribute_values (attr_value_id));
 joinable!(prod_attr_values -> attributes (attr_id));
 joinable!(prod_attr_values -> base_products (base_prod_id));
 joinable!(prod_attr_values -> products (prod_id));
+joinable!(product_photos -> products (product_id));
+joinable!(product_views -> base_products (base_product_id));
 joinable!(products -> base_products (base_product_id));
+joinable!(stock_reservations -> products (product_id));
+joinable!(store_categories -> categories (category_id));
+joinable!(store_categories -> stores (store_id));
+joinable!(store_faqs -> stores (store_id));
+joinable!(store_product_positions -> base_products (base_product_id));
+joinable!(store_product_positions -> stores (store_id));
+joinable!(store_products_milestones -> stores (store_id));
+joinable!(store_slug_history -> stores (store_id));
+joinable!(store_verification_codes -> stores (store_id));
 joinable!(used_coupons -> coupons (coupon_id));
 
 allow_tables_to_appear_in_same_query!(
+    archived_wizard_stores,
     attributes,
+    attribute_groups,
+    attribute_group_attributes,
     attribute_values,
+    audit_log,
+    base_product_checklist_results,
     base_products,
     cat_attr_values,
     categories,
+    category_promotions,
     coupons,
+    coupon_redemptions,
     coupon_scope_base_products,
     coupon_scope_categories,
     currency_exchange,
     custom_attributes,
+    index_migrations,
+    jobs,
+    listings,
+    moderation_checklist_items,
     moderator_product_comments,
     moderator_store_comments,
+    price_rules,
     prod_attr_values,
+    product_photos,
+    product_views,
     products,
+    search_synonyms,
+    stock_reservations,
     stores,
+    store_categories,
+    store_faqs,
+    store_product_positions,
+    store_products_milestones,
+    store_slug_history,
+    store_verification_codes,
     used_coupons,
     user_roles,
     wizard_stores,
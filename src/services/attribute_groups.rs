@@ -0,0 +1,178 @@
+//! AttributeGroups Services, presents CRUD operations with attribute groups
+use std::collections::HashSet;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+
+use stq_types::AttributeId;
+
+use errors::Error;
+use models::{AttributeGroup, AttributeGroupWithAttributes, NewAttributeGroup, SetAttributeGroupAttributes, UpdateAttributeGroup};
+use repos::ReposFactory;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait AttributeGroupsService {
+    /// Returns all groups with their attributes ordered by position
+    fn list_attribute_groups(&self) -> ServiceFuture<Vec<AttributeGroupWithAttributes>>;
+    /// Creates new group
+    fn create_attribute_group(&self, payload: NewAttributeGroup) -> ServiceFuture<AttributeGroup>;
+    /// Updates specific group
+    fn update_attribute_group(&self, group_id: i32, payload: UpdateAttributeGroup) -> ServiceFuture<AttributeGroup>;
+    /// Deletes specific group
+    fn delete_attribute_group(&self, group_id: i32) -> ServiceFuture<AttributeGroup>;
+    /// Replaces attributes of the group
+    fn set_attribute_group_attributes(
+        &self,
+        group_id: i32,
+        payload: SetAttributeGroupAttributes,
+    ) -> ServiceFuture<AttributeGroupWithAttributes>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > AttributeGroupsService for Service<T, M, F>
+{
+    /// Returns all groups with their attributes ordered by position
+    fn list_attribute_groups(&self) -> ServiceFuture<Vec<AttributeGroupWithAttributes>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let attribute_groups_repo = repo_factory.create_attribute_groups_repo(&*conn, user_id);
+            attribute_groups_repo
+                .list()
+                .and_then(|groups| {
+                    let links = attribute_groups_repo.list_attributes()?;
+                    Ok(groups
+                        .into_iter()
+                        .map(|group| AttributeGroupWithAttributes::new(group, &links))
+                        .collect())
+                })
+                .map_err(|e: FailureError| e.context("Service AttributeGroups, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Creates new group
+    fn create_attribute_group(&self, payload: NewAttributeGroup) -> ServiceFuture<AttributeGroup> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let attribute_groups_repo = repo_factory.create_attribute_groups_repo(&*conn, user_id);
+            attribute_groups_repo
+                .create(payload)
+                .map_err(|e: FailureError| e.context("Service AttributeGroups, create endpoint error occurred.").into())
+        })
+    }
+
+    /// Updates specific group
+    fn update_attribute_group(&self, group_id: i32, payload: UpdateAttributeGroup) -> ServiceFuture<AttributeGroup> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let attribute_groups_repo = repo_factory.create_attribute_groups_repo(&*conn, user_id);
+            attribute_groups_repo
+                .update(group_id, payload)
+                .map_err(|e: FailureError| e.context("Service AttributeGroups, update endpoint error occurred.").into())
+        })
+    }
+
+    /// Deletes specific group
+    fn delete_attribute_group(&self, group_id: i32) -> ServiceFuture<AttributeGroup> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let attribute_groups_repo = repo_factory.create_attribute_groups_repo(&*conn, user_id);
+            attribute_groups_repo
+                .delete(group_id)
+                .map_err(|e: FailureError| e.context("Service AttributeGroups, delete endpoint error occurred.").into())
+        })
+    }
+
+    /// Replaces attributes of the group
+    fn set_attribute_group_attributes(
+        &self,
+        group_id: i32,
+        payload: SetAttributeGroupAttributes,
+    ) -> ServiceFuture<AttributeGroupWithAttributes> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let attribute_groups_repo = repo_factory.create_attribute_groups_repo(&*conn, user_id);
+            let attributes_repo = repo_factory.create_attributes_repo(&*conn, user_id);
+            conn.transaction::<AttributeGroupWithAttributes, FailureError, _>(move || {
+                let group = attribute_groups_repo
+                    .find(group_id)?
+                    .ok_or(format_err!("Attribute group {} not found", group_id).context(Error::NotFound))?;
+
+                let mut unique_ids = HashSet::new();
+                for attribute_id in &payload.attribute_ids {
+                    if !unique_ids.insert(*attribute_id) {
+                        return Err(format_err!("Attribute {} is listed twice", attribute_id)
+                            .context(Error::Validate(
+                                validation_errors!({"attribute_ids": ["attribute_ids" => "Attribute is listed twice"]}),
+                            ))
+                            .into());
+                    }
+                    if attributes_repo.find(*attribute_id)?.is_none() {
+                        return Err(format_err!("Attribute {} not found", attribute_id)
+                            .context(Error::Validate(
+                                validation_errors!({"attribute_ids": ["attribute_ids" => "Attribute not found"]}),
+                            ))
+                            .into());
+                    }
+                }
+
+                let links = attribute_groups_repo.set_attributes(group_id, payload.attribute_ids)?;
+                Ok(AttributeGroupWithAttributes::new(group, &links))
+            })
+            .map_err(|e: FailureError| e.context("Service AttributeGroups, set_attributes endpoint error occurred.").into())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use stq_types::*;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_list_attribute_groups() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.list_attribute_groups();
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].attribute_ids, vec![AttributeId(1)]);
+    }
+
+    #[test]
+    fn test_set_attribute_group_attributes() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = SetAttributeGroupAttributes {
+            attribute_ids: vec![AttributeId(2), AttributeId(1)],
+        };
+        let work = service.set_attribute_group_attributes(1, payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.attribute_ids, vec![AttributeId(2), AttributeId(1)]);
+    }
+}
@@ -1,4 +1,6 @@
 //! AttributeValue Services, presents CRUD operations with attribute_values
+use std::collections::{HashMap, HashSet};
+
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
@@ -14,7 +16,11 @@ use stq_types::{AttributeId, AttributeValueCode, AttributeValueId};
 use models::attributes::attribute_values::AttributeValue;
 use models::attributes::attribute_values::NewAttributeValue;
 use models::attributes::attribute_values::UpdateAttributeValue;
-use repos::{AttributeValuesSearchTerms, ProductAttrsRepo, ProductAttrsSearchTerms};
+use models::attributes::translation_import::{
+    merge_translation, parse_translations_csv, TranslationImportReport, TranslationImportRow, TranslationImportRowReport,
+};
+use models::{Job, JobKind};
+use repos::{AttributeValuesRepo, AttributeValuesSearchTerms, AttributesRepo, ProductAttrsRepo, ProductAttrsSearchTerms, RepoResult};
 
 pub trait AttributeValuesService {
     fn create_attribute_value(&self, new_attribute_value: NewAttributeValue) -> ServiceFuture<AttributeValue>;
@@ -22,8 +28,16 @@ pub trait AttributeValuesService {
     fn delete_attribute_value(&self, attr_value_id: AttributeValueId) -> ServiceFuture<AttributeValue>;
     fn get_attribute_values(&self, attr_id: AttributeId) -> ServiceFuture<Vec<AttributeValue>>;
     fn update_attribute_value(&self, attr_value_id: AttributeValueId, update: UpdateAttributeValue) -> ServiceFuture<AttributeValue>;
+    /// Imports translations from csv with `value_id,lang,text` rows in one transaction
+    fn import_attribute_value_translations(&self, csv: String) -> ServiceFuture<TranslationImportReport>;
+    /// Imports translations from csv in background job, values are updated in batches,
+    /// so the batches applied before cancellation are kept
+    fn start_attribute_value_translations_import(&self, csv: String) -> ServiceFuture<Job>;
 }
 
+/// Number of csv rows applied in one transaction by the import job
+const TRANSLATIONS_IMPORT_BATCH_SIZE: usize = 100;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NewAttributeValuePayload {
     pub code: AttributeValueCode,
@@ -101,6 +115,168 @@ impl<
                 .map_err(|e| e.context("AttributeValuesService, update_attribute_value error occurred.").into())
         })
     }
+
+    fn import_attribute_value_translations(&self, csv: String) -> ServiceFuture<TranslationImportReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
+                let attributes_repo = repo_factory.create_attributes_repo(&*conn, user_id);
+
+                let (rows, mut errors) = parse_translations_csv(&csv);
+                let updated = conn.transaction::<Option<Vec<AttributeValue>>, FailureError, _>(|| {
+                    let (updates, missing) = lock_translation_updates(&*attribute_values_repo, &rows)?;
+                    errors.extend(missing);
+                    if !errors.is_empty() {
+                        return Ok(None);
+                    }
+                    update_translations(&*attribute_values_repo, &updates).map(Some)
+                })?;
+
+                let updated = match updated {
+                    Some(updated) => updated,
+                    None => {
+                        errors.sort_by_key(|report| report.line);
+                        return Ok(TranslationImportReport {
+                            applied: false,
+                            updated_values: 0,
+                            rows: errors,
+                        });
+                    }
+                };
+                invalidate_attributes(&*attributes_repo, &updated);
+
+                Ok(TranslationImportReport {
+                    applied: true,
+                    updated_values: updated.len(),
+                    rows: rows.iter().map(|row| TranslationImportRowReport::ok(row.line, row.value_id)).collect(),
+                })
+            })
+            .map_err(|e: FailureError| e.context("AttributeValuesService, import_attribute_value_translations error occurred.").into()),
+        )
+    }
+
+    fn start_attribute_value_translations_import(&self, csv: String) -> ServiceFuture<Job> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        Box::new(
+            self.start_job(JobKind::AttributeValueTranslationsImport, move |conn, tracker| {
+                let attribute_values_repo = repo_factory.create_attribute_values_repo(conn, user_id);
+                let attributes_repo = repo_factory.create_attributes_repo(conn, user_id);
+
+                let (rows, mut errors) = parse_translations_csv(&csv);
+                errors.extend(missing_translation_values(&*attribute_values_repo, &rows)?);
+                if !errors.is_empty() {
+                    let invalid_rows = errors.len();
+                    for report in errors {
+                        tracker.add_error(format!("line {}", report.line), report.error.unwrap_or_default());
+                    }
+                    return Err(format_err!("{} rows are invalid, no translations are imported", invalid_rows));
+                }
+
+                tracker.set_total(rows.len())?;
+                let mut processed = 0;
+                for batch in rows.chunks(TRANSLATIONS_IMPORT_BATCH_SIZE) {
+                    if tracker.is_canceled() {
+                        break;
+                    }
+
+                    let updated = conn.transaction::<(Vec<AttributeValue>), FailureError, _>(|| {
+                        let (updates, missing) = lock_translation_updates(&*attribute_values_repo, batch)?;
+                        if let Some(report) = missing.into_iter().next() {
+                            return Err(format_err!("line {}: {}", report.line, report.error.unwrap_or_default()));
+                        }
+                        update_translations(&*attribute_values_repo, &updates)
+                    })?;
+                    invalidate_attributes(&*attributes_repo, &updated);
+                    processed += batch.len();
+                    tracker.set_processed(processed)?;
+                }
+
+                Ok(())
+            })
+            .map_err(|e: FailureError| {
+                e.context("AttributeValuesService, start_attribute_value_translations_import error occurred.")
+                    .into()
+            }),
+        )
+    }
+}
+
+/// Reports rows of attribute values which do not exist
+fn missing_rows(rows: &[TranslationImportRow], values: &HashMap<AttributeValueId, AttributeValue>) -> Vec<TranslationImportRowReport> {
+    rows.iter()
+        .filter(|row| !values.contains_key(&row.value_id))
+        .map(|row| TranslationImportRowReport::error(row.line, Some(row.value_id), format!("Attribute value {} not found", row.value_id)))
+        .collect()
+}
+
+/// Checks that values of all rows exist without locking them, used before the import is split into batches
+fn missing_translation_values(
+    attribute_values_repo: &AttributeValuesRepo,
+    rows: &[TranslationImportRow],
+) -> RepoResult<Vec<TranslationImportRowReport>> {
+    let ids: HashSet<AttributeValueId> = rows.iter().map(|row| row.value_id).collect();
+    let values: HashMap<AttributeValueId, AttributeValue> = attribute_values_repo
+        .find_many(AttributeValuesSearchTerms {
+            ids: Some(ids.into_iter().collect()),
+            ..Default::default()
+        })?
+        .into_iter()
+        .map(|value| (value.id, value))
+        .collect();
+
+    Ok(missing_rows(rows, &values))
+}
+
+/// Locks values of the rows and merges the rows into their current translations, so translations
+/// written by a concurrent update are kept. Has to be called inside the transaction writing the values.
+/// Returns the merged values and reports of the rows whose values do not exist
+fn lock_translation_updates(
+    attribute_values_repo: &AttributeValuesRepo,
+    rows: &[TranslationImportRow],
+) -> RepoResult<(Vec<AttributeValue>, Vec<TranslationImportRowReport>)> {
+    let ids: HashSet<AttributeValueId> = rows.iter().map(|row| row.value_id).collect();
+    let values: HashMap<AttributeValueId, AttributeValue> = attribute_values_repo
+        .find_many_for_update(ids.into_iter().collect())?
+        .into_iter()
+        .map(|value| (value.id, value))
+        .collect();
+
+    let missing = missing_rows(rows, &values);
+    let mut updates: HashMap<AttributeValueId, AttributeValue> = HashMap::new();
+    for row in rows.iter().filter(|row| values.contains_key(&row.value_id)) {
+        let value = updates.entry(row.value_id).or_insert_with(|| values[&row.value_id].clone());
+        value.translations = Some(merge_translation(value.translations.as_ref(), row.translation.clone()));
+    }
+
+    Ok((updates.into_iter().map(|(_, value)| value).collect(), missing))
+}
+
+fn update_translations(attribute_values_repo: &AttributeValuesRepo, values: &[AttributeValue]) -> RepoResult<Vec<AttributeValue>> {
+    values
+        .iter()
+        .map(|value| {
+            attribute_values_repo.update(
+                value.id,
+                UpdateAttributeValue {
+                    translations: value.translations.clone(),
+                    code: None,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Removes attributes of the updated values from cache, called after the transaction is committed
+fn invalidate_attributes(attributes_repo: &AttributesRepo, values: &[AttributeValue]) {
+    let attribute_ids: HashSet<AttributeId> = values.iter().map(|value| value.attr_id).collect();
+    for attribute_id in attribute_ids {
+        attributes_repo.invalidate_cache(attribute_id);
+    }
 }
 
 fn validate_delete_attribute_value(value: &AttributeValue, prod_attr_repo: &ProductAttrsRepo) -> Result<(), FailureError> {
@@ -119,3 +295,39 @@ fn validate_delete_attribute_value(value: &AttributeValue, prod_attr_repo: &Prod
     }
     Ok(())
 }
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use stq_types::*;
+
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_import_attribute_value_translations() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.import_attribute_value_translations("value_id,lang,text\n1,en,Extra large\n".to_string());
+        let result = core.run(work).unwrap();
+        assert!(result.applied);
+        assert_eq!(result.updated_values, 1);
+    }
+
+    #[test]
+    fn test_import_attribute_value_translations_missing_value() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.import_attribute_value_translations("1,en,Extra large\n2,en,Small\n".to_string());
+        let result = core.run(work).unwrap();
+        assert!(!result.applied);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].line, 2);
+        assert_eq!(result.rows[0].value_id, Some(AttributeValueId(2)));
+    }
+}
@@ -5,10 +5,15 @@ use diesel::Connection;
 use failure::Error as FailureError;
 use r2d2::ManageConnection;
 use stq_static_resources::language::{Language, Translation};
+use stq_static_resources::AttributeType;
 use stq_types::newtypes::AttributeValueCode;
 
-use models::{Attribute, CreateAttributePayload, CreateAttributeWithAttribute, NewAttribute, NewAttributeValue, UpdateAttribute};
-use repos::{AttributeValuesRepo, AttributeValuesSearchTerms, ReposFactory};
+use errors::Error;
+use models::{
+    Attribute, AttributeMetaSchema, CreateAttributePayload, CreateAttributeWithAttribute, ImpactReport, NewAttribute, NewAttributeValue,
+    UpdateAttribute,
+};
+use repos::{AttributeValuesRepo, AttributeValuesSearchTerms, CategoryAttrsRepo, ReposFactory};
 use services::types::ServiceFuture;
 use services::Service;
 use stq_types::AttributeId;
@@ -16,6 +21,8 @@ use stq_types::AttributeId;
 pub trait AttributesService {
     /// Returns attribute by ID
     fn get_attribute(&self, attribute_id: AttributeId) -> ServiceFuture<Option<Attribute>>;
+    /// Returns schema of `meta_field` of the attribute
+    fn get_attribute_schema(&self, attribute_id: AttributeId) -> ServiceFuture<Option<AttributeMetaSchema>>;
     /// Returns all attributes
     fn list_attributes(&self) -> ServiceFuture<Vec<Attribute>>;
     /// Creates new attribute
@@ -24,6 +31,8 @@ pub trait AttributesService {
     fn update_attribute(&self, attribute_id: AttributeId, payload: UpdateAttribute) -> ServiceFuture<Attribute>;
     /// Deletes specific attribute
     fn delete_attribute(&self, attribute_id: AttributeId) -> ServiceFuture<()>;
+    /// Returns what deleting the attribute changes, nothing is deleted
+    fn delete_attribute_impact(&self, attribute_id: AttributeId) -> ServiceFuture<ImpactReport>;
 }
 
 impl<
@@ -45,6 +54,20 @@ impl<
         })
     }
 
+    /// Returns schema of `meta_field` of the attribute
+    fn get_attribute_schema(&self, attribute_id: AttributeId) -> ServiceFuture<Option<AttributeMetaSchema>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let attributes_repo = repo_factory.create_attributes_repo(&*conn, user_id);
+            attributes_repo
+                .find(attribute_id)
+                .map(|attribute| attribute.map(|attribute| AttributeMetaSchema::new(attribute.value_type)))
+                .map_err(|e| e.context("Service Attributes, get_attribute_schema endpoint error occurred.").into())
+        })
+    }
+
     /// Returns all attributes
     fn list_attributes(&self) -> ServiceFuture<Vec<Attribute>> {
         let user_id = self.dynamic_context.user_id;
@@ -68,7 +91,9 @@ impl<
             let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
             conn.transaction::<(Attribute), FailureError, _>(move || {
                 let meta_field = if let Some(meta_field) = &create_attribute_payload.meta_field {
-                    Some(serde_json::to_value(&meta_field)?)
+                    let meta_field = serde_json::to_value(&meta_field)?;
+                    validate_meta_field(&create_attribute_payload.value_type, &meta_field)?;
+                    Some(meta_field)
                 } else {
                     None
                 };
@@ -93,9 +118,16 @@ impl<
 
         self.spawn_on_pool(move |conn| {
             let attributes_repo = repo_factory.create_attributes_repo(&*conn, user_id);
-            attributes_repo
-                .update(attribute_id, payload)
-                .map_err(|e| e.context("Service Attributes, update endpoint error occurred.").into())
+            conn.transaction::<Attribute, FailureError, _>(move || {
+                if let Some(ref meta_field) = payload.meta_field {
+                    let attribute = attributes_repo
+                        .find(attribute_id)?
+                        .ok_or_else(|| format_err!("Attribute {} not found", attribute_id).context(Error::NotFound))?;
+                    validate_meta_field(&attribute.value_type, meta_field)?;
+                }
+                attributes_repo.update(attribute_id, payload)
+            })
+            .map_err(|e| e.context("Service Attributes, update endpoint error occurred.").into())
         })
     }
     /// Deletes specific attribute
@@ -108,30 +140,68 @@ impl<
             let category_attrs_repo = repo_factory.create_category_attrs_repo(&*conn, user_id);
             let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
 
-            let attribute_values = attribute_values_repo.find_many(AttributeValuesSearchTerms {
-                attr_id: Some(attribute_id),
-                ..Default::default()
-            })?;
-            if !attribute_values.is_empty() {
-                return Err(format_err!(
-                    "Can not delete attribute - attribute has {} attribute values",
-                    attribute_values.len()
-                ));
-            }
-
-            let cat_attrs = category_attrs_repo.find_all_attributes_by_attribute_id(attribute_id)?;
-            if !cat_attrs.is_empty() {
-                return Err(format_err!(
-                    "Can not delete attribute - attribute is used in {} categories",
-                    cat_attrs.len()
-                ));
-            }
+            validate_attribute_delete(&*attribute_values_repo, &*category_attrs_repo, attribute_id)?;
 
             attributes_repo.delete(attribute_id)?;
 
             Ok(())
         })
     }
+
+    /// Returns what deleting the attribute changes, nothing is deleted
+    fn delete_attribute_impact(&self, attribute_id: AttributeId) -> ServiceFuture<ImpactReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let category_attrs_repo = repo_factory.create_category_attrs_repo(&*conn, user_id);
+            let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
+            let impact_reports_repo = repo_factory.create_impact_reports_repo(&*conn, user_id);
+
+            validate_attribute_delete(&*attribute_values_repo, &*category_attrs_repo, attribute_id)?;
+
+            impact_reports_repo
+                .attribute_deletion(attribute_id)
+                .map_err(|e| e.context("Service Attributes, delete_attribute_impact endpoint error occurred.").into())
+        })
+    }
+}
+
+/// Attribute is deleted only when neither attribute values nor categories use it
+fn validate_attribute_delete(
+    attribute_values_repo: &AttributeValuesRepo,
+    category_attrs_repo: &CategoryAttrsRepo,
+    attribute_id: AttributeId,
+) -> Result<(), FailureError> {
+    let attribute_values = attribute_values_repo.find_many(AttributeValuesSearchTerms {
+        attr_id: Some(attribute_id),
+        ..Default::default()
+    })?;
+    if !attribute_values.is_empty() {
+        return Err(format_err!(
+            "Can not delete attribute - attribute has {} attribute values",
+            attribute_values.len()
+        ));
+    }
+
+    let cat_attrs = category_attrs_repo.find_all_attributes_by_attribute_id(attribute_id)?;
+    if !cat_attrs.is_empty() {
+        return Err(format_err!(
+            "Can not delete attribute - attribute is used in {} categories",
+            cat_attrs.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks meta field against the schema of the attribute type
+fn validate_meta_field(value_type: &AttributeType, meta_field: &serde_json::Value) -> Result<(), FailureError> {
+    AttributeMetaSchema::new(value_type.clone()).validate(meta_field).map_err(|errors| {
+        format_err!("Meta field {} does not match schema of attribute type {:?}", meta_field, value_type)
+            .context(Error::Validate(errors))
+            .into()
+    })
 }
 
 fn create_attribute_values(
@@ -235,6 +305,7 @@ pub mod tests {
                 values: Some(vec!["45".to_string(), "46".to_string()]),
                 translated_values: None,
                 ui_element: serde_json::Value::Null,
+                unit: None,
             }),
             values: Some(vec![]),
             uuid: uuid::Uuid::new_v4(),
@@ -280,4 +351,34 @@ pub mod tests {
         assert_eq!(result.id, AttributeId(1));
     }
 
+    #[test]
+    fn test_update_with_invalid_meta_field() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let mut new_attribute = create_update_attribute(MOCK_BASE_PRODUCT_NAME_JSON);
+        new_attribute.meta_field = Some(json!({"ui_element": "Range", "unit": "cm"}));
+        let work = service.update_attribute(AttributeId(1), new_attribute);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_get_attribute_schema() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_attribute_schema(AttributeId(1));
+        let result = core.run(work).unwrap().unwrap();
+        assert_eq!(result.value_type, AttributeType::Str);
+        assert!(result.fields.iter().all(|field| field.name != "unit"));
+    }
+
+    #[test]
+    fn test_delete_attribute_impact_of_used_attribute() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.delete_attribute_impact(AttributeId(1));
+        assert!(core.run(work).is_err());
+    }
 }
@@ -1,5 +1,5 @@
 //! Base product service
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
@@ -8,27 +8,48 @@ use failure::Error as FailureError;
 use futures::future;
 use futures::future::*;
 use r2d2::ManageConnection;
+use serde_json;
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
 
-use stq_static_resources::{Currency, ModerationStatus};
-use stq_types::{BaseProductId, BaseProductSlug, CategoryId, ExchangeRate, ProductId, StoreId, StoreIdentifier};
+use stq_static_resources::{Currency, ModerationStatus, Translation};
+use stq_types::{BaseProductId, BaseProductSlug, CategoryId, ExchangeRate, ProductId, SagaId, StoreId, StoreIdentifier, UserId};
 
 use super::types::ServiceFuture;
+use config::FacetLimits;
+use degradation::{self, Degradation};
 use elastic::{ProductsElastic, ProductsElasticImpl};
 use errors::Error;
 use models::*;
+use notifiers::{create_notifier, send_events};
 use repos::clear_child_categories;
 use repos::get_all_children_till_the_end;
 use repos::get_parent_category;
 use repos::remove_unused_categories;
 use repos::{
-    BaseProductsRepo, BaseProductsSearchTerms, CategoriesRepo, ProductAttrsRepo, ProductsRepo, RepoResult, ReposFactory, StoresRepo,
+    AttributeValuesRepo, BaseProductHistoryRepo, BaseProductsRepo, BaseProductsSearchTerms, CategoriesRepo, ProductAttrsRepo, ProductsRepo,
+    RepoResult, ReposFactory, StoreCategoriesRepo, StoresRepo,
 };
 use services::create_product_attributes_values;
-use services::products::calculate_customer_price;
+use services::moderation_checklists::save_checklist_results;
+use services::validate_variant_attributes;
+use services::jobs::JobTracker;
+use services::price_rules::{apply_price_rules, apply_price_rules_to_details};
+use services::products::{calculate_customer_price, convert_seller_price};
 use services::Service;
-use services::{check_can_update_by_status, check_change_status, check_vendor_code};
+use services::{
+    check_can_update_by_status, check_change_status, check_gtin, check_product_quota, check_store_verified, check_vendor_code,
+};
 
 const MAX_PRODUCTS_SEARCH_COUNT: i32 = 1000;
+/// "Did you mean" queries returned with empty search results
+const MAX_SEARCH_SUGGESTIONS_COUNT: i32 = 3;
+
+/// Base products updated in one bulk update
+const MAX_BULK_UPDATE_COUNT: usize = 500;
+
+/// Number of base products moved to another category in one transaction
+const CATEGORY_REASSIGNMENT_BATCH_SIZE: i64 = 100;
 
 pub trait BaseProductsService {
     /// Returns base product count
@@ -40,7 +61,15 @@ pub trait BaseProductsService {
         prod: SearchProductsByName,
         count: i32,
         offset: i32,
-    ) -> ServiceFuture<Vec<BaseProductWithVariants>>;
+    ) -> ServiceFuture<SearchResult<BaseProductWithVariants>>;
+
+    /// Find product by name limited by `count`, continuing after the `token` of the previous page
+    fn search_base_products_after(
+        self,
+        prod: SearchProductsByName,
+        count: i32,
+        token: Option<SearchAfterToken>,
+    ) -> ServiceFuture<BaseProductsSearchPage>;
 
     /// Find product by views limited by `count` and `offset` parameters
     fn search_base_products_most_viewed(
@@ -50,6 +79,13 @@ pub trait BaseProductsService {
         offset: i32,
     ) -> ServiceFuture<Vec<BaseProductWithVariants>>;
 
+    /// Random published products of the category and its children, weighted by rating and views
+    fn sample_base_products(&self, category_id: Option<CategoryId>, count: i32) -> ServiceFuture<Vec<BaseProductWithVariants>>;
+
+    /// Published base products of the same category sharing the most attribute values with the base product,
+    /// completed with products of similar names from elastic when there are fewer than `count` of them
+    fn get_related_base_products(&self, base_product_id: BaseProductId, count: i32) -> ServiceFuture<Vec<BaseProductWithVariants>>;
+
     /// Find product by discount pattern limited by `count` and `offset` parameters
     fn search_base_products_most_discount(
         self,
@@ -58,8 +94,23 @@ pub trait BaseProductsService {
         offset: i32,
     ) -> ServiceFuture<Vec<BaseProductWithVariants>>;
 
-    /// auto complete limited by `count` and `offset` parameters
-    fn base_products_auto_complete(&self, name: AutoCompleteProductName, count: i32, offset: i32) -> ServiceFuture<Vec<String>>;
+    /// auto complete limited by `count` and `offset` parameters, `suggest_corrections` tolerates typos in the name
+    fn base_products_auto_complete(
+        &self,
+        name: AutoCompleteProductName,
+        count: i32,
+        offset: i32,
+        suggest_corrections: bool,
+    ) -> ServiceFuture<Vec<String>>;
+
+    /// Auto complete with product names, categories and attribute values matching the name
+    fn base_products_auto_complete_suggestions(
+        &self,
+        name: AutoCompleteProductName,
+        count: i32,
+        offset: i32,
+        suggest_corrections: bool,
+    ) -> ServiceFuture<Vec<SearchSuggestion>>;
 
     /// search filters
     fn search_base_products_filters_price(self, search_prod: SearchProductsByName) -> ServiceFuture<RangeFilter>;
@@ -70,14 +121,20 @@ pub trait BaseProductsService {
     /// search filters
     fn search_base_products_attributes(&self, search_prod: SearchProductsByName) -> ServiceFuture<Option<Vec<AttributeFilter>>>;
 
+    /// Page of values of a single attribute facet in the search
+    fn search_facet_values(&self, payload: SearchFacetValues) -> ServiceFuture<FacetValues>;
+
     /// search filters
     fn search_base_products_filters_count(&self, search_prod: SearchProductsByName) -> ServiceFuture<i32>;
 
     /// Returns product by ID
     fn get_base_product(&self, base_product_id: BaseProductId, visibility: Option<Visibility>) -> ServiceFuture<Option<BaseProduct>>;
 
-    /// Returns products by IDs
-    fn get_base_products(&self, base_product_ids: Vec<BaseProductId>) -> ServiceFuture<Vec<BaseProduct>>;
+    /// Returns base products by IDs with variants and their attributes, in the order of requested IDs
+    fn find_many(&self, base_product_ids: Vec<BaseProductId>) -> ServiceFuture<Vec<BaseProductDetails>>;
+
+    /// Returns published base products with variants identical to the payload by attributes
+    fn match_base_products(&self, payload: ProductMatchPayload) -> ServiceFuture<Vec<BaseProductDetails>>;
 
     /// Returns product by ID
     fn get_base_product_without_filters(&self, base_product_id: BaseProductId) -> ServiceFuture<Option<BaseProduct>>;
@@ -90,14 +147,22 @@ pub trait BaseProductsService {
         visibility: Option<Visibility>,
     ) -> ServiceFuture<Option<BaseProduct>>;
 
-    /// Returns base product by ID with update views
-    fn get_base_product_with_views_update(&self, base_product_id: BaseProductId) -> ServiceFuture<Option<BaseProduct>>;
+    /// Returns base product by ID with update views, `viewer_token` identifies anonymous viewers
+    fn get_base_product_with_views_update(
+        &self,
+        base_product_id: BaseProductId,
+        viewer_token: Option<String>,
+    ) -> ServiceFuture<Option<BaseProduct>>;
+
+    /// Returns public card of published base product, the same for every user
+    fn get_embed_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<Option<EmbedBaseProduct>>;
 
-    /// Returns base product by Slug with update views
+    /// Returns base product by Slug with update views, `viewer_token` identifies anonymous viewers
     fn get_base_product_by_slug_with_views_update(
         &self,
         store_identifier: StoreIdentifier,
         base_product_slug: BaseProductSlug,
+        viewer_token: Option<String>,
     ) -> ServiceFuture<Option<BaseProduct>>;
 
     /// Returns base_product by product ID
@@ -110,15 +175,41 @@ pub trait BaseProductsService {
     /// Deactivates specific product
     fn deactivate_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct>;
 
+    /// Restores deactivated base product with variants deactivated together with it
+    fn restore_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct>;
+
+    /// Shows base product to customers
+    fn publish_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct>;
+
+    /// Hides base product from customers, it stays a draft until published again
+    fn unpublish_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct>;
+
+    /// Rolls back base products created under saga id
+    fn compensate_base_products_creation(&self, saga_id: SagaId) -> ServiceFuture<Vec<BaseProduct>>;
+
     /// Creates base product
     fn create_base_product(&self, payload: NewBaseProduct) -> ServiceFuture<BaseProduct>;
 
     /// Creates base product with variants
-    fn create_base_product_with_variants(&self, payload: NewBaseProductWithVariants) -> ServiceFuture<BaseProduct>;
+    fn create_base_product_with_variants(&self, payload: NewBaseProductWithVariants) -> ServiceFuture<BaseProductWithVariants>;
+
+    /// Copies base product with its variants and attributes into a new draft of the same store
+    fn duplicate_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct>;
+
+    /// Moves base product with its variants to another store. For superusers
+    fn transfer_base_product(&self, base_product_id: BaseProductId, payload: TransferBaseProduct) -> ServiceFuture<BaseProduct>;
 
     /// Lists base products limited by `from` and `count` parameters
     fn list_base_products(&self, from: BaseProductId, count: i32, visibility: Option<Visibility>) -> ServiceFuture<Vec<BaseProduct>>;
 
+    /// Returns page of base_products after the cursor, `next_cursor` is set when the page is full
+    fn list_base_products_by_cursor(
+        &self,
+        cursor: Option<BaseProductsCursor>,
+        count: i32,
+        visibility: Option<Visibility>,
+    ) -> ServiceFuture<BaseProductsPage>;
+
     /// Returns list of base_products by store id and exclude base_product_id_arg, limited by 10
     fn get_base_products_of_the_store(
         &self,
@@ -129,9 +220,37 @@ pub trait BaseProductsService {
         visibility: Option<Visibility>,
     ) -> ServiceFuture<Vec<BaseProduct>>;
 
+    /// Returns deactivated base_products of the store to its owner, limited by from and count
+    fn get_archived_base_products_of_the_store(
+        &self,
+        store_id: StoreId,
+        from: BaseProductId,
+        count: i32,
+    ) -> ServiceFuture<Vec<BaseProduct>>;
+
+    /// Replaces storefront order of products of the store, pinned products go first
+    fn set_store_products_order(&self, store_id: StoreId, payload: SetStoreProductsOrder) -> ServiceFuture<Vec<StoreProductPosition>>;
+
     /// Updates base product
     fn update_base_product(&self, base_product_id: BaseProductId, payload: UpdateBaseProduct) -> ServiceFuture<BaseProduct>;
 
+    /// Returns change history of the base product, the latest first
+    fn get_base_product_history(&self, base_product_id: BaseProductId) -> ServiceFuture<Vec<BaseProductHistoryEntry>>;
+
+    /// Applies updates to base products of the store in one transaction, nothing is updated if any of them is invalid
+    fn update_base_products_of_store(
+        &self,
+        store_id: StoreId,
+        updates: Vec<BaseProductBulkUpdate>,
+    ) -> ServiceFuture<BaseProductsBulkUpdateResult>;
+
+    /// Deactivates base products of the store matching the filter in background job, dry run only counts them
+    fn deactivate_base_products_of_store(
+        &self,
+        store_id: StoreId,
+        payload: BaseProductsBulkDeactivation,
+    ) -> ServiceFuture<BaseProductsBulkDeactivationResult>;
+
     /// Cart
     fn find_by_cart(&self, cart: Vec<CartProduct>) -> ServiceFuture<Vec<StoreWithBaseProducts>>;
 
@@ -151,8 +270,13 @@ pub trait BaseProductsService {
         status: ModerationStatus,
     ) -> ServiceFuture<Vec<BaseProduct>>;
 
-    /// Set moderation status for base_product_id
-    fn set_moderation_status_base_product(&self, base_product_id: BaseProductId, status: ModerationStatus) -> ServiceFuture<BaseProduct>;
+    /// Set moderation status for base_product_id, checklist results are saved when the base product is declined
+    fn set_moderation_status_base_product(
+        &self,
+        base_product_id: BaseProductId,
+        status: ModerationStatus,
+        checklist: Vec<ChecklistResult>,
+    ) -> ServiceFuture<BaseProduct>;
 
     /// send base product to moderation from store manager
     fn send_base_product_to_moderation(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct>;
@@ -176,11 +300,17 @@ pub trait BaseProductsService {
     /// Create currency map
     fn create_currency_map(&self, options: Option<ProductsSearchOptions>) -> ServiceFuture<Option<ProductsSearchOptions>>;
 
+    /// Sets storefront order of the store to options of search in the store, explicit sorting takes precedence
+    fn create_store_order(&self, options: Option<ProductsSearchOptions>) -> ServiceFuture<Option<ProductsSearchOptions>>;
+
     /// Replace category in all base products
     fn replace_category(&self, payload: CategoryReplacePayload) -> ServiceFuture<Vec<BaseProduct>>;
 
     /// Check that you can update base product
     fn validate_update_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<bool>;
+
+    /// Starts background job moving filtered base products to another category, its progress is polled at `/jobs/<job_id>`
+    fn start_category_reassignment(&self, payload: CategoryReassignment) -> ServiceFuture<Job>;
 }
 
 impl<
@@ -209,31 +339,80 @@ impl<
         mut search_product: SearchProductsByName,
         count: i32,
         offset: i32,
-    ) -> ServiceFuture<Vec<BaseProductWithVariants>> {
+    ) -> ServiceFuture<SearchResult<BaseProductWithVariants>> {
         let repo_factory = self.static_context.repo_factory.clone();
         let user_id = self.dynamic_context.user_id;
         let client_handle = self.static_context.client_handle.clone();
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
-        let address = self.static_context.config.server.elastic.clone();
-        let products_el = ProductsElasticImpl::new(client_handle, address);
+        let price_rules_config = self.static_context.config.price_rules.clone();
+        let address = self.static_context.elastic_pool.address();
+        let boosts = self.static_context.config.search().boosts;
+        let suggest_el = ProductsElasticImpl::new(client_handle.clone(), address.clone());
+        let products_el = ProductsElasticImpl::new(client_handle, address).with_boosts(boosts);
+        let elastic_degraded = self.static_context.elastic_pool.is_degraded();
+        let name = search_product.name.clone();
         let service = self.clone();
+        let store_order_service = self.clone();
         Box::new(
             self.flatten_categories(search_product.options.clone())
                 .and_then(move |options| self.create_currency_map(options))
+                .and_then(move |options| store_order_service.create_store_order(options))
                 .and_then(move |options| {
                     search_product.options = options;
                     products_el.search_by_name(search_product, count, offset)
                 })
+                .map(move |mut search_result| {
+                    if elastic_degraded {
+                        degradation::add(&mut search_result.degraded, Degradation::Search);
+                    }
+                    search_result
+                })
+                .and_then(move |search_result| -> ServiceFuture<SearchResult<ElasticProduct>> {
+                    if search_result.total > 0 || name.trim().is_empty() {
+                        return Box::new(future::ok(search_result));
+                    }
+                    // Suggestions are optional, failing to get them must not fail the search
+                    Box::new(suggest_el.suggest(name, MAX_SEARCH_SUGGESTIONS_COUNT).then(move |suggestions| {
+                        let mut degraded = search_result.degraded;
+                        let suggestions = suggestions.unwrap_or_else(|e| {
+                            error!("Search suggestions are not available: {:?}", e);
+                            degradation::add(&mut degraded, Degradation::Search);
+                            vec![]
+                        });
+                        Ok::<_, FailureError>(SearchResult {
+                            suggestions,
+                            degraded,
+                            ..search_result
+                        })
+                    }))
+                })
                 .and_then({
-                    move |el_products| {
+                    move |search_result| {
                         service.spawn_on_pool(move |conn| {
                             let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
                             let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
-                            let mut base_products = base_products_repo.convert_from_elastic(el_products)?;
+                            let SearchResult {
+                                total,
+                                took_ms,
+                                items,
+                                suggestions,
+                                mut degraded,
+                            } = search_result;
+                            let mut base_products = base_products_repo.convert_from_elastic(items)?;
                             let latest_currencies = currency_exchange.get_latest()?;
-                            calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
-                            Ok(base_products)
+                            if !calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency) {
+                                degradation::add(&mut degraded, Degradation::Currency);
+                            }
+                            apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
+                            degradation::record("search_base_products_by_name", &degraded);
+                            Ok(SearchResult {
+                                total,
+                                took_ms,
+                                items: base_products,
+                                suggestions,
+                                degraded,
+                            })
                         })
                     }
                 })
@@ -244,6 +423,64 @@ impl<
         )
     }
 
+    /// Find product by name limited by `count`, continuing after the `token` of the previous page
+    fn search_base_products_after(
+        self,
+        mut search_product: SearchProductsByName,
+        count: i32,
+        token: Option<SearchAfterToken>,
+    ) -> ServiceFuture<BaseProductsSearchPage> {
+        let repo_factory = self.static_context.repo_factory.clone();
+        let user_id = self.dynamic_context.user_id;
+        let client_handle = self.static_context.client_handle.clone();
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+        let address = self.static_context.elastic_pool.address();
+        let boosts = self.static_context.config.search().boosts;
+        let products_el = ProductsElasticImpl::new(client_handle, address).with_boosts(boosts);
+        let elastic_degraded = self.static_context.elastic_pool.is_degraded();
+        let service = self.clone();
+        let store_order_service = self.clone();
+        Box::new(
+            self.flatten_categories(search_product.options.clone())
+                .and_then(move |options| self.create_currency_map(options))
+                .and_then(move |options| store_order_service.create_store_order(options))
+                .and_then(move |options| {
+                    search_product.options = options;
+                    products_el.search_by_name_after(search_product, count, token)
+                })
+                .and_then({
+                    move |(el_products, next_token)| {
+                        service.spawn_on_pool(move |conn| {
+                            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                            let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+                            let mut base_products = base_products_repo.convert_from_elastic(el_products)?;
+                            let latest_currencies = currency_exchange.get_latest()?;
+                            let mut degraded = vec![];
+                            if elastic_degraded {
+                                degraded.push(Degradation::Search);
+                            }
+                            if !calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency) {
+                                degraded.push(Degradation::Currency);
+                            }
+                            apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
+                            degradation::record("search_base_products_after", &degraded);
+                            Ok(BaseProductsSearchPage {
+                                items: base_products,
+                                next_token: next_token.map(|token| token.encode()),
+                                degraded,
+                            })
+                        })
+                    }
+                })
+                .map_err(|e| {
+                    e.context("Service BaseProduct, search_base_products_after endpoint error occurred.")
+                        .into()
+                }),
+        )
+    }
+
     /// Find product by views limited by `count` and `offset` parameters
     fn search_base_products_most_viewed(
         &self,
@@ -254,6 +491,7 @@ impl<
         let user_id = self.dynamic_context.user_id;
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
         let repo_factory = self.static_context.repo_factory.clone();
 
         self.spawn_on_pool(move |conn| {
@@ -263,6 +501,7 @@ impl<
                 let mut base_products = base_products_repo.most_viewed(search_product, count, offset)?;
                 let latest_currencies = currency_exchange.get_latest()?;
                 calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
                 Ok(base_products)
             }
             .map_err(|e: FailureError| {
@@ -272,6 +511,114 @@ impl<
         })
     }
 
+    /// Random published products of the category and its children, weighted by rating and views
+    fn sample_base_products(&self, category_id: Option<CategoryId>, count: i32) -> ServiceFuture<Vec<BaseProductWithVariants>> {
+        let user_id = self.dynamic_context.user_id;
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+
+                let categories_ids = match category_id {
+                    Some(category_id) => Some(match categories_repo.find(category_id)? {
+                        Some(ref category) if !category.children.is_empty() => {
+                            get_all_children_till_the_end(category.clone()).into_iter().map(|c| c.id).collect()
+                        }
+                        _ => vec![category_id],
+                    }),
+                    None => None,
+                };
+
+                let mut base_products = base_products_repo.sample(categories_ids, count)?;
+                let latest_currencies = currency_exchange.get_latest()?;
+                calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
+                Ok(base_products)
+            }
+            .map_err(|e: FailureError| e.context("Service BaseProduct, sample_base_products endpoint error occurred.").into())
+        })
+    }
+
+    /// Published base products of the same category sharing the most attribute values with the base product,
+    /// completed with products of similar names from elastic when there are fewer than `count` of them
+    fn get_related_base_products(&self, base_product_id: BaseProductId, count: i32) -> ServiceFuture<Vec<BaseProductWithVariants>> {
+        let user_id = self.dynamic_context.user_id;
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+        let repo_factory = self.static_context.repo_factory.clone();
+        let client_handle = self.static_context.client_handle.clone();
+        let address = self.static_context.elastic_pool.address();
+        let products_el = ProductsElasticImpl::new(client_handle, address);
+        let service = self.clone();
+
+        Box::new(
+            self.spawn_on_pool({
+                let repo_factory = repo_factory.clone();
+                move |conn| {
+                    let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                    let base_product = base_products_repo
+                        .find(base_product_id, Visibility::Published)?
+                        .ok_or_else(|| format_err!("Base product {} not found", base_product_id).context(Error::NotFound))?;
+                    let related = base_products_repo.find_related(base_product_id, count)?;
+                    Ok((base_product, related))
+                }
+            })
+            .and_then(move |(base_product, related)| -> ServiceFuture<Vec<BaseProductWithVariants>> {
+                let missing = count - related.len() as i32;
+                let names = base_product_names(&base_product);
+                if missing <= 0 || names.is_empty() {
+                    return Box::new(future::ok(related));
+                }
+
+                let mut exclude = related.iter().map(|related| related.base_product.id).collect::<Vec<_>>();
+                exclude.push(base_product_id);
+                Box::new(
+                    products_el
+                        .more_like_this(names, base_product.category_id, exclude, missing)
+                        .then(move |el_products| -> ServiceFuture<Vec<BaseProductWithVariants>> {
+                            match el_products {
+                                Ok(el_products) => service.spawn_on_pool(move |conn| {
+                                    let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                                    let mut related = related;
+                                    related.extend(base_products_repo.convert_from_elastic(el_products)?);
+                                    Ok(related)
+                                }),
+                                Err(e) => {
+                                    warn!("Related base products of {} are found without elastic: {}", base_product_id, e);
+                                    degradation::record("get_related_base_products", &[Degradation::Search]);
+                                    Box::new(future::ok(related))
+                                }
+                            }
+                        }),
+                )
+            })
+            .and_then({
+                let service = self.clone();
+                let repo_factory = self.static_context.repo_factory.clone();
+                move |mut related| {
+                    service.spawn_on_pool(move |conn| {
+                        let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+                        let latest_currencies = currency_exchange.get_latest()?;
+                        calculate_base_products_customer_price(&mut related, latest_currencies, currency, fiat_currency);
+                        apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut related)?;
+                        Ok(related)
+                    })
+                }
+            })
+            .map_err(|e: FailureError| {
+                e.context("Service BaseProduct, get_related_base_products endpoint error occurred.")
+                    .into()
+            }),
+        )
+    }
+
     /// Find product by discount pattern limited by `count` and `offset` parameters
     fn search_base_products_most_discount(
         self,
@@ -280,12 +627,13 @@ impl<
         offset: i32,
     ) -> ServiceFuture<Vec<BaseProductWithVariants>> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let products_el = ProductsElasticImpl::new(client_handle, address);
 
         let user_id = self.dynamic_context.user_id;
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
         let repo_factory = self.static_context.repo_factory.clone();
         Box::new(
             self.flatten_categories(search_product.options.clone())
@@ -301,6 +649,7 @@ impl<
                             let mut base_products = base_products_repo.convert_from_elastic(el_products)?;
                             let latest_currencies = currency_exchange.get_latest()?;
                             calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                            apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
                             Ok(base_products)
                         })
                     }
@@ -312,12 +661,24 @@ impl<
         )
     }
 
-    fn base_products_auto_complete(&self, name: AutoCompleteProductName, count: i32, offset: i32) -> ServiceFuture<Vec<String>> {
+    fn base_products_auto_complete(
+        &self,
+        name: AutoCompleteProductName,
+        count: i32,
+        offset: i32,
+        suggest_corrections: bool,
+    ) -> ServiceFuture<Vec<String>> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
+        let fuzziness = self.static_context.config.search().auto_complete;
+        let language = self.dynamic_context.language;
         let products_names = {
             let products_el = ProductsElasticImpl::new(client_handle, address);
-            products_el.auto_complete(name, count, offset)
+            if suggest_corrections {
+                products_el.auto_complete_fuzzy(name, count, fuzziness, language)
+            } else {
+                products_el.auto_complete(name, count, offset, language)
+            }
         };
 
         Box::new(products_names.map_err(|e| {
@@ -326,9 +687,65 @@ impl<
         }))
     }
 
+    /// Auto complete with product names, categories and attribute values matching the name
+    fn base_products_auto_complete_suggestions(
+        &self,
+        name: AutoCompleteProductName,
+        count: i32,
+        offset: i32,
+        suggest_corrections: bool,
+    ) -> ServiceFuture<Vec<SearchSuggestion>> {
+        let client_handle = self.static_context.client_handle.clone();
+        let address = self.static_context.elastic_pool.address();
+        let fuzziness = self.static_context.config.search().auto_complete;
+        let language = self.dynamic_context.language;
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let term = name.name.clone();
+
+        let products_names = {
+            let products_el = ProductsElasticImpl::new(client_handle, address);
+            if suggest_corrections {
+                products_el.auto_complete_fuzzy(name, count, fuzziness, language)
+            } else {
+                products_el.auto_complete(name, count, offset, language)
+            }
+        };
+
+        let categories_and_values = self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
+
+            let root = categories_repo.get_all_categories_with_products()?;
+            let mut suggestions = find_categories_by_name(&root, &term, count as usize)
+                .into_iter()
+                .map(SearchSuggestion::from)
+                .collect::<Vec<_>>();
+            let values = attribute_values_repo.search_by_name(term, i64::from(count))?;
+            suggestions.extend(values.into_iter().map(SearchSuggestion::from));
+            Ok(suggestions)
+        });
+
+        Box::new(
+            products_names
+                .join(categories_and_values)
+                .map(|(products_names, categories_and_values)| {
+                    products_names
+                        .into_iter()
+                        .map(|name| SearchSuggestion::Product { name })
+                        .chain(categories_and_values)
+                        .collect()
+                })
+                .map_err(|e| {
+                    e.context("Service BaseProduct, base_products_auto_complete_suggestions endpoint error occurred.")
+                        .into()
+                }),
+        )
+    }
+
     fn search_base_products_filters_price(self, mut search_product: SearchProductsByName) -> ServiceFuture<RangeFilter> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let products_el = ProductsElasticImpl::new(client_handle, address);
         Box::new(
             self.flatten_categories(search_product.options.clone())
@@ -347,7 +764,7 @@ impl<
     /// search filters
     fn search_base_products_filters_count(&self, mut search_prod: SearchProductsByName) -> ServiceFuture<i32> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let products_el = ProductsElasticImpl::new(client_handle, address);
         Box::new(
             self.flatten_categories(search_prod.options.clone())
@@ -365,7 +782,7 @@ impl<
     /// search filters
     fn search_base_products_filters_category(self, search_prod: SearchProductsByName) -> ServiceFuture<Category> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
 
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
@@ -409,8 +826,9 @@ impl<
     /// search filters
     fn search_base_products_attributes(&self, mut search_product: SearchProductsByName) -> ServiceFuture<Option<Vec<AttributeFilter>>> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let products_el = ProductsElasticImpl::new(client_handle, address);
+        let facet_limits = self.static_context.config.search().facets;
         Box::new(
             self.remove_non_third_level_categories(search_product.options.clone())
                 .and_then(move |options| -> ServiceFuture<Option<Vec<AttributeFilter>>> {
@@ -420,7 +838,7 @@ impl<
                             return Box::new(
                                 products_el
                                     .search_by_name(search_product, MAX_PRODUCTS_SEARCH_COUNT, 0)
-                                    .map(get_attribute_filters),
+                                    .map(move |search_result| get_attribute_filters(search_result.items, &facet_limits)),
                             );
                         }
                     }
@@ -433,6 +851,33 @@ impl<
         )
     }
 
+    /// Page of values of a single attribute facet in the search
+    fn search_facet_values(&self, mut payload: SearchFacetValues) -> ServiceFuture<FacetValues> {
+        let client_handle = self.static_context.client_handle.clone();
+        let address = self.static_context.elastic_pool.address();
+        let products_el = ProductsElasticImpl::new(client_handle, address);
+        let attribute_id = payload.attribute_id;
+        let offset = payload.offset as usize;
+        let count = payload.count as usize;
+        Box::new(
+            self.remove_non_third_level_categories(payload.search.options.clone())
+                .and_then(move |options| {
+                    payload.search.options = options;
+                    products_el
+                        .search_by_name(payload.search, MAX_PRODUCTS_SEARCH_COUNT, 0)
+                        .map(move |search_result| {
+                            let (mut equal_attrs, _) = collect_attribute_values(search_result.items);
+                            let values = equal_attrs
+                                .remove(&attribute_id)
+                                .map(|values| values.into_iter().collect())
+                                .unwrap_or_default();
+                            FacetValues::new(attribute_id, values, offset, count)
+                        })
+                })
+                .map_err(|e| e.context("Service BaseProduct, search_facet_values endpoint error occurred.").into()),
+        )
+    }
+
     /// Returns product by ID
     fn get_base_product(&self, base_product_id: BaseProductId, visibility: Option<Visibility>) -> ServiceFuture<Option<BaseProduct>> {
         let user_id = self.dynamic_context.user_id;
@@ -441,25 +886,79 @@ impl<
 
         debug!("Get base product by id = {:?} with visibility = {:?}", base_product_id, visibility);
 
-        self.spawn_on_pool(move |conn| {
-            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+        self.spawn_on_replica(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_read_repo(&*conn, user_id);
             base_products_repo
                 .find(base_product_id, visibility)
                 .map_err(|e| e.context("Service BaseProduct, get_base_product endpoint error occurred.").into())
         })
     }
 
-    /// Returns products by IDs
-    fn get_base_products(&self, base_product_ids: Vec<BaseProductId>) -> ServiceFuture<Vec<BaseProduct>> {
+    /// Returns base products by IDs with variants and their attributes, in the order of requested IDs
+    fn find_many(&self, base_product_ids: Vec<BaseProductId>) -> ServiceFuture<Vec<BaseProductDetails>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
 
-        debug!("Get base products by ids ({})", base_product_ids.len());
+        debug!("Find base products with variants by ids ({})", base_product_ids.len());
         self.spawn_on_pool(move |conn| {
-            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
-            base_products_repo
-                .find_many(base_product_ids)
-                .map_err(|e| e.context("Service BaseProduct, get_base_products endpoint error occurred.").into())
+            {
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+
+                let mut seen = HashSet::new();
+                let base_product_ids = base_product_ids
+                    .into_iter()
+                    .filter(|base_product_id| seen.insert(*base_product_id))
+                    .collect::<Vec<_>>();
+
+                let mut found = base_products_repo
+                    .find_many_with_variants(base_product_ids.clone())?
+                    .into_iter()
+                    .map(|catalog| (catalog.base_product.id, BaseProductDetails::from(catalog)))
+                    .collect::<HashMap<_, _>>();
+                let mut base_products = base_product_ids
+                    .into_iter()
+                    .filter_map(|base_product_id| found.remove(&base_product_id))
+                    .collect::<Vec<_>>();
+
+                let latest_currencies = currency_exchange.get_latest()?;
+                calculate_base_product_details_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                apply_price_rules_to_details(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
+                Ok(base_products)
+            }
+            .map_err(|e: FailureError| e.context("Service BaseProduct, find_many endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns published base products with variants matching attribute fingerprint of the payload
+    fn match_base_products(&self, payload: ProductMatchPayload) -> ServiceFuture<Vec<BaseProductDetails>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+
+        debug!("Match base products by attributes {:?}", payload);
+        self.spawn_on_pool(move |conn| {
+            {
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+
+                let mut base_products = base_products_repo
+                    .find_by_attribute_fingerprint(payload.category_id, payload.fingerprint())?
+                    .into_iter()
+                    .map(BaseProductDetails::from)
+                    .collect::<Vec<_>>();
+
+                let latest_currencies = currency_exchange.get_latest()?;
+                calculate_base_product_details_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                apply_price_rules_to_details(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
+                Ok(base_products)
+            }
+            .map_err(|e: FailureError| e.context("Service BaseProduct, match_base_products endpoint error occurred.").into())
         })
     }
 
@@ -483,14 +982,44 @@ impl<
         })
     }
 
-    /// Returns base product by ID with update views
-    fn get_base_product_with_views_update(&self, base_product_id: BaseProductId) -> ServiceFuture<Option<BaseProduct>> {
+    /// Returns public card of published base product, repos are created without user so ACL is the same for everyone
+    fn get_embed_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<Option<EmbedBaseProduct>> {
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, None);
+                let products_repo = repo_factory.create_product_repo(&*conn, None);
+                let stores_repo = repo_factory.create_stores_repo(&*conn, None);
+
+                let base_product = match base_products_repo.find(base_product_id, Visibility::Published)? {
+                    Some(base_product) => base_product,
+                    None => return Ok(None),
+                };
+                let store = match stores_repo.find(base_product.store_id, Visibility::Published)? {
+                    Some(store) => store,
+                    None => return Ok(None),
+                };
+                let variants = products_repo.find_with_base_id(base_product_id)?;
+                Ok(Some(EmbedBaseProduct::new(base_product, variants, store)))
+            }
+            .map_err(|e: FailureError| e.context("Service BaseProduct, get_embed_base_product endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns base product by ID with update views, `viewer_token` identifies anonymous viewers
+    fn get_base_product_with_views_update(
+        &self,
+        base_product_id: BaseProductId,
+        viewer_token: Option<String>,
+    ) -> ServiceFuture<Option<BaseProduct>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
+        let viewer_hash = Viewer::new(user_id, viewer_token).map(|viewer| viewer.hash());
 
         self.spawn_on_pool(move |conn| {
             let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
-            base_products_repo.update_views(base_product_id).map_err(|e| {
+            base_products_repo.update_views(base_product_id, viewer_hash).map_err(|e| {
                 e.context("Service BaseProduct, get_base_product_with_views_update endpoint error occurred.")
                     .into()
             })
@@ -507,6 +1036,7 @@ impl<
         let repo_factory = self.static_context.repo_factory.clone();
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
         let visibility = visibility.unwrap_or(Visibility::Published);
 
         debug!(
@@ -528,6 +1058,7 @@ impl<
                         let mut base_products = vec![base_product];
                         let latest_currencies = currency_exchange.get_latest()?;
                         calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                        apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
                         return Ok(base_products.pop());
                     };
                 }
@@ -550,19 +1081,13 @@ impl<
             let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
             let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
             let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
             conn.transaction::<BaseProduct, FailureError, _>(move || {
-                let prod = base_products_repo.deactivate(base_product_id)?;
-                let _ = products_repo.deactivate_by_base_product(base_product_id)?;
-                // update product categories of the store
-                let store = stores_repo.find(prod.store_id, Visibility::Active)?;
-                if let Some(store) = store {
-                    let category_root = categories_repo.get_all_categories()?;
-                    let cat = get_first_level_category(prod.category_id, category_root)?;
-                    let service_update_store =
-                        ServiceUpdateStore::delete_category_from_product_categories(store.product_categories.clone(), cat.id);
-                    let _ = stores_repo.update_service_fields(store.id, service_update_store)?;
-                };
-                Ok(prod)
+                deactivate_with_variants(&*base_products_repo, &*products_repo, &*stores_repo, &*categories_repo, base_product_id)
+            })
+            .map(|base_product| {
+                catalog_health_repo.invalidate(base_product.store_id);
+                base_product
             })
             .map_err(|e: FailureError| {
                 e.context("Service BaseProduct, deactivate_base_product endpoint error occurred.")
@@ -571,28 +1096,162 @@ impl<
         })
     }
 
-    /// Lists base products limited by `from` and `count` parameters
-    fn list_base_products(&self, from: BaseProductId, count: i32, visibility: Option<Visibility>) -> ServiceFuture<Vec<BaseProduct>> {
+    /// Restores deactivated base product with variants deactivated together with it
+    fn restore_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct> {
         let user_id = self.dynamic_context.user_id;
+        let quota_config = self.static_context.config.product_quota.clone();
         let repo_factory = self.static_context.repo_factory.clone();
-        let visibility = visibility.unwrap_or(Visibility::Published);
 
-        debug!(
-            "List base products from id = {:?} with count = {}, visibility = {:?}",
-            from, count, visibility
-        );
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+            conn.transaction::<BaseProduct, FailureError, _>(move || {
+                let filters = BaseProductsSearchTerms {
+                    is_active: Some(false),
+                    ..Default::default()
+                };
+                let deactivated = base_products_repo
+                    .find_by_filters(base_product_id, filters)?
+                    .ok_or_else(|| format_err!("Deactivated base product {} not found", base_product_id).context(Error::NotFound))?;
+                check_product_quota(quota_config.as_ref(), &*stores_repo, &*base_products_repo, deactivated.store_id)?;
+                let prod = base_products_repo.activate(base_product_id)?;
+                let _ = products_repo.activate_by_base_product(base_product_id)?;
+                add_product_categories(&*stores_repo, &*categories_repo, prod.store_id, prod.category_id)?;
+                Ok(prod)
+            })
+            .map(|base_product| {
+                catalog_health_repo.invalidate(base_product.store_id);
+                base_product
+            })
+            .map_err(|e: FailureError| e.context("Service BaseProduct, restore_base_product endpoint error occurred.").into())
+        })
+    }
+
+    /// Shows base product to customers
+    fn publish_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
 
         self.spawn_on_pool(move |conn| {
             let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
             base_products_repo
-                .list(from, count, visibility)
-                .map_err(|e| e.context("Service BaseProduct, list endpoint error occurred.").into())
+                .set_published(base_product_id, true)
+                .map_err(|e: FailureError| e.context("Service BaseProduct, publish_base_product endpoint error occurred.").into())
         })
     }
 
-    /// Returns list of base_products by store id and exclude skip_base_product_id, limited by from and count
-    fn get_base_products_of_the_store(
-        &self,
+    /// Hides base product from customers, it stays a draft until published again
+    fn unpublish_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            base_products_repo
+                .set_published(base_product_id, false)
+                .map_err(|e: FailureError| e.context("Service BaseProduct, unpublish_base_product endpoint error occurred.").into())
+        })
+    }
+
+    /// Rolls back base products created under saga id. Already rolled back
+    /// base products are skipped, so the saga can retry compensation safely.
+    fn compensate_base_products_creation(&self, saga_id: SagaId) -> ServiceFuture<Vec<BaseProduct>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        info!("Compensate base products creation for saga {}", saga_id);
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+            conn.transaction::<Vec<BaseProduct>, FailureError, _>(move || {
+                let base_products = base_products_repo.deactivate_by_saga_id(saga_id)?;
+                for base_product in &base_products {
+                    let _ = products_repo.deactivate_by_base_product(base_product.id)?;
+                    let store = stores_repo.find(base_product.store_id, Visibility::Active)?;
+                    if let Some(store) = store {
+                        let category_root = categories_repo.get_all_categories()?;
+                        let cat = get_first_level_category(base_product.category_id, category_root)?;
+                        let service_update_store =
+                            ServiceUpdateStore::delete_category_from_product_categories(store.product_categories.clone(), cat.id);
+                        let _ = stores_repo.update_service_fields(store.id, service_update_store)?;
+                    };
+                }
+                Ok(base_products)
+            })
+            .map(|base_products| {
+                let store_ids: HashSet<StoreId> = base_products.iter().map(|base_product| base_product.store_id).collect();
+                for store_id in store_ids {
+                    catalog_health_repo.invalidate(store_id);
+                }
+                base_products
+            })
+            .map_err(|e: FailureError| {
+                e.context("Service BaseProduct, compensate_base_products_creation endpoint error occurred.")
+                    .into()
+            })
+        })
+    }
+
+    /// Lists base products limited by `from` and `count` parameters
+    fn list_base_products(&self, from: BaseProductId, count: i32, visibility: Option<Visibility>) -> ServiceFuture<Vec<BaseProduct>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let visibility = visibility.unwrap_or(Visibility::Published);
+
+        debug!(
+            "List base products from id = {:?} with count = {}, visibility = {:?}",
+            from, count, visibility
+        );
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            base_products_repo
+                .list(BaseProductsListFrom::Id(from), count, visibility)
+                .map_err(|e| e.context("Service BaseProduct, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns page of base_products after the cursor, `next_cursor` is set when the page is full
+    fn list_base_products_by_cursor(
+        &self,
+        cursor: Option<BaseProductsCursor>,
+        count: i32,
+        visibility: Option<Visibility>,
+    ) -> ServiceFuture<BaseProductsPage> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let visibility = visibility.unwrap_or(Visibility::Published);
+
+        debug!(
+            "List base products after cursor = {:?} with count = {}, visibility = {:?}",
+            cursor, count, visibility
+        );
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            base_products_repo
+                .list(BaseProductsListFrom::Cursor(cursor), count, visibility)
+                .map(|items| {
+                    let next_cursor = if items.len() as i32 == count {
+                        items.last().map(|base_product| BaseProductsCursor::new(base_product).encode())
+                    } else {
+                        None
+                    };
+                    BaseProductsPage { items, next_cursor }
+                })
+                .map_err(|e| e.context("Service BaseProduct, list_by_cursor endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns list of base_products by store id and exclude skip_base_product_id, limited by from and count
+    fn get_base_products_of_the_store(
+        &self,
         store_id: StoreId,
         skip_base_product_id: Option<BaseProductId>,
         from: BaseProductId,
@@ -617,20 +1276,89 @@ impl<
         })
     }
 
+    /// Returns deactivated base_products of the store to its owner, limited by from and count
+    fn get_archived_base_products_of_the_store(
+        &self,
+        store_id: StoreId,
+        from: BaseProductId,
+        count: i32,
+    ) -> ServiceFuture<Vec<BaseProduct>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        debug!(
+            "Get archived base products of the store with id = {:?}, from id = {:?}, count = {}",
+            store_id, from, count
+        );
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            base_products_repo
+                .get_products_of_the_store(store_id, None, from, count, Visibility::Deactivated)
+                .map_err(|e| {
+                    e.context("Service BaseProduct, get_archived_base_products_of_the_store endpoint error occurred.")
+                        .into()
+                })
+        })
+    }
+
+    /// Replaces storefront order of products of the store, pinned products go first
+    fn set_store_products_order(&self, store_id: StoreId, payload: SetStoreProductsOrder) -> ServiceFuture<Vec<StoreProductPosition>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let store_product_positions_repo = repo_factory.create_store_product_positions_repo(&*conn, user_id);
+            conn.transaction::<Vec<StoreProductPosition>, FailureError, _>(move || {
+                let base_product_ids = payload.products.iter().map(|item| item.base_product_id).collect::<Vec<_>>();
+                let unique_ids = base_product_ids.iter().map(|base_product_id| base_product_id.0).collect::<HashSet<_>>();
+                if unique_ids.len() != base_product_ids.len() {
+                    return Err(format_err!("Order {:?} of store {} lists base products more than once", base_product_ids, store_id)
+                        .context(Error::Validate(validation_errors!({
+                            "products": ["unique" => "Every base product must be listed once"]
+                        })))
+                        .into());
+                }
+
+                let store_products_count = base_products_repo
+                    .find_many(base_product_ids.clone())?
+                    .into_iter()
+                    .filter(|base_product| base_product.store_id == store_id)
+                    .count();
+                if store_products_count != base_product_ids.len() {
+                    return Err(format_err!("Order {:?} lists base products not of store {}", base_product_ids, store_id)
+                        .context(Error::Validate(validation_errors!({
+                            "products": ["not_found" => "Base product not found in the store"]
+                        })))
+                        .into());
+                }
+
+                store_product_positions_repo.replace(store_id, payload.into_positions(store_id))
+            })
+            .map_err(|e: FailureError| e.context("Service BaseProduct, set_store_products_order endpoint error occurred.").into())
+        })
+    }
+
     /// Creates new base product
     fn create_base_product(&self, mut payload: NewBaseProduct) -> ServiceFuture<BaseProduct> {
         let user_id = self.dynamic_context.user_id;
+        let quota_config = self.static_context.config.product_quota.clone();
 
         let repo_factory = self.static_context.repo_factory.clone();
         self.spawn_on_pool(move |conn| {
             let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
             let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
             let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, user_id);
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
             conn.transaction::<(BaseProduct), FailureError, _>(move || {
                 //validate
                 validate_base_product(&*base_products_repo, &payload)?;
                 //enrich
                 enrich_new_base_product(&*stores_repo, &mut payload)?;
+                check_product_quota(quota_config.as_ref(), &*stores_repo, &*base_products_repo, payload.store_id)?;
+                check_store_category(&*store_categories_repo, &*categories_repo, payload.store_id, payload.category_id)?;
                 // create base_product
                 let base_prod = base_products_repo.create(payload)?;
 
@@ -639,13 +1367,20 @@ impl<
 
                 Ok(base_prod)
             })
+            .map(|base_product| {
+                catalog_health_repo.invalidate(base_product.store_id);
+                base_product
+            })
             .map_err(|e| e.context("Service BaseProduct, create endpoint error occurred.").into())
         })
     }
 
     /// Creates base product with variants
-    fn create_base_product_with_variants(&self, payload: NewBaseProductWithVariants) -> ServiceFuture<BaseProduct> {
+    fn create_base_product_with_variants(&self, payload: NewBaseProductWithVariants) -> ServiceFuture<BaseProductWithVariants> {
         let user_id = self.dynamic_context.user_id;
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let quota_config = self.static_context.config.product_quota.clone();
 
         let repo_factory = self.static_context.repo_factory.clone();
         let NewBaseProductWithVariants {
@@ -663,12 +1398,22 @@ impl<
             let attr_repo = repo_factory.create_attributes_repo(&*conn, user_id);
             let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
             let custom_attributes_repo = repo_factory.create_custom_attributes_repo(&*conn, user_id);
+            let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+            let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, user_id);
 
-            conn.transaction::<BaseProduct, FailureError, _>(move || {
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+            conn.transaction::<BaseProductWithVariants, FailureError, _>(move || {
                 //validate base_product
                 validate_base_product(&*base_products_repo, &new_base_product)?;
                 //enrich base_product
                 enrich_new_base_product(&*stores_repo, &mut new_base_product)?;
+                check_product_quota(quota_config.as_ref(), &*stores_repo, &*base_products_repo, new_base_product.store_id)?;
+                check_store_category(
+                    &*store_categories_repo,
+                    &*categories_repo,
+                    new_base_product.store_id,
+                    new_base_product.category_id,
+                )?;
                 // create base_product
                 let base_prod = base_products_repo.create(new_base_product)?;
                 let base_prod_id = base_prod.id;
@@ -683,8 +1428,13 @@ impl<
                     variant
                 });
 
+                let mut products = vec![];
                 for variant in variants {
                     check_vendor_code(&*stores_repo, store_id, &variant.product.vendor_code)?;
+                    if let Some(gtin) = &variant.product.gtin {
+                        check_gtin(&*stores_repo, store_id, gtin)?;
+                    }
+                    validate_variant_attributes(&*products_repo, &*custom_attributes_repo, base_prod.id, &variant.attributes)?;
                     // create variant
                     let product = products_repo.create((variant.product, base_prod.currency).into())?;
                     // create attributes values for variant
@@ -698,9 +1448,26 @@ impl<
                         base_prod.id,
                         variant.attributes,
                     )?;
+                    products.push(product);
                 }
 
-                Ok(base_prod)
+                // created variants are returned with prices in the currencies of the user
+                let currencies_map = currency_exchange
+                    .get_latest()?
+                    .and_then(|all_rates| all_rates.data.get(&base_prod.currency).cloned());
+                let products = products
+                    .into_iter()
+                    .map(|product| {
+                        let customer_price = calculate_customer_price(&product, &currencies_map, currency, fiat_currency);
+                        Product::new(product, customer_price)
+                    })
+                    .collect();
+
+                Ok(BaseProductWithVariants::new(base_prod, products))
+            })
+            .map(|base_product| {
+                catalog_health_repo.invalidate(base_product.base_product.store_id);
+                base_product
             })
             .map_err(|e| {
                 e.context("Service BaseProduct, create with variants and attributes endpoint error occurred.")
@@ -709,9 +1476,99 @@ impl<
         })
     }
 
+    /// Copies base product with its variants and attributes into a new draft of the same store
+    fn duplicate_base_product(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct> {
+        let user_id = self.dynamic_context.user_id;
+        let quota_config = self.static_context.config.product_quota.clone();
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+            conn.transaction::<BaseProduct, FailureError, _>(|| {
+                let original = base_products_repo
+                    .find(base_product_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Base product {} not found", base_product_id).context(Error::NotFound))?;
+                check_product_quota(quota_config.as_ref(), &*stores_repo, &*base_products_repo, original.store_id)?;
+                let store_id = original.store_id;
+                copy_base_product(&*conn, &repo_factory, user_id, original, store_id)
+            })
+            .map(|base_product| {
+                catalog_health_repo.invalidate(base_product.store_id);
+                base_product
+            })
+            .map_err(|e| e.context("Service BaseProduct, duplicate endpoint error occurred.").into())
+        })
+    }
+
+    /// Moves base product with its variants to another store. For superusers.
+    /// Variants, attributes and photos belong to the base product and move with it, coupons of the former store stop applying
+    fn transfer_base_product(&self, base_product_id: BaseProductId, payload: TransferBaseProduct) -> ServiceFuture<BaseProduct> {
+        let user_id = self.dynamic_context.user_id;
+        let quota_config = self.static_context.config.product_quota.clone();
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        info!("Transfer base product {} to store {}", base_product_id, payload.store_id);
+
+        if !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Cannot transfer base product").into()));
+        }
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, user_id);
+            let coupon_scope_repo = repo_factory.create_coupon_scope_base_products_repo(&*conn, user_id);
+
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+            conn.transaction::<(BaseProduct, StoreId), FailureError, _>(move || {
+                let original = base_products_repo
+                    .find(base_product_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Base product {} not found", base_product_id).context(Error::NotFound))?;
+                if original.store_id == payload.store_id {
+                    return Err(format_err!("Base product {} already belongs to store {}", base_product_id, payload.store_id)
+                        .context(Error::Validate(validation_errors!({
+                            "store_id": ["same_store" => "Base product already belongs to the store"]
+                        })))
+                        .into());
+                }
+
+                let store = stores_repo
+                    .find(payload.store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("There is no store with id {}", payload.store_id).context(Error::NotFound))?;
+                check_product_quota(quota_config.as_ref(), &*stores_repo, &*base_products_repo, store.id)?;
+                check_store_category(&*store_categories_repo, &*categories_repo, store.id, original.category_id)?;
+                for variant in products_repo.find_with_base_id(base_product_id)? {
+                    check_vendor_code(&*stores_repo, store.id, &variant.vendor_code)?;
+                    if let Some(ref gtin) = variant.gtin {
+                        check_gtin(&*stores_repo, store.id, gtin)?;
+                    }
+                }
+
+                let base_product = base_products_repo.set_store(base_product_id, &store)?;
+                let _ = coupon_scope_repo.delete_by_base_product(base_product_id)?;
+                remove_product_category(&*stores_repo, &*categories_repo, original.store_id, original.category_id)?;
+                add_product_categories(&*stores_repo, &*categories_repo, base_product.store_id, base_product.category_id)?;
+
+                Ok((base_product, original.store_id))
+            })
+            .map(|(base_product, former_store_id)| {
+                catalog_health_repo.invalidate(former_store_id);
+                catalog_health_repo.invalidate(base_product.store_id);
+                base_product
+            })
+            .map_err(|e| e.context("Service BaseProduct, transfer endpoint error occurred.").into())
+        })
+    }
+
     /// Updates specific product
     fn update_base_product(&self, base_product_id: BaseProductId, payload: UpdateBaseProduct) -> ServiceFuture<BaseProduct> {
         let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
         let repo_factory = self.static_context.repo_factory.clone();
 
         self.spawn_on_pool(move |conn| {
@@ -719,42 +1576,214 @@ impl<
             let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
             let products_repo = repo_factory.create_product_repo(&*conn, user_id);
             let product_attrs_repo = repo_factory.create_product_attrs_repo(&*conn, user_id);
+            let history_repo = repo_factory.create_base_product_history_repo(&*conn, user_id);
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
             conn.transaction::<BaseProduct, FailureError, _>(move || {
                 let old_prod = base_products_repo.find(base_product_id, Visibility::Active)?;
                 if let Some(old_prod) = old_prod {
                     // validate
                     validate_base_product_update(&*base_products_repo, old_prod.store_id.clone(), old_prod.id, &payload)?;
-                    let updated_prod = base_products_repo.update(base_product_id, payload.clone())?;
-                    if let Some(new_cat_id) = payload.category_id {
-                        // updating product categories of the store
-                        if old_prod.category_id != new_cat_id {
-                            let _ = after_base_product_category_update(&*products_repo, &*product_attrs_repo, base_product_id);
-                        }
-                        let _ = update_product_categories(&*stores_repo, old_prod.store_id, old_prod.category_id, new_cat_id)?;
-                    }
-
-                    if let Some(currency) = payload.currency {
-                        // updating currency of base_products variants
-                        products_repo.update_currency(currency, updated_prod.id)?;
-                    }
-
-                    match updated_prod.status {
-                        ModerationStatus::Decline => base_products_repo.set_moderation_status(updated_prod.id, ModerationStatus::Draft),
-                        _ => Ok(updated_prod),
-                    }
+                    let updated_prod = apply_base_product_update(
+                        &*base_products_repo,
+                        &*stores_repo,
+                        &*products_repo,
+                        &*product_attrs_repo,
+                        old_prod.clone(),
+                        payload,
+                    )?;
+                    let changes = BaseProductChange::between(&old_prod, &updated_prod);
+                    record_base_product_changes(&*history_repo, user_id, impersonator, base_product_id, changes)?;
+                    Ok(updated_prod)
                 } else {
                     Err(Error::NotFound.into())
                 }
             })
+            .map(|base_product| {
+                catalog_health_repo.invalidate(base_product.store_id);
+                base_product
+            })
             .map_err(|e| e.context("Service BaseProduct, update endpoint error occurred.").into())
         })
     }
 
+    /// Returns change history of the base product, the latest first
+    fn get_base_product_history(&self, base_product_id: BaseProductId) -> ServiceFuture<Vec<BaseProductHistoryEntry>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let history_repo = repo_factory.create_base_product_history_repo(&*conn, user_id);
+            history_repo
+                .list(base_product_id)
+                .map_err(|e| e.context("Service BaseProduct, get_base_product_history endpoint error occurred.").into())
+        })
+    }
+
+    /// Applies updates to base products of the store in one transaction, nothing is updated if any of them is invalid
+    fn update_base_products_of_store(
+        &self,
+        store_id: StoreId,
+        updates: Vec<BaseProductBulkUpdate>,
+    ) -> ServiceFuture<BaseProductsBulkUpdateResult> {
+        let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let product_attrs_repo = repo_factory.create_product_attrs_repo(&*conn, user_id);
+            let history_repo = repo_factory.create_base_product_history_repo(&*conn, user_id);
+            conn.transaction::<BaseProductsBulkUpdateResult, FailureError, _>(move || {
+                if updates.len() > MAX_BULK_UPDATE_COUNT {
+                    return Err(format_err!("Bulk update of {} base products is too big", updates.len())
+                        .context(Error::Validate(
+                            validation_errors!({"base_products": ["count" => "Too many base products in one update"]}),
+                        ))
+                        .into());
+                }
+
+                let mut updated_ids = HashSet::new();
+                let mut new_slugs = HashSet::new();
+                let mut checked = vec![];
+                for update in updates {
+                    let old_prod = base_products_repo
+                        .find(update.id, Visibility::Active)?
+                        .filter(|base_product| base_product.store_id == store_id);
+                    let errors = if !updated_ids.insert(update.id) {
+                        Some(validation_errors!({"id": ["unique" => "Base product is updated more than once"]}))
+                    } else if update.payload.slug.as_ref().map(|slug| !new_slugs.insert(slug.clone())).unwrap_or(false) {
+                        Some(validation_errors!({"slug": ["unique" => "Slug is set to more than one base product"]}))
+                    } else {
+                        base_product_update_errors(&*base_products_repo, store_id, old_prod.as_ref(), &update)?
+                    };
+                    checked.push((update, old_prod, errors));
+                }
+
+                let applied = checked.iter().all(|&(_, _, ref errors)| errors.is_none());
+                let mut items = vec![];
+                for (update, old_prod, errors) in checked {
+                    let base_product = match old_prod {
+                        Some(old_prod) if applied => {
+                            let updated_prod = apply_base_product_update(
+                                &*base_products_repo,
+                                &*stores_repo,
+                                &*products_repo,
+                                &*product_attrs_repo,
+                                old_prod.clone(),
+                                update.payload,
+                            )?;
+                            let changes = BaseProductChange::between(&old_prod, &updated_prod);
+                            record_base_product_changes(&*history_repo, user_id, impersonator, update.id, changes)?;
+                            Some(updated_prod)
+                        }
+                        _ => None,
+                    };
+                    items.push(BaseProductBulkUpdateItemResult {
+                        id: update.id,
+                        base_product,
+                        errors,
+                    });
+                }
+
+                Ok(BaseProductsBulkUpdateResult { applied, items })
+            })
+            .map_err(|e| e.context("Service BaseProduct, update_base_products_of_store endpoint error occurred.").into())
+        })
+    }
+
+    /// Deactivates base products of the store matching the filter in background job, dry run only counts them
+    fn deactivate_base_products_of_store(
+        &self,
+        store_id: StoreId,
+        payload: BaseProductsBulkDeactivation,
+    ) -> ServiceFuture<BaseProductsBulkDeactivationResult> {
+        let user_id = self.dynamic_context.user_id;
+        let is_super_admin = self.dynamic_context.is_super_admin();
+        let repo_factory = self.static_context.repo_factory.clone();
+        let service = self.clone();
+        let dry_run = payload.dry_run;
+        info!("Deactivate base products of store {} matching {:?}", store_id, payload);
+
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+
+                let store = stores_repo
+                    .find(store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+                if is_super_admin || Some(store.user_id) == user_id {
+                    find_base_products_to_deactivate(&*base_products_repo, &*products_repo, store_id, payload)
+                } else {
+                    Err(format_err!("Base products of store {} are deactivated by its owner only", store_id)
+                        .context(Error::Forbidden)
+                        .into())
+                }
+            })
+            .and_then(move |matched| -> ServiceFuture<BaseProductsBulkDeactivationResult> {
+                let base_products_count = matched.len();
+                let products_count = matched.iter().map(|(_, variants_count)| variants_count).sum();
+                if dry_run {
+                    return Box::new(future::ok(BaseProductsBulkDeactivationResult::dry_run(base_products_count, products_count)));
+                }
+
+                let repo_factory = service.static_context.repo_factory.clone();
+                let base_product_ids: Vec<BaseProductId> = matched.into_iter().map(|(base_product_id, _)| base_product_id).collect();
+                Box::new(
+                    service
+                        .start_job(JobKind::BaseProductsBulkDeactivation, move |conn, tracker| {
+                            let base_products_repo = repo_factory.create_base_product_repo(conn, user_id);
+                            let products_repo = repo_factory.create_product_repo(conn, user_id);
+                            let stores_repo = repo_factory.create_stores_repo(conn, user_id);
+                            let categories_repo = repo_factory.create_categories_repo(conn, user_id);
+
+                            tracker.set_total(base_product_ids.len())?;
+                            for (processed, base_product_id) in base_product_ids.into_iter().enumerate() {
+                                if tracker.is_canceled() {
+                                    break;
+                                }
+
+                                let deactivated = conn.transaction::<BaseProduct, FailureError, _>(|| {
+                                    deactivate_with_variants(
+                                        &*base_products_repo,
+                                        &*products_repo,
+                                        &*stores_repo,
+                                        &*categories_repo,
+                                        base_product_id,
+                                    )
+                                });
+                                if let Err(e) = deactivated {
+                                    tracker.add_error(format!("base product {}", base_product_id), e.to_string());
+                                }
+                                tracker.set_processed(processed + 1)?;
+                            }
+
+                            Ok(())
+                        })
+                        .map(move |job| BaseProductsBulkDeactivationResult {
+                            base_products_count,
+                            products_count,
+                            job: Some(job),
+                            impact: None,
+                        }),
+                )
+            })
+            .map_err(|e: FailureError| {
+                e.context("Service BaseProduct, deactivate_base_products_of_store endpoint error occurred.")
+                    .into()
+            }),
+        )
+    }
+
     /// Find by cart
     fn find_by_cart(&self, cart: Vec<CartProduct>) -> ServiceFuture<Vec<StoreWithBaseProducts>> {
         let user_id = self.dynamic_context.user_id;
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
         let repo_factory = self.static_context.repo_factory.clone();
 
         self.spawn_on_pool(move |conn| {
@@ -790,6 +1819,7 @@ impl<
 
                 let latest_currencies = currency_exchange.get_latest()?;
                 calculate_base_products_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                apply_price_rules(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
 
                 let mut group_by_store_id = BTreeMap::<StoreId, Vec<BaseProductWithVariants>>::default();
                 for base_product_with_variants in base_products {
@@ -860,70 +1890,142 @@ impl<
     ) -> ServiceFuture<Vec<BaseProduct>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
+        let notifier = create_notifier(&self.static_context.config, self.static_context.client_handle.clone());
         debug!("Set moderation status {} for base_products {:?}", status, &base_product_ids);
 
-        self.spawn_on_pool(move |conn| {
-            let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
-            base_products_repo
-                .set_moderation_statuses(base_product_ids, status)
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                {
+                    let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
+                    let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
+                    let store_feed_repo = repo_factory.create_store_feed_repo(&conn, user_id);
+                    let catalog_health_repo = repo_factory.create_catalog_health_repo(&conn, user_id);
+                    let base_products = base_products_repo.set_moderation_statuses(base_product_ids, status)?;
+
+                    let store_ids: HashSet<StoreId> = base_products.iter().map(|base_product| base_product.store_id).collect();
+                    for store_id in store_ids {
+                        store_feed_repo.invalidate(store_id);
+                        catalog_health_repo.invalidate(store_id);
+                    }
+
+                    let mut events = vec![];
+                    for base_product in base_products.iter() {
+                        if let Some(event) = products_milestone_event(&*base_products_repo, &*stores_repo, base_product)? {
+                            events.push(event);
+                        }
+                    }
+
+                    Ok((base_products, events))
+                }
                 .map_err(|e: FailureError| {
                     e.context("Service base_products, set_moderation_status_base_products endpoint error occurred.")
                         .into()
                 })
-        })
+            })
+            .and_then(move |(base_products, events)| send_events(notifier, events).map(move |_| base_products)),
+        )
     }
 
-    /// Set moderation status for base_product_id
-    fn set_moderation_status_base_product(&self, base_product_id: BaseProductId, status: ModerationStatus) -> ServiceFuture<BaseProduct> {
+    /// Set moderation status for base_product_id, checklist results are saved when the base product is declined
+    fn set_moderation_status_base_product(
+        &self,
+        base_product_id: BaseProductId,
+        status: ModerationStatus,
+        checklist: Vec<ChecklistResult>,
+    ) -> ServiceFuture<BaseProduct> {
         let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
         let repo_factory = self.static_context.repo_factory.clone();
+        let notifier = create_notifier(&self.static_context.config, self.static_context.client_handle.clone());
         info!("Set moderation status {} for base_product {}", status, base_product_id);
 
-        self.spawn_on_pool(move |conn| {
-            {
-                let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
-                let base_product = base_products_repo.find(base_product_id, Visibility::Active)?;
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                {
+                    let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
+                    let base_product = base_products_repo.find(base_product_id, Visibility::Active)?;
 
-                let current_status = match base_product {
-                    Some(value) => value.status,
-                    None => return Err(Error::NotFound.into()),
-                };
+                    let base_product = match base_product {
+                        Some(value) => value,
+                        None => return Err(Error::NotFound.into()),
+                    };
 
-                if check_change_status(current_status, status) {
-                    base_products_repo.set_moderation_status(base_product_id, status)
-                } else {
-                    Err(format_err!("Base product status: {} not valid for set", status)
-                        .context(Error::Validate(
-                            validation_errors!({"base_products": ["base_products" => "Base product new status is not valid"]}),
-                        ))
-                        .into())
-                }
-            }
-            .map_err(|e: FailureError| {
-                e.context("Service base_products, set_moderation_status_base_product endpoint error occurred.")
-                    .into()
-            })
-        })
-    }
+                    if !checklist.is_empty() && status != ModerationStatus::Decline {
+                        return Err(format_err!("Checklist results are accepted only with status {}", ModerationStatus::Decline)
+                            .context(Error::Validate(
+                                validation_errors!({"checklist": ["checklist" => "Checklist is answered only when declining"]}),
+                            ))
+                            .into());
+                    }
+
+                    if check_change_status(base_product.status, status) {
+                        let base_product = conn.transaction::<BaseProduct, FailureError, _>(|| {
+                            if !checklist.is_empty() {
+                                let checklists_repo = repo_factory.create_moderation_checklists_repo(&conn, user_id);
+                                let categories_repo = repo_factory.create_categories_repo(&conn, user_id);
+                                save_checklist_results(&*checklists_repo, &*categories_repo, &base_product, user_id, checklist)?;
+                            }
+                            let updated_prod = base_products_repo.set_moderation_status(base_product_id, status)?;
+                            let history_repo = repo_factory.create_base_product_history_repo(&conn, user_id);
+                            let changes = BaseProductChange::between(&base_product, &updated_prod);
+                            record_base_product_changes(&*history_repo, user_id, impersonator, base_product_id, changes)?;
+                            Ok(updated_prod)
+                        })?;
+                        repo_factory
+                            .create_store_feed_repo(&conn, user_id)
+                            .invalidate(base_product.store_id);
+                        repo_factory
+                            .create_catalog_health_repo(&conn, user_id)
+                            .invalidate(base_product.store_id);
+                        let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
+                        let events = products_milestone_event(&*base_products_repo, &*stores_repo, &base_product)?
+                            .into_iter()
+                            .collect();
+                        Ok((base_product, events))
+                    } else {
+                        Err(format_err!("Base product status: {} not valid for set", status)
+                            .context(Error::Validate(
+                                validation_errors!({"base_products": ["base_products" => "Base product new status is not valid"]}),
+                            ))
+                            .into())
+                    }
+                }
+                .map_err(|e: FailureError| {
+                    e.context("Service base_products, set_moderation_status_base_product endpoint error occurred.")
+                        .into()
+                })
+            })
+            .and_then(move |(base_product, events)| send_events(notifier, events).map(move |_| base_product)),
+        )
+    }
 
     /// Send base product to moderation from store manager
     fn send_base_product_to_moderation(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct> {
         let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
         let repo_factory = self.static_context.repo_factory.clone();
         info!("Send base product: {} to moderation", base_product_id);
 
         self.spawn_on_pool(move |conn| {
             {
                 let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
-                let base_product = base_products_repo.find(base_product_id, Visibility::Active)?;
-
-                let status = match base_product {
-                    Some(value) => value.status,
+                let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
+                let history_repo = repo_factory.create_base_product_history_repo(&conn, user_id);
+                let base_product = match base_products_repo.find(base_product_id, Visibility::Active)? {
+                    Some(value) => value,
                     None => return Err(Error::NotFound.into()),
                 };
 
-                if check_change_status(status, ModerationStatus::Moderation) {
-                    base_products_repo.set_moderation_status(base_product_id, ModerationStatus::Moderation)
+                check_store_verified(&*stores_repo, base_product.store_id)?;
+
+                if check_change_status(base_product.status, ModerationStatus::Moderation) {
+                    let updated_prod = base_products_repo.set_moderation_status(base_product_id, ModerationStatus::Moderation)?;
+                    let changes = BaseProductChange::between(&base_product, &updated_prod);
+                    record_base_product_changes(&*history_repo, user_id, impersonator, base_product_id, changes)?;
+                    repo_factory
+                        .create_catalog_health_repo(&conn, user_id)
+                        .invalidate(updated_prod.store_id);
+                    Ok(updated_prod)
                 } else {
                     Err(
                         format_err!("Base product with id: {}, cannot be sent to moderation", base_product_id)
@@ -944,14 +2046,25 @@ impl<
     /// Hide base product from search. For store manager
     fn set_base_product_moderation_status_draft(&self, base_product_id: BaseProductId) -> ServiceFuture<BaseProduct> {
         let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
         let repo_factory = self.static_context.repo_factory.clone();
         info!("Hide base product: {}", base_product_id);
 
         self.spawn_on_pool(move |conn| {
             {
                 let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
+                let history_repo = repo_factory.create_base_product_history_repo(&conn, user_id);
 
-                set_base_product_moderation_status_draft(&*base_products_repo, base_product_id)
+                let old_prod = base_products_repo.find(base_product_id, Visibility::Active)?;
+                let updated_prod = set_base_product_moderation_status_draft(&*base_products_repo, base_product_id)?;
+                if let Some(old_prod) = old_prod {
+                    let changes = BaseProductChange::between(&old_prod, &updated_prod);
+                    record_base_product_changes(&*history_repo, user_id, impersonator, base_product_id, changes)?;
+                }
+                repo_factory
+                    .create_catalog_health_repo(&conn, user_id)
+                    .invalidate(updated_prod.store_id);
+                Ok(updated_prod)
             }
             .map_err(|e: FailureError| {
                 e.context("Service base_products, set_base_product_moderation_status_draft endpoint error occurred.")
@@ -1066,6 +2179,29 @@ impl<
         }
     }
 
+    fn create_store_order(&self, options: Option<ProductsSearchOptions>) -> ServiceFuture<Option<ProductsSearchOptions>> {
+        let repo_factory = self.static_context.repo_factory.clone();
+        let user_id = self.dynamic_context.user_id;
+
+        if let Some(mut options) = options {
+            if options.sort_by.is_none() {
+                if let Some(store_id) = options.store_id {
+                    return self.spawn_on_pool(move |conn| {
+                        let store_product_positions_repo = repo_factory.create_store_product_positions_repo(&*conn, user_id);
+                        let positions = store_product_positions_repo.list(store_id)?;
+                        if !positions.is_empty() {
+                            options.store_order = Some(positions.into_iter().map(|position| position.base_product_id).collect());
+                        }
+                        Ok(Some(options))
+                    });
+                }
+            }
+            Box::new(future::ok(Some(options)))
+        } else {
+            Box::new(future::ok(None))
+        }
+    }
+
     fn get_base_product_by_slug(
         &self,
         store_identifier: StoreIdentifier,
@@ -1081,9 +2217,9 @@ impl<
             base_product_slug, visibility
         );
 
-        self.spawn_on_pool(move |conn| {
-            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
-            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+        self.spawn_on_replica(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_read_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_read_repo(&*conn, user_id);
             let store_id = match store_identifier {
                 StoreIdentifier::Id(store_id) => store_id,
                 StoreIdentifier::Slug(store_slug) => stores_repo
@@ -1104,9 +2240,11 @@ impl<
         &self,
         store_identifier: StoreIdentifier,
         base_product_slug: BaseProductSlug,
+        viewer_token: Option<String>,
     ) -> ServiceFuture<Option<BaseProduct>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
+        let viewer_hash = Viewer::new(user_id, viewer_token).map(|viewer| viewer.hash());
 
         self.spawn_on_pool(move |conn| {
             let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
@@ -1118,7 +2256,7 @@ impl<
                     .map(|store| store.id)
                     .ok_or(format_err!("Store with slug {} not found", store_slug))?,
             };
-            base_products_repo.update_views_by_slug(store_id, base_product_slug).map_err(|e| {
+            base_products_repo.update_views_by_slug(store_id, base_product_slug, viewer_hash).map_err(|e| {
                 e.context("Service BaseProduct, get_base_product_by_slug_with_views_update endpoint error occurred.")
                     .into()
             })
@@ -1173,6 +2311,41 @@ impl<
             Ok(check_can_update_by_status(current_status))
         })
     }
+
+    /// Starts background job moving filtered base products to another category, its progress is polled at `/jobs/<job_id>`
+    fn start_category_reassignment(&self, payload: CategoryReassignment) -> ServiceFuture<Job> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let service = self.clone();
+        info!("Start category reassignment {:?}", payload);
+
+        if !payload.has_filter() {
+            return Box::new(future::err(
+                format_err!("Category reassignment without filter")
+                    .context(Error::Validate(validation_errors!({
+                        "filter": ["filter" => "At least one of store_id, current_category_id or name_pattern must be set"]
+                    })))
+                    .into(),
+            ));
+        }
+
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+                check_reassignment_category(&*categories_repo, payload.new_category_id).map(|_| payload)
+            })
+            .and_then(move |payload| {
+                let repo_factory = service.static_context.repo_factory.clone();
+                service.start_job(JobKind::CategoryReassignment, move |conn, tracker| {
+                    reassign_category(conn, &repo_factory, user_id, tracker, payload)
+                })
+            })
+            .map_err(|e: FailureError| {
+                e.context("Service base_products, start_category_reassignment endpoint error occurred.")
+                    .into()
+            }),
+        )
+    }
 }
 
 fn after_base_product_category_update(
@@ -1190,6 +2363,86 @@ fn after_base_product_category_update(
     Ok(())
 }
 
+/// Updates validated base product with its variants and categories of the store
+fn apply_base_product_update(
+    base_products_repo: &BaseProductsRepo,
+    stores_repo: &StoresRepo,
+    products_repo: &ProductsRepo,
+    product_attrs_repo: &ProductAttrsRepo,
+    old_prod: BaseProduct,
+    payload: UpdateBaseProduct,
+) -> Result<BaseProduct, FailureError> {
+    let updated_prod = base_products_repo.update(old_prod.id, payload.clone())?;
+    if let Some(new_cat_id) = payload.category_id {
+        // updating product categories of the store
+        if old_prod.category_id != new_cat_id {
+            let _ = after_base_product_category_update(products_repo, product_attrs_repo, old_prod.id);
+        }
+        let _ = update_product_categories(stores_repo, old_prod.store_id, old_prod.category_id, new_cat_id)?;
+    }
+
+    if let Some(currency) = payload.currency {
+        // updating currency of base_products variants
+        products_repo.update_currency(currency, updated_prod.id)?;
+    }
+
+    match updated_prod.status {
+        ModerationStatus::Decline => base_products_repo.set_moderation_status(updated_prod.id, ModerationStatus::Draft),
+        _ => Ok(updated_prod),
+    }
+}
+
+/// Records changes of the base product made by the user of the request, changes made without user are not recorded
+pub fn record_base_product_changes(
+    history_repo: &BaseProductHistoryRepo,
+    user_id: Option<UserId>,
+    impersonator: Option<UserId>,
+    base_product_id: BaseProductId,
+    changes: Vec<BaseProductChange>,
+) -> Result<(), FailureError> {
+    let changed_by = match user_id {
+        Some(changed_by) => changed_by,
+        None => return Ok(()),
+    };
+
+    history_repo.record(
+        changes
+            .into_iter()
+            .map(|change| NewBaseProductChange {
+                base_product_id,
+                changed_by,
+                impersonated_by: impersonator,
+                change,
+            })
+            .collect(),
+    )
+}
+
+/// Errors of the update of the store base product, `None` when it can be applied
+fn base_product_update_errors(
+    base_products_repo: &BaseProductsRepo,
+    store_id: StoreId,
+    base_product: Option<&BaseProduct>,
+    update: &BaseProductBulkUpdate,
+) -> Result<Option<ValidationErrors>, FailureError> {
+    if base_product.is_none() {
+        return Ok(Some(validation_errors!({"id": ["not_found" => "Base product not found in the store"]})));
+    }
+
+    if let Err(errors) = update.payload.validate() {
+        return Ok(Some(errors));
+    }
+
+    if let Some(ref slug) = update.payload.slug {
+        let base_product_with_same_slug = base_products_repo.find_by_slug(store_id, BaseProductSlug(slug.clone()), Visibility::Active)?;
+        if base_product_with_same_slug.map(|base_product| base_product.id != update.id).unwrap_or(false) {
+            return Ok(Some(validation_errors!({"slug": ["unique" => "Base product with such slug already exists"]})));
+        }
+    }
+
+    Ok(None)
+}
+
 fn validate_base_product(base_products_repo: &BaseProductsRepo, payload: &NewBaseProduct) -> Result<(), FailureError> {
     if let Some(base_product_slug) = payload.slug.clone() {
         let base_product_with_same_slug =
@@ -1240,23 +2493,166 @@ fn enrich_new_base_product(stores_repo: &StoresRepo, new_base_product: &mut NewB
         .find(new_base_product.store_id, Visibility::Active)?
         .ok_or_else(|| format_err!("There is no store with id {}", new_base_product.store_id).context(Error::NotFound))?;
     new_base_product.store_status = Some(store.status);
+    new_base_product.store_is_on_vacation = Some(store.is_on_vacation);
+    new_base_product.inherit_dimensions(&store);
     Ok(())
 }
 
+/// Vendor codes are unique in the store, copies get `-copy`, `-copy-2` and so on suffixes
+fn duplicate_vendor_code(stores_repo: &StoresRepo, store_id: StoreId, vendor_code: &str) -> Result<String, FailureError> {
+    let mut copy_no = 1;
+    loop {
+        let candidate = match copy_no {
+            1 => format!("{}-copy", vendor_code),
+            _ => format!("{}-copy-{}", vendor_code, copy_no),
+        };
+        let exists = stores_repo
+            .vendor_code_exists(store_id, &candidate)?
+            .ok_or(format_err!("Store with id {} not found.", store_id).context(Error::NotFound))?;
+        if !exists {
+            return Ok(candidate);
+        }
+        copy_no += 1;
+    }
+}
+
+/// Creates a draft copy of the base product with its variants and attributes in the store.
+/// Vendor codes get `-copy` suffixes when the copy stays in the same store
+pub fn copy_base_product<T, F>(
+    conn: &T,
+    repo_factory: &F,
+    user_id: Option<UserId>,
+    original: BaseProduct,
+    store_id: StoreId,
+) -> Result<BaseProduct, FailureError>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    let base_products_repo = repo_factory.create_base_product_repo(conn, user_id);
+    let stores_repo = repo_factory.create_stores_repo(conn, user_id);
+    let categories_repo = repo_factory.create_categories_repo(conn, user_id);
+    let products_repo = repo_factory.create_product_repo(conn, user_id);
+    let prod_attr_repo = repo_factory.create_product_attrs_repo(conn, user_id);
+    let custom_attributes_repo = repo_factory.create_custom_attributes_repo(conn, user_id);
+
+    let base_product_id = original.id;
+    let same_store = original.store_id == store_id;
+
+    // slug and moderation status are left to their defaults, so the copy gets a fresh slug and is a draft
+    let mut new_base_product = NewBaseProduct {
+        name: original.name,
+        store_id,
+        short_description: original.short_description,
+        long_description: original.long_description,
+        seo_title: original.seo_title,
+        seo_description: original.seo_description,
+        currency: original.currency,
+        category_id: original.category_id,
+        slug: None,
+        length_cm: original.length_cm,
+        width_cm: original.width_cm,
+        height_cm: original.height_cm,
+        weight_g: original.weight_g,
+        uuid: Uuid::new_v4(),
+        store_status: None,
+        store_is_on_vacation: None,
+        dimensions_inherited: None,
+        saga_id: None,
+    };
+    enrich_new_base_product(&*stores_repo, &mut new_base_product)?;
+    let copy = base_products_repo.create(new_base_product)?;
+    add_product_categories(&*stores_repo, &*categories_repo, copy.store_id, copy.category_id)?;
+
+    for custom_attribute in custom_attributes_repo.find_all_attributes(base_product_id)? {
+        custom_attributes_repo.create(NewCustomAttribute::new(custom_attribute.attribute_id, copy.id))?;
+    }
+
+    for variant in products_repo.find_with_base_id(base_product_id)? {
+        let vendor_code = if same_store {
+            duplicate_vendor_code(&*stores_repo, copy.store_id, &variant.vendor_code)?
+        } else {
+            variant.vendor_code
+        };
+        // GTIN is unique in the store, copies in the same store are left without it
+        let gtin = if same_store { None } else { variant.gtin };
+        let product = products_repo.create(NewProduct {
+            base_product_id: Some(copy.id),
+            discount: variant.discount,
+            photo_main: variant.photo_main,
+            additional_photos: variant.additional_photos,
+            vendor_code,
+            cashback: variant.cashback,
+            price: variant.price,
+            currency: copy.currency,
+            pre_order: Some(variant.pre_order),
+            pre_order_days: Some(variant.pre_order_days),
+            uuid: Uuid::new_v4(),
+            // stock is not copied, the copy is a draft
+            quantity: None,
+            gtin,
+        })?;
+
+        for prod_attr in prod_attr_repo.find_all_attributes(variant.id)? {
+            prod_attr_repo.create(NewProdAttr::new(
+                product.id,
+                copy.id,
+                prod_attr.attr_id,
+                prod_attr.value,
+                prod_attr.value_type,
+                prod_attr.meta_field,
+                prod_attr.attr_value_id,
+            ))?;
+        }
+    }
+
+    Ok(copy)
+}
+
+/// Returns false when some prices are left in seller currency for lack of exchange rates
 fn calculate_base_products_customer_price(
     base_products: &mut [BaseProductWithVariants],
     latest_currencies: Option<CurrencyExchange>,
     crypto_currency: Currency,
     fiat_currency: Currency,
-) {
+) -> bool {
+    let mut converted = true;
     for base_product in base_products {
         let currency = base_product.base_product.currency;
         let currencies_map = latest_currencies
             .as_ref()
             .and_then(|all_rates| all_rates.data.get(&currency).cloned());
+        converted &= currencies_map.is_some();
         for mut variant in &mut base_product.variants {
             variant.customer_price = calculate_customer_price(&variant.product, &currencies_map, crypto_currency, fiat_currency);
         }
+        // "from" price is served by the columns kept by products repo, not by the loaded variants
+        base_product.customer_price_range = match (base_product.base_product.min_price, base_product.base_product.max_price) {
+            (Some(min_price), Some(max_price)) => Some(CustomerPriceRange {
+                min_price: convert_seller_price(min_price, currency, &currencies_map, crypto_currency, fiat_currency),
+                max_price: convert_seller_price(max_price, currency, &currencies_map, crypto_currency, fiat_currency),
+            }),
+            _ => None,
+        };
+    }
+    converted
+}
+
+pub fn calculate_base_product_details_customer_price(
+    base_products: &mut [BaseProductDetails],
+    latest_currencies: Option<CurrencyExchange>,
+    crypto_currency: Currency,
+    fiat_currency: Currency,
+) {
+    for base_product in base_products {
+        let currency = base_product.base_product.currency;
+        let currencies_map = latest_currencies
+            .as_ref()
+            .and_then(|all_rates| all_rates.data.get(&currency).cloned());
+        for variant in &mut base_product.variants {
+            variant.product.customer_price =
+                calculate_customer_price(&variant.product.product, &currencies_map, crypto_currency, fiat_currency);
+        }
     }
 }
 
@@ -1273,15 +2669,23 @@ fn get_path_to_searched_category(searched_category: Option<Category>, root: Cate
     }
 }
 
-fn get_attribute_filters(el_products: Vec<ElasticProduct>) -> Option<Vec<AttributeFilter>> {
-    let mut equal_attrs = HashMap::<i32, HashSet<String>>::default();
-    let mut range_attrs = HashMap::<i32, RangeFilter>::default();
+/// Texts of the base product name in all languages
+fn base_product_names(base_product: &BaseProduct) -> Vec<String> {
+    serde_json::from_value::<Vec<Translation>>(base_product.name.clone())
+        .map(|translations| translations.into_iter().map(|translation| translation.text).collect())
+        .unwrap_or_default()
+}
+
+/// Values of string attributes in alphabetical order and ranges of numeric attributes found in the products
+fn collect_attribute_values(el_products: Vec<ElasticProduct>) -> (BTreeMap<i32, BTreeSet<String>>, BTreeMap<i32, RangeFilter>) {
+    let mut equal_attrs = BTreeMap::<i32, BTreeSet<String>>::default();
+    let mut range_attrs = BTreeMap::<i32, RangeFilter>::default();
 
     for product in el_products {
         for variant in product.variants {
             for attr_value in variant.attrs {
                 if let Some(value) = attr_value.str_val {
-                    let equal = equal_attrs.entry(attr_value.attr_id).or_insert_with(HashSet::<String>::default);
+                    let equal = equal_attrs.entry(attr_value.attr_id).or_insert_with(BTreeSet::<String>::default);
                     equal.insert(value);
                 }
                 if let Some(value) = attr_value.float_val {
@@ -1292,18 +2696,30 @@ fn get_attribute_filters(el_products: Vec<ElasticProduct>) -> Option<Vec<Attribu
         }
     }
 
-    let eq_filters = equal_attrs.into_iter().map(|(k, v)| AttributeFilter {
-        id: k,
-        equal: Some(EqualFilter {
-            values: v.iter().cloned().collect(),
-        }),
-        range: None,
+    (equal_attrs, range_attrs)
+}
+
+/// Equal filters are cut to facet limits of their attributes
+fn get_attribute_filters(el_products: Vec<ElasticProduct>, facet_limits: &FacetLimits) -> Option<Vec<AttributeFilter>> {
+    let (equal_attrs, range_attrs) = collect_attribute_values(el_products);
+
+    let eq_filters = equal_attrs.into_iter().map(|(k, v)| {
+        let limit = facet_limits.values_limit(k);
+        AttributeFilter {
+            id: k,
+            more_available: v.len() > limit,
+            equal: Some(EqualFilter {
+                values: v.into_iter().take(limit).collect(),
+            }),
+            range: None,
+        }
     });
 
     let range_filters = range_attrs.into_iter().map(|(k, v)| AttributeFilter {
         id: k,
         equal: None,
         range: Some(v),
+        more_available: false,
     });
 
     Some(eq_filters.chain(range_filters).collect())
@@ -1320,6 +2736,35 @@ fn get_first_level_category(third_level_category_id: CategoryId, root: Category)
         })
 }
 
+/// Checks that the store sells in the first level category of the base product,
+/// stores which declared no categories are not limited
+fn check_store_category(
+    store_categories_repo: &StoreCategoriesRepo,
+    categories_repo: &CategoriesRepo,
+    store_id: StoreId,
+    category_id: CategoryId,
+) -> RepoResult<()> {
+    let store_categories = store_categories_repo.list(store_id)?;
+    if store_categories.is_empty() {
+        return Ok(());
+    }
+
+    let category_root = categories_repo.get_all_categories()?;
+    let first_level_category = get_first_level_category(category_id, category_root)?;
+    let is_allowed = store_categories
+        .iter()
+        .any(|store_category| store_category.category_id == first_level_category.id && store_category.status.is_allowed());
+    if is_allowed {
+        Ok(())
+    } else {
+        Err(format_err!("Store {} does not sell in category {}", store_id, first_level_category.id)
+            .context(Error::Validate(validation_errors!({
+                "category_id": [CATEGORY_NOT_DECLARED => "Store does not sell in the category, request category expansion first"]
+            })))
+            .into())
+    }
+}
+
 /// Add product categories of the store
 fn add_product_categories(
     stores_repo: &StoresRepo,
@@ -1338,8 +2783,76 @@ fn add_product_categories(
     Ok(())
 }
 
+/// Deactivates base product with its variants and removes its category from product categories of the store
+fn deactivate_with_variants(
+    base_products_repo: &BaseProductsRepo,
+    products_repo: &ProductsRepo,
+    stores_repo: &StoresRepo,
+    categories_repo: &CategoriesRepo,
+    base_product_id: BaseProductId,
+) -> RepoResult<BaseProduct> {
+    let prod = base_products_repo.deactivate(base_product_id)?;
+    let _ = products_repo.deactivate_by_base_product(base_product_id)?;
+    // update product categories of the store
+    remove_product_category(stores_repo, categories_repo, prod.store_id, prod.category_id)?;
+    Ok(prod)
+}
+
+/// Remove one product of the category from product categories of the store
+fn remove_product_category(
+    stores_repo: &StoresRepo,
+    categories_repo: &CategoriesRepo,
+    store_id_arg: StoreId,
+    category_id_arg: CategoryId,
+) -> RepoResult<()> {
+    let store = stores_repo.find(store_id_arg, Visibility::Active)?;
+    if let Some(store) = store {
+        let category_root = categories_repo.get_all_categories()?;
+        let cat = get_first_level_category(category_id_arg, category_root)?;
+        let service_update_store = ServiceUpdateStore::delete_category_from_product_categories(store.product_categories.clone(), cat.id);
+        let _ = stores_repo.update_service_fields(store.id, service_update_store)?;
+    }
+
+    Ok(())
+}
+
+/// Returns active base products of the store matching the filter with numbers of their active variants
+fn find_base_products_to_deactivate(
+    base_products_repo: &BaseProductsRepo,
+    products_repo: &ProductsRepo,
+    store_id: StoreId,
+    payload: BaseProductsBulkDeactivation,
+) -> RepoResult<Vec<(BaseProductId, usize)>> {
+    let base_products = base_products_repo.search(BaseProductsSearchTerms {
+        is_active: Some(true),
+        store_id: Some(store_id),
+        category_id: payload.category_id,
+        created_before: payload.created_before,
+        ..Default::default()
+    })?;
+
+    let mut variants = HashMap::<BaseProductId, Vec<ProductId>>::new();
+    for product in products_repo.find_with_base_ids(base_products.iter().map(|b| b.id).collect())? {
+        variants.entry(product.base_product_id).or_insert_with(Vec::new).push(product.id);
+    }
+
+    let out_of_stock = payload.out_of_stock_product_ids.map(|ids| ids.into_iter().collect::<HashSet<_>>());
+    Ok(base_products
+        .into_iter()
+        .map(|base_product| {
+            let product_ids = variants.remove(&base_product.id).unwrap_or_default();
+            (base_product.id, product_ids)
+        })
+        .filter(|(_, product_ids)| match out_of_stock {
+            Some(ref out_of_stock) => product_ids.iter().all(|product_id| out_of_stock.contains(product_id)),
+            None => true,
+        })
+        .map(|(base_product_id, product_ids)| (base_product_id, product_ids.len()))
+        .collect())
+}
+
 /// Update product categories of store
-fn update_product_categories(
+pub fn update_product_categories(
     stores_repo: &StoresRepo,
     store_id_arg: StoreId,
     old_category: CategoryId,
@@ -1355,6 +2868,123 @@ fn update_product_categories(
     Ok(())
 }
 
+/// Base products can be moved only to existing leaf category
+pub fn check_reassignment_category(categories_repo: &CategoriesRepo, new_category_id: CategoryId) -> RepoResult<()> {
+    let new_category = categories_repo
+        .find(new_category_id)?
+        .ok_or(format_err!("Category {} not found", new_category_id).context(Error::NotFound))?;
+
+    if new_category.level != Category::MAX_LEVEL_NESTING {
+        return Err(format_err!("Category {} is not a leaf category", new_category.id)
+            .context(Error::Validate(validation_errors!({
+                "new_category_id": ["level" => "Base products can be moved only to a third level category"]
+            })))
+            .into());
+    }
+
+    Ok(())
+}
+
+/// Moves base products in batches, each batch in its own transaction, and saves progress after every batch
+fn reassign_category<T, F>(
+    conn: &T,
+    repo_factory: &F,
+    user_id: Option<UserId>,
+    tracker: &JobTracker,
+    job: CategoryReassignment,
+) -> RepoResult<()>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    let base_products_repo = repo_factory.create_base_product_repo(conn, user_id);
+    let stores_repo = repo_factory.create_stores_repo(conn, user_id);
+    let new_category = job.new_category_id;
+    let search_terms = ModeratorBaseProductSearchTerms {
+        name: job.name_pattern.clone(),
+        store_id: job.store_id.map(|store_id| store_id.0),
+        state: None,
+        category_id: job.current_category_id,
+    };
+
+    let mut start = None;
+    let mut processed = 0;
+    loop {
+        let pagination_params = PaginationParams {
+            direction: Direction::Forward,
+            limit: CATEGORY_REASSIGNMENT_BATCH_SIZE,
+            ordering: Ordering::Ascending,
+            skip: 0,
+            start,
+        };
+        let batch = base_products_repo.moderator_search(pagination_params, search_terms.clone())?;
+
+        if start.is_none() {
+            tracker.set_total(batch.total_count as usize)?;
+        }
+
+        start = match batch.base_products.last() {
+            Some(base_product) => Some(base_product.id),
+            None => break,
+        };
+
+        let mut base_product_ids_by_category: HashMap<CategoryId, Vec<BaseProductId>> = HashMap::new();
+        for base_product in batch.base_products.iter().filter(|b| b.category_id != new_category) {
+            base_product_ids_by_category
+                .entry(base_product.category_id)
+                .or_insert_with(Vec::new)
+                .push(base_product.id);
+        }
+
+        conn.transaction::<(), FailureError, _>(|| {
+            for (current_category, base_product_ids) in base_product_ids_by_category {
+                let updated_products = base_products_repo.replace_category(CategoryReplacePayload {
+                    current_category,
+                    new_category,
+                    base_product_ids: Some(base_product_ids),
+                })?;
+
+                for base_product in updated_products {
+                    update_product_categories(&*stores_repo, base_product.store_id, current_category, new_category)?;
+                }
+            }
+            Ok(())
+        })?;
+
+        processed += batch.base_products.len();
+        tracker.set_processed(processed)?;
+        if tracker.is_canceled() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns milestone event when the just published base product makes the count of published products
+/// in the store reach `PRODUCTS_MILESTONE`. The milestone is announced once per store, even when
+/// several products are published at once or the count drops and grows again
+fn products_milestone_event(
+    base_products_repo: &BaseProductsRepo,
+    stores_repo: &StoresRepo,
+    base_product: &BaseProduct,
+) -> RepoResult<Option<FeedEvent>> {
+    if base_product.status != ModerationStatus::Published {
+        return Ok(None);
+    }
+
+    let published_products = base_products_repo.count_with_store_id(base_product.store_id, Visibility::Published)?;
+    if published_products >= PRODUCTS_MILESTONE && stores_repo.mark_products_milestone(base_product.store_id, published_products)? {
+        Ok(Some(FeedEvent::ProductsMilestone {
+            store_id: base_product.store_id,
+            base_product_id: base_product.id,
+            published_products,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
 pub fn set_base_product_moderation_status_draft(
     base_products_repo: &BaseProductsRepo,
     base_product_id: BaseProductId,
@@ -1384,6 +3014,7 @@ pub fn set_base_product_moderation_status_draft(
 #[cfg(test)]
 pub mod tests {
     use std::sync::Arc;
+    use std::time::SystemTime;
 
     use serde_json;
     use tokio_core::reactor::Core;
@@ -1394,7 +3025,9 @@ pub mod tests {
 
     use models::*;
     use repos::repo_factory::tests::*;
+    use repos::BaseProductsReadRepo;
     use services::*;
+    use services::products::tests::create_new_product_with_attributes;
 
     pub fn create_new_base_product(name: &str) -> NewBaseProduct {
         NewBaseProduct {
@@ -1413,6 +3046,9 @@ pub mod tests {
             height_cm: Some(20),
             weight_g: Some(150),
             store_status: None,
+            store_is_on_vacation: None,
+            dimensions_inherited: None,
+            saga_id: None,
         }
     }
 
@@ -1443,6 +3079,40 @@ pub mod tests {
         assert_eq!(result.unwrap().id, BaseProductId(1));
     }
 
+    #[test]
+    fn test_get_embed_base_product() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.get_embed_base_product(BaseProductId(1));
+        let result = core.run(work).unwrap().unwrap();
+        assert_eq!(result.id, BaseProductId(1));
+        assert_eq!(result.store.id, MOCK_STORE_ID);
+        assert_eq!(result.price, Some(ProductPrice(0f64)));
+    }
+
+    #[test]
+    fn test_find_many() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.find_many(vec![BaseProductId(3), BaseProductId(1), BaseProductId(3)]);
+        let result = core.run(work).unwrap();
+        let ids = result.iter().map(|base_product| base_product.base_product.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![BaseProductId(3), BaseProductId(1)]);
+        assert_eq!(result[0].variants.len(), 1);
+    }
+
+    #[test]
+    fn test_sample_base_products() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.sample_base_products(None, 3);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 3);
+    }
+
     #[test]
     fn test_list() {
         let mut core = Core::new().unwrap();
@@ -1453,6 +3123,33 @@ pub mod tests {
         assert_eq!(result.len(), 5);
     }
 
+    #[test]
+    fn test_get_archived_base_products_of_the_store() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_archived_base_products_of_the_store(MOCK_STORE_ID, BaseProductId(1), 5);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 5);
+        assert!(result.iter().all(|base_product| !base_product.is_active));
+    }
+
+    #[test]
+    fn test_list_by_cursor() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.list_base_products_by_cursor(None, 5, Some(Visibility::Active));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.items.len(), 5);
+
+        let cursor = BaseProductsCursor::decode(&result.next_cursor.unwrap()).unwrap();
+        assert_eq!(cursor.id, BaseProductId(5));
+        let work = service.list_base_products_by_cursor(Some(cursor), 5, Some(Visibility::Active));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.items[0].id, BaseProductId(6));
+    }
+
     #[test]
     fn test_create_base_product() {
         let mut core = Core::new().unwrap();
@@ -1464,6 +3161,102 @@ pub mod tests {
         assert_eq!(result.id, MOCK_BASE_PRODUCT_ID);
     }
 
+    #[test]
+    fn test_create_base_product_in_undeclared_category() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let mut new_base_product = create_new_base_product(MOCK_BASE_PRODUCT_NAME_JSON);
+        new_base_product.store_id = StoreId(2);
+        let work = service.create_base_product(new_base_product);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_create_base_product_with_variants() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let mut variant = create_new_product_with_attributes(MOCK_BASE_PRODUCT_ID);
+        variant.attributes = vec![];
+        let payload = NewBaseProductWithVariants {
+            new_base_product: create_new_base_product(MOCK_BASE_PRODUCT_NAME_JSON),
+            variants: vec![variant],
+            selected_attributes: vec![],
+        };
+        let work = service.create_base_product_with_variants(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.base_product.id, MOCK_BASE_PRODUCT_ID);
+        assert_eq!(result.variants.len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_base_product() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.duplicate_base_product(BaseProductId(1));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.id, MOCK_BASE_PRODUCT_ID);
+    }
+
+    #[test]
+    fn test_transfer_base_product() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.transfer_base_product(BaseProductId(1), TransferBaseProduct { store_id: StoreId(3) });
+        let result = core.run(work).unwrap();
+        assert_eq!(result.store_id, StoreId(3));
+
+        let work = service.transfer_base_product(BaseProductId(1), TransferBaseProduct { store_id: MOCK_STORE_ID });
+        assert!(core.run(work).is_err());
+
+        // the product category is not declared by the store
+        let work = service.transfer_base_product(BaseProductId(1), TransferBaseProduct { store_id: StoreId(2) });
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_set_store_products_order() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let item = |base_product_id, pinned| StoreProductsOrderItem {
+            base_product_id: BaseProductId(base_product_id),
+            pinned,
+        };
+
+        let payload = SetStoreProductsOrder {
+            products: vec![item(3, false), item(1, true), item(2, false)],
+        };
+        let work = service.set_store_products_order(MOCK_STORE_ID, payload.clone());
+        let result = core.run(work).unwrap();
+        let ids = result.iter().map(|position| position.base_product_id.0).collect::<Vec<_>>();
+        assert_eq!(ids, vec![1, 3, 2]);
+
+        let duplicated = SetStoreProductsOrder {
+            products: vec![item(1, false), item(1, true)],
+        };
+        let work = service.set_store_products_order(MOCK_STORE_ID, duplicated);
+        assert!(core.run(work).is_err());
+
+        // mock base products belong to the mock store
+        let work = service.set_store_products_order(StoreId(2), payload);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_get_related_base_products() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_related_base_products(MOCK_BASE_PRODUCT_ID, 3);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|related| related.base_product.id != MOCK_BASE_PRODUCT_ID));
+    }
+
     #[test]
     fn test_update() {
         let mut core = Core::new().unwrap();
@@ -1476,6 +3269,79 @@ pub mod tests {
         assert_eq!(result.id, MOCK_BASE_PRODUCT_ID);
     }
 
+    #[test]
+    fn test_update_base_products_of_store() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = UpdateBaseProduct {
+            currency: Some(Currency::STQ),
+            ..Default::default()
+        };
+        let updates = vec![
+            BaseProductBulkUpdate {
+                id: BaseProductId(1),
+                payload: payload.clone(),
+            },
+            BaseProductBulkUpdate {
+                id: BaseProductId(2),
+                payload,
+            },
+        ];
+        let work = service.update_base_products_of_store(MOCK_STORE_ID, updates);
+        let result = core.run(work).unwrap();
+        assert!(result.applied);
+        assert!(result.items.iter().all(|item| item.base_product.is_some() && item.errors.is_none()));
+    }
+
+    #[test]
+    fn test_update_base_products_of_store_is_not_applied_with_invalid_update() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let update = BaseProductBulkUpdate {
+            id: BaseProductId(1),
+            payload: UpdateBaseProduct {
+                currency: Some(Currency::STQ),
+                ..Default::default()
+            },
+        };
+        let work = service.update_base_products_of_store(MOCK_STORE_ID, vec![update.clone(), update]);
+        let result = core.run(work).unwrap();
+        assert!(!result.applied);
+        assert!(result.items.iter().all(|item| item.base_product.is_none()));
+        assert!(result.items[1].errors.is_some());
+    }
+
+    #[test]
+    fn test_get_base_product_history() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_base_product_history(BaseProductId(1));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].base_product_id, BaseProductId(1));
+        assert_eq!(result[0].changed_by, MOCK_USER_ID);
+    }
+
+    #[test]
+    fn test_deactivate_base_products_of_store_dry_run() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = BaseProductsBulkDeactivation {
+            out_of_stock_product_ids: Some(vec![MOCK_PRODUCT_ID]),
+            dry_run: true,
+            ..Default::default()
+        };
+        let work = service.deactivate_base_products_of_store(MOCK_STORE_ID, payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.base_products_count, 0);
+        assert!(result.job.is_none());
+        assert_eq!(result.impact, Some(ImpactReport::default()));
+    }
+
     #[test]
     fn test_deactivate() {
         let mut core = Core::new().unwrap();
@@ -1486,4 +3352,64 @@ pub mod tests {
         assert_eq!(result.id, BaseProductId(1));
         assert_eq!(result.is_active, false);
     }
+
+    #[test]
+    fn test_start_category_reassignment() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = CategoryReassignment {
+            store_id: Some(MOCK_STORE_ID),
+            current_category_id: None,
+            name_pattern: None,
+            new_category_id: CategoryId(3),
+        };
+        let work = service.start_category_reassignment(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.kind, JobKind::CategoryReassignment);
+        assert_eq!(result.status, JobStatus::Pending);
+    }
+
+    #[test]
+    fn test_start_category_reassignment_without_filter() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = CategoryReassignment {
+            store_id: None,
+            current_category_id: None,
+            name_pattern: None,
+            new_category_id: CategoryId(3),
+        };
+        let work = service.start_category_reassignment(payload);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_calculate_base_products_customer_price_range() {
+        let mut base_product = BaseProductsRepoMock::default()
+            .find(MOCK_BASE_PRODUCT_ID, Visibility::Active)
+            .unwrap()
+            .unwrap();
+        base_product.currency = Currency::STQ;
+        base_product.min_price = Some(ProductPrice(10f64));
+        base_product.max_price = Some(ProductPrice(30f64));
+        let mut base_products = vec![BaseProductWithVariants::new(base_product, vec![])];
+        let latest_currencies = CurrencyExchange {
+            id: Default::default(),
+            data: vec![(Currency::STQ, vec![(Currency::ETH, ExchangeRate(2f64))].into_iter().collect())]
+                .into_iter()
+                .collect(),
+            created_at: SystemTime::now(),
+        };
+
+        let converted = super::calculate_base_products_customer_price(&mut base_products, Some(latest_currencies), Currency::ETH, Currency::USD);
+
+        assert!(converted);
+        let range = base_products[0].customer_price_range.clone().unwrap();
+        assert_eq!(range.min_price.price, ProductPrice(5f64));
+        assert_eq!(range.max_price.price, ProductPrice(15f64));
+        assert_eq!(range.min_price.currency, Currency::ETH);
+    }
 }
@@ -2,6 +2,7 @@
 
 use diesel::connection::{AnsiTransactionManager, Connection};
 use diesel::pg::Pg;
+use failure::Error as FailureError;
 use r2d2::ManageConnection;
 
 use stq_types::newtypes::UserId;
@@ -9,11 +10,16 @@ use stq_types::newtypes::UserId;
 use super::types::ServiceFuture;
 use controller::responses::catalogs::*;
 use models::visibility::Visibility;
+use models::{SyncEntities, SyncEntityType, SyncRange, SyncState};
 use repos::repo_factory::ReposFactory;
 use services::Service;
 
 pub trait CatalogService {
     fn get_catalog(&self) -> ServiceFuture<CatalogResponse>;
+    /// Returns max `kafka_update_no` per entity type, so consumers can detect missed updates
+    fn get_sync_state(&self) -> ServiceFuture<SyncState>;
+    /// Returns entities in range of `kafka_update_no` for backfilling missed updates
+    fn get_sync_entities(&self, entity_type: SyncEntityType, range: SyncRange, count: i64) -> ServiceFuture<SyncEntities>;
 }
 
 impl<
@@ -68,4 +74,28 @@ impl<
             })
         })
     }
+
+    fn get_sync_state(&self) -> ServiceFuture<SyncState> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let sync_state_repo = repo_factory.create_sync_state_repo(&*conn, user_id);
+            sync_state_repo
+                .get_state()
+                .map_err(|e: FailureError| e.context("Service Catalog, get_sync_state endpoint error occurred.").into())
+        })
+    }
+
+    fn get_sync_entities(&self, entity_type: SyncEntityType, range: SyncRange, count: i64) -> ServiceFuture<SyncEntities> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let sync_state_repo = repo_factory.create_sync_state_repo(&*conn, user_id);
+            sync_state_repo
+                .find_entities(entity_type, range, count)
+                .map_err(|e: FailureError| e.context("Service Catalog, get_sync_entities endpoint error occurred.").into())
+        })
+    }
 }
@@ -1,20 +1,27 @@
 //! Categories Services, presents CRUD operations with categories
+use std::collections::BTreeSet;
 
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
 use failure::Error as FailureError;
+use failure::Fail;
+use futures::future;
 use r2d2::ManageConnection;
 
-use stq_types::{CategoryId, CategorySlug};
+use stq_types::{BaseProductId, CategoryId, CategorySlug, StoreId};
 
 use super::types::ServiceFuture;
 use errors::Error;
 use models::{Attribute, NewCatAttr, OldCatAttr};
-use models::{Category, NewCategory, UpdateCategory};
+use models::{
+    category_paths, BaseProductCategoryPath, Category, CategoryReplacePayload, CategoryTreeDiff, ImpactReport, MergeCategories,
+    MoveCategory, NewCategory, UpdateCategory,
+};
 use repos::remove_empty_children_categories;
 use repos::types::RepoResult;
 use repos::{BaseProductsRepo, BaseProductsSearchTerms, CategoriesRepo, ReposFactory};
+use services::base_products::{check_reassignment_category, update_product_categories};
 use services::Service;
 
 pub trait CategoriesService {
@@ -28,11 +35,21 @@ pub trait CategoriesService {
     fn update_category(&self, category_id: CategoryId, payload: UpdateCategory) -> ServiceFuture<Category>;
     /// Deletes category
     fn delete_category(&self, category_id: CategoryId) -> ServiceFuture<()>;
+    /// Moves category with its subtree under another parent
+    fn move_category(&self, category_id: CategoryId, payload: MoveCategory) -> ServiceFuture<Category>;
+    /// Moves base products and missing attributes of the source category to the target one, then deletes the source
+    fn merge_categories(&self, payload: MergeCategories) -> ServiceFuture<Category>;
+    /// Returns what deleting the category with its children changes, nothing is deleted
+    fn delete_category_impact(&self, category_id: CategoryId) -> ServiceFuture<ImpactReport>;
     /// Returns all categories as a tree
     fn get_all_categories(&self) -> ServiceFuture<Category>;
     /// Returns all categories as a tree
     /// Tree contains only categories where exists products
     fn get_all_categories_with_products(&self) -> ServiceFuture<Category>;
+    /// Compares exported categories tree with the live one. For superadmin
+    fn diff_categories(&self, exported: Category) -> ServiceFuture<CategoryTreeDiff>;
+    /// Returns breadcrumb paths to categories of base products, names are in the request language
+    fn get_category_paths(&self, base_product_ids: Vec<BaseProductId>) -> ServiceFuture<Vec<BaseProductCategoryPath>>;
     /// Returns all category attributes belonging to category
     fn find_all_attributes_for_category(&self, category_id_arg: CategoryId) -> ServiceFuture<Vec<Attribute>>;
     /// Creates new category attribute
@@ -131,6 +148,101 @@ impl<
         })
     }
 
+    /// Moves category with its subtree under another parent
+    fn move_category(&self, category_id: CategoryId, payload: MoveCategory) -> ServiceFuture<Category> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            categories_repo
+                .move_category(category_id, payload.parent_id)
+                .map_err(|e| e.context("Service Categories, move_category endpoint error occurred.").into())
+        })
+    }
+
+    /// Moves base products and missing attributes of the source category to the target one, then deletes the source
+    fn merge_categories(&self, payload: MergeCategories) -> ServiceFuture<Category> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let category_attrs_repo = repo_factory.create_category_attrs_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+
+            conn.transaction::<Category, FailureError, _>(|| {
+                let MergeCategories { source_id, target_id } = payload;
+                validate_categories_merge(&*categories_repo, source_id, target_id)?;
+
+                let moved_base_products = base_products_repo.replace_category(CategoryReplacePayload {
+                    current_category: source_id,
+                    new_category: target_id,
+                    base_product_ids: None,
+                })?;
+                let store_ids = moved_base_products
+                    .iter()
+                    .map(|base_product| base_product.store_id)
+                    .collect::<BTreeSet<StoreId>>();
+                for store_id in store_ids {
+                    update_product_categories(&*stores_repo, store_id, source_id, target_id)?;
+                }
+
+                let target_attrs = category_attrs_repo
+                    .find_all_attributes(target_id)?
+                    .into_iter()
+                    .map(|cat_attr| cat_attr.attr_id)
+                    .collect::<BTreeSet<_>>();
+                for cat_attr in category_attrs_repo.find_all_attributes(source_id)? {
+                    if !target_attrs.contains(&cat_attr.attr_id) {
+                        category_attrs_repo.create(NewCatAttr {
+                            cat_id: target_id,
+                            attr_id: cat_attr.attr_id,
+                        })?;
+                    }
+                }
+
+                category_attrs_repo.delete_all_by_category_ids(&[source_id])?;
+                categories_repo.delete_all(&[source_id])?;
+
+                let target = categories_repo
+                    .find(target_id)?
+                    .ok_or(format_err!("No such category with id : {}", target_id).context(Error::NotFound))?;
+                Ok(target)
+            })
+            .map(|target| {
+                // Tree could have been cached again by concurrent requests while the transaction was running
+                categories_repo.invalidate_cache();
+                target
+            })
+            .map_err(|e| e.context("Service Categories, merge_categories endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns what deleting the category with its children changes, nothing is deleted
+    fn delete_category_impact(&self, category_id: CategoryId) -> ServiceFuture<ImpactReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let base_product_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let impact_reports_repo = repo_factory.create_impact_reports_repo(&*conn, user_id);
+
+            let category: Category = categories_repo
+                .find(category_id)?
+                .ok_or(format_err!("No such category with id : {}", category_id).context(Error::NotFound))?;
+            let category_ids = category_and_children_ids(&category);
+
+            validate_category_delete(&category_ids, &*base_product_repo as &BaseProductsRepo)?;
+
+            impact_reports_repo
+                .categories_deletion(&category_ids)
+                .map_err(|e| e.context("Service Categories, delete_category_impact endpoint error occurred.").into())
+        })
+    }
+
     /// Returns category by ID
     fn get_all_categories(&self) -> ServiceFuture<Category> {
         let user_id = self.dynamic_context.user_id;
@@ -165,6 +277,50 @@ impl<
         })
     }
 
+    /// Compares exported categories tree with the live one. For superadmin
+    fn diff_categories(&self, exported: Category) -> ServiceFuture<CategoryTreeDiff> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        if !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Only superadmin can diff categories").into()));
+        }
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            categories_repo
+                .get_all_categories()
+                .map(|live| CategoryTreeDiff::new(&live, &exported))
+                .map_err(|e| e.context("Service Categories, diff_categories endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns breadcrumb paths to categories of base products, names are in the request language
+    fn get_category_paths(&self, base_product_ids: Vec<BaseProductId>) -> ServiceFuture<Vec<BaseProductCategoryPath>> {
+        let user_id = self.dynamic_context.user_id;
+        let lang = self.dynamic_context.language.to_639_1().unwrap_or("en");
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let base_products = base_products_repo.find_many(base_product_ids)?;
+                let paths = category_paths(&categories_repo.get_all_categories()?, lang);
+
+                Ok(base_products
+                    .into_iter()
+                    .map(|base_product| BaseProductCategoryPath {
+                        base_product_id: base_product.id,
+                        category_id: base_product.category_id,
+                        path: paths.get(&base_product.category_id).cloned().unwrap_or_default(),
+                    })
+                    .collect())
+            }
+            .map_err(|e: FailureError| e.context("Service Categories, get_category_paths endpoint error occurred.").into())
+        })
+    }
+
     /// Returns all category attributes belonging to category
     fn find_all_attributes_for_category(&self, category_id_arg: CategoryId) -> ServiceFuture<Vec<Attribute>> {
         let user_id = self.dynamic_context.user_id;
@@ -275,6 +431,41 @@ fn validate_category_delete(category_ids: &[CategoryId], base_products_repo: &Ba
     Ok(())
 }
 
+/// Only leaf categories can be merged, otherwise children of the source would be lost or products would get a non leaf category
+fn validate_categories_merge(categories_repo: &CategoriesRepo, source_id: CategoryId, target_id: CategoryId) -> Result<(), FailureError> {
+    if source_id == target_id {
+        return Err(format_err!("Category {} can not be merged into itself", source_id)
+            .context(Error::Validate(
+                validation_errors!({"target_id": ["target_id" => "Category can not be merged into itself."]}),
+            ))
+            .into());
+    }
+
+    let source = categories_repo
+        .find(source_id)?
+        .ok_or(format_err!("No such category with id : {}", source_id).context(Error::NotFound))?;
+    if !source.children.is_empty() {
+        return Err(format_err!("Category {} has children", source_id)
+            .context(Error::Validate(
+                validation_errors!({"source_id": ["children" => "Category with children can not be merged."]}),
+            ))
+            .into());
+    }
+
+    let target = categories_repo
+        .find(target_id)?
+        .ok_or(format_err!("No such category with id : {}", target_id).context(Error::NotFound))?;
+    if !target.children.is_empty() {
+        return Err(format_err!("Category {} has children", target_id)
+            .context(Error::Validate(
+                validation_errors!({"target_id": ["children" => "Category with children can not be merged into."]}),
+            ))
+            .into());
+    }
+
+    check_reassignment_category(categories_repo, target_id)
+}
+
 fn category_and_children_ids(category: &Category) -> Vec<CategoryId> {
     let mut ids = Vec::new();
     add_ids(category, &mut ids);
@@ -297,7 +488,7 @@ pub mod tests {
     use repos::repo_factory::tests::*;
     use services::*;
 
-    use stq_types::CategoryId;
+    use stq_types::{BaseProductId, CategoryId};
 
     pub fn create_new_categories(name: &str) -> NewCategory {
         NewCategory {
@@ -367,4 +558,90 @@ pub mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_move_category() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.move_category(CategoryId(3), MoveCategory { parent_id: CategoryId(0) });
+        let result = core.run(work).unwrap();
+        assert_eq!(result.parent_id, Some(CategoryId(0)));
+    }
+
+    #[test]
+    fn test_merge_categories() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = MergeCategories {
+            source_id: CategoryId(4),
+            target_id: CategoryId(3),
+        };
+        let work = service.merge_categories(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.id, CategoryId(3));
+    }
+
+    #[test]
+    fn test_merge_category_into_not_leaf() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = MergeCategories {
+            source_id: CategoryId(3),
+            target_id: CategoryId(4),
+        };
+        let work = service.merge_categories(payload);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_merge_category_into_itself() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = MergeCategories {
+            source_id: CategoryId(3),
+            target_id: CategoryId(3),
+        };
+        let work = service.merge_categories(payload);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_delete_category_impact() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.delete_category_impact(CategoryId(1));
+        let result = core.run(work).unwrap();
+        assert!(result.tables["categories"] > 0);
+        assert!(result.elastic_documents.is_empty());
+    }
+
+    #[test]
+    fn test_diff_categories() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.diff_categories(Category::default());
+        let result = core.run(work);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_get_category_paths() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_category_paths(vec![BaseProductId(1), BaseProductId(2)]);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].category_id, CategoryId(1));
+        assert_eq!(result[0].path.len(), 1);
+        assert_eq!(result[0].path[0].id, CategoryId(1));
+    }
+
 }
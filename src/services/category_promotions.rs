@@ -0,0 +1,187 @@
+//! CategoryPromotions Services, presents CRUD operations with promotional slots of categories.
+//! Everyone sees live promotions, superusers manage them and see scheduled and finished ones too
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use failure::Fail;
+use futures::future;
+use r2d2::ManageConnection;
+
+use stq_types::CategoryId;
+
+use errors::Error;
+use models::{CategoryPromotion, NewCategoryPromotion, UpdateCategoryPromotion};
+use repos::{CategoriesRepo, CategoryPromotionsRepo, ReposFactory};
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait CategoryPromotionsService {
+    /// Returns promotions of the category ordered by position, scheduled and finished ones are returned when `all` is set
+    fn list_category_promotions(&self, category_id: CategoryId, all: bool) -> ServiceFuture<Vec<CategoryPromotion>>;
+    /// Creates new promotion of the category
+    fn create_category_promotion(&self, payload: NewCategoryPromotion) -> ServiceFuture<CategoryPromotion>;
+    /// Updates promotion of the category
+    fn update_category_promotion(
+        &self,
+        category_id: CategoryId,
+        promotion_id: i32,
+        payload: UpdateCategoryPromotion,
+    ) -> ServiceFuture<CategoryPromotion>;
+    /// Deletes promotion of the category
+    fn delete_category_promotion(&self, category_id: CategoryId, promotion_id: i32) -> ServiceFuture<CategoryPromotion>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > CategoryPromotionsService for Service<T, M, F>
+{
+    /// Returns promotions of the category ordered by position, scheduled and finished ones are returned when `all` is set
+    fn list_category_promotions(&self, category_id: CategoryId, all: bool) -> ServiceFuture<Vec<CategoryPromotion>> {
+        if all && !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Only superusers see scheduled and finished promotions").into()));
+        }
+
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let category_promotions_repo = repo_factory.create_category_promotions_repo(&*conn, user_id);
+            check_category_exists(&*categories_repo, category_id)
+                .and_then(|_| category_promotions_repo.list(category_id, !all))
+                .map_err(|e: FailureError| e.context("Service CategoryPromotions, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Creates new promotion of the category
+    fn create_category_promotion(&self, payload: NewCategoryPromotion) -> ServiceFuture<CategoryPromotion> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let category_promotions_repo = repo_factory.create_category_promotions_repo(&*conn, user_id);
+            check_category_exists(&*categories_repo, payload.category_id)
+                .and_then(|_| category_promotions_repo.create(payload))
+                .map_err(|e: FailureError| e.context("Service CategoryPromotions, create endpoint error occurred.").into())
+        })
+    }
+
+    /// Updates promotion of the category
+    fn update_category_promotion(
+        &self,
+        category_id: CategoryId,
+        promotion_id: i32,
+        payload: UpdateCategoryPromotion,
+    ) -> ServiceFuture<CategoryPromotion> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let category_promotions_repo = repo_factory.create_category_promotions_repo(&*conn, user_id);
+            conn.transaction::<CategoryPromotion, FailureError, _>(move || {
+                let promotion = find_category_promotion(&*category_promotions_repo, category_id, promotion_id)?;
+                payload
+                    .validate_schedule(&promotion)
+                    .map_err(|e| format_err!("Validation failed, target: UpdateCategoryPromotion").context(Error::Validate(e)))?;
+                category_promotions_repo
+                    .update(promotion_id, payload)?
+                    .ok_or_else(|| format_err!("Promotion {} not found", promotion_id).context(Error::NotFound).into())
+            })
+            .map_err(|e: FailureError| e.context("Service CategoryPromotions, update endpoint error occurred.").into())
+        })
+    }
+
+    /// Deletes promotion of the category
+    fn delete_category_promotion(&self, category_id: CategoryId, promotion_id: i32) -> ServiceFuture<CategoryPromotion> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let category_promotions_repo = repo_factory.create_category_promotions_repo(&*conn, user_id);
+            conn.transaction::<CategoryPromotion, FailureError, _>(move || {
+                find_category_promotion(&*category_promotions_repo, category_id, promotion_id)?;
+                category_promotions_repo
+                    .delete(promotion_id)?
+                    .ok_or_else(|| format_err!("Promotion {} not found", promotion_id).context(Error::NotFound).into())
+            })
+            .map_err(|e: FailureError| e.context("Service CategoryPromotions, delete endpoint error occurred.").into())
+        })
+    }
+}
+
+fn check_category_exists(categories_repo: &CategoriesRepo, category_id: CategoryId) -> Result<(), FailureError> {
+    categories_repo
+        .find(category_id)?
+        .map(|_| ())
+        .ok_or_else(|| format_err!("Category {} not found", category_id).context(Error::NotFound).into())
+}
+
+fn find_category_promotion(
+    category_promotions_repo: &CategoryPromotionsRepo,
+    category_id: CategoryId,
+    promotion_id: i32,
+) -> Result<CategoryPromotion, FailureError> {
+    category_promotions_repo
+        .find(promotion_id)?
+        .filter(|promotion| promotion.category_id == category_id)
+        .ok_or_else(|| {
+            format_err!("Promotion {} of category {} not found", promotion_id, category_id)
+                .context(Error::NotFound)
+                .into()
+        })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use stq_types::CategoryId;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_list_category_promotions() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.list_category_promotions(CategoryId(1), false);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, 1);
+
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.list_category_promotions(CategoryId(1), true);
+        assert!(core.run(work).is_err());
+
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.list_category_promotions(CategoryId(1), true);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_update_category_promotion_of_other_category() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = UpdateCategoryPromotion {
+            banner_url: None,
+            target_url: Some("/sale".to_string()),
+            position: None,
+            starts_at: None,
+            ends_at: None,
+        };
+        let work = service.update_category_promotion(CategoryId(2), 1, payload);
+        assert!(core.run(work).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Chaos Services, lets superusers set faults of the layers during resilience tests
+use std::collections::HashMap;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use failure::Fail;
+use futures::Future;
+use r2d2::ManageConnection;
+
+use stq_types::StoresRole;
+
+use chaos::{self, Fault, FaultLayer};
+use errors::Error;
+use repos::ReposFactory;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait ChaosService {
+    /// Returns faults set for the layers
+    fn get_faults(&self) -> ServiceFuture<HashMap<FaultLayer, Fault>>;
+    /// Sets fault of the layer
+    fn set_fault(&self, layer: FaultLayer, fault: Fault) -> ServiceFuture<Fault>;
+    /// Removes faults of all layers
+    fn reset_faults(&self) -> ServiceFuture<()>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > ChaosService for Service<T, M, F>
+{
+    /// Returns faults set for the layers
+    fn get_faults(&self) -> ServiceFuture<HashMap<FaultLayer, Fault>> {
+        Box::new(self.check_superuser().map(|_| chaos::faults()))
+    }
+
+    /// Sets fault of the layer
+    fn set_fault(&self, layer: FaultLayer, fault: Fault) -> ServiceFuture<Fault> {
+        Box::new(self.check_superuser().map(move |_| {
+            warn!("Injecting {} fault {:?}", layer, fault);
+            chaos::set_fault(layer, fault);
+            fault
+        }))
+    }
+
+    /// Removes faults of all layers
+    fn reset_faults(&self) -> ServiceFuture<()> {
+        Box::new(self.check_superuser().map(|_| {
+            warn!("Removing all injected faults");
+            chaos::reset_faults()
+        }))
+    }
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > Service<T, M, F>
+{
+    /// Roles are read bypassing `spawn_on_pool`, otherwise repo faults could lock superusers out of removing them
+    fn check_superuser(&self) -> ServiceFuture<()> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let db_pool = self.static_context.db_pool.clone();
+        let cpu_pool = self.static_context.cpu_pool.clone();
+
+        Box::new(cpu_pool.spawn_fn(move || {
+            {
+                let user_id = user_id.ok_or_else(|| format_err!("Fault injection requires authorized user").context(Error::Forbidden))?;
+                let conn = db_pool.get().map_err(|e| e.context(Error::Connection))?;
+                let user_roles_repo = repo_factory.create_user_roles_repo(&*conn, Some(user_id));
+                if user_roles_repo.list_for_user(user_id)?.contains(&StoresRole::Superuser) {
+                    Ok(())
+                } else {
+                    Err(format_err!("Fault injection is allowed to superusers only").context(Error::Forbidden).into())
+                }
+            }
+            .map_err(|e: FailureError| e.context("Service Chaos, check_superuser error occurred.").into())
+        }))
+    }
+}
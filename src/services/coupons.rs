@@ -12,15 +12,16 @@ use future::IntoFuture;
 use futures::future;
 
 use uuid::prelude::*;
+use validator::ValidationErrors;
 
-use stq_types::{BaseProductId, CouponId, UserId};
+use stq_types::{BaseProductId, CategoryId, CouponId, StoresRole, UserId};
 
 use super::types::ServiceFuture;
 use errors::Error;
 use models::*;
 use repos::CouponSearch;
 
-use repos::{CouponValidate, RepoResult, ReposFactory, UsedCouponSearch};
+use repos::{CategoriesRepo, CouponValidate, RepoResult, ReposFactory, UsedCouponSearch};
 use services::products::calculate_product_customer_price;
 use services::Service;
 
@@ -35,6 +36,14 @@ pub trait CouponsService {
     fn get_coupon_by_code(&self, payload: CouponsSearchCodePayload) -> ServiceFuture<Option<Coupon>>;
     /// Search coupons
     fn find_coupons(&self, search: CouponSearch) -> ServiceFuture<Vec<Coupon>>;
+    /// Search coupons of all stores. For moderators
+    fn admin_search_coupons(
+        &self,
+        from: Option<CouponId>,
+        skip: i64,
+        count: i64,
+        terms: AdminCouponsSearchTerms,
+    ) -> ServiceFuture<AdminCouponsSearchResults>;
     /// Update coupon
     fn update_coupon(&self, id_arg: CouponId, payload: UpdateCoupon) -> ServiceFuture<Coupon>;
     /// Deletes coupons
@@ -55,6 +64,10 @@ pub trait CouponsService {
     fn validate_coupon_by_code(&self, payload: CouponsSearchCodePayload) -> ServiceFuture<Option<CouponValidate>>;
     /// Validate coupon by coupon id
     fn validate_coupon(&self, id_arg: CouponId) -> ServiceFuture<Option<CouponValidate>>;
+    /// Validate coupon for user's product and record its usage in one transaction
+    fn redeem_coupon(&self, payload: RedeemCouponPayload) -> ServiceFuture<CouponRedemption>;
+    /// Void coupon redemption, used on saga rollback
+    fn void_coupon_redemption(&self, token: Uuid) -> ServiceFuture<CouponRedemption>;
 }
 
 impl<
@@ -134,6 +147,48 @@ impl<
         })
     }
 
+    /// Search coupons of all stores. For moderators
+    fn admin_search_coupons(
+        &self,
+        from: Option<CouponId>,
+        skip: i64,
+        count: i64,
+        terms: AdminCouponsSearchTerms,
+    ) -> ServiceFuture<AdminCouponsSearchResults> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        debug!(
+            "Searching for coupons of all stores (from id: {:?}, skip: {}, count: {}) with terms: {:?}",
+            from, skip, count, terms
+        );
+
+        let pagination_params = PaginationParams {
+            direction: Direction::Reverse,
+            limit: count,
+            ordering: Ordering::Descending,
+            skip,
+            start: from.filter(|id| id.0 > 0),
+        };
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let user_id = user_id.ok_or_else(|| format_err!("Coupons search requires authorized user").context(Error::Forbidden))?;
+                let user_roles_repo = repo_factory.create_user_roles_repo(&*conn, Some(user_id));
+                let roles = user_roles_repo.list_for_user(user_id)?;
+                if roles.contains(&StoresRole::Superuser) || roles.contains(&StoresRole::Moderator) {
+                    let coupon_repo = repo_factory.create_coupon_repo(&*conn, Some(user_id));
+                    coupon_repo.admin_search(pagination_params, terms)
+                } else {
+                    Err(format_err!("Coupons of all stores are listed to moderators only")
+                        .context(Error::Forbidden)
+                        .into())
+                }
+            }
+            .map_err(|e: FailureError| e.context("Service Coupons, admin_search_coupons endpoint error occurred.").into())
+        })
+    }
+
     /// Update coupon
     fn update_coupon(&self, id_arg: CouponId, payload: UpdateCoupon) -> ServiceFuture<Coupon> {
         let user_id = self.dynamic_context.user_id;
@@ -375,6 +430,130 @@ impl<
             .map_err(|e: FailureError| e.context("Service Coupons, validate_coupon endpoint error occurred.").into())
         })
     }
+
+    /// Validate coupon for user's product and record its usage in one transaction
+    fn redeem_coupon(&self, payload: RedeemCouponPayload) -> ServiceFuture<CouponRedemption> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        info!("Redeem coupon {} for user {}", payload.code, payload.user_id);
+
+        self.spawn_on_pool(move |conn| {
+            let coupon_repo = repo_factory.create_coupon_repo(&*conn, user_id);
+            let used_coupons_repo = repo_factory.create_used_coupons_repo(&*conn, user_id);
+            let coupon_redemptions_repo = repo_factory.create_coupon_redemptions_repo(&*conn, user_id);
+            let coupon_scope_base_products_repo = repo_factory.create_coupon_scope_base_products_repo(&*conn, user_id);
+            let coupon_scope_categories_repo = repo_factory.create_coupon_scope_categories_repo(&*conn, user_id);
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+
+            conn.transaction::<CouponRedemption, FailureError, _>(move || {
+                // coupon row stays locked till commit, so concurrent orders can not exceed the quantity
+                let coupon = coupon_repo
+                    .get_by_code_for_update(payload.code.clone(), payload.store_id)?
+                    .ok_or(format_err!("Coupon with code {} not found.", payload.code).context(Error::NotFound))?;
+
+                let used_coupons = used_coupons_repo.find_by(UsedCouponSearch::Coupon(coupon.id))?;
+                let check_result = validate_coupon(coupon.clone(), payload.user_id, used_coupons);
+                if check_result != CouponValidate::Valid {
+                    return Err(format_err!("Coupon {} can not be redeemed: {:?}", coupon.id, check_result)
+                        .context(Error::Validate(coupon_validate_errors(&check_result)))
+                        .into());
+                }
+
+                let product = products_repo
+                    .find(payload.product_id)?
+                    .ok_or(format_err!("Product with id {} not found.", payload.product_id).context(Error::NotFound))?;
+                let base_product = base_products_repo
+                    .find(product.base_product_id, Visibility::Published)?
+                    .ok_or(format_err!("Base product with id {} not found.", product.base_product_id).context(Error::NotFound))?;
+
+                let in_scope = base_product.store_id == coupon.store_id
+                    && match coupon.scope {
+                        CouponScope::BaseProducts => coupon_scope_base_products_repo
+                            .find_base_products(coupon.id)?
+                            .contains(&base_product.id),
+                        CouponScope::Categories => {
+                            // coupon of a category is valid for all its subcategories
+                            let coupon_categories = coupon_scope_categories_repo.find_categories(coupon.id)?;
+                            category_with_ancestors(&*categories_repo, base_product.category_id)?
+                                .iter()
+                                .any(|category_id| coupon_categories.contains(category_id))
+                        }
+                        CouponScope::Store => true,
+                    };
+                if !in_scope {
+                    return Err(format_err!("Product {} is out of coupon {} scope.", payload.product_id, coupon.id)
+                        .context(Error::Validate(
+                            validation_errors!({"coupon": ["scope" => "Coupon can not be applied to the product"]}),
+                        ))
+                        .into());
+                }
+
+                used_coupons_repo.create(NewUsedCoupon {
+                    coupon_id: coupon.id,
+                    user_id: payload.user_id,
+                })?;
+
+                coupon_redemptions_repo.create(NewCouponRedemption {
+                    token: Uuid::new_v4(),
+                    coupon_id: coupon.id,
+                    user_id: payload.user_id,
+                    product_id: payload.product_id,
+                })
+            })
+            .map_err(|e| e.context("Service Coupons, redeem_coupon endpoint error occurred.").into())
+        })
+    }
+
+    /// Void coupon redemption, used on saga rollback
+    fn void_coupon_redemption(&self, token: Uuid) -> ServiceFuture<CouponRedemption> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        info!("Void coupon redemption {}", token);
+
+        self.spawn_on_pool(move |conn| {
+            let used_coupons_repo = repo_factory.create_used_coupons_repo(&*conn, user_id);
+            let coupon_redemptions_repo = repo_factory.create_coupon_redemptions_repo(&*conn, user_id);
+
+            conn.transaction::<CouponRedemption, FailureError, _>(move || {
+                let redemption = coupon_redemptions_repo.delete(token)?;
+                used_coupons_repo.delete(redemption.coupon_id, redemption.user_id)?;
+
+                Ok(redemption)
+            })
+            .map_err(|e| {
+                e.context("Service Coupons, void_coupon_redemption endpoint error occurred.")
+                    .into()
+            })
+        })
+    }
+}
+
+fn coupon_validate_errors(check_result: &CouponValidate) -> ValidationErrors {
+    match check_result {
+        CouponValidate::NotActive => validation_errors!({"coupon": ["not_active" => "Coupon is not active"]}),
+        CouponValidate::HasExpired => validation_errors!({"coupon": ["has_expired" => "Coupon has expired"]}),
+        CouponValidate::NoActivationsAvailable => validation_errors!({"coupon": ["no_activations" => "No activations available"]}),
+        CouponValidate::AlreadyActivated => validation_errors!({"coupon": ["already_activated" => "Coupon already activated by user"]}),
+        CouponValidate::Valid => ValidationErrors::new(),
+    }
+}
+
+/// Category and its parents up to the root
+fn category_with_ancestors(categories_repo: &CategoriesRepo, category_id: CategoryId) -> RepoResult<Vec<CategoryId>> {
+    let mut categories = vec![category_id];
+    let mut current = category_id;
+    for _ in 0..Category::MAX_LEVEL_NESTING {
+        match categories_repo.find(current)?.and_then(|category| category.parent_id) {
+            Some(parent_id) => {
+                categories.push(parent_id);
+                current = parent_id;
+            }
+            None => break,
+        }
+    }
+    Ok(categories)
 }
 
 pub fn validate_coupon(coupon: Coupon, user_id: UserId, used_coupons: Vec<UsedCoupon>) -> CouponValidate {
@@ -420,6 +599,7 @@ pub mod tests {
 
     use std::time::{self, Duration, SystemTime};
     use tokio_core::reactor::Core;
+    use uuid::Uuid;
 
     use stq_types::*;
 
@@ -496,6 +676,24 @@ pub mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_admin_search_coupons() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle.clone());
+        let terms = AdminCouponsSearchTerms {
+            store_id: Some(StoreId(1)),
+            ..Default::default()
+        };
+        let work = service.admin_search_coupons(None, 0, 10, terms.clone());
+        let result = core.run(work).unwrap();
+        assert_eq!(result.total_count, 1);
+
+        let service = create_service(Some(UserId(2)), handle);
+        let work = service.admin_search_coupons(None, 0, 10, terms);
+        assert!(core.run(work).is_err());
+    }
+
     #[test]
     fn test_delete_coupon() {
         let mut core = Core::new().unwrap();
@@ -555,6 +753,35 @@ pub mod tests {
         assert_eq!(result.is_ok(), true);
     }
 
+    #[test]
+    fn test_void_coupon_redemption() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let token = Uuid::new_v4();
+        let work = service.void_coupon_redemption(token);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.token, token);
+        assert_eq!(result.coupon_id, MOCK_COUPON_ID);
+    }
+
+    #[test]
+    fn test_redeem_coupon_out_of_categories() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        // coupon is limited to category 10, while the product is in category 1 with no such parent
+        let payload = RedeemCouponPayload {
+            code: CouponCode(MOCK_CATEGORIES_COUPON_CODE.to_string()),
+            store_id: MOCK_STORE_ID,
+            user_id: MOCK_USER_ID_PLUS1,
+            product_id: MOCK_PRODUCT_ID,
+        };
+        let work = service.redeem_coupon(payload);
+        let err = core.run(work).unwrap_err();
+        assert!(err.iter_chain().any(|cause| cause.to_string().contains("out of coupon")));
+    }
+
     fn create_test_coupon() -> Coupon {
         Coupon {
             id: MOCK_COUPON_ID,
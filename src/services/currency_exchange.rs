@@ -3,10 +3,11 @@
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
+use futures::future;
 use r2d2::ManageConnection;
 
 use super::types::ServiceFuture;
-use models::{CurrencyExchange, NewCurrencyExchange};
+use models::{CurrencyExchange, CurrencyMeta, NewCurrencyExchange};
 use repos::ReposFactory;
 use services::Service;
 
@@ -15,6 +16,8 @@ pub trait CurrencyExchangeService {
     fn get_latest_currencies(&self) -> ServiceFuture<Option<CurrencyExchange>>;
     /// Updates currencies exchange
     fn update_currencies(&self, payload: NewCurrencyExchange) -> ServiceFuture<CurrencyExchange>;
+    /// Returns formatting metadata of all currencies
+    fn get_currencies_meta(&self) -> ServiceFuture<Vec<CurrencyMeta>>;
 }
 
 impl<
@@ -48,6 +51,11 @@ impl<
                 .map_err(|e| e.context("Service CurrencyExchange, update endpoint error occurred.").into())
         })
     }
+
+    /// Returns formatting metadata of all currencies
+    fn get_currencies_meta(&self) -> ServiceFuture<Vec<CurrencyMeta>> {
+        Box::new(future::ok(CurrencyMeta::all()))
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +97,17 @@ pub mod tests {
         assert_eq!(result.is_ok(), true);
     }
 
+    #[test]
+    fn test_get_currencies_meta() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_currencies_meta();
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), Currency::enum_iter().count());
+        let usd = result.iter().find(|meta| meta.currency == Currency::USD).unwrap();
+        assert_eq!(usd.symbol, "$");
+        assert_eq!(usd.decimal_places, 2);
+    }
+
 }
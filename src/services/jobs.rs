@@ -0,0 +1,236 @@
+//! Jobs Services, long-running work returns job id at once and runs in background reporting its progress
+use std::cell::{Cell, RefCell};
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use failure::Fail;
+use futures::future;
+use futures::Future;
+use r2d2::ManageConnection;
+use serde_json;
+
+use stq_types::UserId;
+
+use errors::Error;
+use models::*;
+use repos::{JobsRepo, RepoResult, ReposFactory};
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait JobsService {
+    /// Returns status, progress and item errors of the job
+    fn get_job(&self, job_id: i32) -> ServiceFuture<JobReport>;
+    /// Cancels pending or running job, the work stops at its next progress update
+    fn cancel_job(&self, job_id: i32) -> ServiceFuture<JobReport>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > JobsService for Service<T, M, F>
+{
+    /// Returns status, progress and item errors of the job
+    fn get_job(&self, job_id: i32) -> ServiceFuture<JobReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let jobs_repo = repo_factory.create_jobs_repo(&*conn, user_id);
+            find_job(&*jobs_repo, job_id)
+                .map(JobReport::from)
+                .map_err(|e: FailureError| e.context("Service jobs, get_job endpoint error occurred.").into())
+        })
+    }
+
+    /// Cancels pending or running job, the work stops at its next progress update
+    fn cancel_job(&self, job_id: i32) -> ServiceFuture<JobReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        info!("Cancel job {}", job_id);
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let jobs_repo = repo_factory.create_jobs_repo(&*conn, user_id);
+                let job = find_job(&*jobs_repo, job_id)?;
+                if job.status.is_done() {
+                    return Err(format_err!("Job {} is already {:?}", job_id, job.status)
+                        .context(Error::Validate(validation_errors!({
+                            "status": ["status" => "Only pending or running jobs can be canceled"]
+                        })))
+                        .into());
+                }
+
+                jobs_repo
+                    .update(
+                        job_id,
+                        UpdateJob {
+                            status: Some(JobStatus::Canceled),
+                            ..Default::default()
+                        },
+                    )
+                    .map(JobReport::from)
+            }
+            .map_err(|e: FailureError| e.context("Service jobs, cancel_job endpoint error occurred.").into())
+        })
+    }
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > Service<T, M, F>
+{
+    /// Creates job of the current user and runs `work` in background, the job is returned before the work starts
+    pub fn start_job<Func>(&self, kind: JobKind, work: Func) -> ServiceFuture<Job>
+    where
+        Func: FnOnce(&T, &JobTracker) -> RepoResult<()> + Send + 'static,
+    {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let service = self.clone();
+        info!("Start {:?} job", kind);
+
+        let owner_id = match user_id {
+            Some(user_id) => user_id,
+            None => return Box::new(future::err(Error::Forbidden.context("Jobs require authorization").into())),
+        };
+
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                let jobs_repo = repo_factory.create_jobs_repo(&*conn, user_id);
+                jobs_repo.create(NewJob::new(owner_id, kind))
+            })
+            .map(move |job| {
+                let repo_factory = service.static_context.repo_factory.clone();
+                let job_id = job.id;
+                service.spawn_background(move |conn| run_job(&*conn, &repo_factory, user_id, job_id, work));
+                job
+            })
+            .map_err(|e: FailureError| e.context("Service jobs, start_job error occurred.").into()),
+        )
+    }
+}
+
+/// Progress of the running job, it is saved to the jobs table on every update
+pub struct JobTracker<'a> {
+    jobs_repo: Box<JobsRepo + 'a>,
+    job_id: i32,
+    errors: RefCell<Vec<JobItemError>>,
+    canceled: Cell<bool>,
+}
+
+impl<'a> JobTracker<'a> {
+    fn new(jobs_repo: Box<JobsRepo + 'a>, job_id: i32) -> Self {
+        Self {
+            jobs_repo,
+            job_id,
+            errors: RefCell::new(Vec::new()),
+            canceled: Cell::new(false),
+        }
+    }
+
+    pub fn set_total(&self, total: usize) -> RepoResult<()> {
+        self.save(UpdateJob {
+            total: Some(total as i32),
+            ..Default::default()
+        })
+    }
+
+    /// Errors are saved with the next progress update
+    pub fn add_error(&self, item: String, message: String) {
+        self.errors.borrow_mut().push(JobItemError { item, message });
+    }
+
+    pub fn set_processed(&self, processed: usize) -> RepoResult<()> {
+        let errors = serde_json::to_value(&*self.errors.borrow())?;
+        self.save(UpdateJob {
+            processed: Some(processed as i32),
+            errors: Some(errors),
+            ..Default::default()
+        })
+    }
+
+    /// Becomes true after any update of the canceled job, the work must stop then
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.get()
+    }
+
+    fn save(&self, payload: UpdateJob) -> RepoResult<()> {
+        let job = self.jobs_repo.update(self.job_id, payload)?;
+        self.canceled.set(job.status == JobStatus::Canceled);
+        Ok(())
+    }
+}
+
+fn find_job(jobs_repo: &JobsRepo, job_id: i32) -> RepoResult<Job> {
+    jobs_repo
+        .find(job_id)?
+        .ok_or_else(|| format_err!("Job {} not found", job_id).context(Error::NotFound).into())
+}
+
+/// Runs the work of the job and records its result, error of the work is saved as the last item error
+fn run_job<T, F, Func>(conn: &T, repo_factory: &F, user_id: Option<UserId>, job_id: i32, work: Func) -> RepoResult<()>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+    Func: FnOnce(&T, &JobTracker) -> RepoResult<()>,
+{
+    let tracker = JobTracker::new(repo_factory.create_jobs_repo(conn, user_id), job_id);
+    tracker.save(UpdateJob {
+        status: Some(JobStatus::Running),
+        ..Default::default()
+    })?;
+
+    let result = if tracker.is_canceled() { Ok(()) } else { work(conn, &tracker) };
+    let status = match result {
+        Ok(_) => JobStatus::Finished,
+        Err(ref e) => {
+            tracker.add_error("job".to_string(), e.to_string());
+            JobStatus::Failed
+        }
+    };
+    let errors = serde_json::to_value(&*tracker.errors.borrow())?;
+    tracker.save(UpdateJob {
+        status: Some(status),
+        errors: Some(errors),
+        ..Default::default()
+    })?;
+
+    result
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_get_job() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_job(1);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.job.status, JobStatus::Running);
+        assert_eq!(result.progress_percent, 25);
+    }
+
+    #[test]
+    fn test_cancel_job() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.cancel_job(1);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.job.status, JobStatus::Canceled);
+    }
+}
@@ -0,0 +1,132 @@
+//! Listings Services, groups identical products of different stores and returns their best offers
+use std::collections::HashMap;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+
+use stq_types::StoreId;
+
+use errors::Error;
+use models::{BaseProductDetails, Listing, ListingOffer, ListingOffers, NewListing, ProductMatchPayload, Visibility};
+use repos::ReposFactory;
+use services::calculate_base_product_details_customer_price;
+use services::price_rules::apply_price_rules_to_details;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait ListingsService {
+    /// Returns listing of products matching the payload, creates it if there is none
+    fn create_listing(&self, payload: ProductMatchPayload) -> ServiceFuture<Listing>;
+    /// Returns best offers of the listing
+    fn get_listing_offers(&self, listing_id: i32, count: usize) -> ServiceFuture<ListingOffers>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > ListingsService for Service<T, M, F>
+{
+    /// Returns listing of products matching the payload, creates it if there is none
+    fn create_listing(&self, payload: ProductMatchPayload) -> ServiceFuture<Listing> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let listings_repo = repo_factory.create_listings_repo(&*conn, user_id);
+            let payload = NewListing::from(payload);
+            conn.transaction::<Listing, FailureError, _>(move || match listings_repo.find_by_attributes(&payload)? {
+                Some(listing) => Ok(listing),
+                None => listings_repo.create(payload),
+            })
+            .map_err(|e: FailureError| e.context("Service Listings, create endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns best offers of the listing
+    fn get_listing_offers(&self, listing_id: i32, count: usize) -> ServiceFuture<ListingOffers> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let listings_repo = repo_factory.create_listings_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+
+                let listing = listings_repo
+                    .find(listing_id)?
+                    .ok_or_else(|| format_err!("Listing {} not found", listing_id).context(Error::NotFound))?;
+
+                let mut base_products = base_products_repo
+                    .find_by_attribute_fingerprint(listing.category_id, listing.fingerprint())?
+                    .into_iter()
+                    .map(BaseProductDetails::from)
+                    .collect::<Vec<_>>();
+                let latest_currencies = currency_exchange.get_latest()?;
+                calculate_base_product_details_customer_price(&mut base_products, latest_currencies, currency, fiat_currency);
+                apply_price_rules_to_details(&*conn, &repo_factory, &price_rules_config, &mut base_products)?;
+
+                let mut store_ratings = HashMap::<StoreId, f64>::new();
+                let mut offers = vec![];
+                for base_product in base_products {
+                    let store_id = base_product.base_product.store_id;
+                    let base_product_id = base_product.base_product.id;
+                    if !store_ratings.contains_key(&store_id) {
+                        let rating = stores_repo
+                            .find(store_id, Visibility::Published)?
+                            .map(|store| store.rating)
+                            .unwrap_or_default();
+                        store_ratings.insert(store_id, rating);
+                    }
+
+                    let store_rating = store_ratings[&store_id];
+                    offers.extend(base_product.variants.into_iter().map(|variant| ListingOffer {
+                        store_id,
+                        store_rating,
+                        base_product_id,
+                        product_id: variant.product.product.id,
+                        customer_price: variant.product.customer_price,
+                    }));
+                }
+
+                Ok(ListingOffers::new(listing, offers, count))
+            }
+            .map_err(|e: FailureError| e.context("Service Listings, get_offers endpoint error occurred.").into())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use serde_json;
+    use tokio_core::reactor::Core;
+
+    use models::ProductMatchPayload;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_create_listing_normalizes_attributes() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = serde_json::from_value::<ProductMatchPayload>(json!({
+            "category_id": 1,
+            "attributes": [{"attr_id": 2, "value": " XL"}, {"attr_id": 1, "value": "Red"}]
+        }))
+        .unwrap();
+        let work = service.create_listing(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.attributes, json!([{"attr_id": 1, "value": "red"}, {"attr_id": 2, "value": "xl"}]));
+    }
+}
@@ -1,31 +1,57 @@
 //! Services is a core layer for the app business logic like
 //! validation, authorization, etc.
 
+pub mod attribute_groups;
 pub mod attribute_values;
 pub mod attributes;
 pub mod base_products;
 pub mod catalogs;
 pub mod categories;
+pub mod category_promotions;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod coupons;
 pub mod currency_exchange;
 pub mod custom_attributes;
+pub mod jobs;
+pub mod listings;
+pub mod moderation_checklists;
 pub mod moderator_comments;
+pub mod price_rules;
 pub mod products;
+pub mod retention;
+pub mod search_synonyms;
+pub mod stock_reservations;
+pub mod store_categories;
+pub mod store_faqs;
 pub mod stores;
 pub mod types;
 pub mod user_roles;
 pub mod wizard_stores;
 
+pub use self::attribute_groups::*;
 pub use self::attribute_values::*;
 pub use self::attributes::*;
 pub use self::base_products::*;
 pub use self::catalogs::*;
 pub use self::categories::*;
+pub use self::category_promotions::*;
+#[cfg(feature = "chaos")]
+pub use self::chaos::*;
 pub use self::coupons::*;
 pub use self::currency_exchange::*;
 pub use self::custom_attributes::*;
+pub use self::jobs::*;
+pub use self::listings::*;
+pub use self::moderation_checklists::*;
 pub use self::moderator_comments::*;
+pub use self::price_rules::*;
 pub use self::products::*;
+pub use self::retention::*;
+pub use self::search_synonyms::*;
+pub use self::stock_reservations::*;
+pub use self::store_categories::*;
+pub use self::store_faqs::*;
 pub use self::stores::*;
 pub use self::types::*;
 pub use self::user_roles::*;
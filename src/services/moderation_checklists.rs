@@ -0,0 +1,201 @@
+//! ModerationChecklists Services, presents CRUD operations with checklist items of categories
+//! and decline details of base products built from the latest answers of the moderator
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+
+use stq_types::{BaseProductId, CategoryId, UserId};
+
+use errors::Error;
+use models::*;
+use repos::{CategoriesRepo, ModerationChecklistsRepo, ReposFactory};
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait ModerationChecklistsService {
+    /// Returns items applying to base products of the category, including items of its parents
+    fn list_moderation_checklist(&self, category_id: CategoryId) -> ServiceFuture<Vec<ModerationChecklistItem>>;
+    /// Creates new item of the category
+    fn create_moderation_checklist_item(&self, payload: NewModerationChecklistItem) -> ServiceFuture<ModerationChecklistItem>;
+    /// Updates specific item
+    fn update_moderation_checklist_item(&self, item_id: i32, payload: UpdateModerationChecklistItem)
+        -> ServiceFuture<ModerationChecklistItem>;
+    /// Deletes specific item
+    fn delete_moderation_checklist_item(&self, item_id: i32) -> ServiceFuture<ModerationChecklistItem>;
+    /// Returns comment of the moderator and unmet checklist items of the declined base product
+    fn get_base_product_decline_details(&self, base_product_id: BaseProductId) -> ServiceFuture<DeclineDetails>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > ModerationChecklistsService for Service<T, M, F>
+{
+    /// Returns items applying to base products of the category, including items of its parents
+    fn list_moderation_checklist(&self, category_id: CategoryId) -> ServiceFuture<Vec<ModerationChecklistItem>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let checklists_repo = repo_factory.create_moderation_checklists_repo(&*conn, user_id);
+            find_checklist_items(&*checklists_repo, &*categories_repo, category_id)
+                .map_err(|e: FailureError| e.context("Service ModerationChecklists, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Creates new item of the category
+    fn create_moderation_checklist_item(&self, payload: NewModerationChecklistItem) -> ServiceFuture<ModerationChecklistItem> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let checklists_repo = repo_factory.create_moderation_checklists_repo(&*conn, user_id);
+            checklists_repo
+                .create_item(payload)
+                .map_err(|e: FailureError| e.context("Service ModerationChecklists, create endpoint error occurred.").into())
+        })
+    }
+
+    /// Updates specific item
+    fn update_moderation_checklist_item(
+        &self,
+        item_id: i32,
+        payload: UpdateModerationChecklistItem,
+    ) -> ServiceFuture<ModerationChecklistItem> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let checklists_repo = repo_factory.create_moderation_checklists_repo(&*conn, user_id);
+            checklists_repo
+                .update_item(item_id, payload)
+                .and_then(|item| {
+                    item.ok_or_else(|| format_err!("Moderation checklist item {} not found", item_id).context(Error::NotFound).into())
+                })
+                .map_err(|e: FailureError| e.context("Service ModerationChecklists, update endpoint error occurred.").into())
+        })
+    }
+
+    /// Deletes specific item
+    fn delete_moderation_checklist_item(&self, item_id: i32) -> ServiceFuture<ModerationChecklistItem> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let checklists_repo = repo_factory.create_moderation_checklists_repo(&*conn, user_id);
+            checklists_repo
+                .delete_item(item_id)
+                .and_then(|item| {
+                    item.ok_or_else(|| format_err!("Moderation checklist item {} not found", item_id).context(Error::NotFound).into())
+                })
+                .map_err(|e: FailureError| e.context("Service ModerationChecklists, delete endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns comment of the moderator and unmet checklist items of the declined base product
+    fn get_base_product_decline_details(&self, base_product_id: BaseProductId) -> ServiceFuture<DeclineDetails> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let moderator_comments_repo = repo_factory.create_moderator_product_comments_repo(&*conn, user_id);
+                let checklists_repo = repo_factory.create_moderation_checklists_repo(&*conn, user_id);
+
+                let base_product = base_products_repo
+                    .find(base_product_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Base product {} not found", base_product_id).context(Error::NotFound))?;
+
+                Ok(DeclineDetails {
+                    base_product_id,
+                    status: base_product.status,
+                    comment: moderator_comments_repo.find_by_base_product_id(base_product_id)?,
+                    unmet_items: checklists_repo.find_unmet_items(base_product_id)?,
+                })
+            }
+            .map_err(|e: FailureError| {
+                e.context("Service ModerationChecklists, get_base_product_decline_details endpoint error occurred.")
+                    .into()
+            })
+        })
+    }
+}
+
+/// Items applying to base products of the category, including items of its parents
+pub fn find_checklist_items(
+    checklists_repo: &ModerationChecklistsRepo,
+    categories_repo: &CategoriesRepo,
+    category_id: CategoryId,
+) -> Result<Vec<ModerationChecklistItem>, FailureError> {
+    let categories = categories_repo.get_raw_categories()?;
+    checklists_repo.list_items(category_with_parents(&categories, category_id))
+}
+
+/// Saves answers of the moderator declining the base product instead of the previous ones.
+/// Every answered item must apply to the category of the base product
+pub fn save_checklist_results(
+    checklists_repo: &ModerationChecklistsRepo,
+    categories_repo: &CategoriesRepo,
+    base_product: &BaseProduct,
+    moderator_id: Option<UserId>,
+    checklist: Vec<ChecklistResult>,
+) -> Result<Vec<BaseProductChecklistResult>, FailureError> {
+    let moderator_id = moderator_id.ok_or_else(|| format_err!("Checklist is answered by moderators only").context(Error::Forbidden))?;
+    let items = find_checklist_items(checklists_repo, categories_repo, base_product.category_id)?;
+
+    if let Some(result) = checklist.iter().find(|result| !items.iter().any(|item| item.id == result.item_id)) {
+        return Err(format_err!(
+            "Checklist item {} does not apply to category {}",
+            result.item_id,
+            base_product.category_id
+        )
+        .context(Error::Validate(
+            validation_errors!({"checklist": ["checklist" => "Checklist item does not apply to the category of the base product"]}),
+        ))
+        .into());
+    }
+
+    let payload = checklist
+        .into_iter()
+        .map(|result| NewBaseProductChecklistResult::new(base_product.id, moderator_id, result))
+        .collect();
+    checklists_repo.replace_results(base_product.id, payload)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use stq_types::{BaseProductId, CategoryId};
+
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_list_moderation_checklist() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.list_moderation_checklist(CategoryId(3));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.iter().map(|item| item.id).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_get_base_product_decline_details() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_base_product_decline_details(BaseProductId(1));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.unmet_items.len(), 1);
+        assert_eq!(result.unmet_items[0].description, "CE certification photo present");
+    }
+}
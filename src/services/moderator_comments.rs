@@ -21,6 +21,12 @@ pub trait ModeratorCommentsService {
     fn get_latest_for_store(&self, store_id: StoreId) -> ServiceFuture<Option<ModeratorStoreComments>>;
     /// Creates new moderator store comment
     fn create_store_comment(&self, payload: NewModeratorStoreComments) -> ServiceFuture<ModeratorStoreComments>;
+    /// Returns comments of the base product, newest first
+    fn get_product_comments(&self, base_product_id: BaseProductId, skip: i64, count: i64) -> ServiceFuture<Vec<ModeratorProductComments>>;
+    /// Returns comments of the store, newest first
+    fn get_store_comments(&self, store_id: StoreId, skip: i64, count: i64) -> ServiceFuture<Vec<ModeratorStoreComments>>;
+    /// Searches comments of base products and stores together, newest first. `from` comments are skipped
+    fn search_comments(&self, terms: ModeratorCommentsSearchTerms, from: i64, count: i64) -> ServiceFuture<Vec<ModeratorComment>>;
 }
 
 impl<
@@ -84,6 +90,59 @@ impl<
                 })
         })
     }
+
+    /// Returns comments of the base product, newest first
+    fn get_product_comments(&self, base_product_id: BaseProductId, skip: i64, count: i64) -> ServiceFuture<Vec<ModeratorProductComments>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let moderator_product_repo = repo_factory.create_moderator_product_comments_repo(&*conn, user_id);
+            moderator_product_repo
+                .list_by_base_product_id(base_product_id, skip, count)
+                .map_err(|e| e.context("Service ModeratorComments, get_product_comments endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns comments of the store, newest first
+    fn get_store_comments(&self, store_id: StoreId, skip: i64, count: i64) -> ServiceFuture<Vec<ModeratorStoreComments>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let moderator_store_repo = repo_factory.create_moderator_store_comments_repo(&*conn, user_id);
+            moderator_store_repo
+                .list_by_store_id(store_id, skip, count)
+                .map_err(|e| e.context("Service ModeratorComments, get_store_comments endpoint error occurred.").into())
+        })
+    }
+
+    /// Searches comments of base products and stores together, newest first. `from` comments are skipped
+    fn search_comments(&self, terms: ModeratorCommentsSearchTerms, from: i64, count: i64) -> ServiceFuture<Vec<ModeratorComment>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let moderator_product_repo = repo_factory.create_moderator_product_comments_repo(&*conn, user_id);
+                let moderator_store_repo = repo_factory.create_moderator_store_comments_repo(&*conn, user_id);
+
+                // the page is somewhere in the first `from + count` comments of both tables
+                let from = from.max(0);
+                let limit = from + count;
+                let mut comments = moderator_product_repo
+                    .search(terms.clone(), limit)?
+                    .into_iter()
+                    .map(ModeratorComment::from)
+                    .collect::<Vec<_>>();
+                comments.extend(moderator_store_repo.search(terms, limit)?.into_iter().map(ModeratorComment::from));
+                comments.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+                Ok(comments.into_iter().skip(from as usize).take(count as usize).collect())
+            }
+            .map_err(|e: FailureError| e.context("Service ModeratorComments, search_comments endpoint error occurred.").into())
+        })
+    }
 }
 
 #[cfg(test)]
@@ -103,6 +162,7 @@ pub mod tests {
             moderator_id: MOCK_USER_ID,
             base_product_id: BaseProductId(1),
             comments: "new comment".to_string(),
+            moderator_name: Some("Moderator".to_string()),
         }
     }
 
@@ -111,6 +171,7 @@ pub mod tests {
             moderator_id: MOCK_USER_ID,
             store_id: StoreId(1),
             comments: "new comment".to_string(),
+            moderator_name: None,
         }
     }
 
@@ -156,4 +217,28 @@ pub mod tests {
         assert_eq!(result.comments, payload.comments);
     }
 
+    #[test]
+    fn test_get_product_comments() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_product_comments(BaseProductId(1), 0, 5);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 5);
+    }
+
+    #[test]
+    fn test_search_comments() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let terms = ModeratorCommentsSearchTerms {
+            author: Some("Moderator".to_string()),
+            text: Some("spam".to_string()),
+        };
+        let work = service.search_comments(terms, 1, 10);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].comments, "spam");
+    }
 }
@@ -0,0 +1,334 @@
+//! PriceRules Services, presents CRUD operations with price rules of the store and applies them to customer prices
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+
+use stq_types::StoreId;
+
+use config;
+use errors::Error;
+use models::*;
+use repos::{BaseProductsSearchTerms, PriceRulesRepo, RepoResult, ReposFactory};
+use services::products::calculate_customer_price;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait PriceRulesService {
+    /// Returns rules of the store in evaluation order
+    fn list_price_rules(&self, store_id: StoreId) -> ServiceFuture<Vec<PriceRule>>;
+    /// Creates new rule of the store
+    fn create_price_rule(&self, payload: NewPriceRule) -> ServiceFuture<PriceRule>;
+    /// Updates rule of the store
+    fn update_price_rule(&self, store_id: StoreId, price_rule_id: i32, payload: UpdatePriceRule) -> ServiceFuture<PriceRule>;
+    /// Deletes rule of the store
+    fn delete_price_rule(&self, store_id: StoreId, price_rule_id: i32) -> ServiceFuture<PriceRule>;
+    /// Shows customer prices of store products after price rules, nothing is saved
+    fn preview_price_rules(&self, store_id: StoreId, payload: PreviewPriceRules) -> ServiceFuture<Vec<PriceRulePreview>>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > PriceRulesService for Service<T, M, F>
+{
+    /// Returns rules of the store in evaluation order
+    fn list_price_rules(&self, store_id: StoreId) -> ServiceFuture<Vec<PriceRule>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let price_rules_repo = repo_factory.create_price_rules_repo(&*conn, user_id);
+            price_rules_repo
+                .list(store_id)
+                .map_err(|e: FailureError| e.context("Service PriceRules, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Creates new rule of the store
+    fn create_price_rule(&self, payload: NewPriceRule) -> ServiceFuture<PriceRule> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let price_rules_repo = repo_factory.create_price_rules_repo(&*conn, user_id);
+            conn.transaction::<PriceRule, FailureError, _>(move || price_rules_repo.create(payload))
+                .map_err(|e: FailureError| e.context("Service PriceRules, create endpoint error occurred.").into())
+        })
+    }
+
+    /// Updates rule of the store
+    fn update_price_rule(&self, store_id: StoreId, price_rule_id: i32, payload: UpdatePriceRule) -> ServiceFuture<PriceRule> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let price_rules_repo = repo_factory.create_price_rules_repo(&*conn, user_id);
+            find_price_rule(&*price_rules_repo, store_id, price_rule_id)
+                .and_then(|_| price_rules_repo.update(price_rule_id, payload))
+                .map_err(|e: FailureError| e.context("Service PriceRules, update endpoint error occurred.").into())
+        })
+    }
+
+    /// Deletes rule of the store
+    fn delete_price_rule(&self, store_id: StoreId, price_rule_id: i32) -> ServiceFuture<PriceRule> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let price_rules_repo = repo_factory.create_price_rules_repo(&*conn, user_id);
+            find_price_rule(&*price_rules_repo, store_id, price_rule_id)
+                .and_then(|_| price_rules_repo.delete(price_rule_id))
+                .map_err(|e: FailureError| e.context("Service PriceRules, delete endpoint error occurred.").into())
+        })
+    }
+
+    /// Shows customer prices of store products after price rules, nothing is saved.
+    /// Works while the feature is off, so owners can check their rules before prices change
+    fn preview_price_rules(&self, store_id: StoreId, payload: PreviewPriceRules) -> ServiceFuture<Vec<PriceRulePreview>> {
+        let user_id = self.dynamic_context.user_id;
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let max_markdown_percent = self.static_context.config.price_rules.as_ref().map(|c| c.max_markdown_percent);
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let price_rules_repo = repo_factory.create_price_rules_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+
+                stores_repo
+                    .find(store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+
+                let mut rules = price_rules_repo.list(store_id)?;
+                if let Some(rule) = payload.rule {
+                    let rule = NewPriceRule::new(store_id, rule);
+                    rules.push(PriceRule {
+                        id: UNSAVED_PRICE_RULE_ID,
+                        store_id,
+                        name: rule.name,
+                        category_id: rule.category_id,
+                        max_stock: rule.max_stock,
+                        max_views: rule.max_views,
+                        percent: rule.percent,
+                        position: rule.position,
+                        is_active: true,
+                        created_at: SystemTime::now(),
+                        updated_at: SystemTime::now(),
+                    });
+                }
+                let engine = PriceRuleEngine::new(rules, max_markdown_percent);
+                let stocks = payload
+                    .stocks
+                    .into_iter()
+                    .map(|stock| (stock.product_id, stock.quantity))
+                    .collect::<HashMap<_, _>>();
+
+                let base_products = base_products_repo.search(BaseProductsSearchTerms {
+                    is_active: Some(true),
+                    store_id: Some(store_id),
+                    ..Default::default()
+                })?;
+                let base_products = base_products.into_iter().map(|b| (b.id, b)).collect::<HashMap<_, _>>();
+                let products = products_repo.find_with_base_ids(base_products.keys().cloned().collect())?;
+                let latest_currencies = currency_exchange.get_latest()?;
+
+                let mut previews = vec![];
+                for product in products {
+                    if let Some(base_product) = base_products.get(&product.base_product_id) {
+                        let currencies_map = latest_currencies
+                            .as_ref()
+                            .and_then(|all_rates| all_rates.data.get(&base_product.currency).cloned());
+                        let old_price = calculate_customer_price(&product, &currencies_map, currency, fiat_currency);
+                        let subject = PriceRuleSubject {
+                            category_id: base_product.category_id,
+                            views: base_product.views,
+                            stock: stocks.get(&product.id).cloned().or(product.quantity),
+                        };
+                        let (new_price, price_rule_id) = engine.adjust(store_id, &subject, old_price.clone());
+                        previews.push(PriceRulePreview {
+                            base_product_id: product.base_product_id,
+                            product_id: product.id,
+                            price_rule_id,
+                            old_price,
+                            new_price,
+                        });
+                    }
+                }
+
+                Ok(previews)
+            }
+            .map_err(|e: FailureError| e.context("Service PriceRules, preview endpoint error occurred.").into())
+        })
+    }
+}
+
+fn find_price_rule(price_rules_repo: &PriceRulesRepo, store_id: StoreId, price_rule_id: i32) -> RepoResult<PriceRule> {
+    price_rules_repo.find(store_id, price_rule_id)?.ok_or_else(|| {
+        format_err!("Price rule {} of store {} not found", price_rule_id, store_id)
+            .context(Error::NotFound)
+            .into()
+    })
+}
+
+/// Loads active rules of the stores, no rules are loaded while the feature is off
+pub fn load_price_rules<T, F>(
+    conn: &T,
+    repo_factory: &F,
+    config: &Option<config::PriceRules>,
+    store_ids: Vec<StoreId>,
+) -> RepoResult<PriceRuleEngine>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    match *config {
+        Some(ref config) if !store_ids.is_empty() => {
+            // rules are private to owners, but they change prices for every customer
+            let price_rules_repo = repo_factory.create_price_rules_repo_with_sys_acl(conn);
+            let rules = price_rules_repo.list_active(store_ids)?;
+            Ok(PriceRuleEngine::new(rules, Some(config.max_markdown_percent)))
+        }
+        _ => Ok(PriceRuleEngine::default()),
+    }
+}
+
+/// Changes customer prices of the variants by price rules of their stores
+pub fn apply_price_rules<T, F>(
+    conn: &T,
+    repo_factory: &F,
+    config: &Option<config::PriceRules>,
+    base_products: &mut [BaseProductWithVariants],
+) -> RepoResult<()>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    let store_ids = base_products.iter().map(|b| b.base_product.store_id).collect();
+    let engine = load_price_rules(conn, repo_factory, config, store_ids)?;
+    for base_product in base_products {
+        let store_id = base_product.base_product.store_id;
+        for variant in &mut base_product.variants {
+            let subject = price_rule_subject(&base_product.base_product, variant.product.quantity);
+            let (price, _) = engine.adjust(store_id, &subject, variant.customer_price.clone());
+            variant.customer_price = price;
+        }
+        // range covers variants that are not loaded, their stocks are unknown
+        if let Some(ref mut range) = base_product.customer_price_range {
+            let subject = price_rule_subject(&base_product.base_product, None);
+            let (min_price, max_price) = engine.adjust_range(store_id, &subject, range.min_price.clone(), range.max_price.clone());
+            range.min_price = min_price;
+            range.max_price = max_price;
+        }
+    }
+    Ok(())
+}
+
+/// Changes customer prices of the variants by price rules of their stores
+pub fn apply_price_rules_to_details<T, F>(
+    conn: &T,
+    repo_factory: &F,
+    config: &Option<config::PriceRules>,
+    base_products: &mut [BaseProductDetails],
+) -> RepoResult<()>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    let store_ids = base_products.iter().map(|b| b.base_product.store_id).collect();
+    let engine = load_price_rules(conn, repo_factory, config, store_ids)?;
+    for base_product in base_products {
+        for variant in &mut base_product.variants {
+            let subject = price_rule_subject(&base_product.base_product, variant.product.product.quantity);
+            let (price, _) = engine.adjust(base_product.base_product.store_id, &subject, variant.product.customer_price.clone());
+            variant.product.customer_price = price;
+        }
+    }
+    Ok(())
+}
+
+/// Changes price of the product by price rules of its store, only prices of published products are changed
+pub fn apply_product_price_rules<T, F>(
+    conn: &T,
+    repo_factory: &F,
+    config: &Option<config::PriceRules>,
+    product: &RawProduct,
+    price: CustomerPrice,
+) -> RepoResult<CustomerPrice>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    if config.is_none() {
+        return Ok(price);
+    }
+
+    let base_products_repo = repo_factory.create_base_product_repo(conn, None);
+    match base_products_repo.find(product.base_product_id, Visibility::Published)? {
+        Some(base_product) => {
+            let engine = load_price_rules(conn, repo_factory, config, vec![base_product.store_id])?;
+            let (price, _) = engine.adjust(base_product.store_id, &price_rule_subject(&base_product, product.quantity), price);
+            Ok(price)
+        }
+        None => Ok(price),
+    }
+}
+
+/// Subject of the variant with `stock` units in stock, the rest of the facts are taken from its base product
+fn price_rule_subject(base_product: &BaseProduct, stock: Option<i32>) -> PriceRuleSubject {
+    PriceRuleSubject {
+        category_id: base_product.category_id,
+        views: base_product.views,
+        stock,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_create_price_rule() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = NewPriceRule {
+            store_id: MOCK_STORE_ID,
+            name: "Slow movers".to_string(),
+            category_id: None,
+            max_stock: None,
+            max_views: Some(10),
+            percent: -20,
+            position: 0,
+        };
+        let work = service.create_price_rule(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.percent, -20);
+    }
+
+    #[test]
+    fn test_preview_price_rules() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = PreviewPriceRules { rule: None, stocks: vec![] };
+        let work = service.preview_price_rules(MOCK_STORE_ID, payload);
+        let result = core.run(work);
+        assert!(result.is_ok());
+    }
+}
@@ -1,24 +1,33 @@
 //! Products Services, presents CRUD operations with product
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
 
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
 use failure::Error as FailureError;
+use futures::Future;
 use r2d2::ManageConnection;
+use validator::{Validate, ValidationError, ValidationErrors};
 
 use stq_static_resources::currency_type::CurrencyType;
 use stq_static_resources::Currency;
-use stq_types::{AttributeId, AttributeValueCode, BaseProductId, ExchangeRate, ProductId, ProductPrice, ProductSellerPrice, StoreId};
+use stq_types::{
+    AttributeId, AttributeValueCode, BaseProductId, ExchangeRate, ProductId, ProductPrice, ProductSellerPrice, StoreId, UserId,
+};
 
 use super::types::ServiceFuture;
 use errors::Error;
 use models::*;
+use notifiers::{create_notifier, send_events};
 use repos::{
     AttributeValuesRepo, AttributesRepo, BaseProductsSearchTerms, CurrencyExchangeRepo, CustomAttributesRepo, ProductAttrsRepo,
     ProductFilters, ProductsRepo, RepoResult, ReposFactory, StoresRepo,
 };
+use services::base_products::record_base_product_changes;
 use services::check_can_update_by_status;
+use services::price_rules::apply_product_price_rules;
 use services::Service;
 
 pub trait ProductsService {
@@ -26,14 +35,26 @@ pub trait ProductsService {
     fn get_product(&self, product_id: ProductId) -> ServiceFuture<Option<Product>>;
     /// Returns products by IDs
     fn get_products(&self, product_ids: Vec<ProductId>) -> ServiceFuture<Vec<Product>>;
+    /// Returns active product of the store by vendor code
+    fn get_product_by_vendor_code(&self, store_id: StoreId, vendor_code: String) -> ServiceFuture<Option<Product>>;
+    /// Returns published products by GTIN, EAN, UPC or ISBN
+    fn get_products_by_gtin(&self, gtin: String) -> ServiceFuture<Vec<Product>>;
+    /// Returns products of the store running out of stock. For store owner
+    fn get_low_stock_products(&self, store_id: StoreId, threshold: Option<i32>) -> ServiceFuture<Vec<LowStockProduct>>;
     /// Return product by ID
     fn get_product_without_filters(&self, product_id: ProductId) -> ServiceFuture<Option<Product>>;
     /// Returns product seller price by ID
     fn get_product_seller_price(&self, product_id: ProductId) -> ServiceFuture<Option<ProductSellerPrice>>;
+    /// Returns seller prices of cart lines converted to the currencies of the request headers
+    fn convert_prices(&self, payload: Vec<ConvertPrice>) -> ServiceFuture<Vec<ConvertedPrice>>;
     /// Returns store_id by ID
     fn get_product_store_id(&self, product_id: ProductId, visibility: Option<Visibility>) -> ServiceFuture<Option<StoreId>>;
     /// Deactivates specific product
     fn deactivate_product(&self, product_id: ProductId) -> ServiceFuture<Product>;
+    /// Takes units of the product from stock, fails with `NOT_ENOUGH_STOCK` when there are less of them. For orders saga
+    fn decrement_product_stock(&self, product_id: ProductId, payload: DecrementStock) -> ServiceFuture<Product>;
+    /// Sets order of all active variants of the base product and its default variant
+    fn reorder_products(&self, base_product_id: BaseProductId, payload: ReorderProducts) -> ServiceFuture<Vec<Product>>;
     /// Creates base product
     fn create_product(&self, payload: NewProductWithAttributes) -> ServiceFuture<Product>;
     /// Lists product variants limited by `from` and `count` parameters
@@ -46,8 +67,18 @@ pub trait ProductsService {
     fn find_products_with_store_id(&self, store_id: StoreId) -> ServiceFuture<Vec<Product>>;
     /// Get by base product id
     fn find_products_attributes(&self, product_id: ProductId) -> ServiceFuture<Vec<AttrValue>>;
+    /// Returns product attributes split into groups of the product page
+    fn find_products_grouped_attributes(&self, product_id: ProductId) -> ServiceFuture<Vec<GroupedAttrValues>>;
     /// Check that you can update product
     fn validate_update_product(&self, product_id: ProductId) -> ServiceFuture<bool>;
+    /// Changes prices of all active variants of the base product, either to the price or by percents
+    fn update_prices_by_base_product(&self, base_product_id: BaseProductId, payload: UpdateProductPrices) -> ServiceFuture<Vec<Product>>;
+    /// Adds photos to the end of the product gallery, fails with `TOO_MANY_PHOTOS` over `MAX_PRODUCT_PHOTOS`
+    fn add_product_photos(&self, product_id: ProductId, payload: AddProductPhotos) -> ServiceFuture<Vec<ProductPhoto>>;
+    /// Removes photos from the product gallery
+    fn remove_product_photos(&self, product_id: ProductId, payload: RemoveProductPhotos) -> ServiceFuture<Vec<ProductPhoto>>;
+    /// Changes order of the product gallery
+    fn reorder_product_photos(&self, product_id: ProductId, payload: ReorderProductPhotos) -> ServiceFuture<Vec<ProductPhoto>>;
 }
 
 impl<
@@ -62,14 +93,17 @@ impl<
         let repo_factory = self.static_context.repo_factory.clone();
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
 
-        self.spawn_on_pool(move |conn| {
+        self.spawn_on_replica(move |conn| {
             {
-                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let products_repo = repo_factory.create_product_read_repo(&*conn, user_id);
                 let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
                 let raw_product = products_repo.find(product_id)?;
                 if let Some(raw_product) = raw_product {
                     let customer_price = calculate_product_customer_price(&*currency_exchange, &raw_product, currency, fiat_currency)?;
+                    let customer_price =
+                        apply_product_price_rules(&*conn, &repo_factory, &price_rules_config, &raw_product, customer_price)?;
                     let result_product = Product::new(raw_product, customer_price);
 
                     Ok(Some(result_product))
@@ -81,12 +115,101 @@ impl<
         })
     }
 
+    /// Returns active product of the store by vendor code
+    fn get_product_by_vendor_code(&self, store_id: StoreId, vendor_code: String) -> ServiceFuture<Option<Product>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+                let raw_product = products_repo.find_by_vendor_code(store_id, &vendor_code)?;
+                if let Some(raw_product) = raw_product {
+                    let customer_price = calculate_product_customer_price(&*currency_exchange, &raw_product, currency, fiat_currency)?;
+                    let customer_price =
+                        apply_product_price_rules(&*conn, &repo_factory, &price_rules_config, &raw_product, customer_price)?;
+
+                    Ok(Some(Product::new(raw_product, customer_price)))
+                } else {
+                    Ok(None)
+                }
+            }
+            .map_err(|e: FailureError| e.context("Service Product, get_product_by_vendor_code endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns published products by GTIN, EAN, UPC or ISBN
+    fn get_products_by_gtin(&self, gtin: String) -> ServiceFuture<Vec<Product>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+                products_repo
+                    .find_by_gtin(&gtin)?
+                    .into_iter()
+                    .map(|raw_product| -> Result<Product, FailureError> {
+                        let customer_price = calculate_product_customer_price(&*currency_exchange, &raw_product, currency, fiat_currency)?;
+                        let customer_price =
+                            apply_product_price_rules(&*conn, &repo_factory, &price_rules_config, &raw_product, customer_price)?;
+                        Ok(Product::new(raw_product, customer_price))
+                    })
+                    .collect::<Result<Vec<Product>, FailureError>>()
+            }
+            .map_err(|e: FailureError| e.context("Service Product, get_products_by_gtin endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns products of the store running out of stock. For store owner
+    fn get_low_stock_products(&self, store_id: StoreId, threshold: Option<i32>) -> ServiceFuture<Vec<LowStockProduct>> {
+        let user_id = self.dynamic_context.user_id;
+        let is_super_admin = self.dynamic_context.is_super_admin();
+        let repo_factory = self.static_context.repo_factory.clone();
+        let threshold = threshold.unwrap_or(DEFAULT_LOW_STOCK_THRESHOLD);
+
+        self.spawn_on_pool(move |conn| {
+            {
+                if threshold < 1 {
+                    return Err(format_err!("Low stock threshold {} is less than 1", threshold)
+                        .context(Error::Validate(
+                            validation_errors!({"threshold": ["threshold" => "Threshold must be at least 1"]}),
+                        ))
+                        .into());
+                }
+
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let store = stores_repo
+                    .find(store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+                if !is_super_admin && Some(store.user_id) != user_id {
+                    return Err(format_err!("Stock of store {} is shown to its owner only", store_id)
+                        .context(Error::Forbidden)
+                        .into());
+                }
+
+                products_repo.find_low_stock(store_id, threshold)
+            }
+            .map_err(|e: FailureError| e.context("Service Product, get_low_stock_products endpoint error occurred.").into())
+        })
+    }
+
     /// Returns products by IDs
     fn get_products(&self, product_ids: Vec<ProductId>) -> ServiceFuture<Vec<Product>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
         let currency = self.dynamic_context.currency;
         let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
 
         self.spawn_on_pool(move |conn| {
             {
@@ -97,6 +220,9 @@ impl<
                     .into_iter()
                     .map(|raw_product| {
                         calculate_product_customer_price(&*currency_exchange, &raw_product, currency, fiat_currency)
+                            .and_then(|customer_price| {
+                                apply_product_price_rules(&*conn, &repo_factory, &price_rules_config, &raw_product, customer_price)
+                            })
                             .map(|customer_price| Product::new(raw_product, customer_price))
                     })
                     .collect();
@@ -137,15 +263,22 @@ impl<
     fn get_product_seller_price(&self, product_id: ProductId) -> ServiceFuture<Option<ProductSellerPrice>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
+        let price_rules_config = self.static_context.config.price_rules.clone();
 
         self.spawn_on_pool(move |conn| {
             {
                 let products_repo = repo_factory.create_product_repo(&*conn, user_id);
                 let product = products_repo.find(product_id)?;
                 if let Some(product) = product {
-                    Ok(Some(ProductSellerPrice {
+                    // orders are charged by the seller price, so it follows price rules as well
+                    let seller_price = CustomerPrice {
                         price: product.price,
                         currency: product.currency,
+                    };
+                    let seller_price = apply_product_price_rules(&*conn, &repo_factory, &price_rules_config, &product, seller_price)?;
+                    Ok(Some(ProductSellerPrice {
+                        price: seller_price.price,
+                        currency: seller_price.currency,
                         discount: product.discount,
                     }))
                 } else {
@@ -156,6 +289,74 @@ impl<
         })
     }
 
+    /// Returns seller prices of cart lines converted to the currencies of the request headers,
+    /// exchange rates are read once for the whole cart
+    fn convert_prices(&self, payload: Vec<ConvertPrice>) -> ServiceFuture<Vec<ConvertedPrice>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let crypto_currency = self.dynamic_context.currency;
+        let fiat_currency = self.dynamic_context.fiat_currency;
+        let price_rules_config = self.static_context.config.price_rules.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                for item in &payload {
+                    if let Err(e) = item.validate() {
+                        return Err(format_err!("Validation failed, target: ConvertPrice")
+                            .context(Error::Validate(e))
+                            .into());
+                    }
+                }
+
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let currency_exchange = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+                let product_ids = payload.iter().map(|item| item.product_id).collect();
+                let raw_products = products_repo.find_many(product_ids)?;
+                let rates = currency_exchange.get_latest()?.map(|currency_exchange| currency_exchange.data);
+
+                payload
+                    .into_iter()
+                    .map(|item| -> Result<ConvertedPrice, FailureError> {
+                        let raw_product = raw_products
+                            .iter()
+                            .find(|raw_product| raw_product.id.0 == item.product_id.0)
+                            .ok_or_else(|| format_err!("Product with id {} not found.", item.product_id).context(Error::NotFound))?;
+
+                        // orders are charged by the seller price, so it follows price rules before the conversion
+                        let seller_price = CustomerPrice {
+                            price: raw_product.price,
+                            currency: raw_product.currency,
+                        };
+                        let seller_price =
+                            apply_product_price_rules(&*conn, &repo_factory, &price_rules_config, raw_product, seller_price)?;
+
+                        let currency = match seller_price.currency.currency_type() {
+                            CurrencyType::Crypto => crypto_currency,
+                            CurrencyType::Fiat => fiat_currency,
+                        };
+                        let rate = rates
+                            .as_ref()
+                            .and_then(|rates| rates.get(&seller_price.currency))
+                            .and_then(|currency_rates| currency_rates.get(&currency))
+                            .map(|rate| rate.0)
+                            .unwrap_or(1.0);
+
+                        Ok(ConvertedPrice {
+                            product_id: item.product_id,
+                            quantity: item.quantity,
+                            seller_price: seller_price.price,
+                            seller_currency: seller_price.currency,
+                            price: ProductPrice(seller_price.price.0 / rate),
+                            currency,
+                            rate: ExchangeRate(rate),
+                        })
+                    })
+                    .collect()
+            }
+            .map_err(|e: FailureError| e.context("Service Product, convert_prices endpoint error occurred.").into())
+        })
+    }
+
     /// Returns store_id by ID
     fn get_product_store_id(&self, product_id: ProductId, visibility: Option<Visibility>) -> ServiceFuture<Option<StoreId>> {
         let user_id = self.dynamic_context.user_id;
@@ -201,10 +402,107 @@ impl<
 
                 Ok(result_product.into())
             })
+            .and_then(|product: Product| {
+                invalidate_catalog_health(&*conn, &repo_factory, user_id, product.product.base_product_id)?;
+                Ok(product)
+            })
             .map_err(|e| e.context("Service Product, deactivate endpoint error occurred.").into())
         })
     }
 
+    /// Takes units of the product from stock, fails with `NOT_ENOUGH_STOCK` when there are less of them. For orders saga
+    fn decrement_product_stock(&self, product_id: ProductId, payload: DecrementStock) -> ServiceFuture<Product> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        info!("Decrement stock of product {} by {}", product_id, payload.quantity);
+
+        self.spawn_on_pool(move |conn| {
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let stock_reservations_repo = repo_factory.create_stock_reservations_repo(&*conn, user_id);
+            conn.transaction::<Product, FailureError, _>(|| {
+                let not_enough_stock = || -> FailureError {
+                    format_err!("Product {} has less than {} units in stock", product_id, payload.quantity)
+                        .context(Error::Validate(validation_errors!({
+                            "quantity": [NOT_ENOUGH_STOCK => "Not enough units in stock"]
+                        })))
+                        .into()
+                };
+
+                // units held by other carts are not for sale, the lock keeps reservations from taking them meanwhile
+                let product = products_repo.find_for_update(product_id)?.ok_or_else(&not_enough_stock)?;
+                let reserved_by_other_carts = stock_reservations_repo.reserved_quantity(product_id, payload.cart_id, SystemTime::now())?;
+                if product
+                    .quantity
+                    .map(|quantity| quantity - reserved_by_other_carts < payload.quantity)
+                    .unwrap_or(false)
+                {
+                    return Err(not_enough_stock());
+                }
+
+                let product = products_repo
+                    .decrement_quantity(product_id, payload.quantity)?
+                    .ok_or_else(&not_enough_stock)?;
+                if let Some(cart_id) = payload.cart_id {
+                    stock_reservations_repo.release_cart(cart_id, product_id)?;
+                }
+                Ok(Product::from(product))
+            })
+            .and_then(|product| {
+                invalidate_catalog_health(&*conn, &repo_factory, user_id, product.product.base_product_id)?;
+                Ok(product)
+            })
+            .map_err(|e: FailureError| e.context("Service Product, decrement_product_stock endpoint error occurred.").into())
+        })
+    }
+
+    /// Sets order of all active variants of the base product and its default variant
+    fn reorder_products(&self, base_product_id: BaseProductId, payload: ReorderProducts) -> ServiceFuture<Vec<Product>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            conn.transaction::<Vec<RawProduct>, FailureError, _>(move || {
+                let mut current_ids = products_repo
+                    .find_with_base_id(base_product_id)?
+                    .into_iter()
+                    .map(|product| product.id.0)
+                    .collect::<Vec<_>>();
+                let mut requested_ids = payload.product_ids.iter().map(|product_id| product_id.0).collect::<Vec<_>>();
+                current_ids.sort();
+                requested_ids.sort();
+                if current_ids != requested_ids {
+                    return Err(format_err!(
+                        "Order {:?} does not list every active variant of base product {} once",
+                        payload.product_ids,
+                        base_product_id
+                    )
+                    .context(Error::Validate(validation_errors!({
+                        "product_ids": ["product_ids" => "Every active variant of the base product must be listed once"]
+                    })))
+                    .into());
+                }
+                if let Some(default_product_id) = payload.default_product_id {
+                    if !payload.product_ids.contains(&default_product_id) {
+                        return Err(format_err!(
+                            "Default variant {} does not belong to base product {}",
+                            default_product_id,
+                            base_product_id
+                        )
+                        .context(Error::Validate(validation_errors!({
+                            "default_product_id": ["default_product_id" => "Default variant must be one of the listed variants"]
+                        })))
+                        .into());
+                    }
+                }
+
+                products_repo.set_order(base_product_id, payload.product_ids, payload.default_product_id)
+            })
+            .map(|products| products.into_iter().map(Product::from).collect())
+            .map_err(|e: FailureError| e.context("Service Product, reorder_products endpoint error occurred.").into())
+        })
+    }
+
     /// Lists users limited by `from` and `count` parameters
     fn list_products(&self, from: i32, count: i32) -> ServiceFuture<Vec<Product>> {
         let user_id = self.dynamic_context.user_id;
@@ -261,6 +559,11 @@ impl<
                 product.base_product_id = Some(base_product_id);
 
                 check_vendor_code(&*stores_repo, base_product.store_id, &product.vendor_code)?;
+                if let Some(gtin) = &product.gtin {
+                    check_gtin(&*stores_repo, base_product.store_id, gtin)?;
+                }
+
+                validate_variant_attributes(&*products_repo, &*custom_attributes_repo, base_product.id, &attributes)?;
 
                 let result_product: Product = products_repo.create((product, base_product.currency).into())?.into();
 
@@ -277,6 +580,10 @@ impl<
 
                 Ok(result_product)
             })
+            .and_then(|product: Product| {
+                invalidate_catalog_health(&*conn, &repo_factory, user_id, product.product.base_product_id)?;
+                Ok(product)
+            })
             .map_err(|e| e.context("Service Product, create endpoint error occurred.").into())
         })
     }
@@ -284,9 +591,12 @@ impl<
     /// Updates specific product
     fn update_product(&self, product_id: ProductId, payload: UpdateProductWithAttributes) -> ServiceFuture<Product> {
         let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
         let repo_factory = self.static_context.repo_factory.clone();
+        let big_discount_threshold = self.static_context.config.social_feed.as_ref().map(|c| c.big_discount_threshold);
+        let notifier = create_notifier(&self.static_context.config, self.static_context.client_handle.clone());
 
-        self.spawn_on_pool(move |conn| {
+        let fut = self.spawn_on_pool(move |conn| {
             let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
             let products_repo = repo_factory.create_product_repo(&*conn, user_id);
             let prod_attr_repo = repo_factory.create_product_attrs_repo(&*conn, user_id);
@@ -294,26 +604,61 @@ impl<
             let attribute_values_repo = repo_factory.create_attribute_values_repo(&*conn, user_id);
             let custom_attributes_repo = repo_factory.create_custom_attributes_repo(&*conn, user_id);
             let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let history_repo = repo_factory.create_base_product_history_repo(&*conn, user_id);
 
-            conn.transaction::<Product, FailureError, _>(move || {
+            conn.transaction::<(Product, Vec<FeedEvent>), FailureError, _>(move || {
+                let mut events = vec![];
                 let original_product = products_repo
                     .find(product_id)?
                     .ok_or(format_err!("Not found such product id: {}", product_id).context(Error::NotFound))?;
 
                 let product = if let Some(product) = payload.product {
-                    if let Some(vendor_code) = &product.vendor_code {
+                    product.validate_discount_window(&original_product).map_err(|e| {
+                        format_err!("Discount window of product {} is invalid", product_id).context(Error::Validate(e))
+                    })?;
+
+                    if product.vendor_code.is_some() || product.gtin.is_some() {
                         let BaseProduct { store_id, .. } = base_products_repo
                             .find(original_product.base_product_id, Visibility::Active)?
                             .ok_or(
                             format_err!("Base product with id {} not found.", original_product.base_product_id).context(Error::NotFound),
                         )?;
 
-                        if *original_product.vendor_code.as_str() != *vendor_code {
-                            check_vendor_code(&*stores_repo, store_id, &vendor_code)?;
+                        if let Some(vendor_code) = &product.vendor_code {
+                            if *original_product.vendor_code.as_str() != *vendor_code {
+                                check_vendor_code(&*stores_repo, store_id, &vendor_code)?;
+                            }
+                        }
+                        if let Some(gtin) = &product.gtin {
+                            if original_product.gtin.as_ref() != Some(gtin) {
+                                check_gtin(&*stores_repo, store_id, gtin)?;
+                            }
                         }
                     };
 
-                    products_repo.update(product_id, product)?
+                    let updated_product = products_repo.update(product_id, product)?.without_inactive_discount(SystemTime::now());
+                    if original_product.price != updated_product.price {
+                        let change = BaseProductChange::PriceChanged {
+                            product_id,
+                            old_price: original_product.price,
+                            new_price: updated_product.price,
+                        };
+                        record_base_product_changes(&*history_repo, user_id, impersonator, updated_product.base_product_id, vec![change])?;
+                    }
+                    if let Some(threshold) = big_discount_threshold {
+                        if is_big_discount_added(original_product.discount, updated_product.discount, threshold) {
+                            let base_product = base_products_repo.find(updated_product.base_product_id, Visibility::Active)?;
+                            if let Some(base_product) = base_product {
+                                events.push(FeedEvent::BigDiscountAdded {
+                                    store_id: base_product.store_id,
+                                    base_product_id: base_product.id,
+                                    product_id: updated_product.id,
+                                    discount: updated_product.discount.unwrap_or_default(),
+                                });
+                            }
+                        }
+                    }
+                    updated_product
                 } else {
                     original_product
                 };
@@ -333,10 +678,16 @@ impl<
                     )?;
                 }
 
-                Ok(result_product)
+                Ok((result_product, events))
+            })
+            .and_then(|(product, events): (Product, Vec<FeedEvent>)| {
+                invalidate_catalog_health(&*conn, &repo_factory, user_id, product.product.base_product_id)?;
+                Ok((product, events))
             })
             .map_err(|e| e.context("Service Product, update endpoint error occurred.").into())
-        })
+        });
+
+        Box::new(fut.and_then(move |(product, events)| send_events(notifier, events).map(move |_| product)))
     }
 
     /// Get by base product id
@@ -432,6 +783,35 @@ impl<
         })
     }
 
+    /// Returns product attributes split into groups of the product page
+    fn find_products_grouped_attributes(&self, product_id: ProductId) -> ServiceFuture<Vec<GroupedAttrValues>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let prod_attr_repo = repo_factory.create_product_attrs_repo(&*conn, user_id);
+            let attribute_groups_repo = repo_factory.create_attribute_groups_repo(&*conn, user_id);
+            prod_attr_repo
+                .find_all_attributes(product_id)
+                .and_then(|pr_attrs| {
+                    let attr_values = pr_attrs
+                        .into_iter()
+                        .map(|pr_attr| AttrValue {
+                            attr_id: pr_attr.attr_id,
+                            attr_value_id: pr_attr.attr_value_id,
+                            value: pr_attr.value,
+                            meta_field: pr_attr.meta_field,
+                        })
+                        .collect();
+                    let groups = attribute_groups_repo.list()?;
+                    let links = attribute_groups_repo.list_attributes()?;
+
+                    Ok(group_attr_values(groups, &links, attr_values))
+                })
+                .map_err(|e| e.context("Service Product, find_grouped_attributes endpoint error occurred.").into())
+        })
+    }
+
     /// Check that you can update product
     fn validate_update_product(&self, product_id: ProductId) -> ServiceFuture<bool> {
         let user_id = self.dynamic_context.user_id;
@@ -456,6 +836,149 @@ impl<
             Ok(check_can_update_by_status(current_status))
         })
     }
+
+    /// Changes prices of all active variants of the base product, either to the price or by percents
+    fn update_prices_by_base_product(&self, base_product_id: BaseProductId, payload: UpdateProductPrices) -> ServiceFuture<Vec<Product>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                if !payload.is_single_change() {
+                    return Err(format_err!("Prices of base product {} are changed by {:?}", base_product_id, payload)
+                        .context(Error::Validate(validation_errors!({
+                            "price": ["price" => "Either price or percent must be given"]
+                        })))
+                        .into());
+                }
+
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+                let base_product = base_products_repo
+                    .find(base_product_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Base product with id {} not found.", base_product_id).context(Error::NotFound))?;
+
+                let products = products_repo.update_prices_by_base_product(base_product_id, payload)?;
+                catalog_health_repo.invalidate(base_product.store_id);
+                Ok(products.into_iter().map(Product::from).collect())
+            }
+            .map_err(|e: FailureError| e.context("Service Product, update_prices_by_base_product endpoint error occurred.").into())
+        })
+    }
+
+    /// Adds photos to the end of the product gallery, fails with `TOO_MANY_PHOTOS` over `MAX_PRODUCT_PHOTOS`
+    fn add_product_photos(&self, product_id: ProductId, payload: AddProductPhotos) -> ServiceFuture<Vec<ProductPhoto>> {
+        let user_id = self.dynamic_context.user_id;
+        let impersonator = self.dynamic_context.impersonator;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let history_repo = repo_factory.create_base_product_history_repo(&*conn, user_id);
+            conn.transaction::<Vec<ProductPhoto>, FailureError, _>(move || {
+                let mut urls = products_repo
+                    .find_photos(product_id)?
+                    .into_iter()
+                    .map(|photo| photo.url)
+                    .collect::<Vec<_>>();
+                let mut added_urls = vec![];
+                for url in payload.urls {
+                    if !urls.contains(&url) {
+                        urls.push(url.clone());
+                        added_urls.push(url);
+                    }
+                }
+
+                if urls.len() > MAX_PRODUCT_PHOTOS {
+                    return Err(format_err!("Product {} can not have more than {} photos", product_id, MAX_PRODUCT_PHOTOS)
+                        .context(Error::Validate(validation_errors!({
+                            "urls": [TOO_MANY_PHOTOS => "Too many photos of the product"]
+                        })))
+                        .into());
+                }
+
+                let photos = products_repo.set_photos(product_id, urls)?;
+                if !added_urls.is_empty() {
+                    let product = products_repo
+                        .find(product_id)?
+                        .ok_or(format_err!("Not found such product id: {}", product_id).context(Error::NotFound))?;
+                    let change = BaseProductChange::PhotosAdded {
+                        product_id,
+                        urls: added_urls,
+                    };
+                    record_base_product_changes(&*history_repo, user_id, impersonator, product.base_product_id, vec![change])?;
+                }
+                Ok(photos)
+            })
+            .map_err(|e: FailureError| e.context("Service Product, add_product_photos endpoint error occurred.").into())
+        })
+    }
+
+    /// Removes photos from the product gallery
+    fn remove_product_photos(&self, product_id: ProductId, payload: RemoveProductPhotos) -> ServiceFuture<Vec<ProductPhoto>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            conn.transaction::<Vec<ProductPhoto>, FailureError, _>(move || {
+                let urls = products_repo
+                    .find_photos(product_id)?
+                    .into_iter()
+                    .map(|photo| photo.url)
+                    .filter(|url| !payload.urls.contains(url))
+                    .collect::<Vec<_>>();
+
+                products_repo.set_photos(product_id, urls)
+            })
+            .map_err(|e: FailureError| e.context("Service Product, remove_product_photos endpoint error occurred.").into())
+        })
+    }
+
+    /// Changes order of the product gallery, the payload has to list every photo of the product
+    fn reorder_product_photos(&self, product_id: ProductId, payload: ReorderProductPhotos) -> ServiceFuture<Vec<ProductPhoto>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            conn.transaction::<Vec<ProductPhoto>, FailureError, _>(move || {
+                let mut current_urls = products_repo
+                    .find_photos(product_id)?
+                    .into_iter()
+                    .map(|photo| photo.url)
+                    .collect::<Vec<_>>();
+                let mut new_urls = payload.urls.clone();
+                current_urls.sort();
+                new_urls.sort();
+
+                if current_urls != new_urls {
+                    return Err(format_err!("Urls {:?} are not the photos of product {}", payload.urls, product_id)
+                        .context(Error::Validate(validation_errors!({
+                            "urls": ["urls" => "Urls must list every photo of the product once"]
+                        })))
+                        .into());
+                }
+
+                products_repo.set_photos(product_id, payload.urls)
+            })
+            .map_err(|e: FailureError| e.context("Service Product, reorder_product_photos endpoint error occurred.").into())
+        })
+    }
+}
+
+/// Drops cached catalog health report of the store of the base product, called once the change is committed
+fn invalidate_catalog_health<T, F>(conn: &T, repo_factory: &F, user_id: Option<UserId>, base_product_id: BaseProductId) -> RepoResult<()>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    F: ReposFactory<T>,
+{
+    let base_products_repo = repo_factory.create_base_product_repo(conn, user_id);
+    if let Some(base_product) = base_products_repo.find(base_product_id, Visibility::Active)? {
+        repo_factory.create_catalog_health_repo(conn, user_id).invalidate(base_product.store_id);
+    }
+    Ok(())
 }
 
 pub fn calculate_product_customer_price(
@@ -478,13 +1001,24 @@ pub fn calculate_customer_price(
     crypto_currency: Currency,
     fiat_currency: Currency,
 ) -> CustomerPrice {
-    let header_currency = match product.currency.currency_type() {
+    convert_seller_price(product.price, product.currency, product_currency_map, crypto_currency, fiat_currency)
+}
+
+/// Converts price in seller currency to the currency of the same type requested by customer
+pub fn convert_seller_price(
+    price: ProductPrice,
+    seller_currency: Currency,
+    seller_currency_map: &Option<HashMap<Currency, ExchangeRate>>,
+    crypto_currency: Currency,
+    fiat_currency: Currency,
+) -> CustomerPrice {
+    let header_currency = match seller_currency.currency_type() {
         CurrencyType::Crypto => crypto_currency,
         CurrencyType::Fiat => fiat_currency,
     };
 
-    if let Some(currency_map) = product_currency_map {
-        let price = ProductPrice(product.price.0 / currency_map.get(&header_currency).map(|c| c.0).unwrap_or(1.0));
+    if let Some(currency_map) = seller_currency_map {
+        let price = ProductPrice(price.0 / currency_map.get(&header_currency).map(|c| c.0).unwrap_or(1.0));
         CustomerPrice {
             price,
             currency: header_currency,
@@ -492,7 +1026,7 @@ pub fn calculate_customer_price(
     } else {
         // When no currency convert how seller price
         CustomerPrice {
-            price: product.price,
+            price,
             currency: header_currency,
         }
     }
@@ -625,6 +1159,78 @@ fn update_custom_attributes(
     Ok(())
 }
 
+/// Checks that attributes of the new variant exactly match custom attributes of the base product.
+/// The first variant defines the set itself, so nothing is checked while base product has no variants.
+pub fn validate_variant_attributes(
+    products_repo: &ProductsRepo,
+    custom_attributes_repo: &CustomAttributesRepo,
+    base_product_id: BaseProductId,
+    attributes: &[AttrValue],
+) -> Result<(), FailureError> {
+    if products_repo.find_with_base_id(base_product_id)?.is_empty() {
+        return Ok(());
+    }
+
+    let declared_attributes: HashSet<AttributeId> = custom_attributes_repo
+        .find_all_attributes(base_product_id)?
+        .into_iter()
+        .map(|ca| ca.attribute_id)
+        .collect();
+    if declared_attributes.is_empty() {
+        return Ok(());
+    }
+
+    let attribute_errors = variant_attributes_errors(&declared_attributes, attributes);
+    if attribute_errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut errors = ValidationErrors::new();
+    for error in attribute_errors {
+        errors.add("attributes", error);
+    }
+    Err(format_err!("Variant attributes do not match attributes of base product {}", base_product_id)
+        .context(Error::Validate(errors))
+        .into())
+}
+
+/// Reports every unknown, repeated and missing attribute of the variant
+pub fn variant_attributes_errors(declared_attributes: &HashSet<AttributeId>, attributes: &[AttrValue]) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    let mut submitted_attributes = HashSet::new();
+
+    for attr in attributes {
+        if !declared_attributes.contains(&attr.attr_id) {
+            errors.push(attribute_error(attr.attr_id, "extra", "Attribute is not declared for base product"));
+        } else if !submitted_attributes.insert(attr.attr_id) {
+            errors.push(attribute_error(attr.attr_id, "duplicate", "Attribute is set more than once"));
+        }
+    }
+
+    let mut missing_attributes: Vec<AttributeId> = declared_attributes.difference(&submitted_attributes).cloned().collect();
+    missing_attributes.sort_by_key(|attr_id| attr_id.0);
+    for attr_id in missing_attributes {
+        errors.push(attribute_error(attr_id, "missing", "Attribute declared for base product is not set"));
+    }
+
+    errors
+}
+
+fn attribute_error(attr_id: AttributeId, code: &'static str, message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::from(message));
+    error.add_param("attr_id".into(), &attr_id);
+    error
+}
+
+/// Checks that the discount has just crossed the threshold, so that
+/// repeated updates of an already discounted variant are not announced again
+pub fn is_big_discount_added(old_discount: Option<f64>, new_discount: Option<f64>, threshold: f64) -> bool {
+    let old_discount = old_discount.unwrap_or_default();
+    let new_discount = new_discount.unwrap_or_default();
+    old_discount < threshold && new_discount >= threshold
+}
+
 pub fn check_vendor_code(stores_repo: &StoresRepo, store_id: StoreId, vendor_code: &str) -> Result<(), FailureError> {
     let vendor_code_exists = stores_repo
         .vendor_code_exists(store_id, vendor_code)?
@@ -643,6 +1249,21 @@ pub fn check_vendor_code(stores_repo: &StoresRepo, store_id: StoreId, vendor_cod
     }
 }
 
+/// GTIN identifies one item, so it is given to one active variant of the store
+pub fn check_gtin(stores_repo: &StoresRepo, store_id: StoreId, gtin: &str) -> Result<(), FailureError> {
+    let gtin_exists = stores_repo
+        .gtin_exists(store_id, gtin)?
+        .ok_or(format_err!("Store with id {} not found.", store_id).context(Error::NotFound))?;
+
+    if gtin_exists {
+        Err(format_err!("GTIN '{}' already exists for store with id {}.", gtin, store_id)
+            .context(Error::Validate(validation_errors!({"gtin": ["not_unique" => "GTIN is given to another product of the store."]})))
+            .into())
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::sync::Arc;
@@ -676,6 +1297,14 @@ pub mod tests {
             pre_order_days: 0,
             kafka_update_no: 0,
             uuid: Uuid::new_v4(),
+            quantity: Some(10),
+            position: 0,
+            is_default: false,
+            discount_starts_at: None,
+            discount_ends_at: None,
+            gtin: None,
+            price_updated_at: SystemTime::now(),
+            deactivated_with_base_product: false,
         }
     }
 
@@ -703,6 +1332,8 @@ pub mod tests {
             pre_order: Some(false),
             pre_order_days: Some(0),
             uuid: Uuid::new_v4(),
+            quantity: Some(10),
+            gtin: None,
         }
     }
 
@@ -717,6 +1348,10 @@ pub mod tests {
             currency: None,
             pre_order: None,
             pre_order_days: None,
+            quantity: None,
+            discount_starts_at: None,
+            discount_ends_at: None,
+            gtin: None,
         }
     }
 
@@ -737,6 +1372,125 @@ pub mod tests {
         assert_eq!(result.unwrap().product.id, ProductId(1));
     }
 
+    #[test]
+    fn test_convert_prices() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = vec![
+            ConvertPrice {
+                product_id: ProductId(1),
+                quantity: 2,
+            },
+            ConvertPrice {
+                product_id: ProductId(2),
+                quantity: 1,
+            },
+        ];
+        let work = service.convert_prices(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].quantity, 2);
+        assert_eq!(result[1].product_id, ProductId(2));
+
+        let payload = vec![ConvertPrice {
+            product_id: ProductId(1),
+            quantity: 0,
+        }];
+        let work = service.convert_prices(payload);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_find_products_grouped_attributes() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.find_products_grouped_attributes(ProductId(1));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].group.as_ref().map(|group| group.id), Some(1));
+        assert_eq!(result[0].attributes[0].attr_id, AttributeId(1));
+    }
+
+    #[test]
+    fn test_reorder_products() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ReorderProducts {
+            product_ids: vec![MOCK_PRODUCT_ID],
+            default_product_id: Some(MOCK_PRODUCT_ID),
+        };
+        let work = service.reorder_products(MOCK_BASE_PRODUCT_ID, payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].product.is_default);
+
+        let payload = ReorderProducts {
+            product_ids: vec![MOCK_PRODUCT_ID, ProductId(2)],
+            default_product_id: None,
+        };
+        let work = service.reorder_products(MOCK_BASE_PRODUCT_ID, payload);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_low_stock_products() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_low_stock_products(MOCK_STORE_ID, Some(20));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].product_id, MOCK_PRODUCT_ID);
+        let work = service.get_low_stock_products(MOCK_STORE_ID, None);
+        let result = core.run(work).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_get_product_by_vendor_code() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_product_by_vendor_code(MOCK_STORE_ID, "vendor_code".to_string());
+        let result = core.run(work).unwrap();
+        assert_eq!(result.map(|product| product.product.id), Some(MOCK_PRODUCT_ID));
+
+        let work = service.get_product_by_vendor_code(MOCK_STORE_ID, "unknown".to_string());
+        let result = core.run(work).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_get_products_by_gtin() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_products_by_gtin(MOCK_GTIN.to_string());
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].product.gtin, Some(MOCK_GTIN.to_string()));
+
+        let work = service.get_products_by_gtin("036000291452".to_string());
+        let result = core.run(work).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_create_product_with_existing_gtin() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let mut new_product = create_new_product_with_attributes(MOCK_BASE_PRODUCT_ID);
+        new_product.product.gtin = Some(MOCK_GTIN.to_string());
+        let work = service.create_product(new_product);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_list() {
         let mut core = Core::new().unwrap();
@@ -781,4 +1535,122 @@ pub mod tests {
         assert_eq!(result.product.is_active, false);
     }
 
+    #[test]
+    fn test_decrement_product_stock() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.decrement_product_stock(ProductId(1), DecrementStock { quantity: 3, cart_id: None });
+        let result = core.run(work).unwrap();
+        assert_eq!(result.product.quantity, Some(7));
+
+        let work = service.decrement_product_stock(ProductId(1), DecrementStock { quantity: 11, cart_id: None });
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_decrement_product_stock_reserved() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        // 4 of 10 units are held by the mock cart
+        let work = service.decrement_product_stock(MOCK_PRODUCT_ID, DecrementStock { quantity: 7, cart_id: None });
+        assert!(core.run(work).is_err());
+
+        let payload = DecrementStock {
+            quantity: 7,
+            cart_id: Some(mock_cart_id()),
+        };
+        let work = service.decrement_product_stock(MOCK_PRODUCT_ID, payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.product.quantity, Some(3));
+    }
+
+    #[test]
+    fn test_update_prices_by_base_product() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = UpdateProductPrices {
+            price: Some(ProductPrice(12.5)),
+            percent: None,
+        };
+        let work = service.update_prices_by_base_product(MOCK_BASE_PRODUCT_ID, payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result[0].product.price, ProductPrice(12.5));
+
+        let payload = UpdateProductPrices {
+            price: Some(ProductPrice(12.5)),
+            percent: Some(-10.0),
+        };
+        let work = service.update_prices_by_base_product(MOCK_BASE_PRODUCT_ID, payload);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_add_product_photos() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = AddProductPhotos {
+            urls: vec!["photo-2".to_string(), "photo-3".to_string()],
+        };
+        let work = service.add_product_photos(ProductId(1), payload);
+        let result = core.run(work).unwrap();
+        let urls = result.into_iter().map(|photo| photo.url).collect::<Vec<_>>();
+        assert_eq!(urls, vec!["photo-1", "photo-2", "photo-3"]);
+
+        let payload = AddProductPhotos {
+            urls: (3..12).map(|index| format!("photo-{}", index)).collect(),
+        };
+        let work = service.add_product_photos(ProductId(1), payload);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_reorder_product_photos() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ReorderProductPhotos {
+            urls: vec!["photo-2".to_string(), "photo-1".to_string()],
+        };
+        let work = service.reorder_product_photos(ProductId(1), payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result[0].url, "photo-2");
+        assert_eq!(result[1].position, 1);
+
+        let payload = ReorderProductPhotos {
+            urls: vec!["photo-2".to_string()],
+        };
+        let work = service.reorder_product_photos(ProductId(1), payload);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_is_big_discount_added() {
+        assert!(is_big_discount_added(None, Some(0.5), 0.3));
+        assert!(is_big_discount_added(Some(0.1), Some(0.3), 0.3));
+        assert!(!is_big_discount_added(Some(0.4), Some(0.5), 0.3));
+        assert!(!is_big_discount_added(None, Some(0.2), 0.3));
+    }
+
+    #[test]
+    fn test_variant_attributes_errors() {
+        let attr_value = |attr_id| AttrValue {
+            attr_id: AttributeId(attr_id),
+            attr_value_id: None,
+            value: AttributeValueCode("value".to_string()),
+            meta_field: None,
+        };
+        let declared = vec![AttributeId(1), AttributeId(2)].into_iter().collect();
+
+        assert!(variant_attributes_errors(&declared, &[attr_value(1), attr_value(2)]).is_empty());
+
+        let codes: Vec<String> = variant_attributes_errors(&declared, &[attr_value(1), attr_value(1), attr_value(3)])
+            .into_iter()
+            .map(|e| e.code.into_owned())
+            .collect();
+        assert_eq!(codes, vec!["duplicate", "extra", "missing"]);
+    }
 }
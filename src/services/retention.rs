@@ -0,0 +1,112 @@
+//! Retention Services, lets superusers run the retention job on demand.
+//! Deletions are made only when the job itself would make them, see `Retention::deletion_enabled`
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use failure::Fail;
+use r2d2::ManageConnection;
+
+use stq_types::{StoresRole, UserId};
+
+use errors::Error;
+use loaders::retention::{remove_deactivated, RetentionRun};
+use models::{ImpactReport, RetentionReport};
+use repos::{ReposFactory, UserRolesRepo};
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait RetentionService {
+    /// Removes deactivated entities right away. For superusers
+    fn run_retention(&self) -> ServiceFuture<RetentionReport>;
+    /// Returns what removing deactivated entities deletes, nothing is deleted. For superusers
+    fn run_retention_impact(&self) -> ServiceFuture<ImpactReport>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > RetentionService for Service<T, M, F>
+{
+    /// Removes deactivated entities right away. For superusers
+    fn run_retention(&self) -> ServiceFuture<RetentionReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let config = self.static_context.config.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                check_superuser(&*repo_factory.create_user_roles_repo(&*conn, user_id), user_id)?;
+                let retention = config
+                    .retention
+                    .as_ref()
+                    .ok_or_else(|| format_err!("Retention config not found").context(Error::NotFound))?;
+                let run = RetentionRun::new(retention, &config.client)?;
+                if run.dry_run {
+                    return Err(format_err!("Deletion of deactivated entities is disabled")
+                        .context(Error::Validate(validation_errors!({
+                            "dry_run": ["dry_run" => "Retention only runs dry until orders reference check is configured"]
+                        })))
+                        .into());
+                }
+                remove_deactivated(&*conn, &run)
+            }
+            .map_err(|e: FailureError| e.context("Service Retention, run_retention endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns what removing deactivated entities deletes, nothing is deleted. For superusers
+    fn run_retention_impact(&self) -> ServiceFuture<ImpactReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let config = self.static_context.config.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                check_superuser(&*repo_factory.create_user_roles_repo(&*conn, user_id), user_id)?;
+                let retention = config
+                    .retention
+                    .as_ref()
+                    .ok_or_else(|| format_err!("Retention config not found").context(Error::NotFound))?;
+                let run = RetentionRun {
+                    dry_run: true,
+                    ..RetentionRun::new(retention, &config.client)?
+                };
+                remove_deactivated(&*conn, &run).map(|report| report.impact())
+            }
+            .map_err(|e: FailureError| e.context("Service Retention, run_retention_impact endpoint error occurred.").into())
+        })
+    }
+}
+
+/// Retention repo makes no ACL checks, so roles are checked here
+fn check_superuser(user_roles_repo: &UserRolesRepo, user_id: Option<UserId>) -> Result<(), FailureError> {
+    let user_id = user_id.ok_or_else(|| format_err!("Retention requires authorized user").context(Error::Forbidden))?;
+    if user_roles_repo.list_for_user(user_id)?.contains(&StoresRole::Superuser) {
+        Ok(())
+    } else {
+        Err(format_err!("Retention is allowed to superusers only").context(Error::Forbidden).into())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use stq_types::UserId;
+
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_run_retention_impact_not_superuser() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(UserId(2)), handle);
+        let work = service.run_retention_impact();
+        assert!(core.run(work).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! SearchSynonyms Services, presents CRUD operations with synonyms of the product search.
+//! Every change rebuilds synonym rules of products index from the whole table,
+//! if the index update fails the next change brings it up to date.
+//! Result counts of `search.replay_queries` in the old and the rebuilt index are saved as index migration
+//! before the rebuilt index takes over the alias.
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use futures::Future;
+use r2d2::ManageConnection;
+
+use elastic::{SynonymsElastic, SynonymsElasticImpl};
+use errors::Error;
+use models::{IndexMigration, NewIndexMigration, NewSearchSynonym, SearchSynonym};
+use repos::ReposFactory;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait SearchSynonymsService {
+    /// Returns all synonyms
+    fn list_search_synonyms(&self) -> ServiceFuture<Vec<SearchSynonym>>;
+    /// Creates new synonym and updates products index
+    fn create_search_synonym(&self, payload: NewSearchSynonym) -> ServiceFuture<SearchSynonym>;
+    /// Deletes synonym and updates products index
+    fn delete_search_synonym(&self, synonym_id: i32) -> ServiceFuture<SearchSynonym>;
+    /// Returns result counts of replayed queries in the old and the rebuilt products index
+    fn get_index_migration_verification(&self, migration_id: i32) -> ServiceFuture<IndexMigration>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > SearchSynonymsService for Service<T, M, F>
+{
+    /// Returns all synonyms
+    fn list_search_synonyms(&self) -> ServiceFuture<Vec<SearchSynonym>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let search_synonyms_repo = repo_factory.create_search_synonyms_repo(&*conn, user_id);
+            search_synonyms_repo
+                .list()
+                .map_err(|e: FailureError| e.context("Service SearchSynonyms, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Creates new synonym and updates products index
+    fn create_search_synonym(&self, payload: NewSearchSynonym) -> ServiceFuture<SearchSynonym> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let service = self.clone();
+
+        let created = self.spawn_on_pool(move |conn| {
+            let search_synonyms_repo = repo_factory.create_search_synonyms_repo(&*conn, user_id);
+            conn.transaction::<(SearchSynonym, Vec<SearchSynonym>), FailureError, _>(move || {
+                let synonym = search_synonyms_repo.create(payload)?;
+                let synonyms = search_synonyms_repo.list()?;
+                Ok((synonym, synonyms))
+            })
+        });
+
+        Box::new(
+            created
+                .and_then(move |(synonym, synonyms)| update_product_synonyms(service, &synonyms).map(|_| synonym))
+                .map_err(|e: FailureError| e.context("Service SearchSynonyms, create endpoint error occurred.").into()),
+        )
+    }
+
+    /// Deletes synonym and updates products index
+    fn delete_search_synonym(&self, synonym_id: i32) -> ServiceFuture<SearchSynonym> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let service = self.clone();
+
+        let deleted = self.spawn_on_pool(move |conn| {
+            let search_synonyms_repo = repo_factory.create_search_synonyms_repo(&*conn, user_id);
+            conn.transaction::<(SearchSynonym, Vec<SearchSynonym>), FailureError, _>(move || {
+                let synonym = search_synonyms_repo
+                    .delete(synonym_id)?
+                    .ok_or_else(|| format_err!("Search synonym {} not found", synonym_id).context(Error::NotFound))?;
+                let synonyms = search_synonyms_repo.list()?;
+                Ok((synonym, synonyms))
+            })
+        });
+
+        Box::new(
+            deleted
+                .and_then(move |(synonym, synonyms)| update_product_synonyms(service, &synonyms).map(|_| synonym))
+                .map_err(|e: FailureError| e.context("Service SearchSynonyms, delete endpoint error occurred.").into()),
+        )
+    }
+
+    /// Returns result counts of replayed queries in the old and the rebuilt products index
+    fn get_index_migration_verification(&self, migration_id: i32) -> ServiceFuture<IndexMigration> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let index_migrations_repo = repo_factory.create_index_migrations_repo(&*conn, user_id);
+            index_migrations_repo
+                .find(migration_id)
+                .and_then(|migration| {
+                    migration.ok_or_else(|| format_err!("Index migration {} not found", migration_id).context(Error::NotFound).into())
+                })
+                .map_err(|e: FailureError| {
+                    e.context("Service SearchSynonyms, get_index_migration_verification endpoint error occurred.")
+                        .into()
+                })
+        })
+    }
+}
+
+/// Rebuilds products index with the synonyms, the rebuilt index takes over the alias after replays are saved
+fn update_product_synonyms<T, M, F>(service: Service<T, M, F>, synonyms: &[SearchSynonym]) -> ServiceFuture<()>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    M: ManageConnection<Connection = T>,
+    F: ReposFactory<T>,
+{
+    let rules = synonyms.iter().map(SearchSynonym::to_rule).collect();
+    let queries = service.static_context.config.search().replay_queries;
+    let synonyms_el = SynonymsElasticImpl::new(service.static_context.client_handle.clone(), service.static_context.elastic_pool.address());
+
+    let replayed = synonyms_el.build_products_index(rules).and_then({
+        let synonyms_el = synonyms_el.clone();
+        move |(old_index, new_index)| {
+            synonyms_el
+                .replay_queries(old_index.clone(), new_index.clone(), queries)
+                .map(move |replays| NewIndexMigration::new(old_index, new_index, &replays))
+        }
+    });
+
+    Box::new(
+        replayed
+            .and_then({
+                let service = service.clone();
+                move |payload| save_index_migration(&service, payload)
+            })
+            .and_then(move |migration| {
+                synonyms_el
+                    .switch_products_index(migration.old_index, migration.new_index)
+                    .map(move |_| migration.id)
+            })
+            .and_then(move |migration_id| {
+                let user_id = service.dynamic_context.user_id;
+                let repo_factory = service.static_context.repo_factory.clone();
+                service.spawn_on_pool(move |conn| {
+                    let index_migrations_repo = repo_factory.create_index_migrations_repo(&*conn, user_id);
+                    index_migrations_repo.set_switched(migration_id).map(|_| ())
+                })
+            })
+            .map_err(|e| e.context("Synonyms are saved, but products index was not updated").into()),
+    )
+}
+
+fn save_index_migration<T, M, F>(service: &Service<T, M, F>, payload: NewIndexMigration) -> ServiceFuture<IndexMigration>
+where
+    T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+    M: ManageConnection<Connection = T>,
+    F: ReposFactory<T>,
+{
+    let user_id = service.dynamic_context.user_id;
+    let repo_factory = service.static_context.repo_factory.clone();
+
+    service.spawn_on_pool(move |conn| {
+        let index_migrations_repo = repo_factory.create_index_migrations_repo(&*conn, user_id);
+        let migration = index_migrations_repo.create(payload)?;
+        for replay in migration.replays.iter().filter(|replay| replay.is_lost()) {
+            warn!(
+                "Query '{}' finds {} products in {} and none in {}, see index migration {}",
+                replay.query, replay.old_count, migration.old_index, migration.new_index, migration.id
+            );
+        }
+        info!("Replays of products index rebuild are saved as index migration {}", migration.id);
+        Ok(migration)
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_list_search_synonyms() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.list_search_synonyms();
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].to_rule(), "sneakers, trainers");
+    }
+
+    #[test]
+    fn test_get_index_migration_verification() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_index_migration_verification(1);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.replays[0].delta, 2);
+
+        let work = service.get_index_migration_verification(2);
+        assert!(core.run(work).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! StockReservations Services, carts hold units of products for a while and the orders service reconciles them
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+use validator::Validate;
+
+use errors::Error;
+use models::*;
+use repos::ReposFactory;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait StockReservationsService {
+    /// Holds units of the product for the cart till the reservation expires,
+    /// fails with `NOT_ENOUGH_STOCK` when other carts hold the rest of the stock
+    fn reserve_stock(&self, payload: ReserveStock) -> ServiceFuture<StockReservation>;
+    /// Replaces active reservations with the authoritative state of the orders service
+    fn reconcile_stock(&self, payload: ReconcileStock) -> ServiceFuture<StockReconciliation>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > StockReservationsService for Service<T, M, F>
+{
+    /// Holds units of the product for the cart till the reservation expires,
+    /// fails with `NOT_ENOUGH_STOCK` when other carts hold the rest of the stock
+    fn reserve_stock(&self, payload: ReserveStock) -> ServiceFuture<StockReservation> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let ttl = Duration::from_secs(self.static_context.config.stock_reservations().ttl_s);
+        info!("Reserve {} units of product {} for cart {}", payload.quantity, payload.product_id, payload.cart_id);
+
+        self.spawn_on_pool(move |conn| {
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let stock_reservations_repo = repo_factory.create_stock_reservations_repo(&*conn, user_id);
+
+            conn.transaction::<StockReservation, FailureError, _>(move || {
+                let now = SystemTime::now();
+                // the row lock makes concurrent reservations of the product wait until this one is committed
+                let product = products_repo
+                    .find_for_update(payload.product_id)?
+                    .filter(|product| product.is_active)
+                    .ok_or(format_err!("Product with id {} not found.", payload.product_id).context(Error::NotFound))?;
+
+                let reserved_by_other_carts = stock_reservations_repo.reserved_quantity(payload.product_id, Some(payload.cart_id), now)?;
+                let not_enough_stock = product
+                    .quantity
+                    .map(|quantity| quantity - reserved_by_other_carts < payload.quantity)
+                    .unwrap_or(false);
+                if not_enough_stock {
+                    return Err(format_err!(
+                        "Product {} has less than {} units not reserved by other carts",
+                        payload.product_id,
+                        payload.quantity
+                    )
+                    .context(Error::Validate(validation_errors!({
+                        "quantity": [NOT_ENOUGH_STOCK => "Not enough units in stock"]
+                    })))
+                    .into());
+                }
+
+                stock_reservations_repo.reserve(NewStockReservation {
+                    cart_id: payload.cart_id,
+                    product_id: payload.product_id,
+                    quantity: payload.quantity,
+                    expires_at: now + ttl,
+                })
+            })
+            .map_err(|e: FailureError| e.context("Service StockReservations, reserve_stock endpoint error occurred.").into())
+        })
+    }
+
+    /// Replaces active reservations with the authoritative state of the orders service
+    fn reconcile_stock(&self, payload: ReconcileStock) -> ServiceFuture<StockReconciliation> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let ttl = Duration::from_secs(self.static_context.config.stock_reservations().ttl_s);
+        info!("Reconcile stock reservations with {} reservations of orders service", payload.reservations.len());
+
+        self.spawn_on_pool(move |conn| {
+            let stock_reservations_repo = repo_factory.create_stock_reservations_repo(&*conn, user_id);
+
+            let mut keys = HashSet::new();
+            for reservation in &payload.reservations {
+                if let Err(e) = reservation.validate() {
+                    return Err(format_err!("Validation failed, target: ReserveStock")
+                        .context(Error::Validate(e))
+                        .into());
+                }
+                if !keys.insert((reservation.cart_id, reservation.product_id.0)) {
+                    return Err(format_err!(
+                        "Product {} is reserved twice by cart {}",
+                        reservation.product_id,
+                        reservation.cart_id
+                    )
+                    .context(Error::Validate(validation_errors!({
+                        "reservations": ["duplicate" => "Product is reserved twice by the same cart"]
+                    })))
+                    .into());
+                }
+            }
+
+            conn.transaction::<StockReconciliation, FailureError, _>(move || {
+                let now = SystemTime::now();
+                let released_ids = stock_reservations_repo
+                    .list_active(now)?
+                    .into_iter()
+                    .filter(|reservation| !keys.contains(&(reservation.cart_id, reservation.product_id.0)))
+                    .map(|reservation| reservation.id)
+                    .collect::<Vec<_>>();
+                let released = if released_ids.is_empty() {
+                    0
+                } else {
+                    stock_reservations_repo.release(released_ids)?.len()
+                };
+
+                let reserved = payload.reservations.len();
+                for reservation in payload.reservations {
+                    stock_reservations_repo.reserve(NewStockReservation {
+                        cart_id: reservation.cart_id,
+                        product_id: reservation.product_id,
+                        quantity: reservation.quantity,
+                        expires_at: now + ttl,
+                    })?;
+                }
+
+                Ok(StockReconciliation { reserved, released })
+            })
+            .map_err(|e: FailureError| e.context("Service StockReservations, reconcile_stock endpoint error occurred.").into())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+    use uuid::Uuid;
+
+    use stq_types::ProductId;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_reserve_stock() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ReserveStock {
+            cart_id: Uuid::new_v4(),
+            product_id: MOCK_PRODUCT_ID,
+            quantity: 6,
+        };
+        let work = service.reserve_stock(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.quantity, 6);
+
+        let payload = ReserveStock {
+            cart_id: Uuid::new_v4(),
+            product_id: MOCK_PRODUCT_ID,
+            quantity: 7,
+        };
+        let work = service.reserve_stock(payload);
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_stock() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ReconcileStock {
+            reservations: vec![ReserveStock {
+                cart_id: Uuid::new_v4(),
+                product_id: ProductId(2),
+                quantity: 1,
+            }],
+        };
+        let work = service.reconcile_stock(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result, StockReconciliation { reserved: 1, released: 1 });
+
+        let payload = ReconcileStock {
+            reservations: vec![ReserveStock {
+                cart_id: mock_cart_id(),
+                product_id: MOCK_PRODUCT_ID,
+                quantity: 4,
+            }],
+        };
+        let work = service.reconcile_stock(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result, StockReconciliation { reserved: 1, released: 0 });
+    }
+}
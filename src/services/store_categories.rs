@@ -0,0 +1,179 @@
+//! StoreCategories Services, categories the store sells in and requests of the store to sell in more of them
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+
+use stq_types::{CategoryId, StoreId};
+
+use errors::Error;
+use models::*;
+use repos::{CategoriesRepo, RepoResult, ReposFactory};
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait StoreCategoriesService {
+    /// Returns declared and requested categories of the store
+    fn list_store_categories(&self, store_id: StoreId) -> ServiceFuture<Vec<StoreCategory>>;
+    /// Asks moderators to let the store sell in one more category
+    fn request_category_expansion(&self, store_id: StoreId, payload: NewCategoryExpansion) -> ServiceFuture<StoreCategory>;
+    /// Approves or rejects the expansion request. For moderators
+    fn moderate_category_expansion(
+        &self,
+        store_id: StoreId,
+        category_id: CategoryId,
+        payload: ModerateCategoryExpansion,
+    ) -> ServiceFuture<StoreCategory>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > StoreCategoriesService for Service<T, M, F>
+{
+    /// Returns declared and requested categories of the store
+    fn list_store_categories(&self, store_id: StoreId) -> ServiceFuture<Vec<StoreCategory>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, user_id);
+            store_categories_repo
+                .list(store_id)
+                .map_err(|e: FailureError| e.context("Service StoreCategories, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Asks moderators to let the store sell in one more category
+    fn request_category_expansion(&self, store_id: StoreId, payload: NewCategoryExpansion) -> ServiceFuture<StoreCategory> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let categories_repo = repo_factory.create_categories_repo(&*conn, user_id);
+            let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, user_id);
+            conn.transaction::<StoreCategory, FailureError, _>(move || {
+                check_first_level_categories(&*categories_repo, &[payload.category_id])?;
+
+                match store_categories_repo.find(store_id, payload.category_id)? {
+                    Some(ref store_category) if store_category.status.is_allowed() => {
+                        Err(format_err!("Store {} already sells in category {}", store_id, payload.category_id)
+                            .context(Error::Validate(validation_errors!({
+                                "category_id": ["allowed" => "Store already sells in the category"]
+                            })))
+                            .into())
+                    }
+                    Some(ref store_category) if store_category.status == StoreCategoryStatus::Requested => Ok(store_category.clone()),
+                    _ => store_categories_repo.request(store_id, payload.category_id),
+                }
+            })
+            .map_err(|e: FailureError| {
+                e.context("Service StoreCategories, request_category_expansion endpoint error occurred.")
+                    .into()
+            })
+        })
+    }
+
+    /// Approves or rejects the expansion request. For moderators
+    fn moderate_category_expansion(
+        &self,
+        store_id: StoreId,
+        category_id: CategoryId,
+        payload: ModerateCategoryExpansion,
+    ) -> ServiceFuture<StoreCategory> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, user_id);
+            conn.transaction::<StoreCategory, FailureError, _>(move || {
+                let moderator_id =
+                    user_id.ok_or_else(|| format_err!("Expansion requests are moderated by authorized users").context(Error::Forbidden))?;
+                if payload.status != StoreCategoryStatus::Approved && payload.status != StoreCategoryStatus::Rejected {
+                    return Err(format_err!("Expansion request can not be moderated to {:?}", payload.status)
+                        .context(Error::Validate(validation_errors!({
+                            "status": ["status" => "Expansion request is either approved or rejected"]
+                        })))
+                        .into());
+                }
+
+                let store_category = store_categories_repo
+                    .find(store_id, category_id)?
+                    .filter(|store_category| store_category.status == StoreCategoryStatus::Requested)
+                    .ok_or_else(|| {
+                        format_err!("Expansion request of store {} to category {} not found", store_id, category_id).context(Error::NotFound)
+                    })?;
+
+                store_categories_repo.set_status(store_category.id, payload.status, moderator_id)
+            })
+            .map_err(|e: FailureError| {
+                e.context("Service StoreCategories, moderate_category_expansion endpoint error occurred.")
+                    .into()
+            })
+        })
+    }
+}
+
+/// Checks that stores are declaring categories of the first level, they include all the deeper ones
+pub fn check_first_level_categories(categories_repo: &CategoriesRepo, category_ids: &[CategoryId]) -> RepoResult<()> {
+    let category_root = categories_repo.get_all_categories()?;
+    for category_id in category_ids {
+        if !category_root.children.iter().any(|category| category.id == *category_id) {
+            return Err(format_err!("Category {} is not of the first level", category_id)
+                .context(Error::Validate(validation_errors!({
+                    "category_id": ["level" => "Stores sell in categories of the first level"]
+                })))
+                .into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use stq_types::{CategoryId, StoreId};
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_request_category_expansion() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.request_category_expansion(StoreId(2), NewCategoryExpansion { category_id: CategoryId(1) });
+        let result = core.run(work).unwrap();
+        assert_eq!(result.status, StoreCategoryStatus::Requested);
+
+        let work = service.request_category_expansion(MOCK_STORE_ID, NewCategoryExpansion { category_id: CategoryId(1) });
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_moderate_category_expansion() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ModerateCategoryExpansion {
+            status: StoreCategoryStatus::Approved,
+        };
+        let work = service.moderate_category_expansion(StoreId(2), CategoryId(1), payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.status, StoreCategoryStatus::Approved);
+        assert_eq!(result.moderator_id, Some(MOCK_USER_ID));
+
+        let payload = ModerateCategoryExpansion {
+            status: StoreCategoryStatus::Declared,
+        };
+        let work = service.moderate_category_expansion(StoreId(2), CategoryId(1), payload);
+        assert!(core.run(work).is_err());
+    }
+}
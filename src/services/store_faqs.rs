@@ -0,0 +1,140 @@
+//! StoreFaqs Services, presents CRUD operations with questions of the store
+use diesel::connection::AnsiTransactionManager;
+use diesel::pg::Pg;
+use diesel::Connection;
+use failure::Error as FailureError;
+use r2d2::ManageConnection;
+
+use stq_types::StoreId;
+
+use errors::Error;
+use models::{NewStoreFaq, StoreFaq, UpdateStoreFaq, Visibility};
+use repos::ReposFactory;
+use services::types::ServiceFuture;
+use services::Service;
+
+pub trait StoreFaqsService {
+    /// Returns questions of the store ordered by position
+    fn list_store_faqs(&self, store_id: StoreId) -> ServiceFuture<Vec<StoreFaq>>;
+    /// Creates new question of the store
+    fn create_store_faq(&self, payload: NewStoreFaq) -> ServiceFuture<StoreFaq>;
+    /// Updates question of the store
+    fn update_store_faq(&self, store_id: StoreId, faq_id: i32, payload: UpdateStoreFaq) -> ServiceFuture<StoreFaq>;
+    /// Deletes question of the store
+    fn delete_store_faq(&self, store_id: StoreId, faq_id: i32) -> ServiceFuture<StoreFaq>;
+}
+
+impl<
+        T: Connection<Backend = Pg, TransactionManager = AnsiTransactionManager> + 'static,
+        M: ManageConnection<Connection = T>,
+        F: ReposFactory<T>,
+    > StoreFaqsService for Service<T, M, F>
+{
+    /// Returns questions of the store ordered by position
+    fn list_store_faqs(&self, store_id: StoreId) -> ServiceFuture<Vec<StoreFaq>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let store_faqs_repo = repo_factory.create_store_faqs_repo(&*conn, user_id);
+            stores_repo
+                .find(store_id, Visibility::Active)
+                .and_then(|store| store.ok_or(format_err!("Store {} not found", store_id).context(Error::NotFound).into()))
+                .and_then(|_| store_faqs_repo.list(store_id))
+                .map_err(|e: FailureError| e.context("Service StoreFaqs, list endpoint error occurred.").into())
+        })
+    }
+
+    /// Creates new question of the store
+    fn create_store_faq(&self, payload: NewStoreFaq) -> ServiceFuture<StoreFaq> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let store_faqs_repo = repo_factory.create_store_faqs_repo(&*conn, user_id);
+            conn.transaction::<StoreFaq, FailureError, _>(move || store_faqs_repo.create(payload))
+                .map_err(|e: FailureError| e.context("Service StoreFaqs, create endpoint error occurred.").into())
+        })
+    }
+
+    /// Updates question of the store
+    fn update_store_faq(&self, store_id: StoreId, faq_id: i32, payload: UpdateStoreFaq) -> ServiceFuture<StoreFaq> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let store_faqs_repo = repo_factory.create_store_faqs_repo(&*conn, user_id);
+            store_faqs_repo
+                .find(store_id, faq_id)
+                .and_then(|faq| {
+                    faq.ok_or(
+                        format_err!("Question {} of store {} not found", faq_id, store_id)
+                            .context(Error::NotFound)
+                            .into(),
+                    )
+                })
+                .and_then(|_| store_faqs_repo.update(faq_id, payload))
+                .map_err(|e: FailureError| e.context("Service StoreFaqs, update endpoint error occurred.").into())
+        })
+    }
+
+    /// Deletes question of the store
+    fn delete_store_faq(&self, store_id: StoreId, faq_id: i32) -> ServiceFuture<StoreFaq> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let store_faqs_repo = repo_factory.create_store_faqs_repo(&*conn, user_id);
+            store_faqs_repo
+                .find(store_id, faq_id)
+                .and_then(|faq| {
+                    faq.ok_or(
+                        format_err!("Question {} of store {} not found", faq_id, store_id)
+                            .context(Error::NotFound)
+                            .into(),
+                    )
+                })
+                .and_then(|_| store_faqs_repo.delete(faq_id))
+                .map_err(|e: FailureError| e.context("Service StoreFaqs, delete endpoint error occurred.").into())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio_core::reactor::Core;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    #[test]
+    fn test_list_store_faqs() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.list_store_faqs(MOCK_STORE_ID);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].store_id, MOCK_STORE_ID);
+    }
+
+    #[test]
+    fn test_create_store_faq() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = NewStoreFaq {
+            store_id: MOCK_STORE_ID,
+            question: json!([{"lang": "en", "text": "Do you ship abroad?"}]),
+            answer: json!([{"lang": "en", "text": "Yes"}]),
+            position: 1,
+        };
+        let work = service.create_store_faq(payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.position, 1);
+    }
+}
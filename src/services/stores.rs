@@ -1,4 +1,8 @@
 //! Stores Services, presents CRUD operations with stores
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
 use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
@@ -8,17 +12,25 @@ use futures::{future, Future};
 use r2d2::ManageConnection;
 
 use stq_static_resources::ModerationStatus;
-use stq_types::{SagaId, StoreId, StoreSlug, UserId};
+use stq_types::{ExchangeRate, SagaId, StoreId, StoreSlug, StoresRole, UserId};
 
 use super::types::ServiceFuture;
+use config::{Config, ProductQuota};
 use elastic::{StoresElastic, StoresElasticImpl};
 use errors::Error;
 use models::{
-    Category, Direction, ModeratorStoreSearchResults, ModeratorStoreSearchTerms, NewStore, Ordering, PaginationParams, SearchStore,
-    ServiceUpdateBaseProduct, Store, UpdateStore, Visibility,
+    convert_price, CatalogHealthReport, Category, CloneStore, ConfirmStoreVerification, CurrencyChangePreview, Direction,
+    ElasticStoresWithFacets, FeedEvent, ImpactReport, ModeratorStoreSearchResults, ModeratorStoreSearchTerms, NewStore,
+    NewStoreVerificationCode, NewUserRole, Ordering, PaginationParams, PreviewCurrencyChange, SearchStore, SearchStoreWithFacets,
+    SearchStoresNearby, SendStoreVerification, ServiceUpdateBaseProduct, ServiceUpdateStore, SetStoreFranchise, SetStoreOnboarding,
+    SetStoreQuotaPlan, SetStoreVacation, Store, StoreBySlug, StoreClone, StoreContactMessage, StoreContactNotification, StoreOnboarding,
+    StoreProfile, StoreQuota, StoreStatistics, StoreVerificationSent, StoreWithDistance, StoreWithOpeningStatus, StoreWithProductsCount,
+    TransferStoreOwnership, UpdateStore, VerifyStore, Visibility, DEFAULT_STALE_PRICE_DAYS, QUOTA_EXCEEDED,
 };
+use notifiers::{create_notifier, create_store_contact_sender, create_verification_sender, send_events};
 use repos::remove_unused_categories;
 use repos::{BaseProductsRepo, BaseProductsSearchTerms, ReposFactory, StoresRepo};
+use services::copy_base_product;
 use services::Service;
 
 pub trait StoresService {
@@ -26,6 +38,10 @@ pub trait StoresService {
     fn count(&self, visibility: Option<Visibility>) -> ServiceFuture<i64>;
     /// Find stores by name limited by `count` parameters
     fn find_store_by_name(self, search_store: SearchStore, count: i32, offset: i32) -> ServiceFuture<Vec<Store>>;
+    /// Find stores by name with country and category facets
+    fn find_store_by_name_with_facets(self, search_store: SearchStore, count: i32, offset: i32) -> ServiceFuture<SearchStoreWithFacets>;
+    /// Find published stores within radius, closest first
+    fn search_nearby(self, search: SearchStoresNearby, count: i32, offset: i32) -> ServiceFuture<Vec<StoreWithDistance>>;
     /// search filters count
     fn search_store_filters_count(&self, search_store: SearchStore) -> ServiceFuture<i32>;
     /// search filters country
@@ -35,17 +51,21 @@ pub trait StoresService {
     /// Find stores auto complete limited by `count` parameters
     fn store_auto_complete(&self, name: String, count: i32, offset: i32) -> ServiceFuture<Vec<String>>;
     /// Returns store by ID
-    fn get_store(&self, store_id: StoreId, visibility: Option<Visibility>) -> ServiceFuture<Option<Store>>;
-    /// Returns store by slug
-    fn get_store_by_slug(&self, store_slug: StoreSlug, visibility: Option<Visibility>) -> ServiceFuture<Option<Store>>;
+    fn get_store(&self, store_id: StoreId, visibility: Option<Visibility>) -> ServiceFuture<Option<StoreWithOpeningStatus>>;
+    /// Returns store by slug, old slugs of the store lead to its current slug
+    fn get_store_by_slug(&self, store_slug: StoreSlug, visibility: Option<Visibility>) -> ServiceFuture<Option<StoreBySlug>>;
     /// Returns products count
     fn get_store_products_count(&self, store_id: StoreId, visibility: Option<Visibility>) -> ServiceFuture<i32>;
     /// Deactivates specific store
     fn deactivate_store(&self, store_id: StoreId) -> ServiceFuture<Store>;
     /// Deactivates store by saga ID
     fn deactivate_store_by_saga_id(&self, saga_id: SagaId) -> ServiceFuture<Store>;
+    /// Rolls back store created under saga ID
+    fn compensate_store_creation(&self, saga_id: SagaId) -> ServiceFuture<Option<Store>>;
     /// Get store by user id
     fn get_store_by_user(&self, user_id: UserId) -> ServiceFuture<Option<Store>>;
+    /// Returns stores with the ids and counts of their active base products
+    fn find_many_stores(&self, store_ids: Vec<StoreId>) -> ServiceFuture<Vec<StoreWithProductsCount>>;
     /// Deactivates store by user id
     fn delete_store_by_user(&self, user_id: UserId) -> ServiceFuture<Option<Store>>;
     /// Creates new store
@@ -81,6 +101,63 @@ pub trait StoresService {
 
     /// Delete store by id
     fn delete(&self, store_id: StoreId) -> ServiceFuture<()>;
+
+    /// Returns what deleting the store changes, nothing is deleted
+    fn delete_impact(&self, store_id: StoreId) -> ServiceFuture<ImpactReport>;
+
+    /// Shows prices of store products after switching to another currency, nothing is saved
+    fn preview_currency_change(&self, store_id: StoreId, payload: PreviewCurrencyChange) -> ServiceFuture<Vec<CurrencyChangePreview>>;
+
+    /// Returns product quota of the store and its usage
+    fn get_store_quota(&self, store_id: StoreId) -> ServiceFuture<StoreQuota>;
+
+    /// Sets billing plan of the store. For billing service
+    fn set_store_quota_plan(&self, store_id: StoreId, payload: SetStoreQuotaPlan) -> ServiceFuture<StoreQuota>;
+
+    /// Returns listings of the store that need seller's attention
+    fn get_catalog_health(&self, store_id: StoreId, stale_price_days: Option<u64>) -> ServiceFuture<CatalogHealthReport>;
+
+    /// Returns aggregate statistics of the store catalog. For store owner
+    fn get_store_statistics(&self, store_id: StoreId) -> ServiceFuture<StoreStatistics>;
+
+    /// Returns onboarding checklist of the store. For store owner
+    fn get_store_onboarding(&self, store_id: StoreId) -> ServiceFuture<StoreOnboarding>;
+
+    /// Sets payout and shipping facts of the store onboarding. For billing and delivery services
+    fn set_store_onboarding(&self, store_id: StoreId, payload: SetStoreOnboarding) -> ServiceFuture<StoreOnboarding>;
+
+    /// Marks store as franchise. For superusers
+    fn set_store_franchise(&self, store_id: StoreId, payload: SetStoreFranchise) -> ServiceFuture<Store>;
+
+    /// Pauses or resumes sales of the store, products of the vacationing store are not found by search
+    fn set_store_vacation(&self, store_id: StoreId, payload: SetStoreVacation) -> ServiceFuture<Store>;
+
+    /// Sets verification status and badges of the store. For moderators
+    fn verify_store(&self, store_id: StoreId, payload: VerifyStore) -> ServiceFuture<Store>;
+
+    /// Hands the store over to another user, who gets the role of store owners. For superusers
+    fn transfer_store_ownership(&self, store_id: StoreId, payload: TransferStoreOwnership) -> ServiceFuture<Store>;
+
+    /// Copies store into a new draft store of the same owner. For superusers and owners of franchise stores
+    fn clone_store(&self, store_id: StoreId, payload: CloneStore) -> ServiceFuture<StoreClone>;
+
+    /// Sends code confirming store email or phone
+    fn send_store_verification(&self, store_id: StoreId, payload: SendStoreVerification) -> ServiceFuture<StoreVerificationSent>;
+
+    /// Confirms store email or phone with the received code
+    fn confirm_store_verification(&self, store_id: StoreId, payload: ConfirmStoreVerification) -> ServiceFuture<Store>;
+
+    /// Relays message of the user to the store owner through the notifications service
+    fn contact_store(&self, store_id: StoreId, payload: StoreContactMessage) -> ServiceFuture<()>;
+
+    /// Returns public profile of the published store
+    fn get_store_profile(&self, store_slug: StoreSlug) -> ServiceFuture<StoreProfile>;
+
+    /// Returns RSS feed of recently published products of the store, titles are in `lang`
+    fn get_store_rss(&self, store_id: StoreId, lang: Option<String>) -> ServiceFuture<String>;
+
+    /// Returns sitemap of the store and its recently published products
+    fn get_store_sitemap(&self, store_id: StoreId) -> ServiceFuture<String>;
 }
 
 impl<
@@ -97,8 +174,8 @@ impl<
 
         debug!("Getting store count with visibility = {:?}", visibility);
 
-        self.spawn_on_pool(move |conn| {
-            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+        self.spawn_on_replica(move |conn| {
+            let stores_repo = repo_factory.create_stores_read_repo(&*conn, user_id);
             stores_repo
                 .count(visibility)
                 .map_err(|e: FailureError| e.context("Service `stores`, `count` endpoint error occurred.").into())
@@ -107,10 +184,10 @@ impl<
 
     fn store_auto_complete(&self, name: String, count: i32, offset: i32) -> ServiceFuture<Vec<String>> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let stores_names = {
             let stores_el = StoresElasticImpl::new(client_handle, address);
-            stores_el.auto_complete(name, count, offset)
+            stores_el.auto_complete(name, count, offset, self.dynamic_context.language)
         };
 
         Box::new(stores_names.map_err(|e| e.context("Service Stores, auto_complete endpoint error occurred.").into()))
@@ -119,7 +196,7 @@ impl<
     /// Find stores by name
     fn find_store_by_name(self, search_store: SearchStore, count: i32, offset: i32) -> ServiceFuture<Vec<Store>> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
         let stores = {
@@ -149,10 +226,89 @@ impl<
         )
     }
 
+    /// Find stores by name with country and category facets
+    fn find_store_by_name_with_facets(self, search_store: SearchStore, count: i32, offset: i32) -> ServiceFuture<SearchStoreWithFacets> {
+        let client_handle = self.static_context.client_handle.clone();
+        let address = self.static_context.elastic_pool.address();
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let search_result = {
+            let stores_el = StoresElasticImpl::new(client_handle, address);
+            stores_el.find_by_name_with_facets(search_store, count, offset)
+        };
+
+        Box::new(
+            search_result
+                .and_then(move |search_result| {
+                    self.spawn_on_pool(move |conn| {
+                        let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                        let ElasticStoresWithFacets {
+                            stores: el_stores,
+                            total_count,
+                            countries,
+                            categories,
+                        } = search_result;
+                        let stores = el_stores
+                            .into_iter()
+                            .map(|el_store| {
+                                let store = stores_repo.find(el_store.id, Visibility::Published)?;
+                                store.ok_or(
+                                    format_err!("Not found such store id : {}", el_store.id)
+                                        .context(Error::NotFound)
+                                        .into(),
+                                )
+                            })
+                            .collect::<Result<Vec<Store>, FailureError>>()?;
+
+                        Ok(SearchStoreWithFacets {
+                            stores,
+                            total_count,
+                            countries,
+                            categories,
+                        })
+                    })
+                })
+                .map_err(|e| e.context("Service Stores, find_by_name_with_facets endpoint error occurred.").into()),
+        )
+    }
+
+    /// Find published stores within radius, closest first
+    fn search_nearby(self, search: SearchStoresNearby, count: i32, offset: i32) -> ServiceFuture<Vec<StoreWithDistance>> {
+        let client_handle = self.static_context.client_handle.clone();
+        let address = self.static_context.elastic_pool.address();
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let stores = {
+            let stores_el = StoresElasticImpl::new(client_handle, address);
+            stores_el.search_nearby(search, count, offset)
+        };
+
+        Box::new(
+            stores
+                .and_then(move |el_stores| {
+                    self.spawn_on_pool(move |conn| {
+                        let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                        el_stores
+                            .into_iter()
+                            .map(|(el_store, distance_km)| {
+                                let store = stores_repo.find(el_store.id, Visibility::Published)?;
+                                store.map(|store| StoreWithDistance { store, distance_km }).ok_or(
+                                    format_err!("Not found such store id : {}", el_store.id)
+                                        .context(Error::NotFound)
+                                        .into(),
+                                )
+                            })
+                            .collect()
+                    })
+                })
+                .map_err(|e| e.context("Service Stores, search_nearby endpoint error occurred.").into()),
+        )
+    }
+
     /// search filters count
     fn search_store_filters_count(&self, search_store: SearchStore) -> ServiceFuture<i32> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let search_filters = {
             let stores_el = StoresElasticImpl::new(client_handle, address);
             stores_el.search_count(search_store)
@@ -164,7 +320,7 @@ impl<
     /// search filters country
     fn search_store_filters_country(&self, search_store: SearchStore) -> ServiceFuture<Vec<String>> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let search_filters = {
             let stores_el = StoresElasticImpl::new(client_handle, address);
             stores_el.aggregate_countries(search_store)
@@ -176,7 +332,7 @@ impl<
     /// search filters category
     fn search_store_filters_category(self, search_store: SearchStore) -> ServiceFuture<Category> {
         let client_handle = self.static_context.client_handle.clone();
-        let address = self.static_context.config.server.elastic.clone();
+        let address = self.static_context.elastic_pool.address();
         let stores_el = StoresElasticImpl::new(client_handle, address);
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
@@ -197,29 +353,36 @@ impl<
     }
 
     /// Returns store by ID
-    fn get_store(&self, store_id: StoreId, visibility: Option<Visibility>) -> ServiceFuture<Option<Store>> {
+    fn get_store(&self, store_id: StoreId, visibility: Option<Visibility>) -> ServiceFuture<Option<StoreWithOpeningStatus>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
         let visibility = visibility.unwrap_or(Visibility::Published);
 
-        self.spawn_on_pool(move |conn| {
-            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+        self.spawn_on_replica(move |conn| {
+            let stores_repo = repo_factory.create_stores_read_repo(&*conn, user_id);
             stores_repo
                 .find(store_id, visibility)
+                .map(|store| store.map(|store| StoreWithOpeningStatus::new(store, Utc::now())))
                 .map_err(|e| e.context("Service Stores, get endpoint error occurred.").into())
         })
     }
 
-    /// Returns store by slug
-    fn get_store_by_slug(&self, store_slug: StoreSlug, visibility: Option<Visibility>) -> ServiceFuture<Option<Store>> {
+    /// Returns store by slug, old slugs of the store lead to its current slug
+    fn get_store_by_slug(&self, store_slug: StoreSlug, visibility: Option<Visibility>) -> ServiceFuture<Option<StoreBySlug>> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
         let visibility = visibility.unwrap_or(Visibility::Published);
 
-        self.spawn_on_pool(move |conn| {
-            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+        self.spawn_on_replica(move |conn| {
+            let stores_repo = repo_factory.create_stores_read_repo(&*conn, user_id);
             stores_repo
-                .find_by_slug(store_slug, visibility)
+                .find_by_slug(store_slug.clone(), visibility)
+                .and_then(|store| match store {
+                    Some(store) => Ok(Some(StoreBySlug::Found(StoreWithOpeningStatus::new(store, Utc::now())))),
+                    None => stores_repo
+                        .find_by_old_slug(store_slug, visibility)
+                        .map(|store| store.map(|store| StoreBySlug::Moved(store.into()))),
+                })
                 .map_err(|e| e.context("Service Stores, get_store_by_slug endpoint error occurred.").into())
         })
     }
@@ -232,8 +395,8 @@ impl<
 
         debug!("Get product count in store with id = {:?}, visibility = {:?}", store_id, visibility);
 
-        self.spawn_on_pool(move |conn| {
-            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+        self.spawn_on_replica(move |conn| {
+            let base_products_repo = repo_factory.create_base_product_read_repo(&*conn, user_id);
             base_products_repo
                 .count_with_store_id(store_id, visibility)
                 .map_err(|e| e.context("Service Stores, get_products_count endpoint error occurred.").into())
@@ -251,7 +414,8 @@ impl<
                 let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
                 let products_repo = repo_factory.create_product_repo(&*conn, user_id);
                 let wizard_stores_repo = repo_factory.create_wizard_stores_repo(&*conn, user_id);
-                conn.transaction::<Store, FailureError, _>(move || {
+                let store_profile_repo = repo_factory.create_store_profile_repo(&*conn, user_id);
+                let store = conn.transaction::<Store, FailureError, _>(move || {
                     let deactive_store = stores_repo.deactivate(store_id)?;
 
                     let base_products = base_products_repo.deactivate_by_store(store_id)?;
@@ -263,7 +427,9 @@ impl<
                     let _wizard_store = wizard_stores_repo.delete(deactive_store.user_id);
 
                     Ok(deactive_store)
-                })
+                })?;
+                store_profile_repo.invalidate(StoreSlug(store.slug.clone()));
+                Ok(store)
             }
             .map_err(|e: FailureError| e.context("Service Stores, deactivate endpoint error occurred.").into())
         })
@@ -297,6 +463,46 @@ impl<
         })
     }
 
+    /// Rolls back store created under saga ID. Returns `None` when nothing
+    /// was created under this saga, so compensation can be retried.
+    fn compensate_store_creation(&self, saga_id_arg: SagaId) -> ServiceFuture<Option<Store>> {
+        let repo_factory = self.static_context.repo_factory.clone();
+        let user_id = self.dynamic_context.user_id;
+        info!("Compensate store creation for saga {}", saga_id_arg);
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+                let wizard_stores_repo = repo_factory.create_wizard_stores_repo(&*conn, user_id);
+                conn.transaction::<Option<Store>, FailureError, _>(move || {
+                    let store = match stores_repo.find_by_saga_id(saga_id_arg)? {
+                        Some(store) => store,
+                        None => return Ok(None),
+                    };
+
+                    let store = if store.is_active {
+                        stores_repo.deactivate(store.id)?
+                    } else {
+                        store
+                    };
+
+                    let base_products = base_products_repo.deactivate_by_store(store.id)?;
+
+                    for base_product in &base_products {
+                        products_repo.deactivate_by_base_product(base_product.id)?;
+                    }
+
+                    let _wizard_store = wizard_stores_repo.delete(store.user_id);
+
+                    Ok(Some(store))
+                })
+            }
+            .map_err(|e: FailureError| e.context("Service Stores, compensate_store_creation endpoint error occurred.").into())
+        })
+    }
+
     /// Delete store by user id
     fn delete_store_by_user(&self, user_id_arg: UserId) -> ServiceFuture<Option<Store>> {
         let user_id = self.dynamic_context.user_id;
@@ -323,6 +529,19 @@ impl<
         })
     }
 
+    /// Returns stores with the ids and counts of their active base products
+    fn find_many_stores(&self, store_ids: Vec<StoreId>) -> ServiceFuture<Vec<StoreWithProductsCount>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            stores_repo
+                .find_many(store_ids)
+                .map_err(|e| e.context("Service Stores, find_many_stores endpoint error occurred.").into())
+        })
+    }
+
     /// Lists users limited by `from` and `count` parameters
     fn list_stores(&self, from: StoreId, count: i32, visibility: Option<Visibility>) -> ServiceFuture<Vec<Store>> {
         let user_id = self.dynamic_context.user_id;
@@ -391,14 +610,21 @@ impl<
                     }
                 }
 
-                conn.transaction::<Store, FailureError, _>(move || {
+                let store_profile_repo = repo_factory.create_store_profile_repo(&*conn, user_id);
+                let updated_store = conn.transaction::<Store, FailureError, _>(move || {
                     let store = stores_repo.update(store_id, payload)?;
 
                     match store.status {
                         ModerationStatus::Decline => stores_repo.set_moderation_status(store_id, ModerationStatus::Draft),
                         _ => Ok(store),
                     }
-                })
+                })?;
+                // profile is cached by slug, so both the former and the new slug are dropped
+                if updated_store.slug != store.slug {
+                    store_profile_repo.invalidate(StoreSlug(updated_store.slug.clone()));
+                }
+                store_profile_repo.invalidate(StoreSlug(store.slug));
+                Ok(updated_store)
             }
             .map_err(|e| e.context("Service Stores, update endpoint error occurred.").into())
         })
@@ -452,19 +678,46 @@ impl<
     fn set_store_moderation_status(&self, store_id: StoreId, status: ModerationStatus) -> ServiceFuture<Store> {
         let user_id = self.dynamic_context.user_id;
         let repo_factory = self.static_context.repo_factory.clone();
+        let notifier = create_notifier(&self.static_context.config, self.static_context.client_handle.clone());
         debug!("Set moderation status {} for store {}", status, store_id);
 
-        self.spawn_on_pool(move |conn| {
-            {
-                let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
-                let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
-
-                conn.transaction::<Store, FailureError, _>(move || {
-                    change_store_status(&*stores_repo, &*base_products_repo, store_id, status)
-                })
-            }
-            .map_err(|e: FailureError| e.context("Service stores, set_moderation_status endpoint error occurred.").into())
-        })
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                {
+                    let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
+                    let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
+                    let store_feed_repo = repo_factory.create_store_feed_repo(&conn, user_id);
+
+                    let store_profile_repo = repo_factory.create_store_profile_repo(&conn, user_id);
+
+                    let (store, was_published) = conn.transaction::<(Store, bool), FailureError, _>(move || {
+                        let was_published = stores_repo
+                            .find(store_id, Visibility::Active)?
+                            .map(|store| store.status == ModerationStatus::Published)
+                            .unwrap_or(false);
+                        let store = change_store_status(&*stores_repo, &*base_products_repo, store_id, status)?;
+                        Ok((store, was_published))
+                    })?;
+                    store_feed_repo.invalidate(store_id);
+                    store_profile_repo.invalidate(StoreSlug(store.slug.clone()));
+                    Ok((store, was_published))
+                }
+                .map_err(|e: FailureError| e.context("Service stores, set_moderation_status endpoint error occurred.").into())
+            })
+            .and_then(move |(store, was_published)| {
+                // store is announced only when it gets published, not when the status is set again
+                let events = if !was_published && store.status == ModerationStatus::Published {
+                    vec![FeedEvent::StorePublished {
+                        store_id: store.id,
+                        store_slug: store.slug.clone(),
+                        name: store.name.clone(),
+                    }]
+                } else {
+                    vec![]
+                };
+                send_events(notifier, events).map(move |_| store)
+            }),
+        )
     }
 
     /// Send store to moderation from store manager
@@ -479,6 +732,7 @@ impl<
                 let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
 
                 conn.transaction::<Store, FailureError, _>(move || {
+                    check_store_verified(&*stores_repo, store_id)?;
                     change_store_status(&*stores_repo, &*base_products_repo, store_id, ModerationStatus::Moderation)
                 })
             }
@@ -499,10 +753,13 @@ impl<
             {
                 let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
                 let base_products_repo = repo_factory.create_base_product_repo(&conn, user_id);
+                let store_profile_repo = repo_factory.create_store_profile_repo(&conn, user_id);
 
-                conn.transaction::<Store, FailureError, _>(move || {
+                let store = conn.transaction::<Store, FailureError, _>(move || {
                     change_store_status(&*stores_repo, &*base_products_repo, store_id, ModerationStatus::Draft)
-                })
+                })?;
+                store_profile_repo.invalidate(StoreSlug(store.slug.clone()));
+                Ok(store)
             }
             .map_err(|e: FailureError| {
                 e.context("Service stores, set_store_moderation_status_draft endpoint error occurred.")
@@ -552,16 +809,35 @@ impl<
             {
                 let stores_repo = repo_factory.create_stores_repo(&conn, user_id);
                 let wizard_stores_repo = repo_factory.create_wizard_stores_repo(&conn, user_id);
+                let store_profile_repo = repo_factory.create_store_profile_repo(&conn, user_id);
+                let store = stores_repo.find(store_id, Visibility::Active)?;
                 conn.transaction::<(), FailureError, _>(move || {
                     let _ = wizard_stores_repo.delete_by_store(store_id)?;
 
                     stores_repo.delete(store_id)
-                })
+                })?;
+                if let Some(store) = store {
+                    store_profile_repo.invalidate(StoreSlug(store.slug));
+                }
+                Ok(())
             }
             .map_err(|e: FailureError| e.context("Service stores, delete endpoint error occurred.").into())
         })
     }
 
+    /// Returns what deleting the store changes, nothing is deleted
+    fn delete_impact(&self, store_id: StoreId) -> ServiceFuture<ImpactReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let impact_reports_repo = repo_factory.create_impact_reports_repo(&*conn, user_id);
+            impact_reports_repo
+                .store_deletion(store_id)
+                .map_err(|e: FailureError| e.context("Service stores, delete_impact endpoint error occurred.").into())
+        })
+    }
+
     /// Check that you can update store
     fn validate_update_store(&self, store_id: StoreId) -> ServiceFuture<bool> {
         let user_id = self.dynamic_context.user_id;
@@ -580,88 +856,694 @@ impl<
             Ok(check_can_update_by_status(current_status))
         })
     }
-}
 
-pub fn change_store_status(
-    stores_repo: &StoresRepo,
-    base_products_repo: &BaseProductsRepo,
-    store_id: StoreId,
-    new_status: ModerationStatus,
-) -> Result<Store, FailureError> {
-    let store = stores_repo.find(store_id, Visibility::Active)?;
+    /// Shows prices of store products after switching to another currency, nothing is saved
+    fn preview_currency_change(&self, store_id: StoreId, payload: PreviewCurrencyChange) -> ServiceFuture<Vec<CurrencyChangePreview>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let new_currency = payload.currency;
 
-    let status = match store {
-        Some(value) => value.status,
-        None => return Err(Error::NotFound.into()),
-    };
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let products_repo = repo_factory.create_product_repo(&*conn, user_id);
+            let currency_exchange_repo = repo_factory.create_currency_exchange_repo(&*conn, user_id);
+
+            let result: Result<Vec<CurrencyChangePreview>, FailureError> = stores_repo
+                .find(store_id, Visibility::Active)
+                .and_then(|store| store.ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound).into()))
+                .and_then(|_| {
+                    base_products_repo.search(BaseProductsSearchTerms {
+                        is_active: Some(true),
+                        store_id: Some(store_id),
+                        ..Default::default()
+                    })
+                })
+                .and_then(|base_products| products_repo.find_with_base_ids(base_products.into_iter().map(|b| b.id).collect()))
+                .and_then(|products| {
+                    let mut rates = HashMap::new();
+                    products
+                        .into_iter()
+                        .filter(|product| product.is_active)
+                        .map(|product| -> Result<CurrencyChangePreview, FailureError> {
+                            let rate = if product.currency == new_currency {
+                                ExchangeRate(1.0)
+                            } else {
+                                if !rates.contains_key(&product.currency) {
+                                    let exchange = currency_exchange_repo.get_exchange_for_currency(product.currency)?;
+                                    rates.insert(product.currency, exchange.and_then(|e| e.get(&new_currency).cloned()));
+                                }
+                                rates.get(&product.currency).cloned().and_then(|rate| rate).ok_or_else(|| {
+                                    format_err!("No exchange rate from {:?} to {:?}", product.currency, new_currency).context(
+                                        Error::Validate(validation_errors!({
+                                            "currency": ["currency" => "No exchange rate for this currency"]
+                                        })),
+                                    )
+                                })?
+                            };
+
+                            Ok(CurrencyChangePreview {
+                                base_product_id: product.base_product_id,
+                                product_id: product.id,
+                                old_price: product.price,
+                                old_currency: product.currency,
+                                new_price: convert_price(product.price, rate, new_currency),
+                                new_currency,
+                            })
+                        })
+                        .collect()
+                });
 
-    if !check_change_status(status, new_status) {
-        return Err(format_err!("Store with id: {} cannot be sent to {}", store_id, new_status)
-            .context(Error::Validate(
-                validation_errors!({"stores": ["stores" => "Store can not be sent to new status"]}),
-            ))
-            .into());
+            result.map_err(|e| e.context("Service Stores, preview_currency_change endpoint error occurred.").into())
+        })
     }
 
-    let _ = base_products_repo.update_service_fields(
-        BaseProductsSearchTerms {
-            store_id: Some(store_id),
-            ..Default::default()
-        },
-        ServiceUpdateBaseProduct {
-            store_status: Some(new_status),
-        },
-    )?;
+    /// Returns product quota of the store and its usage
+    fn get_store_quota(&self, store_id: StoreId) -> ServiceFuture<StoreQuota> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let quota_config = self.static_context.config.product_quota.clone();
 
-    stores_repo.set_moderation_status(store_id, new_status)
-}
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            let store = stores_repo
+                .find(store_id, Visibility::Active)
+                .and_then(|store| store.ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound).into()));
 
-pub fn check_change_status(current_status: ModerationStatus, new_status: ModerationStatus) -> bool {
-    match (current_status, new_status) {
-        (ModerationStatus::Draft, ModerationStatus::Moderation)
-        | (ModerationStatus::Moderation, ModerationStatus::Published)
-        | (ModerationStatus::Published, ModerationStatus::Moderation)
-        | (ModerationStatus::Moderation, ModerationStatus::Blocked)
-        | (ModerationStatus::Blocked, ModerationStatus::Moderation)
-        | (ModerationStatus::Moderation, ModerationStatus::Decline)
-        | (ModerationStatus::Decline, ModerationStatus::Moderation)
-        | (ModerationStatus::Published, ModerationStatus::Draft)
-        | (ModerationStatus::Decline, ModerationStatus::Draft) => true,
-        (_, _) => {
-            debug!("change status from {} to {} unreachable.", current_status, new_status);
+            store
+                .and_then(|store| store_quota(quota_config.as_ref(), &*base_products_repo, store))
+                .map_err(|e: FailureError| e.context("Service Stores, get_store_quota endpoint error occurred.").into())
+        })
+    }
 
-            false
+    /// Sets billing plan of the store. For billing service
+    fn set_store_quota_plan(&self, store_id: StoreId, payload: SetStoreQuotaPlan) -> ServiceFuture<StoreQuota> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let quota_config = self.static_context.config.product_quota.clone();
+
+        info!("Set quota plan {:?} for store {}", payload.plan, store_id);
+
+        if !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Cannot set store quota plan").into()));
         }
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            stores_repo
+                .set_quota_plan(store_id, payload.plan)
+                .and_then(|store| store_quota(quota_config.as_ref(), &*base_products_repo, store))
+                .map_err(|e: FailureError| e.context("Service Stores, set_store_quota_plan endpoint error occurred.").into())
+        })
     }
-}
 
-/// Validate update object for store manager
-pub fn check_can_update_by_status(current_status: ModerationStatus) -> bool {
-    match current_status {
-        ModerationStatus::Draft | ModerationStatus::Decline | ModerationStatus::Published => true,
-        _ => {
-            debug!("update object in status {} unreachable.", current_status);
+    /// Returns listings of the store that need seller's attention
+    fn get_catalog_health(&self, store_id: StoreId, stale_price_days: Option<u64>) -> ServiceFuture<CatalogHealthReport> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let stale_price_days = stale_price_days.unwrap_or(DEFAULT_STALE_PRICE_DAYS);
 
-            false
-        }
+        self.spawn_on_pool(move |conn| {
+            let catalog_health_repo = repo_factory.create_catalog_health_repo(&*conn, user_id);
+            catalog_health_repo
+                .get(store_id, stale_price_days)
+                .and_then(|report| report.ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound).into()))
+                .map_err(|e: FailureError| e.context("Service Stores, get_catalog_health endpoint error occurred.").into())
+        })
     }
-}
 
-#[cfg(test)]
-pub mod tests {
-    use std::sync::Arc;
+    /// Returns aggregate statistics of the store catalog. For store owner
+    fn get_store_statistics(&self, store_id: StoreId) -> ServiceFuture<StoreStatistics> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
 
-    use serde_json;
-    use tokio_core::reactor::Core;
-    use uuid::Uuid;
+        self.spawn_on_pool(move |conn| {
+            let store_statistics_repo = repo_factory.create_store_statistics_repo(&*conn, user_id);
+            store_statistics_repo
+                .get(store_id)
+                .and_then(|statistics| {
+                    statistics.ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound).into())
+                })
+                .map_err(|e: FailureError| e.context("Service Stores, get_store_statistics endpoint error occurred.").into())
+        })
+    }
 
-    use stq_types::*;
+    /// Returns onboarding checklist of the store. For store owner
+    fn get_store_onboarding(&self, store_id: StoreId) -> ServiceFuture<StoreOnboarding> {
+        let user_id = self.dynamic_context.user_id;
+        let is_super_admin = self.dynamic_context.is_super_admin();
+        let repo_factory = self.static_context.repo_factory.clone();
 
-    use models::*;
-    use repos::repo_factory::tests::*;
-    use services::*;
+        self.spawn_on_pool(move |conn| {
+            {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let store = stores_repo
+                    .find(store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+                if is_super_admin || Some(store.user_id) == user_id {
+                    let published_products_count = base_products_repo.count_with_store_id(store_id, Visibility::Published)?;
+                    Ok(StoreOnboarding::new(&store, published_products_count))
+                } else {
+                    Err(format_err!("Onboarding of store {} is shown to its owner only", store_id)
+                        .context(Error::Forbidden)
+                        .into())
+                }
+            }
+            .map_err(|e: FailureError| e.context("Service Stores, get_store_onboarding endpoint error occurred.").into())
+        })
+    }
 
-    pub fn create_new_store(name: serde_json::Value) -> NewStore {
+    /// Sets payout and shipping facts of the store onboarding. For billing and delivery services
+    fn set_store_onboarding(&self, store_id: StoreId, payload: SetStoreOnboarding) -> ServiceFuture<StoreOnboarding> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        info!("Set onboarding {:?} for store {}", payload, store_id);
+
+        if !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Cannot set store onboarding").into()));
+        }
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+                let store = stores_repo.set_onboarding(store_id, payload)?;
+                let published_products_count = base_products_repo.count_with_store_id(store_id, Visibility::Published)?;
+                Ok(StoreOnboarding::new(&store, published_products_count))
+            }
+            .map_err(|e: FailureError| e.context("Service Stores, set_store_onboarding endpoint error occurred.").into())
+        })
+    }
+
+    /// Marks store as franchise. For superusers
+    fn set_store_franchise(&self, store_id: StoreId, payload: SetStoreFranchise) -> ServiceFuture<Store> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        info!("Set franchise {} for store {}", payload.franchise, store_id);
+
+        if !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Cannot set store franchise").into()));
+        }
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            stores_repo
+                .set_franchise(store_id, payload.franchise)
+                .map_err(|e: FailureError| e.context("Service Stores, set_store_franchise endpoint error occurred.").into())
+        })
+    }
+
+    /// Pauses or resumes sales of the store, products of the vacationing store are not found by search
+    fn set_store_vacation(&self, store_id: StoreId, payload: SetStoreVacation) -> ServiceFuture<Store> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        info!("Set vacation {} for store {}", payload.is_on_vacation, store_id);
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+            conn.transaction::<Store, FailureError, _>(move || {
+                let SetStoreVacation {
+                    is_on_vacation,
+                    vacation_message,
+                } = payload;
+                // Message of the previous vacation is not kept after sales are resumed
+                let vacation_message = if is_on_vacation { vacation_message } else { None };
+                let store = stores_repo.set_vacation(store_id, is_on_vacation, vacation_message)?;
+
+                let _ = base_products_repo.update_service_fields(
+                    BaseProductsSearchTerms {
+                        store_id: Some(store_id),
+                        ..Default::default()
+                    },
+                    ServiceUpdateBaseProduct {
+                        store_is_on_vacation: Some(is_on_vacation),
+                        ..Default::default()
+                    },
+                )?;
+
+                Ok(store)
+            })
+            .map_err(|e: FailureError| e.context("Service Stores, set_store_vacation endpoint error occurred.").into())
+        })
+    }
+
+    /// Sets verification status and badges of the store. For moderators
+    fn verify_store(&self, store_id: StoreId, payload: VerifyStore) -> ServiceFuture<Store> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        info!("Set verification {:?} for store {}", payload.verification_status, store_id);
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            stores_repo
+                .set_verification(store_id, payload)
+                .map_err(|e: FailureError| e.context("Service Stores, verify_store endpoint error occurred.").into())
+        })
+    }
+
+    /// Hands the store over to another user, who gets the role of store owners. For superusers
+    fn transfer_store_ownership(&self, store_id: StoreId, payload: TransferStoreOwnership) -> ServiceFuture<Store> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        info!("Transfer ownership of store {} to user {}", store_id, payload.user_id);
+
+        if !self.dynamic_context.is_super_admin() {
+            return Box::new(future::err(Error::Forbidden.context("Cannot transfer store ownership").into()));
+        }
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let user_roles_repo = repo_factory.create_user_roles_repo(&*conn, user_id);
+            let new_owner = payload.user_id;
+
+            conn.transaction::<(UserId, Store), FailureError, _>(|| {
+                let store = stores_repo
+                    .find(store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+                if store.user_id == new_owner {
+                    return Err(format_err!("Store {} already belongs to user {}", store_id, new_owner)
+                        .context(Error::Validate(
+                            validation_errors!({"user_id": ["same_owner" => "Store already belongs to the user"]}),
+                        ))
+                        .into());
+                }
+                if stores_repo.get_by_user(new_owner)?.is_some() {
+                    return Err(format_err!("User {} already has a store", new_owner)
+                        .context(Error::Validate(
+                            validation_errors!({"user_id": ["store" => "User can have only one store"]}),
+                        ))
+                        .into());
+                }
+                if !user_roles_repo.list_for_user(new_owner)?.contains(&StoresRole::User) {
+                    user_roles_repo.create(NewUserRole {
+                        id: None,
+                        user_id: new_owner,
+                        name: StoresRole::User,
+                        data: None,
+                    })?;
+                }
+
+                let updated = stores_repo.set_owner(store_id, new_owner)?;
+                Ok((store.user_id, updated))
+            })
+            .map(|(previous_owner, store)| {
+                // roles are cached after the transaction commits, so stale ones are dropped only now
+                user_roles_repo.invalidate_cache(previous_owner);
+                user_roles_repo.invalidate_cache(new_owner);
+                store
+            })
+            .map_err(|e: FailureError| e.context("Service Stores, transfer_store_ownership endpoint error occurred.").into())
+        })
+    }
+
+    /// Copies store into a new draft store of the same owner. For superusers and owners of franchise stores
+    fn clone_store(&self, store_id: StoreId, payload: CloneStore) -> ServiceFuture<StoreClone> {
+        let user_id = self.dynamic_context.user_id;
+        let is_super_admin = self.dynamic_context.is_super_admin();
+        let repo_factory = self.static_context.repo_factory.clone();
+        let quota_config = self.static_context.config.product_quota.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let base_products_repo = repo_factory.create_base_product_repo(&*conn, user_id);
+
+            conn.transaction::<StoreClone, FailureError, _>(|| {
+                let source = stores_repo
+                    .find(store_id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+                if !is_super_admin && !(source.franchise && Some(source.user_id) == user_id) {
+                    return Err(format_err!("Store {} is cloned by superusers or its owner when it is a franchise", store_id)
+                        .context(Error::Forbidden)
+                        .into());
+                }
+                if stores_repo.slug_exists(payload.slug.clone())? {
+                    return Err(format_err!("Store with slug '{}' already exists.", payload.slug)
+                        .context(Error::Validate(
+                            validation_errors!({"slug": ["slug" => "Store with this slug already exists"]}),
+                        ))
+                        .into());
+                }
+
+                let store = stores_repo.create(NewStore::clone_of(&source, payload.slug))?;
+                let mut copied_base_products = 0;
+                if payload.copy_catalog {
+                    let originals = base_products_repo.search(BaseProductsSearchTerms {
+                        is_active: Some(true),
+                        store_id: Some(store_id),
+                        ..Default::default()
+                    })?;
+                    for original in originals {
+                        check_product_quota(quota_config.as_ref(), &*stores_repo, &*base_products_repo, store.id)?;
+                        copy_base_product(&*conn, &repo_factory, user_id, original, store.id)?;
+                        copied_base_products += 1;
+                    }
+                } else {
+                    let categories = ServiceUpdateStore::copy_product_categories(source.product_categories.clone());
+                    stores_repo.update_service_fields(store.id, categories)?;
+                }
+
+                let store = stores_repo
+                    .find(store.id, Visibility::Active)?
+                    .ok_or_else(|| format_err!("Store with id {} not found", store.id).context(Error::NotFound))?;
+                Ok(StoreClone {
+                    store,
+                    source_store_id: source.id,
+                    copied_base_products,
+                })
+            })
+            .map_err(|e: FailureError| e.context("Service Stores, clone_store endpoint error occurred.").into())
+        })
+    }
+
+    /// Sends code confirming store email or phone
+    fn send_store_verification(&self, store_id: StoreId, payload: SendStoreVerification) -> ServiceFuture<StoreVerificationSent> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let sender = create_verification_sender(&self.static_context.config, self.static_context.client_handle.clone());
+        let channel = payload.channel;
+        info!("Send {:?} verification code for store {}", channel, store_id);
+
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let codes_repo = repo_factory.create_store_verification_codes_repo(&*conn, user_id);
+
+                conn.transaction::<StoreVerificationCode, FailureError, _>(|| {
+                    // the store row lock keeps concurrent requests from passing the limits together
+                    let store = stores_repo
+                        .find_for_update(store_id)?
+                        .ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+
+                    let destination = store.contact(channel).ok_or_else(|| {
+                        format_err!("Store {} has no contact for {:?}", store_id, channel).context(Error::Validate(
+                            validation_errors!({"channel": ["missing" => "Store has no contact for this channel"]}),
+                        ))
+                    })?;
+
+                    let now = SystemTime::now();
+                    let cooling_down = codes_repo
+                        .find_latest(store_id, channel)?
+                        .map(|code| code.is_cooling_down(now))
+                        .unwrap_or(false);
+                    let window_start = now - Duration::from_secs(VERIFICATION_CODES_WINDOW_S);
+                    if cooling_down || codes_repo.count_sent_since(store_id, channel, window_start)? >= MAX_VERIFICATION_CODES_PER_WINDOW {
+                        return Err(format_err!("Too many {:?} verification codes requested for store {}", channel, store_id)
+                            .context(Error::TooManyRequests)
+                            .into());
+                    }
+
+                    codes_repo.create(NewStoreVerificationCode::new(store_id, channel, destination))
+                })
+            })
+            .and_then(move |code| {
+                let sent = StoreVerificationSent {
+                    channel: code.channel,
+                    expires_at: code.expires_at,
+                };
+                sender.send_code(code.channel, code.destination, code.code).map(move |_| sent)
+            })
+            .map_err(|e: FailureError| e.context("Service Stores, send_store_verification endpoint error occurred.").into()),
+        )
+    }
+
+    /// Relays message of the user to the store owner through the notifications service
+    fn contact_store(&self, store_id: StoreId, payload: StoreContactMessage) -> ServiceFuture<()> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let sender = create_store_contact_sender(&self.static_context.config, self.static_context.client_handle.clone());
+
+        let sender_id = match user_id {
+            Some(sender_id) => sender_id,
+            None => {
+                return Box::new(future::err(
+                    format_err!("Only authorized users can contact stores").context(Error::Forbidden).into(),
+                ))
+            }
+        };
+        info!("User {} contacts store {}", sender_id, store_id);
+
+        Box::new(
+            self.spawn_on_pool(move |conn| {
+                let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+                let store = stores_repo
+                    .find(store_id, Visibility::Published)?
+                    .ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+                Ok(store)
+            })
+            .and_then(move |store| {
+                sender.send_message(StoreContactNotification {
+                    store_id: store.id,
+                    store_user_id: store.user_id,
+                    store_email: store.email,
+                    sender_id,
+                    sender_name: payload.name,
+                    sender_email: payload.email,
+                    subject: payload.subject,
+                    text: payload.text,
+                })
+            })
+            .map_err(|e: FailureError| e.context("Service Stores, contact_store endpoint error occurred.").into()),
+        )
+    }
+
+    /// Confirms store email or phone with the received code
+    fn confirm_store_verification(&self, store_id: StoreId, payload: ConfirmStoreVerification) -> ServiceFuture<Store> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let channel = payload.channel;
+        info!("Confirm {:?} of store {}", channel, store_id);
+
+        self.spawn_on_pool(move |conn| {
+            let stores_repo = repo_factory.create_stores_repo(&*conn, user_id);
+            let codes_repo = repo_factory.create_store_verification_codes_repo(&*conn, user_id);
+
+            let store = stores_repo
+                .find(store_id, Visibility::Active)?
+                .ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+
+            let code = match codes_repo.find_latest(store_id, channel)? {
+                Some(ref code) if !code.is_expired(SystemTime::now()) && store.contact(channel).as_ref() == Some(&code.destination) => {
+                    code.clone()
+                }
+                _ => {
+                    return Err(format_err!("No valid {:?} verification code for store {}", channel, store_id)
+                        .context(Error::Validate(
+                            validation_errors!({"code": ["expired" => "Verification code is expired, request a new one"]}),
+                        ))
+                        .into());
+                }
+            };
+
+            if !code.matches(&payload.code) {
+                codes_repo.increment_attempts(code.id)?;
+                return Err(format_err!("Wrong {:?} verification code for store {}", channel, store_id)
+                    .context(Error::Validate(validation_errors!({"code": ["wrong" => "Wrong verification code"]})))
+                    .into());
+            }
+
+            let store = stores_repo.set_contact_verified(store_id, channel)?;
+            codes_repo.delete_by_store(store_id, channel)?;
+            Ok(store)
+        })
+        .map_err(|e: FailureError| e.context("Service Stores, confirm_store_verification endpoint error occurred.").into())
+    }
+
+    /// Returns public profile of the published store
+    fn get_store_profile(&self, store_slug: StoreSlug) -> ServiceFuture<StoreProfile> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let store_profile_repo = repo_factory.create_store_profile_repo(&*conn, user_id);
+            store_profile_repo
+                .get(store_slug.clone())
+                .and_then(|profile| {
+                    profile.ok_or(format_err!("Store with slug {} not found", store_slug).context(Error::NotFound).into())
+                })
+                .map_err(|e: FailureError| e.context("Service Stores, get_store_profile endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns RSS feed of recently published products of the store, titles are in `lang`
+    fn get_store_rss(&self, store_id: StoreId, lang: Option<String>) -> ServiceFuture<String> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let storefront_url = storefront_url(&self.static_context.config);
+
+        self.spawn_on_pool(move |conn| {
+            let store_feed_repo = repo_factory.create_store_feed_repo(&*conn, user_id);
+            store_feed_repo
+                .get(store_id)
+                .and_then(|feed| feed.ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound).into()))
+                .and_then(|feed| {
+                    let lang = lang.unwrap_or_else(|| feed.default_language.clone());
+                    feed.to_rss(&storefront_url, &lang)
+                })
+                .map_err(|e: FailureError| e.context("Service Stores, get_store_rss endpoint error occurred.").into())
+        })
+    }
+
+    /// Returns sitemap of the store and its recently published products
+    fn get_store_sitemap(&self, store_id: StoreId) -> ServiceFuture<String> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+        let storefront_url = storefront_url(&self.static_context.config);
+
+        self.spawn_on_pool(move |conn| {
+            let store_feed_repo = repo_factory.create_store_feed_repo(&*conn, user_id);
+            store_feed_repo
+                .get(store_id)
+                .and_then(|feed| feed.ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound).into()))
+                .and_then(|feed| feed.to_sitemap(&storefront_url))
+                .map_err(|e: FailureError| e.context("Service Stores, get_store_sitemap endpoint error occurred.").into())
+        })
+    }
+}
+
+/// Store feeds link to the storefront, links are relative when it is not configured
+fn storefront_url(config: &Config) -> String {
+    config.storefront.as_ref().map(|storefront| storefront.url.clone()).unwrap_or_default()
+}
+
+pub fn change_store_status(
+    stores_repo: &StoresRepo,
+    base_products_repo: &BaseProductsRepo,
+    store_id: StoreId,
+    new_status: ModerationStatus,
+) -> Result<Store, FailureError> {
+    let store = stores_repo.find(store_id, Visibility::Active)?;
+
+    let status = match store {
+        Some(value) => value.status,
+        None => return Err(Error::NotFound.into()),
+    };
+
+    if !check_change_status(status, new_status) {
+        return Err(format_err!("Store with id: {} cannot be sent to {}", store_id, new_status)
+            .context(Error::Validate(
+                validation_errors!({"stores": ["stores" => "Store can not be sent to new status"]}),
+            ))
+            .into());
+    }
+
+    let _ = base_products_repo.update_service_fields(
+        BaseProductsSearchTerms {
+            store_id: Some(store_id),
+            ..Default::default()
+        },
+        ServiceUpdateBaseProduct {
+            store_status: Some(new_status),
+            ..Default::default()
+        },
+    )?;
+
+    stores_repo.set_moderation_status(store_id, new_status)
+}
+
+/// Unverified stores can keep drafts, but cannot send anything to moderation
+pub fn check_store_verified(stores_repo: &StoresRepo, store_id: StoreId) -> Result<(), FailureError> {
+    let store = stores_repo
+        .find(store_id, Visibility::Active)?
+        .ok_or(format_err!("Store with id {} not found", store_id).context(Error::NotFound))?;
+
+    if store.is_verified() {
+        Ok(())
+    } else {
+        Err(format_err!("Store with id {} has unverified contacts", store_id)
+            .context(Error::Validate(
+                validation_errors!({"store": ["not_verified" => "Store email and phone must be verified before sending to moderation"]}),
+            ))
+            .into())
+    }
+}
+
+pub fn check_change_status(current_status: ModerationStatus, new_status: ModerationStatus) -> bool {
+    match (current_status, new_status) {
+        (ModerationStatus::Draft, ModerationStatus::Moderation)
+        | (ModerationStatus::Moderation, ModerationStatus::Published)
+        | (ModerationStatus::Published, ModerationStatus::Moderation)
+        | (ModerationStatus::Moderation, ModerationStatus::Blocked)
+        | (ModerationStatus::Blocked, ModerationStatus::Moderation)
+        | (ModerationStatus::Moderation, ModerationStatus::Decline)
+        | (ModerationStatus::Decline, ModerationStatus::Moderation)
+        | (ModerationStatus::Published, ModerationStatus::Draft)
+        | (ModerationStatus::Decline, ModerationStatus::Draft) => true,
+        (_, _) => {
+            debug!("change status from {} to {} unreachable.", current_status, new_status);
+
+            false
+        }
+    }
+}
+
+/// Validate update object for store manager
+pub fn check_can_update_by_status(current_status: ModerationStatus) -> bool {
+    match current_status {
+        ModerationStatus::Draft | ModerationStatus::Decline | ModerationStatus::Published => true,
+        _ => {
+            debug!("update object in status {} unreachable.", current_status);
+
+            false
+        }
+    }
+}
+
+fn store_quota(quota_config: Option<&ProductQuota>, base_products_repo: &BaseProductsRepo, store: Store) -> Result<StoreQuota, FailureError> {
+    let used = base_products_repo.count_with_store_id(store.id, Visibility::Active)?;
+    Ok(StoreQuota::new(store.id, store.quota_plan, quota_config, used))
+}
+
+/// Fails with `QUOTA_EXCEEDED` when store plan has no room for one more base product.
+/// Store row stays locked until the end of the caller's transaction, so concurrent creations can't both take the last slot
+pub fn check_product_quota(
+    quota_config: Option<&ProductQuota>,
+    stores_repo: &StoresRepo,
+    base_products_repo: &BaseProductsRepo,
+    store_id: StoreId,
+) -> Result<(), FailureError> {
+    if quota_config.is_none() {
+        return Ok(());
+    }
+
+    let store = stores_repo
+        .find_for_update(store_id)?
+        .ok_or_else(|| format_err!("There is no store with id {}", store_id).context(Error::NotFound))?;
+    let quota = store_quota(quota_config, base_products_repo, store)?;
+    if quota.is_exceeded_by(1) {
+        return Err(format_err!("Store {} has reached product quota {:?}", store_id, quota.limit)
+            .context(Error::Validate(validation_errors!({
+                "store_id": [QUOTA_EXCEEDED => "Store has reached product quota of its plan"]
+            })))
+            .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use serde_json;
+    use tokio_core::reactor::Core;
+    use uuid::Uuid;
+
+    use stq_static_resources::Currency;
+    use stq_types::*;
+
+    use models::*;
+    use repos::repo_factory::tests::*;
+    use services::*;
+
+    pub fn create_new_store(name: serde_json::Value) -> NewStore {
         NewStore {
             name,
             user_id: MOCK_USER_ID,
@@ -680,6 +1562,8 @@ pub mod tests {
             slogan: Some("fdsf".to_string()),
             country: None,
             country_code: None,
+            latitude: None,
+            longitude: None,
             administrative_area_level_1: None,
             administrative_area_level_2: None,
             locality: None,
@@ -689,6 +1573,12 @@ pub mod tests {
             saga_id: None,
             street_number: None,
             place_id: None,
+            opening_hours: None,
+            timezone: None,
+            default_length_cm: None,
+            default_width_cm: None,
+            default_height_cm: None,
+            default_weight_g: None,
             uuid: Uuid::new_v4(),
         }
     }
@@ -711,6 +1601,8 @@ pub mod tests {
             slogan: None,
             country: None,
             country_code: None,
+            latitude: None,
+            longitude: None,
             administrative_area_level_1: None,
             administrative_area_level_2: None,
             locality: None,
@@ -719,6 +1611,12 @@ pub mod tests {
             route: None,
             street_number: None,
             place_id: None,
+            opening_hours: None,
+            timezone: None,
+            default_length_cm: None,
+            default_width_cm: None,
+            default_height_cm: None,
+            default_weight_g: None,
         }
     }
 
@@ -729,7 +1627,20 @@ pub mod tests {
         let service = create_service(Some(MOCK_USER_ID), handle);
         let work = service.get_store(StoreId(1), Some(Visibility::Active));
         let result = core.run(work).unwrap();
-        assert_eq!(result.unwrap().id, StoreId(1));
+        assert_eq!(result.unwrap().store.id, StoreId(1));
+    }
+
+    #[test]
+    fn test_get_store_by_old_slug() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.get_store_by_slug(StoreSlug(MOCK_OLD_STORE_SLUG.to_string()), None);
+        let result = core.run(work).unwrap();
+        match result {
+            Some(StoreBySlug::Moved(moved)) => assert_eq!(moved.store_id, MOCK_STORE_ID),
+            _ => panic!("Store by old slug must be moved"),
+        }
     }
 
     #[test]
@@ -782,4 +1693,311 @@ pub mod tests {
         assert_eq!(result.is_active, false);
     }
 
+    #[test]
+    fn test_compensate_store_creation() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let saga_id = SagaId(Uuid::new_v4());
+        let work = service.compensate_store_creation(saga_id);
+        let result = core.run(work).unwrap().unwrap();
+        assert_eq!(result.id, StoreId(1));
+        assert_eq!(result.is_active, false);
+    }
+
+    #[test]
+    fn test_preview_currency_change() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.preview_currency_change(StoreId(1), PreviewCurrencyChange { currency: Currency::STQ });
+        let result = core.run(work);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_set_store_quota_plan() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = SetStoreQuotaPlan {
+            plan: Some("pro".to_string()),
+        };
+        let work = service.set_store_quota_plan(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.plan, Some("pro".to_string()));
+        assert_eq!(result.limit, None);
+    }
+
+    #[test]
+    fn test_get_catalog_health() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_catalog_health(StoreId(1), None);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.stale_price_days, DEFAULT_STALE_PRICE_DAYS);
+        assert_eq!(result.missing_photos, vec![MOCK_PRODUCT_ID]);
+        assert!(result.stale_prices.is_empty());
+        assert!(result.out_of_stock.is_empty());
+    }
+
+    #[test]
+    fn test_get_store_statistics() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_store_statistics(StoreId(1));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.store_id, StoreId(1));
+        assert_eq!(result.base_products, 1);
+        assert_eq!(result.average_rating, None);
+    }
+
+    #[test]
+    fn test_delete_impact() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.delete_impact(StoreId(1));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.tables["stores"], 1);
+        assert_eq!(result.elastic_documents["stores"], 1);
+    }
+
+    #[test]
+    fn test_find_many_stores() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.find_many_stores(vec![StoreId(1), StoreId(2)]);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].store.id, StoreId(2));
+        assert_eq!(result[1].products_count, 1);
+    }
+
+    #[test]
+    fn test_contact_store_anonymous() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.contact_store(
+            StoreId(1),
+            StoreContactMessage {
+                name: "Buyer".to_string(),
+                email: "buyer@example.com".to_string(),
+                subject: None,
+                text: "Do you ship abroad?".to_string(),
+            },
+        );
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_store_onboarding() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_store_onboarding(StoreId(1));
+        let result = core.run(work).unwrap();
+        assert!(!result.is_completed);
+        let product_published = OnboardingItem {
+            step: OnboardingStep::ProductPublished,
+            done: true,
+        };
+        assert!(result.items.contains(&product_published));
+    }
+
+    #[test]
+    fn test_set_store_onboarding() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = SetStoreOnboarding {
+            payout_info_present: Some(true),
+            shipping_profile_set: None,
+        };
+        let work = service.set_store_onboarding(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        let payout_info_present = OnboardingItem {
+            step: OnboardingStep::PayoutInfoPresent,
+            done: true,
+        };
+        assert!(result.items.contains(&payout_info_present));
+    }
+
+    #[test]
+    fn test_clone_store() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = CloneStore {
+            slug: "regional-store".to_string(),
+            copy_catalog: true,
+        };
+        let work = service.clone_store(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.source_store_id, StoreId(1));
+        assert_eq!(result.copied_base_products, 0);
+    }
+
+    #[test]
+    fn test_clone_store_not_franchise() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(UserId(2)), handle);
+        let payload = CloneStore {
+            slug: "regional-store".to_string(),
+            copy_catalog: false,
+        };
+        let work = service.clone_store(StoreId(1), payload);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_store_vacation() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = SetStoreVacation {
+            is_on_vacation: true,
+            vacation_message: Some("Back in two weeks".to_string()),
+        };
+        let work = service.set_store_vacation(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        assert!(result.is_on_vacation);
+        assert_eq!(result.vacation_message, Some("Back in two weeks".to_string()));
+
+        let payload = SetStoreVacation {
+            is_on_vacation: false,
+            vacation_message: Some("Back in two weeks".to_string()),
+        };
+        let work = service.set_store_vacation(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        assert!(!result.is_on_vacation);
+        assert_eq!(result.vacation_message, None);
+    }
+
+    #[test]
+    fn test_verify_store() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = VerifyStore {
+            verification_status: StoreVerificationStatus::Verified,
+            badges: vec!["official_brand".to_string()],
+        };
+        let work = service.verify_store(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        assert_eq!(result.verification_status, StoreVerificationStatus::Verified);
+        assert_eq!(result.badges, json!(["official_brand"]));
+    }
+
+    #[test]
+    fn test_transfer_store_ownership() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.transfer_store_ownership(StoreId(1), TransferStoreOwnership { user_id: UserId(2) });
+        let result = core.run(work).unwrap();
+        assert_eq!(result.user_id, UserId(2));
+
+        let work = service.transfer_store_ownership(StoreId(1), TransferStoreOwnership { user_id: MOCK_USER_ID });
+        assert!(core.run(work).is_err());
+
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(UserId(2)), handle);
+        let work = service.transfer_store_ownership(StoreId(1), TransferStoreOwnership { user_id: UserId(3) });
+        assert!(core.run(work).is_err());
+    }
+
+    #[test]
+    fn test_get_store_profile() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.get_store_profile(StoreSlug("my-store".to_string()));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.store.slug, "my-store");
+        assert_eq!(result.published_products_count, 1);
+    }
+
+    #[test]
+    fn test_get_store_profile_after_update() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_store_profile(StoreSlug("myname".to_string()));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.published_products_count, 1);
+
+        let work = service.get_store_profile(StoreSlug("myname".to_string()));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.published_products_count, 1);
+
+        let new_store = create_update_store(serde_json::from_str(MOCK_STORE_NAME_JSON).unwrap());
+        let work = service.update_store(StoreId(1), new_store);
+        core.run(work).unwrap();
+
+        let work = service.get_store_profile(StoreSlug("myname".to_string()));
+        let result = core.run(work).unwrap();
+        assert_eq!(result.published_products_count, 2);
+    }
+
+    #[test]
+    fn test_get_store_rss() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(None, handle);
+        let work = service.get_store_rss(StoreId(1), Some("en".to_string()));
+        let result = core.run(work).unwrap();
+        assert!(result.contains("<rss version=\"2.0\">"));
+        assert!(result.contains("/store/1/products/1</link>"));
+    }
+
+    #[test]
+    fn test_confirm_store_verification() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ConfirmStoreVerification {
+            channel: VerificationChannel::Email,
+            code: MOCK_VERIFICATION_CODE.to_string(),
+        };
+        let work = service.confirm_store_verification(StoreId(1), payload);
+        let result = core.run(work).unwrap();
+        assert!(result.email_verified);
+    }
+
+    #[test]
+    fn test_send_store_verification_cooldown() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        // the latest code of the mock is just sent
+        let payload = SendStoreVerification {
+            channel: VerificationChannel::Email,
+        };
+        let work = service.send_store_verification(StoreId(1), payload);
+        let err = core.run(work).unwrap_err();
+        assert!(err.iter_chain().any(|cause| cause.to_string() == "Too many requests"));
+    }
+
+    #[test]
+    fn test_confirm_store_verification_wrong_code() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = ConfirmStoreVerification {
+            channel: VerificationChannel::Phone,
+            code: "000000".to_string(),
+        };
+        let work = service.confirm_store_verification(StoreId(1), payload);
+        let result = core.run(work);
+        assert!(result.is_err());
+    }
+
 }
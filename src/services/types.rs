@@ -6,6 +6,7 @@ use failure::Fail;
 use futures::Future;
 use r2d2::{ManageConnection, PooledConnection};
 
+use chaos::{self, FaultLayer};
 use controller::context::{DynamicContext, StaticContext};
 use errors::Error;
 use repos::repo_factory::*;
@@ -45,7 +46,45 @@ impl<
     {
         let db_pool = self.static_context.db_pool.clone();
         let cpu_pool = self.static_context.cpu_pool.clone();
-        Box::new(cpu_pool.spawn_fn(move || db_pool.get().map_err(|e| e.context(Error::Connection).into()).and_then(f)))
+        Box::new(cpu_pool.spawn_fn(move || {
+            chaos::inject(FaultLayer::Repo)
+                .and_then(|_| db_pool.get().map_err(|e| e.context(Error::Connection).into()))
+                .and_then(f)
+        }))
+    }
+
+    /// Runs `f` with a connection to the read replica, only read halves of repos may be created on it.
+    /// Replica lags behind the primary, so requests reading their own writes stay on `spawn_on_pool`
+    pub fn spawn_on_replica<R, Func>(&self, f: Func) -> ServiceFuture<R>
+    where
+        Func: FnOnce(PooledConnection<M>) -> Result<R, FailureError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let db_pool = self.static_context.db_replica_pool.clone();
+        let cpu_pool = self.static_context.cpu_pool.clone();
+        Box::new(cpu_pool.spawn_fn(move || {
+            chaos::inject(FaultLayer::Repo)
+                .and_then(|_| db_pool.get().map_err(|e| e.context(Error::Connection).into()))
+                .and_then(f)
+        }))
+    }
+
+    /// Runs `f` on the pool without waiting for its result, so errors are only logged
+    pub fn spawn_background<Func>(&self, f: Func)
+    where
+        Func: FnOnce(PooledConnection<M>) -> Result<(), FailureError> + Send + 'static,
+    {
+        let db_pool = self.static_context.db_pool.clone();
+        let cpu_pool = self.static_context.cpu_pool.clone();
+        cpu_pool
+            .spawn_fn(move || {
+                db_pool
+                    .get()
+                    .map_err(|e| e.context(Error::Connection).into())
+                    .and_then(f)
+                    .map_err(|e: FailureError| error!("Background task error occurred: {}", e))
+            })
+            .forget();
     }
 }
 
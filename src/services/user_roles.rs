@@ -6,11 +6,13 @@ use diesel::connection::AnsiTransactionManager;
 use diesel::pg::Pg;
 use diesel::Connection;
 use failure::Error as FailureError;
+use failure::Fail;
 use r2d2::ManageConnection;
 
 use stq_types::{RoleId, StoresRole, UserId};
 
-use models::{NewUserRole, RemoveUserRole, UserRole};
+use errors::Error;
+use models::{AuditLogEntry, NewAuditLogEntry, NewUserRole, RemoveUserRole, UserRole};
 use repos::ReposFactory;
 use services::types::ServiceFuture;
 use services::Service;
@@ -33,6 +35,9 @@ pub trait UserRolesService {
 
     /// Returns collection user_id
     fn get_user_ids_by_role(&self, role_name: StoresRole) -> ServiceFuture<HashSet<UserId>>;
+
+    /// Checks that current user is superuser and records request made on behalf of another user
+    fn start_impersonation(&self, effective_user_id: UserId, method: String, path: String) -> ServiceFuture<AuditLogEntry>;
 }
 
 impl<
@@ -117,4 +122,36 @@ impl<
             })
         })
     }
+
+    /// Checks that current user is superuser and records request made on behalf of another user
+    fn start_impersonation(&self, effective_user_id: UserId, method: String, path: String) -> ServiceFuture<AuditLogEntry> {
+        let current_uid = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            {
+                let actor_user_id = current_uid.ok_or_else(|| format_err!("Impersonation requires authorized user").context(Error::Forbidden))?;
+                let user_roles_repo = repo_factory.create_user_roles_repo(&*conn, current_uid);
+                let audit_log_repo = repo_factory.create_audit_log_repo(&*conn, current_uid);
+
+                let roles = user_roles_repo.list_for_user(actor_user_id)?;
+                if !roles.contains(&StoresRole::Superuser) {
+                    return Err(format_err!("User {} is not allowed to impersonate user {}", actor_user_id, effective_user_id)
+                        .context(Error::Forbidden)
+                        .into());
+                }
+
+                info!("User {} acts on behalf of user {}: {} {}", actor_user_id, effective_user_id, method, path);
+                audit_log_repo.create(NewAuditLogEntry {
+                    actor_user_id,
+                    effective_user_id,
+                    method: Some(method),
+                    path: Some(path),
+                    base_product_id: None,
+                    change: None,
+                })
+            }
+            .map_err(|e: FailureError| e.context("Service user_roles, start_impersonation endpoint error occurred.").into())
+        })
+    }
 }
@@ -10,6 +10,7 @@ use super::types::ServiceFuture;
 use errors::Error;
 use models::*;
 use repos::ReposFactory;
+use services::store_categories::check_first_level_categories;
 use services::Service;
 
 pub trait WizardStoresService {
@@ -21,6 +22,10 @@ pub trait WizardStoresService {
     fn create_wizard_store(&self) -> ServiceFuture<WizardStore>;
     /// Updates specific wizard store
     fn update_wizard_store(&self, payload: UpdateWizardStore) -> ServiceFuture<WizardStore>;
+    /// Returns counts of wizards by stage of the funnel
+    fn get_wizard_stores_stats(&self) -> ServiceFuture<WizardStoresStats>;
+    /// Declares categories the store of the wizard sells in
+    fn declare_wizard_store_categories(&self, payload: DeclareStoreCategories) -> ServiceFuture<Vec<StoreCategory>>;
 }
 
 impl<
@@ -146,6 +151,65 @@ impl<
             ))
         }
     }
+
+    /// Returns counts of wizards by stage of the funnel
+    fn get_wizard_stores_stats(&self) -> ServiceFuture<WizardStoresStats> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        self.spawn_on_pool(move |conn| {
+            let wizard_stores_repo = repo_factory.create_wizard_stores_repo(&*conn, user_id);
+            wizard_stores_repo.stats().map_err(|e| {
+                e.context("Service wizard store, get_wizard_stores_stats endpoint error occurred.")
+                    .into()
+            })
+        })
+    }
+
+    /// Declares categories the store of the wizard sells in, categories declared before are replaced
+    fn declare_wizard_store_categories(&self, payload: DeclareStoreCategories) -> ServiceFuture<Vec<StoreCategory>> {
+        let user_id = self.dynamic_context.user_id;
+        let repo_factory = self.static_context.repo_factory.clone();
+
+        if let Some(user_id) = user_id {
+            self.spawn_on_pool(move |conn| {
+                let wizard_stores_repo = repo_factory.create_wizard_stores_repo(&*conn, Some(user_id));
+                let categories_repo = repo_factory.create_categories_repo(&*conn, Some(user_id));
+                let store_categories_repo = repo_factory.create_store_categories_repo(&*conn, Some(user_id));
+                conn.transaction::<Vec<StoreCategory>, FailureError, _>(move || {
+                    let wizard = wizard_stores_repo
+                        .find_by_user_id(user_id)?
+                        .filter(|wizard| !wizard.completed)
+                        .ok_or_else(|| format_err!("Wizard of user {} not found", user_id).context(Error::NotFound))?;
+                    let store_id = wizard.store_id.ok_or_else(|| {
+                        format_err!("Wizard of user {} has no store yet", user_id).context(Error::Validate(
+                            validation_errors!({"store_id": ["required" => "Store must be created before declaring its categories"]}),
+                        ))
+                    })?;
+                    if payload.category_ids.is_empty() {
+                        return Err(format_err!("No categories declared")
+                            .context(Error::Validate(
+                                validation_errors!({"category_ids": ["required" => "Store must sell in at least one category"]}),
+                            ))
+                            .into());
+                    }
+                    check_first_level_categories(&*categories_repo, &payload.category_ids)?;
+
+                    store_categories_repo.replace_declared(store_id, payload.category_ids)
+                })
+                .map_err(|e| {
+                    e.context("Service wizard store, declare_wizard_store_categories endpoint error occurred.")
+                        .into()
+                })
+            })
+        } else {
+            Box::new(future::err(
+                format_err!("Denied request to wizard for unauthorized user")
+                    .context(Error::Forbidden)
+                    .into(),
+            ))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -154,6 +218,8 @@ pub mod tests {
 
     use tokio_core::reactor::Core;
 
+    use stq_types::CategoryId;
+
     use models::*;
     use repos::repo_factory::tests::*;
     use services::*;
@@ -207,4 +273,26 @@ pub mod tests {
         assert_eq!(result.user_id, MOCK_USER_ID);
     }
 
+    #[test]
+    fn test_get_wizard_stores_stats() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let work = service.get_wizard_stores_stats();
+        let result = core.run(work).unwrap();
+        assert_eq!(result.in_progress, 2);
+        assert_eq!(result.archived, 1);
+    }
+
+    #[test]
+    fn test_declare_categories_without_store() {
+        let mut core = Core::new().unwrap();
+        let handle = Arc::new(core.handle());
+        let service = create_service(Some(MOCK_USER_ID), handle);
+        let payload = DeclareStoreCategories {
+            category_ids: vec![CategoryId(1)],
+        };
+        let work = service.declare_wizard_store_categories(payload);
+        assert!(core.run(work).is_err());
+    }
 }
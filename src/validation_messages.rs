@@ -0,0 +1,149 @@
+//! Catalog of validation messages translated by error code.
+//!
+//! Language of the request is taken from `Accept-Language` header, validation errors are answered
+//! with both `code` and the message in that language, so mobile apps can show errors as they are.
+use failure::Error as FailureError;
+use isolang::Language;
+use serde_json;
+
+use errors::Error;
+use models::{CATEGORY_NOT_DECLARED, NOT_ENOUGH_STOCK, QUOTA_EXCEEDED, TOO_MANY_PHOTOS};
+
+/// Language of messages when the request asks for none of the translated ones
+pub const DEFAULT_LANGUAGE: Language = Language::Eng;
+
+/// Languages messages are translated to
+pub const LANGUAGES: &'static [Language] = &[Language::Eng, Language::Rus];
+
+/// Translations of error codes as (code, english, russian)
+const CATALOG: &'static [(&'static str, &'static str, &'static str)] = &[
+    (NOT_ENOUGH_STOCK, "Not enough units in stock", "Недостаточно товара на складе"),
+    (TOO_MANY_PHOTOS, "Too many photos of the product", "Слишком много фотографий товара"),
+    (QUOTA_EXCEEDED, "Store has reached product quota of its plan", "Магазин исчерпал лимит товаров своего тарифа"),
+    (
+        CATEGORY_NOT_DECLARED,
+        "Store does not sell in the category, request category expansion first",
+        "Магазин не продает товары этой категории, сначала запросите расширение категорий",
+    ),
+    ("required", "Value is required", "Обязательное поле"),
+    ("length", "Value has invalid length", "Недопустимая длина значения"),
+    ("range", "Value is out of range", "Значение вне допустимого диапазона"),
+    ("email", "Incorrect email format", "Неверный формат email"),
+    ("phone", "Incorrect phone format", "Неверный формат телефона"),
+    ("slug", "Incorrect slug format", "Неверный формат адреса"),
+    ("language", "Value must be ISO 639-1 format.", "Значение должно быть в формате ISO 639-1."),
+    ("value", "Value must not be empty.", "Значение не должно быть пустым."),
+    ("not_unique", "Value already exists", "Такое значение уже существует"),
+    ("unique", "Value is listed more than once", "Значение указано более одного раза"),
+    ("duplicate", "Value is listed more than once", "Значение указано более одного раза"),
+    ("exists", "Already exists", "Уже существует"),
+    ("not_found", "Not found", "Не найдено"),
+    ("wrong", "Wrong verification code", "Неверный код подтверждения"),
+    ("expired", "Verification code is expired, request a new one", "Срок действия кода истек, запросите новый"),
+    ("not_verified", "Store email and phone must be verified", "Email и телефон магазина должны быть подтверждены"),
+    ("not_active", "Coupon is not active", "Купон не активен"),
+    ("has_expired", "Coupon has expired", "Срок действия купона истек"),
+    ("already_activated", "Coupon already activated by user", "Купон уже активирован пользователем"),
+    ("no_activations", "No activations available", "Нет доступных активаций"),
+];
+
+/// Language of the request from `Accept-Language` header value, languages are tried in the listed order
+pub fn language_from_header(value: &str) -> Language {
+    value
+        .split(',')
+        .filter_map(|range| range.split(';').next())
+        .filter_map(|tag| tag.trim().split('-').next())
+        .filter_map(|primary| Language::from_639_1(&primary.to_lowercase()))
+        .find(|language| LANGUAGES.contains(language))
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+/// Message of the error code in the language, `None` if the code is not in the catalog
+pub fn translate(code: &str, language: Language) -> Option<&'static str> {
+    CATALOG.iter().find(|&&(c, _, _)| c == code).map(|&(_, en, ru)| match language {
+        Language::Rus => ru,
+        _ => en,
+    })
+}
+
+/// Sets messages of serialized validation errors to the language.
+/// English messages written at the error site are kept, they are more precise than the catalog ones.
+/// Errors with codes missing in the catalog keep their messages.
+pub fn localize_messages(value: &mut serde_json::Value, language: Language) {
+    match *value {
+        serde_json::Value::Object(ref mut map) => {
+            let message = map.get("code").and_then(|code| code.as_str()).and_then(|code| translate(code, language));
+            if let Some(message) = message {
+                let keep_original = language == DEFAULT_LANGUAGE && map.get("message").map(|m| m.is_string()).unwrap_or(false);
+                if !keep_original {
+                    map.insert("message".to_string(), json!(message));
+                }
+            }
+            for (_, nested) in map.iter_mut() {
+                localize_messages(nested, language);
+            }
+        }
+        serde_json::Value::Array(ref mut items) => {
+            for item in items.iter_mut() {
+                localize_messages(item, language);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Wraps the validation error of the chain with its localized payload, other errors are returned as they are
+pub fn localize_error(err: FailureError, language: Language) -> FailureError {
+    let payload = err
+        .iter_chain()
+        .filter_map(|cause| cause.downcast_ref::<Error>())
+        .filter_map(|e| match *e {
+            Error::Validate(ref errors) => serde_json::to_value(errors.clone()).ok(),
+            _ => None,
+        })
+        .next();
+
+    match payload {
+        Some(mut payload) => {
+            localize_messages(&mut payload, language);
+            err.context(Error::LocalizedValidate(payload)).into()
+        }
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use isolang::Language;
+    use serde_json;
+
+    use super::*;
+    use models::NOT_ENOUGH_STOCK;
+
+    #[test]
+    fn test_language_from_header() {
+        assert_eq!(language_from_header("ru-RU,ru;q=0.9,en;q=0.8"), Language::Rus);
+        assert_eq!(language_from_header("de-DE, en;q=0.5"), Language::Eng);
+        assert_eq!(language_from_header("fr"), DEFAULT_LANGUAGE);
+        assert_eq!(language_from_header(""), DEFAULT_LANGUAGE);
+    }
+
+    #[test]
+    fn test_localize_messages() {
+        let errors = validation_errors!({
+            "quantity": [NOT_ENOUGH_STOCK => "Not enough units in stock"],
+            "slug": ["custom" => "Custom message"]
+        });
+        let value = serde_json::to_value(errors).unwrap();
+
+        let mut russian = value.clone();
+        localize_messages(&mut russian, Language::Rus);
+        assert_eq!(russian["quantity"][0]["code"], NOT_ENOUGH_STOCK);
+        assert_eq!(russian["quantity"][0]["message"], "Недостаточно товара на складе");
+        assert_eq!(russian["slug"][0]["message"], "Custom message");
+
+        let mut english = value.clone();
+        localize_messages(&mut english, Language::Eng);
+        assert_eq!(english, value);
+    }
+}
@@ -32,6 +32,7 @@ pub fn create_new_base_product(name: &str, short_description: &str) -> NewBasePr
         height_cm: Some(20),
         weight_g: Some(100),
         store_status: Some(ModerationStatus::Moderation),
+        saga_id: None,
     }
 }
 